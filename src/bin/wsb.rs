@@ -3,10 +3,14 @@ use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
 use log;
-use wsb::st8::{St8Config, VersionInfo, detect_project_files, update_version_file, TemplateManager, WstemplateEngine};
+use wsb::st8::hooks::HookKind;
+use wsb::st8::packages::PackageMode;
+use wsb::st8::{St8Config, VersionInfo, VersionScheme, detect_project_files, update_version_file, TemplateManager, WstemplateEngine};
 use wsb::workspace_state::{WorkspaceState, WstemplateEntry};
+use wsb::watcher::{WatchConfig, WatchService};
 use wsb::entities::EntityManager;
 use wsb::logging::{self, log_operation_start, log_operation_complete, log_operation_error, log_warning, log_version_info};
+use wsb::t;
 use sqlx::SqlitePool;
 use sqlx::Row;
 use std::collections::HashMap;
@@ -23,6 +27,15 @@ use std::time::Instant;
 #[command(about = "Workspace - All-in-one development tool suite")]
 #[command(after_help = "Shell completions are automatically set up on first run.")]
 struct Args {
+    /// Guarantee zero filesystem and database writes (implied for query commands)
+    #[arg(long, global = true)]
+    read_only: bool,
+    /// Attribute database changes to this actor (default: WSB_ACTOR, tools.actor.name, then the OS user)
+    #[arg(long = "as", global = true, value_name = "ACTOR")]
+    as_actor: Option<String>,
+    /// Fail at once if another wsb process holds the document lock instead of queueing
+    #[arg(long, global = true)]
+    no_wait: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -30,10 +43,14 @@ struct Args {
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Refactor files and directories using patterns
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    #[command(after_help = "Every run is journaled. Reverse the last one with `wsb refactor undo [--journal ID]`, or list journals with `wsb refactor undo --list`. To refactor a directory named undo, pass it as ./undo.")]
     Refactor {
+        #[command(subcommand)]
+        action: Option<RefactorAction>,
         /// Arguments for refactor tool
         #[command(flatten)]
-        args: wsb::refac::Args,
+        args: Option<wsb::refac::Args>,
     },
     
     /// Git integration and version management
@@ -56,12 +73,46 @@ enum Commands {
         /// Automatically add updated files to git staging area
         #[arg(long)]
         git_add: bool,
+        /// Write this exact version, bypassing the check that versions only go up
+        #[arg(long, value_name = "VERSION")]
+        force_version: Option<String>,
+        /// Update the version file and all project files together, rolling back if any fails
+        #[arg(long)]
+        propagate: bool,
+        /// Only update this workspace package's own version (independent package mode)
+        #[arg(long)]
+        package: Option<String>,
     },
     
+    /// Write the changelog section for the current version from commits since the last tag
+    Changelog {
+        /// Print the section instead of writing the changelog file
+        #[arg(long)]
+        dry_run: bool,
+        /// Start after this tag or commit instead of the nearest tag
+        #[arg(long)]
+        since: Option<String>,
+        /// Regenerate the changelog on every `wsb update` (on, off)
+        #[arg(long, value_name = "on|off")]
+        on_update: Option<String>,
+        /// Tera template for the section, relative to the project root
+        #[arg(long)]
+        template: Option<String>,
+    },
+
     /// Local trash can using a .scrap folder for files you want to delete
     Scrap {
         /// Paths to files or directories to move to .scrap folder
         paths: Vec<std::path::PathBuf>,
+        /// Allow scrapping protected paths (project root, .git, .wsb, tools.scrap.protected_globs)
+        #[arg(long = "i-know-what-im-doing")]
+        allow_protected: bool,
+        /// Move the paths to the operating system's trash instead of .scrap (default: tools.scrap.system_trash)
+        #[arg(long)]
+        system_trash: bool,
+        /// Output format (human, json)
+        #[arg(long, global = true, default_value = "human")]
+        format: String,
         #[command(subcommand)]
         command: Option<ScrapCommands>,
     },
@@ -76,13 +127,71 @@ enum Commands {
         /// Restore to a different location
         #[arg(short = 't', long)]
         to: Option<std::path::PathBuf>,
+        /// Pick the items to restore from a fuzzy-filtered list
+        #[arg(short, long, conflicts_with = "name")]
+        interactive: bool,
+        /// Restore every scrapped item
+        #[arg(short, long, conflicts_with_all = ["name", "interactive"])]
+        all: bool,
+        /// With --all: only items scrapped on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, requires = "all")]
+        since: Option<String>,
+        /// Merge a scrapped directory into an existing one, restoring only missing files
+        #[arg(long, conflicts_with_all = ["force", "interactive", "all"])]
+        merge: bool,
+        /// With --merge: move conflicting existing files aside (name.bak) and restore the scrapped ones
+        #[arg(long, requires = "merge")]
+        backup_existing: bool,
+    },
+    
+    /// Open the artifact behind a feature/task ID, the dashboard, generated docs or the scrap folder
+    Open {
+        /// Feature or task ID, `dashboard`, `docs` or `scrap`
+        target: String,
+        /// Print what would be opened instead of opening it
+        #[arg(long)]
+        print: bool,
     },
     
     /// Process input lines, replacing repeated tokens with a substitute character
     Ldiff {
-        /// Character to use for substitution (default: ░)
-        #[arg(default_value = "░")]
-        substitute_char: String,
+        /// Character to use for substitution (default: ░), or two files to compare
+        /// followed by an optional substitute character
+        #[arg(value_name = "CHAR | FILE_A FILE_B [CHAR]", num_args = 0..=3)]
+        args: Vec<String>,
+        /// Merge several inputs, prefixing each line with its source label
+        #[arg(long, requires = "inputs")]
+        label_streams: bool,
+        /// Input as [LABEL=]PATH ('-' for stdin); repeat for each source
+        #[arg(short = 'i', long = "input", value_name = "[LABEL=]PATH")]
+        inputs: Vec<String>,
+        /// Interleave labelled streams by the leading timestamp in this chrono format
+        #[arg(long, value_name = "FORMAT", requires = "label_streams")]
+        by_timestamp: Option<String>,
+        /// Dim repeated tokens and highlight changed ones (off when NO_COLOR is set)
+        #[arg(long)]
+        color: bool,
+        /// Plain output, overriding --color
+        #[arg(long, overrides_with = "color")]
+        no_color: bool,
+        /// Collapse timestamps, UUIDs, IP addresses and hex IDs into placeholders (⟨ts⟩, ⟨uuid⟩, ...)
+        #[arg(long)]
+        normalize: bool,
+        /// Follow a file as it grows, like tail -f
+        #[arg(long, value_name = "FILE", conflicts_with = "label_streams")]
+        follow: Option<std::path::PathBuf>,
+        /// With --follow: check for new lines at this interval instead of using file system events
+        #[arg(long, value_name = "MS", requires = "follow")]
+        poll_ms: Option<u64>,
+        /// At the end of the stream, print line and suppression counts and the top line templates to stderr
+        #[arg(long)]
+        stats: bool,
+    },
+
+    /// Project-level code analysis (module ownership, TODO scanning)
+    Analyze {
+        #[command(subcommand)]
+        action: AnalyzeAction,
     },
 
     /// AST-based code analysis and transformation
@@ -105,16 +214,37 @@ enum Commands {
     },
 
     /// MCP server for Claude integration with automatic session management
+    #[command(args_conflicts_with_subcommands = true)]
     McpServer {
+        #[command(subcommand)]
+        action: Option<McpServerAction>,
         /// Port for HTTP server (default: 3000)
         #[arg(short, long, default_value = "3000")]
         port: u16,
+        /// Address to listen on; use 0.0.0.0 to accept connections from other machines
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: std::net::IpAddr,
+        /// PEM certificate chain; serves HTTPS instead of HTTP
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// Serve HTTPS with a development certificate, generated in .wsb/tls/ if missing
+        #[arg(long, conflicts_with_all = ["tls_cert", "tls_key"])]
+        self_signed: bool,
+        /// Also listen for plain HTTP on this port and redirect it to HTTPS
+        #[arg(long)]
+        redirect_port: Option<u16>,
         /// Enable debug logging
         #[arg(long)]
         debug: bool,
         /// Migrate features from features.md to database
         #[arg(long)]
         migrate: bool,
+        /// Run in the background, logging to .wsb/mcp-server.log
+        #[arg(long, conflicts_with = "migrate")]
+        daemon: bool,
     },
 
     /// Create sample project with test data for dashboard testing
@@ -197,6 +327,18 @@ enum Commands {
         /// Include detailed metrics and analytics
         #[arg(long)]
         include_metrics: bool,
+        /// Compute the critical path over task dependencies and effort estimates
+        #[arg(long)]
+        critical_path: bool,
+        /// Limit the critical path to the tasks a milestone depends on (ID or title)
+        #[arg(long, requires = "critical_path")]
+        milestone: Option<String>,
+        /// List project risks, such as completed features with no documentation
+        #[arg(long)]
+        risks: bool,
+        /// Per-feature coverage from an lcov or Cobertura report (default: tools.coverage.report)
+        #[arg(long, value_name = "FILE")]
+        coverage: Option<PathBuf>,
         /// Output format (human, json, summary)
         #[arg(long, default_value = "human")]
         format: String,
@@ -233,6 +375,7 @@ enum Commands {
     },
 
     /// Database backup, recovery, and maintenance operations
+    #[command(alias = "db")]
     Database {
         #[command(subcommand)]
         action: DatabaseAction,
@@ -255,22 +398,195 @@ enum Commands {
         #[command(subcommand)]
         action: WstemplateAction,
     },
+
+    /// Entity event outbox delivery to configured integration sinks
+    Events {
+        #[command(subcommand)]
+        action: EventsAction,
+    },
+
+    /// Who did what in a shared project database
+    Actor {
+        #[command(subcommand)]
+        action: ActorAction,
+    },
+
+    /// Project plan as an iCalendar file
+    Calendar {
+        #[command(subcommand)]
+        action: CalendarAction,
+    },
+
+    /// Inspect and upgrade the `.wsb/state.json` workspace state file
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Encrypted tokens for MCP integrations and hooks, stored in state.json
+    Secret {
+        #[command(subcommand)]
+        action: SecretAction,
+    },
+
+    /// Field-level history of entity changes, and how long it is kept
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+
+    /// Milestones grouping features and tasks, with completion from their members
+    Milestone {
+        #[command(subcommand)]
+        action: MilestoneAction,
+    },
+
+    /// Write the active project and its entities to a JSON or YAML snapshot
+    Export {
+        /// Snapshot format (json, yaml)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+        /// Entity kinds to include (comma-separated: features, tasks, sessions, directives,
+        /// milestones, relationships, notes); all by default. The project is always included,
+        /// and note attachments whenever notes are.
+        #[arg(long, value_delimiter = ',')]
+        entity: Vec<String>,
+        /// Embed the content of files attached to notes
+        #[arg(long)]
+        files: bool,
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Load a snapshot written by `wsb export`, remapping IDs that are already taken
+    Import {
+        /// Snapshot file (.json, .yaml or .yml)
+        file: PathBuf,
+        /// Add the snapshot alongside existing data (default)
+        #[arg(long, conflicts_with = "replace")]
+        merge: bool,
+        /// Delete the snapshot project's rows of the imported kinds first
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Reconcile the database with internal/features.md and internal/task_backlog.md
+    #[command(args_conflicts_with_subcommands = true)]
+    Sync {
+        #[command(subcommand)]
+        action: Option<SyncAction>,
+        /// Which way to copy (db-to-md, md-to-db, two-way); the one-way directions overwrite
+        /// the other side, two-way copies each record from the side that changed it
+        #[arg(long, default_value = "two-way")]
+        direction: String,
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Sprints: capacity, planned tasks, velocity and burndown
+    Sprint {
+        #[command(subcommand)]
+        action: SprintAction,
+    },
+
+    /// Recorded work sessions and the git activity captured when they complete
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+
+    /// Projects in this database and which one entity commands work on
+    Project {
+        #[command(subcommand)]
+        action: ProjectAction,
+    },
+
+    /// Prefixes and widths of generated entity IDs
+    Ids {
+        #[command(subcommand)]
+        action: IdsAction,
+    },
+
+    /// Tags shared by features, tasks, notes and sessions
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Named filters for task and feature lists
+    View {
+        #[command(subcommand)]
+        action: ViewAction,
+    },
+
+    /// Project status: feature states, recent sessions, failing checks and context usage
+    Dashboard {
+        /// Full-screen view that refreshes from the database until you quit
+        #[arg(long)]
+        tui: bool,
+
+        /// Seconds between refreshes with --tui
+        #[arg(long, default_value = "2")]
+        interval: u64,
+
+        /// Output format without --tui (human, json)
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Kanban board of the project's tasks, one column per status
+    Board {
+        /// Only show tasks of this feature
+        #[arg(long)]
+        feature: Option<String>,
+
+        /// Only show tasks of this priority (high, medium, low)
+        #[arg(long)]
+        priority: Option<String>,
+    },
+
+    /// Show the advisory locks serializing document writes, and who is waiting
+    Locks {
+        /// Remove locks whose holder has exited or that are too old to trust
+        #[arg(long)]
+        clear_stale: bool,
+
+        /// Output format (human, json)
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
 enum GitCommands {
-    /// Install version management as a pre-commit hook in the current git repository
+    /// Install version management as a git hook in the current git repository
     Install {
         /// Force reinstallation even if already installed
         #[arg(short, long)]
         force: bool,
+        /// Hook to install (pre-commit, prepare-commit-msg, post-commit, pre-push)
+        #[arg(long, default_value = "pre-commit")]
+        hook: String,
     },
     /// Uninstall version management from the current git repository
-    Uninstall,
+    Uninstall {
+        /// Hook to remove; all installed hooks when omitted
+        #[arg(long)]
+        hook: Option<String>,
+    },
     /// Show current version information
     Show,
     /// Show git integration status
     Status,
+    /// Add a Version trailer to a commit message file (run by the prepare-commit-msg hook)
+    StampMessage {
+        /// Commit message file git passes to the hook
+        file: PathBuf,
+    },
+    /// Fail if the version file has uncommitted changes (run by the pre-push hook)
+    Check,
 }
 
 #[derive(Subcommand, Debug)]
@@ -290,6 +606,9 @@ enum TaskAction {
         /// Auto-detect and create feature if mentioned in description
         #[arg(long)]
         auto_feature: bool,
+        /// Tags for the task (comma-separated or repeated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
     },
     /// List tasks with filtering options
     List {
@@ -305,6 +624,18 @@ enum TaskAction {
         /// Show only recent tasks (last N days)
         #[arg(short, long)]
         recent: Option<u32>,
+        /// Only tasks carrying all of these tags (comma-separated or repeated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Apply a saved view (see `wsb view save`); filters given here override it
+        #[arg(long)]
+        view: Option<String>,
+        /// Instead list the open tasks waiting on this task, directly or transitively
+        #[arg(long, conflicts_with_all = ["status", "feature", "priority", "recent", "tags", "view"])]
+        blocked_by: Option<String>,
+        /// Instead list recurring task templates with their instances
+        #[arg(long, conflicts_with_all = ["status", "feature", "priority", "recent", "tags", "view", "blocked_by"])]
+        recurring: bool,
     },
     /// Show detailed task information
     Show {
@@ -327,6 +658,12 @@ enum TaskAction {
         /// Link to feature (for feature association)
         #[arg(short, long)]
         feature: Option<String>,
+        /// Replace the long-form markdown body (empty removes it)
+        #[arg(long, conflicts_with = "body_file")]
+        body: Option<String>,
+        /// Read the markdown body from a file ("-" for stdin)
+        #[arg(long)]
+        body_file: Option<std::path::PathBuf>,
     },
     /// Complete a task and update linked feature status
     Complete {
@@ -349,6 +686,55 @@ enum TaskAction {
         #[arg(short, long)]
         _dependencies: Vec<String>,
     },
+    /// Set a task's due date and repeat rule (exported by `wsb calendar export`)
+    Schedule {
+        /// Task ID to schedule
+        task_id: String,
+        /// Due date (YYYY-MM-DD)
+        #[arg(long)]
+        due: Option<String>,
+        /// Repeat rule: daily, weekly, monthly, yearly, "every N days|weeks|months|years"
+        /// or "cron DOM MONTH WEEKDAY"; completing the task creates the next instance
+        #[arg(long)]
+        repeat: Option<String>,
+        /// Remove the due date and repeat rule
+        #[arg(long, conflicts_with_all = ["due", "repeat"])]
+        clear: bool,
+    },
+    /// Delete a task (soft delete; `wsb task restore` brings it back)
+    Delete {
+        /// Task ID to delete
+        task_id: String,
+        /// Remove the task permanently instead
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Restore a deleted task
+    Restore {
+        /// Task ID to restore
+        task_id: String,
+    },
+    /// Make a task wait until other tasks are done
+    Depend {
+        /// Task that waits
+        task_id: String,
+        /// Tasks it depends on (comma-separated IDs)
+        #[arg(long, required = true, value_delimiter = ',')]
+        on: Vec<String>,
+    },
+    /// List pending tasks whose dependencies are all done
+    Ready {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Start a timer on a task (stops your running timer; a pending task moves to in progress)
+    Start {
+        /// Task ID to work on
+        task_id: String,
+    },
+    /// Stop your running task timer
+    Stop,
 }
 
 #[derive(Subcommand, Debug)]
@@ -389,6 +775,14 @@ enum DirectiveAction {
         /// Directive ID or title pattern to match
         identifier: String,
     },
+    /// Search directives by ID, title or description
+    Search {
+        /// Text to look for (case-insensitive)
+        query: String,
+        /// Filter by directive category
+        #[arg(short, long)]
+        category: Option<String>,
+    },
     /// Update directive properties or enforcement level
     Update {
         /// Directive ID to update
@@ -425,6 +819,9 @@ enum DirectiveAction {
         /// Fail fast on first violation
         #[arg(short, long)]
         fail_fast: bool,
+        /// Only validate mandatory directives (what the pre-commit hook runs)
+        #[arg(short, long)]
+        mandatory: bool,
     },
     /// Check specific files or directories against directives
     Check {
@@ -437,6 +834,50 @@ enum DirectiveAction {
         #[arg(short, long, default_value = "human")]
         format: String,
     },
+    /// Attach a machine-checkable rule to a directive
+    AddRule {
+        /// Directive ID (DIR-…)
+        directive_id: String,
+        /// Regex no line of a matching file may contain
+        #[arg(long, conflicts_with_all = ["require", "max_size", "command"])]
+        forbid: Option<String>,
+        /// File that must exist, relative to the project root
+        #[arg(long, conflicts_with_all = ["max_size", "command"])]
+        require: Option<String>,
+        /// Largest size in bytes a matching file may have
+        #[arg(long, conflicts_with = "command")]
+        max_size: Option<u64>,
+        /// Shell command that must exit successfully in the project root
+        #[arg(long)]
+        command: Option<String>,
+        /// Gitignore-style glob limiting --forbid and --max-size to matching files
+        #[arg(short, long)]
+        glob: Option<String>,
+        /// How to fix a violation, shown next to it
+        #[arg(long)]
+        hint: Option<String>,
+    },
+    /// List directive rules
+    Rules {
+        /// Only rules of this directive
+        directive_id: Option<String>,
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Remove a directive rule and its recorded violations
+    RemoveRule {
+        /// Rule number shown by `wsb directive rules`
+        rule_id: i64,
+    },
+    /// Block commits that violate mandatory directives via the pre-commit hook
+    InstallHook {
+        /// Rewrite the directive block even if it is already installed
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Remove the directive block from the pre-commit hook
+    UninstallHook,
 }
 
 #[derive(Subcommand, Debug)]
@@ -453,6 +894,15 @@ enum FeatureAction {
         /// Initial state (not_started, implemented, testing, completed)
         #[arg(short, long, default_value = "not_started")]
         state: String,
+        /// Long-form markdown body
+        #[arg(long, conflicts_with = "body_file")]
+        body: Option<String>,
+        /// Read the markdown body from a file ("-" for stdin)
+        #[arg(long)]
+        body_file: Option<std::path::PathBuf>,
+        /// Tags for the feature (comma-separated or repeated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
     },
     /// List features with filtering
     List {
@@ -465,6 +915,12 @@ enum FeatureAction {
         /// Show recently modified features
         #[arg(short, long)]
         recent: Option<u32>,
+        /// Only features carrying all of these tags (comma-separated or repeated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Apply a saved view (see `wsb view save`); filters given here override it
+        #[arg(long)]
+        view: Option<String>,
     },
     /// Show detailed feature information
     Show {
@@ -484,6 +940,12 @@ enum FeatureAction {
         /// Force state change without validation
         #[arg(short, long)]
         force: bool,
+        /// Replace the long-form markdown body (empty removes it)
+        #[arg(long, conflicts_with = "body_file")]
+        body: Option<String>,
+        /// Read the markdown body from a file ("-" for stdin)
+        #[arg(long)]
+        body_file: Option<std::path::PathBuf>,
     },
     /// Validate feature state transitions
     Validate {
@@ -493,6 +955,28 @@ enum FeatureAction {
         #[arg(short, long)]
         verbose: bool,
     },
+    /// Step through features in a state and advance/demote each with a keystroke
+    Review {
+        /// State to review (not_started, implemented, testing, completed, issue, critical)
+        #[arg(short, long, default_value = "implemented")]
+        state: String,
+        /// Maximum number of recent test runs to show per feature
+        #[arg(long, default_value = "5")]
+        evidence_limit: u32,
+    },
+    /// Delete a feature and its tasks (soft delete; `wsb feature restore` brings them back)
+    Delete {
+        /// Feature ID to delete
+        feature_id: String,
+        /// Remove the feature and its tasks permanently instead
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Restore a deleted feature and the tasks deleted with it
+    Restore {
+        /// Feature ID to restore
+        feature_id: String,
+    },
     /// Test automatic feature detection (F0107 demonstration)
     DetectFeatures {
         /// Test input text to analyze for potential features
@@ -569,6 +1053,12 @@ enum RelationshipAction {
         #[arg(short, long)]
         description: Option<String>,
     },
+    /// List the allowed relationship types for each entity-type pair
+    Types {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
     /// Show relationship statistics for project
     Stats {
         /// Include detailed breakdown by type
@@ -578,6 +1068,32 @@ enum RelationshipAction {
         #[arg(short, long, default_value = "human")]
         format: String,
     },
+    /// Export the relationship graph as DOT, Mermaid or JSON
+    Graph {
+        /// Output format (dot, mermaid, json)
+        #[arg(short, long, default_value = "dot")]
+        format: String,
+        /// Only the entities within --depth links of this entity
+        #[arg(short, long)]
+        entity: Option<String>,
+        /// Links to follow from --entity
+        #[arg(short, long, default_value = "2")]
+        depth: usize,
+        /// Write the graph to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show everything transitively held up by changing or blocking an entity
+    Impact {
+        /// Entity ID (feature, task, milestone, …)
+        entity_id: String,
+        /// Links to follow from the entity
+        #[arg(short, long, default_value = "5")]
+        depth: usize,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -594,12 +1110,15 @@ enum NoteAction {
         title: String,
         /// Note content
         content: String,
-        /// Note type (architecture, decision, reminder, observation, reference, evidence, progress, issue)
-        #[arg(short = 't', long, default_value = "observation")]
+        /// Note type (general, implementation, testing, bug, feature_request, technical_debt, decision)
+        #[arg(short = 't', long, default_value = "general")]
         note_type: String,
-        /// Optional tags for the note
-        #[arg(long)]
+        /// Optional tags for the note (comma-separated)
+        #[arg(long, alias = "tag")]
         tags: Option<String>,
+        /// Attach a file inside the project (repeatable); its checksum is recorded
+        #[arg(long = "attach", value_name = "FILE")]
+        attach: Vec<PathBuf>,
     },
     /// Add a project-wide note
     AddProject {
@@ -607,12 +1126,15 @@ enum NoteAction {
         title: String,
         /// Note content
         content: String,
-        /// Note type (architecture, decision, reminder, observation, reference, evidence, progress, issue)
-        #[arg(short = 't', long, default_value = "architecture")]
+        /// Note type (general, implementation, testing, bug, feature_request, technical_debt, decision)
+        #[arg(short = 't', long, default_value = "general")]
         note_type: String,
-        /// Optional tags for the note
-        #[arg(long)]
+        /// Optional tags for the note (comma-separated)
+        #[arg(long, alias = "tag")]
         tags: Option<String>,
+        /// Attach a file inside the project (repeatable); its checksum is recorded
+        #[arg(long = "attach", value_name = "FILE")]
+        attach: Vec<PathBuf>,
     },
     /// List notes with optional filtering
     List {
@@ -632,6 +1154,14 @@ enum NoteAction {
         #[arg(long)]
         pinned: bool,
     },
+    /// Show a note and check its attached files against their recorded checksums
+    Show {
+        /// Note ID
+        note_id: String,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
     /// Search notes by content or category
     Search {
         /// Search query to match in title or content
@@ -746,7 +1276,58 @@ enum TemplateAction {
         name: String,
     },
     /// Render all enabled templates
-    Render,
+    Render {
+        /// Show a diff of what would change instead of writing; exits non-zero when any output is out of date
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Expose an extra variable to a template as {{ vars.NAME }}
+    Source {
+        /// Template name
+        name: String,
+        /// Variable name under `vars`
+        var: String,
+        /// Take the value from this environment variable
+        #[arg(long, group = "source")]
+        env: Option<String>,
+        /// Fallback for --env when the variable is unset
+        #[arg(long, requires = "env")]
+        default: Option<String>,
+        /// Take the value from the output of this shell command
+        #[arg(long, group = "source")]
+        command: Option<String>,
+        /// Take the value from .wsb/state.json (dotted path, e.g. tools.scrap.retention)
+        #[arg(long, group = "source")]
+        state: Option<String>,
+        /// Take the value from a TOML or JSON file
+        #[arg(long, group = "source")]
+        file: Option<String>,
+    },
+    /// Show the render order, or mark a template as a partial and declare what it depends on
+    Order {
+        /// Template to configure; omit to only show the order
+        name: Option<String>,
+        /// Only render the template where another one includes it
+        #[arg(long, requires = "name")]
+        partial: bool,
+        /// Render the template to its output file again
+        #[arg(long, requires = "name", conflicts_with = "partial")]
+        no_partial: bool,
+        /// Templates that must render first (comma-separated; empty to clear)
+        #[arg(long, requires = "name", value_delimiter = ',')]
+        depends_on: Option<Vec<String>>,
+    },
+    /// Render a template against a fixture context instead of the live database
+    Test {
+        /// Template name (a configured template, or a built-in doc template such as claude_md)
+        name: String,
+        /// JSON file whose top-level keys become the template variables
+        #[arg(long)]
+        fixture: PathBuf,
+        /// Expected output; exits non-zero when the rendered output differs
+        #[arg(long)]
+        expect: Option<PathBuf>,
+    },
     /// Generate documentation from database entities
     GenerateDocs {
         /// Documentation type to generate (claude, features, progress, status, all)
@@ -771,6 +1352,9 @@ enum TemplateAction {
 enum DatabaseAction {
     /// Create database backup with metadata
     Backup {
+        /// Write a single backup file here instead of a managed backup
+        #[arg(short, long, conflicts_with_all = ["backup_dir", "compress"])]
+        output: Option<PathBuf>,
         /// Backup directory (default: .wsb/backups)
         #[arg(short, long)]
         backup_dir: Option<String>,
@@ -792,7 +1376,7 @@ enum DatabaseAction {
     },
     /// Restore database from backup
     Restore {
-        /// Backup ID or path to restore from
+        /// Backup ID, or a backup file (e.g. one written with `backup --output`)
         backup_id: String,
         /// Target database path (default: current project database)
         #[arg(short, long)]
@@ -819,6 +1403,73 @@ enum DatabaseAction {
         #[arg(short, long)]
         performance: bool,
     },
+    /// Apply pending schema migrations, or list them with --status
+    Migrate {
+        /// Only report applied and pending migrations
+        #[arg(long)]
+        status: bool,
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Run an integrity check and look for orphaned references, with repair suggestions
+    Check {
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Benchmark core entity operations on a synthetic dataset
+    Bench {
+        /// Number of synthetic features to generate
+        #[arg(long, default_value = "100")]
+        features: usize,
+        /// Number of tasks generated per feature
+        #[arg(long, default_value = "10")]
+        tasks_per_feature: usize,
+        /// Timed iterations per read/update operation
+        #[arg(short, long, default_value = "20")]
+        iterations: usize,
+        /// Record this run as the baseline for its scale
+        #[arg(long)]
+        save_baseline: bool,
+        /// Slowdown (percent of baseline mean) reported as a regression
+        #[arg(long, default_value = "25")]
+        threshold: f64,
+        /// Exit with an error when any operation regresses
+        #[arg(long)]
+        fail_on_regression: bool,
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Delete or scrub rows past the retention limits under tools.retention
+    Prune {
+        /// Change the database; without this only report what would be pruned
+        #[arg(long)]
+        apply: bool,
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum McpServerAction {
+    /// Stop the running server, letting requests in flight finish
+    Stop,
+    /// Show whether a server is running for this project
+    Status,
+    /// Stop the running server and start it again in the background with the same options
+    Restart,
+}
+
+#[derive(Subcommand, Debug)]
+enum SyncAction {
+    /// Show or set the side that wins conflicts in two-way syncs
+    Canonical {
+        /// db, md, or none to leave conflicts unresolved
+        source: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -908,6 +1559,34 @@ enum CodeAction {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum AnalyzeAction {
+    /// Show module owners from CODEOWNERS and @owner annotations
+    Owners {
+        /// File or directory to show owners for
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Rescan the project and refresh the stored ownership map
+        #[arg(long)]
+        rescan: bool,
+        /// Output format (human, json)
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+    /// List TODO/FIXME comments with their owners, optionally turning them into tasks
+    Todos {
+        /// File or directory to scan
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Create a task for each TODO not already tracked, assigned to the file's owners
+        #[arg(long, requires = "feature")]
+        create_tasks: bool,
+        /// Feature to link created tasks to
+        #[arg(short, long)]
+        feature: Option<String>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum ArtifactAction {
     /// List all session-generated artifacts with metadata
@@ -1072,6 +1751,39 @@ enum VersionAction {
         /// Major version number to set
         version: u32,
     },
+    /// Set the minor version number used by the semver scheme (stored in database)
+    Minor {
+        /// Minor version number to set
+        version: u32,
+    },
+    /// Show or select how versions are calculated (commit-count, semver, calver, tag)
+    Scheme {
+        /// Scheme to select; omit to show the active one
+        scheme: Option<String>,
+    },
+    /// Raise the major, minor or patch version and rewrite the version file and templates
+    Bump {
+        /// Part to raise (major, minor, patch)
+        part: String,
+        /// Pre-release label to append, e.g. alpha.1 (cleared when omitted)
+        #[arg(long)]
+        pre: Option<String>,
+        /// Also create an annotated git tag for the new version
+        #[arg(long)]
+        tag: bool,
+        /// Tag message
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+    /// List workspace packages and their versions, or choose how they are versioned
+    Packages {
+        /// Package mode to select (lockstep, independent)
+        #[arg(long)]
+        mode: Option<String>,
+        /// Set a package's base version in independent mode, as NAME=VERSION
+        #[arg(long, value_name = "NAME=VERSION")]
+        set: Option<String>,
+    },
     /// Create git tag with current calculated version
     Tag {
         /// Tag prefix (default: 'v')
@@ -1090,9 +1802,454 @@ enum VersionAction {
 }
 
 #[derive(Subcommand, Debug)]
-enum WstemplateAction {
-    /// Set the scan root for this project (replaces any existing entry)
-    Add {
+enum EventsAction {
+    /// Deliver pending events to the sinks configured in .wsb/state.json
+    Dispatch {
+        /// Keep running and dispatch on an interval instead of exiting
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between dispatch runs in watch mode
+        #[arg(long, default_value = "30")]
+        interval: u64,
+    },
+    /// List outbox events
+    List {
+        /// Filter by status (pending, delivered, dead)
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Only events caused by this actor
+        #[arg(long)]
+        by: Option<String>,
+        /// Maximum number of events to show
+        #[arg(short, long, default_value = "20")]
+        limit: u32,
+    },
+    /// Requeue a dead-lettered event for another delivery attempt
+    Retry {
+        /// Outbox event ID
+        event_id: i64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ActorAction {
+    /// List actors recorded in the project database with their attributed work
+    List {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Show attributed activity: sessions started, tasks completed and entity events
+    Activity {
+        /// Only activity by this actor
+        #[arg(long)]
+        by: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(short, long, default_value = "20")]
+        limit: u32,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Show the actor this invocation is attributed to and where it came from
+    Whoami,
+}
+
+#[derive(Subcommand, Debug)]
+enum CalendarAction {
+    /// Write task due dates, milestones and recurring tasks to an iCalendar (.ics) file
+    Export {
+        /// Output file
+        #[arg(short, long, default_value = "project.ics")]
+        out: PathBuf,
+        /// Only events on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only events on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+        /// Also write one calendar per category next to the output file
+        #[arg(long)]
+        per_category: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum StateAction {
+    /// Upgrade state.json to the current schema, keeping a backup of the old file
+    Migrate {
+        /// Show the migration steps without writing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Output format (human, json)
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SecretAction {
+    /// Encrypt and store a value; prompts (or reads stdin) when VALUE is omitted
+    Set {
+        name: String,
+        /// Value to store; passing it here leaves it in shell history
+        value: Option<String>,
+    },
+    /// Print a decrypted value
+    Get {
+        name: String,
+    },
+    /// List the stored secret names
+    List,
+    /// Delete a stored secret
+    Remove {
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AuditAction {
+    /// List audit entries, newest first
+    List {
+        /// Only changes to this entity (e.g. F00042)
+        #[arg(long)]
+        entity: Option<String>,
+        /// Only changes made by this actor
+        #[arg(long)]
+        by: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(short, long, default_value = "20")]
+        limit: u32,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Delete audit entries past the age limit
+    Prune {
+        /// Age limit in days (default: the audit policy under tools.retention)
+        #[arg(long)]
+        older_than: Option<u32>,
+        /// Change the database; without this only report what would be pruned
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Show or set how long audit entries are kept (enforced by `wsb audit prune` and `wsb db prune`)
+    Retention {
+        /// Keep audit entries for this many days
+        #[arg(long, conflicts_with = "clear")]
+        days: Option<u32>,
+        /// Keep audit entries forever
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MilestoneAction {
+    /// Create a milestone
+    Add {
+        /// Milestone title
+        title: String,
+        /// Milestone description
+        #[arg(short, long, default_value = "")]
+        description: String,
+        /// Target date (YYYY-MM-DD)
+        #[arg(long)]
+        target: Option<String>,
+        /// Member features (comma-separated IDs)
+        #[arg(long, value_delimiter = ',')]
+        features: Vec<String>,
+        /// Member tasks (comma-separated IDs)
+        #[arg(long, value_delimiter = ',')]
+        tasks: Vec<String>,
+    },
+    /// Add features or tasks to an open milestone
+    Link {
+        /// Milestone ID (e.g. M001)
+        milestone_id: String,
+        /// Features to add (comma-separated IDs)
+        #[arg(long, value_delimiter = ',')]
+        features: Vec<String>,
+        /// Tasks to add (comma-separated IDs)
+        #[arg(long, value_delimiter = ',')]
+        tasks: Vec<String>,
+    },
+    /// List milestones, soonest target first
+    List {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Show a milestone and the state of its members
+    Show {
+        /// Milestone ID (e.g. M001)
+        milestone_id: String,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Close a milestone
+    Close {
+        /// Milestone ID (e.g. M001)
+        milestone_id: String,
+        /// Outcome: achieved, missed or cancelled
+        #[arg(long, default_value = "achieved")]
+        status: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SprintAction {
+    /// Open a sprint in the active project
+    Create {
+        /// Sprint name, unique in the project
+        name: String,
+        /// First day (YYYY-MM-DD, default: today)
+        #[arg(long)]
+        start: Option<String>,
+        /// Last day (YYYY-MM-DD, default: two weeks from the start)
+        #[arg(long)]
+        end: Option<String>,
+        /// Points the sprint can take
+        #[arg(long)]
+        capacity: Option<i64>,
+        /// What the sprint should achieve
+        #[arg(long)]
+        goal: Option<String>,
+    },
+    /// Add tasks to an open sprint, or take them out with --remove
+    Plan {
+        /// Sprint ID (e.g. SP001)
+        sprint_id: String,
+        /// Tasks (comma-separated IDs)
+        #[arg(long, required = true, value_delimiter = ',')]
+        tasks: Vec<String>,
+        /// Points of each task (default: its estimated effort, or 1)
+        #[arg(long, conflicts_with = "remove")]
+        points: Option<i64>,
+        /// Take the tasks out of the sprint
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Close an open sprint and record the points completed
+    Close {
+        /// Sprint ID (e.g. SP001)
+        sprint_id: String,
+        /// Plan the unfinished tasks into this open sprint
+        #[arg(long)]
+        carry_over: Option<String>,
+    },
+    /// List the active project's sprints
+    List {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Show a sprint with its tasks and points
+    Show {
+        /// Sprint ID (e.g. SP001)
+        sprint_id: String,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Completed points of recent closed sprints and their average
+    Velocity {
+        /// Number of closed sprints to average
+        #[arg(long, default_value_t = 3)]
+        last: usize,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Points left on each day of a sprint
+    Burndown {
+        /// Sprint ID (e.g. SP001)
+        sprint_id: String,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SessionAction {
+    /// Record the start of a session in the active project
+    Create {
+        /// Session title
+        title: String,
+        /// Focus area
+        #[arg(long)]
+        focus: Option<String>,
+    },
+    /// Complete a session, capturing the commits and diffstat made since it started
+    Complete {
+        /// Session ID (e.g. S000001)
+        id: String,
+        /// What the session achieved
+        #[arg(short, long, default_value = "")]
+        summary: String,
+    },
+    /// List the active project's sessions, newest first
+    List {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Show a session with its commits, files touched and diffstat
+    Show {
+        /// Session ID (e.g. S000001)
+        id: String,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ProjectAction {
+    /// List projects, marking the current one
+    List {
+        /// Include archived projects
+        #[arg(long)]
+        all: bool,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Create a project
+    Create {
+        /// Project name
+        name: String,
+        /// Project description (default: "<NAME> project")
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Make the new project the current one
+        #[arg(long)]
+        switch: bool,
+    },
+    /// Make a project the one entity commands work on
+    Switch {
+        /// Project ID
+        id: String,
+    },
+    /// Archive a project; it keeps its data but leaves project lists
+    Archive {
+        /// Project ID
+        id: String,
+    },
+    /// Make an archived project active again
+    Unarchive {
+        /// Project ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IdsAction {
+    /// Show the ID format and next number of each entity type
+    List {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Change the prefix and width of an entity type's IDs in the active project
+    Set {
+        /// Entity type (project, feature, task, session, directive, milestone, sprint)
+        entity: String,
+        /// Prefix of 1 to 4 upper case letters
+        #[arg(long)]
+        prefix: String,
+        /// Number of digits, zero padded
+        #[arg(long)]
+        width: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TagAction {
+    /// List the project's tags
+    List {
+        /// Show how many features, tasks, notes and sessions carry each tag
+        #[arg(long)]
+        counts: bool,
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Tag a feature, task, note or session
+    Add {
+        /// Entity ID (F00001, T000001, TASK-..., S000001, note-...)
+        entity_id: String,
+        /// Tags to add (comma-separated or several arguments)
+        #[arg(required = true, value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+    /// Remove tags from an entity
+    Remove {
+        /// Entity ID
+        entity_id: String,
+        /// Tags to remove
+        #[arg(required = true, value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+    /// Rename a tag, keeping everything it is linked to
+    Rename {
+        /// Current name
+        old: String,
+        /// New name
+        new: String,
+    },
+    /// Move the links of some tags onto another and delete them
+    Merge {
+        /// Tags to merge away
+        #[arg(required = true, value_delimiter = ',')]
+        sources: Vec<String>,
+        /// Tag that keeps the links (created if needed)
+        #[arg(long)]
+        into: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ViewAction {
+    /// Save a named filter, replacing an existing view with the same name
+    Save {
+        /// View name
+        name: String,
+        /// Entity type the view lists (task, feature)
+        #[arg(short, long, default_value = "task")]
+        entity: String,
+        /// Status to filter by (task status, or feature state)
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Priority to filter by (critical, high, medium, low; tasks only)
+        #[arg(short, long)]
+        priority: Option<String>,
+        /// Tags the listed entities must carry (comma-separated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Sort field (id, title, status, priority, created); prefix with - for descending
+        #[arg(long, allow_hyphen_values = true)]
+        sort: Option<String>,
+    },
+    /// List saved views
+    List {
+        /// Output format (human, json)
+        #[arg(short, long, default_value = "human")]
+        format: String,
+    },
+    /// Delete a saved view
+    Delete {
+        /// View name
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WstemplateAction {
+    /// Set the scan root for this project (replaces any existing entry)
+    Add {
         /// Directory tree to scan for .wstemplate files and peer .wsb/state.json files
         path: PathBuf,
         /// Override the auto-derived alias (must be a valid Tera identifier: [a-z_][a-z0-9_]*)
@@ -1109,7 +2266,11 @@ enum WstemplateAction {
     /// List all .wstemplate files relevant to this project (no rendering)
     List,
     /// Render all .wstemplate files relevant to this project
-    Render,
+    Render {
+        /// Keep running and re-render whenever a .wstemplate file under the scan root changes
+        #[arg(long)]
+        watch: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -1120,6 +2281,10 @@ enum ScrapCommands {
         /// Sort by: name, date, size
         #[arg(short, long, default_value = "date")]
         sort: String,
+
+        /// Show entries with identical content and the space saved by storing it once
+        #[arg(long)]
+        duplicates: bool,
     },
 
     /// Clean old items from .scrap folder
@@ -1131,6 +2296,14 @@ enum ScrapCommands {
         /// Show what would be removed without actually removing
         #[arg(short = 'n', long)]
         dry_run: bool,
+
+        /// Overwrite file contents before deleting them
+        #[arg(long)]
+        secure: bool,
+
+        /// Overwrite passes with --secure
+        #[arg(long, requires = "secure")]
+        passes: Option<u32>,
     },
 
     /// Remove all items from .scrap folder
@@ -1138,6 +2311,14 @@ enum ScrapCommands {
         /// Skip confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Overwrite file contents before deleting them
+        #[arg(long)]
+        secure: bool,
+
+        /// Overwrite passes with --secure
+        #[arg(long, requires = "secure")]
+        passes: Option<u32>,
     },
 
     /// Search for files in .scrap
@@ -1145,9 +2326,13 @@ enum ScrapCommands {
         /// Search pattern (regex supported)
         pattern: String,
         
-        /// Also search file contents
+        /// Also search file contents, including inside scrapped directories
         #[arg(short, long)]
         content: bool,
+
+        /// Skip files larger than this many bytes when searching contents
+        #[arg(long, requires = "content")]
+        max_size: Option<u64>,
     },
 
     /// Create archive of .scrap contents
@@ -1159,14 +2344,90 @@ enum ScrapCommands {
         /// Remove files after archiving
         #[arg(short, long)]
         remove: bool,
+
+        /// Compress old items into monthly archives under .scrap/archive/ instead
+        #[arg(long)]
+        tier: bool,
+
+        /// With --tier: archive items older than this many days (default: tools.scrap.archive_after_days)
+        #[arg(long, requires = "tier")]
+        days: Option<u32>,
+    },
+
+    /// Delete scrapped items permanently
+    Rm {
+        /// Names of the items in .scrap to delete
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+
+    /// Show or set the retention policy enforced on every scrap operation
+    Config {
+        /// Remove items scrapped more than N days ago (0 removes the limit)
+        #[arg(long)]
+        max_age_days: Option<u32>,
+
+        /// Keep the total size under this, e.g. 500M or 2G (0 removes the limit)
+        #[arg(long)]
+        max_size: Option<String>,
+
+        /// Keep at most N items (0 removes the limit)
+        #[arg(long)]
+        max_items: Option<usize>,
+
+        /// Remove all retention limits
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RefactorAction {
+    /// Reverse a refactor run using its journal
+    Undo {
+        /// Journal ID to undo (default: the most recent run not yet undone)
+        #[arg(long)]
+        journal: Option<String>,
+        /// List recorded journals instead of undoing
+        #[arg(long)]
+        list: bool,
+        /// Undo even if files changed after the run, discarding those changes
+        #[arg(long)]
+        force: bool,
     },
 }
 
 fn main() {
-    // Initialize logging as early as possible
-    let debug_mode = std::env::args().any(|arg| arg == "--debug" || arg == "-v" || arg == "--verbose");
-    
-    if let Err(e) = logging::setup_logging(debug_mode) {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(err) => {
+            // `wsb --help` is the usual first run, so it still sets up completions
+            use clap::error::ErrorKind;
+            let shows_help = matches!(err.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand | ErrorKind::DisplayVersion);
+            if shows_help && !is_running_as_git_hook() && !wsb::read_only::requested_by_env() {
+                let _ = setup_shell_completions();
+            }
+            exit_with_suggestion(err)
+        }
+    };
+
+    // Query commands and explicit --read-only runs must not mutate the workspace
+    if args.read_only || wsb::read_only::requested_by_env() || is_query_command(&args.command) {
+        wsb::read_only::enable();
+    }
+    if args.no_wait {
+        wsb::locks::set_no_wait();
+    }
+
+    // Everything this process writes to the project database is attributed to one actor
+    let cwd = std::env::current_dir().unwrap_or_default();
+    wsb::entities::actors::init(args.as_actor.as_deref(), &cwd);
+    wsb::i18n::init(&cwd);
+
+    // Initialize logging as early as possible
+    let debug_mode = std::env::args().any(|arg| arg == "--debug" || arg == "-v" || arg == "--verbose");
+    
+    if let Err(e) = logging::setup_logging(debug_mode) {
         eprintln!("Failed to initialize logging: {}", e);
     }
     
@@ -1174,29 +2435,182 @@ fn main() {
     log_version_info(env!("CARGO_PKG_VERSION"), option_env!("GIT_HASH"));
     log::info!("Starting workspace tool suite");
     
-    // Setup shell completions on first run (but not when running as a git hook)
-    if !is_running_as_git_hook() {
+    // Setup shell completions on first run (but not when running as a git hook or read-only)
+    if !is_running_as_git_hook() && !wsb::read_only::is_enabled() {
         if let Err(e) = setup_shell_completions() {
             log_warning("Shell completions", &format!("Failed to setup: {}", e));
         }
     }
     
-    if let Err(e) = run() {
+    if let Err(e) = run(args) {
         log::error!("Application error: {:#}", e);
-        eprintln!("{}: {:#}", "Error".red(), e);
+        eprintln!("{}: {:#}", t!("common.error").red(), e);
         process::exit(1);
     }
     
     log::info!("Workspace tool completed successfully");
 }
 
-fn run() -> Result<()> {
+/// Print a clap error followed by a "did you mean" hint for mistyped subcommands and flags
+fn exit_with_suggestion(err: clap::Error) -> ! {
+    use clap::error::ErrorKind;
+
+    if !matches!(err.kind(), ErrorKind::InvalidSubcommand | ErrorKind::UnknownArgument) {
+        err.exit();
+    }
+
+    let _ = err.print();
+    let argv: Vec<String> = env::args().skip(1).collect();
+    if let Some(corrected) = suggest_command_line(&argv) {
+        eprintln!("\n  {} did you mean `wsb {}`?", "tip:".green(), corrected.join(" "));
+    }
+    process::exit(err.exit_code());
+}
+
+/// Walk the argument list against the clap command tree, correcting mistyped
+/// subcommands and long flags. Returns the corrected arguments if anything changed.
+fn suggest_command_line(argv: &[String]) -> Option<Vec<String>> {
+    let mut command = Args::command();
+    // Propagate global flags such as --read-only into every subcommand
+    command.build();
+    let mut corrected = Vec::with_capacity(argv.len());
+    let mut changed = false;
+    let mut in_subcommands = true;
+
+    for token in argv {
+        if let Some(flag) = token.strip_prefix("--") {
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (flag, None),
+            };
+            let longs: Vec<&str> = command.get_arguments().filter_map(|a| a.get_long()).collect();
+            if !name.is_empty() && !longs.contains(&name) {
+                if let Some(fixed) = wsb::suggest::suggest(name, longs.iter().copied()) {
+                    changed = true;
+                    corrected.push(match value {
+                        Some(value) => format!("--{}={}", fixed, value),
+                        None => format!("--{}", fixed),
+                    });
+                    continue;
+                }
+            }
+            corrected.push(token.clone());
+            continue;
+        }
+
+        if in_subcommands && !token.starts_with('-') && command.has_subcommands() {
+            let names: Vec<String> = command.get_subcommands().map(|c| c.get_name().to_string()).collect();
+            let name = if names.iter().any(|n| n == token) {
+                token.clone()
+            } else if let Some(fixed) = wsb::suggest::suggest(token, names.iter().map(|n| n.as_str())) {
+                changed = true;
+                fixed.to_string()
+            } else {
+                corrected.push(token.clone());
+                in_subcommands = false;
+                continue;
+            };
+
+            corrected.push(name.clone());
+            let next = command.find_subcommand(&name).cloned();
+            match next {
+                Some(sub) => command = sub,
+                None => in_subcommands = false,
+            }
+            continue;
+        }
+
+        in_subcommands = false;
+        corrected.push(token.clone());
+    }
+
+    if changed { Some(corrected) } else { None }
+}
+
+/// Print a "did you mean" hint for an unknown entity ID
+fn print_id_suggestion<'a, I>(identifier: &str, known_ids: I)
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let hint = wsb::suggest::did_you_mean(identifier, known_ids);
+    if !hint.is_empty() {
+        println!("  {} {}", "💡".yellow(), hint);
+    }
+}
+
+/// "<kind> not found: <id>", carrying a "did you mean" hint when a known ID is close
+fn id_not_found<'a, I>(kind: &str, identifier: &str, known_ids: I) -> anyhow::Error
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    match wsb::suggest::did_you_mean(identifier, known_ids).as_str() {
+        "" => anyhow::anyhow!("{} not found: {}", kind, identifier),
+        hint => anyhow::anyhow!("{} not found: {} ({})", kind, identifier, hint),
+    }
+}
+
+/// Fail with [`id_not_found`] unless `identifier` is among the IDs `sql` selects
+async fn require_id(pool: &sqlx::SqlitePool, kind: &str, identifier: &str, sql: &str) -> Result<()> {
+    let ids: Vec<String> = sqlx::query_scalar(sql).fetch_all(pool).await?;
+    if ids.iter().any(|id| id == identifier) {
+        return Ok(());
+    }
+    Err(id_not_found(kind, identifier, ids.iter().map(|id| id.as_str())))
+}
+
+const LIVE_TASK_IDS: &str = "SELECT id FROM tasks WHERE deleted_at IS NULL";
+const LIVE_FEATURE_IDS: &str = "SELECT id FROM features WHERE deleted_at IS NULL";
+
+/// Commands that only inspect state and therefore run in read-only mode
+fn is_query_command(command: &Commands) -> bool {
+    match command {
+        Commands::Status { .. } => true,
+        Commands::Feature { action } => matches!(action, FeatureAction::List { .. } | FeatureAction::Show { .. } | FeatureAction::Validate { .. }),
+        Commands::Task { action } => matches!(action, TaskAction::List { .. } | TaskAction::Show { .. } | TaskAction::Ready { .. }),
+        Commands::Directive { action } => matches!(action, DirectiveAction::List { .. } | DirectiveAction::Show { .. } | DirectiveAction::Search { .. } | DirectiveAction::Rules { .. }),
+        Commands::Relationship { action } => matches!(action, RelationshipAction::List { .. } | RelationshipAction::Types { .. } | RelationshipAction::Stats { .. } | RelationshipAction::Graph { output: None, .. } | RelationshipAction::Impact { .. }),
+        Commands::Note { action } => matches!(action, NoteAction::List { .. } | NoteAction::Show { .. } | NoteAction::Search { .. } | NoteAction::ListLinks { .. }),
+        Commands::Database { action } => matches!(action, DatabaseAction::List { .. } | DatabaseAction::Health { .. } | DatabaseAction::Check { .. } | DatabaseAction::Migrate { status: true, .. } | DatabaseAction::Prune { apply: false, .. }),
+        Commands::Continuity { action } => matches!(action, ContinuityAction::List { .. }),
+        Commands::Version { action } => matches!(action, VersionAction::Show { .. } | VersionAction::Info { .. } | VersionAction::Scheme { scheme: None }),
+        Commands::Code { action } => !matches!(action, Some(CodeAction::Transform { .. })),
+        Commands::McpServer { action, .. } => matches!(action, Some(McpServerAction::Status)),
+        Commands::Events { action } => matches!(action, EventsAction::List { .. }),
+        Commands::Refactor { action, .. } => matches!(action, Some(RefactorAction::Undo { list: true, .. })),
+        Commands::Actor { .. } => true,
+        Commands::Open { .. } => true,
+        Commands::Dashboard { .. } => true,
+        Commands::Locks { clear_stale, .. } => !clear_stale,
+        Commands::State { action } => matches!(action, StateAction::Migrate { dry_run: true, .. }),
+        Commands::Secret { action } => matches!(action, SecretAction::Get { .. } | SecretAction::List),
+        Commands::Audit { action } => matches!(action, AuditAction::List { .. } | AuditAction::Prune { apply: false, .. } | AuditAction::Retention { days: None, clear: false }),
+        Commands::Milestone { action } => matches!(action, MilestoneAction::List { .. } | MilestoneAction::Show { .. }),
+        Commands::Sprint { action } => matches!(action, SprintAction::List { .. } | SprintAction::Show { .. } | SprintAction::Velocity { .. } | SprintAction::Burndown { .. }),
+        Commands::Session { action } => matches!(action, SessionAction::List { .. } | SessionAction::Show { .. }),
+        Commands::Project { action } => matches!(action, ProjectAction::List { .. }),
+        Commands::Ids { action } => matches!(action, IdsAction::List { .. }),
+        Commands::Tag { action } => matches!(action, TagAction::List { .. }),
+        Commands::View { action } => matches!(action, ViewAction::List { .. }),
+        Commands::Export { output, .. } => output.is_none(),
+        Commands::Sync { action, dry_run, .. } => *dry_run || matches!(action, Some(SyncAction::Canonical { source: None })),
+        _ => false,
+    }
+}
+
+fn run(args: Args) -> Result<()> {
     let start_time = Instant::now();
-    let args = Args::parse();
     log::debug!("Parsed command line arguments: {:?}", args);
     
     match args.command {
-        Commands::Refactor { args } => {
+        Commands::Refactor { action: Some(RefactorAction::Undo { journal, list, force }), .. } => {
+            run_refactor_undo(journal.as_deref(), list, force)?;
+        }
+
+        Commands::Refactor { args: None, .. } => {
+            anyhow::bail!("Missing ROOT_DIR; see `wsb refactor --help`");
+        }
+
+        Commands::Refactor { args: Some(args), .. } => {
             log_operation_start("refactor", &format!("root: {:?}", args.root_dir));
             match wsb::run_refac(args) {
                 Ok(()) => log_operation_complete("refactor", start_time.elapsed()),
@@ -1216,22 +2630,67 @@ fn run() -> Result<()> {
             handle_template_command(action)?;
         }
         
-        Commands::Update { no_git, git_add } => {
-            log_operation_start("update", &format!("no_git: {}, git_add: {}", no_git, git_add));
-            update_state(no_git, git_add)?;
+        Commands::Update { no_git, git_add, force_version, propagate, package } => {
+            log_operation_start("update", &format!("no_git: {}, git_add: {}, propagate: {}, package: {:?}", no_git, git_add, propagate, package));
+            update_state(no_git, git_add, force_version.as_deref(), propagate, package.as_deref())?;
             log_operation_complete("update", start_time.elapsed());
         }
+
+        Commands::Changelog { dry_run, since, on_update, template } => {
+            handle_changelog(dry_run, since.as_deref(), on_update.as_deref(), template)?;
+        }
+        
+        Commands::Scrap { paths, allow_protected, system_trash, format, command } => {
+            run_scrap_command(paths, allow_protected, system_trash, format, command)?;
+        }
         
-        Commands::Scrap { paths, command } => {
-            run_scrap_command(paths, command)?;
+        Commands::Unscrap { name, force, to, interactive, all, since, merge, backup_existing } => {
+            run_unscrap_command(name, force, to, interactive, all, since, merge, backup_existing)?;
         }
         
-        Commands::Unscrap { name, force, to } => {
-            run_unscrap_command(name, force, to)?;
+        Commands::Open { target, print } => {
+            run_open_command(&target, print)?;
         }
         
-        Commands::Ldiff { substitute_char } => {
-            run_ldiff_command(substitute_char)?;
+        Commands::Ldiff { args, label_streams, inputs, by_timestamp, color, no_color, normalize, follow, poll_ms, stats } => {
+            let color = wsb::ldiff::color_enabled(color, no_color);
+            let (files, substitute_char) = match args.as_slice() {
+                [] => (None, "░".to_string()),
+                [substitute] => (None, substitute.clone()),
+                [first, second] => (Some((first, second)), "░".to_string()),
+                [first, second, substitute, ..] => (Some((first, second)), substitute.clone()),
+            };
+            let normalizer = if normalize { Some(wsb::ldiff::Normalizer::new()?) } else { None };
+            let differ = wsb::ldiff::LineDiffer::new(ldiff_substitute(&substitute_char)?)
+                .with_color(color)
+                .with_normalizer(normalizer)
+                .with_stats(stats);
+            // Clones share the counters, so this one sees everything the run recorded
+            let summary = differ.clone();
+            if let Some(path) = follow {
+                if files.is_some() {
+                    anyhow::bail!("--follow takes one file; FILE_A FILE_B compares two files instead");
+                }
+                run_ldiff_follow(&path, differ, poll_ms)?;
+            } else if let Some((first, second)) = files {
+                if label_streams {
+                    anyhow::bail!("--label-streams takes its files as --input, not as FILE_A FILE_B");
+                }
+                run_ldiff_compare(std::path::Path::new(first), std::path::Path::new(second), differ)?;
+            } else if label_streams {
+                run_ldiff_label_streams(&inputs, by_timestamp.as_deref(), &differ)?;
+            } else if color || normalize || stats {
+                run_ldiff_stdin(differ)?;
+            } else {
+                run_ldiff_command(substitute_char)?;
+            }
+            if let Some(stats) = summary.stats() {
+                stats.write_summary(&mut std::io::stderr())?;
+            }
+        }
+
+        Commands::Analyze { action } => {
+            run_analyze_command(action)?;
         }
 
         Commands::Code { action } => {
@@ -1249,8 +2708,21 @@ fn run() -> Result<()> {
             handle_test_command(dry_run, install, args)?;
         }
 
-        Commands::McpServer { port, debug, migrate } => {
-            run_mcp_server(port, debug, migrate)?;
+        Commands::McpServer { action: Some(action), .. } => {
+            run_mcp_server_action(action)?;
+        }
+
+        Commands::McpServer { action: None, daemon: true, .. } => {
+            start_mcp_daemon()?;
+        }
+
+        Commands::McpServer { action: None, port, bind, tls_cert, tls_key, self_signed, redirect_port, debug, migrate, daemon: false } => {
+            let tls = match (tls_cert, tls_key) {
+                (Some(cert), Some(key)) => Some(wsb::mcp_server::TlsFiles { cert, key }),
+                _ => None,
+            };
+            let options = wsb::mcp_server::ServeOptions { bind, port, tls, redirect_port, debug, ..Default::default() };
+            run_mcp_server(options, self_signed, migrate)?;
         }
 
         Commands::Sample { project, data, force, output } => {
@@ -1274,8 +2746,9 @@ fn run() -> Result<()> {
             run_consolidate_command(debug_mode, force, generate_diagrams, preserve_complexity)?;
         }
 
-        Commands::Status { debug_mode, include_features, include_metrics, format } => {
-            run_status_command(debug_mode, include_features, include_metrics, format)?;
+        Commands::Status { debug_mode, include_features, include_metrics, critical_path, milestone, risks, coverage, format } => {
+            let critical_path = if critical_path { Some(milestone) } else { None };
+            run_status_command(debug_mode, include_features, include_metrics, critical_path, risks, coverage, format)?;
         }
 
         Commands::Task { action } => {
@@ -1313,6 +2786,82 @@ fn run() -> Result<()> {
         Commands::Wstemplate { action } => {
             handle_wstemplate_command(action)?;
         }
+
+        Commands::Events { action } => {
+            run_events_command(action)?;
+        }
+
+        Commands::Actor { action } => {
+            run_actor_command(action)?;
+        }
+
+        Commands::Calendar { action } => {
+            run_calendar_command(action)?;
+        }
+
+        Commands::State { action } => {
+            run_state_command(action)?;
+        }
+
+        Commands::Secret { action } => {
+            run_secret_command(action)?;
+        }
+
+        Commands::Audit { action } => {
+            run_audit_command(action)?;
+        }
+
+        Commands::Milestone { action } => {
+            run_milestone_command(action)?;
+        }
+
+        Commands::Sprint { action } => {
+            run_sprint_command(action)?;
+        }
+
+        Commands::Session { action } => {
+            run_session_command(action)?;
+        }
+
+        Commands::Project { action } => {
+            run_project_command(action)?;
+        }
+
+        Commands::Ids { action } => {
+            run_ids_command(action)?;
+        }
+
+        Commands::Tag { action } => {
+            run_tag_command(action)?;
+        }
+
+        Commands::View { action } => {
+            run_view_command(action)?;
+        }
+
+        Commands::Export { format, entity, files, output } => {
+            run_export_command(&format, &entity, files, output)?;
+        }
+
+        Commands::Import { file, merge: _, replace } => {
+            run_import_command(&file, replace)?;
+        }
+
+        Commands::Sync { action, direction, dry_run } => {
+            run_sync_command(action, &direction, dry_run)?;
+        }
+
+        Commands::Dashboard { tui, interval, format } => {
+            run_dashboard_command(tui, interval, &format)?;
+        }
+
+        Commands::Board { feature, priority } => {
+            run_board_command(feature, priority)?;
+        }
+
+        Commands::Locks { clear_stale, format } => {
+            run_locks_command(clear_stale, &format)?;
+        }
     }
 
     Ok(())
@@ -1320,10 +2869,12 @@ fn run() -> Result<()> {
 
 fn run_git_command(command: Option<GitCommands>) -> Result<()> {
     match command {
-        Some(GitCommands::Install { force }) => install_hook(force)?,
-        Some(GitCommands::Uninstall) => uninstall_hook()?,
+        Some(GitCommands::Install { force, hook }) => install_hook(force, hook.parse()?)?,
+        Some(GitCommands::Uninstall { hook }) => uninstall_hook(hook.as_deref().map(str::parse).transpose()?)?,
         Some(GitCommands::Show) => show_version()?,
         Some(GitCommands::Status) => show_status()?,
+        Some(GitCommands::StampMessage { file }) => stamp_commit_message(&file)?,
+        Some(GitCommands::Check) => check_version_committed()?,
         None => {
             // Default behavior: install hook if not installed, otherwise update state
             if !is_git_repository() {
@@ -1337,14 +2888,14 @@ fn run_git_command(command: Option<GitCommands>) -> Result<()> {
                 log::info!("Git hook not installed, installing automatically");
                 eprintln!("{}: Git hook not installed", "Info".blue());
                 eprintln!("{}: Installing pre-commit hook for automatic version management", "Info".blue());
-                install_hook(false)?;
+                install_hook(false, HookKind::PreCommit)?;
             } else {
                 // Hook is installed, just update state
                 let project_root = get_project_root()?;
                 let config = St8Config::load(&project_root)?;
                 let workspace_state = WorkspaceState::load(&project_root)?;
 
-                let version_info = calculate_version(&project_root)?;
+                let version_info = calculate_guarded_version(&project_root, None)?;
                 update_version_file(&version_info, &config)?;
 
                 // Render wstemplate files if configured
@@ -1367,24 +2918,49 @@ fn run_git_command(command: Option<GitCommands>) -> Result<()> {
     Ok(())
 }
 
-fn update_state(no_git: bool, git_add: bool) -> Result<()> {
+fn update_state(no_git: bool, git_add: bool, force_version: Option<&str>, propagate: bool, package: Option<&str>) -> Result<()> {
     let project_root = get_project_root()?;
+    let _lock = wsb::locks::acquire(&project_root, wsb::locks::DOCUMENTS, "wsb update")?;
     let config = St8Config::load(&project_root)?;
+
+    if let Some(package) = package {
+        if config.package_mode != PackageMode::Independent {
+            anyhow::bail!(
+                "Packages are versioned in lockstep with the project; run 'wsb update', or switch with 'wsb version packages --mode independent'"
+            );
+        }
+        let written = write_package_versions(&project_root, Some(package))?;
+        if !no_git && git_add && is_git_repository() && !written.is_empty() {
+            add_files_to_git(&written)?;
+        }
+        return Ok(());
+    }
     let workspace_state = WorkspaceState::load(&project_root)?;
 
-    // Calculate version once
-    let version_info = calculate_version(&project_root)?;
+    // Calculate version once, refusing to go backwards
+    let version_info = calculate_guarded_version(&project_root, force_version)?;
 
     // Write version.txt first — other projects read our version.txt
     // when resolving {{ projects.OUR_ALIAS.version }}
-    update_version_file(&version_info, &config)?;
-    if !config.version_file.is_empty() {
-        log::info!("Updated version file: {}", config.version_file);
-        println!("{}: Updated {}", "Info".blue(), config.version_file);
+    let mut propagated_files: Vec<String> = Vec::new();
+    if propagate {
+        for path in wsb::st8::propagate::propagate(&version_info, &project_root, &config)? {
+            log::info!("Propagated version to {}", path.display());
+            println!("{}: Updated {}", "Info".blue(), path.display());
+            propagated_files.push(path.display().to_string());
+        }
+    } else {
+        update_version_file(&version_info, &config)?;
+        if !config.version_file.is_empty() {
+            log::info!("Updated version file: {}", config.version_file);
+            println!("{}: Updated {}", "Info".blue(), config.version_file);
+        }
     }
 
     // Render .tera templates via TemplateManager
-    let template_manager = TemplateManager::new(&workspace_state)?;
+    let mut template_manager = TemplateManager::new(&workspace_state)?;
+    let (features, tasks) = load_template_entities(&project_root)?;
+    template_manager.set_entities(features, tasks);
     let project_name = workspace_state.project_name.as_deref();
     let rendered_tera_files = template_manager.render_all_templates(&version_info, project_name)?;
     if !rendered_tera_files.is_empty() {
@@ -1395,7 +2971,7 @@ fn update_state(no_git: bool, git_add: bool) -> Result<()> {
     let mut rendered_wstemplate_files: Vec<String> = Vec::new();
     if let Some(entry) = workspace_state.wstemplate_entry() {
         let engine = WstemplateEngine::new(
-            version_info,
+            version_info.clone(),
             workspace_state.project_name.clone(),
             entry.alias.clone(),
             project_root.clone(),
@@ -1412,6 +2988,18 @@ fn update_state(no_git: bool, git_add: bool) -> Result<()> {
         }
     }
 
+    let package_files = if config.package_mode == PackageMode::Independent {
+        write_package_versions(&project_root, None)?
+    } else {
+        Vec::new()
+    };
+
+    let changelog_file = if config.changelog_on_update {
+        write_changelog(&project_root, &config, &version_info.full_version, None)?
+    } else {
+        None
+    };
+
     // Save state
     workspace_state.save(&project_root)?;
 
@@ -1423,6 +3011,12 @@ fn update_state(no_git: bool, git_add: bool) -> Result<()> {
             files_to_add.push(config.version_file.clone());
         }
 
+        for file in &propagated_files {
+            if !files_to_add.contains(file) {
+                files_to_add.push(file.clone());
+            }
+        }
+
         for file in &rendered_tera_files {
             files_to_add.push(file.clone());
         }
@@ -1431,6 +3025,14 @@ fn update_state(no_git: bool, git_add: bool) -> Result<()> {
             files_to_add.push(file.clone());
         }
 
+        for file in &package_files {
+            files_to_add.push(file.clone());
+        }
+
+        if let Some(file) = &changelog_file {
+            files_to_add.push(file.clone());
+        }
+
         if !files_to_add.is_empty() {
             let added_files = add_files_to_git(&files_to_add)?;
             if !added_files.is_empty() {
@@ -1446,6 +3048,121 @@ fn update_state(no_git: bool, git_add: bool) -> Result<()> {
     Ok(())
 }
 
+/// Write independently versioned packages' own versions into their manifests, or only
+/// `only`'s. Returns the manifests that changed.
+fn write_package_versions(project_root: &Path, only: Option<&str>) -> Result<Vec<String>> {
+    use wsb::st8::packages;
+
+    let all = packages::discover(project_root)?;
+    let selected = match only {
+        Some(name) => {
+            let package = packages::find(&all, name)?;
+            if package.version.is_none() {
+                anyhow::bail!("Package '{}' inherits the workspace version; it cannot be versioned on its own", name);
+            }
+            vec![package]
+        }
+        None => all,
+    };
+
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let head = wsb::st8::version_guard::head_commit();
+        let mut written = Vec::new();
+
+        for package in selected.iter().filter(|package| package.version.is_some()) {
+            let base = packages::base(&pool, package, head.as_deref()).await?;
+            let version_info = packages::independent_version(project_root, package, &base)?;
+            if package.version.as_deref() != Some(version_info.full_version.as_str()) {
+                wsb::st8::update_project_file(&version_info, &package.manifest)?;
+                log::info!("Package {} version {} -> {}", package.name, package.version.as_deref().unwrap_or("none"), version_info.full_version);
+                println!("{}: {} {}", "Info".blue(), package.name, version_info.full_version.green());
+                written.push(package.manifest.path.display().to_string());
+            }
+            packages::record_written(&pool, package, &version_info.full_version).await?;
+        }
+        anyhow::Ok(written)
+    })
+}
+
+/// Render the changelog section for `version` and write it into the configured file.
+/// Returns the file when its content changed.
+fn write_changelog(project_root: &Path, config: &St8Config, version: &str, since: Option<&str>) -> Result<Option<String>> {
+    let section = render_changelog(project_root, config, version, since)?;
+    let path = project_root.join(&config.changelog_file);
+    if !wsb::st8::changelog::write(&path, version, &section)? {
+        return Ok(None);
+    }
+    log::info!("Updated changelog {} for {}", config.changelog_file, version);
+    println!("{}: Updated {}", "Info".blue(), config.changelog_file);
+    Ok(Some(config.changelog_file.clone()))
+}
+
+fn render_changelog(project_root: &Path, config: &St8Config, version: &str, since: Option<&str>) -> Result<String> {
+    use wsb::st8::changelog;
+
+    let template = match &config.changelog_template {
+        Some(template) => {
+            let path = project_root.join(template);
+            Some(fs::read_to_string(&path).with_context(|| format!("Failed to read changelog template {}", path.display()))?)
+        }
+        None => None,
+    };
+    let entries = changelog::commits(project_root, since)?;
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    changelog::render(version, &date, &entries, template.as_deref())
+}
+
+fn handle_changelog(dry_run: bool, since: Option<&str>, on_update: Option<&str>, template: Option<String>) -> Result<()> {
+    let project_root = get_project_root()?;
+    let _lock = wsb::locks::acquire(&project_root, wsb::locks::DOCUMENTS, "wsb changelog")?;
+    let mut config = St8Config::load(&project_root)?;
+
+    if on_update.is_some() || template.is_some() {
+        if let Some(on_update) = on_update {
+            config.changelog_on_update = match on_update {
+                "on" => true,
+                "off" => false,
+                other => anyhow::bail!("Unknown --on-update value '{}' (expected on or off)", other),
+            };
+            println!("{} Changelog on update: {}", "✅".green(), on_update.green().bold());
+        }
+        if let Some(template) = template {
+            if !project_root.join(&template).exists() {
+                anyhow::bail!("Changelog template not found: {}", template);
+            }
+            println!("{} Changelog template: {}", "✅".green(), template.green().bold());
+            config.changelog_template = Some(template);
+        }
+        config.save(&project_root)?;
+    }
+
+    let (_, version_info) = calculate_version_with_scheme(&project_root)?;
+    if dry_run {
+        print!("{}", render_changelog(&project_root, &config, &version_info.full_version, since)?);
+        return Ok(());
+    }
+    if write_changelog(&project_root, &config, &version_info.full_version, since)?.is_none() {
+        println!("{}: {} is up to date", "Info".blue(), config.changelog_file);
+    }
+    Ok(())
+}
+
+/// The project's features and tasks, for templates that loop over them
+fn load_template_entities(project_root: &Path) -> Result<(Vec<wsb::entities::Feature>, Vec<wsb::entities::Task>)> {
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let entity_manager = EntityManager::new(pool);
+        let features = entity_manager.list_features().await?;
+        let tasks = entity_manager.list_tasks().await?;
+        anyhow::Ok((features, tasks))
+    })
+}
+
 fn handle_wstemplate_command(action: WstemplateAction) -> Result<()> {
     let project_root = get_project_root()?;
     let mut workspace_state = WorkspaceState::load(&project_root)?;
@@ -1540,29 +3257,51 @@ fn handle_wstemplate_command(action: WstemplateAction) -> Result<()> {
             }
         }
 
-        WstemplateAction::Render => {
+        WstemplateAction::Render { watch } => {
             let entry = workspace_state.wstemplate_entry()
                 .ok_or_else(|| anyhow::anyhow!(
                     "No wstemplate entry configured. Run 'wsb wstemplate add <scan-root>' first."
                 ))?;
 
-            let version_info = calculate_version(&project_root)?;
+            let render = || -> Result<()> {
+                let version_info = calculate_version(&project_root)?;
 
-            let engine = WstemplateEngine::new(
-                version_info,
-                workspace_state.project_name.clone(),
-                entry.alias.clone(),
-                project_root.clone(),
-                entry.root.clone(),
-            );
+                let engine = WstemplateEngine::new(
+                    version_info,
+                    workspace_state.project_name.clone(),
+                    entry.alias.clone(),
+                    project_root.clone(),
+                    entry.root.clone(),
+                );
 
-            let rendered = engine.render_relevant()?;
-            if rendered.is_empty() {
-                println!("No templates to render.");
-            } else {
-                println!("Rendered {} templates:", rendered.len());
-                for r in &rendered {
-                    println!("  {} \u{2192} {}", r.source_path.display(), r.output_path.display());
+                let rendered = engine.render_relevant()?;
+                if rendered.is_empty() {
+                    println!("No templates to render.");
+                } else {
+                    println!("Rendered {} templates:", rendered.len());
+                    for r in &rendered {
+                        println!("  {} \u{2192} {}", r.source_path.display(), r.output_path.display());
+                    }
+                }
+                Ok(())
+            };
+
+            if !watch {
+                return render();
+            }
+
+            // Rendered outputs are not .wstemplate files, so rendering never re-triggers itself
+            let service = WatchService::start(&entry.root, WatchConfig::default())?;
+            let changes = service.subscribe(|path| {
+                path.extension().map_or(false, |ext| ext == "wstemplate")
+            });
+
+            render()?;
+            println!("Watching {} for .wstemplate changes (Ctrl+C to stop)...", service.root().display());
+            while let Some(batch) = changes.recv() {
+                println!("{} changed template(s)", batch.paths.len());
+                if let Err(e) = render() {
+                    eprintln!("{}: {:#}", "Error".red(), e);
                 }
             }
         }
@@ -1571,39 +3310,66 @@ fn handle_wstemplate_command(action: WstemplateAction) -> Result<()> {
     Ok(())
 }
 
-fn run_scrap_command(paths: Vec<std::path::PathBuf>, command: Option<ScrapCommands>) -> Result<()> {
+/// `--secure` and `--passes` for `scrap clean` and `scrap purge`
+fn push_secure_args(args: &mut Vec<String>, secure: bool, passes: Option<u32>) {
+    if secure {
+        args.push("--secure".to_string());
+        if let Some(passes) = passes {
+            args.push("--passes".to_string());
+            args.push(passes.to_string());
+        }
+    }
+}
+
+fn run_scrap_command(paths: Vec<std::path::PathBuf>, allow_protected: bool, system_trash: bool, format: String, command: Option<ScrapCommands>) -> Result<()> {
     let mut args = Vec::new();
     
     // Convert clap ScrapCommands to original scrap binary arguments
     match command {
-        Some(ScrapCommands::List { sort }) => {
+        Some(ScrapCommands::List { sort, duplicates }) => {
             args.push("list".to_string());
             args.push("--sort".to_string());
             args.push(sort);
+            if duplicates {
+                args.push("--duplicates".to_string());
+            }
         }
-        Some(ScrapCommands::Clean { days, dry_run }) => {
+        Some(ScrapCommands::Clean { days, dry_run, secure, passes }) => {
             args.push("clean".to_string());
             args.push("--days".to_string());
             args.push(days.to_string());
             if dry_run {
                 args.push("--dry-run".to_string());
             }
+            push_secure_args(&mut args, secure, passes);
         }
-        Some(ScrapCommands::Purge { force }) => {
+        Some(ScrapCommands::Purge { force, secure, passes }) => {
             args.push("purge".to_string());
             if force {
                 args.push("--force".to_string());
             }
+            push_secure_args(&mut args, secure, passes);
         }
-        Some(ScrapCommands::Find { pattern, content }) => {
+        Some(ScrapCommands::Find { pattern, content, max_size }) => {
             args.push("find".to_string());
             args.push(pattern);
             if content {
                 args.push("--content".to_string());
             }
+            if let Some(max_size) = max_size {
+                args.push("--max-size".to_string());
+                args.push(max_size.to_string());
+            }
         }
-        Some(ScrapCommands::Archive { output, remove }) => {
+        Some(ScrapCommands::Archive { output, remove, tier, days }) => {
             args.push("archive".to_string());
+            if tier {
+                args.push("--tier".to_string());
+            }
+            if let Some(days) = days {
+                args.push("--days".to_string());
+                args.push(days.to_string());
+            }
             if let Some(output_path) = output {
                 args.push("--output".to_string());
                 args.push(output_path.to_string_lossy().to_string());
@@ -1612,28 +3378,80 @@ fn run_scrap_command(paths: Vec<std::path::PathBuf>, command: Option<ScrapComman
                 args.push("--remove".to_string());
             }
         }
+        Some(ScrapCommands::Rm { names }) => {
+            args.push("rm".to_string());
+            args.extend(names);
+        }
+        Some(ScrapCommands::Config { max_age_days, max_size, max_items, clear }) => {
+            args.push("config".to_string());
+            if clear {
+                args.push("--clear".to_string());
+            }
+            if let Some(days) = max_age_days {
+                args.push("--max-age-days".to_string());
+                args.push(days.to_string());
+            }
+            if let Some(size) = max_size {
+                args.push("--max-size".to_string());
+                args.push(size);
+            }
+            if let Some(items) = max_items {
+                args.push("--max-items".to_string());
+                args.push(items.to_string());
+            }
+        }
         None => {
             // Add all paths as arguments
             for path in paths {
                 args.push(path.to_string_lossy().to_string());
             }
+            if allow_protected {
+                args.push(wsb::scrap::guard::OVERRIDE_FLAG.to_string());
+            }
+            if system_trash {
+                args.push(wsb::scrap::trash::SYSTEM_TRASH_FLAG.to_string());
+            }
         }
     }
+    args.push(wsb::scrap::report::FORMAT_FLAG.to_string());
+    args.push(format);
     
     wsb::run_scrap(args)
 }
 
-fn run_unscrap_command(name: Option<String>, force: bool, to: Option<std::path::PathBuf>) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+fn run_unscrap_command(name: Option<String>, force: bool, to: Option<std::path::PathBuf>, interactive: bool, all: bool, since: Option<String>, merge: bool, backup_existing: bool) -> Result<()> {
     let mut args = Vec::new();
     
     if let Some(item_name) = name {
         args.push(item_name);
     }
     
+    if interactive {
+        args.push("--interactive".to_string());
+    }
+    
+    if all {
+        args.push("--all".to_string());
+    }
+    
+    if let Some(since) = since {
+        args.push("--since".to_string());
+        args.push(since);
+    }
+    
     if force {
         args.push("--force".to_string());
     }
     
+    if merge {
+        args.push("--merge".to_string());
+    }
+    
+    if backup_existing {
+        args.push("--backup-existing".to_string());
+    }
+    
     if let Some(target_path) = to {
         args.push("--to".to_string());
         args.push(target_path.to_string_lossy().to_string());
@@ -1642,33 +3460,100 @@ fn run_unscrap_command(name: Option<String>, force: bool, to: Option<std::path::
     wsb::run_unscrap(args)
 }
 
+fn run_open_command(target: &str, print: bool) -> Result<()> {
+    use wsb::open::{self, OpenConfig, Target};
+
+    let project_root = get_project_root()?;
+    let state = WorkspaceState::load(&project_root).unwrap_or_default();
+    let config = state.get_tool_config::<OpenConfig>("open").unwrap_or_default();
+    let docs = state
+        .get_tool_config::<wsb::entities::doc_coverage::DocCoverageConfig>("doc_coverage")
+        .unwrap_or_default();
+
+    let mut target = Target::parse(target)?;
+    if let Target::Task(task_id) = &target {
+        let db_path = project_root.join(".wsb/project.db");
+        let rt = tokio::runtime::Runtime::new()?;
+        let task = rt.block_on(async {
+            let pool = wsb::entities::database::initialize_database(&db_path).await?;
+            wsb::entities::crud::tasks::get_by_id(&pool, task_id).await
+        })?;
+        let task = task.ok_or_else(|| anyhow::anyhow!("Task {} not found", task_id))?;
+        println!("{} {} belongs to {}", "→".cyan(), task_id, task.feature_id.bold());
+        target = Target::Feature(task.feature_id);
+    }
+
+    let action = open::resolve(&project_root, &target, &config, &docs)?;
+    if print {
+        println!("{}", action.location());
+        return Ok(());
+    }
+    println!("{} Opening {}", "📂".blue(), action.location());
+    open::launch(&action, &config)
+}
+
 fn run_ldiff_command(substitute_char: String) -> Result<()> {
     wsb::run_ldiff(vec![substitute_char.clone()])
 }
 
-fn install_hook(force: bool) -> Result<()> {
-    if !is_git_repository() {
-        log::warn!("install_hook called outside git repository");
-        eprintln!("{}: Not in a git repository", "Error".red());
-        eprintln!("{}: Navigate to a git repository and try again", "Tip".yellow());
-        return Ok(());
+fn ldiff_substitute(substitute_char: &str) -> Result<char> {
+    substitute_char.chars().next()
+        .ok_or_else(|| anyhow::anyhow!("Substitute character must not be empty"))
+}
+
+fn run_ldiff_stdin(mut differ: wsb::ldiff::LineDiffer) -> Result<()> {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    wsb::ldiff::process_reader(stdin.lock(), &mut differ, &mut out)
+}
+
+fn run_ldiff_follow(path: &std::path::Path, mut differ: wsb::ldiff::LineDiffer, poll_ms: Option<u64>) -> Result<()> {
+    let options = wsb::ldiff::FollowOptions { poll: poll_ms.map(std::time::Duration::from_millis) };
+    let stop = wsb::ldiff::stop_on_interrupt()?;
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    wsb::ldiff::follow(path, &mut differ, &options, &stop, &mut out)
+}
+
+fn run_ldiff_compare(first: &std::path::Path, second: &std::path::Path, mut differ: wsb::ldiff::LineDiffer) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let (first_lines, second_lines) = wsb::ldiff::compare_files(first, second, &mut differ, &mut out)?;
+    if first_lines != second_lines {
+        eprintln!("{} has {} lines, {} has {}", first.display(), first_lines, second.display(), second_lines);
     }
-    
-    let git_root = get_git_root()?;
-    let hooks_dir = git_root.join(".git").join("hooks");
-    let hook_file = hooks_dir.join("pre-commit");
-    
-    // Create hooks directory if it doesn't exist
-    if !hooks_dir.exists() {
-        fs::create_dir_all(&hooks_dir)
-            .context("Failed to create git hooks directory")?;
-        log::info!("Created git hooks directory: {}", hooks_dir.display());
+    Ok(())
+}
+
+fn run_ldiff_label_streams(inputs: &[String], by_timestamp: Option<&str>, differ: &wsb::ldiff::LineDiffer) -> Result<()> {
+    let sources = inputs.iter()
+        .map(|spec| wsb::ldiff::StreamSource::parse(spec))
+        .collect::<Result<Vec<_>>>()?;
+
+    let parser = by_timestamp
+        .map(wsb::ldiff::TimestampParser::new)
+        .transpose()?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    wsb::ldiff::process_labelled_streams_with(&sources, differ, parser.as_ref(), &mut out)
+}
+
+fn install_hook(force: bool, kind: HookKind) -> Result<()> {
+    if !is_git_repository() {
+        log::warn!("install_hook called outside git repository");
+        eprintln!("{}: Not in a git repository", "Error".red());
+        eprintln!("{}: Navigate to a git repository and try again", "Tip".yellow());
+        return Ok(());
     }
     
+    let git_root = get_git_root()?;
+    
     // Check if already installed
-    if !force && is_hook_installed()? {
-        println!("{} Git hook is already installed", "Info".blue());
-        println!("{} Use 'wsb git install --force' to reinstall", "Tip".yellow());
+    if !force && wsb::st8::hooks::is_installed(&git_root, kind)? {
+        println!("{} Git {} hook is already installed", "Info".blue(), kind);
+        println!("{} Use 'wsb git install --hook {} --force' to reinstall", "Tip".yellow(), kind);
         return Ok(());
     }
     
@@ -1676,55 +3561,24 @@ fn install_hook(force: bool) -> Result<()> {
     let current_exe = env::current_exe()
         .context("Failed to get current executable path")?;
     
-    let st8_block = format!(
-        "#!/bin/bash\n# === WS BLOCK START ===\n# DO NOT EDIT THIS BLOCK MANUALLY\n# Use 'wsb git uninstall' to remove this hook\n{} update --git-add\n# === WS BLOCK END ===\n",
-        current_exe.display()
-    );
-    
-    if hook_file.exists() {
-        // Read existing hook content
-        let existing_content = fs::read_to_string(&hook_file)
-            .context("Failed to read existing pre-commit hook")?;
-        
-        // Remove any existing st8 block
-        let cleaned_content = remove_st8_block(&existing_content);
-        
-        // Append new st8 block
-        let new_content = if cleaned_content.trim().is_empty() {
-            st8_block
-        } else {
-            format!("{}\n{}", cleaned_content.trim_end(), st8_block)
-        };
-        
-        fs::write(&hook_file, new_content)
-            .context("Failed to update pre-commit hook")?;
-        
-        log::info!("Updated existing pre-commit hook: {}", hook_file.display());
-    } else {
-        // Create new hook file
-        fs::write(&hook_file, &st8_block)
-            .context("Failed to create pre-commit hook")?;
-        
-        log::info!("Created new pre-commit hook: {}", hook_file.display());
-    }
-    
-    // Make hook executable on Unix systems
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&hook_file)?.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&hook_file, perms)?;
-    }
+    let hook_file = wsb::st8::hooks::install(&git_root, kind, &current_exe)?;
     
-    log::info!("Git pre-commit hook installed successfully at {}", hook_file.display());
-    println!("{} Git hook installed successfully", "Success".green());
-    println!("{} Version will be updated automatically on each commit", "Info".blue());
+    log::info!("Git {} hook installed successfully at {}", kind, hook_file.display());
+    println!("{} Git hook installed successfully ({})", "Success".green(), kind);
+    let effect = match kind {
+        HookKind::PreCommit => "Version will be updated automatically on each commit",
+        HookKind::PrepareCommitMsg => "Commit messages will get a Version trailer",
+        HookKind::PostCommit => "The version will be reported after each commit",
+        HookKind::PrePush => "Pushes will be refused while the version file has uncommitted changes",
+    };
+    println!("{} {}", "Info".blue(), effect);
     
     Ok(())
 }
 
-fn uninstall_hook() -> Result<()> {
+fn uninstall_hook(kind: Option<HookKind>) -> Result<()> {
+    use wsb::st8::hooks::Removal;
+
     if !is_git_repository() {
         log::warn!("uninstall_hook called outside git repository");
         eprintln!("{}: Not in a git repository", "Error".red());
@@ -1732,37 +3586,88 @@ fn uninstall_hook() -> Result<()> {
     }
     
     let git_root = get_git_root()?;
-    let hook_file = git_root.join(".git").join("hooks").join("pre-commit");
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => HookKind::ALL.to_vec(),
+    };
     
-    if !hook_file.exists() {
-        println!("{} No pre-commit hook found", "Info".blue());
-        return Ok(());
+    let mut removed = 0;
+    for kind in kinds {
+        match wsb::st8::hooks::uninstall(&git_root, kind)? {
+            Removal::NotInstalled => {}
+            Removal::Deleted => {
+                removed += 1;
+                println!("{} Removed {} hook", "Success".green(), kind);
+                log::info!("Removed {} hook", kind);
+            }
+            Removal::Stripped => {
+                removed += 1;
+                println!("{} Removed st8 from {} hook", "Success".green(), kind);
+                log::info!("Removed st8 block from {} hook", kind);
+            }
+        }
     }
     
-    let content = fs::read_to_string(&hook_file)
-        .context("Failed to read pre-commit hook")?;
+    if removed == 0 {
+        match kind {
+            Some(kind) => println!("{} No st8 hook block found in {} hook", "Info".blue(), kind),
+            None if !wsb::st8::hooks::hook_path(&git_root, HookKind::PreCommit).exists() => {
+                println!("{} No pre-commit hook found", "Info".blue())
+            }
+            None => println!("{} No st8 hook blocks found", "Info".blue()),
+        }
+    } else {
+        println!("{} Git hook uninstalled successfully", "Success".green());
+    }
     
-    if !content.contains("=== WS BLOCK START ===") {
-        println!("{} No st8 hook block found in pre-commit hook", "Info".blue());
+    Ok(())
+}
+
+/// Append a `Version:` trailer with the version file's content to a commit message
+fn stamp_commit_message(file: &std::path::Path) -> Result<()> {
+    let project_root = get_project_root()?;
+    let config = St8Config::load(&project_root)?;
+    if config.version_file.is_empty() {
         return Ok(());
     }
     
-    let cleaned_content = remove_st8_block(&content);
+    let version_path = project_root.join(&config.version_file);
+    let version = match fs::read_to_string(&version_path) {
+        Ok(version) if !version.trim().is_empty() => version.trim().to_string(),
+        _ => {
+            log::info!("No version in {}, leaving commit message unchanged", version_path.display());
+            return Ok(());
+        }
+    };
+    
+    let message = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read commit message {}", file.display()))?;
+    fs::write(file, wsb::st8::hooks::stamp_message(&message, &version))
+        .with_context(|| format!("Failed to write commit message {}", file.display()))?;
+    Ok(())
+}
+
+/// Refuse (exit non-zero) while the version file differs from what is committed
+fn check_version_committed() -> Result<()> {
+    let project_root = get_project_root()?;
+    let config = St8Config::load(&project_root)?;
+    if config.version_file.is_empty() || !is_git_repository() {
+        return Ok(());
+    }
     
-    if cleaned_content.trim().is_empty() {
-        // Remove the entire hook file if only st8 content
-        fs::remove_file(&hook_file)
-            .context("Failed to remove pre-commit hook")?;
-        println!("{} Removed pre-commit hook", "Success".green());
-        log::info!("Removed pre-commit hook: {}", hook_file.display());
-    } else {
-        // Write back the cleaned content
-        fs::write(&hook_file, cleaned_content.trim_end())
-            .context("Failed to update pre-commit hook")?;
-        println!("{} Removed st8 from pre-commit hook", "Success".green());
-        log::info!("Removed st8 block from pre-commit hook: {}", hook_file.display());
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "--", &config.version_file])
+        .output()
+        .context("Failed to run git status")?;
+    let pending = String::from_utf8_lossy(&output.stdout);
+    if !pending.trim().is_empty() {
+        anyhow::bail!(
+            "{} has uncommitted changes; commit the version update before pushing",
+            config.version_file
+        );
     }
     
+    println!("{} {} is committed", "✓".green(), config.version_file);
     Ok(())
 }
 
@@ -1772,8 +3677,9 @@ fn show_version() -> Result<()> {
     
     println!("{}", "Version Information".bold().underline());
     println!();
-    let version_info = VersionInfo::calculate()?;
+    let (scheme, version_info) = calculate_version_with_scheme(&project_root)?;
     println!("{}: {}", "Current Version".blue(), version_info.full_version);
+    println!("{}: {} ({})", "Version Scheme".blue(), scheme, scheme.description());
     println!("{}: {}", "Project Name".blue(), workspace_state.project_name.as_deref().unwrap_or("Unknown"));
     
     if is_git_repository() {
@@ -1816,11 +3722,21 @@ fn show_status() -> Result<()> {
     println!();
     
     // Hook status
-    if is_hook_installed()? {
-        println!("{}: Installed ✓", "Pre-commit Hook".green());
+    for kind in HookKind::ALL {
+        let label = format!("{} hook", kind);
+        if wsb::st8::hooks::is_installed(&git_root, kind)? {
+            println!("{}: Installed ✓", label.green());
+        } else if kind == HookKind::PreCommit {
+            println!("{}: Not installed ✗", label.red());
+            println!("{}: Run 'wsb git install' to set up automatic version management", "Tip".yellow());
+        } else {
+            println!("{}: Not installed", label.blue());
+        }
+    }
+    if wsb::st8::hooks::is_directive_gate_installed(&git_root)? {
+        println!("{}: Installed ✓", "Directive check".green());
     } else {
-        println!("{}: Not installed ✗", "Pre-commit Hook".red());
-        println!("{}: Run 'wsb git install' to set up automatic version management", "Tip".yellow());
+        println!("{}: Not installed", "Directive check".blue());
     }
     
     // Version file status
@@ -1837,8 +3753,8 @@ fn show_status() -> Result<()> {
     
     // Show current version
     let workspace_state = WorkspaceState::load(&git_root)?;
-    let version_info = VersionInfo::calculate()?;
-    println!("{}: {}", "Current Version".blue(), version_info.full_version);
+    let (scheme, version_info) = calculate_version_with_scheme(&git_root)?;
+    println!("{}: {} ({})", "Current Version".blue(), version_info.full_version, scheme);
     
     // Template status
     let template_manager = TemplateManager::new(&workspace_state)?;
@@ -1855,15 +3771,61 @@ fn show_status() -> Result<()> {
 }
 
 fn calculate_version(project_root: &std::path::Path) -> Result<VersionInfo> {
+    let (_, version_info) = calculate_version_with_scheme(project_root)?;
+    Ok(version_info)
+}
+
+/// Calculate the version with the project's configured scheme, returning the scheme too
+fn calculate_version_with_scheme(project_root: &std::path::Path) -> Result<(VersionScheme, VersionInfo)> {
     let db_path = project_root.join(".wsb/project.db");
     let rt = tokio::runtime::Runtime::new()?;
-    let version_info = rt.block_on(async {
+    let (scheme, version_info) = rt.block_on(async {
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
-        let major_version = get_project_major_version(&pool).await?;
-        wsb::st8::VersionInfo::calculate_with_major(major_version)
+        calculate_project_version(&pool).await
     })?;
-    log::info!("Version calculated: {}", version_info.full_version);
-    Ok(version_info)
+    log::info!("Version calculated with {} scheme: {}", scheme, version_info.full_version);
+    Ok((scheme, version_info))
+}
+
+/// The version to write: the calculated one, checked against recorded versions and
+/// release tags so it never duplicates or goes below them, or `force_version` as given.
+/// The result is recorded for the next check.
+fn calculate_guarded_version(project_root: &std::path::Path, force_version: Option<&str>) -> Result<VersionInfo> {
+    use wsb::st8::version_guard::{self, Verdict};
+
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let head = version_guard::head_commit();
+
+        let version_info = match force_version {
+            Some(forced) => {
+                let version_info = VersionInfo::from_version(forced)?;
+                log::warn!("Version forced to {}", version_info.full_version);
+                println!("{}: Forcing version {}", "Warning".yellow(), version_info.full_version);
+                version_info
+            }
+            None => {
+                let (_, calculated) = calculate_project_version(&pool).await?;
+                let mut recorded = version_guard::history(&pool).await?;
+                recorded.extend(version_guard::release_tags()?);
+                match version_guard::check(&calculated.full_version, head.as_deref(), &recorded, version_guard::in_history)? {
+                    Verdict::Advance => calculated,
+                    Verdict::Keep(version) => {
+                        if version != calculated.full_version {
+                            log::info!("HEAD already has version {}; keeping it over {}", version, calculated.full_version);
+                        }
+                        VersionInfo::from_version(&version)?
+                    }
+                }
+            }
+        };
+
+        version_guard::record(&pool, &version_info.full_version, head.as_deref(), force_version.is_some()).await?;
+        log::info!("Version calculated: {}", version_info.full_version);
+        anyhow::Ok(version_info)
+    })
 }
 
 fn log_action(message: &str) {
@@ -1880,15 +3842,79 @@ fn log_to_file(message: &str) -> Result<()> {
 }
 
 fn handle_template_command(action: TemplateAction) -> Result<()> {
+    // Rendering calculates the version on its own runtime
+    if let TemplateAction::Render { dry_run } = action {
+        return handle_template_render(dry_run);
+    }
+
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
         match action {
             TemplateAction::GenerateDocs { doc_type, output, force } => {
+                let _lock = wsb::locks::acquire(&get_project_root()?, wsb::locks::DOCUMENTS, "wsb template generate-docs")?;
                 handle_generate_docs(&doc_type, output.as_deref(), force).await
             }
             TemplateAction::InitDocs { force } => {
+                let _lock = wsb::locks::acquire(&get_project_root()?, wsb::locks::DOCUMENTS, "wsb template init-docs")?;
                 handle_init_docs(force)
             }
+            TemplateAction::Test { name, fixture, expect } => {
+                handle_template_test(&name, &fixture, expect.as_deref())
+            }
+            TemplateAction::Order { name, partial, no_partial, depends_on } => {
+                let project_root = get_project_root()?;
+                let _lock = wsb::locks::acquire(&project_root, wsb::locks::DOCUMENTS, "wsb template order")?;
+                let workspace_state = WorkspaceState::load(&project_root)?;
+                let mut template_manager = TemplateManager::new(&workspace_state)?;
+
+                if let Some(name) = name {
+                    let partial = if partial { Some(true) } else if no_partial { Some(false) } else { None };
+                    let depends_on = depends_on.map(|names| names.into_iter().filter(|n| !n.trim().is_empty()).collect());
+                    if !template_manager.configure_template(&name, partial, depends_on)? {
+                        anyhow::bail!("Template not found: {}", name);
+                    }
+                    println!("{} Template '{}' updated", "✅".green(), name);
+                }
+
+                println!("{}", "Render order:".blue().bold());
+                for (index, name) in template_manager.render_order()?.iter().enumerate() {
+                    let template_config = template_manager.get_template(name).expect("ordered templates exist");
+                    let mut notes = Vec::new();
+                    if template_config.partial {
+                        notes.push("partial".to_string());
+                    }
+                    if !template_config.enabled {
+                        notes.push("disabled".to_string());
+                    }
+                    if !template_config.depends_on.is_empty() {
+                        notes.push(format!("after {}", template_config.depends_on.join(", ")));
+                    }
+                    let notes = if notes.is_empty() { String::new() } else { format!(" ({})", notes.join("; ")) };
+                    println!("  {}. {}{}", index + 1, name.cyan(), notes.dimmed());
+                }
+                Ok(())
+            }
+            TemplateAction::Source { name, var, env, default, command, state, file } => {
+                use wsb::st8::sources::{ContextSource, NamedSource};
+
+                let source = match (env, command, state, file) {
+                    (Some(var), _, _, _) => ContextSource::Env { var, default },
+                    (_, Some(command), _, _) => ContextSource::Command { command },
+                    (_, _, Some(key), _) => ContextSource::State { key },
+                    (_, _, _, Some(path)) => ContextSource::File { path },
+                    _ => anyhow::bail!("Specify one of --env, --command, --state or --file"),
+                };
+
+                let project_root = get_project_root()?;
+                let _lock = wsb::locks::acquire(&project_root, wsb::locks::DOCUMENTS, "wsb template source")?;
+                let workspace_state = WorkspaceState::load(&project_root)?;
+                let mut template_manager = TemplateManager::new(&workspace_state)?;
+                if !template_manager.add_source(&name, NamedSource { name: var.clone(), source })? {
+                    anyhow::bail!("Template not found: {}", name);
+                }
+                println!("{} Template '{}' now exposes {{{{ vars.{} }}}}", "✅".green(), name, var);
+                Ok(())
+            }
             TemplateAction::List => {
                 println!("Template management not yet implemented");
                 Ok(())
@@ -1901,9 +3927,114 @@ fn handle_template_command(action: TemplateAction) -> Result<()> {
     })
 }
 
+/// Render every enabled template with the current version, or with `dry_run` show how
+/// each output would change and fail when any of them is out of date
+fn handle_template_render(dry_run: bool) -> Result<()> {
+    use wsb::st8::diff::{diff_lines, DiffLine};
+
+    let project_root = get_project_root()?;
+    let _lock = if dry_run {
+        None
+    } else {
+        Some(wsb::locks::acquire(&project_root, wsb::locks::DOCUMENTS, "wsb template render")?)
+    };
+    let workspace_state = WorkspaceState::load(&project_root)?;
+    let mut template_manager = TemplateManager::new(&workspace_state)?;
+    let (features, tasks) = load_template_entities(&project_root)?;
+    template_manager.set_entities(features, tasks);
+    let (_, version_info) = calculate_version_with_scheme(&project_root)?;
+    let project_name = workspace_state.project_name.as_deref();
+
+    if !dry_run {
+        let rendered = template_manager.render_all_templates(&version_info, project_name)?;
+        println!("{} Rendered {} template(s)", "✅".green(), rendered.len());
+        for file in &rendered {
+            println!("  - {}", file);
+        }
+        return Ok(());
+    }
+
+    let previews = template_manager.preview_all_templates(&version_info, project_name)?;
+    let drifted: Vec<_> = previews.iter().filter(|preview| preview.has_drift()).collect();
+    for preview in &drifted {
+        let state = if preview.current.is_some() { "current" } else { "missing" };
+        println!("{} {} ({})", "---".red().bold(), preview.output_path.display(), state);
+        println!("{} {} (template {})", "+++".green().bold(), preview.output_path.display(), preview.name);
+
+        let diff = diff_lines(preview.current.as_deref().unwrap_or(""), &preview.rendered, 3);
+        if diff.is_empty() {
+            println!("{}", "  (only line endings or the trailing newline differ)".yellow());
+        }
+        for line in diff {
+            match line {
+                DiffLine::Context(line) => println!(" {}", line),
+                DiffLine::Removed(line) => println!("{}", format!("-{}", line).red()),
+                DiffLine::Added(line) => println!("{}", format!("+{}", line).green()),
+                DiffLine::Skipped(count) => println!("{}", format!("@@ {} unchanged line(s) @@", count).cyan()),
+            }
+        }
+        println!();
+    }
+
+    if drifted.is_empty() {
+        println!("{} All {} template output(s) are up to date", "✅".green(), previews.len());
+        return Ok(());
+    }
+    anyhow::bail!("{} of {} template output(s) are out of date; run 'wsb template render'", drifted.len(), previews.len())
+}
+
+/// Render a template against a fixture and optionally compare it with an expected file
+fn handle_template_test(name: &str, fixture: &Path, expect: Option<&Path>) -> Result<()> {
+    let context = wsb::st8::load_fixture(fixture)?;
+
+    let project_root = get_project_root()?;
+    let workspace_state = WorkspaceState::load(&project_root)?;
+    let template_manager = TemplateManager::new(&workspace_state)?;
+
+    let rendered = if template_manager.get_template(name).is_some() {
+        template_manager.render_to_string(name, &context)?
+    } else {
+        // Built-in documentation templates, from the same place `generate-docs` reads them
+        let builtin = project_root.join("src/templates").join(format!("{}.tera", name.trim_end_matches(".tera")));
+        if !builtin.exists() {
+            anyhow::bail!("Template not found: {} (not configured and no {})", name, builtin.display());
+        }
+        let source = fs::read_to_string(&builtin)
+            .with_context(|| format!("Failed to read template: {}", builtin.display()))?;
+        let mut tera = tera::Tera::default();
+        wsb::st8::templates::register_filters(&mut tera);
+        tera.add_raw_template(name, &source)
+            .with_context(|| format!("Failed to register template: {}", name))?;
+        tera.render(name, &context)
+            .with_context(|| format!("Failed to render template: {}", name))?
+    };
+
+    let expect = match expect {
+        Some(expect) => expect,
+        None => {
+            print!("{}", rendered);
+            return Ok(());
+        }
+    };
+
+    let expected = fs::read_to_string(expect)
+        .with_context(|| format!("Failed to read expected output: {}", expect.display()))?;
+    match wsb::st8::compare_output(&expected, &rendered) {
+        None => {
+            println!("{} Template '{}' matches {}", "✅".green(), name, expect.display());
+            Ok(())
+        }
+        Some(mismatch) => {
+            eprintln!("{} Template '{}' differs from {} at line {}", "❌".red(), name, expect.display(), mismatch.line);
+            eprintln!("  {} {}", "expected:".bold(), mismatch.expected.as_deref().unwrap_or("<end of output>"));
+            eprintln!("  {} {}", "actual:  ".bold(), mismatch.actual.as_deref().unwrap_or("<end of output>"));
+            anyhow::bail!("Template output does not match {}", expect.display())
+        }
+    }
+}
+
 async fn handle_generate_docs(doc_type: &str, output_dir: Option<&str>, force: bool) -> Result<()> {
     use tera::Tera;
-    use std::collections::HashMap;
     
     let db_path = get_project_root()?.join(".wsb/project.db");
     let pool = wsb::entities::database::initialize_database(&db_path).await?;
@@ -1917,6 +4048,8 @@ async fn handle_generate_docs(doc_type: &str, output_dir: Option<&str>, force: b
     let features = entity_manager.list_features().await?;
     let sessions = entity_manager.list_sessions_by_project(&project.id).await?;
     let tasks = entity_manager.list_tasks().await?;
+    let bodies = wsb::entities::bodies::all(&entity_manager.pool).await?;
+    let milestones = entity_manager.list_milestones(&project.id).await?;
     
     // Calculate metrics
     let total_features = features.len();
@@ -1928,18 +4061,7 @@ async fn handle_generate_docs(doc_type: &str, output_dir: Option<&str>, force: b
     // Setup Tera template engine with embedded templates
     let mut tera = Tera::new("src/templates/*.tera")?;
     
-    // Add custom filters
-    tera.register_filter("feature_state_emoji", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
-        match value.as_str().unwrap_or("") {
-            "not_implemented" => Ok(tera::Value::String("❌".to_string())),
-            "implemented_no_tests" => Ok(tera::Value::String("🟠".to_string())),
-            "implemented_failing_tests" => Ok(tera::Value::String("🟡".to_string())),
-            "implemented_passing_tests" => Ok(tera::Value::String("🟢".to_string())),
-            "tests_broken" => Ok(tera::Value::String("⚠️".to_string())),
-            "critical_issue" => Ok(tera::Value::String("🔴".to_string())),
-            _ => Ok(tera::Value::String("❓".to_string())),
-        }
-    });
+    wsb::st8::templates::register_filters(&mut tera);
     
     let output_path = output_dir.unwrap_or(".");
     
@@ -1947,10 +4069,10 @@ async fn handle_generate_docs(doc_type: &str, output_dir: Option<&str>, force: b
         "all" => {
             generate_claude_md(&tera, &project, &features, &sessions, &tasks, 
                              implementation_percentage, test_percentage, output_path, force).await?;
-            generate_features_md(&tera, &project, &features, total_features, 
+            generate_features_md(&tera, &project, &features, &bodies, total_features, 
                                 implementation_percentage, test_percentage, output_path, force).await?;
             generate_progress_md(&tera, &sessions, output_path, force).await?;
-            generate_status_report(&project, &features, &tasks, &sessions, 
+            generate_status_report(&project, &features, &tasks, &sessions, &milestones,
                                  implementation_percentage, test_percentage, output_path, force).await?;
         }
         "claude" => {
@@ -1958,14 +4080,14 @@ async fn handle_generate_docs(doc_type: &str, output_dir: Option<&str>, force: b
                              implementation_percentage, test_percentage, output_path, force).await?;
         }
         "features" => {
-            generate_features_md(&tera, &project, &features, total_features, 
+            generate_features_md(&tera, &project, &features, &bodies, total_features, 
                                 implementation_percentage, test_percentage, output_path, force).await?;
         }
         "progress" => {
             generate_progress_md(&tera, &sessions, output_path, force).await?;
         }
         "status" => {
-            generate_status_report(&project, &features, &tasks, &sessions, 
+            generate_status_report(&project, &features, &tasks, &sessions, &milestones,
                                  implementation_percentage, test_percentage, output_path, force).await?;
         }
         _ => {
@@ -2010,6 +4132,7 @@ async fn generate_features_md(
     tera: &tera::Tera,
     project: &wsb::entities::schema_models::Project,
     features: &[wsb::entities::schema_models::Feature],
+    bodies: &HashMap<String, String>,
     total_features: usize,
     implementation_percentage: usize,
     test_percentage: usize,
@@ -2021,6 +4144,11 @@ async fn generate_features_md(
     let mut context = tera::Context::new();
     context.insert("project", project);
     context.insert("features", features);
+    // Bodies sit under a ### heading per feature
+    let bodies: HashMap<&String, String> = bodies.iter()
+        .map(|(id, body)| (id, wsb::entities::bodies::shift_headings(body, 2)))
+        .collect();
+    context.insert("bodies", &bodies);
     context.insert("total_features", &total_features);
     context.insert("implementation_percentage", &implementation_percentage);
     context.insert("test_percentage", &test_percentage);
@@ -2097,6 +4225,7 @@ async fn generate_status_report(
     features: &[wsb::entities::schema_models::Feature],
     tasks: &[wsb::entities::schema_models::Task],
     sessions: &[wsb::entities::schema_models::Session],
+    milestones: &[wsb::entities::schema_models::Milestone],
     implementation_percentage: usize,
     test_percentage: usize,
     output_path: &str,
@@ -2134,6 +4263,17 @@ async fn generate_status_report(
         };
         content.push_str(&format!("- {} {}: {}\n", emoji, state, count));
     }
+
+    if !milestones.is_empty() {
+        content.push_str("\n## Milestones\n\n");
+        content.push_str("| Milestone | Status | Target | Completion |\n");
+        content.push_str("|-----------|--------|--------|------------|\n");
+        for milestone in milestones {
+            content.push_str(&format!("| {} {} | {} | {} | {:.1}% |\n",
+                milestone.id, milestone.title, milestone.status,
+                milestone.target_date.as_deref().unwrap_or("-"), milestone.completion_percentage));
+        }
+    }
     
     content.push_str("\n---\n\n*Generated from database entities*\n");
     
@@ -2173,47 +4313,7 @@ fn is_hook_installed() -> Result<bool> {
         return Ok(false);
     }
     
-    let git_root = get_git_root()?;
-    let hook_file = git_root.join(".git").join("hooks").join("pre-commit");
-    
-    if !hook_file.exists() {
-        return Ok(false);
-    }
-    
-    let content = fs::read_to_string(&hook_file)
-        .context("Failed to read pre-commit hook")?;
-    
-    Ok(content.contains("=== WS BLOCK START ==="))
-}
-
-fn remove_st8_block(content: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result = Vec::new();
-    let mut in_st8_block = false;
-    let ends_with_newline = content.ends_with('\n');
-    
-    for line in lines {
-        if line.contains("=== WS BLOCK START ===") {
-            in_st8_block = true;
-            continue;
-        }
-        
-        if line.contains("=== WS BLOCK END ===") {
-            in_st8_block = false;
-            continue;
-        }
-        
-        if !in_st8_block {
-            result.push(line);
-        }
-    }
-    
-    let mut output = result.join("\n");
-    if ends_with_newline && !output.is_empty() {
-        output.push('\n');
-    }
-    
-    output
+    wsb::st8::hooks::is_installed(&get_git_root()?, HookKind::PreCommit)
 }
 
 fn is_git_repository() -> bool {
@@ -2310,7 +4410,7 @@ fn show_completion_hint_if_needed(shell: Shell) {
     if !project_root.join(".wsb").join("state.json").exists() {
         return;
     }
-    let mut state = match WorkspaceState::load(&project_root) {
+    let mut state = match WorkspaceState::peek(&project_root) {
         Ok(s) => s,
         Err(_) => return,
     };
@@ -2443,7 +4543,7 @@ fn print_activation_hint(shell: Shell, file_path: &std::path::Path) {
     }
 }
 
-fn run_mcp_server(_port: u16, _debug: bool, migrate: bool) -> Result<()> {
+fn run_mcp_server(mut options: wsb::mcp_server::ServeOptions, self_signed: bool, migrate: bool) -> Result<()> {
     tokio::runtime::Runtime::new()?.block_on(async {
         if migrate {
             // Migrate features from features.md to database
@@ -2473,12 +4573,77 @@ fn run_mcp_server(_port: u16, _debug: bool, migrate: bool) -> Result<()> {
             }
         }
         
-        // TODO: Implement MCP server when needed
-        println!("MCP server functionality not implemented in new schema");
-        Ok(())
+        let root = get_project_root()?;
+        std::fs::create_dir_all(root.join(".wsb"))?;
+        if self_signed {
+            options.tls = Some(self_signed_certificate(&root.join(".wsb/tls"), options.bind)?);
+        }
+        options.pidfile = Some(root.join(".wsb").join(wsb::mcp_server::daemon::PIDFILE));
+        wsb::mcp_server::serve(&root.join(".wsb/project.db"), options).await
     })
 }
 
+/// Start this same `wsb mcp-server` command again as a background process
+fn start_mcp_daemon() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).filter(|arg| arg != "--daemon").collect();
+    spawn_mcp_daemon(&args)
+}
+
+fn spawn_mcp_daemon(args: &[String]) -> Result<()> {
+    use wsb::mcp_server::daemon;
+
+    let wsb_dir = get_project_root()?.join(".wsb");
+    std::fs::create_dir_all(&wsb_dir)?;
+    let log = wsb_dir.join(daemon::LOGFILE);
+    let record = daemon::spawn(&wsb_dir.join(daemon::PIDFILE), &log, args)?;
+    println!("{} MCP server running in the background on {} (pid {})", "✅".green(), record.url.bold(), record.pid);
+    println!("   Log: {}", log.display());
+    println!("   Stop it with `wsb mcp-server stop`");
+    Ok(())
+}
+
+fn run_mcp_server_action(action: McpServerAction) -> Result<()> {
+    use wsb::mcp_server::daemon;
+
+    let pidfile = get_project_root()?.join(".wsb").join(daemon::PIDFILE);
+    match action {
+        McpServerAction::Status => match daemon::running(&pidfile)? {
+            Some(record) => {
+                println!("{} MCP server running on {} (pid {})", "●".green(), record.url.bold(), record.pid);
+                println!("   Started: {}", record.started_at.format("%Y-%m-%d %H:%M:%S UTC"));
+                println!("   Command: wsb {}", record.args.join(" "));
+            }
+            None => println!("{} MCP server is not running", "○".dimmed()),
+        },
+        McpServerAction::Stop => match daemon::stop(&pidfile, daemon::STOP_TIMEOUT)? {
+            Some(record) => println!("{} Stopped MCP server (pid {})", "✅".green(), record.pid),
+            None => println!("{} MCP server is not running", "○".dimmed()),
+        },
+        McpServerAction::Restart => {
+            let record = daemon::running(&pidfile)?
+                .ok_or_else(|| anyhow::anyhow!("MCP server is not running; start it with `wsb mcp-server --daemon`"))?;
+            daemon::stop(&pidfile, daemon::STOP_TIMEOUT)?;
+            println!("{} Stopped MCP server (pid {})", "✅".green(), record.pid);
+            spawn_mcp_daemon(&record.args)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reuse the development certificate in `dir`, generating one the first time
+fn self_signed_certificate(dir: &std::path::Path, bind: std::net::IpAddr) -> Result<wsb::mcp_server::TlsFiles> {
+    let existing = wsb::mcp_server::TlsFiles { cert: dir.join("cert.pem"), key: dir.join("key.pem") };
+    if existing.cert.exists() && existing.key.exists() {
+        eprintln!("Using self-signed certificate {}", existing.cert.display());
+        return Ok(existing);
+    }
+    // A wildcard bind has no single address to name in the certificate
+    let extra_hosts = if bind.is_unspecified() { Vec::new() } else { vec![bind.to_string()] };
+    let files = wsb::mcp_server::generate_self_signed(dir, &extra_hosts)?;
+    eprintln!("Generated self-signed certificate {} (for development only)", files.cert.display());
+    Ok(files)
+}
+
 fn run_sample_command(project: bool, data: bool, force: bool, output: String) -> Result<()> {
     println!("{}", "=== Sample Project & Data Creation ===".bold().blue());
     
@@ -3075,10 +5240,19 @@ fn initialize_session(
         println!();
         println!("{}", "### Features.md Analysis".bold());
         println!("{}: {} ({}% implemented)", "Total Features".bold(), total_features, implementation_rate);
-        
-        if let Some(next_priority) = extract_next_priority(&context.features_content) {
-            println!("{}: {}", "Next Priority".bold(), next_priority);
+    }
+
+    let brief = match load_action_brief(context) {
+        Ok(brief) => brief,
+        Err(e) => {
+            if debug_mode {
+                println!("Action brief unavailable: {}", e);
+            }
+            None
         }
+    };
+    if let Some(brief) = brief.as_ref().filter(|b| !b.is_empty()) {
+        print_action_brief(brief);
     }
     
     // Critical rules
@@ -3098,8 +5272,10 @@ fn initialize_session(
         println!("{}: {}", "Starting Task".bold(), task);
     } else if let Some(continue_task) = continue_from {
         println!("{}: Continue from {}", "Resuming".bold(), continue_task);
-    } else if let Some(next_priority) = extract_next_priority(&context.features_content) {
-        println!("{}: {}", "Next Priority".bold(), next_priority);
+    } else if let Some(task) = brief.as_ref().and_then(|b| b.ready_tasks.first()) {
+        println!("{}: {} - {}", "Next Priority".bold(), task.id, task.title);
+    } else if let Some(feature) = brief.as_ref().and_then(|b| b.near_complete_features.first()) {
+        println!("{}: {} - {} ({})", "Next Priority".bold(), feature.id, feature.name, feature.next_step);
     } else {
         println!("{}: Ready for feature development", "Status".bold());
     }
@@ -3107,36 +5283,75 @@ fn initialize_session(
     Ok(())
 }
 
-fn setup_git_exclusions() -> Result<()> {
-    let git_dir = std::path::Path::new(".git");
-    if git_dir.exists() {
-        let exclude_file = git_dir.join("info").join("exclude");
-        std::fs::create_dir_all(exclude_file.parent().unwrap())?;
-        
-        let mut exclude_content = String::new();
-        if exclude_file.exists() {
-            exclude_content = std::fs::read_to_string(&exclude_file)?;
+/// Next-actions brief from the project database; `None` when there is no database or project
+fn load_action_brief(context: &ProjectContext) -> Result<Option<wsb::entities::brief::ActionBrief>> {
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(None);
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => project,
+            None => return Ok(None),
+        };
+        let brief = wsb::entities::brief::load(&pool, &project.id, chrono::Utc::now()).await?;
+        Ok(Some(brief))
+    })
+}
+
+fn print_action_brief(brief: &wsb::entities::brief::ActionBrief) {
+    println!();
+    println!("{}", "### Next Actions".bold());
+
+    if !brief.ready_tasks.is_empty() {
+        println!("{}", "Ready tasks:".bold());
+        for task in &brief.ready_tasks {
+            let unblocks = if task.unblocks > 0 { format!(", unblocks {}", task.unblocks) } else { String::new() };
+            println!("  {} {} [{}, {}{}]", task.id.cyan(), task.title, task.priority, task.status, unblocks);
         }
-        
-        let entries_to_add = vec!["CLAUDE.md", "internal/", ".claude/"];
-        let mut modified = false;
-        
-        for entry in entries_to_add {
-            if !exclude_content.contains(entry) {
-                if !exclude_content.ends_with('\n') && !exclude_content.is_empty() {
-                    exclude_content.push('\n');
-                }
-                exclude_content.push_str(entry);
-                exclude_content.push('\n');
-                modified = true;
+    }
+    if !brief.near_complete_features.is_empty() {
+        println!("{}", "One step from done:".bold());
+        for feature in &brief.near_complete_features {
+            println!("  {} {} - {}", feature.id.cyan(), feature.name, feature.next_step);
+        }
+    }
+    if !brief.reminders.is_empty() {
+        println!("{}", "Reminders:".bold());
+        for reminder in &brief.reminders {
+            match &reminder.due {
+                Some(due) => println!("  {} (due {})", reminder.text.yellow(), due),
+                None => println!("  {}", reminder.text.yellow()),
             }
         }
-        
-        if modified {
-            std::fs::write(&exclude_file, exclude_content)?;
-            println!("Updated Git exclusions for project files");
+    }
+    if !brief.blockers.is_empty() {
+        println!("{}", "Blocked:".bold());
+        for blocker in &brief.blockers {
+            if blocker.waiting_on.is_empty() {
+                println!("  {} {}", blocker.id.red(), blocker.title);
+            } else {
+                println!("  {} {} (waiting on {})", blocker.id.red(), blocker.title, blocker.waiting_on.join(", "));
+            }
+        }
+    }
+    if !brief.unreviewed_notes.is_empty() {
+        println!("{}", "Notes since last session:".bold());
+        for note in &brief.unreviewed_notes {
+            let about = note.entity_id.as_deref().map(|id| format!(" on {}", id)).unwrap_or_default();
+            println!("  {} {} [{}{}]", note.id.cyan(), note.title, note.note_type, about);
         }
     }
+}
+
+fn setup_git_exclusions() -> Result<()> {
+    let added = wsb::entities::bootstrap::exclude_from_git(std::path::Path::new("."))?;
+    if added.map_or(false, |entries| !entries.is_empty()) {
+        println!("Updated Git exclusions for project files");
+    }
     
     Ok(())
 }
@@ -3159,9 +5374,7 @@ fn validate_git_exclusions(debug_mode: bool) -> Result<()> {
     }
     
     let exclude_content = std::fs::read_to_string(&exclude_file)?;
-    let required_entries = vec!["CLAUDE.md", "internal/", ".claude/"];
-    
-    for entry in required_entries {
+    for entry in wsb::entities::bootstrap::GIT_EXCLUSIONS {
         if !exclude_content.contains(entry) {
             println!("{}: {} not in Git exclusions", "Warning".yellow(), entry);
         } else if debug_mode {
@@ -3225,6 +5438,7 @@ fn run_end_command(
     if debug_mode {
         println!("{}", "=== End Command Debug Mode ===".bold().blue());
     }
+    let _lock = wsb::locks::acquire(&get_project_root()?, wsb::locks::DOCUMENTS, "wsb end")?;
 
     // Phase 1: Load current project context
     let project_context = load_project_context(debug_mode)?;
@@ -3534,6 +5748,7 @@ fn run_consolidate_command(
     if debug_mode {
         println!("{}", "=== Consolidate Command Debug Mode ===".bold().blue());
     }
+    let _lock = wsb::locks::acquire(&get_project_root()?, wsb::locks::DOCUMENTS, "wsb consolidate")?;
 
     // Phase 1: Load current project context
     let project_context = load_project_context(debug_mode)?;
@@ -3889,46 +6104,29 @@ fn generate_feature_dependency_diagram(context: &ProjectContext, diagrams_dir: &
         println!("  Generating feature dependency diagram...");
     }
     
-    let dot_content = format!(r#"
-digraph feature_dependencies {{
-    rankdir=TB;
-    node [shape=box, style=filled];
-    
-    // Parse features from features.md and generate DOT content
-    // This is a simplified example
+    // The project's relationship graph; an empty one when there is no database yet
+    let db_path = context.project_root.join(".wsb/project.db");
+    let graph = if db_path.exists() {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(async {
+            let pool = wsb::entities::database::initialize_database(&db_path).await?;
+            match wsb::entities::workspace::current_project(&pool).await? {
+                Some(project) => wsb::entities::graph::load(&pool, &project.id).await,
+                None => Ok(wsb::entities::graph::EntityGraph::default()),
+            }
+        })?
+    } else {
+        wsb::entities::graph::EntityGraph::default()
+    };
+    let dot_content = format!(
+        "// Project: {}\n// Generated: {}\n{}",
+        context.workspace_state.project_name.as_deref().unwrap_or("Unknown"),
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        graph.to_dot("feature_dependencies")
+    );
     
-    subgraph cluster_core {{
-        label="Core Tool Features";
-        color=blue;
-        F0001 [label="Unified CLI", fillcolor=lightgreen];
-        F0002 [label="Shell Completion", fillcolor=lightgreen];
-    }}
-    
-    subgraph cluster_commands {{
-        label="Command System";
-        color=red;
-        F0100 [label="Start Command", fillcolor=lightgreen];
-        F0101 [label="End Command", fillcolor=lightgreen];
-        F0102 [label="Consolidate Command", fillcolor=yellow];
-    }}
-    
-    // Dependencies
-    F0100 -> F0001;
-    F0101 -> F0001;
-    F0102 -> F0001;
-    F0102 -> F0100;
-    F0102 -> F0101;
-    
-    // Project: {}
-    // Generated: {}
-}}
-"#, 
-        context.workspace_state.project_name.as_deref().unwrap_or("Unknown"),
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-    );
-    
-    let diagram_path = diagrams_dir.join("feature_dependencies.dot");
-    std::fs::write(&diagram_path, dot_content)?;
+    let diagram_path = diagrams_dir.join("feature_dependencies.dot");
+    std::fs::write(&diagram_path, dot_content)?;
     
     if debug_mode {
         println!("    ✅ Created {}", diagram_path.display());
@@ -4066,6 +6264,9 @@ fn run_status_command(
     debug_mode: bool,
     include_features: bool,
     include_metrics: bool,
+    critical_path: Option<Option<String>>,
+    risks: bool,
+    coverage: Option<PathBuf>,
     format: String,
 ) -> Result<()> {
     if debug_mode {
@@ -4076,18 +6277,170 @@ fn run_status_command(
     let project_context = load_project_context(debug_mode)?;
     
     // Phase 2: Calculate project metrics
-    let project_metrics = calculate_project_metrics(&project_context, debug_mode)?;
+    let mut project_metrics = calculate_project_metrics(&project_context, debug_mode)?;
+    project_metrics.wip = load_wip_utilization(&project_context)?;
+    project_metrics.doc_coverage = load_doc_coverage(&project_context)?;
+    project_metrics.code_coverage = load_code_coverage(&project_context, coverage)?;
+    project_metrics.milestones = load_milestones(&project_context)?;
+    if include_metrics {
+        project_metrics.time_by_feature = load_time_by_feature(&project_context)?;
+    }
+    if let Some(milestone) = critical_path {
+        project_metrics.critical_path = Some(load_critical_path(&project_context, milestone.as_deref())?);
+    }
     
     // Phase 3: Generate status report
     match format.as_str() {
-        "json" => generate_json_status(&project_context, &project_metrics, include_features, include_metrics)?,
+        "json" => generate_json_status(&project_context, &project_metrics, include_features, include_metrics, risks)?,
         "summary" => generate_summary_status(&project_context, &project_metrics)?,
-        "human" | _ => generate_human_status(&project_context, &project_metrics, include_features, include_metrics, debug_mode)?,
+        "human" | _ => generate_human_status(&project_context, &project_metrics, include_features, include_metrics, risks, debug_mode)?,
     }
     
     Ok(())
 }
 
+/// WIP policy from `tools.policy` in the workspace state
+fn load_wip_policy(project_root: &Path) -> wsb::entities::policy::WipPolicy {
+    WorkspaceState::load(project_root)
+        .ok()
+        .and_then(|state| state.get_tool_config("policy"))
+        .unwrap_or_default()
+}
+
+/// Warn about a WIP limit violation, or refuse the change when the policy is strict
+fn apply_wip_policy(policy: &wsb::entities::policy::WipPolicy, violation: Option<String>) -> Result<()> {
+    if let Some(message) = violation {
+        if policy.strict {
+            anyhow::bail!("{} (strict policy; raise the limit in tools.policy or finish in-progress work first)", message);
+        }
+        println!("{} {}", "⚠️".yellow(), message.yellow());
+    }
+    Ok(())
+}
+
+/// Documentation coverage of the project's features; `None` without a project database
+fn load_doc_coverage(context: &ProjectContext) -> Result<Option<wsb::entities::doc_coverage::DocCoverageReport>> {
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let config = context.workspace_state
+        .get_tool_config::<wsb::entities::doc_coverage::DocCoverageConfig>("doc_coverage")
+        .unwrap_or_default();
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => project,
+            None => return Ok(None),
+        };
+        let report = wsb::entities::doc_coverage::analyze(&pool, &project.id, &context.project_root, &config).await?;
+        Ok(Some(report))
+    })
+}
+
+/// Per-feature code coverage from `report`, or the report under `tools.coverage`;
+/// `None` when neither is given or there is no project database
+fn load_code_coverage(context: &ProjectContext, report: Option<PathBuf>) -> Result<Option<wsb::entities::code_coverage::CodeCoverageReport>> {
+    use wsb::entities::code_coverage::CodeCoverageConfig;
+
+    let config = context.workspace_state.get_tool_config::<CodeCoverageConfig>("coverage").unwrap_or_default();
+    let report = match report.or_else(|| config.report.as_ref().map(|r| context.project_root.join(r))) {
+        Some(report) => report,
+        None => return Ok(None),
+    };
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let mapping = context.workspace_state.get_tool_config::<wsb::open::OpenConfig>("open").unwrap_or_default();
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => project,
+            None => return Ok(None),
+        };
+        let coverage = wsb::entities::code_coverage::analyze(
+            &pool, &project.id, &context.project_root, &report, &mapping, config.low_threshold,
+        ).await?;
+        Ok(Some(coverage))
+    })
+}
+
+/// The project's milestones with their completion; empty without a project database
+fn load_milestones(context: &ProjectContext) -> Result<Vec<wsb::entities::schema_models::Milestone>> {
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => wsb::entities::crud::milestones::list_by_project(&pool, &project.id).await,
+            None => Ok(Vec::new()),
+        }
+    })
+}
+
+/// Time tracked per feature, from the task timers
+fn load_time_by_feature(context: &ProjectContext) -> Result<Vec<wsb::entities::time_tracking::FeatureTime>> {
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => wsb::entities::time_tracking::by_feature(&pool, &project.id).await,
+            None => Ok(Vec::new()),
+        }
+    })
+}
+
+/// Critical path over the project's open tasks, optionally up to a milestone
+fn load_critical_path(context: &ProjectContext, milestone: Option<&str>) -> Result<wsb::entities::schedule::CriticalPath> {
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        anyhow::bail!("No project database found; --critical-path needs tasks in .wsb/project.db");
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => project,
+            None => anyhow::bail!("No active project found"),
+        };
+        wsb::entities::schedule::critical_path(&pool, &project.id, milestone).await
+    })
+}
+
+fn load_wip_utilization(context: &ProjectContext) -> Result<Option<wsb::entities::policy::WipUtilization>> {
+    let policy = context.workspace_state.get_tool_config::<wsb::entities::policy::WipPolicy>("policy").unwrap_or_default();
+    let db_path = context.project_root.join(".wsb/project.db");
+    if !policy.has_limits() || !db_path.exists() {
+        return Ok(None);
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = match wsb::entities::workspace::current_project(&pool).await? {
+            Some(project) => project,
+            None => return Ok(None),
+        };
+        let usage = wsb::entities::policy::load_utilization(&pool, &policy, &project.id).await?;
+        Ok(Some(usage))
+    })
+}
+
 #[derive(Debug)]
 struct ProjectMetrics {
     total_features: usize,
@@ -4098,6 +6451,12 @@ struct ProjectMetrics {
     features_by_state: std::collections::HashMap<String, usize>,
     recent_activity: RecentActivity,
     project_health: ProjectHealth,
+    wip: Option<wsb::entities::policy::WipUtilization>,
+    critical_path: Option<wsb::entities::schedule::CriticalPath>,
+    doc_coverage: Option<wsb::entities::doc_coverage::DocCoverageReport>,
+    code_coverage: Option<wsb::entities::code_coverage::CodeCoverageReport>,
+    milestones: Vec<wsb::entities::schema_models::Milestone>,
+    time_by_feature: Vec<wsb::entities::time_tracking::FeatureTime>,
 }
 
 #[derive(Debug)]
@@ -4179,6 +6538,12 @@ fn calculate_project_metrics(context: &ProjectContext, debug_mode: bool) -> Resu
         features_by_state,
         recent_activity,
         project_health,
+        wip: None,
+        critical_path: None,
+        doc_coverage: None,
+        code_coverage: None,
+        milestones: Vec::new(),
+        time_by_feature: Vec::new(),
     })
 }
 
@@ -4376,75 +6741,182 @@ fn generate_human_status(
     metrics: &ProjectMetrics,
     include_features: bool,
     include_metrics: bool,
+    include_risks: bool,
     debug_mode: bool,
 ) -> Result<()> {
     if debug_mode {
         println!("Generating human-readable status report...");
     }
     
-    println!("{}", "Project Status Report".bold().underline());
+    println!("{}", t!("status.title").bold().underline());
     println!();
     
     // Project overview
     let project_name = context.workspace_state.project_name
-        .as_deref()
-        .unwrap_or("Unknown Project");
-    println!("{}: {}", "Project".bold(), project_name);
+        .clone()
+        .unwrap_or_else(|| t!("status.unknown_project"));
+    println!("{}: {}", t!("status.project").bold(), project_name);
     
     if let Some(ref last_session) = metrics.recent_activity.last_session_date {
-        println!("{}: {}", "Last Session".bold(), last_session);
+        println!("{}: {}", t!("status.last_session").bold(), last_session);
     }
     
     // Feature summary
     println!();
-    println!("{}", "### Feature Progress".bold());
-    println!("{}: {} features total", "Total".bold(), metrics.total_features);
-    println!("{}: {} ({:.1}%)", "Implemented".bold(), metrics.implemented_features, metrics.implementation_rate);
-    println!("{}: {} ({:.1}%)", "Tested".bold(), metrics.tested_features, metrics.test_coverage_rate);
+    println!("{}", t!("status.feature_progress").bold());
+    println!("{}: {}", t!("status.total").bold(), t!("status.features_total", count = metrics.total_features));
+    println!("{}: {} ({:.1}%)", t!("status.implemented").bold(), metrics.implemented_features, metrics.implementation_rate);
+    println!("{}: {} ({:.1}%)", t!("status.tested").bold(), metrics.tested_features, metrics.test_coverage_rate);
+    if let Some(ref coverage) = metrics.doc_coverage {
+        println!("{}: {}/{} ({:.1}%)", t!("status.documented").bold(), coverage.documented, coverage.total, coverage.documentation_rate);
+    }
     
     // Feature breakdown by state
     if include_features && !metrics.features_by_state.is_empty() {
         println!();
-        println!("{}", "### Feature Breakdown".bold());
+        println!("{}", t!("status.feature_breakdown").bold());
         for (state, count) in &metrics.features_by_state {
             println!("{}: {}", state.bold(), count);
         }
     }
     
+    // WIP limits
+    if let Some(ref wip) = metrics.wip {
+        println!();
+        println!("{}", t!("status.wip_limits").bold());
+        let usages = wip.features.iter().chain(wip.tasks.iter());
+        for usage in usages {
+            let label = if usage.scope == "features" {
+                t!("status.features_in_progress")
+            } else {
+                t!("status.tasks_in_progress", scope = usage.scope)
+            };
+            let value = format!("{}/{}", usage.current, usage.limit);
+            let value = if usage.is_over() {
+                value.red().to_string()
+            } else if usage.current == usage.limit {
+                value.yellow().to_string()
+            } else {
+                value.green().to_string()
+            };
+            println!("{}: {}", label.bold(), value);
+        }
+    }
+
+    if !metrics.milestones.is_empty() {
+        println!();
+        println!("{}", t!("status.milestones").bold());
+        for milestone in &metrics.milestones {
+            let target = milestone.target_date.as_deref()
+                .map(|date| t!("status.milestone_due", date = date))
+                .unwrap_or_default();
+            println!("  {} {:<32} {:<12} {:>5.1}% {}",
+                milestone.id.cyan(), milestone.title, milestone.status, milestone.completion_percentage, target.dimmed());
+        }
+    }
+
+    if let Some(ref critical_path) = metrics.critical_path {
+        print_critical_path(critical_path);
+    }
+
+    if let Some(ref coverage) = metrics.code_coverage {
+        println!();
+        println!("{}", t!("status.code_coverage").bold());
+        if let Some(overall) = coverage.overall {
+            println!("{}: {:.1}%", t!("status.coverage_overall", format = coverage.format).bold(), overall);
+        }
+        for feature in &coverage.features {
+            let value = match feature.percent {
+                Some(percent) if percent < coverage.threshold => format!("{:.1}%", percent).red().to_string(),
+                Some(percent) => format!("{:.1}%", percent).green().to_string(),
+                None => t!("status.coverage_no_files").dimmed().to_string(),
+            };
+            let flag = if coverage.low_coverage_complete().iter().any(|f| f.id == feature.id) { " ⚠️" } else { "" };
+            println!("  {} {:<32} {:<28} {}{}", feature.id.cyan(), feature.name, feature.state.dimmed(), value, flag);
+        }
+    }
+
+    if include_risks {
+        println!();
+        println!("{}", t!("status.risks").bold());
+        let undocumented = metrics.doc_coverage.as_ref().map(|c| c.undocumented_complete()).unwrap_or_default();
+        let low_coverage = metrics.code_coverage.as_ref().map(|c| c.low_coverage_complete()).unwrap_or_default();
+        if undocumented.is_empty() && low_coverage.is_empty() {
+            println!("{}", t!("status.no_risks").green());
+        }
+        if !undocumented.is_empty() {
+            println!("{}", t!("status.undocumented_complete", count = undocumented.len()).yellow());
+            for feature in undocumented {
+                println!("  {} {}", feature.id.cyan(), feature.name);
+            }
+        }
+        if let Some(ref coverage) = metrics.code_coverage {
+            if !low_coverage.is_empty() {
+                println!("{}", t!("status.low_coverage_complete", count = low_coverage.len(), threshold = coverage.threshold).yellow());
+                for feature in low_coverage {
+                    println!("  {} {} ({:.1}%)", feature.id.cyan(), feature.name, feature.percent.unwrap_or_default());
+                }
+            }
+        }
+    }
+
     // Project health
     println!();
-    println!("{}", "### Project Health".bold());
+    println!("{}", t!("status.project_health").bold());
     match &metrics.project_health.compilation_status {
-        CompilationStatus::Passing => println!("{}: {}", "Compilation".bold(), "✅ Passing".green()),
+        CompilationStatus::Passing => println!("{}: {}", t!("status.compilation").bold(), t!("status.passing").green()),
         CompilationStatus::Failing(error) => {
             log::error!("Compilation failing: {}", error.lines().next().unwrap_or("Unknown error"));
-            println!("{}: {}", "Compilation".bold(), "❌ Failing".red());
+            println!("{}: {}", t!("status.compilation").bold(), t!("status.failing").red());
             if include_metrics {
-                println!("  Error: {}", error.lines().next().unwrap_or("Unknown error"));
+                println!("  {}: {}", t!("common.error"), error.lines().next().unwrap_or("Unknown error"));
             }
         }
-        CompilationStatus::Unknown => println!("{}: {}", "Compilation".bold(), "❓ Unknown".yellow()),
+        CompilationStatus::Unknown => println!("{}: {}", t!("status.compilation").bold(), t!("status.status_unknown").yellow()),
     }
     
     match &metrics.project_health.test_status {
-        TestStatus::AllPassing(count) => println!("{}: {} ({} tests)", "Tests".bold(), "✅ All Passing".green(), count),
+        TestStatus::AllPassing(count) => println!(
+            "{}: {} ({})",
+            t!("status.tests").bold(),
+            t!("status.all_passing").green(),
+            t!("status.tests_count", count = count)
+        ),
         TestStatus::SomeFailures(total, failed) => {
             log::warn!("Test failures: {}/{} tests failed", failed, total);
-            println!("{}: {} ({}/{} failed)", "Tests".bold(), "❌ Some Failures".red(), failed, total);
+            println!(
+                "{}: {} ({})",
+                t!("status.tests").bold(),
+                t!("status.some_failures").red(),
+                t!("status.tests_failed", failed = failed, total = total)
+            );
         },
-        TestStatus::Unknown => println!("{}: {}", "Tests".bold(), "❓ Unknown".yellow()),
+        TestStatus::Unknown => println!("{}: {}", t!("status.tests").bold(), t!("status.status_unknown").yellow()),
     }
     
-    println!("{}: {:.1}/100", "Code Quality Score".bold(), metrics.project_health.code_quality_score);
+    println!("{}: {:.1}/100", t!("status.code_quality").bold(), metrics.project_health.code_quality_score);
     
     // Recent activity
     if include_metrics {
         println!();
-        println!("{}", "### Recent Activity".bold());
-        println!("{}: {}", "Sessions This Week".bold(), metrics.recent_activity.sessions_this_week);
-        println!("{}: {}", "Features Completed".bold(), metrics.recent_activity.features_completed_recently);
+        println!("{}", t!("status.recent_activity").bold());
+        println!("{}: {}", t!("status.sessions_this_week").bold(), metrics.recent_activity.sessions_this_week);
+        println!("{}: {}", t!("status.features_completed").bold(), metrics.recent_activity.features_completed_recently);
         if metrics.recent_activity.git_commits_today > 0 {
-            println!("{}: {}", "Git Commits Today".bold(), metrics.recent_activity.git_commits_today);
+            println!("{}: {}", t!("status.git_commits_today").bold(), metrics.recent_activity.git_commits_today);
+        }
+    }
+
+    if include_metrics && !metrics.time_by_feature.is_empty() {
+        use wsb::entities::time_tracking::format_duration;
+        println!();
+        println!("{}", t!("status.time_per_feature").bold());
+        for feature in &metrics.time_by_feature {
+            println!("  {} {:<32} {:>8} {}",
+                feature.feature_id.cyan(),
+                feature.feature_name.as_deref().unwrap_or("-"),
+                format_duration(feature.total_seconds),
+                t!("status.time_tasks", count = feature.tasks).dimmed());
         }
     }
     
@@ -4464,11 +6936,74 @@ fn generate_human_status(
     Ok(())
 }
 
+fn print_critical_path(critical_path: &wsb::entities::schedule::CriticalPath) {
+    println!();
+    match critical_path.milestone {
+        Some(ref milestone) => println!("{} {}", "### Critical Path to".bold(), milestone.bold()),
+        None => println!("{}", "### Critical Path".bold()),
+    }
+
+    if critical_path.path.is_empty() {
+        println!("No open tasks in scope");
+        return;
+    }
+
+    println!("{}: {} (effort units)", "Remaining".bold(), critical_path.total_effort);
+    for task in &critical_path.path {
+        let marker = if critical_path.gating.as_deref() == Some(task.id.as_str()) { "⛔" } else { "•" };
+        let estimate = if task.estimated { task.effort.to_string() } else { format!("{}?", task.effort) };
+        println!(
+            "  {} {} {} [{}] {}–{} (effort {})",
+            marker,
+            task.id.bold(),
+            task.title,
+            task.status,
+            task.earliest_start,
+            task.earliest_finish,
+            estimate
+        );
+    }
+
+    let with_slack: Vec<_> = critical_path.tasks.iter().filter(|t| t.slack > 0).collect();
+    if !with_slack.is_empty() {
+        println!("{}", "Slack:".bold());
+        for task in with_slack {
+            println!("  {} {} [{}] slack {}", task.id, task.title, task.status, task.slack);
+        }
+    }
+
+    match critical_path.gating {
+        Some(ref gating) => println!(
+            "{} {} is blocked on the critical path and is gating the end date",
+            "⛔".red(),
+            gating.red().bold()
+        ),
+        None => println!("{}", "No blocked task is gating the end date".green()),
+    }
+    if !critical_path.blocked_off_path.is_empty() {
+        println!(
+            "{}: {} (blocked, but with slack)",
+            "Not gating".bold(),
+            critical_path.blocked_off_path.join(", ")
+        );
+    }
+    if !critical_path.unestimated.is_empty() {
+        println!(
+            "{} {} task(s) without an estimate counted as {}: {}",
+            "⚠️".yellow(),
+            critical_path.unestimated.len(),
+            wsb::entities::schedule::DEFAULT_EFFORT,
+            critical_path.unestimated.join(", ")
+        );
+    }
+}
+
 fn generate_json_status(
     _context: &ProjectContext,
     metrics: &ProjectMetrics,
     include_features: bool,
     include_metrics: bool,
+    include_risks: bool,
 ) -> Result<()> {
     use serde_json::json;
     
@@ -4484,6 +7019,44 @@ fn generate_json_status(
     if include_features {
         status["features_by_state"] = serde_json::to_value(&metrics.features_by_state)?;
     }
+
+    if let Some(ref wip) = metrics.wip {
+        status["wip"] = serde_json::to_value(wip)?;
+    }
+
+    if !metrics.milestones.is_empty() {
+        status["milestones"] = serde_json::to_value(&metrics.milestones)?;
+    }
+
+    if let Some(ref critical_path) = metrics.critical_path {
+        status["critical_path"] = serde_json::to_value(critical_path)?;
+    }
+
+    if let Some(ref coverage) = metrics.doc_coverage {
+        status["documentation_rate"] = json!(coverage.documentation_rate);
+        if include_features {
+            status["feature_coverage"] = serde_json::to_value(&coverage.features)?;
+        }
+    }
+
+    if let Some(ref coverage) = metrics.code_coverage {
+        status["code_coverage"] = serde_json::to_value(coverage)?;
+    }
+
+    if include_risks {
+        let undocumented: Vec<&str> = metrics.doc_coverage
+            .as_ref()
+            .map(|c| c.undocumented_complete().into_iter().map(|f| f.id.as_str()).collect())
+            .unwrap_or_default();
+        let low_coverage: Vec<&str> = metrics.code_coverage
+            .as_ref()
+            .map(|c| c.low_coverage_complete().into_iter().map(|f| f.id.as_str()).collect())
+            .unwrap_or_default();
+        status["risks"] = json!({
+            "undocumented_complete_features": undocumented,
+            "low_coverage_complete_features": low_coverage,
+        });
+    }
     
     if include_metrics {
         status["recent_activity"] = json!({
@@ -4492,6 +7065,7 @@ fn generate_json_status(
             "features_completed_recently": metrics.recent_activity.features_completed_recently,
             "git_commits_today": metrics.recent_activity.git_commits_today
         });
+        status["time_by_feature"] = serde_json::to_value(&metrics.time_by_feature)?;
         
         status["documentation_health"] = json!({
             "claude_md_size_kb": metrics.project_health.documentation_health.claude_md_size_kb,
@@ -4536,17 +7110,27 @@ fn generate_summary_status(
 
 fn run_task_command(action: TaskAction) -> Result<()> {
     match action {
-        TaskAction::Add { title, description, feature, priority, auto_feature } => {
-            add_task_to_database_with_detection(title, description, feature, priority, auto_feature)?;
+        TaskAction::Add { title, description, feature, priority, auto_feature, tags } => {
+            let task_id = add_task_to_database_with_detection(title, description, feature, priority, auto_feature)?;
+            tag_new_entity("task", &task_id, &tags)?;
         }
-        TaskAction::List { status, feature, priority, recent } => {
-            list_tasks(status, feature, priority, recent)?;
+        TaskAction::List { recurring: true, .. } => {
+            list_recurring_tasks()?;
+        }
+        TaskAction::List { blocked_by: Some(task_id), .. } => {
+            list_tasks_blocked_by(task_id)?;
+        }
+        TaskAction::List { status, feature, priority, recent, tags, view, blocked_by: None, recurring: false } => {
+            list_tasks(status, feature, priority, recent, tags, view)?;
         }
         TaskAction::Show { identifier } => {
             show_task(identifier)?;
         }
-        TaskAction::Update { task_id, status, priority, notes, feature } => {
-            update_task(task_id, status, priority, notes, feature)?;
+        TaskAction::Update { task_id, status, priority, notes, feature, body, body_file } => {
+            update_task(task_id.clone(), status, priority, notes, feature)?;
+            if let Some(body) = read_body_arg(body, body_file)? {
+                set_entity_body(&task_id, &body)?;
+            }
         }
         TaskAction::Complete { task_id, notes, advance_feature } => {
             complete_task(task_id, notes, advance_feature)?;
@@ -4554,6 +7138,27 @@ fn run_task_command(action: TaskAction) -> Result<()> {
         TaskAction::Block { task_id, reason, _dependencies } => {
             block_task(task_id, reason, _dependencies)?;
         }
+        TaskAction::Schedule { task_id, due, repeat, clear } => {
+            schedule_task(task_id, due, repeat, clear)?;
+        }
+        TaskAction::Delete { task_id, purge } => {
+            delete_task(task_id, purge)?;
+        }
+        TaskAction::Restore { task_id } => {
+            restore_task(task_id)?;
+        }
+        TaskAction::Depend { task_id, on } => {
+            add_task_dependencies(task_id, on)?;
+        }
+        TaskAction::Ready { format } => {
+            list_ready_tasks(&format)?;
+        }
+        TaskAction::Start { task_id } => {
+            start_task_timer(task_id)?;
+        }
+        TaskAction::Stop => {
+            stop_task_timer()?;
+        }
     }
     Ok(())
 }
@@ -4747,13 +7352,21 @@ fn create_initial_task_backlog() -> String {
     )
 }
 
-fn list_tasks(status: Option<String>, feature: Option<String>, priority: Option<String>, recent: Option<u32>) -> Result<()> {
-    println!("{}", "Task List".bold().blue());
-    
+fn list_tasks(status: Option<String>, feature: Option<String>, priority: Option<String>, recent: Option<u32>, tags: Vec<String>, view: Option<String>) -> Result<()> {
+    let view = view.map(|name| load_view(&name, "task")).transpose()?;
+    let status = status.or_else(|| view.as_ref().and_then(|view| view.status.clone()));
+    let priority = priority.or_else(|| view.as_ref().and_then(|view| view.priority.clone()));
+    let tags = if tags.is_empty() { view.as_ref().map(|view| view.tags.clone()).unwrap_or_default() } else { tags };
+    let tagged = if tags.is_empty() { None } else { Some(load_tagged("task", &tags)?) };
+    match &view {
+        Some(view) => println!("{} {}", "Task List".bold().blue(), format!("(view {}: {})", view.name, describe_view(view)).dimmed()),
+        None => println!("{}", "Task List".bold().blue()),
+    }
+
     let tasks = load_tasks_from_backlog()?;
     
     // Apply filters
-    let filtered_tasks: Vec<&Task> = tasks.iter()
+    let mut filtered_tasks: Vec<&Task> = tasks.iter()
         .filter(|task| {
             if let Some(ref filter_status) = status {
                 if task.status.to_string() != *filter_status {
@@ -4770,6 +7383,11 @@ fn list_tasks(status: Option<String>, feature: Option<String>, priority: Option<
                     return false;
                 }
             }
+            if let Some(ref tagged) = tagged {
+                if !tagged.contains(&task.id) {
+                    return false;
+                }
+            }
             if let Some(days) = recent {
                 let task_date = chrono::DateTime::parse_from_str(
                     &format!("{} +00:00", task.created_date),
@@ -4790,11 +7408,29 @@ fn list_tasks(status: Option<String>, feature: Option<String>, priority: Option<
         println!("No tasks found matching criteria.");
         return Ok(());
     }
+    if let Some((field, descending)) = view.as_ref().and_then(|view| view.sort_key()) {
+        filtered_tasks.sort_by(|a, b| {
+            let order = match field {
+                "title" => a.title.cmp(&b.title),
+                "status" => task_status_rank(&a.status).cmp(&task_status_rank(&b.status)),
+                "priority" => task_priority_rank(&a.priority).cmp(&task_priority_rank(&b.priority)),
+                "created" => a.created_date.cmp(&b.created_date),
+                _ => a.id.cmp(&b.id),
+            };
+            if descending { order.reverse() } else { order }
+        });
+    }
+    
+    let bodies = load_entity_bodies()?;
     
-    // Group by status
-    let mut by_status: std::collections::HashMap<String, Vec<&Task>> = std::collections::HashMap::new();
+    // Group by status, in the order the groups first appear
+    let mut by_status: Vec<(String, Vec<&Task>)> = Vec::new();
     for task in filtered_tasks {
-        by_status.entry(task.status.to_string()).or_insert_with(Vec::new).push(task);
+        let status = task.status.to_string();
+        match by_status.iter_mut().find(|(group, _)| *group == status) {
+            Some((_, tasks)) => tasks.push(task),
+            None => by_status.push((status, vec![task])),
+        }
     }
     
     for (status, tasks) in by_status {
@@ -4824,12 +7460,32 @@ fn list_tasks(status: Option<String>, feature: Option<String>, priority: Option<
                     String::new()
                 }
             );
+            if let Some(body) = bodies.get(task.id.as_str()) {
+                println!("      {}", wsb::entities::bodies::summary_line(body, body_preview_width(6)).dimmed());
+            }
         }
     }
     
     Ok(())
 }
 
+fn task_status_rank(status: &TaskStatus) -> u8 {
+    match status {
+        TaskStatus::InProgress => 0,
+        TaskStatus::Blocked => 1,
+        TaskStatus::Pending => 2,
+        TaskStatus::Completed => 3,
+    }
+}
+
+fn task_priority_rank(priority: &TaskPriority) -> u8 {
+    match priority {
+        TaskPriority::High => 0,
+        TaskPriority::Medium => 1,
+        TaskPriority::Low => 2,
+    }
+}
+
 fn load_tasks_from_backlog() -> Result<Vec<Task>> {
     let project_root = get_project_root()?;
     let backlog_path = project_root.join("internal").join("task_backlog.md");
@@ -4847,7 +7503,8 @@ fn load_tasks_from_backlog() -> Result<Vec<Task>> {
     
     while i < lines.len() {
         let line = lines[i];
-        if line.starts_with("### TASK-") {
+        // TASK-### entries from `task add`, T###### ones written by `wsb sync`
+        if line.starts_with("### T") && line.contains(" - ") {
             if let Some(task) = parse_task_from_lines(&lines, i)? {
                 tasks.push(task);
             }
@@ -4962,6 +7619,9 @@ fn show_task(identifier: String) -> Result<()> {
             }
             println!("\nDescription:");
             println!("{}", task.description);
+            if let Some(body) = load_entity_bodies()?.get(task.id.as_str()) {
+                print_body(body);
+            }
             
             if !task.notes.is_empty() {
                 println!("\nNotes:");
@@ -4969,9 +7629,12 @@ fn show_task(identifier: String) -> Result<()> {
                     println!("  • {}", note);
                 }
             }
+            print_task_time(&task.id)?;
         }
+        None if show_database_task(&identifier)? => {}
         None => {
             println!("{} Task not found: {}", "Error".red(), identifier);
+            print_id_suggestion(&identifier, tasks.iter().map(|t| t.id.as_str()));
         }
     }
     
@@ -4981,8 +7644,23 @@ fn show_task(identifier: String) -> Result<()> {
 fn update_task(task_id: String, status: Option<String>, priority: Option<String>, notes: Option<String>, feature: Option<String>) -> Result<()> {
     println!("{} Updating task: {}", "Info".blue(), task_id.bold());
     
-    // For now, just show what would be updated
     if let Some(status) = status {
+        use wsb::entities::schema_models::TaskStatus;
+        let new_status = TaskStatus::from_str(&status).map_err(|e| anyhow::anyhow!(e))?;
+
+        let project_root = get_project_root()?;
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(async {
+            let db_path = project_root.join(".wsb/project.db");
+            let pool = wsb::entities::database::initialize_database(&db_path).await?;
+            require_id(&pool, "Task", &task_id, LIVE_TASK_IDS).await?;
+
+            let policy = load_wip_policy(&project_root);
+            let violation = wsb::entities::policy::check_task_transition(&pool, &policy, &task_id, &new_status).await?;
+            apply_wip_policy(&policy, violation)?;
+
+            wsb::entities::crud::tasks::update_status(&pool, &task_id, new_status).await
+        })?;
         println!("  {} Status → {}", "→".green(), status);
     }
     if let Some(priority) = priority {
@@ -5032,60 +7710,325 @@ fn block_task(task_id: String, reason: String, dependencies: Vec<String>) -> Res
     Ok(())
 }
 
-fn run_directive_command(action: DirectiveAction) -> Result<()> {
-    match action {
-        DirectiveAction::Add { title, description, category, enforcement, priority } => {
-            add_directive(title, description, category, enforcement, priority)?;
-        }
-        DirectiveAction::List { category, enforcement, priority, recent } => {
-            list_directives(category, enforcement, priority, recent)?;
-        }
-        DirectiveAction::Show { identifier } => {
-            show_directive(identifier)?;
-        }
-        DirectiveAction::Update { directive_id, enforcement, priority, description, category } => {
-            update_directive(directive_id, enforcement, priority, description, category)?;
-        }
-        DirectiveAction::Remove { directive_id, force } => {
-            remove_directive(directive_id, force)?;
-        }
-        DirectiveAction::Validate { category, verbose, fail_fast } => {
-            validate_directives(category, verbose, fail_fast)?;
-        }
-        DirectiveAction::Check { paths, category, format } => {
-            check_paths_against_directives(paths, category, format)?;
-        }
+fn schedule_task(task_id: String, due: Option<String>, repeat: Option<String>, clear: bool) -> Result<()> {
+    use wsb::entities::calendar;
+
+    let due = due.map(|d| calendar::parse_date(&d)).transpose()?;
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        calendar::schedule_task(&pool, &task_id, due, repeat.as_deref(), clear).await
+    })?;
+
+    if clear {
+        println!("{} Schedule removed from {}", "✅".green(), task_id.bold());
+        return Ok(());
+    }
+    match (due, repeat) {
+        (Some(due), Some(repeat)) => println!("{} {} due {}, repeating {}", "📅".blue(), task_id.bold(), due, repeat),
+        (Some(due), None) => println!("{} {} due {}", "📅".blue(), task_id.bold(), due),
+        (None, Some(repeat)) => println!("{} {} now repeats {}", "📅".blue(), task_id.bold(), repeat),
+        (None, None) => {}
     }
     Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct Directive {
-    id: String,
-    title: String,
-    description: String,
-    category: DirectiveCategory,
-    enforcement: EnforcementLevel,
-    priority: DirectivePriority,
-    created_date: String,
-    _updated_date: String,
-    violation_count: u32,
-    last_validated: Option<String>,
-}
+fn delete_task(task_id: String, purge: bool) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        require_id(&pool, "Task", &task_id, if purge { "SELECT id FROM tasks" } else { LIVE_TASK_IDS }).await?;
+        let manager = EntityManager::new(pool);
+        if purge {
+            manager.purge_task(&task_id).await
+        } else {
+            manager.delete_task(&task_id).await
+        }
+    })?;
 
-#[derive(Debug, Clone)]
-enum DirectiveCategory {
-    Security,
-    Testing,
-    Coding,
-    Methodology,
-    Deployment,
+    if purge {
+        println!("{} Task {} permanently deleted", "🗑️".red(), task_id.bold());
+    } else {
+        println!("{} Task {} deleted (restore with 'wsb task restore {}')", "🗑️".yellow(), task_id.bold(), task_id);
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum EnforcementLevel {
-    Mandatory,
-    Recommended,
+fn restore_task(task_id: String) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).restore_task(&task_id).await
+    })?;
+
+    println!("{} Task {} restored", "✅".green(), task_id.bold());
+    Ok(())
+}
+
+fn add_task_dependencies(task_id: String, prerequisites: Vec<String>) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        // All or nothing, so a cycle in the last prerequisite leaves none behind
+        let mut tx = pool.begin().await?;
+        for prerequisite in &prerequisites {
+            wsb::entities::crud::tasks::add_dependency(&mut *tx, &task_id, prerequisite).await?;
+        }
+        tx.commit().await?;
+        Ok::<_, anyhow::Error>(())
+    })?;
+
+    println!("{} {} now depends on {}", "🔗".green(), task_id.bold(), prerequisites.join(", "));
+    Ok(())
+}
+
+fn list_ready_tasks(format: &str) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let tasks = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        let project = manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        manager.list_ready_tasks(&project.id).await
+    })?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&tasks)?),
+        "human" => {
+            if tasks.is_empty() {
+                println!("No tasks are ready to start");
+            }
+            for task in tasks {
+                println!("{} {} {}", task.id.cyan(), task.task, format!("[{}]", task.priority).dimmed());
+            }
+        }
+        other => anyhow::bail!("Unknown format '{}' (expected human or json)", other),
+    }
+    Ok(())
+}
+
+/// Show a task from the project database, for tasks not in the markdown backlog.
+/// Returns false when there is no such task.
+fn show_database_task(id: &str) -> Result<bool> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(false);
+    }
+    let rt = tokio::runtime::Runtime::new()?;
+    let task = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).get_task(id).await
+    })?;
+    let Some(task) = task else {
+        return Ok(false);
+    };
+
+    println!("{}", format!("Task: {}", task.task).bold().blue());
+    println!("ID: {}", task.id);
+    println!("Status: {}", task.status);
+    println!("Priority: {}", task.priority);
+    println!("Created: {}", task.created_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("Linked Feature: {}", task.feature_id.green());
+    print_task_time(&task.id)?;
+    Ok(true)
+}
+
+/// Cumulative time from the task's timers, if any were recorded
+fn print_task_time(task_id: &str) -> Result<()> {
+    use wsb::entities::time_tracking::format_duration;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let rt = tokio::runtime::Runtime::new()?;
+    let time = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).get_task_time(task_id).await
+    })?;
+    if time.entries == 0 {
+        return Ok(());
+    }
+
+    let mut line = format!("Time Tracked: {} across {} entries", format_duration(time.total_seconds), time.entries);
+    if let Some(since) = time.running_since {
+        line.push_str(&format!(" (running since {})", since));
+    }
+    println!("\n{}", line);
+    Ok(())
+}
+
+fn start_task_timer(task_id: String) -> Result<()> {
+    use wsb::entities::time_tracking::format_duration;
+
+    let project_root = get_project_root()?;
+    let rt = tokio::runtime::Runtime::new()?;
+    let (entry, stopped) = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&project_root.join(".wsb/project.db")).await?;
+
+        let policy = load_wip_policy(&project_root);
+        let violation = wsb::entities::policy::check_task_transition(&pool, &policy, &task_id, &wsb::entities::schema_models::TaskStatus::InProgress).await?;
+        apply_wip_policy(&policy, violation)?;
+
+        EntityManager::new(pool).start_task_timer(&task_id).await
+    })?;
+
+    if let Some(stopped) = stopped {
+        println!("{} Stopped {} after {}", "⏹".yellow(), stopped.task_id.bold(), format_duration(stopped.duration_seconds.unwrap_or(0)));
+    }
+    match entry.session_id {
+        Some(session) => println!("{} Timer started on {} (session {})", "⏱".green(), entry.task_id.bold(), session),
+        None => println!("{} Timer started on {}", "⏱".green(), entry.task_id.bold()),
+    }
+    Ok(())
+}
+
+fn stop_task_timer() -> Result<()> {
+    use wsb::entities::time_tracking::format_duration;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let entry = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).stop_task_timer().await
+    })?;
+    println!("{} Stopped {} after {}", "⏹".green(), entry.task_id.bold(), format_duration(entry.duration_seconds.unwrap_or(0)));
+    Ok(())
+}
+
+fn list_tasks_blocked_by(task_id: String) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let blocked = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).list_tasks_blocked_by(&task_id).await
+    })?;
+
+    if blocked.is_empty() {
+        println!("No open tasks are waiting on {}", task_id.bold());
+        return Ok(());
+    }
+    println!("{}", format!("Tasks blocked by {}", task_id).bold().blue());
+    for (task, waiting) in blocked {
+        println!("{} {} {}", task.id.cyan(), task.task, format!("(waiting on {})", waiting.join(", ")).dimmed());
+    }
+    Ok(())
+}
+
+fn list_recurring_tasks() -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let series = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        match manager.get_current_project().await? {
+            Some(project) => manager.list_recurring_tasks(&project.id).await,
+            None => Ok(Vec::new()),
+        }
+    })?;
+
+    if series.is_empty() {
+        println!("No recurring tasks. Add a repeat rule with `wsb task schedule <id> --due <date> --repeat <rule>`");
+        return Ok(());
+    }
+    println!("{}", "Recurring tasks".bold().blue());
+    for entry in series {
+        let template = &entry.template;
+        println!(
+            "{} {} {} {}",
+            template.id.cyan(),
+            template.title,
+            format!("[template, {}]", entry.rule).yellow(),
+            format!("due {}, {}", template.due_date.as_deref().unwrap_or("-"), template.status).dimmed()
+        );
+        for instance in &entry.instances {
+            println!(
+                "  └ {} {}",
+                instance.id.cyan(),
+                format!("[instance] due {}, {}", instance.due_date.as_deref().unwrap_or("-"), instance.status).dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn run_directive_command(action: DirectiveAction) -> Result<()> {
+    match action {
+        DirectiveAction::Add { title, description, category, enforcement, priority } => {
+            add_directive(title, description, category, enforcement, priority)?;
+        }
+        DirectiveAction::List { category, enforcement, priority, recent } => {
+            list_directives(category, enforcement, priority, recent)?;
+        }
+        DirectiveAction::Show { identifier } => {
+            show_directive(identifier)?;
+        }
+        DirectiveAction::Search { query, category } => {
+            search_directives(query, category)?;
+        }
+        DirectiveAction::Update { directive_id, enforcement, priority, description, category } => {
+            update_directive(directive_id, enforcement, priority, description, category)?;
+        }
+        DirectiveAction::Remove { directive_id, force } => {
+            remove_directive(directive_id, force)?;
+        }
+        DirectiveAction::Validate { category, verbose, fail_fast, mandatory } => {
+            validate_directives(category, verbose, fail_fast, mandatory)?;
+        }
+        DirectiveAction::Check { paths, category, format } => {
+            check_paths_against_directives(paths, category, format)?;
+        }
+        DirectiveAction::AddRule { directive_id, forbid, require, max_size, command, glob, hint } => {
+            add_directive_rule(directive_id, forbid, require, max_size, command, glob, hint)?;
+        }
+        DirectiveAction::Rules { directive_id, format } => {
+            list_directive_rules(directive_id, format)?;
+        }
+        DirectiveAction::RemoveRule { rule_id } => {
+            remove_directive_rule(rule_id)?;
+        }
+        DirectiveAction::InstallHook { force } => {
+            install_directive_hook(force)?;
+        }
+        DirectiveAction::UninstallHook => {
+            uninstall_directive_hook()?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct Directive {
+    id: String,
+    title: String,
+    description: String,
+    category: DirectiveCategory,
+    enforcement: EnforcementLevel,
+    priority: DirectivePriority,
+    created_date: String,
+    _updated_date: String,
+    violation_count: u32,
+    last_validated: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum DirectiveCategory {
+    Security,
+    Testing,
+    Coding,
+    Methodology,
+    Deployment,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum EnforcementLevel {
+    Mandatory,
+    Recommended,
     Optional,
 }
 
@@ -5390,9 +8333,30 @@ fn load_directives_from_file() -> Result<Vec<Directive>> {
         i += 1;
     }
     
+    apply_directive_validation_states(&project_root, &mut directives)?;
     Ok(directives)
 }
 
+/// Fill in the violation count and time of the latest `validate` or `check` run
+fn apply_directive_validation_states(project_root: &Path, directives: &mut [Directive]) -> Result<()> {
+    let db_path = project_root.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let rt = tokio::runtime::Runtime::new()?;
+    let states = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        wsb::entities::directive_rules::validation_states(&pool).await
+    })?;
+    for directive in directives.iter_mut() {
+        if let Some(state) = states.get(&directive.id) {
+            directive.violation_count = state.violation_count as u32;
+            directive.last_validated = Some(state.validated_at.clone());
+        }
+    }
+    Ok(())
+}
+
 fn parse_directive_from_lines(lines: &[&str], start_idx: usize) -> Result<Option<Directive>> {
     if start_idx >= lines.len() {
         return Ok(None);
@@ -5512,17 +8476,61 @@ fn show_directive(identifier: String) -> Result<()> {
                 println!("Last Validated: {}", last_validated);
             }
             
+            if directive.violation_count > 0 {
+                let db_path = get_project_root()?.join(".wsb/project.db");
+                let rt = tokio::runtime::Runtime::new()?;
+                let found = rt.block_on(async {
+                    let pool = wsb::entities::database::initialize_database(&db_path).await?;
+                    wsb::entities::directive_rules::violations(&pool, &directive.id).await
+                })?;
+                println!("\nViolations from the latest run:");
+                print_directive_violations(&found);
+            }
+            
             println!("\nDescription:");
             println!("{}", directive.description);
         }
         None => {
             println!("{} Directive not found: {}", "Error".red(), identifier);
+            print_id_suggestion(&identifier, directives.iter().map(|d| d.id.as_str()));
         }
     }
     
     Ok(())
 }
 
+fn search_directives(query: String, category: Option<String>) -> Result<()> {
+    let needle = query.to_lowercase();
+    let directives = load_directives_from_file()?;
+    let matches: Vec<&Directive> = directives.iter()
+        .filter(|d| match &category {
+            Some(c) => d.category.to_string() == *c,
+            None => true,
+        })
+        .filter(|d| {
+            d.id.to_lowercase().contains(&needle)
+                || d.title.to_lowercase().contains(&needle)
+                || d.description.to_lowercase().contains(&needle)
+        })
+        .collect();
+
+    if matches.is_empty() {
+        println!("No directives match '{}'", query);
+        return Ok(());
+    }
+    println!("{}", format!("Directives matching '{}' ({})", query, matches.len()).bold().blue());
+    for directive in matches {
+        println!("  {} [{}] {} ({}, {})",
+            directive.id.bold(),
+            directive.category.to_string().cyan(),
+            directive.title,
+            directive.enforcement,
+            directive.priority
+        );
+    }
+    Ok(())
+}
+
 fn update_directive(directive_id: String, enforcement: Option<String>, priority: Option<String>, description: Option<String>, category: Option<String>) -> Result<()> {
     println!("{} Updating directive: {}", "Info".blue(), directive_id.bold());
     
@@ -5559,14 +8567,18 @@ fn remove_directive(directive_id: String, force: bool) -> Result<()> {
     Ok(())
 }
 
-fn validate_directives(category: Option<String>, verbose: bool, fail_fast: bool) -> Result<()> {
+fn validate_directives(category: Option<String>, verbose: bool, fail_fast: bool, mandatory: bool) -> Result<()> {
     println!("{}", "Validating Project Against Directives".bold().blue());
     
+    let project_root = get_project_root()?;
     let directives = load_directives_from_file()?;
     
-    // Filter by category if specified
+    // Filter by category and enforcement if specified
     let filtered_directives: Vec<&Directive> = directives.iter()
         .filter(|d| {
+            if mandatory && d.enforcement != EnforcementLevel::Mandatory {
+                return false;
+            }
             if let Some(ref cat) = category {
                 d.category.to_string() == *cat
             } else {
@@ -5580,41 +8592,90 @@ fn validate_directives(category: Option<String>, verbose: bool, fail_fast: bool)
         return Ok(());
     }
     
-    let mut violations = 0;
+    let results = run_directive_rules(&project_root, &filtered_directives, None, fail_fast)?;
+    print_directive_results(&results, verbose);
+    let outcome = fail_on_mandatory_violations(&results);
+    if outcome.is_err() && is_running_as_git_hook() {
+        eprintln!("{} Commit blocked. Fix the violations above, or bypass once with 'git commit --no-verify'", "❌".red());
+    }
+    outcome
+}
+
+/// Evaluate the rules of each directive and store what they found. Directives without
+/// rules that apply are paired with None. With `fail_fast`, stops after the first
+/// mandatory directive with violations.
+fn run_directive_rules<'a>(
+    project_root: &Path,
+    directives: &[&'a Directive],
+    paths: Option<&[PathBuf]>,
+    fail_fast: bool,
+) -> Result<Vec<(&'a Directive, Option<Vec<wsb::entities::directive_rules::Violation>>)>> {
+    use wsb::entities::directive_rules;
+
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let mut results = Vec::new();
+        for &directive in directives {
+            // Checking paths only runs the rules that look at individual files
+            let rules: Vec<_> = directive_rules::list_rules(&pool, Some(&directive.id)).await?
+                .into_iter()
+                .filter(|rule| paths.is_none() || rule.spec.is_file_rule())
+                .collect();
+            if rules.is_empty() {
+                results.push((directive, None));
+                continue;
+            }
+            let found = directive_rules::evaluate(project_root, &rules, paths)?;
+            directive_rules::record_run(&pool, &directive.id, &found).await?;
+            let stop = fail_fast && !found.is_empty() && directive.enforcement == EnforcementLevel::Mandatory;
+            results.push((directive, Some(found)));
+            if stop {
+                println!("{} Failing fast due to mandatory directive violation", "❌".red());
+                break;
+            }
+        }
+        Ok::<_, anyhow::Error>(results)
+    })
+}
+
+fn print_directive_results(results: &[(&Directive, Option<Vec<wsb::entities::directive_rules::Violation>>)], verbose: bool) {
     let mut checks = 0;
+    let mut violations = 0;
     
-    for directive in filtered_directives {
+    for (directive, found) in results {
+        let found = match found {
+            Some(found) => found,
+            None => {
+                if verbose {
+                    println!("  ➖ No checkable rules: {} {}", directive.id, directive.title);
+                }
+                continue;
+            }
+        };
         checks += 1;
         
-        if verbose {
-            println!("\n🔍 Checking: {} ({})", directive.title, directive.category);
-        }
-        
-        // Simulate directive validation (in real implementation, this would check actual rules)
-        let is_violation = simulate_directive_check(directive);
-        
-        if is_violation {
-            violations += 1;
-            let severity = match directive.enforcement {
-                EnforcementLevel::Mandatory => "🚨 VIOLATION",
-                EnforcementLevel::Recommended => "⚠️  WARNING",
-                EnforcementLevel::Optional => "💡 SUGGESTION",
-            };
-            
-            println!("  {} {}: {}", severity, directive.category.to_string().cyan(), directive.title);
-            
-            if fail_fast && directive.enforcement == EnforcementLevel::Mandatory {
-                println!("{} Failing fast due to mandatory directive violation", "❌".red());
-                return Err(anyhow::anyhow!("Mandatory directive violation: {}", directive.title));
+        if found.is_empty() {
+            if verbose {
+                println!("  ✅ Compliant: {} {}", directive.id, directive.title);
             }
-        } else if verbose {
-            println!("  ✅ Compliant: {}", directive.title);
+            continue;
         }
+        violations += found.len();
+        
+        let severity = match directive.enforcement {
+            EnforcementLevel::Mandatory => "🚨 VIOLATION",
+            EnforcementLevel::Recommended => "⚠️  WARNING",
+            EnforcementLevel::Optional => "💡 SUGGESTION",
+        };
+        println!("  {} {} [{}] {} ({})", severity, directive.id.bold(), directive.category.to_string().cyan(), directive.title, found.len());
+        print_directive_violations(found);
     }
     
     // Summary
     println!("\n{}", "Validation Summary".bold());
-    println!("Checks performed: {}", checks);
+    println!("Directives checked: {}", checks);
     println!("Violations found: {}", if violations > 0 { violations.to_string().red() } else { violations.to_string().green() });
     
     if violations == 0 {
@@ -5622,82 +8683,253 @@ fn validate_directives(category: Option<String>, verbose: bool, fail_fast: bool)
     } else {
         println!("{} {} directive violations found", "⚠️".yellow(), violations);
     }
-    
-    Ok(())
 }
 
-fn simulate_directive_check(directive: &Directive) -> bool {
-    // Simple simulation: some directives pass, some fail
-    // In real implementation, this would check actual project state against rules
-    match directive.category {
-        DirectiveCategory::Security => directive.title.contains("secret") || directive.title.contains("password"),
-        DirectiveCategory::Testing => directive.title.contains("coverage") && directive.title.contains("100%"),
-        DirectiveCategory::Coding => directive.title.contains("TODO") || directive.title.contains("FIXME"),
-        DirectiveCategory::Methodology => false, // Most methodology directives pass
-        DirectiveCategory::Deployment => directive.title.contains("production"),
+/// Print each violation, followed by the remediation hint of every rule that failed
+fn print_directive_violations(found: &[wsb::entities::directive_rules::Violation]) {
+    for violation in found {
+        let location = match (&violation.path, violation.line) {
+            (Some(path), Some(line)) => format!("{}:{}: ", path, line),
+            (Some(path), None) => format!("{}: ", path),
+            _ => String::new(),
+        };
+        println!("      {}{}", location, violation.message);
+    }
+    let mut hinted = std::collections::HashSet::new();
+    for violation in found {
+        if let Some(ref hint) = violation.hint {
+            if hinted.insert(violation.rule_id) {
+                println!("      {} {}", "💡".yellow(), hint);
+            }
+        }
+    }
+}
+
+/// Fail when a mandatory directive has violations, naming the directives
+fn fail_on_mandatory_violations(results: &[(&Directive, Option<Vec<wsb::entities::directive_rules::Violation>>)]) -> Result<()> {
+    let violated: Vec<&str> = results.iter()
+        .filter(|(directive, found)| {
+            directive.enforcement == EnforcementLevel::Mandatory && found.as_ref().is_some_and(|found| !found.is_empty())
+        })
+        .map(|(directive, _)| directive.id.as_str())
+        .collect();
+    if !violated.is_empty() {
+        anyhow::bail!("Mandatory directive violations: {}", violated.join(", "));
     }
+    Ok(())
 }
 
 fn check_paths_against_directives(paths: Vec<std::path::PathBuf>, category: Option<String>, format: String) -> Result<()> {
-    println!("{} Checking paths against directives", "Info".blue());
-    
+    let project_root = get_project_root()?;
+    let cwd = env::current_dir()?;
+    let paths: Vec<PathBuf> = if paths.is_empty() {
+        vec![cwd]
+    } else {
+        paths.into_iter().map(|path| cwd.join(path)).collect()
+    };
     for path in &paths {
-        println!("  {} Checking: {}", "→".green(), path.display());
-    }
-    
-    if let Some(cat) = category {
-        println!("  {} Category filter: {}", "→".green(), cat.cyan());
+        if !path.exists() {
+            anyhow::bail!("Path not found: {}", path.display());
+        }
     }
     
-    println!("  {} Output format: {}", "→".green(), format);
+    let directives = load_directives_from_file()?;
+    let filtered_directives: Vec<&Directive> = directives.iter()
+        .filter(|d| match category {
+            Some(ref cat) => d.category.to_string() == *cat,
+            None => true,
+        })
+        .collect();
     
-    // Simulate checking (in real implementation, would analyze files against rules)
-    let issues_found = paths.len() % 3; // Simulate some issues
+    let results = run_directive_rules(&project_root, &filtered_directives, Some(&paths), false)?;
+    let violations: Vec<&wsb::entities::directive_rules::Violation> = results.iter()
+        .filter_map(|(_, found)| found.as_ref())
+        .flatten()
+        .collect();
+    let checked = results.iter().filter(|(_, found)| found.is_some()).count();
     
     match format.as_str() {
         "json" => {
             let result = serde_json::json!({
                 "paths_checked": paths.len(),
-                "issues_found": issues_found,
-                "status": if issues_found == 0 { "compliant" } else { "violations" }
+                "directives_checked": checked,
+                "issues_found": violations.len(),
+                "violations": violations,
+                "status": if violations.is_empty() { "compliant" } else { "violations" }
             });
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
         "report" => {
             println!("\n=== Directive Compliance Report ===");
             println!("Paths Checked: {}", paths.len());
-            println!("Issues Found: {}", issues_found);
-            println!("Status: {}", if issues_found == 0 { "✅ Compliant" } else { "⚠️ Violations" });
+            println!("Directives Checked: {}", checked);
+            println!("Issues Found: {}", violations.len());
+            for (directive, found) in &results {
+                if let Some(found) = found.as_ref().filter(|found| !found.is_empty()) {
+                    println!("\n{} - {} ({})", directive.id, directive.title, directive.enforcement);
+                    print_directive_violations(found);
+                }
+            }
+            println!("Status: {}", if violations.is_empty() { "✅ Compliant" } else { "⚠️ Violations" });
         }
         _ => {
-            if issues_found == 0 {
-                println!("{} All {} paths compliant with directives", "✅".green(), paths.len());
-            } else {
-                println!("{} {} issues found in {} paths", "⚠️".yellow(), issues_found, paths.len());
-            }
+            println!("{} Checking {} paths against directives", "Info".blue(), paths.len());
+            print_directive_results(&results, false);
         }
     }
     
-    Ok(())
+    fail_on_mandatory_violations(&results)
 }
 
-fn run_feature_command(action: FeatureAction) -> Result<()> {
-    match action {
-        FeatureAction::Add { title, description, category, state } => {
-            add_feature_to_database(title, description, category, state)?;
-        }
-        FeatureAction::List { state, category, recent } => {
-            list_features(state, category, recent)?;
+fn install_directive_hook(force: bool) -> Result<()> {
+    if !is_git_repository() {
+        anyhow::bail!("Not in a git repository");
+    }
+    let git_root = get_git_root()?;
+    
+    if !force && wsb::st8::hooks::is_directive_gate_installed(&git_root)? {
+        println!("{} Directive pre-commit hook is already installed", "Info".blue());
+        println!("{} Use 'wsb directive install-hook --force' to reinstall", "Tip".yellow());
+        return Ok(());
+    }
+    
+    let current_exe = env::current_exe()
+        .context("Failed to get current executable path")?;
+    let hook_file = wsb::st8::hooks::install_directive_gate(&git_root, &current_exe)?;
+    
+    log::info!("Directive pre-commit hook installed at {}", hook_file.display());
+    println!("{} Directive pre-commit hook installed ({})", "Success".green(), hook_file.display());
+    println!("{} Commits will be blocked while a mandatory directive has violations", "Info".blue());
+    Ok(())
+}
+
+fn uninstall_directive_hook() -> Result<()> {
+    use wsb::st8::hooks::Removal;
+
+    if !is_git_repository() {
+        anyhow::bail!("Not in a git repository");
+    }
+    match wsb::st8::hooks::uninstall_directive_gate(&get_git_root()?)? {
+        Removal::NotInstalled => println!("{} Directive pre-commit hook is not installed", "Info".blue()),
+        Removal::Deleted => println!("{} Removed pre-commit hook", "Success".green()),
+        Removal::Stripped => println!("{} Removed directive check from pre-commit hook", "Success".green()),
+    }
+    Ok(())
+}
+
+fn add_directive_rule(
+    directive_id: String,
+    forbid: Option<String>,
+    require: Option<String>,
+    max_size: Option<u64>,
+    command: Option<String>,
+    glob: Option<String>,
+    hint: Option<String>,
+) -> Result<()> {
+    use wsb::entities::directive_rules::{self, RuleSpec};
+
+    let spec = match (forbid, require, max_size, command) {
+        (Some(pattern), None, None, None) => RuleSpec::ForbiddenPattern { glob, pattern },
+        (None, Some(path), None, None) => RuleSpec::RequiredFile { path },
+        (None, None, Some(max_bytes), None) => RuleSpec::MaxFileSize { glob, max_bytes },
+        (None, None, None, Some(command)) => RuleSpec::Command { command },
+        _ => anyhow::bail!("Give exactly one of --forbid, --require, --max-size or --command"),
+    };
+    
+    let directives = load_directives_from_file()?;
+    if !directives.iter().any(|d| d.id == directive_id) {
+        println!("{} Directive not found: {}", "Error".red(), directive_id);
+        print_id_suggestion(&directive_id, directives.iter().map(|d| d.id.as_str()));
+        anyhow::bail!("Directive not found: {}", directive_id);
+    }
+    
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let rule_id = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        directive_rules::add_rule(&pool, &directive_id, &spec, hint.as_deref()).await
+    })?;
+    
+    println!("{} Rule {} added to {}: {}", "✅".green(), rule_id.to_string().bold(), directive_id.bold(), spec.describe());
+    Ok(())
+}
+
+fn list_directive_rules(directive_id: Option<String>, format: String) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rules = if db_path.exists() {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(async {
+            let pool = wsb::entities::database::initialize_database(&db_path).await?;
+            wsb::entities::directive_rules::list_rules(&pool, directive_id.as_deref()).await
+        })?
+    } else {
+        Vec::new()
+    };
+    
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&rules)?);
+        return Ok(());
+    }
+    
+    println!("{}", "Directive Rules".bold().blue());
+    if rules.is_empty() {
+        println!("No directive rules defined. Add one with 'wsb directive add-rule'.");
+        return Ok(());
+    }
+    for rule in &rules {
+        println!("  {} {} {}", format!("#{}", rule.id).bold(), rule.directive_id.cyan(), rule.description);
+        if let Some(ref hint) = rule.hint {
+            println!("      {} {}", "💡".yellow(), hint);
+        }
+    }
+    Ok(())
+}
+
+fn remove_directive_rule(rule_id: i64) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        wsb::entities::directive_rules::remove_rule(&pool, rule_id).await
+    })?;
+    println!("{} Rule {} removed", "🗑️".yellow(), rule_id.to_string().bold());
+    Ok(())
+}
+
+fn run_feature_command(action: FeatureAction) -> Result<()> {
+    match action {
+        FeatureAction::Add { title, description, category, state, body, body_file, tags } => {
+            let body = read_body_arg(body, body_file)?;
+            let feature_id = add_feature_to_database(title, description, category, state)?;
+            tag_new_entity("feature", &feature_id, &tags)?;
+            if let Some(body) = body {
+                set_entity_body(&feature_id, &body)?;
+            }
+        }
+        FeatureAction::List { state, category, recent, tags, view } => {
+            list_features(state, category, recent, tags, view)?;
         }
         FeatureAction::Show { feature_id } => {
             show_feature(feature_id)?;
         }
-        FeatureAction::Update { feature_id, state, evidence, force } => {
-            update_feature(feature_id, state, evidence, force)?;
+        FeatureAction::Update { feature_id, state, evidence, force, body, body_file } => {
+            update_feature(feature_id.clone(), state, evidence, force)?;
+            if let Some(body) = read_body_arg(body, body_file)? {
+                set_entity_body(&feature_id, &body)?;
+            }
         }
         FeatureAction::Validate { feature_id, verbose } => {
             validate_features(feature_id, verbose)?;
         }
+        FeatureAction::Review { state, evidence_limit } => {
+            review_features(state, evidence_limit)?;
+        }
+        FeatureAction::Delete { feature_id, purge } => {
+            delete_feature(feature_id, purge)?;
+        }
+        FeatureAction::Restore { feature_id } => {
+            restore_feature(feature_id)?;
+        }
         FeatureAction::DetectFeatures { input } => {
             analyze_user_input_for_features(&input)?;
         }
@@ -5712,6 +8944,40 @@ fn run_feature_command(action: FeatureAction) -> Result<()> {
     Ok(())
 }
 
+fn delete_feature(feature_id: String, purge: bool) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        require_id(&pool, "Feature", &feature_id, if purge { "SELECT id FROM features" } else { LIVE_FEATURE_IDS }).await?;
+        let manager = EntityManager::new(pool);
+        if purge {
+            manager.purge_feature(&feature_id).await
+        } else {
+            manager.delete_feature(&feature_id).await
+        }
+    })?;
+
+    if purge {
+        println!("{} Feature {} and its tasks permanently deleted", "🗑️".red(), feature_id.bold());
+    } else {
+        println!("{} Feature {} and its tasks deleted (restore with 'wsb feature restore {}')", "🗑️".yellow(), feature_id.bold(), feature_id);
+    }
+    Ok(())
+}
+
+fn restore_feature(feature_id: String) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).restore_feature(&feature_id).await
+    })?;
+
+    println!("{} Feature {} restored", "✅".green(), feature_id.bold());
+    Ok(())
+}
+
 // Entity relationship management command handler
 fn run_relationship_command(action: RelationshipAction) -> Result<()> {
     match action {
@@ -5727,15 +8993,82 @@ fn run_relationship_command(action: RelationshipAction) -> Result<()> {
         RelationshipAction::Resolve { dependency_id, description } => {
             resolve_entity_relationship(dependency_id, description)?;
         }
+        RelationshipAction::Types { format } => {
+            show_relationship_types(format)?;
+        }
         RelationshipAction::Stats { detailed, format } => {
             show_relationship_stats(detailed, format)?;
         }
+        RelationshipAction::Graph { format, entity, depth, output } => {
+            export_relationship_graph(format, entity, depth, output)?;
+        }
+        RelationshipAction::Impact { entity_id, depth, format } => {
+            show_relationship_impact(entity_id, depth, format)?;
+        }
     }
     
     Ok(())
 }
 
 // Database-backed feature management (addresses user request)
+/// Body text from `--body` or `--body-file` ("-" reads stdin)
+fn read_body_arg(body: Option<String>, body_file: Option<std::path::PathBuf>) -> Result<Option<String>> {
+    match (body, body_file) {
+        (Some(body), _) => Ok(Some(body)),
+        (None, Some(path)) if path.as_os_str() == "-" => {
+            let mut body = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut body)?;
+            Ok(Some(body))
+        }
+        (None, Some(path)) => std::fs::read_to_string(&path)
+            .map(Some)
+            .with_context(|| format!("Failed to read body file {}", path.display())),
+        (None, None) => Ok(None),
+    }
+}
+
+fn set_entity_body(entity_id: &str, body: &str) -> Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let db_path = get_project_root()?.join(".wsb/project.db");
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        wsb::entities::bodies::set(&pool, entity_id, body).await
+    })?;
+    if body.trim().is_empty() {
+        println!("{} Body removed from {}", "✅".green(), entity_id.bold());
+    } else {
+        println!("{} Body of {} updated ({} lines)", "✅".green(), entity_id.bold(), body.lines().count());
+    }
+    Ok(())
+}
+
+/// Stored bodies by entity ID; empty when there is no project database
+fn load_entity_bodies() -> Result<HashMap<String, String>> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    if !db_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        wsb::entities::bodies::all(&pool).await
+    })
+}
+
+/// Width available for a list line's body preview after `indent` columns
+fn body_preview_width(indent: usize) -> usize {
+    let columns = crossterm::terminal::size().map(|(columns, _)| columns as usize).unwrap_or(100);
+    columns.saturating_sub(indent).max(20)
+}
+
+fn print_body(body: &str) {
+    println!();
+    println!("{}", "Details:".bold());
+    for line in body.lines() {
+        println!("  {}", line);
+    }
+}
+
 fn add_feature_to_database(title: String, description: String, category: String, state: String) -> Result<String> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
@@ -5791,13 +9124,13 @@ fn add_task_to_database(title: String, description: String, feature_id: Option<S
     
     // TODO: Add to SQLite database instead of file
     // For now, add to task backlog file
-    add_task_to_file(title, description, feature_id, priority)?;
+    add_task_to_file(&task_id, title, description, feature_id, priority)?;
     
     println!("{} Task {} added (database storage pending)", "✅".green(), task_id);
     Ok(task_id)
 }
 
-fn add_task_to_database_with_detection(title: String, description: String, feature: Option<String>, priority: String, auto_feature: bool) -> Result<()> {
+fn add_task_to_database_with_detection(title: String, description: String, feature: Option<String>, priority: String, auto_feature: bool) -> Result<String> {
     // Feature auto-detection if enabled
     let feature_id = if auto_feature && feature.is_none() {
         // Analyze description for feature mentions
@@ -5813,15 +9146,13 @@ fn add_task_to_database_with_detection(title: String, description: String, featu
         feature
     };
     
-    add_task_to_database(title, description, feature_id, priority)?;
-    Ok(())
+    add_task_to_database(title, description, feature_id, priority)
 }
 
-fn add_task_to_file(title: String, description: String, feature_id: Option<String>, priority: String) -> Result<()> {
+fn add_task_to_file(task_id: &str, title: String, description: String, feature_id: Option<String>, priority: String) -> Result<()> {
     let project_root = get_project_root()?;
     let backlog_path = project_root.join("internal/task_backlog.md");
     
-    let task_id = format!("TASK-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
     let created_date = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
     
     let feature_text = if let Some(ref fid) = feature_id {
@@ -5879,15 +9210,23 @@ fn add_feature_to_file(title: String, description: String, category: String, sta
     Ok(())
 }
 
-fn list_features(state: Option<String>, category: Option<String>, _recent: Option<u32>) -> Result<()> {
+fn list_features(state: Option<String>, category: Option<String>, _recent: Option<u32>, tags: Vec<String>, view: Option<String>) -> Result<()> {
+    let view = view.map(|name| load_view(&name, "feature")).transpose()?;
+    let state = state.or_else(|| view.as_ref().and_then(|view| view.status.clone()));
+    let tags = if tags.is_empty() { view.as_ref().map(|view| view.tags.clone()).unwrap_or_default() } else { tags };
+    let tagged = if tags.is_empty() { None } else { Some(load_tagged("feature", &tags)?) };
     let project_root = get_project_root()?;
     let features_path = project_root.join("internal").join("features.md");
     let features_content = std::fs::read_to_string(&features_path)?;
     
-    println!("{}", "Feature List".bold());
+    match &view {
+        Some(view) => println!("{} {}", "Feature List".bold(), format!("(view {}: {})", view.name, describe_view(view)).dimmed()),
+        None => println!("{}", "Feature List".bold()),
+    }
     println!();
     
-    let mut found_any = false;
+    let bodies = load_entity_bodies()?;
+    let mut rows: Vec<(&str, &str, &str)> = Vec::new();
     for line in features_content.lines() {
         if line.starts_with("| F") && line.matches("|").count() >= 5 {
             // Apply filters
@@ -5908,16 +9247,33 @@ fn list_features(state: Option<String>, category: Option<String>, _recent: Optio
             if parts.len() >= 5 {
                 let id = parts[0].trim_start_matches("| ");
                 let name = parts[1].trim_start_matches("**").trim_end_matches("**");
-                let state_part = parts[3];
-                
-                println!("  {} {} - {}", state_part, id.bold(), name);
-                found_any = true;
+                if tagged.as_ref().map_or(false, |tagged| !tagged.contains(id.trim())) {
+                    continue;
+                }
+                rows.push((id, name, parts[3]));
             }
         }
     }
     
-    if !found_any {
+    if rows.is_empty() {
         println!("No features found matching criteria.");
+        return Ok(());
+    }
+    if let Some((field, descending)) = view.as_ref().and_then(|view| view.sort_key()) {
+        rows.sort_by(|a, b| {
+            let order = match field {
+                "title" => a.1.cmp(b.1),
+                "status" => a.2.cmp(b.2),
+                _ => a.0.cmp(b.0),
+            };
+            if descending { order.reverse() } else { order }
+        });
+    }
+    for (id, name, state_part) in rows {
+        println!("  {} {} - {}", state_part, id.bold(), name);
+        if let Some(body) = bodies.get(id.trim()) {
+            println!("      {}", wsb::entities::bodies::summary_line(body, body_preview_width(6)).dimmed());
+        }
     }
     
     Ok(())
@@ -5941,6 +9297,9 @@ fn show_feature(feature_id: String) -> Result<()> {
                 println!("{}: {}", "Name".bold(), name);
                 println!("{}: {}", "Description".bold(), description);
                 println!("{}: {}", "Notes".bold(), notes);
+                if let Some(body) = load_entity_bodies()?.get(feature_id.as_str()) {
+                    print_body(body);
+                }
                 return Ok(());
             }
         }
@@ -5948,6 +9307,12 @@ fn show_feature(feature_id: String) -> Result<()> {
     
     log::error!("Feature not found: {}", feature_id);
     println!("{} Feature {} not found", "❌".red(), feature_id);
+    let known_ids: Vec<&str> = features_content.lines()
+        .filter(|line| line.starts_with("| F") && line.matches("|").count() >= 5)
+        .filter_map(|line| line.split(" | ").next())
+        .map(|id| id.trim_start_matches("| ").trim())
+        .collect();
+    print_id_suggestion(&feature_id, known_ids);
     Ok(())
 }
 
@@ -6022,6 +9387,178 @@ fn validate_features(feature_id: Option<String>, verbose: bool) -> Result<()> {
     Ok(())
 }
 
+fn parse_feature_state_arg(state: &str) -> Result<wsb::entities::schema_models::FeatureState> {
+    use wsb::entities::schema_models::FeatureState;
+    match state {
+        "not_started" => Ok(FeatureState::NotImplemented),
+        "implemented" => Ok(FeatureState::ImplementedNoTests),
+        "testing" => Ok(FeatureState::ImplementedFailingTests),
+        "completed" => Ok(FeatureState::ImplementedPassingTests),
+        "issue" => Ok(FeatureState::TestsBroken),
+        "critical" => Ok(FeatureState::CriticalIssue),
+        other => FeatureState::from_str(other).map_err(|e| anyhow::anyhow!(e)),
+    }
+}
+
+/// Review decision captured from a single keystroke
+enum ReviewDecision {
+    Advance,
+    Demote,
+    Skip,
+    Quit,
+}
+
+/// Leaves raw mode when dropped, so the terminal is restored on errors and panics too
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Result<Self> {
+        crossterm::terminal::enable_raw_mode()?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+fn read_review_decision() -> Result<ReviewDecision> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+
+    wsb::tui::require_terminal("wsb feature review")?;
+    let _raw_mode = RawModeGuard::enable()?;
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('a') | KeyCode::Right => return Ok(ReviewDecision::Advance),
+                KeyCode::Char('d') | KeyCode::Left => return Ok(ReviewDecision::Demote),
+                KeyCode::Char('s') | KeyCode::Char(' ') | KeyCode::Enter | KeyCode::Down => return Ok(ReviewDecision::Skip),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(ReviewDecision::Quit),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn review_features(state: String, evidence_limit: u32) -> Result<()> {
+    let target_state = parse_feature_state_arg(&state)?;
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let db_path = get_project_root()?.join(".wsb/project.db");
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let entity_manager = EntityManager::new(pool.clone());
+
+        let project = entity_manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project"))?;
+        let features = wsb::entities::crud::features::list_by_state(&pool, &project.id, target_state.clone()).await?;
+
+        if features.is_empty() {
+            println!("No features in state {} {}", target_state.emoji(), target_state.as_str());
+            return Ok(());
+        }
+
+        let tasks = entity_manager.list_tasks_by_project(&project.id, None).await?;
+        let policy = load_wip_policy(&get_project_root()?);
+        let total = features.len();
+        let (mut advanced, mut demoted, mut skipped) = (0, 0, 0);
+
+        for (index, feature) in features.iter().enumerate() {
+            println!();
+            println!("{} [{}/{}] {} {} - {}", "🔎".cyan(), index + 1, total, target_state.emoji(), feature.id.bold(), feature.name);
+            println!("  {}: {}", "Description".bold(), feature.description);
+            if let Some(ref category) = feature.category {
+                println!("  {}: {}", "Category".bold(), category);
+            }
+            println!("  {}: {}", "Test status".bold(), feature.test_status);
+
+            let linked_tasks: Vec<_> = tasks.iter().filter(|t| t.feature_id == feature.id).collect();
+            let criteria = wsb::entities::crud::tasks::acceptance_criteria(&pool, &feature.id).await?;
+            if !criteria.is_empty() {
+                println!("  {}:", "Acceptance criteria".bold());
+                for (task_id, text) in &criteria {
+                    println!("    {} {}", task_id.dimmed(), text);
+                }
+            }
+
+            let evidence = sqlx::query("SELECT name, passed, run_at FROM tests WHERE feature_id = ? ORDER BY run_at DESC LIMIT ?")
+                .bind(&feature.id)
+                .bind(evidence_limit as i64)
+                .fetch_all(&pool)
+                .await?;
+            if evidence.is_empty() {
+                println!("  {}: none recorded", "Test evidence".bold());
+            } else {
+                println!("  {}:", "Test evidence".bold());
+                for row in &evidence {
+                    let mark = if row.get::<bool, _>("passed") { "✅" } else { "❌" };
+                    println!("    {} {} ({})", mark, row.get::<String, _>("name"), row.get::<String, _>("run_at"));
+                }
+            }
+
+            if linked_tasks.is_empty() {
+                println!("  {}: none", "Linked tasks".bold());
+            } else {
+                println!("  {}:", "Linked tasks".bold());
+                for task in &linked_tasks {
+                    println!("    {} [{}] {}", task.id, task.status, task.task);
+                }
+            }
+
+            let advance_to = target_state.advance();
+            let demote_to = target_state.demote();
+            println!(
+                "  {} [a] advance{}  [d] demote{}  [s] skip  [q] quit",
+                "→".green(),
+                advance_to.as_ref().map(|s| format!(" to {}", s.emoji())).unwrap_or_default(),
+                demote_to.as_ref().map(|s| format!(" to {}", s.emoji())).unwrap_or_default(),
+            );
+
+            match read_review_decision()? {
+                ReviewDecision::Advance => match advance_to {
+                    Some(next) => {
+                        let violation = wsb::entities::policy::check_feature_transition(&pool, &policy, &feature.id, &next).await?;
+                        if let Err(e) = apply_wip_policy(&policy, violation) {
+                            println!("  {} {}", "🚫".red(), e);
+                            skipped += 1;
+                            continue;
+                        }
+                        wsb::entities::crud::features::update_state(&pool, &feature.id, next.clone()).await?;
+                        println!("  {} {} advanced to {}", "✅".green(), feature.id, next.emoji());
+                        advanced += 1;
+                    }
+                    None => {
+                        println!("  {} {} is already at the final state", "⚠️".yellow(), feature.id);
+                        skipped += 1;
+                    }
+                },
+                ReviewDecision::Demote => match demote_to {
+                    Some(prev) => {
+                        wsb::entities::crud::features::update_state(&pool, &feature.id, prev.clone()).await?;
+                        println!("  {} {} demoted to {}", "⬇️".yellow(), feature.id, prev.emoji());
+                        demoted += 1;
+                    }
+                    None => {
+                        println!("  {} {} cannot be demoted further", "⚠️".yellow(), feature.id);
+                        skipped += 1;
+                    }
+                },
+                ReviewDecision::Skip => skipped += 1,
+                ReviewDecision::Quit => break,
+            }
+        }
+
+        println!();
+        println!("{} Review finished: {} advanced, {} demoted, {} skipped", "📊".cyan(), advanced, demoted, skipped);
+        Ok(())
+    })
+}
+
 fn get_next_feature_id(features_content: &str) -> String {
     let mut max_id = 0;
     
@@ -6070,6 +9607,7 @@ fn update_feature_state(feature_id: &str, new_state: &str, evidence: Option<Stri
         let db_path = get_project_root()?.join(".wsb/project.db");
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
         let _entity_manager = EntityManager::new(pool.clone());
+        require_id(&pool, "Feature", feature_id, LIVE_FEATURE_IDS).await?;
         
         println!("{} Updating feature {} state to {}", "🔄".blue(), feature_id, new_state);
         
@@ -6087,6 +9625,10 @@ fn update_feature_state(feature_id: &str, new_state: &str, evidence: Option<Stri
             }
         };
         
+        let policy = load_wip_policy(&get_project_root()?);
+        let violation = wsb::entities::policy::check_feature_transition(&pool, &policy, feature_id, &feature_state).await?;
+        apply_wip_policy(&policy, violation)?;
+
         // Update feature in database
         wsb::entities::crud::features::update_state(&pool, feature_id, feature_state).await?;
         
@@ -6107,17 +9649,19 @@ fn validate_state_transition(feature_id: &str, new_state: &str) -> Result<()> {
     let features_content = std::fs::read_to_string(&features_path)?;
     
     // Find current state
+    let mut known_ids = Vec::new();
     for line in features_content.lines() {
-        if line.starts_with(&format!("| {}", feature_id)) {
+        if line.starts_with("| F") {
             let parts: Vec<&str> = line.split(" | ").collect();
-            if parts.len() >= 4 {
-                let current_state = parts[3];
-                return validate_transition(current_state, new_state);
+            let id = parts[0].trim_start_matches("| ").trim();
+            if id == feature_id && parts.len() >= 4 {
+                return validate_transition(parts[3], new_state);
             }
+            known_ids.push(id);
         }
     }
     
-    Err(anyhow::anyhow!("Feature not found"))
+    Err(id_not_found("Feature", feature_id, known_ids))
 }
 
 fn validate_transition(current: &str, new: &str) -> Result<()> {
@@ -6425,6 +9969,7 @@ fn handle_list_features_api(payload: Option<String>) -> Result<()> {
         
         // Get all features from database (using list_by_project with default project)
         let all_features = wsb::entities::crud::features::list_by_project(&pool, "P001").await?;
+        let bodies = wsb::entities::bodies::all(&pool).await?;
         
         // Apply filters and convert to JSON
         let mut filtered_features = Vec::new();
@@ -6477,6 +10022,7 @@ fn handle_list_features_api(payload: Option<String>) -> Result<()> {
                     "id": feature.id,
                     "name": feature.name,
                     "description": feature.description,
+                    "body": bodies.get(&feature.id),
                     "state": state_str,
                     "category": category_str,
                     "created_at": feature.created_at.to_rfc3339(),
@@ -7976,34 +11522,84 @@ fn link_entities(from_entity: String, from_type: String, to_entity: String, to_t
         let entity_manager = wsb::entities::EntityManager::new(pool.clone());
         
         // Get current project
-        let project = entity_manager.get_current_project().await?;
+        let project = entity_manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project"))?;
         
         // Parse entity types
         let from_entity_type = parse_entity_type(&from_type)?;
         let to_entity_type = parse_entity_type(&to_type)?;
+        require_entity_id(&pool, &from_entity_type, &from_entity).await?;
+        require_entity_id(&pool, &to_entity_type, &to_entity).await?;
         
-        // Create the relationship
-        // TODO: Implement dependency creation when needed
-        println!("Dependency creation not implemented in new schema");
+        // Create the relationship (validated against the typed relationship schema)
+        let dependency = wsb::entities::crud::dependencies::create(
+            &pool,
+            &project.id,
+            &from_entity,
+            from_entity_type,
+            &to_entity,
+            to_entity_type,
+            &relationship_type,
+            description,
+        ).await?;
+
+        println!("{} Linked {} {} --{}--> {} {} ({})", "🔗".green(), from_type, from_entity, relationship_type, to_type, to_entity, dependency.id);
         Ok(())
     })
 }
 
-fn list_entity_relationships(entity_id: String, entity_type: String, _relationship_type: Option<String>, _include_resolved: bool) -> Result<()> {
+fn list_entity_relationships(entity_id: String, entity_type: String, relationship_type: Option<String>, include_resolved: bool) -> Result<()> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
         let db_path = get_project_root()?.join(".wsb/project.db");
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
         
-        // Get relationships for this entity
-        // TODO: Implement relationship listing when needed
-        println!("Relationship listing not implemented in new schema");
-        println!("{} Relationships for {} {}", "🔗".cyan(), entity_type, entity_id);
+        let entity_type = parse_entity_type(&entity_type)?;
+        let relationships = wsb::entities::crud::dependencies::list_for_entity(&pool, &entity_id, entity_type.as_str()).await?;
+
+        println!("{} Relationships for {} {}", "🔗".cyan(), entity_type.as_str(), entity_id);
+        let mut shown = 0;
+        for dep in relationships {
+            if let Some(ref filter) = relationship_type {
+                if &dep.dependency_type != filter {
+                    continue;
+                }
+            }
+            if dep.resolved_at.is_some() && !include_resolved {
+                continue;
+            }
+            let resolved = if dep.resolved_at.is_some() { " (resolved)" } else { "" };
+            println!("  {} {} {} --{}--> {} {}{}", dep.id.dimmed(), dep.from_entity_type, dep.from_entity_id, dep.dependency_type, dep.to_entity_type, dep.to_entity_id, resolved);
+            shown += 1;
+        }
+        if shown == 0 {
+            println!("  No relationships found");
+        }
         
         Ok(())
     })
 }
 
+fn show_relationship_types(format: String) -> Result<()> {
+    let rules = wsb::entities::relationships::relationship_rules();
+
+    if format == "json" {
+        let matrix: Vec<_> = rules.iter().map(|rule| serde_json::json!({
+            "from": rule.from.as_str(),
+            "to": rule.to.as_str(),
+            "types": rule.kinds,
+        })).collect();
+        println!("{}", serde_json::to_string_pretty(&matrix)?);
+    } else {
+        println!("{} Allowed relationship types", "🔗".cyan());
+        for rule in rules {
+            println!("  {:<10} → {:<10} {}", rule.from.as_str(), rule.to.as_str(), rule.kinds.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
 fn unlink_entities(dependency_id: String, force: bool) -> Result<()> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
@@ -8042,8 +11638,7 @@ fn resolve_entity_relationship(dependency_id: String, description: Option<String
         let db_path = get_project_root()?.join(".wsb/project.db");
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
         
-        // TODO: Implement dependency resolution when needed
-        println!("Dependency resolution not implemented in new schema");
+        wsb::entities::crud::dependencies::resolve(&pool, &dependency_id).await?;
         
         println!("{} Resolved relationship {}", "✅".green(), dependency_id);
         if let Some(desc) = description {
@@ -8089,6 +11684,100 @@ fn show_relationship_stats(detailed: bool, format: String) -> Result<()> {
     })
 }
 
+fn export_relationship_graph(format: String, entity: Option<String>, depth: usize, output: Option<PathBuf>) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let graph = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = wsb::entities::workspace::current_project(&pool).await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        wsb::entities::graph::load(&pool, &project.id).await
+    })?;
+    let graph = match entity {
+        Some(ref entity) => graph.neighbourhood(entity, depth)?,
+        None => graph,
+    };
+    
+    let rendered = match format.as_str() {
+        "dot" => graph.to_dot("entities"),
+        "mermaid" => graph.to_mermaid(),
+        "json" => serde_json::to_string_pretty(&graph)? + "\n",
+        other => anyhow::bail!("Unknown graph format '{}' (expected dot, mermaid or json)", other),
+    };
+    
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("{} Wrote {} entities and {} links to {}", "✅".green(), graph.nodes.len(), graph.edges.len(), path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+fn show_relationship_impact(entity_id: String, depth: usize, format: String) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let graph = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = wsb::entities::workspace::current_project(&pool).await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        wsb::entities::graph::load(&pool, &project.id).await
+    })?;
+    let report = graph.impact(&entity_id, depth)?;
+    
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    
+    println!("{} Impact of changing or blocking {} {} ({})", "💥".red(), report.root.id.bold(), report.root.label, report.root.entity_type);
+    let sections = [
+        ("Features blocked", &report.features_blocked),
+        ("Tasks stalled", &report.tasks_stalled),
+        ("Milestones at risk", &report.milestones_at_risk),
+        ("Other affected", &report.other),
+    ];
+    for (title, impacts) in sections {
+        if impacts.is_empty() {
+            continue;
+        }
+        println!("\n{} ({})", title.bold(), impacts.len());
+        for impact in impacts {
+            println!("  {} {} [{}] via {}, depth {}",
+                impact.node.id.bold(),
+                impact.node.label,
+                impact.node.state.as_deref().unwrap_or("-").cyan(),
+                impact.via,
+                impact.depth
+            );
+            if impact.depth > 1 {
+                println!("      {}", impact.path.join(" → ").dimmed());
+            }
+        }
+    }
+    
+    if report.total() == 0 {
+        println!("Nothing waits on {} within {} links", report.root.id, report.max_depth);
+    } else {
+        println!("\n{} entities affected within {} links", report.total(), report.max_depth);
+    }
+    Ok(())
+}
+
+/// Check a relationship endpoint exists; directives live in internal/directives.md, not the database
+async fn require_entity_id(pool: &sqlx::SqlitePool, entity_type: &wsb::entities::EntityType, id: &str) -> Result<()> {
+    use wsb::entities::EntityType;
+    match entity_type {
+        EntityType::Project => require_id(pool, "Project", id, "SELECT id FROM projects WHERE deleted_at IS NULL").await,
+        EntityType::Feature => require_id(pool, "Feature", id, LIVE_FEATURE_IDS).await,
+        EntityType::Task => require_id(pool, "Task", id, LIVE_TASK_IDS).await,
+        EntityType::Session => require_id(pool, "Session", id, "SELECT id FROM sessions").await,
+        EntityType::Directive => Ok(()),
+    }
+}
+
 fn parse_entity_type(type_str: &str) -> Result<wsb::entities::EntityType> {
     match type_str.to_lowercase().as_str() {
         "project" => Ok(wsb::entities::EntityType::Project),
@@ -8103,15 +11792,18 @@ fn parse_entity_type(type_str: &str) -> Result<wsb::entities::EntityType> {
 
 fn run_note_command(action: NoteAction) -> Result<()> {
     match action {
-        NoteAction::Add { entity_type, entity_id, title, content, note_type, tags } => {
-            add_entity_note(entity_type, entity_id, title, content, note_type, tags)?;
+        NoteAction::Add { entity_type, entity_id, title, content, note_type, tags, attach } => {
+            add_entity_note(Some((entity_type, entity_id)), title, content, note_type, tags, attach)?;
         }
-        NoteAction::AddProject { title, content, note_type, tags } => {
-            add_project_note(title, content, note_type, tags)?;
+        NoteAction::AddProject { title, content, note_type, tags, attach } => {
+            add_entity_note(None, title, content, note_type, tags, attach)?;
         }
         NoteAction::List { entity_type, entity_id, note_type, project_wide, pinned } => {
             list_notes(entity_type, entity_id, note_type, project_wide, pinned)?;
         }
+        NoteAction::Show { note_id, format } => {
+            show_note(&note_id, &format)?;
+        }
         NoteAction::Search { query, note_type, format } => {
             search_notes(query, note_type, format)?;
         }
@@ -8137,35 +11829,91 @@ fn run_note_command(action: NoteAction) -> Result<()> {
     Ok(())
 }
 
-fn add_entity_note(entity_type: String, entity_id: String, title: String, content: String, note_type: String, _tags: Option<String>) -> Result<()> {
+fn add_entity_note(entity: Option<(String, String)>, title: String, content: String, note_type: String, tags: Option<String>, attach: Vec<PathBuf>) -> Result<()> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
-        let db_path = get_project_root()?.join(".wsb/project.db");
+        let project_root = get_project_root()?;
+        let db_path = project_root.join(".wsb/project.db");
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
-        let _entity_manager = wsb::entities::EntityManager::new(pool.clone());
+        let entity_manager = wsb::entities::EntityManager::new(pool.clone());
 
-        let entity_type_enum = parse_entity_type(&entity_type)?;
-        let note_type_enum = parse_note_type(&note_type)?;
+        let project = entity_manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        let entity = match entity {
+            Some((entity_type, entity_id)) => Some((parse_entity_type(&entity_type)?.as_str().to_string(), entity_id)),
+            None => None,
+        };
+        let note = wsb::entities::notes::NewNote {
+            project_id: project.id,
+            entity,
+            note_type: parse_note_type(&note_type)?,
+            title,
+            content,
+            tags,
+        };
+        let note = entity_manager.create_note(note, &project_root, &attach).await?;
 
-        // TODO: Implement note creation when needed
-        println!("Note creation not implemented in new schema");
+        let target = match (&note.entity_type, &note.entity_id) {
+            (Some(entity_type), Some(entity_id)) => format!("{} {}", entity_type, entity_id),
+            _ => "the project".to_string(),
+        };
+        println!("{} Added note {} to {}", "✅".green(), note.id.bold(), target);
+        for attachment in &note.attachments {
+            println!("  {} {} ({} bytes, sha256 {})", "📎".cyan(), attachment.path, attachment.size_bytes, &attachment.sha256[..12]);
+        }
         Ok(())
     })
 }
 
-fn add_project_note(title: String, content: String, note_type: String, _tags: Option<String>) -> Result<()> {
+fn show_note(note_id: &str, format: &str) -> Result<()> {
+    use wsb::entities::notes::AttachmentState;
+
+    let project_root = get_project_root()?;
+    let db_path = project_root.join(".wsb/project.db");
     let rt = tokio::runtime::Runtime::new()?;
-    rt.block_on(async {
-        let db_path = get_project_root()?.join(".wsb/project.db");
+    let note = rt.block_on(async {
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
-        let entity_manager = wsb::entities::EntityManager::new(pool.clone());
+        require_id(&pool, "Note", note_id, "SELECT id FROM notes").await?;
+        wsb::entities::EntityManager::new(pool).get_note(note_id).await
+    })?
+    .ok_or_else(|| anyhow::anyhow!("Note not found: {}", note_id))?;
+    let states: Vec<AttachmentState> = note.attachments.iter().map(|attachment| attachment.verify(&project_root)).collect();
+
+    if format == "json" {
+        let mut value = serde_json::to_value(&note)?;
+        for (attachment, state) in value["attachments"].as_array_mut().into_iter().flatten().zip(&states) {
+            attachment["state"] = serde_json::json!(state);
+        }
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
 
-        let project = entity_manager.get_current_project().await?;
+    println!("{} {} {}", "📝".cyan(), note.id.bold(), note.title);
+    match (&note.entity_type, &note.entity_id) {
+        (Some(entity_type), Some(entity_id)) => println!("  On: {} {}", entity_type, entity_id),
+        _ => println!("  On: project-wide"),
+    }
+    println!("  Type: {}", note.note_type);
+    if let Some(tags) = &note.tags {
+        println!("  Tags: {}", tags);
+    }
+    println!("  Created: {}{}", note.created_at, note.author.as_ref().map(|author| format!(" by {}", author)).unwrap_or_default());
+    println!();
+    println!("{}", note.content);
 
-        // TODO: Implement project note creation when needed
-        println!("Project note creation not implemented in new schema");
-        Ok(())
-    })
+    if !note.attachments.is_empty() {
+        println!();
+        println!("{}", "Attachments:".bold());
+        for (attachment, state) in note.attachments.iter().zip(&states) {
+            let marker = match state {
+                AttachmentState::Intact => "✓ intact".green(),
+                AttachmentState::Modified => "⚠ modified".yellow(),
+                AttachmentState::Missing => "✗ missing".red(),
+            };
+            println!("  {} {} ({} bytes) {}", "📎".cyan(), attachment.path, attachment.size_bytes, marker);
+        }
+    }
+    Ok(())
 }
 
 fn list_notes(_entity_type: Option<String>, entity_id: Option<String>, _note_type: Option<String>, project_wide: bool, _pinned: bool) -> Result<()> {
@@ -8310,7 +12058,10 @@ fn link_note_to_target(source_note_id: String, target_id: String, target_type: S
 
 fn run_database_command(action: DatabaseAction) -> Result<()> {
     match action {
-        DatabaseAction::Backup { backup_dir, compress, max_backups } => {
+        DatabaseAction::Backup { output: Some(output), .. } => {
+            backup_database_to(output)?;
+        }
+        DatabaseAction::Backup { output: None, backup_dir, compress, max_backups } => {
             create_database_backup(backup_dir, compress, max_backups)?;
         }
         DatabaseAction::List { backup_dir, format } => {
@@ -8325,6 +12076,67 @@ fn run_database_command(action: DatabaseAction) -> Result<()> {
         DatabaseAction::Health { performance } => {
             check_database_health(performance)?;
         }
+        DatabaseAction::Migrate { status, format } => {
+            migrate_database(status, format)?;
+        }
+        DatabaseAction::Check { format } => {
+            check_database_integrity(format)?;
+        }
+        DatabaseAction::Bench { features, tasks_per_feature, iterations, save_baseline, threshold, fail_on_regression, format } => {
+            let scale = wsb::entities::bench::BenchScale { features, tasks_per_feature, iterations };
+            run_database_bench(scale, save_baseline, threshold, fail_on_regression, format)?;
+        }
+        DatabaseAction::Prune { apply, format } => {
+            prune_database(apply, format)?;
+        }
+    }
+    Ok(())
+}
+
+fn prune_database(apply: bool, format: String) -> Result<()> {
+    use wsb::entities::retention::{self, RetentionConfig};
+    use colored::*;
+
+    let project_root = get_project_root()?;
+    let config = WorkspaceState::load(&project_root)
+        .ok()
+        .and_then(|state| state.get_tool_config::<RetentionConfig>("retention"))
+        .unwrap_or_default();
+    if config.tables.is_empty() {
+        let known: Vec<&str> = retention::TABLES.iter().map(|t| t.name).collect();
+        println!("No retention policies configured. Add them under tools.retention in .wsb/state.json, e.g.");
+        println!("  {{\"tables\": {{\"audit\": {{\"max_age_days\": 180}}, \"sessions\": {{\"max_age_days\": 365, \"action\": \"scrub\"}}}}}}");
+        println!("Tables: {}", known.join(", "));
+        return Ok(());
+    }
+    if apply {
+        wsb::read_only::ensure_writable("prune the database")?;
+    }
+
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let outcomes = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        retention::prune(&pool, &config, apply).await
+    })?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "applied": apply, "tables": outcomes }))?);
+        return Ok(());
+    }
+
+    let verb = |action: retention::RetentionAction| match (action, apply) {
+        (retention::RetentionAction::Delete, true) => "deleted",
+        (retention::RetentionAction::Delete, false) => "to delete",
+        (retention::RetentionAction::Scrub, true) => "scrubbed",
+        (retention::RetentionAction::Scrub, false) => "to scrub",
+    };
+    for outcome in &outcomes {
+        println!("  {:<12} {:<28} older than {:>4} days: {} {}",
+                 outcome.name.bold(), outcome.table.dimmed(), outcome.max_age_days, outcome.rows, verb(outcome.action));
+    }
+    if !apply && outcomes.iter().any(|o| o.rows > 0) {
+        println!("{} Dry run; pass --apply to prune", "💡".yellow());
     }
     Ok(())
 }
@@ -8366,6 +12178,100 @@ fn create_database_backup(backup_dir: Option<String>, compress: bool, max_backup
     })
 }
 
+fn backup_database_to(output: PathBuf) -> Result<()> {
+    use colored::*;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    if !db_path.exists() {
+        anyhow::bail!("No project database found at {}", db_path.display());
+    }
+    let rt = tokio::runtime::Runtime::new()?;
+    let backup = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        wsb::entities::maintenance::backup_to(&pool, &output).await
+    })?;
+
+    println!("{} Database backed up to {}", "✅".green(), backup.path.display());
+    println!("  Size: {} bytes", backup.size_bytes);
+    println!("  Checksum: {}", &backup.sha256[..16]);
+    Ok(())
+}
+
+fn migrate_database(status_only: bool, format: String) -> Result<()> {
+    use wsb::entities::migrations;
+    use colored::*;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let (applied, statuses) = rt.block_on(async {
+        // Opening for writing applies pending migrations; read-only opens (--status) do not
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let applied = if status_only { Vec::new() } else { migrations::migrate(&pool).await? };
+        Ok::<_, anyhow::Error>((applied, migrations::status(&pool).await?))
+    })?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&statuses)?);
+        return Ok(());
+    }
+    for status in &statuses {
+        let state = match &status.applied_at {
+            Some(at) => format!("applied {}", at).green(),
+            None => "pending".yellow(),
+        };
+        println!("  {:>3} {:<28} {}", status.version, status.name.bold(), state);
+        println!("      {}", status.description.dimmed());
+    }
+    let pending = statuses.iter().filter(|status| status.applied_at.is_none()).count();
+    if pending > 0 {
+        println!("{} {} pending migration(s); run `wsb db migrate` to apply them", "💡".yellow(), pending);
+    } else if applied.is_empty() {
+        println!("{} Schema is up to date (version {})", "✅".green(), migrations::latest_version());
+    } else {
+        println!("{} Applied {} migration(s)", "✅".green(), applied.len());
+    }
+    Ok(())
+}
+
+fn check_database_integrity(format: String) -> Result<()> {
+    use colored::*;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let report = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        wsb::entities::maintenance::check(&pool).await
+    })?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        if report.integrity.is_empty() {
+            println!("{} Integrity check passed", "✅".green());
+        } else {
+            println!("{} Integrity check found {} problem(s):", "❌".red(), report.integrity.len());
+            for problem in &report.integrity {
+                println!("  {}", problem);
+            }
+            println!("  {} Restore a backup with `wsb db restore <file>`", "💡".yellow());
+        }
+        if report.orphans.is_empty() {
+            println!("{} No orphaned references", "✅".green());
+        } else {
+            println!("{} {} orphaned reference(s):", "⚠️".yellow(), report.orphans.len());
+            for orphan in &report.orphans {
+                println!("  {} row {}: {} = {} has no match in {}", orphan.table.bold(), orphan.rowid, orphan.column,
+                         orphan.value.as_deref().unwrap_or("NULL"), orphan.parent);
+                println!("    {} {}", "repair:".dimmed(), orphan.suggestion);
+            }
+        }
+    }
+    if !report.is_healthy() {
+        anyhow::bail!("Database check found problems");
+    }
+    Ok(())
+}
+
 fn list_database_backups(backup_dir: Option<String>, format: String) -> Result<()> {
     use wsb::entities::database::{BackupConfig, list_backups};
     use colored::*;
@@ -8416,6 +12322,34 @@ fn restore_database_backup(backup_id: String, target: Option<String>, force: boo
     
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
+        let target_path = match &target {
+            Some(target) => PathBuf::from(target),
+            None => get_project_root()?.join(".wsb/project.db"),
+        };
+
+        // A plain file is restored directly, without backup metadata
+        let source = PathBuf::from(&backup_id);
+        if source.is_file() {
+            if target_path.exists() && !force {
+                print!("{} Replace the database at {} with {}? (y/N): ",
+                       "⚠️".yellow(), target_path.display(), source.display());
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if !input.trim().to_lowercase().starts_with('y') {
+                    println!("{} Restore cancelled", "❌".red());
+                    return Ok(());
+                }
+            }
+            let report = wsb::entities::maintenance::restore_from(&source, &target_path).await?;
+            println!("{} Database restored from {}", "✅".green(), report.source.display());
+            if let Some(previous) = report.previous {
+                println!("  Previous database kept at {}", previous.display());
+            }
+            return Ok(());
+        }
+
         let config = BackupConfig::default();
         let backups = list_backups(&config).await?;
         
@@ -8432,12 +12366,6 @@ fn restore_database_backup(backup_id: String, target: Option<String>, force: boo
             anyhow::anyhow!("Backup not found: {}", backup_id)
         })?;
         
-        let target_path = if let Some(target) = target {
-            PathBuf::from(target)
-        } else {
-            get_project_root()?.join(".wsb/project.db")
-        };
-        
         if target_path.exists() && !force {
             print!("{} Database exists at {}. Overwrite? (y/N): ", 
                    "⚠️".yellow(), target_path.display());
@@ -8550,6 +12478,83 @@ fn check_database_health(performance: bool) -> Result<()> {
     })
 }
 
+fn run_database_bench(scale: wsb::entities::bench::BenchScale, save_baseline: bool, threshold: f64, fail_on_regression: bool, format: String) -> Result<()> {
+    use wsb::entities::bench::{self, BenchStore};
+    use colored::*;
+
+    // Benchmarks run against a scratch database so the project's data is never touched
+    let scratch_path = std::env::temp_dir().join(format!("wsb-bench-{}.db", uuid::Uuid::new_v4()));
+    let rt = tokio::runtime::Runtime::new()?;
+    let result = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&scratch_path).await?;
+        let manager = wsb::entities::EntityManager::new(pool.clone());
+        if format != "json" {
+            println!("{} Benchmarking {} features / {} tasks ({} iterations)...",
+                     "⏳".yellow(), scale.features, scale.total_tasks(), scale.iterations);
+        }
+        let report = bench::run(&manager, scale).await;
+        pool.close().await;
+        report
+    });
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{}", scratch_path.display(), suffix));
+    }
+    let report = result?;
+
+    let store = BenchStore::new(&get_project_root()?.join(".wsb"));
+    let baseline = store.baseline_for(&report.scale)?;
+    let comparisons = baseline.as_ref().map(|b| bench::compare(b, &report, threshold)).unwrap_or_default();
+    let regressions: Vec<_> = comparisons.iter().filter(|c| c.regression).collect();
+
+    store.append_history(&report)?;
+    if save_baseline {
+        store.save_baseline(&report)?;
+    }
+
+    match format.as_str() {
+        "json" => {
+            let output = serde_json::json!({
+                "report": report,
+                "baseline": baseline.as_ref().map(|b| &b.created_at),
+                "comparison": comparisons,
+                "regressions": regressions.len(),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        _ => {
+            println!("{} Database Benchmark ({})", "📊".blue(), report.scale.label());
+            println!();
+            println!("  {:<22} {:>10} {:>10} {:>10} {:>12}", "Operation".bold(), "Mean ms".bold(), "p95 ms".bold(), "Max ms".bold(), "vs baseline".bold());
+            for timing in &report.timings {
+                let change = match comparisons.iter().find(|c| c.operation == timing.operation) {
+                    Some(c) if c.regression => format!("{:+.1}%", c.change_percent).red(),
+                    Some(c) => format!("{:+.1}%", c.change_percent).green(),
+                    None => "-".normal(),
+                };
+                println!("  {:<22} {:>10.3} {:>10.3} {:>10.3} {:>12}", timing.operation, timing.mean_ms, timing.p95_ms, timing.max_ms, change);
+            }
+            println!();
+
+            match &baseline {
+                Some(b) => println!("  {} {} ({})", "Baseline:".bold(), b.created_at, b.version),
+                None => println!("  {} none recorded for this scale (use --save-baseline)", "Baseline:".bold()),
+            }
+            if save_baseline {
+                println!("{} Saved as baseline for {}", "✅".green(), report.scale.label());
+            }
+            if !regressions.is_empty() {
+                println!("{} {} operation(s) slower than baseline by more than {}%", "⚠️".yellow(), regressions.len(), threshold);
+            }
+        }
+    }
+
+    if fail_on_regression && !regressions.is_empty() {
+        anyhow::bail!("{} operation(s) regressed beyond {}%", regressions.len(), threshold);
+    }
+
+    Ok(())
+}
+
 fn run_continuity_command(action: ContinuityAction) -> Result<()> {
     match action {
         ContinuityAction::Save { session_id, focus, notes } => {
@@ -9008,8 +13013,11 @@ fn list_note_links(id: String, incoming: bool, outgoing: bool, format: String) -
 }
 
 fn parse_note_type(type_str: &str) -> Result<String> {
-    // TODO: Implement proper note type parsing when needed
-    Ok(type_str.to_string())
+    let note_type = type_str.trim().to_lowercase().replace('-', "_");
+    if !wsb::entities::notes::NOTE_TYPES.contains(&note_type.as_str()) {
+        anyhow::bail!("Unknown note type: {} (expected one of: {})", type_str, wsb::entities::notes::NOTE_TYPES.join(", "));
+    }
+    Ok(note_type)
 }
 
 // ============================================================================
@@ -9784,6 +13792,18 @@ fn run_version_command(action: VersionAction) -> Result<()> {
         VersionAction::Major { version } => {
             handle_version_major(version)
         }
+        VersionAction::Minor { version } => {
+            handle_version_minor(version)
+        }
+        VersionAction::Scheme { scheme } => {
+            handle_version_scheme(scheme)
+        }
+        VersionAction::Bump { part, pre, tag, message } => {
+            handle_version_bump(&part, pre, tag, message)
+        }
+        VersionAction::Packages { mode, set } => {
+            handle_version_packages(mode, set)
+        }
         VersionAction::Tag { prefix, message } => {
             handle_version_tag(prefix, message)
         }
@@ -9804,15 +13824,16 @@ fn handle_version_show(verbose: bool, format: String) -> Result<()> {
         let project = entity_manager.get_current_project().await?;
         let major_version = get_project_major_version(&pool).await?;
         
-        // Calculate version using new system
-        let version_info = wsb::st8::VersionInfo::calculate_with_major(major_version)?;
+        // Calculate version with the project's scheme
+        let (scheme, version_info) = calculate_project_version(&pool).await?;
         
         match format.as_str() {
             "json" => {
-                let json_output = if verbose {
+                let json_output = if verbose && scheme == VersionScheme::CommitCount {
                     let calc_info = wsb::st8::VersionInfo::get_calculation_info(major_version)?;
                     serde_json::json!({
                         "version": version_info.full_version,
+                        "scheme": scheme.name(),
                         "major": major_version,
                         "minor": version_info.minor_version,
                         "patch": version_info.patch_version,
@@ -9827,7 +13848,8 @@ fn handle_version_show(verbose: bool, format: String) -> Result<()> {
                 } else {
                     serde_json::json!({
                         "version": version_info.full_version,
-                        "major": major_version,
+                        "scheme": scheme.name(),
+                        "major": version_info.major_version.trim_start_matches('v'),
                         "minor": version_info.minor_version,
                         "patch": version_info.patch_version,
                         "project": project.map(|p| p.name.clone()).unwrap_or_else(|| "Unknown".to_string())
@@ -9839,8 +13861,11 @@ fn handle_version_show(verbose: bool, format: String) -> Result<()> {
                 println!("{} {}", "Version:".blue().bold(), version_info.full_version.green().bold());
                 let project_name = project.map(|p| p.name.clone()).unwrap_or_else(|| "Unknown".to_string());
                 println!("{} {}", "Project:".blue(), project_name);
+                println!("{} {}", "Scheme:".blue(), scheme);
                 
-                if verbose {
+                if verbose && scheme != VersionScheme::CommitCount {
+                    println!("\n{} {}", "Calculation:".blue().bold(), scheme.description());
+                } else if verbose {
                     let calc_info = wsb::st8::VersionInfo::get_calculation_info(major_version)?;
                     println!("\n{}", "Calculation Breakdown:".blue().bold());
                     println!("  {} {}", "Major (DB):".blue(), major_version.to_string().yellow());
@@ -9868,14 +13893,18 @@ fn handle_version_major(version: u32) -> Result<()> {
         let db_path = get_project_root()?.join(".wsb/project.db");
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
         
+        let project_id = current_project_id(&pool).await?;
+
         // Update major version in database
-        sqlx::query("UPDATE projects SET major_version = ?, updated_at = datetime('now') WHERE id = (SELECT id FROM projects LIMIT 1)")
+        sqlx::query("UPDATE projects SET major_version = ?, updated_at = ? WHERE id = ?")
             .bind(version)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&project_id)
             .execute(&pool)
             .await?;
         
         // Calculate new version
-        let version_info = wsb::st8::VersionInfo::calculate_with_major(version)?;
+        let (_, version_info) = calculate_project_version(&pool).await?;
         
         log::info!("Major version set to {}, new version: {}", version, version_info.full_version);
         println!("{} Major version set to {}", "✅".green(), version.to_string().green().bold());
@@ -9894,32 +13923,88 @@ fn handle_version_tag(prefix: String, message: Option<String>) -> Result<()> {
         let db_path = get_project_root()?.join(".wsb/project.db");
         let pool = wsb::entities::database::initialize_database(&db_path).await?;
         
-        let major_version = get_project_major_version(&pool).await?;
-        let version_info = wsb::st8::VersionInfo::calculate_with_major(major_version)?;
-        
-        let tag_name = format!("{}{}", prefix, version_info.full_version);
-        let tag_message = message.unwrap_or_else(|| format!("Release version {}", version_info.full_version));
-        
-        // Create git tag
-        let output = Command::new("git")
-            .args(["tag", "-a", &tag_name, "-m", &tag_message])
-            .output()
-            .context("Failed to create git tag")?;
-        
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to create git tag: {}", stderr);
-        }
-        
-        log::info!("Created git tag: {}", tag_name);
-        println!("{} Created git tag: {}", "✅".green(), tag_name.green().bold());
-        println!("{} Message: {}", "📝".blue(), tag_message);
-        println!("\n{} To push tag to remote: git push origin {}", "💡".yellow(), tag_name);
-        
-        anyhow::Ok(())
+        let (_, version_info) = calculate_project_version(&pool).await?;
+        create_release_tag(&prefix, &version_info.full_version, message)
     })
 }
 
+/// Create an annotated `{prefix}{version}` tag on HEAD
+fn create_release_tag(prefix: &str, version: &str, message: Option<String>) -> Result<()> {
+    let tag_name = format!("{}{}", prefix, version);
+    let tag_message = message.unwrap_or_else(|| format!("Release version {}", version));
+    
+    // Create git tag
+    let output = Command::new("git")
+        .args(["tag", "-a", &tag_name, "-m", &tag_message])
+        .output()
+        .context("Failed to create git tag")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to create git tag: {}", stderr);
+    }
+    
+    log::info!("Created git tag: {}", tag_name);
+    println!("{} Created git tag: {}", "✅".green(), tag_name.green().bold());
+    println!("{} Message: {}", "📝".blue(), tag_message);
+    println!("\n{} To push tag to remote: git push origin {}", "💡".yellow(), tag_name);
+    Ok(())
+}
+
+fn handle_version_bump(part: &str, pre: Option<String>, tag: bool, message: Option<String>) -> Result<()> {
+    use wsb::st8::schemes::{self, BumpPart};
+
+    let part: BumpPart = part.parse()?;
+    let project_root = get_project_root()?;
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let (scheme, version_info, needs_tag) = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let (scheme, base) = get_project_version_settings(&pool).await?;
+        let current = schemes::calculate(scheme, &base)?;
+        let head = wsb::st8::version_guard::head_commit();
+        let (next, needs_tag) = schemes::bump(scheme, &base, &current, part, pre, head)?;
+
+        let project_id = current_project_id(&pool).await?;
+        sqlx::query("UPDATE projects SET major_version = ?, minor_version = ?, patch_version = ?, pre_release = ?, version_base_commit = ?, updated_at = ? WHERE id = ?")
+            .bind(next.major)
+            .bind(next.minor)
+            .bind(next.patch)
+            .bind(&next.pre_release)
+            .bind(&next.base_commit)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&project_id)
+            .execute(&pool)
+            .await?;
+
+        // The tag scheme cannot see the new base until it is tagged, so write it as given
+        let version_info = if needs_tag {
+            let release = format!("{}.{}.{}", next.major, next.minor, next.patch);
+            let full = match &next.pre_release {
+                Some(pre) => format!("{}-{}", release, pre),
+                None => release,
+            };
+            VersionInfo::from_version(&full)?
+        } else {
+            schemes::calculate(scheme, &next)?
+        };
+        log::info!("Bumped version from {} to {}", current.full_version, version_info.full_version);
+        println!("{} Bumped version: {} → {}", "✅".green(), current.full_version, version_info.full_version.green().bold());
+        anyhow::Ok((scheme, version_info, needs_tag))
+    })?;
+
+    // Rewrite the version file and templates with exactly the bumped version
+    update_state(false, false, Some(&version_info.full_version), false, None)?;
+
+    if tag || needs_tag {
+        if !tag {
+            println!("{} The {} scheme reads versions from tags; tagging the bump", "Note:".blue(), scheme);
+        }
+        create_release_tag("v", &version_info.full_version, message)?;
+    }
+    Ok(())
+}
+
 fn handle_version_info(include_history: bool) -> Result<()> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
@@ -9991,12 +14076,155 @@ fn handle_version_info(include_history: bool) -> Result<()> {
     })
 }
 
-async fn get_project_major_version(pool: &SqlitePool) -> Result<u32> {
-    let row = sqlx::query("SELECT major_version FROM projects LIMIT 1")
+fn handle_version_minor(version: u32) -> Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let db_path = get_project_root()?.join(".wsb/project.db");
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+
+        let project_id = current_project_id(&pool).await?;
+
+        // A hand-set minor starts over from the tags rather than an earlier bump
+        sqlx::query("UPDATE projects SET minor_version = ?, patch_version = 0, version_base_commit = NULL, updated_at = ? WHERE id = ?")
+            .bind(version)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&project_id)
+            .execute(&pool)
+            .await?;
+
+        let (scheme, version_info) = calculate_project_version(&pool).await?;
+        log::info!("Minor version set to {}, new version: {}", version, version_info.full_version);
+        println!("{} Minor version set to {}", "✅".green(), version.to_string().green().bold());
+        println!("{} New version: {}", "🔢".blue(), version_info.full_version.green().bold());
+        if scheme != VersionScheme::Semver {
+            println!("\n{} The {} scheme does not use the stored minor; select semver with 'wsb version scheme semver'", "Note:".blue(), scheme);
+        }
+
+        anyhow::Ok(())
+    })
+}
+
+fn handle_version_packages(mode: Option<String>, set: Option<String>) -> Result<()> {
+    use wsb::st8::packages::{self, PackageBase};
+
+    let project_root = get_project_root()?;
+    let mut config = St8Config::load(&project_root)?;
+    if let Some(mode) = mode {
+        config.package_mode = mode.parse()?;
+        config.save(&project_root)?;
+        log::info!("Package mode set to {}", config.package_mode);
+        println!("{} Package mode set to {}", "✅".green(), config.package_mode.to_string().green().bold());
+    }
+
+    let all = packages::discover(&project_root)?;
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let head = wsb::st8::version_guard::head_commit();
+
+        if let Some(set) = set {
+            let (name, version) = set
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected NAME=VERSION, got '{}'", set))?;
+            let package = packages::find(&all, name)?;
+            let (major, minor, patch) = wsb::st8::version_guard::parse(version)
+                .ok_or_else(|| anyhow::anyhow!("'{}' is not a major.minor.patch version", version))?;
+            packages::set_base(&pool, &package, &PackageBase { major, minor, patch, base_commit: head.clone() }).await?;
+            println!("{} {} base version set to {}", "✅".green(), package.name, version.green().bold());
+            if config.package_mode != PackageMode::Independent {
+                println!("{} Package bases only apply in independent mode ('wsb version packages --mode independent')", "Note:".blue());
+            }
+        }
+
+        if all.is_empty() {
+            println!("{}", "No Cargo or npm workspace members found".yellow());
+            return anyhow::Ok(());
+        }
+
+        println!("{} {}", "Package mode:".blue(), config.package_mode.to_string().green().bold());
+        for package in &all {
+            let version = match (&package.version, config.package_mode) {
+                (None, _) => "inherited".to_string(),
+                (Some(version), PackageMode::Lockstep) => version.clone(),
+                (Some(_), PackageMode::Independent) => {
+                    let base = packages::base(&pool, package, head.as_deref()).await?;
+                    packages::independent_version(&project_root, package, &base)?.full_version
+                }
+            };
+            println!("  {} {} {}", format!("{:<24}", package.name).cyan(), format!("{:<12}", version).green(), package.dir.display());
+        }
+        anyhow::Ok(())
+    })
+}
+
+fn handle_version_scheme(scheme: Option<String>) -> Result<()> {
+    let project_root = get_project_root()?;
+    let mut config = St8Config::load(&project_root)?;
+
+    if let Some(scheme) = scheme {
+        config.version_scheme = scheme.parse()?;
+        config.save(&project_root)?;
+        log::info!("Version scheme set to {}", config.version_scheme);
+        println!("{} Version scheme set to {}", "✅".green(), config.version_scheme.to_string().green().bold());
+    }
+
+    let (scheme, version_info) = calculate_version_with_scheme(&project_root)?;
+    println!("{} {} ({})", "Scheme:".blue(), scheme.to_string().green().bold(), scheme.description());
+    println!("{} {}", "Version:".blue(), version_info.full_version.green().bold());
+    println!();
+    println!("{}", "Available schemes:".blue());
+    for available in VersionScheme::ALL {
+        let marker = if available == scheme { "*" } else { " " };
+        println!("  {} {:<13} {}", marker, available.name(), available.description());
+    }
+    Ok(())
+}
+
+/// Id of the current project, the one version commands write to
+async fn current_project_id(pool: &SqlitePool) -> Result<String> {
+    let project = wsb::entities::workspace::current_project(pool).await?
+        .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+    Ok(project.id)
+}
+
+/// The current project's row, the one version commands read and write
+async fn current_project_row(pool: &SqlitePool) -> Result<Option<sqlx::sqlite::SqliteRow>> {
+    let Some(project) = wsb::entities::workspace::current_project(pool).await? else {
+        return Ok(None);
+    };
+    // SELECT * so a read-only open of a database without the scheme columns still works
+    Ok(sqlx::query("SELECT * FROM projects WHERE id = ? AND deleted_at IS NULL")
+        .bind(&project.id)
         .fetch_optional(pool)
-        .await?;
-    
-    if let Some(row) = row {
+        .await?)
+}
+
+/// The project's version scheme with the hand-set base version it may use
+async fn get_project_version_settings(pool: &SqlitePool) -> Result<(VersionScheme, wsb::st8::schemes::BaseVersion)> {
+    Ok(match current_project_row(pool).await? {
+        Some(row) => (
+            row.try_get::<String, _>("version_scheme").ok().and_then(|s| s.parse().ok()).unwrap_or_default(),
+            wsb::st8::schemes::BaseVersion {
+                major: row.get::<i64, _>("major_version") as u32,
+                minor: row.try_get::<i64, _>("minor_version").unwrap_or(0) as u32,
+                patch: row.try_get::<i64, _>("patch_version").unwrap_or(0) as u32,
+                pre_release: row.try_get::<Option<String>, _>("pre_release").ok().flatten(),
+                base_commit: row.try_get::<Option<String>, _>("version_base_commit").ok().flatten(),
+            },
+        ),
+        None => (VersionScheme::default(), Default::default()),
+    })
+}
+
+/// Calculate the version with the project's configured scheme
+async fn calculate_project_version(pool: &SqlitePool) -> Result<(VersionScheme, VersionInfo)> {
+    let (scheme, base) = get_project_version_settings(pool).await?;
+    Ok((scheme, wsb::st8::schemes::calculate(scheme, &base)?))
+}
+
+async fn get_project_major_version(pool: &SqlitePool) -> Result<u32> {
+    if let Some(row) = current_project_row(pool).await? {
         Ok(row.get::<i64, _>("major_version") as u32)
     } else {
         // No project found, return default
@@ -10254,9 +14482,1346 @@ fn ensure_test_runner_available(test_cmd: &str, project_files: &[wsb::st8::Proje
     Ok(())
 }
 
+fn run_events_command(action: EventsAction) -> Result<()> {
+    use wsb::entities::events::{self, EventStatus, EventsConfig};
 
+    let project_root = get_project_root()?;
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let db_path = project_root.join(".wsb/project.db");
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
 
+        match action {
+            EventsAction::Dispatch { watch, interval } => {
+                let state = WorkspaceState::load(&project_root)?;
+                let config: EventsConfig = state.get_tool_config("events").unwrap_or_default();
+                if config.sinks.is_empty() {
+                    println!("{} No event sinks configured (set tools.events.sinks in .wsb/state.json)", "⚠️".yellow());
+                    return Ok(());
+                }
 
-
-
-
+                if watch {
+                    println!("{} Dispatching events every {}s to {} sink(s) (Ctrl+C to stop)", "📡".blue(), interval, config.sinks.len());
+                    events::run_dispatcher(pool, config, std::time::Duration::from_secs(interval)).await?;
+                } else {
+                    let report = events::dispatch(&pool, &config).await?;
+                    println!("{} {} delivered, {} scheduled for retry, {} dead-lettered",
+                        "📡".blue(), report.delivered, report.retried, report.dead_lettered);
+                }
+            }
+            EventsAction::List { status, by, limit } => {
+                let status = status.map(|s| EventStatus::from_str(&s)).transpose().map_err(|e| anyhow::anyhow!(e))?;
+                let outbox = events::list(&pool, status, by.as_deref(), limit).await?;
+                if outbox.is_empty() {
+                    println!("No events found");
+                }
+                for event in outbox {
+                    let marker = match event.status.as_str() {
+                        "delivered" => "✅",
+                        "dead" => "💀",
+                        _ => "⏳",
+                    };
+                    let by = event.actor.as_deref().map(|a| format!(" by {}", a)).unwrap_or_default();
+                    println!("{} #{} {} {} {}{} (attempts: {})", marker, event.id, event.event_type.bold(), event.entity_type, event.entity_id, by, event.attempts);
+                    if let Some(ref error) = event.last_error {
+                        println!("    {}", error.dimmed());
+                    }
+                }
+            }
+            EventsAction::Retry { event_id } => {
+                if events::requeue(&pool, event_id).await? {
+                    println!("{} Event #{} requeued", "✅".green(), event_id);
+                } else {
+                    println!("{} Event #{} is not dead-lettered", "⚠️".yellow(), event_id);
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
+
+fn run_actor_command(action: ActorAction) -> Result<()> {
+    use wsb::entities::actors;
+
+    let project_root = get_project_root()?;
+    if let ActorAction::Whoami = action {
+        let actor = actors::current();
+        println!("{} (from {})", actor.name.bold(), actor.source.as_str());
+        return Ok(());
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let db_path = project_root.join(".wsb/project.db");
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+
+        match action {
+            ActorAction::List { format } => {
+                let summaries = actors::list(&pool).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&summaries)?);
+                    return Ok(());
+                }
+                if summaries.is_empty() {
+                    println!("No actors recorded yet");
+                }
+                for actor in summaries {
+                    println!("{} ({}) sessions: {}, tasks completed: {}, events: {}, last seen {}",
+                        actor.name.bold(), actor.source, actor.sessions, actor.tasks_completed, actor.events, actor.last_seen);
+                }
+            }
+            ActorAction::Activity { by, limit, format } => {
+                let entries = actors::activity(&pool, by.as_deref(), limit).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&entries)?);
+                    return Ok(());
+                }
+                if entries.is_empty() {
+                    println!("No attributed activity found");
+                }
+                for entry in entries {
+                    println!("{} {} {} {} {}", entry.at.dimmed(), entry.actor.bold(), entry.kind, entry.entity_type, entry.entity_id);
+                }
+            }
+            ActorAction::Whoami => unreachable!("handled before opening the database"),
+        }
+
+        Ok(())
+    })
+}
+
+fn run_calendar_command(action: CalendarAction) -> Result<()> {
+    use wsb::entities::calendar;
+
+    match action {
+        CalendarAction::Export { out, since, until, per_category } => {
+            let since = since.map(|d| calendar::parse_date(&d)).transpose()?;
+            let until = until.map(|d| calendar::parse_date(&d)).transpose()?;
+            if let (Some(since), Some(until)) = (since, until) {
+                if since > until {
+                    anyhow::bail!("--since {} is after --until {}", since, until);
+                }
+            }
+
+            let db_path = get_project_root()?.join(".wsb/project.db");
+            if !db_path.exists() {
+                anyhow::bail!("No project database found; the calendar is built from .wsb/project.db");
+            }
+            let rt = tokio::runtime::Runtime::new()?;
+            let (project_name, events) = rt.block_on(async {
+                let pool = wsb::entities::database::initialize_database(&db_path).await?;
+                let project = match wsb::entities::workspace::current_project(&pool).await? {
+                    Some(project) => project,
+                    None => anyhow::bail!("No active project found"),
+                };
+                let events = calendar::load_events(&pool, &project.id).await?;
+                Ok::<_, anyhow::Error>((project.name, events))
+            })?;
+            let events: Vec<calendar::CalendarEvent> = events.iter().filter_map(|e| e.within(since, until)).collect();
+
+            let now = chrono::Utc::now();
+            fs::write(&out, calendar::render(&project_name, &events, until, now))
+                .with_context(|| format!("Failed to write {}", out.display()))?;
+            println!("{} Wrote {} events to {}", "📅".blue(), events.len(), out.display().to_string().bold());
+
+            if per_category {
+                let stem = out.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "project".to_string());
+                let mut categories: Vec<&str> = events.iter().map(|e| e.category.as_str()).collect();
+                categories.sort_unstable();
+                categories.dedup();
+                for category in categories {
+                    let selected: Vec<calendar::CalendarEvent> = events.iter().filter(|e| e.category == category).cloned().collect();
+                    let path = out.with_file_name(format!("{}-{}.ics", stem, category));
+                    let name = format!("{} ({})", project_name, category);
+                    fs::write(&path, calendar::render(&name, &selected, until, now))
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("  {} events to {}", selected.len(), path.display());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_state_command(action: StateAction) -> Result<()> {
+    match action {
+        StateAction::Migrate { dry_run, format } => {
+            let root = get_project_root()?;
+            let report = WorkspaceState::migrate(&root, dry_run)?;
+
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+                "human" => {
+                    if report.steps.is_empty() {
+                        println!("{} Workspace state is already at schema v{}", "✅".green(), report.to);
+                        return Ok(());
+                    }
+                    let verb = if dry_run { "Would migrate" } else { "Migrated" };
+                    println!("{} {} workspace state from schema v{} to v{}", "🔄".blue(), verb, report.from, report.to);
+                    for step in &report.steps {
+                        println!("  • {}", step);
+                    }
+                    if let Some(backup) = &report.backup {
+                        println!("  Backup: {}", backup.display().to_string().dimmed());
+                    }
+                }
+                other => anyhow::bail!("Unknown format '{}' (expected human or json)", other),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_secret_command(action: SecretAction) -> Result<()> {
+    use std::io::Read;
+    use wsb::secrets;
+
+    let root = get_project_root()?;
+    match action {
+        SecretAction::Set { name, value } => {
+            let value = match value {
+                Some(value) => value,
+                None if atty::is(atty::Stream::Stdin) => dialoguer::Password::new()
+                    .with_prompt(format!("Value for {}", name))
+                    .interact()?,
+                None => {
+                    let mut value = String::new();
+                    std::io::stdin().read_to_string(&mut value).context("Failed to read secret from stdin")?;
+                    value.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            secrets::set(&root, &name, &value)?;
+            println!("{} Stored secret '{}'", "🔐".green(), name);
+        }
+        SecretAction::Get { name } => match secrets::get(&root, &name)? {
+            Some(value) => println!("{}", value),
+            None => anyhow::bail!("No secret named '{}'", name),
+        },
+        SecretAction::List => {
+            let names = secrets::names(&root)?;
+            if names.is_empty() {
+                println!("No secrets stored");
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        SecretAction::Remove { name } => {
+            if !secrets::remove(&root, &name)? {
+                anyhow::bail!("No secret named '{}'", name);
+            }
+            println!("{} Removed secret '{}'", "🗑️".yellow(), name);
+        }
+    }
+    Ok(())
+}
+
+fn run_audit_command(action: AuditAction) -> Result<()> {
+    use wsb::entities::audit;
+    use wsb::entities::retention::RetentionConfig;
+
+    let root = get_project_root()?;
+    let configured = |state: &WorkspaceState| {
+        state
+            .get_tool_config::<RetentionConfig>("retention")
+            .unwrap_or_default()
+    };
+
+    if let AuditAction::Retention { days, clear } = action {
+        if days.is_none() && !clear {
+            let config = WorkspaceState::load(&root).map(|state| configured(&state)).unwrap_or_default();
+            match config.tables.get(audit::RETENTION_TABLE).and_then(|policy| policy.max_age_days) {
+                Some(days) => println!("Audit entries are kept for {} days", days),
+                None => println!("Audit entries are kept forever"),
+            }
+            return Ok(());
+        }
+
+        wsb::read_only::ensure_writable("change the audit retention")?;
+        let mut state = WorkspaceState::load(&root)?;
+        let mut config = configured(&state);
+        if clear {
+            config.tables.remove(audit::RETENTION_TABLE);
+        } else {
+            config.tables.entry(audit::RETENTION_TABLE.to_string()).or_default().max_age_days = days;
+        }
+        state.set_tool_config("retention", &config)?;
+        state.save(&root)?;
+        match days {
+            Some(days) => println!("{} Audit entries will be kept for {} days", "✅".green(), days),
+            None => println!("{} Audit entries will be kept forever", "✅".green()),
+        }
+        return Ok(());
+    }
+
+    let db_path = root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+
+        match action {
+            AuditAction::List { entity, by, limit, format } => {
+                let entries = audit::list(&pool, entity.as_deref(), by.as_deref(), limit).await?;
+                match format.as_str() {
+                    "json" => println!("{}", serde_json::to_string_pretty(&entries)?),
+                    "human" => {
+                        if entries.is_empty() {
+                            println!("No audit entries found");
+                        }
+                        for entry in entries {
+                            let change = match &entry.field {
+                                Some(field) => format!(" {}: {} → {}",
+                                    field,
+                                    entry.old_value.as_deref().unwrap_or("∅"),
+                                    entry.new_value.as_deref().unwrap_or("∅")),
+                                None => String::new(),
+                            };
+                            let session = entry.session_id.as_deref().map(|s| format!(" in {}", s)).unwrap_or_default();
+                            println!("{} {} {} {}{} by {}{}",
+                                entry.timestamp.dimmed(), entry.entity_id.bold(), entry.entity_type, entry.operation, change, entry.actor, session);
+                        }
+                    }
+                    other => anyhow::bail!("Unknown format '{}' (expected human or json)", other),
+                }
+            }
+            AuditAction::Prune { older_than, apply } => {
+                let days = match older_than {
+                    Some(days) => days,
+                    None => WorkspaceState::load(&root)
+                        .ok()
+                        .and_then(|state| configured(&state).tables.get(audit::RETENTION_TABLE).and_then(|policy| policy.max_age_days))
+                        .ok_or_else(|| anyhow::anyhow!("No audit retention configured; pass --older-than or run 'wsb audit retention --days N'"))?,
+                };
+                if apply {
+                    wsb::read_only::ensure_writable("prune the audit trail")?;
+                }
+                let rows = audit::prune(&pool, days, apply).await?;
+                if apply {
+                    println!("{} Deleted {} audit entries older than {} days", "🗑️".yellow(), rows, days);
+                } else {
+                    println!("{} audit entries older than {} days to delete", rows, days);
+                    if rows > 0 {
+                        println!("{} Dry run; pass --apply to prune", "💡".yellow());
+                    }
+                }
+            }
+            AuditAction::Retention { .. } => unreachable!("handled above"),
+        }
+        Ok(())
+    })
+}
+
+fn run_milestone_command(action: MilestoneAction) -> Result<()> {
+    use wsb::entities::crud;
+    use wsb::entities::schema_models::MilestoneStatus;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+
+        match action {
+            MilestoneAction::Add { title, description, target, features, tasks } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                // Create and link in one transaction so a bad member ID leaves nothing behind
+                let mut tx = pool.begin().await?;
+                let mut milestone = crud::milestones::create(&mut *tx, project.id, title, description, target).await?;
+                if !features.is_empty() || !tasks.is_empty() {
+                    milestone = crud::milestones::add_members(&mut *tx, &milestone.id, &features, &tasks).await?;
+                }
+                tx.commit().await?;
+                println!("{} Created milestone {}: {}", "✅".green(), milestone.id.bold(), milestone.title);
+            }
+            MilestoneAction::Link { milestone_id, features, tasks } => {
+                if features.is_empty() && tasks.is_empty() {
+                    anyhow::bail!("Nothing to link; pass --features and/or --tasks");
+                }
+                require_id(&pool, "Milestone", &milestone_id, "SELECT id FROM milestones").await?;
+                for feature_id in &features {
+                    require_id(&pool, "Feature", feature_id, LIVE_FEATURE_IDS).await?;
+                }
+                for task_id in &tasks {
+                    require_id(&pool, "Task", task_id, LIVE_TASK_IDS).await?;
+                }
+                let milestone = crud::milestones::add_members(&pool, &milestone_id, &features, &tasks).await?;
+                println!("{} {} now has {} feature(s) and {} task(s), {:.1}% complete",
+                    "✅".green(), milestone.id.bold(), milestone.feature_ids.len(), milestone.task_ids.len(), milestone.completion_percentage);
+            }
+            MilestoneAction::List { format } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                let milestones = crud::milestones::list_by_project(&pool, &project.id).await?;
+                match format.as_str() {
+                    "json" => println!("{}", serde_json::to_string_pretty(&milestones)?),
+                    "human" => {
+                        if milestones.is_empty() {
+                            println!("No milestones found");
+                        }
+                        for milestone in milestones {
+                            let target = milestone.target_date.as_deref().map(|d| format!(" due {}", d)).unwrap_or_default();
+                            println!("{} {:<32} {:<12} {:>5.1}%{}",
+                                milestone.id.cyan(), milestone.title, milestone.status, milestone.completion_percentage, target.dimmed());
+                        }
+                    }
+                    other => anyhow::bail!("Unknown format '{}' (expected human or json)", other),
+                }
+            }
+            MilestoneAction::Show { milestone_id, format } => {
+                require_id(&pool, "Milestone", &milestone_id, "SELECT id FROM milestones").await?;
+                let milestone = crud::milestones::get_by_id(&pool, &milestone_id).await?
+                    .ok_or_else(|| anyhow::anyhow!("Milestone not found: {}", milestone_id))?;
+                match format.as_str() {
+                    "json" => println!("{}", serde_json::to_string_pretty(&milestone)?),
+                    "human" => {
+                        println!("{} {}", milestone.id.bold(), milestone.title.bold());
+                        if !milestone.description.is_empty() {
+                            println!("{}", milestone.description);
+                        }
+                        println!("{}: {}", "Status".bold(), milestone.status);
+                        println!("{}: {:.1}%", "Completion".bold(), milestone.completion_percentage);
+                        if let Some(ref target) = milestone.target_date {
+                            println!("{}: {}", "Target".bold(), target);
+                        }
+                        if let Some(ref achieved) = milestone.achieved_date {
+                            println!("{}: {}", "Achieved".bold(), achieved);
+                        }
+                        if !milestone.feature_ids.is_empty() {
+                            println!("{}", "Features".bold());
+                            for id in &milestone.feature_ids {
+                                match crud::features::get_by_id(&pool, id).await? {
+                                    Some(feature) => println!("  {} {:<32} {}", id.cyan(), feature.name, feature.state.dimmed()),
+                                    None => println!("  {} {}", id.cyan(), "(deleted)".dimmed()),
+                                }
+                            }
+                        }
+                        if !milestone.task_ids.is_empty() {
+                            println!("{}", "Tasks".bold());
+                            for id in &milestone.task_ids {
+                                match crud::tasks::get_by_id(&pool, id).await? {
+                                    Some(task) => println!("  {} {:<32} {}", id.cyan(), task.task, task.status.dimmed()),
+                                    None => println!("  {} {}", id.cyan(), "(deleted)".dimmed()),
+                                }
+                            }
+                        }
+                    }
+                    other => anyhow::bail!("Unknown format '{}' (expected human or json)", other),
+                }
+            }
+            MilestoneAction::Close { milestone_id, status } => {
+                let status = MilestoneStatus::from_str(&status).map_err(|e| anyhow::anyhow!(e))?;
+                require_id(&pool, "Milestone", &milestone_id, "SELECT id FROM milestones").await?;
+                let milestone = crud::milestones::close(&pool, &milestone_id, status).await?;
+                println!("{} Closed {} as {} at {:.1}% complete",
+                    "✅".green(), milestone.id.bold(), milestone.status, milestone.completion_percentage);
+            }
+        }
+        Ok(())
+    })
+}
+
+fn print_sprint_summary(summary: &wsb::entities::sprints::SprintSummary) {
+    let sprint = &summary.sprint;
+    println!("{} {} {}", sprint.id.bold().blue(), sprint.name.bold(), format!("({} to {}, {})", sprint.start_date, sprint.end_date, sprint.status).dimmed());
+    if let Some(goal) = &sprint.goal {
+        println!("  Goal: {}", goal);
+    }
+    let capacity = sprint.capacity.map(|capacity| format!(" of {} capacity", capacity)).unwrap_or_default();
+    println!("  Points: {} planned{}, {} completed, {} remaining", summary.planned_points, capacity, summary.completed_points, summary.remaining_points);
+    if summary.over_capacity {
+        println!("  {} Planned points exceed the capacity", "⚠️".yellow());
+    }
+    for task in &summary.tasks {
+        let mark = if task.is_done() { "✓".green() } else { "·".normal() };
+        println!("  {} {} {} {}", mark, task.task_id.bold(), task.title, format!("[{} pt, {}]", task.points, task.status).dimmed());
+    }
+}
+
+fn run_sprint_command(action: SprintAction) -> Result<()> {
+    use wsb::entities::sprints;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+
+        match action {
+            SprintAction::Create { name, start, end, capacity, goal } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                let start = match start {
+                    Some(start) => sprints::parse_date(&start)?,
+                    None => chrono::Utc::now().date_naive(),
+                };
+                let end = match end {
+                    Some(end) => sprints::parse_date(&end)?,
+                    None => start + chrono::Duration::days(13),
+                };
+                let sprint = sprints::create(&pool, &project.id, &name, goal, start, end, capacity).await?;
+                println!("{} Created sprint {}: {} ({} to {})", "✅".green(), sprint.id.bold(), sprint.name, sprint.start_date, sprint.end_date);
+            }
+            SprintAction::Plan { sprint_id, tasks, points, remove } => {
+                let summary = if remove {
+                    sprints::unplan(&pool, &sprint_id, &tasks).await?
+                } else {
+                    sprints::plan(&pool, &sprint_id, &tasks, points).await?
+                };
+                let verb = if remove { "Removed" } else { "Planned" };
+                println!("{} {} {} task(s); {} now has {} point(s) planned", "✅".green(), verb, tasks.len(), sprint_id.bold(), summary.planned_points);
+                if summary.over_capacity {
+                    println!("{} Planned points exceed the capacity of {}", "⚠️".yellow(), summary.sprint.capacity.unwrap_or_default());
+                }
+            }
+            SprintAction::Close { sprint_id, carry_over } => {
+                let summary = sprints::close(&pool, &sprint_id, carry_over.as_deref()).await?;
+                println!("{} Closed sprint {}: {} of {} point(s) completed", "✅".green(), sprint_id.bold(), summary.completed_points, summary.planned_points);
+                let unfinished = summary.tasks.iter().filter(|task| !task.is_done()).count();
+                if let (Some(target), true) = (carry_over, unfinished > 0) {
+                    println!("Carried {} unfinished task(s) over into {}", unfinished, target.bold());
+                }
+            }
+            SprintAction::List { format } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                let listed = sprints::list(&pool, &project.id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&listed)?);
+                } else if listed.is_empty() {
+                    println!("No sprints yet. Create one with `wsb sprint create <name>`");
+                } else {
+                    println!("{}", "Sprints".bold().blue());
+                    for sprint in listed {
+                        let status = if sprint.is_open() { sprint.status.green() } else { sprint.status.dimmed() };
+                        println!("  {} {} {} {}", sprint.id.bold(), sprint.name, status, format!("{} to {}", sprint.start_date, sprint.end_date).dimmed());
+                    }
+                }
+            }
+            SprintAction::Show { sprint_id, format } => {
+                let summary = sprints::summary(&pool, &sprint_id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&summary)?);
+                } else {
+                    print_sprint_summary(&summary);
+                }
+            }
+            SprintAction::Velocity { last, format } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                let velocity = sprints::velocity(&pool, &project.id, last).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&velocity)?);
+                } else if velocity.sprints.is_empty() {
+                    println!("No closed sprints yet; velocity needs at least one");
+                } else {
+                    println!("{} {} point(s) per sprint", "Velocity:".bold(), velocity.average.to_string().bold());
+                    for sprint in &velocity.sprints {
+                        println!("  {} {} {}/{} {}", sprint.sprint_id.bold(), sprint.name, sprint.completed_points, sprint.planned_points, format!("(ended {})", sprint.end_date).dimmed());
+                    }
+                }
+            }
+            SprintAction::Burndown { sprint_id, format } => {
+                let burndown = sprints::burndown(&pool, &sprint_id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&burndown)?);
+                } else {
+                    println!("{} {} {}", "Burndown".bold().blue(), burndown.sprint_id.bold(), format!("({} point(s))", burndown.total_points).dimmed());
+                    for day in &burndown.days {
+                        let bar = "█".repeat(day.remaining.max(0) as usize);
+                        println!("  {} {:>4} {} {}", day.date, day.remaining, bar.cyan(), format!("ideal {:.1}", day.ideal).dimmed());
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_session_command(action: SessionAction) -> Result<()> {
+    use wsb::entities::crud;
+
+    let project_root = get_project_root()?;
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+
+        match action {
+            SessionAction::Create { title, focus } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                let session = crud::sessions::create(&pool, project.id, title, focus).await?;
+                println!("{} Started session {}: {}", "✅".green(), session.id.bold(), session.title);
+            }
+            SessionAction::Complete { id, summary } => {
+                require_id(&pool, "Session", &id, "SELECT id FROM sessions").await?;
+                crud::sessions::complete_in(&pool, &id, summary, &project_root).await?;
+                let session = crud::sessions::get_by_id(&pool, &id).await?
+                    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+                println!("{} Completed session {}", "✅".green(), session.id.bold());
+                match session.get_git_activity().map_err(anyhow::Error::msg)? {
+                    Some(activity) => println!("  {} commit(s), {} file(s), {} {}", activity.commits.len(), activity.files.len(),
+                        format!("+{}", activity.insertions).green(), format!("-{}", activity.deletions).red()),
+                    None => println!("  {}", "Not a git repository; no commits recorded".dimmed()),
+                }
+            }
+            SessionAction::List { format } => {
+                let project = wsb::entities::workspace::current_project(&pool).await?
+                    .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+                let sessions = crud::sessions::list_by_project(&pool, &project.id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&sessions)?);
+                } else if sessions.is_empty() {
+                    println!("No sessions yet. Start one with `wsb session create <title>`");
+                } else {
+                    println!("{}", "Sessions".bold().blue());
+                    for session in sessions {
+                        let commits = match session.get_git_activity() {
+                            Ok(Some(activity)) => format!(" ({} commits)", activity.commits.len()),
+                            _ => String::new(),
+                        };
+                        println!("  {} {} {} {}{}", session.id.bold(), session.date, session.status.cyan(), session.title, commits.dimmed());
+                    }
+                }
+            }
+            SessionAction::Show { id, format } => {
+                require_id(&pool, "Session", &id, "SELECT id FROM sessions").await?;
+                let session = crud::sessions::get_by_id(&pool, &id).await?
+                    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+                let activity = session.get_git_activity().map_err(anyhow::Error::msg)?;
+                if format == "json" {
+                    let mut value = serde_json::to_value(&session)?;
+                    value["git_activity"] = serde_json::to_value(&activity)?;
+                    println!("{}", serde_json::to_string_pretty(&value)?);
+                    return Ok(());
+                }
+
+                println!("{} {}", session.id.bold().blue(), session.title.bold());
+                println!("  Status: {}", session.status);
+                println!("  Focus: {}", session.focus);
+                println!("  Date: {} {}-{}", session.date, session.start_time.as_deref().unwrap_or("?"), session.end_time.as_deref().unwrap_or("…"));
+                match activity {
+                    None if session.status == "active" => println!("  {}", "Git activity is captured when the session completes".dimmed()),
+                    None => println!("  {}", "No git activity recorded".dimmed()),
+                    Some(activity) => {
+                        println!();
+                        println!("{} {} commit(s), {} {}", "Git activity:".bold(), activity.commits.len(),
+                            format!("+{}", activity.insertions).green(), format!("-{}", activity.deletions).red());
+                        for commit in &activity.commits {
+                            println!("  {} {} {}", commit.sha.chars().take(10).collect::<String>().yellow(), commit.summary, format!("({})", commit.author).dimmed());
+                        }
+                        if !activity.files.is_empty() {
+                            println!("{}", "Files:".bold());
+                            for file in &activity.files {
+                                let counts = if file.binary { "binary".to_string() } else { format!("+{} -{}", file.insertions, file.deletions) };
+                                println!("  {} {}", file.path, counts.dimmed());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_project_command(action: ProjectAction) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+
+        match action {
+            ProjectAction::List { all, format } => {
+                let current = manager.get_current_project().await?.map(|project| project.id);
+                let projects = manager.list_projects(all).await?;
+                if format == "json" {
+                    let listed: Vec<serde_json::Value> = projects
+                        .iter()
+                        .map(|project| serde_json::json!({
+                            "id": project.id,
+                            "name": project.name,
+                            "description": project.description,
+                            "status": project.status,
+                            "current": current.as_deref() == Some(project.id.as_str()),
+                        }))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&listed)?);
+                } else if projects.is_empty() {
+                    println!("No projects yet. Create one with `wsb project create <name>`");
+                } else {
+                    println!("{}", "Projects".bold().blue());
+                    for project in projects {
+                        let marker = if current.as_deref() == Some(project.id.as_str()) { "*".green().bold() } else { " ".normal() };
+                        let status = if project.status == "active" { String::new() } else { format!(" ({})", project.status) };
+                        println!("{} {} {}{}", marker, project.id.bold(), project.name, status.dimmed());
+                    }
+                }
+            }
+            ProjectAction::Create { name, description, switch } => {
+                let description = description.unwrap_or_else(|| format!("{} project", name));
+                let project = manager.create_project(name, description).await?;
+                println!("{} Created project {} {}", "✅".green(), project.id.bold(), project.name);
+                if switch {
+                    manager.switch_project(&project.id).await?;
+                    println!("Switched to {}", project.id.bold());
+                }
+            }
+            ProjectAction::Switch { id } => {
+                let project = manager.switch_project(&id).await?;
+                println!("{} Switched to {} {}", "✅".green(), project.id.bold(), project.name);
+            }
+            ProjectAction::Archive { id } => {
+                manager.archive_project(&id).await?;
+                println!("{} Archived project {}", "✅".green(), id.bold());
+                if let Some(current) = manager.get_current_project().await? {
+                    println!("Current project: {} {}", current.id.bold(), current.name);
+                }
+            }
+            ProjectAction::Unarchive { id } => {
+                manager.unarchive_project(&id).await?;
+                println!("{} Project {} is active again", "✅".green(), id.bold());
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_ids_command(action: IdsAction) -> Result<()> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        let project = manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+
+        match action {
+            IdsAction::List { format } => {
+                let formats = manager.id_formats(&project.id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&formats)?);
+                } else {
+                    println!("{} {}", "Entity IDs".bold().blue(), format!("({})", project.id).dimmed());
+                    for id_format in formats {
+                        let origin = if id_format.configured { "" } else { " (default)" };
+                        println!("  {:<10} next {}{}", id_format.entity_type, id_format.format(id_format.next_value).bold(), origin.dimmed());
+                    }
+                }
+            }
+            IdsAction::Set { entity, prefix, width } => {
+                let id_format = manager.configure_ids(&project.id, &entity, &prefix, width).await?;
+                println!("{} New {} IDs look like {}", "✅".green(), id_format.entity_type, id_format.format(id_format.next_value).bold());
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_tag_command(action: TagAction) -> Result<()> {
+    use wsb::entities::tags;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        let project = manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+
+        match action {
+            TagAction::List { counts, format } => {
+                let usage = manager.list_tags(&project.id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&usage)?);
+                } else if usage.is_empty() {
+                    println!("No tags yet. Tag an entity with `wsb tag add <id> <tags>` or `--tag` on add commands");
+                } else if counts {
+                    println!("{}", "Tags".bold().blue());
+                    for tag in usage {
+                        let by_type: Vec<String> = tag.by_type.iter().map(|(entity_type, count)| format!("{} {}s", count, entity_type)).collect();
+                        println!("  {} {} {}", tag.name.bold(), tag.total.to_string().cyan(), format!("({})", by_type.join(", ")).dimmed());
+                    }
+                } else {
+                    let mut names: Vec<String> = usage.into_iter().map(|tag| tag.name).collect();
+                    names.sort();
+                    println!("{}", names.join("\n"));
+                }
+            }
+            TagAction::Add { entity_id, tags: names } => {
+                let entity_type = tags::entity_type_of(&entity_id)?;
+                let now = manager.tag_entity(&project.id, entity_type, &entity_id, &names).await?;
+                println!("{} {} {} tagged: {}", "✅".green(), entity_type, entity_id.bold(), now.join(", "));
+            }
+            TagAction::Remove { entity_id, tags: names } => {
+                let entity_type = tags::entity_type_of(&entity_id)?;
+                let now = manager.untag_entity(&project.id, entity_type, &entity_id, &names).await?;
+                let remaining = if now.is_empty() { "no tags left".to_string() } else { now.join(", ") };
+                println!("{} {} {}: {}", "✅".green(), entity_type, entity_id.bold(), remaining);
+            }
+            TagAction::Rename { old, new } => {
+                manager.rename_tag(&project.id, &old, &new).await?;
+                println!("{} Renamed tag {} to {}", "✅".green(), old, tags::normalize(&new)?.bold());
+            }
+            TagAction::Merge { sources, into } => {
+                let moved = manager.merge_tags(&project.id, &sources, &into).await?;
+                println!("{} Merged {} into {} ({} links moved)", "✅".green(), sources.join(", "), tags::normalize(&into)?.bold(), moved);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Tag an entity that was just created, if any tags were given
+fn tag_new_entity(entity_type: &str, entity_id: &str, tags: &[String]) -> Result<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let tags = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        let project = manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found to hold tags"))?;
+        manager.tag_entity(&project.id, entity_type, entity_id, tags).await
+    })?;
+    println!("  {} Tags: {}", "🏷️".cyan(), tags.join(", "));
+    Ok(())
+}
+
+/// IDs of the active project's entities of a type carrying all of `tags`
+fn load_tagged(entity_type: &str, tags: &[String]) -> Result<std::collections::HashSet<String>> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        match manager.get_current_project().await? {
+            Some(project) => manager.find_tagged(&project.id, entity_type, tags).await,
+            None => Ok(Default::default()),
+        }
+    })
+}
+
+fn run_view_command(action: ViewAction) -> Result<()> {
+    use wsb::entities::views::ViewDefinition;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        let project = manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+
+        match action {
+            ViewAction::Save { name, entity, status, priority, tags, sort } => {
+                let definition = ViewDefinition {
+                    entity_type: entity.to_lowercase(),
+                    status,
+                    priority: priority.map(|priority| priority.to_lowercase()),
+                    tags: tags.into_iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect(),
+                    sort,
+                };
+                let view = manager.save_view(&project.id, &name, definition).await?;
+                println!("{} Saved view {} ({} list: {})", "✅".green(), view.name.bold(), view.entity_type, describe_view(&view));
+                println!("  Use it with `wsb {} list --view {}`", view.entity_type, view.name);
+            }
+            ViewAction::List { format } => {
+                let views = manager.list_views(&project.id).await?;
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&views)?);
+                } else if views.is_empty() {
+                    println!("No saved views. Save one with `wsb view save <name> --entity task --status in_progress`");
+                } else {
+                    println!("{}", "Saved views".bold().blue());
+                    for view in views {
+                        println!("  {} {} {}", view.name.bold(), format!("[{}]", view.entity_type).cyan(), describe_view(&view));
+                    }
+                }
+            }
+            ViewAction::Delete { name } => {
+                if !manager.delete_view(&project.id, &name).await? {
+                    anyhow::bail!("View not found: {}", name);
+                }
+                println!("{} Deleted view {}", "✅".green(), name.bold());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The filters of a view, e.g. `status=in_progress priority=high sort=-created`
+fn describe_view(view: &wsb::entities::views::SavedView) -> String {
+    let mut parts = Vec::new();
+    if let Some(status) = &view.status {
+        parts.push(format!("status={}", status));
+    }
+    if let Some(priority) = &view.priority {
+        parts.push(format!("priority={}", priority));
+    }
+    if !view.tags.is_empty() {
+        parts.push(format!("tags={}", view.tags.join(",")));
+    }
+    if let Some(sort) = &view.sort {
+        parts.push(format!("sort={}", sort));
+    }
+    if parts.is_empty() {
+        "everything".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// A saved view of the active project, which must list `entity_type`
+fn load_view(name: &str, entity_type: &str) -> Result<wsb::entities::views::SavedView> {
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let view = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        match manager.get_current_project().await? {
+            Some(project) => manager.get_view(&project.id, name).await,
+            None => Ok(None),
+        }
+    })?
+    .ok_or_else(|| anyhow::anyhow!("View not found: {}", name))?;
+    if view.entity_type != entity_type {
+        anyhow::bail!("View {} lists {}s, not {}s", name, view.entity_type, entity_type);
+    }
+    Ok(view)
+}
+
+fn run_export_command(format: &str, entity: &[String], files: bool, output: Option<PathBuf>) -> Result<()> {
+    use wsb::entities::transfer::{EntityKind, Format};
+
+    let format = Format::from_str(format)?;
+    let kinds = if entity.is_empty() {
+        EntityKind::ALL.to_vec()
+    } else {
+        entity.iter().map(|name| EntityKind::from_str(name)).collect::<Result<Vec<_>>>()?
+    };
+
+    let project_root = get_project_root()?;
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let mut snapshot = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let manager = EntityManager::new(pool);
+        let project = manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        manager.export_project(&project.id, &kinds).await
+    })?;
+    if files {
+        for path in wsb::entities::transfer::embed_files(&mut snapshot, &project_root)? {
+            eprintln!("{} Not embedding {}: missing or changed since it was attached", "⚠️".yellow(), path);
+        }
+    }
+
+    let text = format.render(&snapshot)?;
+    match output {
+        Some(path) => {
+            fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+            let rows: usize = snapshot.entities.values().map(Vec::len).sum();
+            eprintln!("{} Exported {} rows of {} to {}", "✅".green(), rows, snapshot.project_id.bold(), path.display());
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+fn run_import_command(file: &Path, replace: bool) -> Result<()> {
+    use wsb::entities::transfer::{Format, ImportMode};
+
+    let text = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let snapshot = Format::from_path(file).parse(&text)?;
+    let mode = if replace { ImportMode::Replace } else { ImportMode::Merge };
+
+    let project_root = get_project_root()?;
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    let report = rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        EntityManager::new(pool).import_snapshot(&snapshot, mode).await
+    })?;
+    let restored = wsb::entities::transfer::restore_files(&snapshot, &project_root)?;
+
+    let counts: Vec<String> = report.imported.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect();
+    println!("{} Imported {}", "✅".green(), counts.join(", "));
+    if report.deleted > 0 {
+        println!("  {} Replaced {} existing rows", "→".yellow(), report.deleted);
+    }
+    for (old, new) in &report.remapped {
+        println!("  {} {} → {}", "→".blue(), old, new.bold());
+    }
+    for path in &restored {
+        println!("  {} Restored {}", "📎".cyan(), path);
+    }
+    Ok(())
+}
+
+fn run_sync_command(action: Option<SyncAction>, direction: &str, dry_run: bool) -> Result<()> {
+    use wsb::entities::sync::{self, Action, Direction, Source};
+
+    let direction = Direction::from_str(direction)?;
+    let project_root = get_project_root()?;
+    let db_path = project_root.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        if let Some(SyncAction::Canonical { source }) = action {
+            match source.as_deref() {
+                None => match sync::canonical_source(&pool).await? {
+                    Some(source) => println!("Canonical source: {}", source.as_str().bold()),
+                    None => println!("No canonical source; two-way syncs leave conflicts for you to resolve"),
+                },
+                Some("none") => {
+                    sync::set_canonical_source(&pool, None).await?;
+                    println!("{} Cleared the canonical source", "✅".green());
+                }
+                Some(name) => {
+                    let source = Source::from_str(name)?;
+                    sync::set_canonical_source(&pool, Some(source)).await?;
+                    println!("{} {} now wins conflicts in two-way syncs", "✅".green(), source.as_str().bold());
+                }
+            }
+            return Ok(());
+        }
+
+        let plan = sync::plan(&pool, &project_root, direction).await?;
+        println!("{} Sync {} ({})", "🔄".blue(), plan.project.id.bold(), direction.as_str());
+        for change in &plan.changes {
+            match change.action {
+                Action::Conflict => println!("  {} {}", "⚠️".yellow(), change.describe()),
+                _ => println!("  {} {}", "→".green(), change.describe()),
+            }
+        }
+        println!("  {} records already in sync", plan.unchanged);
+        if dry_run {
+            println!("Dry run: nothing changed");
+            return Ok(());
+        }
+
+        let outcome = sync::apply(&pool, &project_root, &plan).await?;
+        for (old, new) in &outcome.assigned {
+            println!("  {} {} → {}", "→".blue(), old, new.bold());
+        }
+        for file in &outcome.written {
+            println!("  {} Wrote {}", "📝".cyan(), file);
+        }
+        let conflicts = plan.conflicts().count();
+        if conflicts > 0 {
+            anyhow::bail!(
+                "{} conflicting records left as they were; edit one side to match, sync with --direction, or set `wsb sync canonical db|md`",
+                conflicts
+            );
+        }
+        println!("{} Database and markdown are in sync", "✅".green());
+        Ok(())
+    })
+}
+
+fn run_dashboard_command(tui: bool, interval: u64, format: &str) -> Result<()> {
+    use wsb::tui::dashboard;
+
+    if tui {
+        if interval == 0 {
+            anyhow::bail!("--interval must be at least 1 second");
+        }
+        wsb::tui::require_terminal("wsb dashboard --tui")?;
+    } else if !matches!(format, "human" | "json") {
+        anyhow::bail!("Unknown format '{}' (expected human or json)", format);
+    }
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        if tui {
+            return dashboard::run(&pool, std::time::Duration::from_secs(interval)).await;
+        }
+
+        let project = wsb::entities::workspace::current_project(&pool)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        let snapshot = dashboard::load(&pool, &project).await?;
+        if format == "json" {
+            println!("{}", serde_json::to_string_pretty(&snapshot)?);
+            return Ok(());
+        }
+
+        println!("{} {} ({})", "📊".blue(), snapshot.project_name.bold(), snapshot.project_id);
+        println!();
+        println!("{} ({})", "Features".bold(), snapshot.feature_total);
+        for state in snapshot.feature_states.iter().filter(|s| s.count > 0) {
+            println!("  {} {:<16}{:>5}", state.emoji, state.label, state.count);
+        }
+        println!("{}", "Tasks".bold());
+        for status in snapshot.task_statuses.iter().filter(|s| s.count > 0) {
+            println!("  {:<16}{:>5}", status.label, status.count);
+        }
+
+        println!();
+        println!("{}", "Recent sessions".bold());
+        if snapshot.recent_sessions.is_empty() {
+            println!("  No sessions yet");
+        }
+        for session in &snapshot.recent_sessions {
+            println!("  {} {} [{}] {}", session.id.cyan(), session.title, session.state, session.date.dimmed());
+        }
+
+        println!();
+        if snapshot.failing_checks.is_empty() {
+            println!("{} All checks passing", "✅".green());
+        } else {
+            println!("{} ({})", "Failing checks".bold().red(), snapshot.failing_checks.len());
+            for check in &snapshot.failing_checks {
+                println!("  {} {} {}", "❌".red(), check.id, check.detail);
+            }
+        }
+
+        match &snapshot.context {
+            Some(usage) => println!(
+                "{} Context usage: {:.0}% ({} tokens, {})",
+                "📈".cyan(),
+                usage.percent,
+                usage.tokens,
+                usage.session_id
+            ),
+            None => println!("{} Context usage: not recorded", "📈".cyan()),
+        }
+        Ok(())
+    })
+}
+
+fn run_board_command(feature: Option<String>, priority: Option<String>) -> Result<()> {
+    use wsb::entities::schema_models::TaskPriority;
+    use wsb::tui::board::{self, BoardFilter};
+
+    if let Some(priority) = &priority {
+        TaskPriority::from_str(priority).map_err(|e| anyhow::anyhow!(e))?;
+    }
+    wsb::tui::require_terminal("wsb board")?;
+
+    let db_path = get_project_root()?.join(".wsb/project.db");
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let project = wsb::entities::workspace::current_project(&pool)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+        let manager = wsb::entities::EntityManager::new(pool);
+        board::run(&manager, &project.id, BoardFilter { feature, priority }).await
+    })
+}
+
+fn run_locks_command(clear_stale: bool, format: &str) -> Result<()> {
+    use wsb::locks;
+
+    let root = get_project_root()?;
+    let cleared = if clear_stale { locks::clear_stale(&root)? } else { Vec::new() };
+    let statuses = locks::list(&root);
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "locks": statuses, "cleared": cleared }))?);
+        }
+        "human" => {
+            for name in &cleared {
+                println!("{} Cleared stale lock '{}'", "🧹".yellow(), name);
+            }
+            if statuses.is_empty() {
+                println!("No locks held");
+                return Ok(());
+            }
+            for status in &statuses {
+                let holder = match &status.holder {
+                    Some(holder) => format!(
+                        "pid {} on {} ({}) since {}",
+                        holder.pid, holder.host, holder.command, holder.acquired_at.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    None => "unreadable lock file".to_string(),
+                };
+                let stale = if status.stale { format!(" {}", "[stale]".red()) } else { String::new() };
+                println!("{} {}: {}{}", "🔒".blue(), status.name.bold(), holder, stale);
+                for waiter in &status.waiting {
+                    println!("    waiting: pid {} ({}) since {}", waiter.pid, waiter.command, waiter.acquired_at.format("%H:%M:%S"));
+                }
+            }
+            if !clear_stale && statuses.iter().any(|s| s.stale) {
+                println!("Remove stale locks with `wsb locks --clear-stale`");
+            }
+        }
+        other => anyhow::bail!("Unknown format '{}' (expected human or json)", other),
+    }
+    Ok(())
+}
+
+fn run_refactor_undo(journal_id: Option<&str>, list: bool, force: bool) -> Result<()> {
+    use wsb::refac::journal;
+
+    let store = journal::default_store();
+
+    if list {
+        let records = journal::list(&store)?;
+        if records.is_empty() {
+            println!("No refactor journals in {}", store.display());
+            return Ok(());
+        }
+
+        for record in records.iter().rev() {
+            let state = match record.undone_at {
+                Some(_) => "undone".dimmed().to_string(),
+                None => "active".green().to_string(),
+            };
+            println!("{} [{}] '{}' → '{}' in {} ({} content, {} renames)",
+                record.id.bold(), state, record.pattern, record.substitute,
+                record.root_dir.display(), record.content_changes(), record.renames());
+        }
+        return Ok(());
+    }
+
+    wsb::read_only::ensure_writable("undo a refactor")?;
+    // Restored files may be ones a concurrent `wsb update` or template render is writing
+    let project_root = get_project_root()?;
+    let _lock = if project_root.join(".wsb").is_dir() {
+        Some(wsb::locks::acquire(&project_root, wsb::locks::DOCUMENTS, "wsb refactor undo")?)
+    } else {
+        None
+    };
+    let (record, report) = journal::undo(&store, journal_id, force)?;
+    println!("{} Undid refactor {} ('{}' → '{}') in {}",
+        "↩️".blue(), record.id.bold(), record.pattern, record.substitute, record.root_dir.display());
+    println!("  {} renames reverted, {} files restored", report.reverted_renames, report.restored_files);
+    if report.restored_links > 0 {
+        println!("  {} symlink targets restored", report.restored_links);
+    }
+
+    if !report.errors.is_empty() {
+        for error in &report.errors {
+            eprintln!("  {} {}", "✗".red(), error);
+        }
+        anyhow::bail!("{} change(s) could not be undone", report.errors.len());
+    }
+
+    Ok(())
+}
+
+/// Path relative to the project root (empty for the root itself)
+fn project_relative_path(project_root: &Path, path: &Path) -> Result<PathBuf> {
+    let absolute = path.canonicalize()
+        .with_context(|| format!("Path not found: {}", path.display()))?;
+    let root = project_root.canonicalize()?;
+    Ok(absolute.strip_prefix(&root).map(Path::to_path_buf).unwrap_or(absolute))
+}
+
+fn run_analyze_command(action: AnalyzeAction) -> Result<()> {
+    use wsb::code_analysis::owners::{resolve_owner, OwnershipMap};
+    use wsb::entities::crud;
+
+    let project_root = get_project_root()?;
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let db_path = project_root.join(".wsb/project.db");
+        let pool = wsb::entities::database::initialize_database(&db_path).await?;
+        let entity_manager = EntityManager::new(pool.clone());
+        let project = entity_manager.get_current_project().await?
+            .ok_or_else(|| anyhow::anyhow!("No active project"))?;
+
+        let rescan = matches!(action, AnalyzeAction::Owners { rescan: true, .. });
+        let mut entries = crud::code_owners::list(&pool, &project.id).await?;
+        if rescan || entries.is_empty() {
+            let map = OwnershipMap::scan(&project_root)?;
+            crud::code_owners::replace_all(&pool, &project.id, &map.entries).await?;
+            entries = map.entries;
+        }
+
+        match action {
+            AnalyzeAction::Owners { path, format, .. } => {
+                let relative = project_relative_path(&project_root, &path)?;
+                let owner = resolve_owner(&entries, &relative);
+                let within: Vec<_> = entries.iter()
+                    .filter(|e| e.path.starts_with(&relative) && e.path != relative)
+                    .collect();
+
+                if format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                        "path": relative,
+                        "owner": owner,
+                        "entries": within,
+                    }))?);
+                    return Ok(());
+                }
+
+                let shown = if relative.as_os_str().is_empty() { ".".to_string() } else { relative.display().to_string() };
+                match owner {
+                    Some(entry) => println!("{} {} → {} ({}, from {})", "👤".blue(), shown.bold(), entry.owners.join(", ").green(), entry.source.as_str(), entry.path.display()),
+                    None => println!("{} {} has no owner", "👤".blue(), shown.bold()),
+                }
+
+                if !within.is_empty() {
+                    let mut by_owner: std::collections::BTreeMap<String, Vec<&PathBuf>> = std::collections::BTreeMap::new();
+                    for entry in &within {
+                        by_owner.entry(entry.owners.join(", ")).or_default().push(&entry.path);
+                    }
+                    println!();
+                    for (owners, paths) in by_owner {
+                        println!("  {} ({} path{})", owners.green(), paths.len(), if paths.len() == 1 { "" } else { "s" });
+                        for path in paths.iter().take(10) {
+                            println!("    {}", path.display());
+                        }
+                        if paths.len() > 10 {
+                            println!("    ... and {} more", paths.len() - 10);
+                        }
+                    }
+                }
+            }
+            AnalyzeAction::Todos { path, create_tasks, feature } => {
+                let scan_path = project_root.join(project_relative_path(&project_root, &path)?);
+                let todos = wsb::code_analysis::todos::scan_todos(&project_root, &scan_path)?;
+                if todos.is_empty() {
+                    println!("No TODO/FIXME comments found");
+                    return Ok(());
+                }
+
+                let existing: std::collections::HashSet<String> = if create_tasks {
+                    entity_manager.list_tasks_by_project(&project.id, None).await?
+                        .into_iter().map(|t| t.task).collect()
+                } else {
+                    std::collections::HashSet::new()
+                };
+
+                let mut created = 0;
+                for todo in &todos {
+                    let owners = resolve_owner(&entries, &todo.path).map(|e| e.owners.join(", "));
+                    println!("{} {} {} {}", todo.tag.yellow(), todo.location().bright_blue(), todo.text,
+                        owners.as_deref().map(|o| format!("[{}]", o).green().to_string()).unwrap_or_default());
+
+                    if !create_tasks {
+                        continue;
+                    }
+
+                    let description = format!("{}: {} ({})", todo.tag, todo.text, todo.location());
+                    if existing.contains(&description) {
+                        continue;
+                    }
+
+                    let feature_id = feature.clone().unwrap_or_default();
+                    let mut task = crud::tasks::create(&pool, project.id.clone(), feature_id, description, todo.task_category().to_string()).await?;
+                    task.assigned = owners;
+                    task.notes = Some(todo.location().chars().take(100).collect());
+                    crud::tasks::update(&pool, &task).await?;
+                    created += 1;
+                }
+
+                if create_tasks {
+                    println!("{} Created {} task(s) from {} TODO(s)", "✅".green(), created, todos.len());
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
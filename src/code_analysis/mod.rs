@@ -5,9 +5,13 @@ use log::{info, debug};
 
 pub mod search;
 pub mod transform;
+pub mod owners;
+pub mod todos;
+pub mod tokens;
+pub mod tree;
 
 /// Supported languages for AST analysis
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum SupportedLanguage {
     Rust,
     JavaScript,
@@ -34,6 +38,21 @@ impl SupportedLanguage {
         }
     }
 
+    /// Parse a language name as given on the command line (`rust`, `js`, `c++`, ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "rust" | "rs" => Some(SupportedLanguage::Rust),
+            "javascript" | "js" => Some(SupportedLanguage::JavaScript),
+            "typescript" | "ts" => Some(SupportedLanguage::TypeScript),
+            "python" | "py" => Some(SupportedLanguage::Python),
+            "go" => Some(SupportedLanguage::Go),
+            "java" => Some(SupportedLanguage::Java),
+            "c" => Some(SupportedLanguage::C),
+            "cpp" | "c++" => Some(SupportedLanguage::Cpp),
+            _ => None,
+        }
+    }
+
     /// Detect language from file extension
     pub fn from_extension(ext: &str) -> Option<Self> {
         match ext.to_lowercase().as_str() {
@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Locations checked for a CODEOWNERS file, in GitHub's order of precedence
+pub const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Files whose `@owner` annotation applies to the whole directory (module) they live in
+const MODULE_FILES: &[&str] = &["mod.rs", "lib.rs", "main.rs", "__init__.py", "index.js", "index.ts", "package-info.java", "doc.go"];
+
+/// Only the head of a file is scanned for annotations
+const ANNOTATION_SCAN_LINES: usize = 50;
+
+/// Where an ownership entry came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnershipSource {
+    Codeowners,
+    Annotation,
+}
+
+impl OwnershipSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OwnershipSource::Codeowners => "codeowners",
+            OwnershipSource::Annotation => "annotation",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "codeowners" => Ok(OwnershipSource::Codeowners),
+            "annotation" => Ok(OwnershipSource::Annotation),
+            _ => Err(format!("Invalid ownership source: {}", s)),
+        }
+    }
+}
+
+/// Owners of one file or module directory (path relative to the project root)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OwnershipEntry {
+    pub path: PathBuf,
+    pub owners: Vec<String>,
+    pub source: OwnershipSource,
+}
+
+/// One CODEOWNERS rule; the last matching rule wins
+struct CodeownersRule {
+    matcher: Gitignore,
+    owners: Vec<String>,
+}
+
+/// Parsed CODEOWNERS file
+pub struct Codeowners {
+    rules: Vec<CodeownersRule>,
+}
+
+impl Codeowners {
+    /// Parse CODEOWNERS content; patterns follow gitignore rules relative to `root`
+    pub fn parse(root: &Path, content: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let pattern = match parts.next() {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+            let owners: Vec<String> = parts.take_while(|p| !p.starts_with('#')).map(String::from).collect();
+
+            let mut builder = GitignoreBuilder::new(root);
+            builder.add_line(None, pattern)
+                .with_context(|| format!("Invalid CODEOWNERS pattern: {}", pattern))?;
+            rules.push(CodeownersRule { matcher: builder.build()?, owners });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Load the first CODEOWNERS file found under `root`
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        for location in CODEOWNERS_LOCATIONS {
+            let path = root.join(location);
+            if path.is_file() {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                return Self::parse(root, &content).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Owners for a path relative to the root (empty owner lists explicitly unassign)
+    pub fn owners_for(&self, relative: &Path, is_dir: bool) -> Option<&[String]> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matcher.matched_path_or_any_parents(relative, is_dir).is_ignore())
+            .map(|rule| rule.owners.as_slice())
+    }
+}
+
+/// Extract owners from an `@owner` annotation, e.g. `// @owner alice, @bob` or `# @owner: team-core`
+pub fn parse_owner_annotation(content: &str) -> Option<Vec<String>> {
+    let pattern = Regex::new(r"@owners?:?\s+(.+)$").ok()?;
+
+    content.lines().take(ANNOTATION_SCAN_LINES).find_map(|line| {
+        let captures = pattern.captures(line.trim())?;
+        let owners: Vec<String> = captures[1]
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .map(String::from)
+            .collect();
+        if owners.is_empty() { None } else { Some(owners) }
+    })
+}
+
+/// Ownership map for a project: annotations override CODEOWNERS, and module-level
+/// annotations cover every file in their directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnershipMap {
+    pub entries: Vec<OwnershipEntry>,
+}
+
+impl OwnershipMap {
+    /// Scan a project for CODEOWNERS rules and `@owner` annotations (gitignore-aware)
+    pub fn scan(root: &Path) -> Result<Self> {
+        let codeowners = Codeowners::load(root)?;
+        let mut entries = Vec::new();
+
+        for entry in WalkBuilder::new(root).build() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                continue;
+            }
+
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+
+            let annotated = fs::read_to_string(path).ok().and_then(|c| parse_owner_annotation(&c));
+            if let Some(owners) = annotated {
+                let is_module = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| MODULE_FILES.contains(&n))
+                    .unwrap_or(false);
+                let owned_path = match (is_module, relative.parent()) {
+                    (true, Some(parent)) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                    _ => relative.clone(),
+                };
+                entries.push(OwnershipEntry { path: owned_path, owners, source: OwnershipSource::Annotation });
+                continue;
+            }
+
+            if let Some(owners) = codeowners.as_ref().and_then(|c| c.owners_for(&relative, false)) {
+                if !owners.is_empty() {
+                    entries.push(OwnershipEntry { path: relative, owners: owners.to_vec(), source: OwnershipSource::Codeowners });
+                }
+            }
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self { entries })
+    }
+
+    /// Owners of a path: the most specific entry for the path or one of its parent directories,
+    /// preferring annotations over CODEOWNERS
+    pub fn owners_for(&self, relative: &Path) -> Option<&OwnershipEntry> {
+        resolve_owner(&self.entries, relative)
+    }
+}
+
+/// Pick the entry owning `relative` out of `entries` (shared by the in-memory map and DB lookups)
+pub fn resolve_owner<'a>(entries: &'a [OwnershipEntry], relative: &Path) -> Option<&'a OwnershipEntry> {
+    entries
+        .iter()
+        .filter(|e| relative.starts_with(&e.path))
+        .max_by_key(|e| (e.source == OwnershipSource::Annotation, e.path.components().count()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_owner_annotation() {
+        assert_eq!(parse_owner_annotation("// @owner alice, @bob\nfn main() {}"), Some(vec!["alice".to_string(), "@bob".to_string()]));
+        assert_eq!(parse_owner_annotation("# @owners: team-core"), Some(vec!["team-core".to_string()]));
+        assert_eq!(parse_owner_annotation("/* @owner carol */"), Some(vec!["carol".to_string()]));
+        assert_eq!(parse_owner_annotation("fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_codeowners_last_match_wins() {
+        let root = Path::new("/repo");
+        let codeowners = Codeowners::parse(root, "# comment\n* @everyone\n/src/db/ @data-team\n*.md @docs\n").unwrap();
+
+        assert_eq!(codeowners.owners_for(Path::new("src/db/pool.rs"), false), Some(&["@data-team".to_string()][..]));
+        assert_eq!(codeowners.owners_for(Path::new("README.md"), false), Some(&["@docs".to_string()][..]));
+        assert_eq!(codeowners.owners_for(Path::new("src/main.rs"), false), Some(&["@everyone".to_string()][..]));
+    }
+
+    #[test]
+    fn test_scan_prefers_module_annotations() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/db")).unwrap();
+        fs::write(root.join("CODEOWNERS"), "* @everyone\n").unwrap();
+        fs::write(root.join("src/db/mod.rs"), "//! @owner @data-team\n").unwrap();
+        fs::write(root.join("src/db/pool.rs"), "fn pool() {}\n").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let map = OwnershipMap::scan(root).unwrap();
+
+        let pool = map.owners_for(Path::new("src/db/pool.rs")).unwrap();
+        assert_eq!(pool.owners, vec!["@data-team"]);
+        assert_eq!(pool.source, OwnershipSource::Annotation);
+
+        let main = map.owners_for(Path::new("src/main.rs")).unwrap();
+        assert_eq!(main.owners, vec!["@everyone"]);
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use log::info;
 
 use super::{CodeAnalyzer, SupportedLanguage};
@@ -145,6 +145,50 @@ impl AstSearchEngine {
     }
 }
 
+/// A line matching a `search_project` pattern
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CodeHit {
+    /// Relative to the searched root
+    pub file: PathBuf,
+    /// 1-based
+    pub line: usize,
+    pub snippet: String,
+}
+
+/// Search the supported source files under `root` (gitignore-aware) for `pattern`, only
+/// in `languages` when any are given. Stops after `max_results` hits; the flag returned
+/// says whether it did.
+pub fn search_project(root: &Path, pattern: &str, languages: &[SupportedLanguage], max_results: usize) -> Result<(Vec<CodeHit>, bool)> {
+    let mut hits = Vec::new();
+
+    for relative in super::tree::project_files(root)? {
+        let language = match relative.extension().and_then(|e| e.to_str()).and_then(SupportedLanguage::from_extension) {
+            Some(language) => language,
+            None => continue,
+        };
+        if !languages.is_empty() && !languages.contains(&language) {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(root.join(&relative)) else {
+            continue;
+        };
+
+        let matches = CodeAnalyzer::new(language).find_matches(&source, pattern)?;
+        // Matches come back in source order, so each is found at or after the previous one
+        let mut lines = source.lines().enumerate();
+        for matched in matches {
+            let Some((index, _)) = lines.by_ref().find(|(_, line)| *line == matched) else {
+                break;
+            };
+            if hits.len() == max_results {
+                return Ok((hits, true));
+            }
+            hits.push(CodeHit { file: relative.clone(), line: index + 1, snippet: matched.trim().to_string() });
+        }
+    }
+    Ok((hits, false))
+}
+
 /// Common AST patterns for different languages
 pub struct CommonPatterns;
 
@@ -236,6 +280,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_search_project() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        std::fs::create_dir_all(temp.path().join("src"))?;
+        std::fs::write(temp.path().join("src/lib.rs"), "fn a() {\n    todo!();\n}\n\nfn b() {\n    todo!();\n}\n")?;
+        std::fs::write(temp.path().join("app.py"), "def a():\n    todo!()\n")?;
+        std::fs::write(temp.path().join("notes.txt"), "todo!();\n")?;
+
+        let (hits, truncated) = search_project(temp.path(), "todo!", &[SupportedLanguage::Rust], 10)?;
+        assert!(!truncated);
+        assert_eq!(hits.iter().map(|h| h.line).collect::<Vec<_>>(), vec![2, 6]);
+        assert_eq!(hits[0].file, PathBuf::from("src/lib.rs"));
+        assert_eq!(hits[0].snippet, "todo!();");
+
+        let (hits, truncated) = search_project(temp.path(), "todo!", &[], 2)?;
+        assert!(truncated);
+        assert_eq!(hits[0].file, PathBuf::from("app.py"));
+        Ok(())
+    }
+
     #[test]
     fn test_common_patterns() {
         let rust_patterns = CommonPatterns::rust();
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A TODO/FIXME comment found in source (path relative to the scanned root)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TodoItem {
+    pub path: PathBuf,
+    pub line: usize,
+    pub tag: String,
+    pub text: String,
+}
+
+impl TodoItem {
+    /// Task category used when the item is turned into a task
+    pub fn task_category(&self) -> &'static str {
+        match self.tag.as_str() {
+            "FIXME" => "bug",
+            _ => "refactor",
+        }
+    }
+
+    /// `path:line` location
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.path.display(), self.line)
+    }
+}
+
+fn todo_pattern() -> Regex {
+    // Comment marker, tag, optional "(who)" and ":", then the text
+    Regex::new(r"(?://+|#+|/\*+|^\s*\*|--|<!--)\s*(TODO|FIXME)\b(?:\([^)]*\))?:?\s*(.*)").unwrap()
+}
+
+/// Find TODO/FIXME comments in a single file's content
+pub fn find_todos(relative: &Path, content: &str) -> Vec<TodoItem> {
+    let pattern = todo_pattern();
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let captures = pattern.captures(line)?;
+            let text = captures[2].trim().trim_end_matches("*/").trim_end_matches("-->").trim().to_string();
+            Some(TodoItem { path: relative.to_path_buf(), line: index + 1, tag: captures[1].to_string(), text })
+        })
+        .collect()
+}
+
+/// Scan a directory tree (gitignore-aware) for TODO/FIXME comments
+pub fn scan_todos(root: &Path, scan_path: &Path) -> Result<Vec<TodoItem>> {
+    let mut items = Vec::new();
+
+    for entry in WalkBuilder::new(scan_path).build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+
+        if let Ok(content) = fs::read_to_string(entry.path()) {
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            items.extend(find_todos(relative, &content));
+        }
+    }
+
+    items.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_todos() {
+        let content = "fn main() {\n    // TODO: handle errors\n    let x = 1; # FIXME(bob) overflow\n    let todo = \"TODO in a string\";\n}\n";
+        let items = find_todos(Path::new("src/main.rs"), content);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].tag, "TODO");
+        assert_eq!(items[0].text, "handle errors");
+        assert_eq!(items[0].location(), "src/main.rs:2");
+        assert_eq!(items[1].tag, "FIXME");
+        assert_eq!(items[1].text, "overflow");
+        assert_eq!(items[1].task_category(), "bug");
+    }
+}
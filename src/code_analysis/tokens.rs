@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::ops::Range;
+
+use super::SupportedLanguage;
+
+/// Lexical category of a span of source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    Code,
+    Comment,
+    String,
+}
+
+/// A comment or string literal, as a byte range of the source (delimiters included)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+}
+
+/// Comment and literal syntax of a language
+struct Syntax {
+    line_comment: &'static [u8],
+    block_comments: bool,
+    nested_block_comments: bool,
+    single_quote_strings: bool,
+    backtick_strings: bool,
+    triple_quote_strings: bool,
+    rust_literals: bool,
+}
+
+fn syntax(language: SupportedLanguage) -> Syntax {
+    let c_like = Syntax {
+        line_comment: b"//",
+        block_comments: true,
+        nested_block_comments: false,
+        single_quote_strings: true,
+        backtick_strings: false,
+        triple_quote_strings: false,
+        rust_literals: false,
+    };
+    match language {
+        SupportedLanguage::Rust => Syntax {
+            nested_block_comments: true,
+            single_quote_strings: false,
+            rust_literals: true,
+            ..c_like
+        },
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript | SupportedLanguage::Go => Syntax {
+            backtick_strings: true,
+            ..c_like
+        },
+        SupportedLanguage::Python => Syntax {
+            line_comment: b"#",
+            block_comments: false,
+            triple_quote_strings: true,
+            ..c_like
+        },
+        SupportedLanguage::Java | SupportedLanguage::C | SupportedLanguage::Cpp => c_like,
+    }
+}
+
+fn is_ident_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// End of a quoted literal starting at `start` (the opening quote), honouring backslash escapes.
+/// Unterminated literals run to the end of the source.
+fn quoted_end(bytes: &[u8], start: usize, quote: &[u8], escapes: bool) -> usize {
+    let mut i = start + quote.len();
+    while i < bytes.len() {
+        if escapes && bytes[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if bytes[i..].starts_with(quote) {
+            return i + quote.len();
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn block_comment_end(bytes: &[u8], start: usize, nested: bool) -> usize {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*") && (nested || depth == 0) {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Rust raw string (`r"…"`, `r#"…"#`, `br"…"`) starting at `i`: its end, if one starts here
+fn rust_raw_string_end(bytes: &[u8], i: usize) -> Option<usize> {
+    if i > 0 && is_ident_byte(bytes[i - 1]) {
+        return None;
+    }
+    let mut j = i;
+    if bytes.get(j) == Some(&b'b') {
+        j += 1;
+    }
+    if bytes.get(j) != Some(&b'r') {
+        return None;
+    }
+    j += 1;
+    let hashes = bytes[j..].iter().take_while(|b| **b == b'#').count();
+    j += hashes;
+    if bytes.get(j) != Some(&b'"') {
+        return None;
+    }
+    let mut close = vec![b'"'];
+    close.extend(std::iter::repeat(b'#').take(hashes));
+    Some(quoted_end(bytes, j, &close, false))
+}
+
+/// Rust char literal at `i` (`'a'`, `'\n'`, `'\u{1F600}'`); lifetimes and labels are not literals
+fn rust_char_end(source: &str, i: usize) -> Option<usize> {
+    let bytes = source.as_bytes();
+    if bytes.get(i + 1) == Some(&b'\\') {
+        return Some(quoted_end(bytes, i, b"'", true));
+    }
+    let ch = source[i + 1..].chars().next()?;
+    let after = i + 1 + ch.len_utf8();
+    (bytes.get(after) == Some(&b'\'')).then_some(after + 1)
+}
+
+/// Comments and string literals in `source`, in order. Everything between them is code.
+/// This is a lexer, not a parser: it is exact for ordinary source and degrades gracefully
+/// (an unterminated literal runs to the end of the file).
+pub fn tokenize(source: &str, language: SupportedLanguage) -> Vec<Token> {
+    let syntax = syntax(language);
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let (kind, end) = if rest.starts_with(syntax.line_comment) {
+            let end = rest.iter().position(|b| *b == b'\n').map(|p| i + p).unwrap_or(bytes.len());
+            (TokenKind::Comment, end)
+        } else if syntax.block_comments && rest.starts_with(b"/*") {
+            (TokenKind::Comment, block_comment_end(bytes, i, syntax.nested_block_comments))
+        } else if syntax.triple_quote_strings && (rest.starts_with(b"\"\"\"") || rest.starts_with(b"'''")) {
+            (TokenKind::String, quoted_end(bytes, i, &rest[..3], true))
+        } else if rest[0] == b'"' {
+            (TokenKind::String, quoted_end(bytes, i, b"\"", true))
+        } else if syntax.single_quote_strings && rest[0] == b'\'' {
+            (TokenKind::String, quoted_end(bytes, i, b"'", true))
+        } else if syntax.backtick_strings && rest[0] == b'`' {
+            (TokenKind::String, quoted_end(bytes, i, b"`", !matches!(language, SupportedLanguage::Go)))
+        } else if let Some(end) = syntax.rust_literals.then(|| rust_raw_string_end(bytes, i)).flatten() {
+            (TokenKind::String, end)
+        } else if let Some(end) = (syntax.rust_literals && rest[0] == b'\'').then(|| rust_char_end(source, i)).flatten() {
+            (TokenKind::String, end)
+        } else {
+            i += 1;
+            continue;
+        };
+        let end = end.min(bytes.len());
+        tokens.push(Token { kind, range: i..end });
+        i = end;
+    }
+
+    tokens
+}
+
+/// Category of the byte at `offset`, given the tokens of its source
+pub fn kind_at(tokens: &[Token], offset: usize) -> TokenKind {
+    let index = tokens.partition_point(|token| token.range.end <= offset);
+    match tokens.get(index) {
+        Some(token) if token.range.contains(&offset) => token.kind,
+        _ => TokenKind::Code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans<'a>(source: &'a str, language: SupportedLanguage) -> Vec<(TokenKind, &'a str)> {
+        tokenize(source, language).into_iter().map(|t| (t.kind, &source[t.range])).collect()
+    }
+
+    #[test]
+    fn test_rust_tokens() {
+        let source = "fn old<'a>(x: &'a str) -> char { /* old /* nested */ */ let s = \"old \\\"q\\\"\"; // old\n r#\"old\"# ; 'o' }";
+        let tokens = spans(source, SupportedLanguage::Rust);
+        assert_eq!(tokens, vec![
+            (TokenKind::Comment, "/* old /* nested */ */"),
+            (TokenKind::String, "\"old \\\"q\\\"\""),
+            (TokenKind::Comment, "// old"),
+            (TokenKind::String, "r#\"old\"#"),
+            (TokenKind::String, "'o'"),
+        ]);
+    }
+
+    #[test]
+    fn test_python_tokens() {
+        let source = "x = 'old'  # old\ndoc = \"\"\"old\n\"quoted\"\n\"\"\"\n";
+        let tokens = spans(source, SupportedLanguage::Python);
+        assert_eq!(tokens, vec![
+            (TokenKind::String, "'old'"),
+            (TokenKind::Comment, "# old"),
+            (TokenKind::String, "\"\"\"old\n\"quoted\"\n\"\"\""),
+        ]);
+    }
+
+    #[test]
+    fn test_kind_at() {
+        let source = "let old = `old ${x}`; // old";
+        let tokens = tokenize(source, SupportedLanguage::JavaScript);
+        assert_eq!(kind_at(&tokens, source.find("old").unwrap()), TokenKind::Code);
+        assert_eq!(kind_at(&tokens, source.find("`old").unwrap() + 1), TokenKind::String);
+        assert_eq!(kind_at(&tokens, source.rfind("old").unwrap()), TokenKind::Comment);
+        assert_eq!(kind_at(&tokens, source.len()), TokenKind::Code);
+    }
+}
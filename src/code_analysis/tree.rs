@@ -0,0 +1,77 @@
+use anyhow::Result;
+use ignore::WalkBuilder;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Files under `root` (gitignore-aware, hidden entries skipped), relative to it and sorted
+pub fn project_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in WalkBuilder::new(root).require_git(false).build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        files.push(entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf());
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Render sorted relative paths as an indented tree, directories suffixed with `/`
+pub fn render_tree(files: &[PathBuf]) -> String {
+    let mut out = String::new();
+    let mut previous: Vec<String> = Vec::new();
+
+    for file in files {
+        let parts: Vec<String> = file.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+        let Some((name, dirs)) = parts.split_last() else {
+            continue;
+        };
+        let shared = previous.iter().zip(dirs).take_while(|(a, b)| a == b).count();
+        for (depth, dir) in dirs.iter().enumerate().skip(shared) {
+            let _ = writeln!(out, "{}{}/", "  ".repeat(depth), dir);
+        }
+        let _ = writeln!(out, "{}{}", "  ".repeat(dirs.len()), name);
+        previous = dirs.to_vec();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_project_files_and_tree() -> Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+        fs::create_dir_all(root.join("src/net"))?;
+        fs::create_dir_all(root.join("target"))?;
+        fs::write(root.join(".gitignore"), "target/\n")?;
+        fs::write(root.join("README.md"), "")?;
+        fs::write(root.join("src/lib.rs"), "")?;
+        fs::write(root.join("src/net/http.rs"), "")?;
+        fs::write(root.join("src/util.rs"), "")?;
+        fs::write(root.join("target/out.bin"), "")?;
+
+        let files = project_files(root)?;
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("src/net/http.rs"),
+                PathBuf::from("src/util.rs"),
+            ]
+        );
+        assert_eq!(render_tree(&files), "README.md\nsrc/\n  lib.rs\n  net/\n    http.rs\n  util.rs\n");
+        Ok(())
+    }
+}
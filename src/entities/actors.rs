@@ -0,0 +1,303 @@
+// Actor Attribution - Who did what in a shared project database
+// The acting identity is resolved once per process (--as flag, WSB_ACTOR, the
+// `tools.actor` config, then the OS user) and stamped on sessions, task
+// completions and outbox events, so several people or agents can share one
+// database and still tell their work apart.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Environment variable naming the acting user or agent
+pub const ACTOR_ENV: &str = "WSB_ACTOR";
+
+/// Actor settings, stored under the `actor` tool key in `.wsb/state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActorConfig {
+    pub name: Option<String>,
+}
+
+/// Where an actor identity came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActorSource {
+    /// `--as <actor>` on the command line
+    Flag,
+    /// `WSB_ACTOR` (also set by the MCP server from the client identity)
+    Env,
+    /// `tools.actor.name` in `.wsb/state.json`
+    Config,
+    /// The operating system user
+    System,
+    Unknown,
+}
+
+impl ActorSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActorSource::Flag => "flag",
+            ActorSource::Env => "env",
+            ActorSource::Config => "config",
+            ActorSource::System => "system",
+            ActorSource::Unknown => "unknown",
+        }
+    }
+}
+
+/// The identity mutations are attributed to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    pub name: String,
+    pub source: ActorSource,
+}
+
+static CURRENT: Mutex<Option<Actor>> = Mutex::new(None);
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Resolve the actor: explicit name, then `WSB_ACTOR`, then project config, then the OS user
+pub fn resolve(explicit: Option<&str>, project_root: &Path) -> Actor {
+    if let Some(name) = non_empty(explicit.map(str::to_string)) {
+        return Actor { name, source: ActorSource::Flag };
+    }
+    if let Some(name) = non_empty(std::env::var(ACTOR_ENV).ok()) {
+        return Actor { name, source: ActorSource::Env };
+    }
+
+    if project_root.join(".wsb").join("state.json").exists() {
+        let configured = crate::workspace_state::WorkspaceState::peek(project_root)
+            .ok()
+            .and_then(|state| state.get_tool_config::<ActorConfig>("actor"))
+            .and_then(|config| non_empty(config.name));
+        if let Some(name) = configured {
+            return Actor { name, source: ActorSource::Config };
+        }
+    }
+
+    match non_empty(std::env::var("USER").ok()).or_else(|| non_empty(std::env::var("USERNAME").ok())) {
+        Some(name) => Actor { name, source: ActorSource::System },
+        None => Actor { name: "unknown".to_string(), source: ActorSource::Unknown },
+    }
+}
+
+/// Resolve and remember the actor for the rest of the process
+pub fn init(explicit: Option<&str>, project_root: &Path) -> Actor {
+    let actor = resolve(explicit, project_root);
+    *CURRENT.lock().unwrap() = Some(actor.clone());
+    actor
+}
+
+/// The process actor; resolved from the current directory if [`init`] was never called
+pub fn current() -> Actor {
+    let mut current = CURRENT.lock().unwrap();
+    if let Some(actor) = current.as_ref() {
+        return actor.clone();
+    }
+    let root = std::env::current_dir().unwrap_or_default();
+    let actor = resolve(None, &root);
+    *current = Some(actor.clone());
+    actor
+}
+
+/// Add a column to an existing table unless it is already there
+pub(crate) async fn ensure_column(pool: &SqlitePool, table: &str, column: &str, declaration: &str) -> Result<()> {
+    let present: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await?;
+
+    if present == 0 {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, declaration))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Create the actors table and the attribution columns on sessions, tasks and the event outbox
+pub async fn initialize_actor_tables(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS actors (
+            name TEXT PRIMARY KEY,
+            source TEXT NOT NULL,
+            first_seen TEXT NOT NULL,
+            last_seen TEXT NOT NULL
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    ensure_column(pool, "sessions", "actor", "TEXT").await?;
+    ensure_column(pool, "tasks", "completed_by", "TEXT").await?;
+    ensure_column(pool, "event_outbox", "actor", "TEXT").await?;
+
+    for index_sql in [
+        "CREATE INDEX IF NOT EXISTS idx_sessions_actor ON sessions (actor)",
+        "CREATE INDEX IF NOT EXISTS idx_tasks_completed_by ON tasks (completed_by)",
+        "CREATE INDEX IF NOT EXISTS idx_event_outbox_actor ON event_outbox (actor)",
+    ] {
+        sqlx::query(index_sql).execute(pool).await?;
+    }
+
+    Ok(())
+}
+
+/// Register the actor, or refresh when it was last seen, inside the caller's transaction
+pub async fn touch(tx: &mut Transaction<'_, Sqlite>, actor: &Actor) -> Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(r#"
+        INSERT INTO actors (name, source, first_seen, last_seen) VALUES (?, ?, ?, ?)
+        ON CONFLICT (name) DO UPDATE SET last_seen = excluded.last_seen, source = excluded.source
+    "#)
+    .bind(&actor.name)
+    .bind(actor.source.as_str())
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+/// A known actor with counts of their attributed work
+#[derive(Debug, Clone, Serialize)]
+pub struct ActorSummary {
+    pub name: String,
+    pub source: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub sessions: i64,
+    pub tasks_completed: i64,
+    pub events: i64,
+}
+
+/// All actors that have touched the database, most recently active first
+pub async fn list(pool: &SqlitePool) -> Result<Vec<ActorSummary>> {
+    let rows = sqlx::query(r#"
+        SELECT a.name, a.source, a.first_seen, a.last_seen,
+            (SELECT COUNT(*) FROM sessions s WHERE s.actor = a.name) AS sessions,
+            (SELECT COUNT(*) FROM tasks t WHERE t.completed_by = a.name) AS tasks_completed,
+            (SELECT COUNT(*) FROM event_outbox e WHERE e.actor = a.name) AS events
+        FROM actors a
+        ORDER BY a.last_seen DESC
+    "#)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ActorSummary {
+            name: row.get("name"),
+            source: row.get("source"),
+            first_seen: row.get("first_seen"),
+            last_seen: row.get("last_seen"),
+            sessions: row.get("sessions"),
+            tasks_completed: row.get("tasks_completed"),
+            events: row.get("events"),
+        })
+        .collect())
+}
+
+/// One attributed action
+#[derive(Debug, Clone, Serialize)]
+pub struct Activity {
+    pub actor: String,
+    pub kind: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub at: String,
+}
+
+/// Attributed actions newest first: sessions started, tasks completed and entity events,
+/// optionally limited to one actor
+pub async fn activity(pool: &SqlitePool, actor: Option<&str>, limit: u32) -> Result<Vec<Activity>> {
+    let rows = sqlx::query(r#"
+        SELECT actor, kind, entity_type, entity_id, at FROM (
+            SELECT actor, 'session.started' AS kind, 'session' AS entity_type, id AS entity_id, started_at AS at
+                FROM sessions WHERE actor IS NOT NULL
+            UNION ALL
+            SELECT completed_by, 'task.completed', 'task', id, COALESCE(completed_at, updated_at)
+                FROM tasks WHERE completed_by IS NOT NULL
+            UNION ALL
+            SELECT actor, event_type, entity_type, entity_id, created_at
+                FROM event_outbox WHERE actor IS NOT NULL
+        )
+        WHERE ?1 IS NULL OR actor = ?1
+        ORDER BY at DESC
+        LIMIT ?2
+    "#)
+    .bind(actor)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Activity {
+            actor: row.get("actor"),
+            kind: row.get("kind"),
+            entity_type: row.get("entity_type"),
+            entity_id: row.get("entity_id"),
+            at: row.get("at"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_explicit_actor_wins() {
+        let temp_dir = TempDir::new().unwrap();
+        let actor = resolve(Some("  alice "), temp_dir.path());
+        assert_eq!(actor, Actor { name: "alice".to_string(), source: ActorSource::Flag });
+    }
+
+    #[test]
+    fn test_config_actor() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = crate::workspace_state::WorkspaceState::initialize(temp_dir.path()).unwrap();
+        state.set_tool_config("actor", &ActorConfig { name: Some("build-bot".to_string()) }).unwrap();
+        state.save(temp_dir.path()).unwrap();
+
+        // The environment outranks config, so only check config when it is unset
+        if std::env::var(ACTOR_ENV).is_err() {
+            let actor = resolve(None, temp_dir.path());
+            assert_eq!(actor, Actor { name: "build-bot".to_string(), source: ActorSource::Config });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_attribution_columns_and_activity() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        // Idempotent on an already migrated database
+        initialize_actor_tables(&pool).await.unwrap();
+
+        let actor = Actor { name: "alice".to_string(), source: ActorSource::Flag };
+        let mut tx = pool.begin().await.unwrap();
+        touch(&mut tx, &actor).await.unwrap();
+        sqlx::query(r#"
+            INSERT INTO event_outbox (project_id, entity_type, entity_id, event_type, payload, next_attempt_at, created_at, actor)
+            VALUES ('P001', 'task', 'T000001', 'task.status_changed', '{}', '2026-01-01', '2026-01-01', 'alice')
+        "#)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        let actors = list(&pool).await.unwrap();
+        assert_eq!(actors.len(), 1);
+        assert_eq!(actors[0].events, 1);
+
+        assert_eq!(activity(&pool, Some("alice"), 10).await.unwrap().len(), 1);
+        assert!(activity(&pool, Some("bob"), 10).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+// Audit Trail - Field-level history of entity changes
+// Every create, update and delete made through the crud layer (and so through
+// EntityManager and EntityTransaction) writes to `entity_audit_trails` inside the same
+// transaction: one row per changed field for updates, one row carrying a JSON snapshot
+// for creates and purges. Rows are attributed to the process actor and the project's
+// active session. Old rows are removed by the `audit` retention policy.
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{Row, Sqlite, SqliteConnection, SqlitePool, Transaction};
+
+use crate::entities::actors;
+use crate::entities::retention::{self, RetentionAction, RetentionConfig, TableRetention};
+
+/// Name of the audit table's policy under `tools.retention`
+pub const RETENTION_TABLE: &str = "audit";
+
+/// Kind of change, stored in `operation_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOperation {
+    Create,
+    Update,
+    Delete,
+    StateChange,
+}
+
+impl AuditOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOperation::Create => "create",
+            AuditOperation::Update => "update",
+            AuditOperation::Delete => "delete",
+            AuditOperation::StateChange => "state_change",
+        }
+    }
+}
+
+/// One changed field; `field` is None for whole-entity snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl FieldChange {
+    pub fn new(field: &str, old_value: Option<&str>, new_value: Option<&str>) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            old_value: old_value.map(str::to_string),
+            new_value: new_value.map(str::to_string),
+        }
+    }
+
+    /// The whole entity before and/or after the change, as JSON
+    pub fn snapshot(old_value: Option<String>, new_value: Option<String>) -> Self {
+        Self { field: None, old_value, new_value }
+    }
+}
+
+/// A stored audit row
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub project_id: String,
+    pub operation: String,
+    pub field: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub actor: String,
+    pub session_id: Option<String>,
+    pub timestamp: String,
+}
+
+/// Fields that differ between two serialized versions of an entity. `updated_at`
+/// is left out since it changes on every write.
+pub fn diff<T: Serialize>(old: &T, new: &T) -> Result<Vec<FieldChange>> {
+    let old = serde_json::to_value(old)?;
+    let new = serde_json::to_value(new)?;
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        anyhow::bail!("Only objects can be diffed");
+    };
+
+    Ok(new
+        .iter()
+        .filter(|(field, value)| field.as_str() != "updated_at" && old.get(field.as_str()) != Some(value))
+        .map(|(field, value)| FieldChange {
+            field: Some(field.clone()),
+            old_value: old.get(field.as_str()).and_then(render),
+            new_value: render(value),
+        })
+        .collect())
+}
+
+fn render(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// JSON snapshot of a row, taken before it is purged. `table` must be one of the
+/// entity tables, never user input.
+pub async fn snapshot(conn: &mut SqliteConnection, table: &str, id: &str) -> Result<Option<String>> {
+    let columns: Vec<String> = sqlx::query(&format!("PRAGMA table_info({})", table))
+        .fetch_all(&mut *conn)
+        .await?
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .collect();
+    let pairs: Vec<String> = columns.iter().map(|c| format!("'{}', {}", c, c)).collect();
+
+    let snapshot = sqlx::query_scalar(&format!("SELECT json_object({}) FROM {} WHERE id = ?", pairs.join(", "), table))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(snapshot)
+}
+
+/// Record a change inside the caller's transaction so it commits atomically with it.
+/// Without field changes a single row is written for the operation.
+pub async fn record(
+    tx: &mut Transaction<'_, Sqlite>,
+    project_id: &str,
+    entity_type: &str,
+    entity_id: &str,
+    operation: AuditOperation,
+    changes: &[FieldChange],
+) -> Result<()> {
+    let actor = actors::current();
+    let now = chrono::Utc::now().to_rfc3339();
+    let session_id: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM sessions WHERE project_id = ? AND state = 'active' ORDER BY started_at DESC LIMIT 1"
+    )
+    .bind(project_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let whole = [FieldChange::snapshot(None, None)];
+    let changes = if changes.is_empty() { &whole[..] } else { changes };
+    for change in changes {
+        sqlx::query(r#"
+            INSERT INTO entity_audit_trails (id, entity_id, entity_type, project_id, operation_type, field_changed, old_value, new_value, triggered_by, session_id, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(format!("audit-{}", uuid::Uuid::new_v4()))
+        .bind(entity_id)
+        .bind(entity_type)
+        .bind(project_id)
+        .bind(operation.as_str())
+        .bind(&change.field)
+        .bind(&change.old_value)
+        .bind(&change.new_value)
+        .bind(&actor.name)
+        .bind(&session_id)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Audit rows, newest first, optionally narrowed to one entity and/or actor
+pub async fn list(pool: &SqlitePool, entity_id: Option<&str>, actor: Option<&str>, limit: u32) -> Result<Vec<AuditEntry>> {
+    let rows = sqlx::query(r#"
+        SELECT id, entity_type, entity_id, project_id, operation_type, field_changed, old_value, new_value, triggered_by, session_id, timestamp
+        FROM entity_audit_trails
+        WHERE (? IS NULL OR entity_id = ?) AND (? IS NULL OR triggered_by = ?)
+        ORDER BY timestamp DESC, rowid DESC
+        LIMIT ?
+    "#)
+    .bind(entity_id)
+    .bind(entity_id)
+    .bind(actor)
+    .bind(actor)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| AuditEntry {
+            id: row.get("id"),
+            entity_type: row.get("entity_type"),
+            entity_id: row.get("entity_id"),
+            project_id: row.get("project_id"),
+            operation: row.get("operation_type"),
+            field: row.get("field_changed"),
+            old_value: row.get("old_value"),
+            new_value: row.get("new_value"),
+            actor: row.get("triggered_by"),
+            session_id: row.get("session_id"),
+            timestamp: row.get("timestamp"),
+        })
+        .collect())
+}
+
+/// Delete audit rows older than `max_age_days`. Without `apply` nothing is deleted
+/// and the count says how many rows would be.
+pub async fn prune(pool: &SqlitePool, max_age_days: u32, apply: bool) -> Result<u64> {
+    let mut config = RetentionConfig::default();
+    config.tables.insert(RETENTION_TABLE.to_string(), TableRetention {
+        max_age_days: Some(max_age_days),
+        action: Some(RetentionAction::Delete),
+        ..Default::default()
+    });
+    let outcomes = retention::prune(pool, &config, apply).await?;
+    Ok(outcomes.iter().map(|outcome| outcome.rows).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use crate::entities::schema_models::TaskStatus;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_skips_unchanged_fields() {
+        let old = serde_json::json!({"name": "Parser", "state": "not_implemented", "notes": null, "updated_at": "a"});
+        let new = serde_json::json!({"name": "Parser", "state": "implemented_no_tests", "notes": "wip", "updated_at": "b"});
+        assert_eq!(
+            diff(&old, &new).unwrap(),
+            vec![
+                FieldChange::new("notes", None, Some("wip")),
+                FieldChange::new("state", Some("not_implemented"), Some("implemented_no_tests")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_crud_writes_are_audited() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Audit".to_string(), "Audit trail".to_string()).await.unwrap();
+        let feature = crud::features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let session = crud::sessions::create(&pool, project.id.clone(), "Work".to_string(), None).await.unwrap();
+        let task = crud::tasks::create(&pool, project.id.clone(), feature.id.clone(), "Write exporter".to_string(), "feature".to_string()).await.unwrap();
+        crud::tasks::update_status(&pool, &task.id, TaskStatus::InProgress).await.unwrap();
+        crud::tasks::purge(&pool, &task.id).await.unwrap();
+
+        let entries = list(&pool, Some(&task.id), None, 50).await.unwrap();
+        let operations: Vec<&str> = entries.iter().map(|e| e.operation.as_str()).collect();
+        assert_eq!(operations, vec!["delete", "state_change", "create"]);
+        assert_eq!(entries[1].field.as_deref(), Some("status"));
+        assert_eq!(entries[1].old_value.as_deref(), Some("pending"));
+        assert_eq!(entries[1].new_value.as_deref(), Some("in_progress"));
+        assert_eq!(entries[1].session_id.as_deref(), Some(session.id.as_str()));
+        assert!(entries[0].old_value.as_deref().unwrap().contains("Write exporter"));
+
+        sqlx::query("UPDATE entity_audit_trails SET timestamp = datetime('now', '-400 days') WHERE entity_id = ?")
+            .bind(&feature.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(prune(&pool, 90, false).await.unwrap(), 1);
+        assert_eq!(prune(&pool, 90, true).await.unwrap(), 1);
+        assert!(list(&pool, Some(&feature.id), None, 50).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+// Benchmarking harness for core EntityManager operations
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::{EntityManager, FeatureState, TaskStatus};
+
+/// Directory (under `.wsb`) holding the recorded baseline and run history
+pub const BENCH_DIR: &str = "bench";
+
+/// Size of the synthetic dataset and how often each operation is timed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BenchScale {
+    pub features: usize,
+    pub tasks_per_feature: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchScale {
+    fn default() -> Self {
+        Self { features: 100, tasks_per_feature: 10, iterations: 20 }
+    }
+}
+
+impl BenchScale {
+    pub fn total_tasks(&self) -> usize {
+        self.features * self.tasks_per_feature
+    }
+
+    /// Label used to match runs against a baseline recorded at the same scale
+    pub fn label(&self) -> String {
+        format!("{}f x {}t", self.features, self.tasks_per_feature)
+    }
+}
+
+/// Timing of one operation, in milliseconds
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperationTiming {
+    pub operation: String,
+    pub samples: usize,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub p95_ms: f64,
+}
+
+impl OperationTiming {
+    fn from_samples(operation: &str, mut samples: Vec<f64>) -> Self {
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let count = samples.len().max(1);
+        let p95_index = ((count as f64 * 0.95).ceil() as usize).clamp(1, count) - 1;
+
+        Self {
+            operation: operation.to_string(),
+            samples: samples.len(),
+            mean_ms: samples.iter().sum::<f64>() / count as f64,
+            min_ms: samples.first().copied().unwrap_or(0.0),
+            max_ms: samples.last().copied().unwrap_or(0.0),
+            p95_ms: samples.get(p95_index).copied().unwrap_or(0.0),
+        }
+    }
+}
+
+/// Results of a benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub scale: BenchScale,
+    pub created_at: String,
+    pub version: String,
+    pub timings: Vec<OperationTiming>,
+}
+
+impl BenchReport {
+    pub fn timing(&self, operation: &str) -> Option<&OperationTiming> {
+        self.timings.iter().find(|t| t.operation == operation)
+    }
+}
+
+/// Change in mean time for one operation relative to the baseline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimingComparison {
+    pub operation: String,
+    pub baseline_ms: f64,
+    pub current_ms: f64,
+    pub change_percent: f64,
+    pub regression: bool,
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Generate a synthetic dataset in `manager`'s database and time the core operations.
+/// The database should be a scratch one: the dataset is not cleaned up.
+pub async fn run(manager: &EntityManager, scale: BenchScale) -> Result<BenchReport> {
+    let iterations = scale.iterations.max(1);
+    let project = manager.create_project("Benchmark Project".to_string(), "Synthetic benchmark dataset".to_string()).await?;
+
+    // Dataset generation doubles as the create benchmarks
+    let mut create_feature = Vec::with_capacity(scale.features);
+    let mut create_task = Vec::with_capacity(scale.total_tasks());
+    let mut feature_ids = Vec::with_capacity(scale.features);
+    let mut task_ids = Vec::with_capacity(scale.total_tasks());
+
+    for f in 0..scale.features {
+        let start = Instant::now();
+        let feature = manager
+            .create_feature_full(project.id.clone(), format!("Feature {}", f), format!("Synthetic feature {}", f), None)
+            .await?;
+        create_feature.push(elapsed_ms(start));
+
+        for t in 0..scale.tasks_per_feature {
+            let start = Instant::now();
+            let task = manager
+                .create_task_full(project.id.clone(), feature.id.clone(), format!("Task {} of feature {}", t, f), "feature".to_string())
+                .await?;
+            create_task.push(elapsed_ms(start));
+            task_ids.push(task.id);
+        }
+        feature_ids.push(feature.id);
+    }
+
+    let mut timings = vec![
+        OperationTiming::from_samples("create_feature", create_feature),
+        OperationTiming::from_samples("create_task", create_task),
+    ];
+
+    let mut get_feature = Vec::new();
+    let mut get_task = Vec::new();
+    let mut list_features = Vec::new();
+    let mut list_tasks = Vec::new();
+    let mut list_tasks_by_status = Vec::new();
+    let mut update_feature_state = Vec::new();
+    let mut update_task_status = Vec::new();
+
+    for i in 0..iterations {
+        if let Some(id) = feature_ids.get(i % feature_ids.len().max(1)) {
+            let start = Instant::now();
+            manager.get_feature(id).await?;
+            get_feature.push(elapsed_ms(start));
+
+            let start = Instant::now();
+            manager.update_feature_state(id, FeatureState::ImplementedNoTests).await?;
+            update_feature_state.push(elapsed_ms(start));
+        }
+
+        if let Some(id) = task_ids.get(i % task_ids.len().max(1)) {
+            let start = Instant::now();
+            manager.get_task(id).await?;
+            get_task.push(elapsed_ms(start));
+
+            let start = Instant::now();
+            manager.update_task_status(id, TaskStatus::InProgress).await?;
+            update_task_status.push(elapsed_ms(start));
+        }
+
+        let start = Instant::now();
+        manager.list_features_by_project(&project.id).await?;
+        list_features.push(elapsed_ms(start));
+
+        let start = Instant::now();
+        manager.list_tasks_by_project(&project.id, None).await?;
+        list_tasks.push(elapsed_ms(start));
+
+        let start = Instant::now();
+        manager.list_tasks_by_project(&project.id, Some(TaskStatus::InProgress)).await?;
+        list_tasks_by_status.push(elapsed_ms(start));
+    }
+
+    for (operation, samples) in [
+        ("get_feature", get_feature),
+        ("get_task", get_task),
+        ("list_features", list_features),
+        ("list_tasks", list_tasks),
+        ("list_tasks_by_status", list_tasks_by_status),
+        ("update_feature_state", update_feature_state),
+        ("update_task_status", update_task_status),
+    ] {
+        if !samples.is_empty() {
+            timings.push(OperationTiming::from_samples(operation, samples));
+        }
+    }
+
+    Ok(BenchReport {
+        scale,
+        created_at: Utc::now().to_rfc3339(),
+        version: crate::get_version().to_string(),
+        timings,
+    })
+}
+
+/// Compare mean timings against a baseline; slowdowns beyond `threshold_percent` are regressions
+pub fn compare(baseline: &BenchReport, current: &BenchReport, threshold_percent: f64) -> Vec<TimingComparison> {
+    current
+        .timings
+        .iter()
+        .filter_map(|timing| {
+            let base = baseline.timing(&timing.operation)?;
+            let change_percent = if base.mean_ms > 0.0 {
+                (timing.mean_ms - base.mean_ms) / base.mean_ms * 100.0
+            } else {
+                0.0
+            };
+            Some(TimingComparison {
+                operation: timing.operation.clone(),
+                baseline_ms: base.mean_ms,
+                current_ms: timing.mean_ms,
+                change_percent,
+                regression: change_percent > threshold_percent,
+            })
+        })
+        .collect()
+}
+
+/// Baseline and history storage in `.wsb/bench/`
+pub struct BenchStore {
+    dir: PathBuf,
+}
+
+impl BenchStore {
+    pub fn new(wsb_dir: &Path) -> Self {
+        Self { dir: wsb_dir.join(BENCH_DIR) }
+    }
+
+    fn baseline_path(&self) -> PathBuf {
+        self.dir.join("baseline.json")
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.dir.join("history.jsonl")
+    }
+
+    /// Recorded baselines, one per scale
+    pub fn load_baselines(&self) -> Result<Vec<BenchReport>> {
+        let path = self.baseline_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read benchmark baseline {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse benchmark baseline {}", path.display()))
+    }
+
+    /// Baseline recorded at the same scale as `scale`
+    pub fn baseline_for(&self, scale: &BenchScale) -> Result<Option<BenchReport>> {
+        Ok(self.load_baselines()?.into_iter().find(|b| b.scale.label() == scale.label()))
+    }
+
+    /// Record `report` as the baseline for its scale, replacing any previous one
+    pub fn save_baseline(&self, report: &BenchReport) -> Result<()> {
+        let mut baselines = self.load_baselines()?;
+        baselines.retain(|b| b.scale.label() != report.scale.label());
+        baselines.push(report.clone());
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.baseline_path(), serde_json::to_string_pretty(&baselines)?)
+            .with_context(|| format!("Failed to write benchmark baseline {}", self.baseline_path().display()))
+    }
+
+    /// Append a run to the history log
+    pub fn append_history(&self, report: &BenchReport) -> Result<()> {
+        use std::io::Write;
+
+        fs::create_dir_all(&self.dir)?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.history_path())
+            .with_context(|| format!("Failed to open benchmark history {}", self.history_path().display()))?;
+        writeln!(file, "{}", serde_json::to_string(report)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(timings: &[(&str, f64)]) -> BenchReport {
+        BenchReport {
+            scale: BenchScale::default(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            version: "0.0.0".to_string(),
+            timings: timings.iter().map(|(op, ms)| OperationTiming::from_samples(op, vec![*ms])).collect(),
+        }
+    }
+
+    #[test]
+    fn test_timing_statistics() {
+        let timing = OperationTiming::from_samples("op", (1..=20).map(|v| v as f64).collect());
+        assert_eq!(timing.samples, 20);
+        assert_eq!(timing.mean_ms, 10.5);
+        assert_eq!(timing.min_ms, 1.0);
+        assert_eq!(timing.max_ms, 20.0);
+        assert_eq!(timing.p95_ms, 19.0);
+    }
+
+    #[test]
+    fn test_compare_flags_regressions() {
+        let baseline = report(&[("list_tasks", 10.0), ("get_task", 1.0)]);
+        let current = report(&[("list_tasks", 15.0), ("get_task", 1.1), ("new_op", 3.0)]);
+
+        let comparisons = compare(&baseline, &current, 20.0);
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons[0].regression);
+        assert_eq!(comparisons[0].change_percent, 50.0);
+        assert!(!comparisons[1].regression);
+    }
+
+    #[test]
+    fn test_baseline_per_scale() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = BenchStore::new(temp_dir.path());
+
+        let mut small = report(&[("get_task", 1.0)]);
+        small.scale.features = 10;
+        store.save_baseline(&small).unwrap();
+        store.save_baseline(&report(&[("get_task", 2.0)])).unwrap();
+        store.save_baseline(&report(&[("get_task", 3.0)])).unwrap();
+
+        assert_eq!(store.load_baselines().unwrap().len(), 2);
+        let baseline = store.baseline_for(&BenchScale::default()).unwrap().unwrap();
+        assert_eq!(baseline.timing("get_task").unwrap().mean_ms, 3.0);
+    }
+}
@@ -0,0 +1,277 @@
+// Rich Text Bodies - Long-form markdown bodies for features and tasks
+// The description stays the one-line summary; the body holds everything else and lives in
+// `entity_bodies`. Bodies are validated before they are stored: headings must fit under
+// the entity title, and links to entity IDs must point at entities that exist.
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+
+use super::schema_models::{EntityUtils, IdValidator};
+
+/// Shallowest heading a body may use; `#` is the entity title in show views and docs
+pub const MIN_HEADING_LEVEL: usize = 2;
+/// Deepest heading a body may use
+pub const MAX_HEADING_LEVEL: usize = 4;
+/// Largest body accepted, in bytes
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// A problem that keeps a body from being stored
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BodyIssue {
+    /// 1-based line of the problem; 0 for the body as a whole
+    pub line: usize,
+    pub message: String,
+}
+
+/// Entity types that carry a body, from the ID prefix
+pub fn entity_type(id: &str) -> Option<&'static str> {
+    if IdValidator::validate_feature_id(id) {
+        Some("feature")
+    } else if IdValidator::validate_task_id(id) {
+        Some("task")
+    } else {
+        None
+    }
+}
+
+/// Entity IDs referenced from `body`, with their lines: markdown links whose target is
+/// an ID (`[login](F00012)` or `[login](wsb:F00012)`) and wiki links (`[[F00012]]`).
+/// Fenced code blocks are skipped.
+pub fn entity_references(body: &str) -> Vec<(usize, String)> {
+    let link = regex::Regex::new(r"\]\((?:wsb:)?([A-Z]\d{3,6})\)|\[\[([A-Z]\d{3,6})\]\]").unwrap();
+    let mut references = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in body.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for captures in link.captures_iter(line) {
+            let id = captures.get(1).or_else(|| captures.get(2)).unwrap().as_str();
+            if EntityUtils::validate_any_entity_id(id) {
+                references.push((index + 1, id.to_string()));
+            }
+        }
+    }
+    references
+}
+
+/// Problems with `body`; `exists` says whether an entity ID is known
+pub fn validate(body: &str, exists: impl Fn(&str) -> bool) -> Vec<BodyIssue> {
+    let mut issues = Vec::new();
+    if body.len() > MAX_BODY_BYTES {
+        issues.push(BodyIssue { line: 0, message: format!("Body is {} bytes; the limit is {}", body.len(), MAX_BODY_BYTES) });
+    }
+
+    let mut in_fence = false;
+    for (index, line) in body.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let is_heading = level > 0 && line[level..].starts_with(' ');
+        if !is_heading {
+            continue;
+        }
+        if level < MIN_HEADING_LEVEL {
+            issues.push(BodyIssue {
+                line: index + 1,
+                message: format!("Heading level {} is reserved for the entity title; start at {}", level, "#".repeat(MIN_HEADING_LEVEL)),
+            });
+        } else if level > MAX_HEADING_LEVEL {
+            issues.push(BodyIssue {
+                line: index + 1,
+                message: format!("Heading level {} is deeper than {}", level, "#".repeat(MAX_HEADING_LEVEL)),
+            });
+        }
+    }
+
+    for (line, id) in entity_references(body) {
+        if !exists(&id) {
+            issues.push(BodyIssue { line, message: format!("Link to unknown entity {}", id) });
+        }
+    }
+    issues
+}
+
+/// Validation problems rendered one per line
+pub fn describe(issues: &[BodyIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| match issue.line {
+            0 => issue.message.clone(),
+            line => format!("line {}: {}", line, issue.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One-line preview of a body for list views: the first line of prose, cut to `width`
+/// characters, followed by how many more lines there are
+pub fn summary_line(body: &str, width: usize) -> String {
+    let lines: Vec<&str> = body.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    let first = lines
+        .iter()
+        .position(|line| !line.starts_with('#') && !line.starts_with("```"))
+        .unwrap_or(0);
+    let text = lines.get(first).map(|line| line.trim_start_matches(['-', '*', '>', ' '])).unwrap_or("");
+    let remaining = lines.len().saturating_sub(first + 1);
+    let suffix = if remaining > 0 { format!(" (+{} more lines)", remaining) } else { String::new() };
+
+    let budget = width.saturating_sub(suffix.chars().count()).max(1);
+    let text = if text.chars().count() > budget {
+        let cut: String = text.chars().take(budget.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    } else {
+        text.to_string()
+    };
+    format!("{}{}", text, suffix)
+}
+
+/// `body` with every heading made `by` levels deeper, for embedding under another heading
+pub fn shift_headings(body: &str, by: usize) -> String {
+    let mut in_fence = false;
+    let mut shifted: Vec<String> = Vec::new();
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if !in_fence && level > 0 && line[level..].starts_with(' ') {
+            shifted.push(format!("{}{}", "#".repeat(by), line));
+        } else {
+            shifted.push(line.to_string());
+        }
+    }
+    shifted.join("\n")
+}
+
+/// IDs of every entity a body may link to
+pub async fn known_ids(pool: &SqlitePool) -> Result<HashSet<String>> {
+    let rows = sqlx::query(r#"
+        SELECT id FROM projects
+        UNION ALL SELECT id FROM features
+        UNION ALL SELECT id FROM tasks
+        UNION ALL SELECT id FROM sessions
+        UNION ALL SELECT id FROM directives
+    "#)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|row| row.get::<String, _>("id")).collect())
+}
+
+/// Store the body of feature or task `id`, replacing any previous body. An empty body
+/// removes it. Fails, storing nothing, when the body does not validate.
+pub async fn set(pool: &SqlitePool, id: &str, body: &str) -> Result<()> {
+    let kind = entity_type(id).ok_or_else(|| anyhow::anyhow!("Only features and tasks have bodies, not {}", id))?;
+    if body.trim().is_empty() {
+        remove(pool, id).await?;
+        return Ok(());
+    }
+
+    let known = known_ids(pool).await?;
+    if !known.contains(id) {
+        anyhow::bail!("No {} {} in the project database", kind, id);
+    }
+    let issues = validate(body, |reference| known.contains(reference));
+    if !issues.is_empty() {
+        anyhow::bail!("Body for {} is not valid:\n{}", id, describe(&issues));
+    }
+
+    sqlx::query(r#"
+        INSERT INTO entity_bodies (entity_id, entity_type, body, updated_at) VALUES (?, ?, ?, ?)
+        ON CONFLICT (entity_id) DO UPDATE SET body = excluded.body, updated_at = excluded.updated_at
+    "#)
+    .bind(id)
+    .bind(kind)
+    .bind(body)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Body of `id`, if it has one
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT body FROM entity_bodies WHERE entity_id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|row| row.get("body")))
+}
+
+/// Every stored body, by entity ID
+pub async fn all(pool: &SqlitePool) -> Result<HashMap<String, String>> {
+    let rows = sqlx::query("SELECT entity_id, body FROM entity_bodies").fetch_all(pool).await?;
+    Ok(rows.into_iter().map(|row| (row.get("entity_id"), row.get("body"))).collect())
+}
+
+/// Remove the body of `id`; false when it had none
+pub async fn remove(pool: &SqlitePool, id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM entity_bodies WHERE entity_id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_heading_levels_and_links() {
+        let body = "# Title\n\n## Design\n\nSee [login](F00001) and [[T000009]].\n\n##### Too deep\n\n```\n# not a heading [x](F00002)\n```\n";
+        let issues = validate(body, |id| id == "F00001");
+        assert_eq!(issues, vec![
+            BodyIssue { line: 1, message: "Heading level 1 is reserved for the entity title; start at ##".to_string() },
+            BodyIssue { line: 7, message: "Heading level 5 is deeper than ####".to_string() },
+            BodyIssue { line: 5, message: "Link to unknown entity T000009".to_string() },
+        ]);
+        assert!(validate("#hashtag is not a heading\n[docs](https://example.com)", |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let body = "## Overview\n\nTokens are refreshed on every request so idle sessions expire.\n\n- second\n- third\n";
+        assert_eq!(summary_line(body, 200), "Tokens are refreshed on every request so idle sessions expire. (+2 more lines)");
+        let short = summary_line(body, 40);
+        assert_eq!(short.chars().count(), 40);
+        assert!(short.ends_with("… (+2 more lines)"));
+        assert_eq!(summary_line("Just one line", 80), "Just one line");
+        assert_eq!(shift_headings("## A\n```\n## code\n```\n### B", 2), "#### A\n```\n## code\n```\n##### B");
+    }
+
+    #[tokio::test]
+    async fn test_set_validates_against_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Bodies".to_string(), "Bodies project".to_string()).await.unwrap();
+        let feature = crud::features::create(&pool, project.id.clone(), "Login".to_string(), "Sign in".to_string(), None)
+            .await
+            .unwrap();
+
+        let error = set(&pool, &feature.id, "## Notes\nDepends on [[F00042]]").await.unwrap_err();
+        assert!(error.to_string().contains("Link to unknown entity F00042"));
+        assert_eq!(get(&pool, &feature.id).await.unwrap(), None);
+
+        let body = format!("## Notes\nOwned by project [[{}]]", project.id);
+        set(&pool, &feature.id, &body).await.unwrap();
+        assert_eq!(get(&pool, &feature.id).await.unwrap(), Some(body));
+
+        set(&pool, &feature.id, "  ").await.unwrap();
+        assert!(all(&pool).await.unwrap().is_empty());
+        assert!(set(&pool, "F09999", "## Notes").await.is_err());
+    }
+}
@@ -0,0 +1,270 @@
+// Project Bootstrap - The full init flow for a new ws-managed project in one call
+// Creates the workspace state, the project database and project row, starter directives
+// for the chosen profile, a starter st8 template and the git exclusions. Every step is
+// idempotent: anything already present is kept and left out of the summary.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::crud;
+use super::schema_models::{DirectiveCategory, Priority};
+use crate::st8::templates::TemplateManager;
+use crate::workspace_state::WorkspaceState;
+
+/// Paths kept out of git in every ws-managed project
+pub const GIT_EXCLUSIONS: &[&str] = &["CLAUDE.md", "internal/", ".claude/"];
+
+/// Kind of project being bootstrapped; picks the starter directives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    General,
+    Web,
+    Api,
+    Cli,
+    Library,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::General => "general",
+            Profile::Web => "web",
+            Profile::Api => "api",
+            Profile::Cli => "cli",
+            Profile::Library => "library",
+        }
+    }
+
+    /// Starter directives as (title, rule, category, priority)
+    fn directives(&self) -> Vec<(&'static str, &'static str, DirectiveCategory, Priority)> {
+        let mut directives = vec![(
+            "Tests before completion",
+            "A feature is only marked implemented_passing_tests once tests covering it pass",
+            DirectiveCategory::Testing,
+            Priority::High,
+        )];
+        directives.extend(match self {
+            Profile::General => vec![],
+            Profile::Web => vec![
+                ("Validate user input", "Validate and escape all user input on the server side", DirectiveCategory::Security, Priority::High),
+                ("Thin UI layer", "Keep business logic out of UI components", DirectiveCategory::Architecture, Priority::Medium),
+            ],
+            Profile::Api => vec![
+                ("Authenticated by default", "Every endpoint requires authentication unless explicitly public", DirectiveCategory::Security, Priority::High),
+                ("Versioned endpoints", "Breaking changes to an endpoint ship under a new API version", DirectiveCategory::Architecture, Priority::High),
+            ],
+            Profile::Cli => vec![
+                ("Exit codes", "Exit non-zero on failure and print errors to stderr", DirectiveCategory::Development, Priority::High),
+                ("Machine-readable output", "Commands that print data support --format json", DirectiveCategory::Development, Priority::Medium),
+            ],
+            Profile::Library => vec![
+                ("Semantic versioning", "No breaking change to the public API without a major version bump", DirectiveCategory::Quality, Priority::High),
+                ("Documented public API", "Every public item has a doc comment", DirectiveCategory::Quality, Priority::Medium),
+            ],
+        });
+        directives
+    }
+}
+
+impl FromStr for Profile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "general" => Ok(Profile::General),
+            "web" | "webapp" => Ok(Profile::Web),
+            "api" => Ok(Profile::Api),
+            "cli" => Ok(Profile::Cli),
+            "library" | "lib" => Ok(Profile::Library),
+            other => anyhow::bail!("Unknown profile '{}' (expected general, web, api, cli or library)", other),
+        }
+    }
+}
+
+/// A row created during bootstrap
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Created {
+    pub id: String,
+    pub title: String,
+}
+
+/// What `bootstrap` created; items that already existed are not listed
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapSummary {
+    pub project_root: PathBuf,
+    pub profile: Profile,
+    pub workspace_created: bool,
+    pub database: PathBuf,
+    pub database_created: bool,
+    pub project: Created,
+    pub project_created: bool,
+    pub directives: Vec<Created>,
+    pub templates: Vec<String>,
+    pub git_repository: bool,
+    pub git_exclusions: Vec<String>,
+}
+
+impl BootstrapSummary {
+    pub fn render(&self) -> String {
+        let mark = |created: bool| if created { "created" } else { "existing" };
+        let mut text = format!("Bootstrapped {} ({}) in {}\n", self.project.title, self.profile.as_str(), self.project_root.display());
+        text.push_str(&format!("  Workspace: .wsb ({})\n", mark(self.workspace_created)));
+        text.push_str(&format!("  Database: {} ({})\n", self.database.display(), mark(self.database_created)));
+        text.push_str(&format!("  Project: {} ({})\n", self.project.id, mark(self.project_created)));
+        for directive in &self.directives {
+            text.push_str(&format!("  Directive: {} {}\n", directive.id, directive.title));
+        }
+        for template in &self.templates {
+            text.push_str(&format!("  Template: {}\n", template));
+        }
+        if !self.git_repository {
+            text.push_str("  Git exclusions: skipped (not a git repository)\n");
+        } else if self.git_exclusions.is_empty() {
+            text.push_str("  Git exclusions: already present\n");
+        } else {
+            text.push_str(&format!("  Git exclusions: {}\n", self.git_exclusions.join(", ")));
+        }
+        text
+    }
+}
+
+/// Add the missing `GIT_EXCLUSIONS` to `.git/info/exclude` under `root`. Returns the
+/// entries added; `None` when `root` is not a git repository.
+pub fn exclude_from_git(root: &Path) -> Result<Option<Vec<String>>> {
+    let git_dir = root.join(".git");
+    if !git_dir.exists() {
+        return Ok(None);
+    }
+    let exclude_file = git_dir.join("info").join("exclude");
+    std::fs::create_dir_all(git_dir.join("info"))?;
+
+    let mut content = if exclude_file.exists() { std::fs::read_to_string(&exclude_file)? } else { String::new() };
+    let mut added = Vec::new();
+    for entry in GIT_EXCLUSIONS {
+        if content.lines().any(|line| line.trim() == *entry) {
+            continue;
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(entry);
+        content.push('\n');
+        added.push(entry.to_string());
+    }
+    if !added.is_empty() {
+        std::fs::write(&exclude_file, content)?;
+    }
+    Ok(Some(added))
+}
+
+/// Starter st8 template: a VERSION file kept in step with the git-derived version
+fn add_starter_template(state: &WorkspaceState) -> Result<Option<String>> {
+    let mut manager = TemplateManager::new(state)?;
+    if manager.get_template("version").is_some() {
+        return Ok(None);
+    }
+    manager.add_template("version", "{{ project.version }}\n", "VERSION", Some("Project version file".to_string()))?;
+    Ok(Some("version".to_string()))
+}
+
+/// Run the full init flow for project `name` at `root`
+pub async fn bootstrap(root: &Path, name: &str, description: Option<&str>, profile: Profile) -> Result<BootstrapSummary> {
+    crate::read_only::ensure_writable("bootstrap a project")?;
+    if name.trim().is_empty() {
+        anyhow::bail!("Project name is required");
+    }
+
+    let workspace_created = !root.join(".wsb").join("state.json").exists();
+    let mut state = if workspace_created { WorkspaceState::initialize(root)? } else { WorkspaceState::load(root)? };
+    if workspace_created || state.project_name.is_none() {
+        state.project_name = Some(name.to_string());
+        state.save(root)?;
+    }
+
+    let database = root.join(".wsb").join("project.db");
+    let database_created = !database.exists();
+    let pool = super::database::initialize_database(&database).await?;
+
+    let (project, project_created) = match super::workspace::current_project(&pool).await? {
+        Some(project) => (project, false),
+        None => {
+            let description = description.map(str::to_string).unwrap_or_else(|| format!("{} project", profile.as_str()));
+            (crud::projects::create(&pool, name.to_string(), description).await?, true)
+        }
+    };
+
+    let existing: Vec<String> = crud::directives::list_active_by_project(&pool, &project.id)
+        .await?
+        .into_iter()
+        .map(|d| d.title)
+        .collect();
+    let mut directives = Vec::new();
+    for (title, rule, category, priority) in profile.directives() {
+        if existing.iter().any(|t| t == title) {
+            continue;
+        }
+        let directive = crud::directives::create(&pool, project.id.clone(), title.to_string(), rule.to_string(), category, priority).await?;
+        directives.push(Created { id: directive.id, title: directive.title });
+    }
+    pool.close().await;
+
+    let templates = add_starter_template(&state)?.into_iter().collect();
+    let git_exclusions = exclude_from_git(root)?;
+
+    Ok(BootstrapSummary {
+        project_root: root.to_path_buf(),
+        profile,
+        workspace_created,
+        database,
+        database_created,
+        project: Created { id: project.id, title: project.name },
+        project_created,
+        directives,
+        templates,
+        git_repository: git_exclusions.is_some(),
+        git_exclusions: git_exclusions.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_profile_parsing() {
+        assert_eq!("CLI".parse::<Profile>().unwrap(), Profile::Cli);
+        assert_eq!("webapp".parse::<Profile>().unwrap(), Profile::Web);
+        assert!("mainframe".parse::<Profile>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_is_idempotent() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+
+        let first = bootstrap(root, "Tracker", None, Profile::Api).await.unwrap();
+        assert!(first.workspace_created && first.database_created && first.project_created);
+        assert_eq!(first.directives.len(), 3);
+        assert_eq!(first.templates, vec!["version".to_string()]);
+        assert_eq!(first.git_exclusions, vec!["CLAUDE.md", "internal/", ".claude/"]);
+        assert!(root.join(".wsb/templates/version.tera").exists());
+
+        let second = bootstrap(root, "Tracker", None, Profile::Api).await.unwrap();
+        assert!(!second.workspace_created && !second.database_created && !second.project_created);
+        assert_eq!(second.project, first.project);
+        assert!(second.directives.is_empty());
+        assert!(second.templates.is_empty());
+        assert!(second.git_repository && second.git_exclusions.is_empty());
+    }
+
+    #[test]
+    fn test_exclude_from_git_outside_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(exclude_from_git(temp_dir.path()).unwrap(), None);
+    }
+}
@@ -0,0 +1,356 @@
+// Action Brief - What to work on next, assembled from the project database
+// Shown by `wsb start`: ranked ready tasks, features one transition from completion,
+// overdue milestones and carried-over reminders, unresolved blockers, and notes
+// written since the last session ended.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+
+use super::schedule::{self, PlanTask};
+
+/// Number of entries shown per section
+pub const SECTION_LIMIT: usize = 5;
+
+/// An open task that can be started now, with its rank
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RankedTask {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub score: i64,
+    /// Open tasks waiting on this one
+    pub unblocks: usize,
+}
+
+/// A feature whose next state change completes it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FeatureStep {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    pub next_step: String,
+}
+
+/// Something past due or carried over from the previous session
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Reminder {
+    pub source: String,
+    pub text: String,
+    pub due: Option<String>,
+}
+
+/// A blocked task and the open tasks it waits on
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Blocker {
+    pub id: String,
+    pub title: String,
+    pub waiting_on: Vec<String>,
+}
+
+/// A note written since the last session ended
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NoteRef {
+    pub id: String,
+    pub title: String,
+    pub note_type: String,
+    pub entity_id: Option<String>,
+}
+
+/// Everything `wsb start` suggests acting on
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ActionBrief {
+    pub ready_tasks: Vec<RankedTask>,
+    pub near_complete_features: Vec<FeatureStep>,
+    pub reminders: Vec<Reminder>,
+    pub blockers: Vec<Blocker>,
+    pub unreviewed_notes: Vec<NoteRef>,
+}
+
+impl ActionBrief {
+    pub fn is_empty(&self) -> bool {
+        self.ready_tasks.is_empty()
+            && self.near_complete_features.is_empty()
+            && self.reminders.is_empty()
+            && self.blockers.is_empty()
+            && self.unreviewed_notes.is_empty()
+    }
+}
+
+/// Open task with the fields the ranking needs
+#[derive(Debug, Clone)]
+pub struct TaskFacts {
+    pub task: PlanTask,
+    pub priority: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+pub fn priority_weight(priority: &str) -> i64 {
+    match priority {
+        "critical" => 40,
+        "high" => 30,
+        "medium" => 20,
+        "low" => 10,
+        _ => 15,
+    }
+}
+
+/// Score a ready task: priority, plus 10 per task it unblocks, plus 15 when already
+/// in progress, plus one point per three days of age (capped at 30 days)
+pub fn score(priority: &str, unblocks: usize, in_progress: bool, age_days: i64) -> i64 {
+    priority_weight(priority)
+        + 10 * unblocks as i64
+        + if in_progress { 15 } else { 0 }
+        + age_days.clamp(0, 30) / 3
+}
+
+/// Rank tasks whose prerequisites are all closed; blocked tasks are never ready
+pub fn rank_ready_tasks(tasks: &[TaskFacts], now: DateTime<Utc>) -> Vec<RankedTask> {
+    let open: HashSet<&str> = tasks.iter().map(|t| t.task.id.as_str()).collect();
+    let mut unblocks: HashMap<&str, usize> = HashMap::new();
+    for facts in tasks {
+        for prerequisite in &facts.task.depends_on {
+            *unblocks.entry(prerequisite.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut ranked: Vec<RankedTask> = tasks
+        .iter()
+        .filter(|facts| facts.task.status != "blocked")
+        .filter(|facts| facts.task.depends_on.iter().all(|d| !open.contains(d.as_str())))
+        .map(|facts| {
+            let unblocks = unblocks.get(facts.task.id.as_str()).copied().unwrap_or(0);
+            let age_days = facts.created_at.map(|c| (now - c).num_days()).unwrap_or(0);
+            RankedTask {
+                id: facts.task.id.clone(),
+                title: facts.task.title.clone(),
+                status: facts.task.status.clone(),
+                priority: facts.priority.clone(),
+                score: score(&facts.priority, unblocks, facts.task.status == "in_progress", age_days),
+                unblocks,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+    ranked
+}
+
+/// Blocked tasks (by status or by open prerequisites they are flagged against)
+pub fn find_blockers(tasks: &[TaskFacts]) -> Vec<Blocker> {
+    let open: HashSet<&str> = tasks.iter().map(|t| t.task.id.as_str()).collect();
+    tasks
+        .iter()
+        .filter(|facts| facts.task.status == "blocked")
+        .map(|facts| Blocker {
+            id: facts.task.id.clone(),
+            title: facts.task.title.clone(),
+            waiting_on: facts.task.depends_on.iter().filter(|d| open.contains(d.as_str())).cloned().collect(),
+        })
+        .collect()
+}
+
+/// The step that moves a feature to `implemented_passing_tests`, if it is one step away
+pub fn completion_step(state: &str) -> Option<&'static str> {
+    match state {
+        "implemented_no_tests" => Some("add tests"),
+        "implemented_failing_tests" => Some("fix failing tests"),
+        "tests_broken" => Some("repair broken tests"),
+        _ => None,
+    }
+}
+
+/// Parse a stored timestamp: RFC 3339, or SQLite's `datetime('now')` format
+pub fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive| DateTime::from_naive_utc_and_offset(naive, Utc))
+}
+
+async fn load_task_facts(pool: &SqlitePool, project_id: &str) -> Result<Vec<TaskFacts>> {
+    let tasks = schedule::load_open_tasks(pool, project_id).await?;
+    let rows = sqlx::query(r#"
+        SELECT id, priority, created_at FROM tasks
+        WHERE project_id = ? AND status NOT IN ('completed', 'cancelled') AND deleted_at IS NULL
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    let details: HashMap<String, (String, String)> = rows
+        .iter()
+        .map(|row| (row.get("id"), (row.get("priority"), row.get("created_at"))))
+        .collect();
+
+    Ok(tasks
+        .into_iter()
+        .map(|task| {
+            let (priority, created_at) = details.get(&task.id).cloned().unwrap_or_default();
+            TaskFacts { task, priority, created_at: parse_timestamp(&created_at) }
+        })
+        .collect())
+}
+
+/// Assemble the brief for a project as of `now`
+pub async fn load(pool: &SqlitePool, project_id: &str, now: DateTime<Utc>) -> Result<ActionBrief> {
+    let tasks = load_task_facts(pool, project_id).await?;
+    let mut ready_tasks = rank_ready_tasks(&tasks, now);
+    ready_tasks.truncate(SECTION_LIMIT);
+    let blockers = find_blockers(&tasks);
+
+    let feature_rows = sqlx::query(r#"
+        SELECT id, name, state, priority FROM features
+        WHERE project_id = ? AND state IN ('implemented_no_tests', 'implemented_failing_tests', 'tests_broken') AND deleted_at IS NULL
+        ORDER BY id
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    let mut features: Vec<(i64, FeatureStep)> = feature_rows
+        .iter()
+        .filter_map(|row| {
+            let state: String = row.get("state");
+            let step = completion_step(&state)?;
+            Some((priority_weight(&row.get::<String, _>("priority")), FeatureStep {
+                id: row.get("id"),
+                name: row.get("name"),
+                state,
+                next_step: step.to_string(),
+            }))
+        })
+        .collect();
+    features.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+    let near_complete_features: Vec<FeatureStep> = features.into_iter().map(|(_, f)| f).take(SECTION_LIMIT).collect();
+
+    let mut reminders = Vec::new();
+    let today = now.format("%Y-%m-%d").to_string();
+    let overdue = sqlx::query(r#"
+        SELECT id, title, target_date FROM milestones
+        WHERE project_id = ? AND status IN ('planned', 'in_progress')
+            AND target_date IS NOT NULL AND substr(target_date, 1, 10) < ?
+        ORDER BY target_date
+    "#)
+    .bind(project_id)
+    .bind(&today)
+    .fetch_all(pool)
+    .await?;
+    for row in &overdue {
+        reminders.push(Reminder {
+            source: row.get("id"),
+            text: format!("Milestone overdue: {}", row.get::<String, _>("title")),
+            due: row.get("target_date"),
+        });
+    }
+
+    // The last ended session bounds what counts as unreviewed and may leave a reminder
+    let last_session = sqlx::query(r#"
+        SELECT id, reminder, ended_at FROM sessions
+        WHERE project_id = ? AND ended_at IS NOT NULL
+        ORDER BY ended_at DESC LIMIT 1
+    "#)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+    let mut reviewed_until: Option<String> = None;
+    if let Some(session) = &last_session {
+        if let Some(reminder) = session.get::<Option<String>, _>("reminder").filter(|r| !r.trim().is_empty()) {
+            reminders.push(Reminder { source: session.get("id"), text: reminder, due: None });
+        }
+        reviewed_until = session.get("ended_at");
+    }
+
+    let note_rows = sqlx::query(r#"
+        SELECT id, title, note_type, entity_id, created_at FROM notes
+        WHERE project_id = ?
+        ORDER BY created_at DESC
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    let reviewed_until = reviewed_until.as_deref().and_then(parse_timestamp);
+    let unreviewed_notes = note_rows
+        .iter()
+        .filter(|row| match (reviewed_until, parse_timestamp(&row.get::<String, _>("created_at"))) {
+            (Some(until), Some(created)) => created > until,
+            _ => reviewed_until.is_none(),
+        })
+        .take(SECTION_LIMIT)
+        .map(|row| NoteRef {
+            id: row.get("id"),
+            title: row.get("title"),
+            note_type: row.get("note_type"),
+            entity_id: row.get("entity_id"),
+        })
+        .collect();
+
+    Ok(ActionBrief { ready_tasks, near_complete_features, reminders, blockers, unreviewed_notes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn facts(id: &str, status: &str, priority: &str, depends_on: &[&str], age_days: i64) -> TaskFacts {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        TaskFacts {
+            task: PlanTask {
+                id: id.to_string(),
+                title: format!("Task {}", id),
+                status: status.to_string(),
+                estimate: None,
+                depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+                feature_ids: Vec::new(),
+            },
+            priority: priority.to_string(),
+            created_at: Some(now - chrono::Duration::days(age_days)),
+        }
+    }
+
+    #[test]
+    fn test_ready_tasks_are_ranked() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let tasks = vec![
+            facts("T000001", "pending", "low", &[], 0),
+            facts("T000002", "pending", "medium", &[], 0),
+            facts("T000003", "pending", "high", &["T000002"], 0),
+            facts("T000004", "in_progress", "medium", &[], 0),
+            facts("T000005", "blocked", "critical", &[], 0),
+            // Depends on a task that is no longer open, so it is ready
+            facts("T000006", "pending", "low", &["T000099"], 30),
+        ];
+
+        let ranked = rank_ready_tasks(&tasks, now);
+        let ids: Vec<&str> = ranked.iter().map(|t| t.id.as_str()).collect();
+        // T000002 unblocks T000003 (20 + 10); T000004 is in progress (20 + 15)
+        assert_eq!(ids, vec!["T000004", "T000002", "T000006", "T000001"]);
+        assert_eq!(ranked[1].unblocks, 1);
+        assert_eq!(ranked[2].score, 10 + 10);
+    }
+
+    #[test]
+    fn test_blockers_list_open_prerequisites() {
+        let tasks = vec![
+            facts("T000001", "pending", "medium", &[], 0),
+            facts("T000002", "blocked", "high", &["T000001", "T000050"], 0),
+        ];
+        let blockers = find_blockers(&tasks);
+        assert_eq!(blockers.len(), 1);
+        assert_eq!(blockers[0].waiting_on, vec!["T000001".to_string()]);
+    }
+
+    #[test]
+    fn test_completion_step_and_timestamps() {
+        assert_eq!(completion_step("implemented_no_tests"), Some("add tests"));
+        assert_eq!(completion_step("not_implemented"), None);
+        assert_eq!(completion_step("implemented_passing_tests"), None);
+
+        assert!(parse_timestamp("2026-03-01T12:00:00+00:00").is_some());
+        assert!(parse_timestamp("2026-03-01 12:00:00").is_some());
+        assert!(parse_timestamp("yesterday").is_none());
+    }
+}
@@ -0,0 +1,298 @@
+// Calendar Export - The project plan as an iCalendar (RFC 5545) file
+// Task due dates, milestone target dates and recurring tasks become all-day events
+// that ordinary calendar apps can import or subscribe to. Due dates and repeat rules
+// are stored on tasks (`wsb task schedule`); milestones use their target date.
+// Recurring tasks are exported as one event with an RRULE, starting at the first
+// occurrence inside the export window.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+use super::actors::ensure_column;
+use super::recurrence::Recurrence;
+
+/// Category of milestone events in per-category calendars
+pub const MILESTONE_CATEGORY: &str = "milestone";
+
+/// Add the scheduling columns to tasks
+pub async fn initialize_calendar_columns(pool: &SqlitePool) -> Result<()> {
+    ensure_column(pool, "tasks", "due_date", "TEXT").await?;
+    ensure_column(pool, "tasks", "repeat", "TEXT").await?;
+    Ok(())
+}
+
+/// A `YYYY-MM-DD` date, or the date part of a timestamp
+pub fn parse_date(value: &str) -> Result<NaiveDate> {
+    let date = value.trim().get(..10).unwrap_or(value.trim());
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").with_context(|| format!("Invalid date '{}' (expected YYYY-MM-DD)", value))
+}
+
+/// Set or clear a task's due date and repeat rule. A repeat rule needs a due date to start
+/// from, and belongs on the template of a recurring series rather than on its instances.
+pub async fn schedule_task(pool: &SqlitePool, task_id: &str, due: Option<NaiveDate>, repeat: Option<&str>, clear: bool) -> Result<()> {
+    let row = sqlx::query("SELECT due_date, recurrence_parent FROM tasks WHERE id = ?")
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", task_id))?;
+
+    if clear {
+        sqlx::query("UPDATE tasks SET due_date = NULL, repeat = NULL, updated_at = ? WHERE id = ?")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(task_id)
+            .execute(pool)
+            .await?;
+        return Ok(());
+    }
+
+    let due = match due {
+        Some(due) => due.format("%Y-%m-%d").to_string(),
+        None => match row.get::<Option<String>, _>("due_date") {
+            Some(existing) => existing,
+            None if repeat.is_some() => anyhow::bail!("Task {} has no due date; pass --due to start the repetition", task_id),
+            None => anyhow::bail!("Nothing to schedule; pass --due, --repeat or --clear"),
+        },
+    };
+    let repeat = repeat.map(Recurrence::parse).transpose()?.map(|rule| rule.to_string());
+    if let (Some(_), Some(template)) = (&repeat, row.get::<Option<String>, _>("recurrence_parent")) {
+        anyhow::bail!("Task {} is an instance of recurring task {}; set the repeat rule there", task_id, template);
+    }
+
+    sqlx::query("UPDATE tasks SET due_date = ?, repeat = COALESCE(?, repeat), updated_at = ? WHERE id = ?")
+        .bind(&due)
+        .bind(repeat)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// One all-day entry in the exported calendar
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: String,
+    /// Task category, or `milestone`
+    pub category: String,
+    pub repeat: Option<String>,
+    pub cancelled: bool,
+}
+
+impl CalendarEvent {
+    /// The event moved to its first occurrence within `since..=until`, or `None` if it
+    /// has none. Without a repeat rule the only occurrence is the event date.
+    pub fn within(&self, since: Option<NaiveDate>, until: Option<NaiveDate>) -> Option<CalendarEvent> {
+        let date = match (&self.repeat, since) {
+            (Some(repeat), Some(since)) if self.date < since => Recurrence::parse(repeat).ok()?.next_on_or_after(self.date, since)?,
+            _ => self.date,
+        };
+        if since.map_or(false, |since| date < since) || until.map_or(false, |until| date > until) {
+            return None;
+        }
+        Some(CalendarEvent { date, ..self.clone() })
+    }
+}
+
+/// Every scheduled task and dated milestone of the project. Instances of a recurring
+/// task are left out: the template's event repeats over their dates.
+pub async fn load_events(pool: &SqlitePool, project_id: &str) -> Result<Vec<CalendarEvent>> {
+    let mut events = Vec::new();
+
+    let tasks = sqlx::query(r#"
+        SELECT id, title, description, category, status, priority, due_date, repeat FROM tasks
+        WHERE project_id = ? AND due_date IS NOT NULL AND recurrence_parent IS NULL AND deleted_at IS NULL
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    for row in tasks {
+        let id: String = row.get("id");
+        let status: String = row.get("status");
+        let due: String = row.get("due_date");
+        events.push(CalendarEvent {
+            uid: format!("{}@{}.wsb", id, project_id),
+            date: parse_date(&due).with_context(|| format!("Task {} has an invalid due date", id))?,
+            summary: format!("{}: {}", id, row.get::<String, _>("title")),
+            description: format!(
+                "{}\n\nStatus: {}, priority: {}",
+                row.get::<String, _>("description"), status, row.get::<String, _>("priority")
+            ),
+            category: row.get("category"),
+            repeat: row.get("repeat"),
+            cancelled: status == "cancelled",
+        });
+    }
+
+    let milestones = sqlx::query(r#"
+        SELECT id, title, description, status, completion_percentage, target_date FROM milestones
+        WHERE project_id = ? AND target_date IS NOT NULL
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    for row in milestones {
+        let id: String = row.get("id");
+        let status: String = row.get("status");
+        let target: String = row.get("target_date");
+        events.push(CalendarEvent {
+            uid: format!("{}@{}.wsb", id, project_id),
+            date: parse_date(&target).with_context(|| format!("Milestone {} has an invalid target date", id))?,
+            summary: format!("Milestone: {}", row.get::<String, _>("title")),
+            description: format!(
+                "{}\n\nStatus: {}, {:.0}% complete",
+                row.get::<String, _>("description"), status, row.get::<f64, _>("completion_percentage")
+            ),
+            category: MILESTONE_CATEGORY.to_string(),
+            repeat: None,
+            cancelled: status == "cancelled",
+        });
+    }
+
+    events.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.uid.cmp(&b.uid)));
+    Ok(events)
+}
+
+/// Text value with the characters RFC 5545 reserves escaped
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// A content line folded to 75 octets, without splitting characters
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(ch);
+        width += ch.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// The iCalendar document for `events`; `until` bounds repeating events
+pub fn render(name: &str, events: &[CalendarEvent], until: Option<NaiveDate>, now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//wsb//Project Calendar//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", (event.date + Duration::days(1)).format("%Y%m%d")));
+        if let Some(rule) = event.repeat.as_deref().and_then(|repeat| Recurrence::parse(repeat).ok()) {
+            let mut rule = format!("RRULE:{}", rule.rrule());
+            if let Some(until) = until {
+                rule.push_str(&format!(";UNTIL={}", until.format("%Y%m%d")));
+            }
+            lines.push(rule);
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push(format!("CATEGORIES:{}", escape(&event.category)));
+        if event.cancelled {
+            lines.push("STATUS:CANCELLED".to_string());
+        }
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    fn event(day: &str, repeat: Option<&str>) -> CalendarEvent {
+        CalendarEvent {
+            uid: "T000001@P001.wsb".to_string(),
+            date: date(day),
+            summary: "T000001: Ship, finally; done".to_string(),
+            description: "Line one\nLine two".to_string(),
+            category: "feature".to_string(),
+            repeat: repeat.map(|r| r.to_string()),
+            cancelled: false,
+        }
+    }
+
+    #[test]
+    fn test_within_window() {
+        let once = event("2026-03-10", None);
+        assert!(once.within(Some(date("2026-03-01")), Some(date("2026-03-31"))).is_some());
+        assert!(once.within(Some(date("2026-03-11")), None).is_none());
+        assert!(once.within(None, Some(date("2026-03-09"))).is_none());
+
+        let weekly = event("2026-03-02", Some("weekly"));
+        assert_eq!(weekly.within(Some(date("2026-03-10")), None).unwrap().date, date("2026-03-16"));
+        assert!(weekly.within(Some(date("2026-03-10")), Some(date("2026-03-15"))).is_none());
+
+        let monthly = event("2026-01-31", Some("monthly"));
+        assert_eq!(monthly.within(Some(date("2026-02-15")), None).unwrap().date, date("2026-02-28"));
+    }
+
+    #[test]
+    fn test_render_ics() {
+        let now = date("2026-03-01").and_hms_opt(12, 0, 0).unwrap().and_local_timezone(Utc).unwrap();
+        let ics = render("Demo", &[event("2026-03-10", Some("weekly"))], Some(date("2026-06-30")), now);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260310\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20260311\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;UNTIL=20260630\r\n"));
+        assert!(ics.contains("SUMMARY:T000001: Ship\\, finally\\; done\r\n"));
+        assert!(ics.contains("DESCRIPTION:Line one\\nLine two\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+
+        let long = fold(&format!("SUMMARY:{}", "é".repeat(60)));
+        assert!(long.split("\r\n").all(|line| line.len() <= 75));
+    }
+
+    #[tokio::test]
+    async fn test_schedule_and_load_events() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        let project = crate::entities::crud::projects::create(&pool, "Calendar".to_string(), "Calendar project".to_string()).await.unwrap();
+        let task = crate::entities::crud::tasks::create(&pool, project.id.clone(), "F00001".to_string(), "Weekly sync".to_string(), "feature".to_string()).await.unwrap();
+
+        assert!(schedule_task(&pool, &task.id, None, Some("weekly"), false).await.is_err());
+        schedule_task(&pool, &task.id, Some(date("2026-03-02")), Some("weekly"), false).await.unwrap();
+        assert!(schedule_task(&pool, &task.id, None, Some("hourly"), false).await.is_err());
+        sqlx::query("INSERT INTO milestones (id, project_id, title, description, target_date) VALUES ('M1', ?, 'Beta', 'Beta release', '2026-04-30T23:59:59Z')")
+            .bind(&project.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let events = load_events(&pool, &project.id).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].repeat.as_deref(), Some("weekly"));
+        assert_eq!(events[1].category, MILESTONE_CATEGORY);
+        assert_eq!(events[1].date, date("2026-04-30"));
+
+        schedule_task(&pool, &task.id, None, None, true).await.unwrap();
+        assert_eq!(load_events(&pool, &project.id).await.unwrap().len(), 1);
+    }
+}
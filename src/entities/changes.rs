@@ -0,0 +1,199 @@
+// Change Notifications - In-process broadcast of committed entity mutations
+// Each EntityManager owns a feed and announces its mutations on it after they commit.
+// The MCP server also relays the event outbox on an interval, so changes made by other
+// `wsb` processes (the CLI, MCP tools shelling out to it) reach subscribers too. Outbox
+// rows are published once per feed, tracked by a watermark on the outbox id; mutations
+// that record no outbox row are published directly. Nothing is read or sent while nobody
+// is subscribed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tokio::sync::{broadcast, Mutex};
+
+/// Changes a slow subscriber may fall behind by before it starts missing them
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A committed change to an entity
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntityChange {
+    /// Outbox event id, when the mutation recorded one
+    pub seq: Option<i64>,
+    pub project_id: Option<String>,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// e.g. `task.status_changed`
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub actor: Option<String>,
+    pub timestamp: String,
+}
+
+/// Broadcast feed of committed changes to one database. Clones share subscribers.
+#[derive(Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<EntityChange>,
+    /// Highest outbox id already published; None until someone subscribes
+    watermark: Arc<Mutex<Option<i64>>>,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChangeFeed {
+    pub fn new() -> Self {
+        Self { sender: broadcast::channel(CHANNEL_CAPACITY).0, watermark: Arc::new(Mutex::new(None)) }
+    }
+
+    /// Receive every change committed from now on. Older outbox events are not replayed.
+    pub async fn subscribe(&self, pool: &SqlitePool) -> Result<broadcast::Receiver<EntityChange>> {
+        let receiver = self.sender.subscribe();
+        let mut mark = self.watermark.lock().await;
+        if mark.is_none() {
+            *mark = Some(latest_outbox_id(pool).await?);
+        }
+        Ok(receiver)
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// Send a change to current subscribers
+    pub fn publish(&self, change: EntityChange) {
+        // Err only means nobody is listening
+        let _ = self.sender.send(change);
+    }
+
+    /// Publish outbox events committed since the last call, in order
+    pub async fn pump(&self, pool: &SqlitePool) -> Result<Vec<EntityChange>> {
+        if !self.has_subscribers() {
+            return Ok(Vec::new());
+        }
+        let mut mark = self.watermark.lock().await;
+        let Some(since) = *mark else {
+            *mark = Some(latest_outbox_id(pool).await?);
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query(r#"
+            SELECT id, project_id, entity_type, entity_id, event_type, payload, created_at, actor
+            FROM event_outbox WHERE id > ? ORDER BY id
+        "#)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        let mut published = Vec::with_capacity(rows.len());
+        for row in rows {
+            let payload: String = row.get("payload");
+            let change = EntityChange {
+                seq: Some(row.get("id")),
+                project_id: Some(row.get("project_id")),
+                entity_type: row.get("entity_type"),
+                entity_id: row.get("entity_id"),
+                event_type: row.get("event_type"),
+                payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::String(payload)),
+                actor: row.try_get("actor").unwrap_or(None),
+                timestamp: row.get("created_at"),
+            };
+            *mark = change.seq;
+            self.publish(change.clone());
+            published.push(change);
+        }
+        Ok(published)
+    }
+
+    /// Announce a mutation that has just committed. Its outbox events are relayed; a
+    /// mutation of a kind the outbox never records is published directly.
+    pub async fn announce(&self, pool: &SqlitePool, entity_type: &str, entity_id: &str, event_type: &str) -> Result<()> {
+        if !self.has_subscribers() {
+            return Ok(());
+        }
+        self.pump(pool).await?;
+
+        // Checked in the table rather than in what this call relayed, since the relay task
+        // may have published the row first
+        let recorded: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM event_outbox WHERE entity_type = ? AND entity_id = ? AND event_type = ?",
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(event_type)
+        .fetch_one(pool)
+        .await?;
+        if recorded == 0 {
+            self.publish(EntityChange {
+                seq: None,
+                project_id: None,
+                entity_type: entity_type.to_string(),
+                entity_id: entity_id.to_string(),
+                event_type: event_type.to_string(),
+                payload: serde_json::Value::Null,
+                actor: Some(crate::entities::actors::current().name),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Relay outbox events committed by any process, until the task is dropped
+    pub async fn relay(&self, pool: SqlitePool, interval: Duration) {
+        loop {
+            if let Err(e) = self.pump(&pool).await {
+                log::warn!("Change relay failed to read the event outbox: {}", e);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+async fn latest_outbox_id(pool: &SqlitePool) -> Result<i64> {
+    Ok(sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(id) FROM event_outbox")
+        .fetch_one(pool)
+        .await?
+        .unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::schema_models::TaskStatus;
+    use crate::entities::{crud, EntityManager};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_mutations_reach_subscribers_once() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Changes".to_string(), "Push".to_string()).await.unwrap();
+        let manager = EntityManager::new(pool.clone());
+        let feature = manager.create_feature_full(project.id.clone(), "Storage".to_string(), "Data".to_string(), None).await.unwrap();
+
+        let mut rx = manager.changes().subscribe(&pool).await.unwrap();
+        let task = manager
+            .create_task_full(project.id.clone(), feature.id.clone(), "Schema".to_string(), "feature".to_string())
+            .await
+            .unwrap();
+        manager.update_task_status(&task.id, TaskStatus::InProgress).await.unwrap();
+        manager.switch_project(&project.id).await.unwrap();
+
+        let created = rx.recv().await.unwrap();
+        assert_eq!((created.event_type.as_str(), created.entity_id.as_str()), ("task.created", task.id.as_str()));
+        assert!(created.seq.is_some());
+        let moved = rx.recv().await.unwrap();
+        assert_eq!(moved.event_type, "task.status_changed");
+        let switched = rx.recv().await.unwrap();
+        assert_eq!((switched.entity_type.as_str(), switched.seq), ("project", None));
+
+        // Already published rows are not relayed again
+        assert!(manager.changes().pump(&pool).await.unwrap().is_empty());
+        assert!(rx.try_recv().is_err());
+    }
+}
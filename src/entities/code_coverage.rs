@@ -0,0 +1,318 @@
+// Code Coverage - Per-feature line coverage from an external coverage report
+// Reads an lcov tracefile or a Cobertura XML report, maps the covered files to features
+// (the files under `tools.open.feature_files`, or else the reported files that mention
+// the feature ID) and sums their lines. Reported by `status --coverage`; completed
+// features under the threshold are a `--risks` item.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use super::crud;
+use super::doc_coverage::mentions;
+use super::schema_models::FeatureState;
+use crate::open::OpenConfig;
+
+/// Coverage settings stored under `tools.coverage` in `.wsb/state.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CodeCoverageConfig {
+    /// Report read by `status` when `--coverage` is not given, relative to the project root
+    pub report: Option<String>,
+    /// Completed features below this percentage are flagged
+    pub low_threshold: f64,
+}
+
+impl Default for CodeCoverageConfig {
+    fn default() -> Self {
+        Self { report: None, low_threshold: 60.0 }
+    }
+}
+
+/// Line counts for one file in a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileCoverage {
+    pub path: String,
+    pub lines_found: u64,
+    pub lines_hit: u64,
+}
+
+/// Coverage of one feature's files
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FeatureCodeCoverage {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    pub files: Vec<String>,
+    pub lines_found: u64,
+    pub lines_hit: u64,
+    /// `None` when no reported file maps to the feature
+    pub percent: Option<f64>,
+}
+
+impl FeatureCodeCoverage {
+    pub fn is_complete(&self) -> bool {
+        self.state == FeatureState::ImplementedPassingTests.as_str()
+    }
+}
+
+/// Per-feature coverage from one report
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeCoverageReport {
+    pub report: String,
+    pub format: String,
+    pub threshold: f64,
+    /// Line coverage of every file in the report
+    pub overall: Option<f64>,
+    pub features: Vec<FeatureCodeCoverage>,
+}
+
+impl CodeCoverageReport {
+    /// Completed features whose mapped files are covered below the threshold
+    pub fn low_coverage_complete(&self) -> Vec<&FeatureCodeCoverage> {
+        self.features
+            .iter()
+            .filter(|f| f.is_complete() && f.percent.map_or(false, |p| p < self.threshold))
+            .collect()
+    }
+}
+
+fn percent(hit: u64, found: u64) -> Option<f64> {
+    (found > 0).then(|| hit as f64 / found as f64 * 100.0)
+}
+
+/// Files in an lcov tracefile. `DA` records are counted when present, `LF`/`LH` otherwise.
+pub fn parse_lcov(content: &str) -> Vec<FileCoverage> {
+    let mut files = Vec::new();
+    let mut path: Option<String> = None;
+    let mut lines: BTreeMap<u64, u64> = BTreeMap::new();
+    let (mut found, mut hit) = (0, 0);
+
+    for line in content.lines().map(str::trim) {
+        if let Some(source) = line.strip_prefix("SF:") {
+            path = Some(source.to_string());
+            lines.clear();
+            found = 0;
+            hit = 0;
+        } else if let Some(record) = line.strip_prefix("DA:") {
+            let mut fields = record.split(',');
+            if let (Some(Ok(number)), Some(Ok(hits))) = (fields.next().map(str::parse), fields.next().map(str::parse::<u64>)) {
+                *lines.entry(number).or_insert(0) += hits;
+            }
+        } else if let Some(count) = line.strip_prefix("LF:") {
+            found = count.parse().unwrap_or(0);
+        } else if let Some(count) = line.strip_prefix("LH:") {
+            hit = count.parse().unwrap_or(0);
+        } else if line == "end_of_record" {
+            if let Some(path) = path.take() {
+                let (lines_found, lines_hit) = if lines.is_empty() {
+                    (found, hit)
+                } else {
+                    (lines.len() as u64, lines.values().filter(|h| **h > 0).count() as u64)
+                };
+                files.push(FileCoverage { path, lines_found, lines_hit });
+            }
+        }
+    }
+    files
+}
+
+/// Files in a Cobertura report, with `<source>` directories kept for resolving paths
+pub fn parse_cobertura(content: &str) -> (Vec<String>, Vec<FileCoverage>) {
+    let source = Regex::new(r"<source>\s*([^<]*?)\s*</source>").unwrap();
+    let class = Regex::new(r#"(?s)<class\b[^>]*?\bfilename="([^"]+)"[^>]*>(.*?)</class>"#).unwrap();
+    let line = Regex::new(r"<line\b([^>]*)>").unwrap();
+    let number = Regex::new(r#"\bnumber="(\d+)""#).unwrap();
+    let hits = Regex::new(r#"\bhits="(\d+)""#).unwrap();
+
+    let sources = source.captures_iter(content).map(|c| c[1].to_string()).collect();
+    let mut by_file: BTreeMap<String, BTreeMap<u64, u64>> = BTreeMap::new();
+    for class in class.captures_iter(content) {
+        let lines = by_file.entry(class[1].to_string()).or_default();
+        for attributes in line.captures_iter(&class[2]) {
+            let attributes = &attributes[1];
+            let number = number.captures(attributes).and_then(|c| c[1].parse().ok());
+            let hits = hits.captures(attributes).and_then(|c| c[1].parse::<u64>().ok());
+            if let (Some(number), Some(hits)) = (number, hits) {
+                *lines.entry(number).or_insert(0) += hits;
+            }
+        }
+    }
+
+    let files = by_file
+        .into_iter()
+        .map(|(path, lines)| FileCoverage {
+            path,
+            lines_found: lines.len() as u64,
+            lines_hit: lines.values().filter(|h| **h > 0).count() as u64,
+        })
+        .collect();
+    (sources, files)
+}
+
+/// Forward-slashed path of a reported file relative to `root` when it lies inside it
+fn normalize(path: &str, root: &Path, sources: &[String]) -> String {
+    let candidate = Path::new(path);
+    let resolved = if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        sources
+            .iter()
+            .map(|source| Path::new(source).join(candidate))
+            .map(|p| if p.is_absolute() { p } else { root.join(p) })
+            .find(|p| p.exists())
+            .unwrap_or_else(|| root.join(candidate))
+    };
+    let relative = resolved.strip_prefix(root).map(Path::to_path_buf).unwrap_or(resolved);
+    relative.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Whether a reported file is the mapped file, allowing either side to carry a prefix
+fn same_file(reported: &str, mapped: &str) -> bool {
+    let mapped = mapped.trim_start_matches("./");
+    reported == mapped || reported.ends_with(&format!("/{}", mapped)) || mapped.ends_with(&format!("/{}", reported))
+}
+
+/// Read a report, detecting lcov or Cobertura from its content
+pub fn load_report(report: &Path, root: &Path) -> Result<(String, Vec<FileCoverage>)> {
+    let content = std::fs::read_to_string(report).with_context(|| format!("Failed to read coverage report {}", report.display()))?;
+    let (format, sources, files) = if content.trim_start().starts_with('<') {
+        let (sources, files) = parse_cobertura(&content);
+        ("cobertura", sources, files)
+    } else if content.lines().any(|line| line.starts_with("SF:")) {
+        ("lcov", Vec::new(), parse_lcov(&content))
+    } else {
+        anyhow::bail!("{} is neither an lcov tracefile nor a Cobertura XML report", report.display());
+    };
+
+    let files = files
+        .into_iter()
+        .map(|file| FileCoverage { path: normalize(&file.path, root, &sources), ..file })
+        .collect();
+    Ok((format.to_string(), files))
+}
+
+/// Coverage of each of the project's features from the report at `report`
+pub async fn analyze(
+    pool: &SqlitePool,
+    project_id: &str,
+    project_root: &Path,
+    report: &Path,
+    mapping: &OpenConfig,
+    threshold: f64,
+) -> Result<CodeCoverageReport> {
+    let (format, files) = load_report(report, project_root)?;
+    let features = crud::features::list_by_project(pool, project_id).await?;
+    let mut contents: HashMap<&str, String> = HashMap::new();
+
+    let mut coverage = Vec::new();
+    for feature in features {
+        let mapped = mapping.feature_files.get(&feature.id).cloned().unwrap_or_default();
+        let mut matched: BTreeSet<usize> = BTreeSet::new();
+        for (index, file) in files.iter().enumerate() {
+            let is_mapped = if mapped.is_empty() {
+                let content = contents
+                    .entry(file.path.as_str())
+                    .or_insert_with(|| std::fs::read_to_string(project_root.join(&file.path)).unwrap_or_default());
+                mentions(content, &feature.id)
+            } else {
+                mapped.iter().any(|m| same_file(&file.path, m))
+            };
+            if is_mapped {
+                matched.insert(index);
+            }
+        }
+
+        let lines_found = matched.iter().map(|i| files[*i].lines_found).sum();
+        let lines_hit = matched.iter().map(|i| files[*i].lines_hit).sum();
+        coverage.push(FeatureCodeCoverage {
+            id: feature.id,
+            name: feature.name,
+            state: feature.state,
+            files: matched.iter().map(|i| files[*i].path.clone()).collect(),
+            lines_found,
+            lines_hit,
+            percent: percent(lines_hit, lines_found),
+        });
+    }
+
+    let found = files.iter().map(|f| f.lines_found).sum();
+    let hit = files.iter().map(|f| f.lines_hit).sum();
+    Ok(CodeCoverageReport {
+        report: report.display().to_string(),
+        format,
+        threshold,
+        overall: percent(hit, found),
+        features: coverage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_lcov() {
+        let lcov = "TN:\nSF:/work/src/auth.rs\nDA:1,3\nDA:2,0\nDA:3,1\nLF:3\nLH:2\nend_of_record\nSF:src/db.rs\nLF:10\nLH:5\nend_of_record\n";
+        assert_eq!(parse_lcov(lcov), vec![
+            FileCoverage { path: "/work/src/auth.rs".to_string(), lines_found: 3, lines_hit: 2 },
+            FileCoverage { path: "src/db.rs".to_string(), lines_found: 10, lines_hit: 5 },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        let xml = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.5">
+  <sources><source>/work/project</source></sources>
+  <packages><package name="app"><classes>
+    <class name="auth" filename="src/auth.py" line-rate="0.5">
+      <lines><line number="1" hits="1"/><line number="2" hits="0"/></lines>
+    </class>
+    <class name="auth.Inner" filename="src/auth.py" line-rate="1">
+      <lines><line number="2" hits="4" branch="false"/><line number="7" hits="0"/></lines>
+    </class>
+  </classes></package></packages>
+</coverage>"#;
+        let (sources, files) = parse_cobertura(xml);
+        assert_eq!(sources, vec!["/work/project".to_string()]);
+        assert_eq!(files, vec![FileCoverage { path: "src/auth.py".to_string(), lines_found: 3, lines_hit: 2 }]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_maps_files_to_features() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let pool = crate::entities::database::initialize_database(&root.join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Coverage".to_string(), "Coverage project".to_string()).await.unwrap();
+        let login = crud::features::create(&pool, project.id.clone(), "Login".to_string(), "Sign in".to_string(), None).await.unwrap();
+        let export = crud::features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        crud::features::update_state(&pool, &login.id, FeatureState::ImplementedPassingTests).await.unwrap();
+
+        std::fs::write(root.join("src/login.rs"), format!("// {}\nfn login() {{}}\n", login.id)).unwrap();
+        std::fs::write(root.join("src/export.rs"), "fn export() {}\n").unwrap();
+        let lcov = format!(
+            "SF:{}\nLF:10\nLH:4\nend_of_record\nSF:src/export.rs\nLF:10\nLH:9\nend_of_record\n",
+            root.join("src/login.rs").display()
+        );
+        std::fs::write(root.join("lcov.info"), lcov).unwrap();
+
+        let mut mapping = OpenConfig::default();
+        mapping.feature_files.insert(export.id.clone(), vec!["src/export.rs".to_string()]);
+        let report = analyze(&pool, &project.id, root, &root.join("lcov.info"), &mapping, 60.0).await.unwrap();
+
+        assert_eq!(report.format, "lcov");
+        assert_eq!(report.overall, Some(65.0));
+        let login_coverage = report.features.iter().find(|f| f.id == login.id).unwrap();
+        assert_eq!(login_coverage.files, vec!["src/login.rs".to_string()]);
+        assert_eq!(login_coverage.percent, Some(40.0));
+        let export_coverage = report.features.iter().find(|f| f.id == export.id).unwrap();
+        assert_eq!(export_coverage.percent, Some(90.0));
+        assert_eq!(report.low_coverage_complete().iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), vec![login.id.as_str()]);
+    }
+}
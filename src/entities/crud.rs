@@ -2,17 +2,24 @@
 // Following Directive D081: Complete replacement with zero backward compatibility
 
 use anyhow::Result;
-use sqlx::{Row, SqlitePool};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Acquire, Executor, Row, Sqlite, SqlitePool};
 
-use crate::entities::schema_models::{Directive, DirectiveCategory, Feature, FeatureState, Priority, Project, Session, Task, TaskStatus};
+use crate::entities::audit::{self, AuditOperation, FieldChange};
+use crate::entities::{actors, events, git_activity};
+use crate::entities::relationships::Dependency;
+use crate::entities::schema_models::{Directive, DirectiveCategory, Feature, FeatureState, Milestone, MilestoneStatus, Priority, Project, Session, Task, TaskStatus};
+use crate::entities::schema_traits::EntityType;
+use crate::entities::ids::IdGenerator;
 
 /// Project CRUD operations
 pub mod projects {
     use super::*;
 
     /// Create new project with validation
-    pub async fn create(pool: &SqlitePool, name: String, description: String) -> Result<Project> {
-        let next_id = get_next_project_id(pool).await?;
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(db: A, name: String, description: String) -> Result<Project> {
+        let mut tx = db.begin().await?;
+        let next_id = IdGenerator::next(&mut *tx, "", "project").await?;
         let project = Project::new(next_id.clone(), name, description)
             .map_err(|e| anyhow::anyhow!("Failed to create project: {}", e))?;
 
@@ -27,20 +34,25 @@ pub mod projects {
         .bind(&project.current_phase)
         .bind(&project.created_at.to_rfc3339())
         .bind(&project.updated_at.to_rfc3339())
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        audit::record(&mut tx, &project.id, "project", &project.id, AuditOperation::Create, &[
+            FieldChange::snapshot(None, Some(serde_json::to_string(&project)?)),
+        ]).await?;
+        tx.commit().await?;
+
         Ok(project)
     }
 
     /// Get project by ID
-    pub async fn get_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Project>> {
+    pub async fn get_by_id<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<Option<Project>> {
         let row = sqlx::query(r#"
             SELECT id, name, description, status, current_phase, created_at, updated_at 
-            FROM projects WHERE id = ?
+            FROM projects WHERE id = ? AND deleted_at IS NULL
         "#)
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(db)
         .await?;
 
         if let Some(row) = row {
@@ -66,7 +78,7 @@ pub mod projects {
     pub async fn list_active(pool: &SqlitePool) -> Result<Vec<Project>> {
         let rows = sqlx::query(r#"
             SELECT id, name, description, status, current_phase, created_at, updated_at 
-            FROM projects WHERE status = 'active' ORDER BY created_at DESC
+            FROM projects WHERE status = 'active' AND deleted_at IS NULL ORDER BY created_at DESC
         "#)
         .fetch_all(pool)
         .await?;
@@ -91,15 +103,17 @@ pub mod projects {
     }
 
     /// Update project
-    pub async fn update(
-        pool: &SqlitePool,
+    pub async fn update<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
         id: &str,
         name: Option<String>,
         description: Option<String>,
         current_phase: Option<String>,
     ) -> Result<()> {
-        let mut project = get_by_id(pool, id).await?
+        let mut tx = db.begin().await?;
+        let mut project = get_by_id(&mut *tx, id).await?
             .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+        let before = project.clone();
 
         project.update(name, description, current_phase)
             .map_err(|e| anyhow::anyhow!("Failed to update project: {}", e))?;
@@ -114,38 +128,134 @@ pub mod projects {
         .bind(&project.current_phase)
         .bind(&project.updated_at.to_rfc3339())
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        let changes = audit::diff(&before, &project)?;
+        if !changes.is_empty() {
+            audit::record(&mut tx, id, "project", id, AuditOperation::Update, &changes).await?;
+        }
+        tx.commit().await?;
+
         Ok(())
     }
 
-    /// Delete project (CASCADE will handle dependent entities)
-    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    /// Soft-delete project; its features and tasks stay untouched and reappear on restore
+    pub async fn delete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let deleted_at = chrono::Utc::now().to_rfc3339();
+        let result = sqlx::query("UPDATE projects SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(&deleted_at)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("Project not found: {}", id);
+        }
+
+        audit::record(&mut tx, id, "project", id, AuditOperation::Delete, &[
+            FieldChange::new("deleted_at", None, Some(&deleted_at)),
+        ]).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Restore a soft-deleted project
+    pub async fn restore<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let deleted_at: String = sqlx::query_scalar("SELECT deleted_at FROM projects WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Project {} is not deleted", id))?;
+
+        sqlx::query("UPDATE projects SET deleted_at = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        audit::record(&mut tx, id, "project", id, AuditOperation::Update, &[
+            FieldChange::new("deleted_at", Some(&deleted_at), None),
+        ]).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Permanently delete project, deleted or not (CASCADE will handle dependent entities,
+    /// including the project's audit trail)
+    pub async fn purge<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<()> {
         sqlx::query("DELETE FROM projects WHERE id = ?")
             .bind(id)
-            .execute(pool)
+            .execute(db)
             .await?;
         Ok(())
     }
 
-    /// Get next sequential project ID
-    async fn get_next_project_id(pool: &SqlitePool) -> Result<String> {
-        let max_id: Option<String> = sqlx::query_scalar(
-            "SELECT id FROM projects ORDER BY CAST(SUBSTR(id, 2) AS INTEGER) DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
+    fn from_row(row: &SqliteRow) -> Result<Project> {
+        let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
+        let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+        Project::from_db_row(
+            row.get("id"),
+            row.get("name"),
+            row.get("description"),
+            row.get("status"),
+            row.get("current_phase"),
+            created_at,
+            updated_at,
+        ).map_err(|e| anyhow::anyhow!("Failed to parse project from DB: {}", e))
+    }
+
+    /// List projects that are not deleted, oldest first; archived ones only when asked
+    pub async fn list(pool: &SqlitePool, include_archived: bool) -> Result<Vec<Project>> {
+        let rows = sqlx::query(r#"
+            SELECT id, name, description, status, current_phase, created_at, updated_at
+            FROM projects WHERE deleted_at IS NULL AND (? OR status != 'archived') ORDER BY created_at, id
+        "#)
+        .bind(include_archived)
+        .fetch_all(pool)
         .await?;
 
-        match max_id {
-            Some(id) => {
-                let num_str = &id[1..];
-                let num: u32 = num_str.parse().unwrap_or(0);
-                Ok(format!("P{:03}", num + 1))
-            },
-            None => Ok("P001".to_string()),
+        rows.iter().map(from_row).collect()
+    }
+
+    /// Archive a project: it keeps its data but drops out of project lists and can no
+    /// longer be the current project
+    pub async fn archive<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        set_archived(db, id, true).await
+    }
+
+    /// Bring an archived project back as an active one
+    pub async fn unarchive<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        set_archived(db, id, false).await
+    }
+
+    async fn set_archived<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, archived: bool) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let status: String = sqlx::query_scalar("SELECT status FROM projects WHERE id = ? AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+        let new_status = if archived { "archived" } else { "active" };
+        if (status == "archived") == archived {
+            anyhow::bail!("Project {} is already {}", id, if archived { "archived" } else { "not archived" });
         }
+
+        sqlx::query("UPDATE projects SET status = ?, archived = ?, updated_at = ? WHERE id = ?")
+            .bind(new_status)
+            .bind(archived)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        audit::record(&mut tx, id, "project", id, AuditOperation::StateChange, &[
+            FieldChange::new("status", Some(&status), Some(new_status)),
+        ]).await?;
+        tx.commit().await?;
+        Ok(())
     }
+
 }
 
 /// Feature CRUD operations
@@ -153,14 +263,15 @@ pub mod features {
     use super::*;
 
     /// Create new feature with validation
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
         project_id: String,
         name: String,
         description: String,
         category: Option<String>,
     ) -> Result<Feature> {
-        let next_id = get_next_feature_id(pool).await?;
+        let mut tx = db.begin().await?;
+        let next_id = IdGenerator::next(&mut *tx, &project_id, "feature").await?;
         let feature = Feature::new(next_id.clone(), project_id.clone(), next_id.clone(), name, description, category)
             .map_err(|e| anyhow::anyhow!("Failed to create feature: {}", e))?;
 
@@ -179,82 +290,104 @@ pub mod features {
         .bind(&feature.priority)
         .bind(&feature.created_at.to_rfc3339())
         .bind(&feature.updated_at.to_rfc3339())
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        events::record(&mut tx, &feature.project_id, "feature", &feature.id, "feature.created", serde_json::json!({
+            "name": feature.name,
+            "state": feature.state,
+        })).await?;
+        audit::record(&mut tx, &feature.project_id, "feature", &feature.id, AuditOperation::Create, &[
+            FieldChange::snapshot(None, Some(serde_json::to_string(&feature)?)),
+        ]).await?;
+        tx.commit().await?;
+
         Ok(feature)
     }
 
     /// Get feature by ID
-    pub async fn get_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Feature>> {
+    pub async fn get_by_id<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<Option<Feature>> {
         let row = sqlx::query(r#"
-            SELECT id, project_id, code, name, description, category, state, test_status, priority, notes, created_at, updated_at 
-            FROM features WHERE id = ?
+            SELECT id, project_id, code, name, description, category, state, test_status, priority, notes, created_at, updated_at, deleted_at
+            FROM features WHERE id = ? AND deleted_at IS NULL
         "#)
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(db)
         .await?;
 
-        if let Some(row) = row {
-            let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
-            let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
-
-            let feature = Feature::from_db_row(
-                row.get("id"),
-                row.get("project_id"),
-                row.get("code"),
-                row.get("name"),
-                row.get("description"),
-                row.get("category"),
-                row.get("state"),
-                row.get("test_status"),
-                row.get("priority"),
-                row.get("notes"),
-                created_at,
-                updated_at,
-            ).map_err(|e| anyhow::anyhow!("Failed to parse feature from DB: {}", e))?;
-            Ok(Some(feature))
-        } else {
-            Ok(None)
-        }
+        row.as_ref().map(from_row).transpose()
     }
 
     /// List features by project
     pub async fn list_by_project(pool: &SqlitePool, project_id: &str) -> Result<Vec<Feature>> {
         let rows = sqlx::query(r#"
-            SELECT id, project_id, code, name, description, category, state, test_status, priority, notes, created_at, updated_at 
-            FROM features WHERE project_id = ? ORDER BY created_at DESC
+            SELECT id, project_id, code, name, description, category, state, test_status, priority, notes, created_at, updated_at, deleted_at
+            FROM features WHERE project_id = ? AND deleted_at IS NULL ORDER BY created_at DESC
         "#)
         .bind(project_id)
         .fetch_all(pool)
         .await?;
 
-        let mut features = Vec::new();
-        for row in rows {
-            let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
-            let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+        rows.iter().map(from_row).collect()
+    }
 
-            let feature = Feature::from_db_row(
-                row.get("id"),
-                row.get("project_id"),
-                row.get("code"),
-                row.get("name"),
-                row.get("description"),
-                row.get("category"),
-                row.get("state"),
-                row.get("test_status"),
-                row.get("priority"),
-                row.get("notes"),
-                created_at,
-                updated_at,
-            ).map_err(|e| anyhow::anyhow!("Failed to parse feature from DB: {}", e))?;
-            features.push(feature);
-        }
-        Ok(features)
+    /// List features in a given state for a project, oldest update first
+    pub async fn list_by_state(pool: &SqlitePool, project_id: &str, state: FeatureState) -> Result<Vec<Feature>> {
+        let rows = sqlx::query(r#"
+            SELECT id, project_id, code, name, description, category, state, test_status, priority, notes, created_at, updated_at, deleted_at
+            FROM features WHERE project_id = ? AND state = ? AND deleted_at IS NULL ORDER BY updated_at ASC
+        "#)
+        .bind(project_id)
+        .bind(state.as_str())
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(from_row).collect()
+    }
+
+    /// List soft-deleted features of a project, most recently deleted first
+    pub async fn list_deleted(pool: &SqlitePool, project_id: &str) -> Result<Vec<Feature>> {
+        let rows = sqlx::query(r#"
+            SELECT id, project_id, code, name, description, category, state, test_status, priority, notes, created_at, updated_at, deleted_at
+            FROM features WHERE project_id = ? AND deleted_at IS NOT NULL ORDER BY deleted_at DESC
+        "#)
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Feature> {
+        let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
+        let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+
+        let mut feature = Feature::from_db_row(
+            row.get("id"),
+            row.get("project_id"),
+            row.get("code"),
+            row.get("name"),
+            row.get("description"),
+            row.get("category"),
+            row.get("state"),
+            row.get("test_status"),
+            row.get("priority"),
+            row.get("notes"),
+            created_at,
+            updated_at,
+        ).map_err(|e| anyhow::anyhow!("Failed to parse feature from DB: {}", e))?;
+        feature.deleted_at = parse_deleted_at(row)?;
+        Ok(feature)
     }
 
     /// Update feature state
-    pub async fn update_state(pool: &SqlitePool, id: &str, new_state: FeatureState) -> Result<()> {
+    pub async fn update_state<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, new_state: FeatureState) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let current = sqlx::query("SELECT project_id, state FROM features WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
         // Simplified implementation - just update the state directly
         sqlx::query(r#"
             UPDATE features 
@@ -264,62 +397,193 @@ pub mod features {
         .bind(new_state.as_str())
         .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(current) = current {
+            let project_id: String = current.get("project_id");
+            let old_state: String = current.get("state");
+            events::record(&mut tx, &project_id, "feature", id, "feature.state_changed", serde_json::json!({
+                "state": new_state.as_str(),
+            })).await?;
+            audit::record(&mut tx, &project_id, "feature", id, AuditOperation::StateChange, &[
+                FieldChange::new("state", Some(&old_state), Some(new_state.as_str())),
+            ]).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Update feature name, description, category, priority and notes. State changes go
+    /// through `update_state`, which records them as transitions.
+    pub async fn update<'c, A: Acquire<'c, Database = Sqlite>>(db: A, feature: &Feature) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let Some(before) = get_by_id(&mut *tx, &feature.id).await? else {
+            anyhow::bail!("Feature not found: {}", feature.id);
+        };
+
+        sqlx::query(r#"
+            UPDATE features
+            SET name = ?, description = ?, category = ?, priority = ?, notes = ?, updated_at = ?
+            WHERE id = ?
+        "#)
+        .bind(&feature.name)
+        .bind(&feature.description)
+        .bind(&feature.category)
+        .bind(&feature.priority)
+        .bind(&feature.notes)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&feature.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let after = Feature { state: before.state.clone(), ..feature.clone() };
+        let changes = audit::diff(&before, &after)?;
+        if !changes.is_empty() {
+            audit::record(&mut tx, &before.project_id, "feature", &feature.id, AuditOperation::Update, &changes).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Soft-delete feature and its live tasks. The tasks share the feature's deletion
+    /// timestamp, which is how `restore` finds them again.
+    pub async fn delete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let deleted_at = chrono::Utc::now().to_rfc3339();
+        let result = sqlx::query("UPDATE features SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(&deleted_at)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("Feature not found: {}", id);
+        }
+
+        let task_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE (feature_ids = ? OR feature_ids LIKE ?) AND deleted_at IS NULL")
+            .bind(id)
+            .bind(format!("%{}%", id))
+            .fetch_all(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE tasks SET deleted_at = ? WHERE (feature_ids = ? OR feature_ids LIKE ?) AND deleted_at IS NULL")
+            .bind(&deleted_at)
+            .bind(id)
+            .bind(format!("%{}%", id))
+            .execute(&mut *tx)
+            .await?;
+
+        let project_id: String = sqlx::query_scalar("SELECT project_id FROM features WHERE id = ?")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        events::record(&mut tx, &project_id, "feature", id, "feature.deleted", serde_json::json!({})).await?;
+        let change = [FieldChange::new("deleted_at", None, Some(&deleted_at))];
+        audit::record(&mut tx, &project_id, "feature", id, AuditOperation::Delete, &change).await?;
+        for task_id in &task_ids {
+            audit::record(&mut tx, &project_id, "task", task_id, AuditOperation::Delete, &change).await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
-    /// Delete feature (CASCADE: manually delete related tasks since feature_ids is JSON)
-    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    /// Restore a soft-deleted feature together with the tasks deleted along with it
+    pub async fn restore<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let row = sqlx::query("SELECT project_id, deleted_at FROM features WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Feature {} is not deleted", id))?;
+        let project_id: String = row.get("project_id");
+        let deleted_at: String = row.get("deleted_at");
+
+        sqlx::query("UPDATE features SET deleted_at = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let task_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE (feature_ids = ? OR feature_ids LIKE ?) AND deleted_at = ?")
+            .bind(id)
+            .bind(format!("%{}%", id))
+            .bind(&deleted_at)
+            .fetch_all(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE tasks SET deleted_at = NULL WHERE (feature_ids = ? OR feature_ids LIKE ?) AND deleted_at = ?")
+            .bind(id)
+            .bind(format!("%{}%", id))
+            .bind(&deleted_at)
+            .execute(&mut *tx)
+            .await?;
+
+        events::record(&mut tx, &project_id, "feature", id, "feature.restored", serde_json::json!({})).await?;
+        let change = [FieldChange::new("deleted_at", Some(&deleted_at), None)];
+        audit::record(&mut tx, &project_id, "feature", id, AuditOperation::Update, &change).await?;
+        for task_id in &task_ids {
+            audit::record(&mut tx, &project_id, "task", task_id, AuditOperation::Update, &change).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Permanently delete feature, deleted or not (CASCADE: manually delete related tasks since feature_ids is JSON)
+    pub async fn purge<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM features WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let task_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE feature_ids = ? OR feature_ids LIKE ?")
+            .bind(id)
+            .bind(format!("%{}%", id))
+            .fetch_all(&mut *tx)
+            .await?;
+        if let Some(project_id) = &project_id {
+            for task_id in &task_ids {
+                let snapshot = audit::snapshot(&mut tx, "tasks", task_id).await?;
+                audit::record(&mut tx, project_id, "task", task_id, AuditOperation::Delete, &[FieldChange::snapshot(snapshot, None)]).await?;
+            }
+            let snapshot = audit::snapshot(&mut tx, "features", id).await?;
+            audit::record(&mut tx, project_id, "feature", id, AuditOperation::Delete, &[FieldChange::snapshot(snapshot, None)]).await?;
+        }
+
         // First, delete tasks that reference this feature_id
         // Since feature_ids is stored as JSON/text, we need to check for the feature_id
         sqlx::query("DELETE FROM tasks WHERE feature_ids = ? OR feature_ids LIKE ?")
             .bind(id) // Exact match for simple string case
             .bind(format!("%{}%", id)) // Pattern match for JSON array case
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
             
         // Now delete the feature itself
         sqlx::query("DELETE FROM features WHERE id = ?")
             .bind(id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    /// Get next sequential feature ID
-    async fn get_next_feature_id(pool: &SqlitePool) -> Result<String> {
-        let max_id: Option<String> = sqlx::query_scalar(
-            "SELECT id FROM features ORDER BY CAST(SUBSTR(id, 2) AS INTEGER) DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
-        .await?;
-
-        match max_id {
-            Some(id) => {
-                let num_str = &id[1..];
-                let num: u32 = num_str.parse().unwrap_or(0);
-                Ok(format!("F{:05}", num + 1))
-            },
-            None => Ok("F00001".to_string()),
-        }
-    }
 }
 
 /// Task CRUD operations
 pub mod tasks {
     use super::*;
+    use crate::entities::recurrence;
+    use crate::entities::relationships::task_ordering;
+    use crate::entities::schedule::parse_id_list;
+    use sqlx::SqliteConnection;
+    use std::collections::{BTreeSet, HashMap, VecDeque};
 
     /// Create new task with validation
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
         project_id: String,
         feature_id: String,
         task_description: String,
         category: String,
     ) -> Result<Task> {
-        let next_id = get_next_task_id(pool).await?;
+        let mut tx = db.begin().await?;
+        let next_id = IdGenerator::next(&mut *tx, &project_id, "task").await?;
         let task = Task::new(next_id.clone(), project_id, feature_id, task_description, category)
             .map_err(|e| anyhow::anyhow!("Failed to create task: {}", e))?;
 
@@ -341,61 +605,41 @@ pub mod tasks {
         .bind(&feature_ids_json) // Store feature_id in feature_ids field
         .bind(&task.created_at.to_rfc3339())
         .bind(&task.updated_at.to_rfc3339())
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        events::record(&mut tx, &task.project_id, "task", &task.id, "task.created", serde_json::json!({
+            "task": task.task,
+            "feature_id": task.feature_id,
+            "status": task.status,
+        })).await?;
+        audit::record(&mut tx, &task.project_id, "task", &task.id, AuditOperation::Create, &[
+            FieldChange::snapshot(None, Some(serde_json::to_string(&task)?)),
+        ]).await?;
+        tx.commit().await?;
+
         Ok(task)
     }
 
     /// Get task by ID
-    pub async fn get_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Task>> {
+    pub async fn get_by_id<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<Option<Task>> {
         let row = sqlx::query(r#"
-            SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at 
-            FROM tasks WHERE id = ?
+            SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at, deleted_at
+            FROM tasks WHERE id = ? AND deleted_at IS NULL
         "#)
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(db)
         .await?;
 
-        if let Some(row) = row {
-            let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
-            let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
-
-            // Extract first feature_id from feature_ids field
-            let feature_ids_str: String = row.get("feature_ids");
-            let feature_id = if feature_ids_str.starts_with('[') {
-                // Handle JSON array case later
-                feature_ids_str.trim_matches(['[', ']', '"']).to_string()
-            } else {
-                feature_ids_str // Simple string case
-            };
-            
-            let task = Task::from_db_row(
-                row.get("id"),
-                row.get("project_id"),
-                feature_id,
-                row.get("title"), // task description is in title field
-                row.get("priority"),
-                row.get("status"),
-                row.get("category"),
-                row.get("depends_on"),
-                row.get("assigned_to"),
-                row.get("notes"),
-                created_at,
-                updated_at,
-            ).map_err(|e| anyhow::anyhow!("Failed to parse task from DB: {}", e))?;
-            Ok(Some(task))
-        } else {
-            Ok(None)
-        }
+        row.as_ref().map(from_row).transpose()
     }
 
     /// List tasks by project with optional status filter
     pub async fn list_by_project(pool: &SqlitePool, project_id: &str, status: Option<TaskStatus>) -> Result<Vec<Task>> {
         let query = if status.is_some() {
-            "SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at FROM tasks WHERE project_id = ? AND status = ? ORDER BY created_at DESC"
+            "SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at, deleted_at FROM tasks WHERE project_id = ? AND status = ? AND deleted_at IS NULL ORDER BY created_at DESC"
         } else {
-            "SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at FROM tasks WHERE project_id = ? ORDER BY created_at DESC"
+            "SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at, deleted_at FROM tasks WHERE project_id = ? AND deleted_at IS NULL ORDER BY created_at DESC"
         };
 
         let rows = if let Some(status_filter) = status {
@@ -411,42 +655,61 @@ pub mod tasks {
                 .await?
         };
 
-        let mut tasks = Vec::new();
-        for row in rows {
-            let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
-            let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+        rows.iter().map(from_row).collect()
+    }
 
-            // Extract first feature_id from feature_ids field
-            let feature_ids_str: String = row.get("feature_ids");
-            let feature_id = if feature_ids_str.starts_with('[') {
-                // Handle JSON array case later
-                feature_ids_str.trim_matches(['[', ']', '"']).to_string()
-            } else {
-                feature_ids_str // Simple string case
-            };
-            
-            let task = Task::from_db_row(
-                row.get("id"),
-                row.get("project_id"),
-                feature_id,
-                row.get("title"), // task description is in title field
-                row.get("priority"),
-                row.get("status"),
-                row.get("category"),
-                row.get("depends_on"),
-                row.get("assigned_to"),
-                row.get("notes"),
-                created_at,
-                updated_at,
-            ).map_err(|e| anyhow::anyhow!("Failed to parse task from DB: {}", e))?;
-            tasks.push(task);
-        }
+    /// List soft-deleted tasks of a project, most recently deleted first
+    pub async fn list_deleted(pool: &SqlitePool, project_id: &str) -> Result<Vec<Task>> {
+        let rows = sqlx::query(r#"
+            SELECT id, project_id, feature_ids, title, category, status, priority, assigned_to, depends_on, notes, created_at, updated_at, deleted_at
+            FROM tasks WHERE project_id = ? AND deleted_at IS NOT NULL ORDER BY deleted_at DESC
+        "#)
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
 
-        Ok(tasks)
+        rows.iter().map(from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Task> {
+        let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
+        let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+
+        // Extract first feature_id from feature_ids field
+        let feature_ids_str: String = row.get("feature_ids");
+        let feature_id = if feature_ids_str.starts_with('[') {
+            // Handle JSON array case later
+            feature_ids_str.trim_matches(['[', ']', '"']).to_string()
+        } else {
+            feature_ids_str // Simple string case
+        };
+
+        let mut task = Task::from_db_row(
+            row.get("id"),
+            row.get("project_id"),
+            feature_id,
+            row.get("title"), // task description is in title field
+            row.get("priority"),
+            row.get("status"),
+            row.get("category"),
+            row.get("depends_on"),
+            row.get("assigned_to"),
+            row.get("notes"),
+            created_at,
+            updated_at,
+        ).map_err(|e| anyhow::anyhow!("Failed to parse task from DB: {}", e))?;
+        task.deleted_at = parse_deleted_at(row)?;
+        Ok(task)
     }
 
     /// Update task status
-    pub async fn update_status(pool: &SqlitePool, id: &str, new_status: TaskStatus) -> Result<()> {
+    pub async fn update_status<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, new_status: TaskStatus) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let current = sqlx::query("SELECT project_id, status FROM tasks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
         sqlx::query(r#"
             UPDATE tasks 
             SET status = ?, updated_at = ?
@@ -455,14 +718,35 @@ pub mod tasks {
         .bind(new_status.as_str())
         .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        if new_status == TaskStatus::Completed {
+            record_completion(&mut tx, id).await?;
+        }
+
+        if let Some(current) = current {
+            let project_id: String = current.get("project_id");
+            let old_status: String = current.get("status");
+            events::record(&mut tx, &project_id, "task", id, "task.status_changed", serde_json::json!({
+                "status": new_status.as_str(),
+            })).await?;
+            audit::record(&mut tx, &project_id, "task", id, AuditOperation::StateChange, &[
+                FieldChange::new("status", Some(&old_status), Some(new_status.as_str())),
+            ]).await?;
+            if new_status == TaskStatus::Completed && old_status != "completed" {
+                recurrence::materialize_next(&mut tx, id).await?;
+            }
+        }
+        tx.commit().await?;
+
         Ok(())
     }
 
     /// Update complete task object
-    pub async fn update(pool: &SqlitePool, task: &Task) -> Result<()> {
+    pub async fn update<'c, A: Acquire<'c, Database = Sqlite>>(db: A, task: &Task) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let before = get_by_id(&mut *tx, &task.id).await?;
         let feature_ids_json = format!("{}", task.feature_id);
         
         sqlx::query(r#"
@@ -481,13 +765,27 @@ pub mod tasks {
         .bind(&task.notes)
         .bind(chrono::Utc::now().to_rfc3339())
         .bind(&task.id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+
+        if let Some(before) = before {
+            let changes = audit::diff(&before, task)?;
+            if !changes.is_empty() {
+                audit::record(&mut tx, &before.project_id, "task", &task.id, AuditOperation::Update, &changes).await?;
+            }
+        }
+        tx.commit().await?;
         Ok(())
     }
 
     /// Complete task
-    pub async fn complete(pool: &SqlitePool, id: &str, _completion_notes: Option<String>) -> Result<()> {
+    pub async fn complete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, _completion_notes: Option<String>) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let current = sqlx::query("SELECT project_id, status FROM tasks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
         sqlx::query(r#"
             UPDATE tasks 
             SET status = ?, updated_at = ?
@@ -496,37 +794,301 @@ pub mod tasks {
         .bind("completed")
         .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        record_completion(&mut tx, id).await?;
+        if let Some(current) = current {
+            let project_id: String = current.get("project_id");
+            let old_status: String = current.get("status");
+            audit::record(&mut tx, &project_id, "task", id, AuditOperation::StateChange, &[
+                FieldChange::new("status", Some(&old_status), Some("completed")),
+            ]).await?;
+            if old_status != "completed" {
+                recurrence::materialize_next(&mut tx, id).await?;
+            }
+        }
+        tx.commit().await?;
+
         Ok(())
     }
 
-    /// Delete task
-    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    /// Stamp the completion time and the actor who completed the task
+    async fn record_completion(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>, id: &str) -> Result<()> {
+        let actor = actors::current();
+        actors::touch(tx, &actor).await?;
+
+        sqlx::query("UPDATE tasks SET completed_at = ?, completed_by = ? WHERE id = ?")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&actor.name)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Soft-delete task
+    pub async fn delete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let project_id: String = sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = ? AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+
+        let deleted_at = chrono::Utc::now().to_rfc3339();
+        sqlx::query("UPDATE tasks SET deleted_at = ? WHERE id = ?")
+            .bind(&deleted_at)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        events::record(&mut tx, &project_id, "task", id, "task.deleted", serde_json::json!({})).await?;
+        audit::record(&mut tx, &project_id, "task", id, AuditOperation::Delete, &[
+            FieldChange::new("deleted_at", None, Some(&deleted_at)),
+        ]).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Restore a soft-deleted task
+    pub async fn restore<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let row = sqlx::query("SELECT project_id, deleted_at FROM tasks WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Task {} is not deleted", id))?;
+        let project_id: String = row.get("project_id");
+        let deleted_at: String = row.get("deleted_at");
+
+        sqlx::query("UPDATE tasks SET deleted_at = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        events::record(&mut tx, &project_id, "task", id, "task.restored", serde_json::json!({})).await?;
+        audit::record(&mut tx, &project_id, "task", id, AuditOperation::Update, &[
+            FieldChange::new("deleted_at", Some(&deleted_at), None),
+        ]).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Permanently delete task, deleted or not
+    pub async fn purge<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if let Some(project_id) = project_id {
+            let snapshot = audit::snapshot(&mut tx, "tasks", id).await?;
+            audit::record(&mut tx, &project_id, "task", id, AuditOperation::Delete, &[FieldChange::snapshot(snapshot, None)]).await?;
+        }
+
         sqlx::query("DELETE FROM tasks WHERE id = ?")
             .bind(id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    /// Get next sequential task ID
-    async fn get_next_task_id(pool: &SqlitePool) -> Result<String> {
-        let max_id: Option<String> = sqlx::query_scalar(
-            "SELECT id FROM tasks ORDER BY CAST(SUBSTR(id, 2) AS INTEGER) DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
+    /// Record that `id` cannot start before `prerequisite_id` is done. Both must be live
+    /// tasks of the same project; a dependency that closes a cycle is rejected.
+    pub async fn add_dependency<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, prerequisite_id: &str) -> Result<Dependency> {
+        let mut tx = db.begin().await?;
+        let task = get_by_id(&mut *tx, id).await?
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        let prerequisite = get_by_id(&mut *tx, prerequisite_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}", prerequisite_id))?;
+        if task.project_id != prerequisite.project_id {
+            anyhow::bail!("Tasks {} and {} belong to different projects", id, prerequisite_id);
+        }
+        let graph = prerequisites(&mut tx, &task.project_id).await?;
+        if graph.get(id).map_or(false, |p| p.contains(prerequisite_id)) {
+            anyhow::bail!("{} already depends on {}", id, prerequisite_id);
+        }
+
+        let dependency = dependencies::create(
+            &mut *tx, &task.project_id, id, EntityType::Task, prerequisite_id, EntityType::Task, "depends_on", None,
+        ).await?;
+        events::record(&mut tx, &task.project_id, "task", id, "task.dependency_added", serde_json::json!({
+            "depends_on": prerequisite_id,
+        })).await?;
+        tx.commit().await?;
+        Ok(dependency)
+    }
+
+    /// Prerequisites of each task in a project: its `depends_on` list plus unresolved task
+    /// ordering relationships
+    pub async fn prerequisites(conn: &mut SqliteConnection, project_id: &str) -> Result<HashMap<String, BTreeSet<String>>> {
+        let mut graph: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let rows = sqlx::query("SELECT id, depends_on FROM tasks WHERE project_id = ? AND deleted_at IS NULL")
+            .bind(project_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        for row in &rows {
+            let ids = parse_id_list(row.get::<Option<String>, _>("depends_on").as_deref());
+            if !ids.is_empty() {
+                graph.entry(row.get("id")).or_default().extend(ids);
+            }
+        }
+
+        let edges = sqlx::query(r#"
+            SELECT from_entity_id, to_entity_id, dependency_type
+            FROM dependencies
+            WHERE project_id = ? AND from_entity_type = 'task' AND to_entity_type = 'task' AND resolved_at IS NULL
+        "#)
+        .bind(project_id)
+        .fetch_all(&mut *conn)
         .await?;
+        for edge in &edges {
+            let from: String = edge.get("from_entity_id");
+            let to: String = edge.get("to_entity_id");
+            let kind: String = edge.get("dependency_type");
+            if let Some((dependent, prerequisite)) = task_ordering(&from, &to, &kind) {
+                graph.entry(dependent.to_string()).or_default().insert(prerequisite.to_string());
+            }
+        }
+        Ok(graph)
+    }
 
-        match max_id {
-            Some(id) => {
-                let num_str = &id[1..];
-                let num: u32 = num_str.parse().unwrap_or(0);
-                Ok(format!("T{:06}", num + 1))
-            },
-            None => Ok("T000001".to_string()),
+    /// The chain of prerequisites through which `from` waits on `to`, starting with `from`
+    /// and ending with `to`; `None` when `from` does not depend on `to`
+    pub fn dependency_chain(graph: &HashMap<String, BTreeSet<String>>, from: &str, to: &str) -> Option<Vec<String>> {
+        if from == to {
+            return Some(vec![to.to_string()]);
         }
+
+        let mut parents: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            for next in graph.get(current).into_iter().flatten() {
+                if next == from || parents.contains_key(next.as_str()) {
+                    continue;
+                }
+                parents.insert(next.as_str(), current);
+                if next == to {
+                    let mut chain = vec![to.to_string()];
+                    let mut at = current;
+                    while at != from {
+                        chain.push(at.to_string());
+                        at = parents[at];
+                    }
+                    chain.push(from.to_string());
+                    chain.reverse();
+                    return Some(chain);
+                }
+                queue.push_back(next.as_str());
+            }
+        }
+        None
+    }
+
+    /// Pending tasks whose prerequisites are all completed or cancelled. Deleted
+    /// prerequisites no longer hold anything up.
+    pub async fn ready(pool: &SqlitePool, project_id: &str) -> Result<Vec<Task>> {
+        let mut conn = pool.acquire().await?;
+        let graph = prerequisites(&mut conn, project_id).await?;
+        let statuses = statuses(&mut conn, project_id).await?;
+
+        let mut ready: Vec<Task> = list_by_project(pool, project_id, Some(TaskStatus::Pending)).await?
+            .into_iter()
+            .filter(|task| waiting_on(&graph, &statuses, &task.id).is_empty())
+            .collect();
+        ready.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(ready)
+    }
+
+    /// `(task id, acceptance criteria)` of the live tasks linked to `feature_id`. `feature_ids`
+    /// holds a bare ID or a JSON array, so whole elements are compared: F00001 never matches F00010.
+    pub async fn acceptance_criteria(pool: &SqlitePool, feature_id: &str) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query(r#"
+            SELECT id, acceptance_criteria FROM tasks
+            WHERE acceptance_criteria IS NOT NULL AND deleted_at IS NULL
+              AND (feature_ids = ? OR (json_valid(feature_ids) AND json_type(feature_ids) = 'array'
+                   AND EXISTS (SELECT 1 FROM json_each(feature_ids) WHERE value = ?)))
+            ORDER BY id
+        "#)
+        .bind(feature_id)
+        .bind(feature_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.iter().map(|row| (row.get("id"), row.get("acceptance_criteria"))).collect())
+    }
+
+    /// Open tasks waiting on `id`, directly or through other open tasks, nearest first,
+    /// each with the prerequisites it is still waiting on. Nothing waits on a done task.
+    pub async fn blocked_by(pool: &SqlitePool, id: &str) -> Result<Vec<(Task, Vec<String>)>> {
+        let task = get_by_id(pool, id).await?
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        if !is_open(&task.status) {
+            return Ok(Vec::new());
+        }
+        let mut conn = pool.acquire().await?;
+        let graph = prerequisites(&mut conn, &task.project_id).await?;
+        let statuses = statuses(&mut conn, &task.project_id).await?;
+
+        let mut dependents: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for (dependent, prerequisites) in &graph {
+            for prerequisite in prerequisites {
+                dependents.entry(prerequisite.as_str()).or_default().insert(dependent.as_str());
+            }
+        }
+
+        let mut order: Vec<&str> = Vec::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(current) = queue.pop_front() {
+            for &dependent in dependents.get(current).into_iter().flatten() {
+                if dependent != id && statuses.get(dependent).map_or(false, |s| is_open(s)) && !order.contains(&dependent) {
+                    order.push(dependent);
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        let mut blocked = Vec::new();
+        for dependent in order {
+            if let Some(task) = get_by_id(&mut *conn, dependent).await? {
+                let waiting = waiting_on(&graph, &statuses, dependent);
+                blocked.push((task, waiting));
+            }
+        }
+        Ok(blocked)
+    }
+
+    fn is_open(status: &str) -> bool {
+        status != TaskStatus::Completed.as_str() && status != TaskStatus::Cancelled.as_str()
+    }
+
+    /// Live prerequisites of `id` that are not done yet
+    fn waiting_on(graph: &HashMap<String, BTreeSet<String>>, statuses: &HashMap<String, String>, id: &str) -> Vec<String> {
+        graph
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter(|p| statuses.get(p.as_str()).map_or(false, |s| is_open(s)))
+            .cloned()
+            .collect()
+    }
+
+    async fn statuses(conn: &mut SqliteConnection, project_id: &str) -> Result<HashMap<String, String>> {
+        let rows = sqlx::query("SELECT id, status FROM tasks WHERE project_id = ? AND deleted_at IS NULL")
+            .bind(project_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(rows.iter().map(|row| (row.get("id"), row.get("status"))).collect())
+    }
+
+}
+
+/// Parse the optional `deleted_at` column of a soft-deletable entity row
+fn parse_deleted_at(row: &SqliteRow) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    match row.try_get::<Option<String>, _>("deleted_at")? {
+        Some(raw) => Ok(Some(chrono::DateTime::parse_from_rfc3339(&raw)?.with_timezone(&chrono::Utc))),
+        None => Ok(None),
     }
 }
 
@@ -535,21 +1097,25 @@ pub mod sessions {
     use super::*;
 
     /// Create new session with validation
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
         project_id: String,
         session_name: String,
         focus_area: Option<String>,
     ) -> Result<Session> {
-        let next_id = get_next_session_id(pool).await?;
+        let mut tx = db.begin().await?;
+        let next_id = IdGenerator::next(&mut *tx, &project_id, "session").await?;
         let focus = focus_area.unwrap_or_else(|| "General development".to_string());
         let session = Session::new(next_id.clone(), project_id, session_name, focus)
             .map_err(|e| anyhow::anyhow!("Failed to create session: {}", e))?;
 
+        let actor = actors::current();
+        actors::touch(&mut tx, &actor).await?;
+
         // Sessions table uses 'state' instead of 'status'
         sqlx::query(r#"
-            INSERT INTO sessions (id, project_id, title, date, start_time, state, focus, major_achievement, completed_tasks, key_achievements, files_modified, issues_resolved, started_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO sessions (id, project_id, title, date, start_time, state, focus, major_achievement, completed_tasks, key_achievements, files_modified, issues_resolved, started_at, updated_at, actor)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#)
         .bind(&session.id)
         .bind(&session.project_id)
@@ -565,129 +1131,146 @@ pub mod sessions {
         .bind(&session.issues_resolved)
         .bind(&session.created_at.to_rfc3339()) // Maps to started_at
         .bind(&session.updated_at.to_rfc3339())
-        .execute(pool)
+        .bind(&actor.name)
+        .execute(&mut *tx)
         .await?;
 
+        audit::record(&mut tx, &session.project_id, "session", &session.id, AuditOperation::Create, &[
+            FieldChange::snapshot(None, Some(serde_json::to_string(&session)?)),
+        ]).await?;
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Session> {
+        let started_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("started_at"))?.with_timezone(&chrono::Utc);
+        let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+
+        let mut session = Session::from_db_row(
+            row.get("id"),
+            row.get("project_id"),
+            row.get("title"),
+            row.get("date"),
+            row.get("start_time"),
+            row.get("end_time"),
+            row.get("state"), // Map state to status
+            row.get("focus"),
+            row.get("major_achievement"),
+            row.get("completed_tasks"),
+            row.get("key_achievements"),
+            row.get("files_modified"),
+            row.get("issues_resolved"),
+            started_at,
+            updated_at,
+        ).map_err(|e| anyhow::anyhow!("Failed to parse session from DB: {}", e))?;
+        session.git_activity = row.get("git_activity");
         Ok(session)
     }
 
     /// List sessions by project
     pub async fn list_by_project(pool: &SqlitePool, project_id: &str) -> Result<Vec<Session>> {
         let rows = sqlx::query(r#"
-            SELECT id, project_id, title, date, start_time, end_time, state, focus, major_achievement, completed_tasks, key_achievements, files_modified, issues_resolved, started_at, updated_at
+            SELECT id, project_id, title, date, start_time, end_time, state, focus, major_achievement, completed_tasks, key_achievements, files_modified, issues_resolved, git_activity, started_at, updated_at
             FROM sessions WHERE project_id = ? ORDER BY started_at DESC
         "#)
         .bind(project_id)
         .fetch_all(pool)
         .await?;
 
-        let mut sessions = Vec::new();
-        for row in rows {
-            let started_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("started_at"))?.with_timezone(&chrono::Utc);
-            let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
-
-            let session = Session::from_db_row(
-                row.get("id"),
-                row.get("project_id"),
-                row.get("title"),
-                row.get("date"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("state"),
-                row.get("focus"),
-                row.get("major_achievement"),
-                row.get("completed_tasks"),
-                row.get("key_achievements"),
-                row.get("files_modified"),
-                row.get("issues_resolved"),
-                started_at,
-                updated_at,
-            ).map_err(|e| anyhow::anyhow!("Failed to parse session from DB: {}", e))?;
-            sessions.push(session);
-        }
-
-        Ok(sessions)
+        rows.iter().map(from_row).collect()
     }
 
     /// Get session by ID
-    pub async fn get_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Session>> {
+    pub async fn get_by_id<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<Option<Session>> {
         let row = sqlx::query(r#"
-            SELECT id, project_id, title, date, start_time, end_time, state, focus, major_achievement, completed_tasks, key_achievements, files_modified, issues_resolved, started_at, updated_at
+            SELECT id, project_id, title, date, start_time, end_time, state, focus, major_achievement, completed_tasks, key_achievements, files_modified, issues_resolved, git_activity, started_at, updated_at
             FROM sessions WHERE id = ?
         "#)
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(db)
         .await?;
 
-        if let Some(row) = row {
-            let started_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("started_at"))?.with_timezone(&chrono::Utc);
-            let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+        row.as_ref().map(from_row).transpose()
+    }
 
-            let session = Session::from_db_row(
-                row.get("id"),
-                row.get("project_id"),
-                row.get("title"),
-                row.get("date"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("state"), // Map state to status
-                row.get("focus"),
-                row.get("major_achievement"),
-                row.get("completed_tasks"),
-                row.get("key_achievements"),
-                row.get("files_modified"),
-                row.get("issues_resolved"),
-                started_at,
-                updated_at,
-            ).map_err(|e| anyhow::anyhow!("Failed to parse session from DB: {}", e))?;
-            Ok(Some(session))
-        } else {
-            Ok(None)
-        }
+    /// Complete session, capturing the git activity of the repository at the working
+    /// directory during the session window
+    pub async fn complete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, summary: String) -> Result<()> {
+        complete_in(db, id, summary, std::path::Path::new(".")).await
     }
 
-    /// Complete session
-    pub async fn complete(pool: &SqlitePool, id: &str, _summary: String) -> Result<()> {
-        // TODO: Implement session completion when schema is finalized
+    /// Complete session, capturing the git activity of the repository containing `repo`
+    pub async fn complete_in<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, summary: String, repo: &std::path::Path) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let current = sqlx::query("SELECT project_id, state, started_at FROM sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+        let project_id: String = current.get("project_id");
+        let old_state: String = current.get("state");
+        if old_state != "active" {
+            anyhow::bail!("Session {} is already {}", id, old_state);
+        }
+
+        let now = chrono::Utc::now();
+        let started_at = chrono::DateTime::parse_from_rfc3339(&current.get::<String, _>("started_at"))?.with_timezone(&chrono::Utc);
+        let activity = match git_activity::collect(repo, started_at, now) {
+            Ok(activity) => activity,
+            Err(e) => {
+                // The session still completes; only its record of commits is missing
+                log::warn!("Could not read git activity for session {}: {:#}", id, e);
+                None
+            }
+        };
+        let activity_json = activity.as_ref().map(serde_json::to_string).transpose()?;
+
         sqlx::query(r#"
-            UPDATE sessions 
-            SET state = ?
+            UPDATE sessions
+            SET state = ?, summary = ?, end_time = ?, ended_at = ?, git_activity = ?, updated_at = ?
             WHERE id = ?
         "#)
         .bind("completed")
+        .bind(&summary)
+        .bind(now.format("%H:%M:%S").to_string())
+        .bind(now.to_rfc3339())
+        .bind(&activity_json)
+        .bind(now.to_rfc3339())
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        let mut changes = vec![FieldChange::new("state", Some(&old_state), Some("completed"))];
+        if let Some(activity) = &activity {
+            changes.push(FieldChange::new("git_activity", None, Some(&format!("{} commits, +{} -{}", activity.commits.len(), activity.insertions, activity.deletions))));
+        }
+        audit::record(&mut tx, &project_id, "session", id, AuditOperation::StateChange, &changes).await?;
+        tx.commit().await?;
+
         Ok(())
     }
 
     /// Delete session (SET NULL will update dependent tasks and audit trails)
-    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    pub async fn delete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if let Some(project_id) = project_id {
+            let snapshot = audit::snapshot(&mut tx, "sessions", id).await?;
+            audit::record(&mut tx, &project_id, "session", id, AuditOperation::Delete, &[FieldChange::snapshot(snapshot, None)]).await?;
+        }
+
         sqlx::query("DELETE FROM sessions WHERE id = ?")
             .bind(id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    /// Get next sequential session ID
-    async fn get_next_session_id(pool: &SqlitePool) -> Result<String> {
-        let max_id: Option<String> = sqlx::query_scalar(
-            "SELECT id FROM sessions ORDER BY CAST(SUBSTR(id, 2) AS INTEGER) DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
-        .await?;
-
-        match max_id {
-            Some(id) => {
-                let num_str = &id[1..];
-                let num: u32 = num_str.parse().unwrap_or(0);
-                Ok(format!("S{:06}", num + 1))
-            },
-            None => Ok("S000001".to_string()),
-        }
-    }
 }
 
 /// Directive CRUD operations
@@ -695,15 +1278,16 @@ pub mod directives {
     use super::*;
 
     /// Create new directive with validation
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
         project_id: String,
         title: String,
         rule: String,
         category: DirectiveCategory,
         priority: Priority,
     ) -> Result<Directive> {
-        let next_id = get_next_directive_id(pool).await?;
+        let mut tx = db.begin().await?;
+        let next_id = IdGenerator::next(&mut *tx, &project_id, "directive").await?;
         let directive = Directive::new(next_id.clone(), project_id, title, rule)
             .map_err(|e| anyhow::anyhow!("Failed to create directive: {}", e))?;
 
@@ -721,21 +1305,27 @@ pub mod directives {
         .bind("active") // Default status
         .bind(&directive.created_at.to_rfc3339())
         .bind(&directive.updated_at.to_rfc3339())
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
         // Fetch the created directive from database to get all fields properly set
-        get_by_id(pool, &directive.id).await?.ok_or_else(|| anyhow::anyhow!("Failed to retrieve created directive"))
+        let created = get_by_id(&mut *tx, &directive.id).await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created directive"))?;
+        audit::record(&mut tx, &created.project_id, "directive", &created.id, AuditOperation::Create, &[
+            FieldChange::snapshot(None, Some(serde_json::to_string(&created)?)),
+        ]).await?;
+        tx.commit().await?;
+        Ok(created)
     }
 
     /// Get directive by ID
-    pub async fn get_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Directive>> {
+    pub async fn get_by_id<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<Option<Directive>> {
         let row = sqlx::query(r#"
             SELECT id, project_id, title, rule, priority, status, context, rationale, category, created_at, updated_at 
             FROM directives WHERE id = ?
         "#)
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(db)
         .await?;
 
         if let Some(row) = row {
@@ -795,7 +1385,13 @@ pub mod directives {
     }
 
     /// Deactivate directive
-    pub async fn deactivate(pool: &SqlitePool, id: &str) -> Result<()> {
+    pub async fn deactivate<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let current = sqlx::query("SELECT project_id, status FROM directives WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
         // Simplified implementation - just update status to inactive
         sqlx::query(r#"
             UPDATE directives 
@@ -805,36 +1401,637 @@ pub mod directives {
         .bind("inactive")
         .bind(chrono::Utc::now().to_rfc3339())
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(current) = current {
+            let project_id: String = current.get("project_id");
+            let old_status: String = current.get("status");
+            audit::record(&mut tx, &project_id, "directive", id, AuditOperation::StateChange, &[
+                FieldChange::new("status", Some(&old_status), Some("inactive")),
+            ]).await?;
+        }
+        tx.commit().await?;
+
         Ok(())
     }
 
     /// Delete directive
-    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    pub async fn delete<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<()> {
+        let mut tx = db.begin().await?;
+        let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM directives WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if let Some(project_id) = project_id {
+            let snapshot = audit::snapshot(&mut tx, "directives", id).await?;
+            audit::record(&mut tx, &project_id, "directive", id, AuditOperation::Delete, &[FieldChange::snapshot(snapshot, None)]).await?;
+        }
+
         sqlx::query("DELETE FROM directives WHERE id = ?")
             .bind(id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    /// Get next sequential directive ID
-    async fn get_next_directive_id(pool: &SqlitePool) -> Result<String> {
-        let max_id: Option<String> = sqlx::query_scalar(
-            "SELECT id FROM directives ORDER BY CAST(SUBSTR(id, 2) AS INTEGER) DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
+}
+
+/// Milestone CRUD operations
+pub mod milestones {
+    use super::*;
+    use crate::entities::schedule::parse_id_list;
+    use sqlx::SqliteConnection;
+
+    const COLUMNS: &str = "id, project_id, title, description, target_date, achieved_date, status, feature_ids, task_ids, completion_percentage, created_at, updated_at";
+
+    /// Create new milestone with validation
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
+        project_id: String,
+        title: String,
+        description: String,
+        target_date: Option<String>,
+    ) -> Result<Milestone> {
+        let mut tx = db.begin().await?;
+        let next_id = IdGenerator::next(&mut *tx, &project_id, "milestone").await?;
+        let milestone = Milestone::new(next_id, project_id, title, description, target_date)
+            .map_err(|e| anyhow::anyhow!("Failed to create milestone: {}", e))?;
+
+        sqlx::query(r#"
+            INSERT INTO milestones (id, project_id, title, description, target_date, status, feature_ids, task_ids, completion_percentage, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, '[]', '[]', 0.0, ?, ?)
+        "#)
+        .bind(&milestone.id)
+        .bind(&milestone.project_id)
+        .bind(&milestone.title)
+        .bind(&milestone.description)
+        .bind(&milestone.target_date)
+        .bind(&milestone.status)
+        .bind(&milestone.created_at.to_rfc3339())
+        .bind(&milestone.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        events::record(&mut tx, &milestone.project_id, "milestone", &milestone.id, "milestone.created", serde_json::json!({
+            "title": milestone.title,
+            "target_date": milestone.target_date,
+        })).await?;
+        audit::record(&mut tx, &milestone.project_id, "milestone", &milestone.id, AuditOperation::Create, &[
+            FieldChange::snapshot(None, Some(serde_json::to_string(&milestone)?)),
+        ]).await?;
+        tx.commit().await?;
+
+        Ok(milestone)
+    }
+
+    /// Get milestone by ID; an open milestone's completion is computed from its current members
+    pub async fn get_by_id<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<Option<Milestone>> {
+        let mut conn = db.acquire().await?;
+        let row = sqlx::query(&format!("SELECT {} FROM milestones WHERE id = ?", COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(with_completion(&mut *conn, from_row(&row)?).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List milestones by project, soonest target first
+    pub async fn list_by_project(pool: &SqlitePool, project_id: &str) -> Result<Vec<Milestone>> {
+        let mut conn = pool.acquire().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM milestones WHERE project_id = ? ORDER BY target_date IS NULL, target_date, created_at",
+            COLUMNS
+        ))
+        .bind(project_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut milestones = Vec::new();
+        for row in rows {
+            milestones.push(with_completion(&mut *conn, from_row(&row)?).await?);
+        }
+        Ok(milestones)
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Milestone> {
+        let created_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc);
+        let updated_at = chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc);
+
+        Ok(Milestone {
+            id: row.get("id"),
+            project_id: row.get("project_id"),
+            title: row.get("title"),
+            description: row.get("description"),
+            target_date: row.get("target_date"),
+            achieved_date: row.get("achieved_date"),
+            status: row.get("status"),
+            feature_ids: parse_id_list(row.get::<Option<String>, _>("feature_ids").as_deref()),
+            task_ids: parse_id_list(row.get::<Option<String>, _>("task_ids").as_deref()),
+            completion_percentage: row.get("completion_percentage"),
+            created_at,
+            updated_at,
+        })
+    }
+
+    /// Fill in the completion of an open milestone; closed ones keep the value stored when they closed
+    async fn with_completion(conn: &mut SqliteConnection, mut milestone: Milestone) -> Result<Milestone> {
+        if !MilestoneStatus::from_str(&milestone.status).map_or(false, |status| status.is_closed()) {
+            milestone.completion_percentage = completion(conn, &milestone).await?;
+        }
+        Ok(milestone)
+    }
+
+    /// Percentage of live members that are done: features with passing tests and
+    /// completed tasks. Cancelled and deleted members do not count.
+    async fn completion(conn: &mut SqliteConnection, milestone: &Milestone) -> Result<f64> {
+        let (mut done, mut total) = (0u32, 0u32);
+        for id in &milestone.feature_ids {
+            let state: Option<String> = sqlx::query_scalar("SELECT state FROM features WHERE id = ? AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?;
+            if let Some(state) = state {
+                total += 1;
+                if state == FeatureState::ImplementedPassingTests.as_str() {
+                    done += 1;
+                }
+            }
+        }
+        for id in &milestone.task_ids {
+            let status: Option<String> = sqlx::query_scalar("SELECT status FROM tasks WHERE id = ? AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?;
+            match status.as_deref() {
+                Some(status) if status == TaskStatus::Cancelled.as_str() => {}
+                Some(status) => {
+                    total += 1;
+                    if status == TaskStatus::Completed.as_str() {
+                        done += 1;
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if total == 0 {
+            return Ok(0.0);
+        }
+        Ok((done as f64 / total as f64 * 1000.0).round() / 10.0)
+    }
+
+    /// Add features and tasks to a milestone. Members must be live entities of the
+    /// milestone's project; ones already present are skipped.
+    pub async fn add_members<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
+        id: &str,
+        feature_ids: &[String],
+        task_ids: &[String],
+    ) -> Result<Milestone> {
+        let mut tx = db.begin().await?;
+        let mut milestone = get_by_id(&mut *tx, id).await?
+            .ok_or_else(|| anyhow::anyhow!("Milestone not found: {}", id))?;
+        if milestone.get_status().map_err(|e| anyhow::anyhow!(e))?.is_closed() {
+            anyhow::bail!("Milestone {} is already {}", id, milestone.status);
+        }
+
+        for feature_id in feature_ids {
+            let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM features WHERE id = ? AND deleted_at IS NULL")
+                .bind(feature_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if project_id.as_deref() != Some(milestone.project_id.as_str()) {
+                anyhow::bail!("Feature not found: {}", feature_id);
+            }
+        }
+        for task_id in task_ids {
+            let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = ? AND deleted_at IS NULL")
+                .bind(task_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if project_id.as_deref() != Some(milestone.project_id.as_str()) {
+                anyhow::bail!("Task not found: {}", task_id);
+            }
+        }
+
+        let old_features = serde_json::to_string(&milestone.feature_ids)?;
+        let old_tasks = serde_json::to_string(&milestone.task_ids)?;
+        for feature_id in feature_ids {
+            if !milestone.feature_ids.contains(feature_id) {
+                milestone.feature_ids.push(feature_id.clone());
+            }
+        }
+        for task_id in task_ids {
+            if !milestone.task_ids.contains(task_id) {
+                milestone.task_ids.push(task_id.clone());
+            }
+        }
+        let new_features = serde_json::to_string(&milestone.feature_ids)?;
+        let new_tasks = serde_json::to_string(&milestone.task_ids)?;
+        if new_features == old_features && new_tasks == old_tasks {
+            tx.commit().await?;
+            return Ok(milestone);
+        }
+
+        milestone.completion_percentage = completion(&mut tx, &milestone).await?;
+        milestone.updated_at = chrono::Utc::now();
+        sqlx::query(r#"
+            UPDATE milestones
+            SET feature_ids = ?, task_ids = ?, completion_percentage = ?, updated_at = ?
+            WHERE id = ?
+        "#)
+        .bind(&new_features)
+        .bind(&new_tasks)
+        .bind(milestone.completion_percentage)
+        .bind(milestone.updated_at.to_rfc3339())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        events::record(&mut tx, &milestone.project_id, "milestone", id, "milestone.members_added", serde_json::json!({
+            "feature_ids": feature_ids,
+            "task_ids": task_ids,
+        })).await?;
+        let mut changes = Vec::new();
+        if new_features != old_features {
+            changes.push(FieldChange::new("feature_ids", Some(&old_features), Some(&new_features)));
+        }
+        if new_tasks != old_tasks {
+            changes.push(FieldChange::new("task_ids", Some(&old_tasks), Some(&new_tasks)));
+        }
+        audit::record(&mut tx, &milestone.project_id, "milestone", id, AuditOperation::Update, &changes).await?;
+        tx.commit().await?;
+
+        Ok(milestone)
+    }
+
+    /// Close a milestone as achieved, missed or cancelled, recording the achieved
+    /// date and freezing its completion percentage
+    pub async fn close<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, status: MilestoneStatus) -> Result<Milestone> {
+        if !status.is_closed() {
+            anyhow::bail!("A milestone can only be closed as achieved, missed or cancelled");
+        }
+
+        let mut tx = db.begin().await?;
+        let mut milestone = get_by_id(&mut *tx, id).await?
+            .ok_or_else(|| anyhow::anyhow!("Milestone not found: {}", id))?;
+        if milestone.get_status().map_err(|e| anyhow::anyhow!(e))?.is_closed() {
+            anyhow::bail!("Milestone {} is already {}", id, milestone.status);
+        }
+
+        let old_status = std::mem::replace(&mut milestone.status, status.as_str().to_string());
+        if status == MilestoneStatus::Achieved {
+            milestone.achieved_date = Some(chrono::Local::now().format("%Y-%m-%d").to_string());
+        }
+        milestone.updated_at = chrono::Utc::now();
+        sqlx::query(r#"
+            UPDATE milestones
+            SET status = ?, achieved_date = ?, completion_percentage = ?, updated_at = ?
+            WHERE id = ?
+        "#)
+        .bind(&milestone.status)
+        .bind(&milestone.achieved_date)
+        .bind(milestone.completion_percentage)
+        .bind(milestone.updated_at.to_rfc3339())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        events::record(&mut tx, &milestone.project_id, "milestone", id, "milestone.closed", serde_json::json!({
+            "status": milestone.status,
+            "completion_percentage": milestone.completion_percentage,
+        })).await?;
+        audit::record(&mut tx, &milestone.project_id, "milestone", id, AuditOperation::StateChange, &[
+            FieldChange::new("status", Some(&old_status), Some(&milestone.status)),
+        ]).await?;
+        tx.commit().await?;
+
+        Ok(milestone)
+    }
+}
+
+/// Dependency (entity relationship) CRUD operations
+pub mod dependencies {
+    use super::*;
+    use crate::entities::relationships::{task_ordering, validate_relationship};
+
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Dependency {
+        Dependency {
+            id: row.get("id"),
+            project_id: row.get("project_id"),
+            from_entity_id: row.get("from_entity_id"),
+            from_entity_type: row.get("from_entity_type"),
+            to_entity_id: row.get("to_entity_id"),
+            to_entity_type: row.get("to_entity_type"),
+            dependency_type: row.get("dependency_type"),
+            description: row.get("description"),
+            created_at: row.get("created_at"),
+            resolved_at: row.get("resolved_at"),
+        }
+    }
+
+    /// Create a relationship after validating it against the typed relationship schema.
+    /// Task ordering relationships that would make a task wait on itself are rejected.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+        db: A,
+        project_id: &str,
+        from_entity_id: &str,
+        from_entity_type: EntityType,
+        to_entity_id: &str,
+        to_entity_type: EntityType,
+        dependency_type: &str,
+        description: Option<String>,
+    ) -> Result<Dependency> {
+        validate_relationship(&from_entity_type, &to_entity_type, dependency_type)
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut tx = db.begin().await?;
+        if from_entity_type == EntityType::Task && to_entity_type == EntityType::Task {
+            if let Some((dependent, prerequisite)) = task_ordering(from_entity_id, to_entity_id, dependency_type) {
+                let graph = tasks::prerequisites(&mut tx, project_id).await?;
+                if let Some(chain) = tasks::dependency_chain(&graph, prerequisite, dependent) {
+                    anyhow::bail!("Dependency cycle: {} → {}", dependent, chain.join(" → "));
+                }
+            }
+        }
+
+        let dependency = Dependency {
+            id: format!("dep-{}", uuid::Uuid::new_v4()),
+            project_id: project_id.to_string(),
+            from_entity_id: from_entity_id.to_string(),
+            from_entity_type: from_entity_type.as_str().to_string(),
+            to_entity_id: to_entity_id.to_string(),
+            to_entity_type: to_entity_type.as_str().to_string(),
+            dependency_type: dependency_type.to_string(),
+            description,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            resolved_at: None,
+        };
+
+        sqlx::query(r#"
+            INSERT INTO dependencies (id, project_id, from_entity_id, from_entity_type, to_entity_id, to_entity_type, dependency_type, description, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(&dependency.id)
+        .bind(&dependency.project_id)
+        .bind(&dependency.from_entity_id)
+        .bind(&dependency.from_entity_type)
+        .bind(&dependency.to_entity_id)
+        .bind(&dependency.to_entity_type)
+        .bind(&dependency.dependency_type)
+        .bind(&dependency.description)
+        .bind(&dependency.created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(dependency)
+    }
+
+    /// List relationships where the entity is either source or target
+    pub async fn list_for_entity(pool: &SqlitePool, entity_id: &str, entity_type: &str) -> Result<Vec<Dependency>> {
+        let rows = sqlx::query(r#"
+            SELECT id, project_id, from_entity_id, from_entity_type, to_entity_id, to_entity_type, dependency_type, description, created_at, resolved_at
+            FROM dependencies
+            WHERE (from_entity_id = ? AND from_entity_type = ?) OR (to_entity_id = ? AND to_entity_type = ?)
+            ORDER BY created_at DESC
+        "#)
+        .bind(entity_id)
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(entity_type)
+        .fetch_all(pool)
         .await?;
 
-        match max_id {
-            Some(id) => {
-                let num_str = &id[1..];
-                let num: u32 = num_str.parse().unwrap_or(0);
-                Ok(format!("D{:03}", num + 1))
-            },
-            None => Ok("D001".to_string()),
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    /// List all relationships in a project
+    pub async fn list_by_project(pool: &SqlitePool, project_id: &str) -> Result<Vec<Dependency>> {
+        let rows = sqlx::query(r#"
+            SELECT id, project_id, from_entity_id, from_entity_type, to_entity_id, to_entity_type, dependency_type, description, created_at, resolved_at
+            FROM dependencies WHERE project_id = ? ORDER BY created_at DESC
+        "#)
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(from_row).collect())
+    }
+
+    /// Mark a relationship as resolved
+    pub async fn resolve<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<()> {
+        sqlx::query("UPDATE dependencies SET resolved_at = ? WHERE id = ?")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete relationship
+    pub async fn delete<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM dependencies WHERE id = ?")
+            .bind(id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Code ownership map CRUD operations
+pub mod code_owners {
+    use super::*;
+    use crate::code_analysis::owners::{resolve_owner, OwnershipEntry, OwnershipSource};
+    use std::path::{Path, PathBuf};
+
+    /// Replace the stored ownership map for a project with a fresh scan
+    pub async fn replace_all(pool: &SqlitePool, project_id: &str, entries: &[OwnershipEntry]) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM code_owners WHERE project_id = ?")
+            .bind(project_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for entry in entries {
+            sqlx::query(r#"
+                INSERT OR REPLACE INTO code_owners (project_id, path, owners, source, scanned_at)
+                VALUES (?, ?, ?, ?, ?)
+            "#)
+            .bind(project_id)
+            .bind(entry.path.to_string_lossy().to_string())
+            .bind(serde_json::to_string(&entry.owners)?)
+            .bind(entry.source.as_str())
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// List the stored ownership map for a project
+    pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<OwnershipEntry>> {
+        let rows = sqlx::query("SELECT path, owners, source FROM code_owners WHERE project_id = ? ORDER BY path")
+            .bind(project_id)
+            .fetch_all(pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let path: String = row.get("path");
+                let owners: String = row.get("owners");
+                let source: String = row.get("source");
+                Ok(OwnershipEntry {
+                    path: PathBuf::from(path),
+                    owners: serde_json::from_str(&owners)?,
+                    source: OwnershipSource::from_str(&source).map_err(|e| anyhow::anyhow!(e))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Owners of a path relative to the project root
+    pub async fn owners_for(pool: &SqlitePool, project_id: &str, relative: &Path) -> Result<Option<OwnershipEntry>> {
+        let entries = list(pool, project_id).await?;
+        Ok(resolve_owner(&entries, relative).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_soft_delete_restore_and_purge() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = projects::create(&pool, "Trash".to_string(), "Soft delete".to_string()).await.unwrap();
+        let feature = features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let task = tasks::create(&pool, project.id.clone(), feature.id.clone(), "Write exporter".to_string(), "feature".to_string()).await.unwrap();
+        let other = tasks::create(&pool, project.id.clone(), feature.id.clone(), "Test exporter".to_string(), "testing".to_string()).await.unwrap();
+
+        // A task deleted on its own stays deleted when its feature is restored
+        tasks::delete(&pool, &other.id).await.unwrap();
+        features::delete(&pool, &feature.id).await.unwrap();
+        assert!(features::get_by_id(&pool, &feature.id).await.unwrap().is_none());
+        assert!(features::list_by_project(&pool, &project.id).await.unwrap().is_empty());
+        assert!(tasks::list_by_project(&pool, &project.id, None).await.unwrap().is_empty());
+        let trash = features::list_deleted(&pool, &project.id).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert!(trash[0].deleted_at.is_some());
+        assert!(features::delete(&pool, &feature.id).await.is_err());
+
+        features::restore(&pool, &feature.id).await.unwrap();
+        assert!(features::get_by_id(&pool, &feature.id).await.unwrap().is_some());
+        let live: Vec<String> = tasks::list_by_project(&pool, &project.id, None).await.unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(live, vec![task.id.clone()]);
+        assert!(features::restore(&pool, &feature.id).await.unwrap_err().to_string().contains("is not deleted"));
+
+        tasks::restore(&pool, &other.id).await.unwrap();
+        tasks::purge(&pool, &other.id).await.unwrap();
+        assert!(tasks::list_deleted(&pool, &project.id).await.unwrap().is_empty());
+        assert!(tasks::restore(&pool, &other.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_task_dependencies_ready_and_blocked_by() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = projects::create(&pool, "Deps".to_string(), "Task graph".to_string()).await.unwrap();
+        let feature = features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let mut ids = Vec::new();
+        for title in ["Design format", "Write exporter", "Test exporter"] {
+            ids.push(tasks::create(&pool, project.id.clone(), feature.id.clone(), title.to_string(), "feature".to_string()).await.unwrap().id);
         }
+        let (design, write, test) = (&ids[0], &ids[1], &ids[2]);
+
+        tasks::add_dependency(&pool, write, design).await.unwrap();
+        tasks::add_dependency(&pool, test, write).await.unwrap();
+        assert!(tasks::add_dependency(&pool, test, write).await.unwrap_err().to_string().contains("already depends on"));
+        let cycle = tasks::add_dependency(&pool, design, test).await.unwrap_err().to_string();
+        assert_eq!(cycle, format!("Dependency cycle: {} → {} → {} → {}", design, test, write, design));
+        assert!(tasks::add_dependency(&pool, design, design).await.is_err());
+        // The relationship path enforces the same rule
+        assert!(dependencies::create(&pool, &project.id, test, EntityType::Task, write, EntityType::Task, "blocks", None).await.is_err());
+
+        let ready: Vec<String> = tasks::ready(&pool, &project.id).await.unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ready, vec![design.clone()]);
+        let blocked: Vec<(String, Vec<String>)> = tasks::blocked_by(&pool, design).await.unwrap().into_iter().map(|(t, w)| (t.id, w)).collect();
+        assert_eq!(blocked, vec![(write.clone(), vec![design.clone()]), (test.clone(), vec![write.clone()])]);
+
+        tasks::update_status(&pool, design, TaskStatus::InProgress).await.unwrap();
+        tasks::update_status(&pool, design, TaskStatus::Completed).await.unwrap();
+        let ready: Vec<String> = tasks::ready(&pool, &project.id).await.unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ready, vec![write.clone()]);
+        assert!(tasks::blocked_by(&pool, design).await.unwrap().is_empty());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_milestone_completion_and_close() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = projects::create(&pool, "Beta".to_string(), "Milestones".to_string()).await.unwrap();
+        let feature = features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let done = tasks::create(&pool, project.id.clone(), feature.id.clone(), "Write exporter".to_string(), "feature".to_string()).await.unwrap();
+        let open = tasks::create(&pool, project.id.clone(), feature.id.clone(), "Test exporter".to_string(), "testing".to_string()).await.unwrap();
+        let dropped = tasks::create(&pool, project.id.clone(), feature.id.clone(), "Document exporter".to_string(), "documentation".to_string()).await.unwrap();
+
+        let milestone = milestones::create(&pool, project.id.clone(), "Beta".to_string(), String::new(), Some("2099-12-31".to_string())).await.unwrap();
+        assert_eq!(milestone.id, "M001");
+        assert!(milestones::add_members(&pool, &milestone.id, &[], &["T999999".to_string()]).await.unwrap_err().to_string().contains("Task not found"));
+
+        let members = [done.id.clone(), open.id.clone(), dropped.id.clone()];
+        milestones::add_members(&pool, &milestone.id, &[feature.id.clone()], &members).await.unwrap();
+        tasks::update_status(&pool, &done.id, TaskStatus::InProgress).await.unwrap();
+        tasks::update_status(&pool, &done.id, TaskStatus::Completed).await.unwrap();
+        tasks::update_status(&pool, &dropped.id, TaskStatus::Cancelled).await.unwrap();
+
+        // One of three counted members is done; the cancelled task is left out
+        let milestone = milestones::get_by_id(&pool, &milestone.id).await.unwrap().unwrap();
+        assert_eq!(milestone.completion_percentage, 33.3);
+        features::update_state(&pool, &feature.id, FeatureState::ImplementedPassingTests).await.unwrap();
+        assert_eq!(milestones::list_by_project(&pool, &project.id).await.unwrap()[0].completion_percentage, 66.7);
+
+        // Closing ahead of the target date records the achieved date and freezes completion
+        let closed = milestones::close(&pool, &milestone.id, MilestoneStatus::Achieved).await.unwrap();
+        assert_eq!(closed.status, "achieved");
+        assert!(closed.achieved_date.is_some());
+        tasks::update_status(&pool, &open.id, TaskStatus::InProgress).await.unwrap();
+        tasks::update_status(&pool, &open.id, TaskStatus::Completed).await.unwrap();
+        assert_eq!(milestones::get_by_id(&pool, &milestone.id).await.unwrap().unwrap().completion_percentage, 66.7);
+        assert!(milestones::close(&pool, &milestone.id, MilestoneStatus::Missed).await.unwrap_err().to_string().contains("already achieved"));
+    }
+
+    #[tokio::test]
+    async fn test_acceptance_criteria_match_whole_feature_ids() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = projects::create(&pool, "Review".to_string(), "Acceptance".to_string()).await.unwrap();
+        let feature = features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let mut ids = Vec::new();
+        for (title, feature_ids) in [("Plain", feature.id.clone()), ("Array", format!(r#"["F99999","{}"]"#, feature.id)), ("Longer", format!("{}0", feature.id)), ("Longer array", format!(r#"["{}0"]"#, feature.id))] {
+            let task = tasks::create(&pool, project.id.clone(), feature.id.clone(), title.to_string(), "feature".to_string()).await.unwrap();
+            sqlx::query("UPDATE tasks SET feature_ids = ?, acceptance_criteria = ? WHERE id = ?")
+                .bind(feature_ids).bind(format!("{} passes", title)).bind(&task.id)
+                .execute(&pool).await.unwrap();
+            ids.push(task.id);
+        }
+        let deleted = tasks::create(&pool, project.id.clone(), feature.id.clone(), "Deleted".to_string(), "feature".to_string()).await.unwrap();
+        sqlx::query("UPDATE tasks SET acceptance_criteria = 'Gone' WHERE id = ?").bind(&deleted.id).execute(&pool).await.unwrap();
+        tasks::delete(&pool, &deleted.id).await.unwrap();
+
+        let criteria = tasks::acceptance_criteria(&pool, &feature.id).await.unwrap();
+        assert_eq!(criteria, vec![(ids[0].clone(), "Plain passes".to_string()), (ids[1].clone(), "Array passes".to_string())]);
+    }
+}
@@ -10,6 +10,11 @@ use tokio::fs as async_fs;
 
 /// Initialize SQLite database with all required tables and indexes
 pub async fn initialize_database(db_path: &Path) -> Result<SqlitePool> {
+    // Query commands never create or migrate the database
+    if crate::read_only::is_enabled() {
+        return open_database_read_only(db_path).await;
+    }
+
     let database_url = format!("sqlite:{}", db_path.display());
     
     // Create database if it doesn't exist
@@ -32,6 +37,47 @@ pub async fn initialize_database(db_path: &Path) -> Result<SqlitePool> {
     Ok(pool)
 }
 
+/// Open an existing database without creating, migrating, or writing to it
+pub async fn open_database_read_only(db_path: &Path) -> Result<SqlitePool> {
+    if !db_path.exists() {
+        anyhow::bail!("No workspace database at {} (read-only mode does not create one)", db_path.display());
+    }
+
+    let database_url = format!("sqlite:{}?mode=ro", db_path.display());
+    let pool = SqlitePool::connect(&database_url).await?;
+    Ok(pool)
+}
+
+/// Milestones table; also used to rebuild tables created with the old date constraint
+pub(crate) const MILESTONES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS milestones (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL,
+            target_date TEXT,
+            achieved_date TEXT,
+            status TEXT NOT NULL DEFAULT 'planned',
+            feature_ids TEXT,
+            success_criteria TEXT,
+            completion_percentage REAL NOT NULL DEFAULT 0.0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            metadata TEXT,
+            
+            -- Foreign Key Constraints
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            
+            -- Check constraints for data integrity
+            CONSTRAINT chk_milestones_status CHECK (status IN (
+                'planned', 'in_progress', 'achieved', 'missed', 'cancelled'
+            )),
+            CONSTRAINT chk_milestones_completion CHECK (
+                completion_percentage >= 0.0 AND completion_percentage <= 100.0
+            )
+        )
+    "#;
+
 /// Create all required tables with proper constraints and indexes
 pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
     // Enable foreign key constraints
@@ -63,7 +109,7 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
             
             -- Check constraints for data integrity
             CONSTRAINT chk_projects_status CHECK (status IN ('active', 'paused', 'completed', 'archived')),
-            CONSTRAINT chk_projects_id_pattern CHECK (id GLOB 'P[0-9][0-9][0-9]')
+            CONSTRAINT chk_projects_id_pattern CHECK (id GLOB '[A-Z]*[0-9]' AND id NOT GLOB '*[^A-Z0-9]*')
         )
     "#)
     .execute(pool)
@@ -107,8 +153,8 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
                 'not_tested', 'failing', 'passing', 'broken', 'tautological'
             )),
             CONSTRAINT chk_features_priority CHECK (priority IN ('critical', 'high', 'medium', 'low')),
-            CONSTRAINT chk_features_id_pattern CHECK (id GLOB 'F[0-9][0-9][0-9][0-9][0-9]'),
-            CONSTRAINT chk_features_code_pattern CHECK (code GLOB 'F[0-9][0-9][0-9][0-9][0-9]'),
+            CONSTRAINT chk_features_id_pattern CHECK (id GLOB '[A-Z]*[0-9]' AND id NOT GLOB '*[^A-Z0-9]*'),
+            CONSTRAINT chk_features_code_pattern CHECK (code GLOB '[A-Z]*[0-9]' AND code NOT GLOB '*[^A-Z0-9]*'),
             CONSTRAINT chk_features_notes_length CHECK (LENGTH(notes) <= 100 OR notes IS NULL)
         )
     "#)
@@ -158,8 +204,8 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
             CONSTRAINT chk_tasks_category CHECK (category IN (
                 'feature', 'bug', 'refactor', 'testing', 'documentation', 'infrastructure', 'api', 'migration'
             )),
-            CONSTRAINT chk_tasks_id_pattern CHECK (id GLOB 'T[0-9][0-9][0-9][0-9][0-9][0-9]'),
-            CONSTRAINT chk_tasks_code_pattern CHECK (code GLOB 'T[0-9][0-9][0-9][0-9][0-9][0-9]'),
+            CONSTRAINT chk_tasks_id_pattern CHECK (id GLOB '[A-Z]*[0-9]' AND id NOT GLOB '*[^A-Z0-9]*'),
+            CONSTRAINT chk_tasks_code_pattern CHECK (code GLOB '[A-Z]*[0-9]' AND code NOT GLOB '*[^A-Z0-9]*'),
             CONSTRAINT chk_tasks_notes_length CHECK (LENGTH(notes) <= 100 OR notes IS NULL)
         )
     "#)
@@ -202,7 +248,7 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
             
             -- Check constraints for data integrity
             CONSTRAINT chk_sessions_state CHECK (state IN ('active', 'completed', 'cancelled')),
-            CONSTRAINT chk_sessions_id_pattern CHECK (id GLOB 'S[0-9][0-9][0-9][0-9][0-9][0-9]')
+            CONSTRAINT chk_sessions_id_pattern CHECK (id GLOB '[A-Z]*[0-9]' AND id NOT GLOB '*[^A-Z0-9]*')
         )
     "#)
     .execute(pool)
@@ -240,8 +286,8 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
             -- Check constraints for data integrity
             CONSTRAINT chk_directives_priority CHECK (priority IN ('critical', 'high', 'medium', 'low')),
             CONSTRAINT chk_directives_status CHECK (status IN ('active', 'inactive', 'archived')),
-            CONSTRAINT chk_directives_id_pattern CHECK (id GLOB 'D[0-9][0-9][0-9]'),
-            CONSTRAINT chk_directives_code_pattern CHECK (code GLOB 'D[0-9][0-9][0-9]')
+            CONSTRAINT chk_directives_id_pattern CHECK (id GLOB '[A-Z]*[0-9]' AND id NOT GLOB '*[^A-Z0-9]*'),
+            CONSTRAINT chk_directives_code_pattern CHECK (code GLOB '[A-Z]*[0-9]' AND code NOT GLOB '*[^A-Z0-9]*')
         )
     "#)
     .execute(pool)
@@ -331,7 +377,8 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
                 to_entity_type IN ('project', 'feature', 'task', 'session', 'directive')
             ),
             CONSTRAINT chk_dependencies_type CHECK (dependency_type IN (
-                'blocks', 'requires', 'implements', 'tests', 'documents', 'references'
+                'blocks', 'requires', 'implements', 'tests', 'documents', 'references',
+                'depends_on', 'worked_in'
             )),
             CONSTRAINT chk_dependencies_not_self CHECK (from_entity_id != to_entity_id OR from_entity_type != to_entity_type)
         )
@@ -377,39 +424,9 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
     .await?;
 
     // Milestones table - project milestones with feature linkage and proper constraints
-    sqlx::query(r#"
-        CREATE TABLE IF NOT EXISTS milestones (
-            id TEXT PRIMARY KEY,
-            project_id TEXT NOT NULL,
-            title TEXT NOT NULL,
-            description TEXT NOT NULL,
-            target_date TEXT,
-            achieved_date TEXT,
-            status TEXT NOT NULL DEFAULT 'planned',
-            feature_ids TEXT,
-            success_criteria TEXT,
-            completion_percentage REAL NOT NULL DEFAULT 0.0,
-            created_at TEXT NOT NULL DEFAULT (datetime('now')),
-            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
-            metadata TEXT,
-            
-            -- Foreign Key Constraints
-            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
-            
-            -- Check constraints for data integrity
-            CONSTRAINT chk_milestones_status CHECK (status IN (
-                'planned', 'in_progress', 'achieved', 'missed', 'cancelled'
-            )),
-            CONSTRAINT chk_milestones_completion CHECK (
-                completion_percentage >= 0.0 AND completion_percentage <= 100.0
-            ),
-            CONSTRAINT chk_milestones_dates CHECK (
-                achieved_date IS NULL OR target_date IS NULL OR achieved_date >= target_date OR status != 'achieved'
-            )
-        )
-    "#)
-    .execute(pool)
-    .await?;
+    sqlx::query(MILESTONES_TABLE)
+        .execute(pool)
+        .await?;
 
     // Feature state transitions table - audit trail with proper constraints
     sqlx::query(r#"
@@ -553,6 +570,105 @@ pub async fn initialize_tables(pool: &SqlitePool) -> Result<()> {
     .execute(pool)
     .await?;
 
+    // Event outbox for reliable integration delivery
+    crate::entities::events::initialize_outbox_table(pool).await?;
+
+    // Actor attribution for shared databases
+    crate::entities::actors::initialize_actor_tables(pool).await?;
+
+    // Due dates and repeat rules for the calendar export
+    crate::entities::calendar::initialize_calendar_columns(pool).await?;
+
+    // Template links of recurring task instances
+    crate::entities::recurrence::initialize_recurrence_columns(pool).await?;
+
+    // Start/stop timers on tasks
+    crate::entities::time_tracking::initialize_time_entries_table(pool).await?;
+
+    // Files attached to notes, with their checksums
+    crate::entities::notes::initialize_note_attachments_table(pool).await?;
+
+    // Named filters for entity lists
+    crate::entities::views::initialize_views_table(pool).await?;
+
+    // Tags shared by features, tasks, notes and sessions
+    crate::entities::tags::initialize_tag_tables(pool).await?;
+
+    // Per-project ID sequences with configurable prefixes and widths
+    crate::entities::ids::initialize_id_counters_table(pool).await?;
+
+    // Database-wide settings such as the current project
+    crate::entities::workspace::initialize_workspace_state_table(pool).await?;
+
+    // Commits and diffstat captured when a session completes
+    crate::entities::git_activity::initialize_git_activity_column(pool).await?;
+
+    // Sprints and the tasks planned into them
+    crate::entities::sprints::initialize_sprint_tables(pool).await?;
+
+    // Machine-checkable directive rules and the violations of their latest run
+    crate::entities::directive_rules::initialize_directive_rule_tables(pool).await?;
+
+    // Module ownership map from CODEOWNERS and @owner annotations
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS code_owners (
+            project_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            owners TEXT NOT NULL,
+            source TEXT NOT NULL,
+            scanned_at TEXT NOT NULL,
+
+            PRIMARY KEY (project_id, path),
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            CONSTRAINT chk_code_owners_source CHECK (source IN ('codeowners', 'annotation'))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    // Long-form markdown bodies of features and tasks, kept apart from the summary line
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS entity_bodies (
+            entity_id TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            body TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+
+            CONSTRAINT chk_entity_bodies_type CHECK (entity_type IN ('feature', 'task'))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    // Versions written by `wsb update`, checked so a new version never goes backwards
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS version_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            version TEXT NOT NULL,
+            commit_hash TEXT,
+            forced BOOLEAN NOT NULL DEFAULT FALSE,
+            recorded_at TEXT NOT NULL
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    // Independently versioned monorepo packages (Cargo and npm workspace members)
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS package_versions (
+            name TEXT PRIMARY KEY,
+            path TEXT NOT NULL,
+            major INTEGER NOT NULL DEFAULT 0,
+            minor INTEGER NOT NULL DEFAULT 0,
+            patch INTEGER NOT NULL DEFAULT 0,
+            base_commit TEXT,
+            version TEXT,
+            updated_at TEXT NOT NULL
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
     // Create indexes for performance
     create_indexes(pool).await?;
 
@@ -728,16 +844,9 @@ async fn create_indexes(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
-/// Simple schema version tracking for future changes
+/// Bring the schema up to date by applying pending migrations
 pub async fn ensure_current_schema(pool: &SqlitePool) -> Result<()> {
-    let current_version = get_schema_version(pool).await?;
-    let target_version = 1; // Current schema version
-    
-    if current_version < target_version {
-        log::info!("Setting initial schema version to {}", target_version);
-        set_schema_version(pool, target_version).await?;
-    }
-    
+    crate::entities::migrations::migrate(pool).await?;
     Ok(())
 }
 
@@ -760,17 +869,6 @@ async fn get_schema_version(pool: &SqlitePool) -> Result<i32> {
     Ok(result.unwrap_or(0))
 }
 
-/// Set schema version
-async fn set_schema_version(pool: &SqlitePool, version: i32) -> Result<()> {
-    sqlx::query("INSERT INTO schema_version (version) VALUES (?)")
-        .bind(version)
-        .execute(pool)
-        .await?;
-    
-    Ok(())
-}
-
-
 /// Analyze index usage and performance
 pub async fn analyze_index_performance(pool: &SqlitePool) -> Result<IndexPerformanceReport> {
     // Get index usage statistics
@@ -1092,7 +1190,7 @@ async fn compress_file(file_path: &Path) -> Result<Vec<u8>> {
 }
 
 /// Decompress gzip data
-async fn decompress_data(compressed_data: &[u8]) -> Result<Vec<u8>> {
+pub(crate) async fn decompress_data(compressed_data: &[u8]) -> Result<Vec<u8>> {
     use flate2::read::GzDecoder;
     use std::io::prelude::*;
     
@@ -1103,7 +1201,7 @@ async fn decompress_data(compressed_data: &[u8]) -> Result<Vec<u8>> {
 }
 
 /// Calculate SHA-256 checksum of file
-async fn calculate_file_checksum(file_path: &Path) -> Result<String> {
+pub(crate) async fn calculate_file_checksum(file_path: &Path) -> Result<String> {
     use sha2::{Sha256, Digest};
     
     let file_data = async_fs::read(file_path).await?;
@@ -1236,7 +1334,7 @@ pub async fn create_context_snapshot(pool: &SqlitePool, project_id: &str) -> Res
     let achievement_rows = sqlx::query(r#"
         SELECT title, completion_notes 
         FROM tasks 
-        WHERE project_id = ? AND status = 'completed'
+        WHERE project_id = ? AND status = 'completed' AND deleted_at IS NULL
         ORDER BY updated_at DESC
         LIMIT 5
     "#)
@@ -1260,7 +1358,7 @@ pub async fn create_context_snapshot(pool: &SqlitePool, project_id: &str) -> Res
     let issue_rows = sqlx::query(r#"
         SELECT title, notes 
         FROM features 
-        WHERE project_id = ? AND state IN ('tests_broken', 'critical_issue')
+        WHERE project_id = ? AND state IN ('tests_broken', 'critical_issue') AND deleted_at IS NULL
         ORDER BY updated_at DESC
     "#)
     .bind(project_id)
@@ -1283,7 +1381,7 @@ pub async fn create_context_snapshot(pool: &SqlitePool, project_id: &str) -> Res
     let priority_rows = sqlx::query(r#"
         SELECT title, description 
         FROM tasks 
-        WHERE project_id = ? AND status = 'pending' AND priority = 'high'
+        WHERE project_id = ? AND status = 'pending' AND priority = 'high' AND deleted_at IS NULL
         ORDER BY created_at ASC
         LIMIT 5
     "#)
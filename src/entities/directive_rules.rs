@@ -0,0 +1,501 @@
+// Directive Rules - Machine-checkable rules behind project directives
+// A directive in internal/directives.md states a rule in prose; `wsb directive rule add`
+// attaches checks that can actually be run: regexes forbidden in files matching a glob,
+// files that must exist, a maximum file size and commands that must exit successfully.
+// `wsb directive validate` and `wsb directive check` evaluate them and store the
+// violations of the latest run per directive, which `list` and `show` report.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Acquire, Executor, Row, Sqlite, SqlitePool};
+
+pub async fn initialize_directive_rule_tables(pool: &SqlitePool) -> Result<()> {
+    // directive_id is a directives.md id (DIR-…) or a database directive id, so it has no
+    // foreign key
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS directive_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            directive_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            glob TEXT,
+            pattern TEXT,
+            max_bytes INTEGER,
+            command TEXT,
+            hint TEXT,
+            created_at TEXT NOT NULL,
+
+            CONSTRAINT chk_directive_rules_kind CHECK (kind IN (
+                'forbidden_pattern', 'required_file', 'max_file_size', 'command'
+            ))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS directive_violations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            directive_id TEXT NOT NULL,
+            rule_id INTEGER NOT NULL,
+            path TEXT,
+            line INTEGER,
+            message TEXT NOT NULL,
+            detected_at TEXT NOT NULL,
+
+            FOREIGN KEY (rule_id) REFERENCES directive_rules (id) ON DELETE CASCADE
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS directive_validations (
+            directive_id TEXT PRIMARY KEY,
+            validated_at TEXT NOT NULL,
+            violation_count INTEGER NOT NULL
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_directive_rules_directive ON directive_rules (directive_id)")
+        .execute(pool)
+        .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_directive_violations_directive ON directive_violations (directive_id)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// What a rule checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSpec {
+    /// No line of a file matching `glob` (every file when None) may match `pattern`
+    ForbiddenPattern { glob: Option<String>, pattern: String },
+    /// `path`, relative to the project root, must exist
+    RequiredFile { path: String },
+    /// No file matching `glob` may be larger than `max_bytes`
+    MaxFileSize { glob: Option<String>, max_bytes: u64 },
+    /// `command` must exit successfully when run by `sh -c` in the project root
+    Command { command: String },
+}
+
+impl RuleSpec {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RuleSpec::ForbiddenPattern { .. } => "forbidden_pattern",
+            RuleSpec::RequiredFile { .. } => "required_file",
+            RuleSpec::MaxFileSize { .. } => "max_file_size",
+            RuleSpec::Command { .. } => "command",
+        }
+    }
+
+    /// Whether the rule looks at individual files, and so applies to `wsb directive check PATHS`
+    pub fn is_file_rule(&self) -> bool {
+        matches!(self, RuleSpec::ForbiddenPattern { .. } | RuleSpec::MaxFileSize { .. })
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            RuleSpec::ForbiddenPattern { glob, pattern } => {
+                regex::Regex::new(pattern).with_context(|| format!("Invalid regex pattern: {}", pattern))?;
+                if let Some(glob) = glob {
+                    compile_glob(Path::new(""), glob)?;
+                }
+            }
+            RuleSpec::RequiredFile { path } => {
+                if path.trim().is_empty() {
+                    anyhow::bail!("Required file path cannot be empty");
+                }
+            }
+            RuleSpec::MaxFileSize { glob, .. } => {
+                if let Some(glob) = glob {
+                    compile_glob(Path::new(""), glob)?;
+                }
+            }
+            RuleSpec::Command { command } => {
+                if command.trim().is_empty() {
+                    anyhow::bail!("Command cannot be empty");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// One-line description for listings
+    pub fn describe(&self) -> String {
+        match self {
+            RuleSpec::ForbiddenPattern { glob, pattern } => {
+                format!("forbid /{}/ in {}", pattern, glob.as_deref().unwrap_or("all files"))
+            }
+            RuleSpec::RequiredFile { path } => format!("require {}", path),
+            RuleSpec::MaxFileSize { glob, max_bytes } => {
+                format!("max {} bytes for {}", max_bytes, glob.as_deref().unwrap_or("all files"))
+            }
+            RuleSpec::Command { command } => format!("command must pass: {}", command),
+        }
+    }
+}
+
+/// A stored rule
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectiveRule {
+    pub id: i64,
+    pub directive_id: String,
+    #[serde(skip)]
+    pub spec: RuleSpec,
+    pub kind: String,
+    pub description: String,
+    /// How to fix a violation, printed next to it
+    pub hint: Option<String>,
+    pub created_at: String,
+}
+
+/// One failed check
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    pub directive_id: String,
+    pub rule_id: i64,
+    /// File relative to the project root; None for command rules
+    pub path: Option<String>,
+    /// 1-based line of a forbidden pattern match
+    pub line: Option<i64>,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+/// Result of the latest validation of a directive
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationState {
+    pub validated_at: String,
+    pub violation_count: i64,
+}
+
+fn rule_from_row(row: &SqliteRow) -> Result<DirectiveRule> {
+    let kind: String = row.get("kind");
+    let glob: Option<String> = row.get("glob");
+    let spec = match kind.as_str() {
+        "forbidden_pattern" => RuleSpec::ForbiddenPattern { glob, pattern: row.get::<Option<String>, _>("pattern").unwrap_or_default() },
+        "required_file" => RuleSpec::RequiredFile { path: glob.unwrap_or_default() },
+        "max_file_size" => RuleSpec::MaxFileSize { glob, max_bytes: row.get::<Option<i64>, _>("max_bytes").unwrap_or(0).max(0) as u64 },
+        "command" => RuleSpec::Command { command: row.get::<Option<String>, _>("command").unwrap_or_default() },
+        other => anyhow::bail!("Unknown directive rule kind: {}", other),
+    };
+    Ok(DirectiveRule {
+        id: row.get("id"),
+        directive_id: row.get("directive_id"),
+        kind,
+        description: spec.describe(),
+        spec,
+        hint: row.get("hint"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// Attach a rule to a directive; returns its id
+pub async fn add_rule<'e, E: Executor<'e, Database = Sqlite>>(db: E, directive_id: &str, spec: &RuleSpec, hint: Option<&str>) -> Result<i64> {
+    spec.validate()?;
+    let (glob, pattern, max_bytes, command) = match spec {
+        RuleSpec::ForbiddenPattern { glob, pattern } => (glob.clone(), Some(pattern.clone()), None, None),
+        RuleSpec::RequiredFile { path } => (Some(path.clone()), None, None, None),
+        RuleSpec::MaxFileSize { glob, max_bytes } => (glob.clone(), None, Some(*max_bytes as i64), None),
+        RuleSpec::Command { command } => (None, None, None, Some(command.clone())),
+    };
+
+    let result = sqlx::query(r#"
+        INSERT INTO directive_rules (directive_id, kind, glob, pattern, max_bytes, command, hint, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    "#)
+    .bind(directive_id)
+    .bind(spec.kind())
+    .bind(glob)
+    .bind(pattern)
+    .bind(max_bytes)
+    .bind(command)
+    .bind(hint)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+/// Delete a rule and the violations recorded against it
+pub async fn remove_rule<'c, A: Acquire<'c, Database = Sqlite>>(db: A, rule_id: i64) -> Result<()> {
+    let mut conn = db.acquire().await?;
+    let mut tx = conn.begin().await?;
+    sqlx::query("DELETE FROM directive_violations WHERE rule_id = ?")
+        .bind(rule_id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query("DELETE FROM directive_rules WHERE id = ?")
+        .bind(rule_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        anyhow::bail!("Directive rule not found: {}", rule_id);
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Rules of one directive, or of all directives, oldest first
+pub async fn list_rules(pool: &SqlitePool, directive_id: Option<&str>) -> Result<Vec<DirectiveRule>> {
+    let rows = sqlx::query("SELECT * FROM directive_rules WHERE (? IS NULL OR directive_id = ?) ORDER BY id")
+        .bind(directive_id)
+        .bind(directive_id)
+        .fetch_all(pool)
+        .await?;
+    rows.iter().map(rule_from_row).collect()
+}
+
+/// Violations found by the latest run for a directive
+pub async fn violations(pool: &SqlitePool, directive_id: &str) -> Result<Vec<Violation>> {
+    let rows = sqlx::query(r#"
+        SELECT v.directive_id, v.rule_id, v.path, v.line, v.message, r.hint
+        FROM directive_violations v
+        JOIN directive_rules r ON r.id = v.rule_id
+        WHERE v.directive_id = ?
+        ORDER BY v.id
+    "#)
+    .bind(directive_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(|row| Violation {
+        directive_id: row.get("directive_id"),
+        rule_id: row.get("rule_id"),
+        path: row.get("path"),
+        line: row.get("line"),
+        message: row.get("message"),
+        hint: row.get("hint"),
+    }).collect())
+}
+
+/// Latest validation of every directive that has been validated. Read-only opens of
+/// databases created before the table existed have none.
+pub async fn validation_states(pool: &SqlitePool) -> Result<HashMap<String, ValidationState>> {
+    let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'directive_validations'")
+        .fetch_one(pool)
+        .await?;
+    if exists == 0 {
+        return Ok(HashMap::new());
+    }
+    let rows = sqlx::query("SELECT directive_id, validated_at, violation_count FROM directive_validations")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(|row| (
+        row.get("directive_id"),
+        ValidationState { validated_at: row.get("validated_at"), violation_count: row.get("violation_count") },
+    )).collect())
+}
+
+/// Replace the stored violations of `directive_id` with the result of a run
+pub async fn record_run<'c, A: Acquire<'c, Database = Sqlite>>(db: A, directive_id: &str, found: &[Violation]) -> Result<()> {
+    let mut conn = db.acquire().await?;
+    let mut tx = conn.begin().await?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query("DELETE FROM directive_violations WHERE directive_id = ?")
+        .bind(directive_id)
+        .execute(&mut *tx)
+        .await?;
+    for violation in found {
+        sqlx::query(r#"
+            INSERT INTO directive_violations (directive_id, rule_id, path, line, message, detected_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(directive_id)
+        .bind(violation.rule_id)
+        .bind(&violation.path)
+        .bind(violation.line)
+        .bind(&violation.message)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(r#"
+        INSERT INTO directive_validations (directive_id, validated_at, violation_count) VALUES (?, ?, ?)
+        ON CONFLICT (directive_id) DO UPDATE SET validated_at = excluded.validated_at, violation_count = excluded.violation_count
+    "#)
+    .bind(directive_id)
+    .bind(&now)
+    .bind(found.len() as i64)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+fn compile_glob(root: &Path, glob: &str) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root);
+    builder.add_line(None, glob).with_context(|| format!("Invalid glob pattern: {}", glob))?;
+    Ok(builder.build()?)
+}
+
+/// Files under `root`, or under `paths` when given, honouring .gitignore and skipping
+/// hidden entries such as .git and .wsb
+fn files_to_check(root: &Path, paths: Option<&[PathBuf]>) -> Vec<PathBuf> {
+    let starts: Vec<PathBuf> = match paths {
+        Some(paths) => paths.iter().map(|path| if path.is_absolute() { path.clone() } else { root.join(path) }).collect(),
+        None => vec![root.to_path_buf()],
+    };
+    let mut files = Vec::new();
+    for start in starts {
+        for entry in ignore::WalkBuilder::new(&start).build().flatten() {
+            if entry.file_type().is_some_and(|kind| kind.is_file()) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+/// Evaluate `rules` against the project at `root`. With `paths`, only file rules run and
+/// only against files under those paths; project-wide rules (required files, commands)
+/// run without them.
+pub fn evaluate(root: &Path, rules: &[DirectiveRule], paths: Option<&[PathBuf]>) -> Result<Vec<Violation>> {
+    let files = if rules.iter().any(|rule| rule.spec.is_file_rule()) {
+        files_to_check(root, paths)
+    } else {
+        Vec::new()
+    };
+
+    let mut found = Vec::new();
+    for rule in rules {
+        if paths.is_some() && !rule.spec.is_file_rule() {
+            continue;
+        }
+        let violation = |path: Option<String>, line: Option<i64>, message: String| Violation {
+            directive_id: rule.directive_id.clone(),
+            rule_id: rule.id,
+            path,
+            line,
+            message,
+            hint: rule.hint.clone(),
+        };
+
+        match &rule.spec {
+            RuleSpec::ForbiddenPattern { glob, pattern } => {
+                let regex = regex::Regex::new(pattern).with_context(|| format!("Invalid regex pattern: {}", pattern))?;
+                let glob = glob.as_deref().map(|glob| compile_glob(root, glob)).transpose()?;
+                for file in &files {
+                    let rel = relative(root, file);
+                    if glob.as_ref().is_some_and(|glob| !glob.matched_path_or_any_parents(&rel, false).is_ignore()) {
+                        continue;
+                    }
+                    // Binary and unreadable files have no lines to match
+                    let Ok(content) = std::fs::read_to_string(file) else { continue };
+                    for (index, text) in content.lines().enumerate() {
+                        if regex.is_match(text) {
+                            found.push(violation(Some(rel.clone()), Some(index as i64 + 1), format!("forbidden pattern /{}/ found", pattern)));
+                        }
+                    }
+                }
+            }
+            RuleSpec::RequiredFile { path } => {
+                if !root.join(path).exists() {
+                    found.push(violation(Some(path.clone()), None, "required file is missing".to_string()));
+                }
+            }
+            RuleSpec::MaxFileSize { glob, max_bytes } => {
+                let glob = glob.as_deref().map(|glob| compile_glob(root, glob)).transpose()?;
+                for file in &files {
+                    let rel = relative(root, file);
+                    if glob.as_ref().is_some_and(|glob| !glob.matched_path_or_any_parents(&rel, false).is_ignore()) {
+                        continue;
+                    }
+                    let size = std::fs::metadata(file).map(|meta| meta.len()).unwrap_or(0);
+                    if size > *max_bytes {
+                        found.push(violation(Some(rel), None, format!("{} bytes exceeds the {} byte limit", size, max_bytes)));
+                    }
+                }
+            }
+            RuleSpec::Command { command } => {
+                let output = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .current_dir(root)
+                    .output()
+                    .with_context(|| format!("Failed to run directive command: {}", command))?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let detail = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+                    let code = output.status.code().map(|code| code.to_string()).unwrap_or_else(|| "signal".to_string());
+                    let message = if detail.is_empty() {
+                        format!("`{}` failed (exit {})", command, code)
+                    } else {
+                        format!("`{}` failed (exit {}): {}", command, code, detail)
+                    };
+                    found.push(violation(None, None, message));
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_rules_find_and_record_violations() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let root = temp.path().join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn ok() {}\n// TODO: remove\nlet password = \"x\";\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "TODO in notes is fine\n").unwrap();
+        std::fs::write(root.join("big.bin"), vec![0u8; 64]).unwrap();
+
+        let forbid = RuleSpec::ForbiddenPattern { glob: Some("*.rs".to_string()), pattern: "TODO|password".to_string() };
+        add_rule(&pool, "DIR-1", &forbid, Some("Resolve before merging")).await.unwrap();
+        add_rule(&pool, "DIR-1", &RuleSpec::RequiredFile { path: "README.md".to_string() }, None).await.unwrap();
+        add_rule(&pool, "DIR-2", &RuleSpec::MaxFileSize { glob: None, max_bytes: 32 }, None).await.unwrap();
+        add_rule(&pool, "DIR-2", &RuleSpec::Command { command: "echo broken >&2; exit 3".to_string() }, None).await.unwrap();
+        assert!(add_rule(&pool, "DIR-3", &RuleSpec::ForbiddenPattern { glob: None, pattern: "(".to_string() }, None).await.is_err());
+
+        let first = list_rules(&pool, Some("DIR-1")).await.unwrap();
+        let found = evaluate(&root, &first, None).unwrap();
+        assert_eq!(found.iter().filter(|v| v.path.as_deref() == Some("src/lib.rs")).map(|v| v.line.unwrap()).collect::<Vec<_>>(), [2, 3]);
+        assert!(found.iter().any(|v| v.path.as_deref() == Some("README.md") && v.message.contains("missing")));
+        assert_eq!(found[0].hint.as_deref(), Some("Resolve before merging"));
+
+        let second = list_rules(&pool, Some("DIR-2")).await.unwrap();
+        let found_second = evaluate(&root, &second, None).unwrap();
+        assert!(found_second.iter().any(|v| v.path.as_deref() == Some("big.bin")));
+        assert!(found_second.iter().any(|v| v.path.is_none() && v.message.contains("exit 3") && v.message.contains("broken")));
+
+        // Checking paths runs only file rules against those paths
+        let in_notes = evaluate(&root, &first, Some(&[PathBuf::from("notes.txt")])).unwrap();
+        assert!(in_notes.is_empty());
+
+        record_run(&pool, "DIR-1", &found).await.unwrap();
+        assert_eq!(violations(&pool, "DIR-1").await.unwrap().len(), 3);
+        assert_eq!(validation_states(&pool).await.unwrap()["DIR-1"].violation_count, 3);
+
+        std::fs::write(root.join("src/lib.rs"), "fn ok() {}\n").unwrap();
+        std::fs::write(root.join("README.md"), "# Repo\n").unwrap();
+        record_run(&pool, "DIR-1", &evaluate(&root, &first, None).unwrap()).await.unwrap();
+        assert!(violations(&pool, "DIR-1").await.unwrap().is_empty());
+        assert_eq!(validation_states(&pool).await.unwrap()["DIR-1"].violation_count, 0);
+
+        remove_rule(&pool, first[0].id).await.unwrap();
+        assert_eq!(list_rules(&pool, None).await.unwrap().len(), 3);
+        assert!(remove_rule(&pool, first[0].id).await.is_err());
+    }
+}
@@ -0,0 +1,301 @@
+// Documentation Coverage - Whether each feature has linked documentation
+// A feature counts as documented by a heading in a doc template, by an architecture
+// note attached to it, or by a doc file mapped to it under `tools.doc_coverage`.
+// Reported by `status`; completed features without documentation are a `--risks` item.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::crud;
+use super::schema_models::FeatureState;
+use crate::st8::templates::TemplateManager;
+
+/// Tag that marks a note as architecture documentation. The notes schema has no
+/// `architecture` type, so decision notes and notes carrying this tag both count.
+pub const ARCHITECTURE_TAG: &str = "architecture";
+
+/// Documentation settings stored under `tools.doc_coverage` in `.wsb/state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DocCoverageConfig {
+    /// Feature ID → doc files (relative to the project root) that document it
+    pub doc_files: BTreeMap<String, Vec<String>>,
+}
+
+/// One piece of documentation linked to a feature
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocSource {
+    TemplateSection { template: String, heading: String },
+    Note { id: String, title: String },
+    MappedFile { path: String },
+}
+
+impl DocSource {
+    fn kind(&self) -> usize {
+        match self {
+            DocSource::TemplateSection { .. } => 0,
+            DocSource::Note { .. } => 1,
+            DocSource::MappedFile { .. } => 2,
+        }
+    }
+}
+
+/// Implementation, test and documentation scores for one feature, each 0–100
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FeatureCoverage {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    pub implementation_score: u32,
+    pub test_score: u32,
+    pub documentation_score: u32,
+    pub sources: Vec<DocSource>,
+}
+
+impl FeatureCoverage {
+    pub fn is_documented(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    /// Implemented with passing tests
+    pub fn is_complete(&self) -> bool {
+        self.state == FeatureState::ImplementedPassingTests.as_str()
+    }
+}
+
+/// Documentation coverage across the project's features
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocCoverageReport {
+    pub features: Vec<FeatureCoverage>,
+    pub documented: usize,
+    pub total: usize,
+    /// Share of features with any documentation, as a percentage
+    pub documentation_rate: f64,
+}
+
+impl DocCoverageReport {
+    /// Completed features that nothing documents
+    pub fn undocumented_complete(&self) -> Vec<&FeatureCoverage> {
+        self.features.iter().filter(|f| f.is_complete() && !f.is_documented()).collect()
+    }
+}
+
+pub fn implementation_score(state: &str) -> u32 {
+    match FeatureState::from_str(state) {
+        Ok(FeatureState::NotImplemented) | Err(_) => 0,
+        Ok(FeatureState::CriticalIssue) => 50,
+        Ok(_) => 100,
+    }
+}
+
+pub fn test_score(state: &str) -> u32 {
+    match FeatureState::from_str(state) {
+        Ok(FeatureState::ImplementedPassingTests) => 100,
+        Ok(FeatureState::ImplementedFailingTests) | Ok(FeatureState::TestsBroken) => 50,
+        _ => 0,
+    }
+}
+
+/// Share of the three documentation kinds (template section, note, mapped file) present
+pub fn documentation_score(sources: &[DocSource]) -> u32 {
+    let mut kinds = [false; 3];
+    for source in sources {
+        kinds[source.kind()] = true;
+    }
+    let present = kinds.iter().filter(|k| **k).count() as u32;
+    (present * 100 + 1) / 3
+}
+
+/// Whether `text` mentions `id` as a whole word (`F00001` but not `F000010`)
+pub(crate) fn mentions(text: &str, id: &str) -> bool {
+    text.match_indices(id).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + id.len()..].chars().next();
+        !before.map_or(false, |c| c.is_alphanumeric()) && !after.map_or(false, |c| c.is_alphanumeric())
+    })
+}
+
+/// Markdown headings in the enabled templates, as (template name, heading text)
+pub fn template_sections(project_root: &Path) -> Vec<(String, String)> {
+    let templates_dir = project_root.join(".wsb").join("templates");
+    let templates = match TemplateManager::load_template_configs(&templates_dir) {
+        Ok(templates) => templates,
+        Err(_) => return Vec::new(),
+    };
+    let mut templates: Vec<_> = templates.into_values().filter(|t| t.enabled).collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut sections = Vec::new();
+    for template in templates {
+        let content = match std::fs::read_to_string(templates_dir.join(&template.source_path)) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                sections.push((template.name.clone(), line.trim_start_matches('#').trim().to_string()));
+            }
+        }
+    }
+    sections
+}
+
+/// Build the coverage report for a project
+pub async fn analyze(
+    pool: &SqlitePool,
+    project_id: &str,
+    project_root: &Path,
+    config: &DocCoverageConfig,
+) -> Result<DocCoverageReport> {
+    let features = crud::features::list_by_project(pool, project_id).await?;
+    let sections = template_sections(project_root);
+
+    let note_rows = sqlx::query(r#"
+        SELECT id, entity_id, title, note_type, tags FROM notes
+        WHERE project_id = ? AND entity_type = 'feature'
+        ORDER BY created_at
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    let mut notes: BTreeMap<String, Vec<DocSource>> = BTreeMap::new();
+    for row in &note_rows {
+        let note_type: String = row.get("note_type");
+        let tags: Option<String> = row.get("tags");
+        let tagged = tags
+            .as_deref()
+            .map(|tags| tags.split(|c: char| c == ',' || c.is_whitespace() || c == '"' || c == '[' || c == ']')
+                .any(|tag| tag.eq_ignore_ascii_case(ARCHITECTURE_TAG)))
+            .unwrap_or(false);
+        if note_type == "decision" || tagged {
+            if let Some(entity_id) = row.get::<Option<String>, _>("entity_id") {
+                notes.entry(entity_id).or_default().push(DocSource::Note {
+                    id: row.get("id"),
+                    title: row.get("title"),
+                });
+            }
+        }
+    }
+
+    let mut report = DocCoverageReport { total: features.len(), ..Default::default() };
+    for feature in features {
+        let mut sources: Vec<DocSource> = sections
+            .iter()
+            .filter(|(_, heading)| mentions(heading, &feature.id))
+            .map(|(template, heading)| DocSource::TemplateSection { template: template.clone(), heading: heading.clone() })
+            .collect();
+        sources.extend(notes.remove(&feature.id).unwrap_or_default());
+        if let Some(paths) = config.doc_files.get(&feature.id) {
+            sources.extend(
+                paths.iter()
+                    .filter(|path| project_root.join(path).is_file())
+                    .map(|path| DocSource::MappedFile { path: path.clone() }),
+            );
+        }
+
+        if !sources.is_empty() {
+            report.documented += 1;
+        }
+        report.features.push(FeatureCoverage {
+            id: feature.id,
+            name: feature.name,
+            implementation_score: implementation_score(&feature.state),
+            test_score: test_score(&feature.state),
+            state: feature.state,
+            documentation_score: documentation_score(&sources),
+            sources,
+        });
+    }
+    // list_by_project is newest first; report in feature order
+    report.features.sort_by(|a, b| a.id.cmp(&b.id));
+    if report.total > 0 {
+        report.documentation_rate = report.documented as f64 / report.total as f64 * 100.0;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mentions_whole_ids_only() {
+        assert!(mentions("F00001: Authentication", "F00001"));
+        assert!(mentions("Login (F00001)", "F00001"));
+        assert!(!mentions("F000012 Sessions", "F00001"));
+        assert!(!mentions("XF00001", "F00001"));
+    }
+
+    #[test]
+    fn test_scores() {
+        assert_eq!(implementation_score("not_implemented"), 0);
+        assert_eq!(implementation_score("implemented_no_tests"), 100);
+        assert_eq!(test_score("implemented_passing_tests"), 100);
+        assert_eq!(test_score("tests_broken"), 50);
+
+        let note = DocSource::Note { id: "N1".to_string(), title: "ADR".to_string() };
+        let file = DocSource::MappedFile { path: "docs/auth.md".to_string() };
+        assert_eq!(documentation_score(&[]), 0);
+        assert_eq!(documentation_score(&[note.clone(), note.clone()]), 33);
+        assert_eq!(documentation_score(&[note.clone(), file.clone()]), 67);
+        let section = DocSource::TemplateSection { template: "docs".to_string(), heading: "F00001".to_string() };
+        assert_eq!(documentation_score(&[note, file, section]), 100);
+    }
+
+    #[test]
+    fn test_template_sections() {
+        let temp_dir = TempDir::new().unwrap();
+        let templates_dir = temp_dir.path().join(".wsb/templates");
+        fs::create_dir_all(&templates_dir).unwrap();
+        fs::write(
+            templates_dir.join("templates.json"),
+            r#"{"arch": {"name": "arch", "description": null, "source_path": "arch.tera", "output_path": "docs/ARCH.md", "enabled": true}}"#,
+        ).unwrap();
+        fs::write(templates_dir.join("arch.tera"), "# Architecture\n\n## F00001 Authentication\nbody F00002\n").unwrap();
+
+        let sections = template_sections(temp_dir.path());
+        assert_eq!(sections.len(), 2);
+        assert!(sections.iter().any(|(_, heading)| mentions(heading, "F00001")));
+        assert!(!sections.iter().any(|(_, heading)| mentions(heading, "F00002")));
+    }
+
+    #[tokio::test]
+    async fn test_undocumented_complete_features() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let pool = crate::entities::database::initialize_database(&root.join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Docs".to_string(), "Docs project".to_string()).await.unwrap();
+        for name in ["Login", "Search", "Export"] {
+            let feature = crud::features::create(&pool, project.id.clone(), name.to_string(), format!("{} feature", name), None)
+                .await
+                .unwrap();
+            crud::features::update_state(&pool, &feature.id, FeatureState::ImplementedNoTests).await.unwrap();
+            crud::features::update_state(&pool, &feature.id, FeatureState::ImplementedPassingTests).await.unwrap();
+        }
+        sqlx::query(r#"
+            INSERT INTO notes (id, project_id, entity_id, entity_type, note_type, title, content, is_project_wide)
+            VALUES ('N1', ?, 'F00001', 'feature', 'decision', 'Session tokens', 'Use signed cookies', FALSE)
+        "#)
+        .bind(&project.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/search.md"), "# Search").unwrap();
+        let mut config = DocCoverageConfig::default();
+        config.doc_files.insert("F00002".to_string(), vec!["docs/search.md".to_string()]);
+
+        let report = analyze(&pool, &project.id, root, &config).await.unwrap();
+        assert_eq!((report.documented, report.total), (2, 3));
+        assert_eq!(report.features[0].sources, vec![DocSource::Note { id: "N1".to_string(), title: "Session tokens".to_string() }]);
+        let undocumented: Vec<&str> = report.undocumented_complete().iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(undocumented, vec!["F00003"]);
+    }
+}
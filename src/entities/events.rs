@@ -0,0 +1,460 @@
+// Transactional Event Outbox - Reliable delivery of entity mutations to integrations
+// Mutations insert an event row in the same transaction as the change itself; a
+// dispatcher later delivers pending events to the configured sinks with retries
+// and dead-lettering, so a temporarily unavailable webhook never loses events.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::entities::actors;
+
+/// Delivery status of an outbox event
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EventStatus {
+    Pending,
+    Delivered,
+    DeadLettered,
+}
+
+impl EventStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventStatus::Pending => "pending",
+            EventStatus::Delivered => "delivered",
+            EventStatus::DeadLettered => "dead",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "pending" => Ok(EventStatus::Pending),
+            "delivered" => Ok(EventStatus::Delivered),
+            "dead" => Ok(EventStatus::DeadLettered),
+            _ => Err(format!("Invalid event status: {}", s)),
+        }
+    }
+}
+
+/// Event row stored in the outbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub project_id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+    /// Who made the change (see `actors`)
+    pub actor: Option<String>,
+    /// Keys (see [`EventSink::key`]) of the sinks that already received the event
+    pub delivered_sinks: Vec<String>,
+}
+
+/// Destination for dispatched events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventSink {
+    /// POST the event as JSON to a URL (delivered via curl)
+    Webhook { url: String },
+    /// Append the event as one JSON line to a file
+    File { path: PathBuf },
+    /// Run a shell command with the event JSON on stdin
+    Command { command: String },
+}
+
+impl EventSink {
+    /// Identifies the sink across dispatch runs, whatever its position in the config
+    pub fn key(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Dispatcher configuration, stored under the `events` tool key in `.wsb/state.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventsConfig {
+    #[serde(default)]
+    pub sinks: Vec<EventSink>,
+    /// Attempts before an event is dead-lettered
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Base delay for exponential backoff between attempts
+    #[serde(default = "default_backoff_seconds")]
+    pub backoff_seconds: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_backoff_seconds() -> u64 {
+    30
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            sinks: Vec::new(),
+            max_attempts: default_max_attempts(),
+            backoff_seconds: default_backoff_seconds(),
+        }
+    }
+}
+
+/// Outcome of a dispatch run
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct DispatchReport {
+    pub delivered: usize,
+    pub retried: usize,
+    pub dead_lettered: usize,
+}
+
+/// Create the outbox table
+pub async fn initialize_outbox_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS event_outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            next_attempt_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            delivered_at TEXT,
+
+            CONSTRAINT chk_event_outbox_status CHECK (status IN ('pending', 'delivered', 'dead'))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_event_outbox_pending ON event_outbox (status, next_attempt_at)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Record an event inside the caller's transaction so it commits atomically with the mutation.
+/// The event is attributed to the process actor.
+pub async fn record(
+    tx: &mut Transaction<'_, Sqlite>,
+    project_id: &str,
+    entity_type: &str,
+    entity_id: &str,
+    event_type: &str,
+    payload: serde_json::Value,
+) -> Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let actor = actors::current();
+    actors::touch(tx, &actor).await?;
+
+    sqlx::query(r#"
+        INSERT INTO event_outbox (project_id, entity_type, entity_id, event_type, payload, status, attempts, next_attempt_at, created_at, actor)
+        VALUES (?, ?, ?, ?, ?, 'pending', 0, ?, ?, ?)
+    "#)
+    .bind(project_id)
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(event_type)
+    .bind(payload.to_string())
+    .bind(&now)
+    .bind(&now)
+    .bind(&actor.name)
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(())
+}
+
+fn from_row(row: &sqlx::sqlite::SqliteRow) -> OutboxEvent {
+    let payload: String = row.get("payload");
+    OutboxEvent {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        entity_type: row.get("entity_type"),
+        entity_id: row.get("entity_id"),
+        event_type: row.get("event_type"),
+        payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::String(payload)),
+        status: row.get("status"),
+        attempts: row.get::<i64, _>("attempts") as u32,
+        last_error: row.get("last_error"),
+        next_attempt_at: row.get("next_attempt_at"),
+        created_at: row.get("created_at"),
+        delivered_at: row.get("delivered_at"),
+        // Databases opened read-only may predate the attribution and sink delivery columns
+        actor: row.try_get("actor").unwrap_or(None),
+        delivered_sinks: row.try_get::<Option<String>, _>("delivered_sinks").ok().flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+    }
+}
+
+/// List outbox events, optionally filtered by status and actor
+pub async fn list(pool: &SqlitePool, status: Option<EventStatus>, actor: Option<&str>, limit: u32) -> Result<Vec<OutboxEvent>> {
+    let rows = match (status, actor) {
+        (Some(status), Some(actor)) => sqlx::query("SELECT * FROM event_outbox WHERE status = ? AND actor = ? ORDER BY id DESC LIMIT ?")
+            .bind(status.as_str())
+            .bind(actor)
+            .bind(limit as i64)
+            .fetch_all(pool)
+            .await?,
+        (Some(status), None) => sqlx::query("SELECT * FROM event_outbox WHERE status = ? ORDER BY id DESC LIMIT ?")
+            .bind(status.as_str())
+            .bind(limit as i64)
+            .fetch_all(pool)
+            .await?,
+        (None, Some(actor)) => sqlx::query("SELECT * FROM event_outbox WHERE actor = ? ORDER BY id DESC LIMIT ?")
+            .bind(actor)
+            .bind(limit as i64)
+            .fetch_all(pool)
+            .await?,
+        (None, None) => sqlx::query("SELECT * FROM event_outbox ORDER BY id DESC LIMIT ?")
+            .bind(limit as i64)
+            .fetch_all(pool)
+            .await?,
+    };
+
+    Ok(rows.iter().map(from_row).collect())
+}
+
+/// Move a dead-lettered event back to pending so the next dispatch retries it
+pub async fn requeue(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let result = sqlx::query(r#"
+        UPDATE event_outbox SET status = 'pending', attempts = 0, last_error = NULL, next_attempt_at = ?
+        WHERE id = ? AND status = 'dead'
+    "#)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deliver one event to a single sink
+fn deliver_to_sink(sink: &EventSink, body: &str) -> Result<()> {
+    match sink {
+        EventSink::Webhook { url } => {
+            let output = Command::new("curl")
+                .args(["-sS", "--fail", "--max-time", "10", "-X", "POST", "-H", "Content-Type: application/json", "--data-binary", "@-", url])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()
+                .and_then(|mut child| {
+                    if let Some(mut stdin) = child.stdin.take() {
+                        stdin.write_all(body.as_bytes())?;
+                    }
+                    child.wait_with_output()
+                })
+                .context("Failed to run curl for webhook delivery")?;
+            if !output.status.success() {
+                anyhow::bail!("Webhook {} failed: {}", url, String::from_utf8_lossy(&output.stderr).trim());
+            }
+        }
+        EventSink::File { path } => {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open event sink file {}", path.display()))?;
+            writeln!(file, "{}", body)?;
+        }
+        EventSink::Command { command } => {
+            let output = Command::new("sh")
+                .args(["-c", command])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()
+                .and_then(|mut child| {
+                    if let Some(mut stdin) = child.stdin.take() {
+                        stdin.write_all(body.as_bytes())?;
+                    }
+                    child.wait_with_output()
+                })
+                .with_context(|| format!("Failed to run event sink command: {}", command))?;
+            if !output.status.success() {
+                anyhow::bail!("Sink command '{}' failed: {}", command, String::from_utf8_lossy(&output.stderr).trim());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Exponential backoff delay for the given attempt count
+pub fn backoff_delay(config: &EventsConfig, attempts: u32) -> chrono::Duration {
+    let factor = 2u64.saturating_pow(attempts.saturating_sub(1).min(16));
+    chrono::Duration::seconds(config.backoff_seconds.saturating_mul(factor).min(86_400) as i64)
+}
+
+/// Deliver all due pending events to every configured sink. Each sink is tried on every
+/// attempt until it has accepted the event, so a retry only goes to the sinks that failed.
+pub async fn dispatch(pool: &SqlitePool, config: &EventsConfig) -> Result<DispatchReport> {
+    let mut report = DispatchReport::default();
+    if config.sinks.is_empty() {
+        return Ok(report);
+    }
+
+    let now = chrono::Utc::now();
+    let rows = sqlx::query("SELECT * FROM event_outbox WHERE status = 'pending' AND next_attempt_at <= ? ORDER BY id ASC")
+        .bind(now.to_rfc3339())
+        .fetch_all(pool)
+        .await?;
+
+    for event in rows.iter().map(from_row) {
+        let body = serde_json::to_string(&serde_json::json!({
+            "id": event.id,
+            "project_id": event.project_id,
+            "entity_type": event.entity_type,
+            "entity_id": event.entity_id,
+            "event_type": event.event_type,
+            "payload": event.payload,
+            "created_at": event.created_at,
+        }))?;
+
+        let mut delivered_sinks = event.delivered_sinks.clone();
+        let mut errors = Vec::new();
+        for sink in &config.sinks {
+            let key = sink.key();
+            if delivered_sinks.contains(&key) {
+                continue;
+            }
+            match deliver_to_sink(sink, &body) {
+                Ok(()) => delivered_sinks.push(key),
+                Err(error) => errors.push(format!("{:#}", error)),
+            }
+        }
+        let delivered_json = serde_json::to_string(&delivered_sinks)?;
+
+        if errors.is_empty() {
+            sqlx::query("UPDATE event_outbox SET status = 'delivered', attempts = attempts + 1, last_error = NULL, delivered_sinks = ?, delivered_at = ? WHERE id = ?")
+                .bind(&delivered_json)
+                .bind(chrono::Utc::now().to_rfc3339())
+                .bind(event.id)
+                .execute(pool)
+                .await?;
+            report.delivered += 1;
+        } else {
+            let attempts = event.attempts + 1;
+            let status = if attempts >= config.max_attempts { EventStatus::DeadLettered } else { EventStatus::Pending };
+            let next_attempt = now + backoff_delay(config, attempts);
+            let error = errors.join("; ");
+            sqlx::query("UPDATE event_outbox SET status = ?, attempts = ?, last_error = ?, delivered_sinks = ?, next_attempt_at = ? WHERE id = ?")
+                .bind(status.as_str())
+                .bind(attempts as i64)
+                .bind(&error)
+                .bind(&delivered_json)
+                .bind(next_attempt.to_rfc3339())
+                .bind(event.id)
+                .execute(pool)
+                .await?;
+            log::warn!("Event {} delivery failed (attempt {}): {}", event.id, attempts, error);
+            if status == EventStatus::DeadLettered {
+                report.dead_lettered += 1;
+            } else {
+                report.retried += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Background dispatcher loop for long-running servers
+pub async fn run_dispatcher(pool: SqlitePool, config: EventsConfig, interval: std::time::Duration) -> Result<()> {
+    loop {
+        match dispatch(&pool, &config).await {
+            Ok(report) if report.delivered + report.retried + report.dead_lettered > 0 => {
+                log::info!("Event dispatch: {} delivered, {} retried, {} dead-lettered", report.delivered, report.retried, report.dead_lettered);
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Event dispatch failed: {:#}", e),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_status_roundtrip() {
+        for status in [EventStatus::Pending, EventStatus::Delivered, EventStatus::DeadLettered] {
+            assert_eq!(EventStatus::from_str(status.as_str()).unwrap(), status);
+        }
+        assert!(EventStatus::from_str("unknown").is_err());
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially() {
+        let config = EventsConfig { backoff_seconds: 10, ..Default::default() };
+        assert_eq!(backoff_delay(&config, 1).num_seconds(), 10);
+        assert_eq!(backoff_delay(&config, 2).num_seconds(), 20);
+        assert_eq!(backoff_delay(&config, 4).num_seconds(), 80);
+        // Capped at one day
+        assert_eq!(backoff_delay(&config, 40).num_seconds(), 86_400);
+    }
+
+    #[test]
+    fn test_sink_config_parsing() {
+        let config: EventsConfig = serde_json::from_value(serde_json::json!({
+            "sinks": [
+                { "type": "webhook", "url": "https://example.com/hook" },
+                { "type": "file", "path": "/tmp/events.jsonl" }
+            ]
+        })).unwrap();
+        assert_eq!(config.sinks.len(), 2);
+        assert_eq!(config.max_attempts, 5);
+        assert!(matches!(config.sinks[0], EventSink::Webhook { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_sinks_that_already_received_the_event() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        record(&mut tx, "P001", "task", "T000001", "task.created", serde_json::json!({})).await.unwrap();
+        tx.commit().await.unwrap();
+
+        // The first sink fails until the marker exists; the second always succeeds
+        let marker = temp_dir.path().join("up");
+        let log = temp_dir.path().join("events.jsonl");
+        let config = EventsConfig {
+            sinks: vec![
+                EventSink::Command { command: format!("test -f '{}'", marker.display()) },
+                EventSink::File { path: log.clone() },
+            ],
+            backoff_seconds: 0,
+            ..Default::default()
+        };
+
+        let report = dispatch(&pool, &config).await.unwrap();
+        assert_eq!((report.delivered, report.retried), (0, 1));
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 1);
+        let event = &list(&pool, Some(EventStatus::Pending), None, 10).await.unwrap()[0];
+        assert_eq!(event.delivered_sinks, vec![config.sinks[1].key()]);
+
+        std::fs::write(&marker, "").unwrap();
+        let report = dispatch(&pool, &config).await.unwrap();
+        assert_eq!((report.delivered, report.retried), (1, 0));
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 1);
+        let event = &list(&pool, Some(EventStatus::Delivered), None, 10).await.unwrap()[0];
+        assert_eq!(event.delivered_sinks.len(), 2);
+    }
+}
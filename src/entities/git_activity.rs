@@ -0,0 +1,173 @@
+// Session Git Activity - Commits and diffstat made while a session was active
+// `crud::sessions::complete` reads `git log` for the window between the session's start
+// and its completion and stores the result as JSON in `sessions.git_activity`, so
+// `wsb session show` lists the commits, the files they touched and the lines changed.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::entities::actors::ensure_column;
+use crate::entities::schema_models::{FileDiffStat, GitActivity, SessionCommit};
+
+/// Separates commits in the log output; fields within a header are separated by 0x1f
+const RECORD: char = '\u{1e}';
+const FIELD: char = '\u{1f}';
+
+pub async fn initialize_git_activity_column(pool: &SqlitePool) -> Result<()> {
+    ensure_column(pool, "sessions", "git_activity", "TEXT").await
+}
+
+/// Commits on HEAD of the repository containing `repo` that were committed between `since`
+/// and `until`, with their combined diffstat. None outside a git work tree or when git is
+/// not installed.
+pub fn collect(repo: &Path, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Option<GitActivity>> {
+    let inside = match Command::new("git").arg("-C").arg(repo).args(["rev-parse", "--is-inside-work-tree"]).output() {
+        Ok(output) => output,
+        Err(e) => {
+            log::debug!("git is not available, skipping session activity: {}", e);
+            return Ok(None);
+        }
+    };
+    if !inside.status.success() || String::from_utf8_lossy(&inside.stdout).trim() != "true" {
+        return Ok(None);
+    }
+    // A repository without commits has no HEAD to read
+    let head = Command::new("git").arg("-C").arg(repo).args(["rev-parse", "--verify", "--quiet", "HEAD"]).output()?;
+    if !head.status.success() {
+        return Ok(Some(GitActivity::default()));
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .arg("log")
+        .arg(format!("--since={}", since.format("%Y-%m-%d %H:%M:%S +0000")))
+        .arg(format!("--until={}", until.format("%Y-%m-%d %H:%M:%S +0000")))
+        .arg(format!("--format={}%H{}%an{}%cI{}%s", RECORD, FIELD, FIELD, FIELD))
+        .arg("--numstat")
+        .arg("HEAD")
+        .output()
+        .context("Failed to run git log")?;
+    if !output.status.success() {
+        anyhow::bail!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(Some(parse_log(&String::from_utf8_lossy(&output.stdout))))
+}
+
+/// Parse `git log --format=<RECORD>%H<FIELD>%an<FIELD>%cI<FIELD>%s --numstat` output
+fn parse_log(log: &str) -> GitActivity {
+    let mut activity = GitActivity::default();
+    let mut files: BTreeMap<String, FileDiffStat> = BTreeMap::new();
+
+    for record in log.split(RECORD).filter(|record| !record.trim().is_empty()) {
+        let mut lines = record.lines();
+        let header: Vec<&str> = lines.next().unwrap_or_default().splitn(4, FIELD).collect();
+        if header.len() < 4 {
+            continue;
+        }
+        activity.commits.push(SessionCommit {
+            sha: header[0].to_string(),
+            author: header[1].to_string(),
+            committed_at: header[2].to_string(),
+            summary: header[3].to_string(),
+        });
+
+        for line in lines {
+            let parts: Vec<&str> = line.splitn(3, '\t').collect();
+            if parts.len() < 3 {
+                continue;
+            }
+            let stat = files.entry(parts[2].to_string()).or_insert_with(|| FileDiffStat {
+                path: parts[2].to_string(),
+                insertions: 0,
+                deletions: 0,
+                binary: false,
+            });
+            // numstat prints `-` for both counts of binary files
+            match (parts[0].parse::<u64>(), parts[1].parse::<u64>()) {
+                (Ok(insertions), Ok(deletions)) => {
+                    stat.insertions += insertions;
+                    stat.deletions += deletions;
+                    activity.insertions += insertions;
+                    activity.deletions += deletions;
+                }
+                _ => stat.binary = true,
+            }
+        }
+    }
+
+    activity.files = files.into_values().collect();
+    activity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_sums_numstat_per_file() {
+        let log = format!(
+            "{r}bbb{f}Ada{f}2026-01-02T10:00:00+00:00{f}Second\n\n3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n\
+             {r}aaa{f}Ada{f}2026-01-02T09:00:00+00:00{f}First\n\n2\t0\tsrc/lib.rs\n1\t1\tREADME.md\n",
+            r = RECORD,
+            f = FIELD,
+        );
+        let activity = parse_log(&log);
+        assert_eq!(activity.commits.iter().map(|commit| commit.sha.as_str()).collect::<Vec<_>>(), ["bbb", "aaa"]);
+        assert_eq!(activity.commits[0].summary, "Second");
+        assert_eq!((activity.insertions, activity.deletions), (6, 2));
+
+        let lib = activity.files.iter().find(|file| file.path == "src/lib.rs").unwrap();
+        assert_eq!((lib.insertions, lib.deletions), (5, 1));
+        assert!(activity.files.iter().any(|file| file.path == "logo.png" && file.binary));
+        assert!(parse_log("").commits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_complete_records_session_commits() {
+        use crate::entities::crud;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Activity".to_string(), "Git".to_string()).await.unwrap();
+        let session = crud::sessions::create(&pool, project.id.clone(), "Work".to_string(), None).await.unwrap();
+
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C").arg(&repo)
+                .args(["-c", "user.name=Ada", "-c", "user.email=ada@example.com"])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        std::fs::write(repo.join("notes.txt"), "one\ntwo\n").unwrap();
+        git(&["add", "notes.txt"]);
+        git(&["commit", "-q", "-m", "Add notes"]);
+
+        crud::sessions::complete_in(&pool, &session.id, "Done".to_string(), &repo).await.unwrap();
+        let completed = crud::sessions::get_by_id(&pool, &session.id).await.unwrap().unwrap();
+        assert_eq!(completed.status, "completed");
+        let activity = completed.get_git_activity().unwrap().unwrap();
+        assert_eq!(activity.commits.len(), 1);
+        assert_eq!(activity.commits[0].summary, "Add notes");
+        assert_eq!(activity.files[0].path, "notes.txt");
+        assert_eq!((activity.insertions, activity.deletions), (2, 0));
+
+        assert!(crud::sessions::complete_in(&pool, &session.id, "Again".to_string(), &repo).await.is_err());
+        let outside = crud::sessions::create(&pool, project.id, "Elsewhere".to_string(), None).await.unwrap();
+        let plain = temp.path().join("plain");
+        std::fs::create_dir_all(&plain).unwrap();
+        crud::sessions::complete_in(&pool, &outside.id, "Done".to_string(), &plain).await.unwrap();
+        assert!(crud::sessions::get_by_id(&pool, &outside.id).await.unwrap().unwrap().git_activity.is_none());
+    }
+}
@@ -0,0 +1,393 @@
+// Relationship Graph - Entities of a project and the links between them
+// Nodes are the project's features, tasks and milestones plus any other entity a link
+// points at. Edges come from the dependencies table, each task's feature, the features
+// and tasks a milestone tracks, and note links. `wsb relationship graph` renders the
+// whole graph, or the neighbourhood of one entity, as DOT, Mermaid or JSON.
+// `wsb relationship impact` follows the same edges from prerequisites to the entities
+// waiting on them to list what changing or blocking one entity holds up.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+use crate::entities::crud;
+use crate::entities::relationships::task_ordering;
+
+/// An entity in the graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub entity_type: String,
+    /// Name or title; the id when the entity could not be found
+    pub label: String,
+    /// State or status, when the entity has one
+    pub state: Option<String>,
+}
+
+/// A directed link between two entities
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Relationship type: a dependency type, `implements`, `tracks` or a note link type
+    pub kind: String,
+    /// Resolved dependencies are kept but drawn dashed
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EntityGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Label and state of an entity that is not loaded up front
+async fn describe(pool: &SqlitePool, entity_type: &str, id: &str) -> Result<Option<(String, Option<String>)>> {
+    let query = match entity_type {
+        "project" => "SELECT name AS label, status AS state FROM projects WHERE id = ?",
+        "feature" => "SELECT name AS label, state FROM features WHERE id = ?",
+        "task" => "SELECT title AS label, status AS state FROM tasks WHERE id = ?",
+        "session" => "SELECT title AS label, state FROM sessions WHERE id = ?",
+        "milestone" => "SELECT title AS label, status AS state FROM milestones WHERE id = ?",
+        "directive" => "SELECT title AS label, status AS state FROM directives WHERE id = ?",
+        "note" => "SELECT title AS label, note_type AS state FROM notes WHERE id = ?",
+        _ => return Ok(None),
+    };
+    let row = sqlx::query(query).bind(id).fetch_optional(pool).await?;
+    Ok(row.map(|row| (row.get("label"), row.get("state"))))
+}
+
+fn add_edge(edges: &mut Vec<GraphEdge>, edge: GraphEdge) {
+    // A task's feature can also be recorded as an `implements` dependency
+    if !edges.contains(&edge) {
+        edges.push(edge);
+    }
+}
+
+/// The relationship graph of `project_id`
+pub async fn load(pool: &SqlitePool, project_id: &str) -> Result<EntityGraph> {
+    let mut nodes: BTreeMap<String, GraphNode> = BTreeMap::new();
+    let mut edges: Vec<GraphEdge> = Vec::new();
+
+    for feature in crud::features::list_by_project(pool, project_id).await? {
+        nodes.insert(feature.id.clone(), GraphNode {
+            id: feature.id,
+            entity_type: "feature".to_string(),
+            label: feature.name,
+            state: Some(feature.state),
+        });
+    }
+    for task in crud::tasks::list_by_project(pool, project_id, None).await? {
+        if !task.feature_id.is_empty() {
+            add_edge(&mut edges, GraphEdge { from: task.id.clone(), to: task.feature_id.clone(), kind: "implements".to_string(), resolved: false });
+        }
+        nodes.insert(task.id.clone(), GraphNode {
+            id: task.id,
+            entity_type: "task".to_string(),
+            label: task.task,
+            state: Some(task.status),
+        });
+    }
+    for milestone in crud::milestones::list_by_project(pool, project_id).await? {
+        for target in milestone.feature_ids.iter().chain(milestone.task_ids.iter()) {
+            add_edge(&mut edges, GraphEdge { from: milestone.id.clone(), to: target.clone(), kind: "tracks".to_string(), resolved: false });
+        }
+        nodes.insert(milestone.id.clone(), GraphNode {
+            id: milestone.id,
+            entity_type: "milestone".to_string(),
+            label: milestone.title,
+            state: Some(milestone.status),
+        });
+    }
+
+    // Endpoints outside the loaded entities, with the type the link gives them
+    let mut linked: Vec<(String, String)> = Vec::new();
+    for dependency in crud::dependencies::list_by_project(pool, project_id).await? {
+        linked.push((dependency.from_entity_id.clone(), dependency.from_entity_type.clone()));
+        linked.push((dependency.to_entity_id.clone(), dependency.to_entity_type.clone()));
+        add_edge(&mut edges, GraphEdge {
+            from: dependency.from_entity_id,
+            to: dependency.to_entity_id,
+            kind: dependency.dependency_type,
+            resolved: dependency.resolved_at.is_some(),
+        });
+    }
+    let note_links = sqlx::query(r#"
+        SELECT source_note_id, target_id, COALESCE(target_entity_type, target_type) AS target_type, link_type
+        FROM note_links WHERE project_id = ?
+        ORDER BY created_at
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    for row in note_links {
+        let (source, target): (String, String) = (row.get("source_note_id"), row.get("target_id"));
+        linked.push((source.clone(), "note".to_string()));
+        linked.push((target.clone(), row.get("target_type")));
+        add_edge(&mut edges, GraphEdge { from: source, to: target, kind: row.get("link_type"), resolved: false });
+    }
+
+    for (id, entity_type) in linked {
+        if nodes.contains_key(&id) {
+            continue;
+        }
+        let (label, state) = describe(pool, &entity_type, &id).await?.unwrap_or_else(|| (id.clone(), None));
+        nodes.insert(id.clone(), GraphNode { id, entity_type, label, state });
+    }
+    // Tasks of deleted features and the like point at nothing that can be drawn
+    edges.retain(|edge| nodes.contains_key(&edge.from) && nodes.contains_key(&edge.to));
+
+    Ok(EntityGraph { nodes: nodes.into_values().collect(), edges })
+}
+
+/// An entity held up, directly or transitively, by the root of an impact analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct Impact {
+    #[serde(flatten)]
+    pub node: GraphNode,
+    /// Links between the root and this entity
+    pub depth: usize,
+    /// Entity ids from the root to this entity
+    pub path: Vec<String>,
+    /// Relationship type of the last link on the path
+    pub via: String,
+}
+
+/// Everything held up by changing or blocking one entity, by kind
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactReport {
+    pub root: GraphNode,
+    pub max_depth: usize,
+    pub features_blocked: Vec<Impact>,
+    pub tasks_stalled: Vec<Impact>,
+    pub milestones_at_risk: Vec<Impact>,
+    /// Affected entities of other types
+    pub other: Vec<Impact>,
+}
+
+impl ImpactReport {
+    pub fn total(&self) -> usize {
+        self.features_blocked.len() + self.tasks_stalled.len() + self.milestones_at_risk.len() + self.other.len()
+    }
+}
+
+/// For an unresolved edge that orders work, the (prerequisite, dependent) pair: the
+/// dependent cannot finish before the prerequisite. A feature waits on the tasks that
+/// implement it and a milestone on what it tracks. None for links such as note
+/// references and `worked_in` that hold nothing up.
+fn waits_on(edge: &GraphEdge) -> Option<(&str, &str)> {
+    if edge.resolved {
+        return None;
+    }
+    match edge.kind.as_str() {
+        "implements" => Some((edge.from.as_str(), edge.to.as_str())),
+        "tracks" => Some((edge.to.as_str(), edge.from.as_str())),
+        kind => task_ordering(&edge.from, &edge.to, kind).map(|(dependent, prerequisite)| (prerequisite, dependent)),
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_id(id: &str) -> String {
+    id.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+fn fill_color(entity_type: &str) -> &'static str {
+    match entity_type {
+        "feature" => "lightblue",
+        "task" => "lightyellow",
+        "milestone" => "plum",
+        "session" => "lightgrey",
+        "directive" => "lightpink",
+        "note" => "white",
+        _ => "whitesmoke",
+    }
+}
+
+impl EntityGraph {
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Entities within `depth` links of `id`, following links in either direction, and the
+    /// edges between them
+    pub fn neighbourhood(&self, id: &str, depth: usize) -> Result<EntityGraph> {
+        if self.node(id).is_none() {
+            anyhow::bail!("Entity not found in this project: {}", id);
+        }
+        let mut reached: HashSet<&str> = HashSet::from([id]);
+        let mut queue: VecDeque<(&str, usize)> = VecDeque::from([(id, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            for edge in &self.edges {
+                let next = if edge.from == current {
+                    edge.to.as_str()
+                } else if edge.to == current {
+                    edge.from.as_str()
+                } else {
+                    continue;
+                };
+                if reached.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        Ok(EntityGraph {
+            nodes: self.nodes.iter().filter(|node| reached.contains(node.id.as_str())).cloned().collect(),
+            edges: self.edges.iter()
+                .filter(|edge| reached.contains(edge.from.as_str()) && reached.contains(edge.to.as_str()))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Entities that wait on `id` through at most `max_depth` links, nearest first
+    pub fn impact(&self, id: &str, max_depth: usize) -> Result<ImpactReport> {
+        let root = self.node(id)
+            .ok_or_else(|| anyhow::anyhow!("Entity not found in this project: {}", id))?
+            .clone();
+        let mut report = ImpactReport {
+            root,
+            max_depth,
+            features_blocked: Vec::new(),
+            tasks_stalled: Vec::new(),
+            milestones_at_risk: Vec::new(),
+            other: Vec::new(),
+        };
+
+        let mut reached: HashSet<&str> = HashSet::from([id]);
+        let mut queue: VecDeque<(&str, Vec<String>)> = VecDeque::from([(id, vec![id.to_string()])]);
+        while let Some((current, path)) = queue.pop_front() {
+            if path.len() > max_depth {
+                continue;
+            }
+            for edge in &self.edges {
+                let Some((prerequisite, dependent)) = waits_on(edge) else { continue };
+                if prerequisite != current || !reached.insert(dependent) {
+                    continue;
+                }
+                let Some(node) = self.node(dependent) else { continue };
+                let mut next_path = path.clone();
+                next_path.push(dependent.to_string());
+                let impact = Impact { node: node.clone(), depth: path.len(), path: next_path.clone(), via: edge.kind.clone() };
+                match node.entity_type.as_str() {
+                    "feature" => report.features_blocked.push(impact),
+                    "task" => report.tasks_stalled.push(impact),
+                    "milestone" => report.milestones_at_risk.push(impact),
+                    _ => report.other.push(impact),
+                }
+                queue.push_back((dependent, next_path));
+            }
+        }
+        Ok(report)
+    }
+
+    /// Graphviz source; render with `dot -Tsvg`
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut out = format!("digraph {} {{\n    rankdir=LR;\n    node [shape=box, style=filled];\n\n", name);
+        for node in &self.nodes {
+            let label = match &node.state {
+                Some(state) => format!("{}\\n{}\\n({})", node.id, dot_escape(&node.label), dot_escape(state)),
+                None => format!("{}\\n{}", node.id, dot_escape(&node.label)),
+            };
+            out.push_str(&format!("    \"{}\" [label=\"{}\", fillcolor={}];\n", dot_escape(&node.id), label, fill_color(&node.entity_type)));
+        }
+        if !self.edges.is_empty() {
+            out.push('\n');
+        }
+        for edge in &self.edges {
+            let style = if edge.resolved { ", style=dashed" } else { "" };
+            out.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"{}];\n", dot_escape(&edge.from), dot_escape(&edge.to), dot_escape(&edge.kind), style));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart source, for Markdown renderers that support it
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+        for node in &self.nodes {
+            let label = format!("{}: {}", node.id, node.label).replace('"', "#quot;");
+            out.push_str(&format!("    {}[\"{}\"]\n", mermaid_id(&node.id), label));
+        }
+        for edge in &self.edges {
+            let arrow = if edge.resolved { "-.->" } else { "-->" };
+            out.push_str(&format!("    {} {}|{}| {}\n", mermaid_id(&edge.from), arrow, edge.kind, mermaid_id(&edge.to)));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::schema_traits::EntityType;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_graph_collects_links_and_renders() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Graph".to_string(), "Links".to_string()).await.unwrap();
+        let login = crud::features::create(&pool, project.id.clone(), "Login \"v2\"".to_string(), "Sign in".to_string(), None).await.unwrap();
+        let audit = crud::features::create(&pool, project.id.clone(), "Audit".to_string(), "Trail".to_string(), None).await.unwrap();
+        let task = crud::tasks::create(&pool, project.id.clone(), login.id.clone(), "Form".to_string(), "feature".to_string()).await.unwrap();
+        crud::dependencies::create(&pool, &project.id, &audit.id, EntityType::Feature, &login.id, EntityType::Feature, "depends_on", None).await.unwrap();
+
+        let graph = load(&pool, &project.id).await.unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert!(graph.edges.contains(&GraphEdge { from: task.id.clone(), to: login.id.clone(), kind: "implements".to_string(), resolved: false }));
+        assert!(graph.edges.iter().any(|edge| edge.from == audit.id && edge.kind == "depends_on"));
+
+        let dot = graph.to_dot("entities");
+        assert!(dot.starts_with("digraph entities {"));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\" [label=\"implements\"]", task.id, login.id)));
+        assert!(dot.contains("Login \\\"v2\\\""));
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains(&format!("{} -->|depends_on| {}", audit.id, login.id)));
+        assert!(mermaid.contains("Login #quot;v2#quot;"));
+
+        // Depth 1 from the task reaches its feature but not the feature depending on it
+        let near = graph.neighbourhood(&task.id, 1).unwrap();
+        assert_eq!(near.nodes.len(), 2);
+        assert_eq!(near.edges.len(), 1);
+        assert_eq!(graph.neighbourhood(&task.id, 2).unwrap().nodes.len(), 3);
+        assert!(graph.neighbourhood("F99999", 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_impact_follows_waiting_entities() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Impact".to_string(), "Chains".to_string()).await.unwrap();
+        let login = crud::features::create(&pool, project.id.clone(), "Login".to_string(), "Sign in".to_string(), None).await.unwrap();
+        let audit = crud::features::create(&pool, project.id.clone(), "Audit".to_string(), "Trail".to_string(), None).await.unwrap();
+        let schema = crud::tasks::create(&pool, project.id.clone(), login.id.clone(), "Schema".to_string(), "feature".to_string()).await.unwrap();
+        let form = crud::tasks::create(&pool, project.id.clone(), login.id.clone(), "Form".to_string(), "feature".to_string()).await.unwrap();
+        crud::dependencies::create(&pool, &project.id, &form.id, EntityType::Task, &schema.id, EntityType::Task, "depends_on", None).await.unwrap();
+        crud::dependencies::create(&pool, &project.id, &audit.id, EntityType::Feature, &login.id, EntityType::Feature, "depends_on", None).await.unwrap();
+
+        // Schema holds up Form and Login; Login holds up Audit
+        let graph = load(&pool, &project.id).await.unwrap();
+        let report = graph.impact(&schema.id, 5).unwrap();
+        assert_eq!(report.tasks_stalled.iter().map(|impact| impact.node.id.as_str()).collect::<Vec<_>>(), [form.id.as_str()]);
+        let features: Vec<&str> = report.features_blocked.iter().map(|impact| impact.node.id.as_str()).collect();
+        assert_eq!(features, [login.id.as_str(), audit.id.as_str()]);
+        let audit_impact = &report.features_blocked[1];
+        assert_eq!(audit_impact.depth, 2);
+        assert_eq!(audit_impact.path, [schema.id.clone(), login.id.clone(), audit.id.clone()]);
+        assert_eq!(audit_impact.via, "depends_on");
+
+        // The depth limit stops before Audit; nothing waits on Audit
+        assert_eq!(graph.impact(&schema.id, 1).unwrap().total(), 2);
+        assert_eq!(graph.impact(&audit.id, 5).unwrap().total(), 0);
+        assert!(graph.impact("T999999", 5).is_err());
+    }
+}
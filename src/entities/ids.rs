@@ -0,0 +1,322 @@
+// Entity ID generation - Sequential IDs per entity type and project
+// Every create in `crud` takes its ID from `IdGenerator::next`, which increments a row of
+// `id_counters` inside the caller's transaction, so concurrent inserts serialize on the
+// counter instead of racing on `MAX(id)`. Prefixes and widths default to P001, F00001,
+// T000001, S000001, D001 and M001 and can be configured per project.
+// IDs are primary keys across all projects, so per-project sequences are not dense: a
+// project's counter starts after the highest ID already stored and skips numbers another
+// project using the same prefix took. Projects with distinct prefixes do not skip.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqlitePool};
+
+pub async fn initialize_id_counters_table(pool: &SqlitePool) -> Result<()> {
+    // `scope` is the project ID, or '' for the sequence of project IDs themselves
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS id_counters (
+            scope TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            prefix TEXT NOT NULL,
+            width INTEGER NOT NULL,
+            next_value INTEGER NOT NULL,
+            updated_at TEXT NOT NULL,
+
+            PRIMARY KEY (scope, entity_type),
+            CONSTRAINT chk_id_counters_width CHECK (width BETWEEN 1 AND 12)
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Where an entity type's IDs live and how they look by default
+struct Sequence {
+    entity_type: &'static str,
+    table: &'static str,
+    prefix: &'static str,
+    width: usize,
+}
+
+const SEQUENCES: [Sequence; 7] = [
+    Sequence { entity_type: "project", table: "projects", prefix: "P", width: 3 },
+    Sequence { entity_type: "feature", table: "features", prefix: "F", width: 5 },
+    Sequence { entity_type: "task", table: "tasks", prefix: "T", width: 6 },
+    Sequence { entity_type: "session", table: "sessions", prefix: "S", width: 6 },
+    Sequence { entity_type: "directive", table: "directives", prefix: "D", width: 3 },
+    Sequence { entity_type: "milestone", table: "milestones", prefix: "M", width: 3 },
+    Sequence { entity_type: "sprint", table: "sprints", prefix: "SP", width: 3 },
+];
+
+fn sequence(entity_type: &str) -> Result<&'static Sequence> {
+    SEQUENCES.iter().find(|sequence| sequence.entity_type == entity_type).ok_or_else(|| {
+        let known: Vec<&str> = SEQUENCES.iter().map(|sequence| sequence.entity_type).collect();
+        anyhow::anyhow!("No ID sequence for '{}' (expected one of: {})", entity_type, known.join(", "))
+    })
+}
+
+/// Projects share one sequence; every other type counts per project
+fn scope<'a>(sequence: &Sequence, project_id: &'a str) -> &'a str {
+    if sequence.entity_type == "project" { "" } else { project_id }
+}
+
+/// How an entity type's IDs are formed in a project, and the number the next one gets
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdFormat {
+    pub entity_type: String,
+    pub prefix: String,
+    pub width: usize,
+    pub next_value: i64,
+    /// False while the defaults apply
+    pub configured: bool,
+}
+
+impl IdFormat {
+    pub fn format(&self, value: i64) -> String {
+        format!("{}{:0width$}", self.prefix, value, width = self.width)
+    }
+}
+
+/// Allocates entity IDs from the `id_counters` table
+pub struct IdGenerator;
+
+impl IdGenerator {
+    /// Entity types with an ID sequence
+    pub fn entity_types() -> impl Iterator<Item = &'static str> {
+        SEQUENCES.iter().map(|sequence| sequence.entity_type)
+    }
+
+    /// Allocate the next ID of `entity_type` in a project (`project_id` is ignored for
+    /// projects). IDs already present in the entity's table, e.g. from an import or another
+    /// project using the same prefix, are skipped, so a project's IDs can have gaps.
+    pub async fn next<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, entity_type: &str) -> Result<String> {
+        let sequence = sequence(entity_type)?;
+        let scope = scope(sequence, project_id);
+        let mut tx = db.begin().await?;
+        // Writing first takes SQLite's write lock, so two allocations never read the same value
+        seed(&mut tx, sequence, scope, sequence.prefix, sequence.width).await?;
+        loop {
+            sqlx::query("UPDATE id_counters SET next_value = next_value + 1, updated_at = ? WHERE scope = ? AND entity_type = ?")
+                .bind(Utc::now().to_rfc3339())
+                .bind(scope)
+                .bind(entity_type)
+                .execute(&mut *tx)
+                .await?;
+            let (prefix, width, value): (String, i64, i64) = sqlx::query_as(
+                "SELECT prefix, width, next_value - 1 FROM id_counters WHERE scope = ? AND entity_type = ?"
+            )
+            .bind(scope)
+            .bind(entity_type)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let id = format!("{}{:0width$}", prefix, value, width = width as usize);
+            if !taken(&mut tx, sequence, &id).await? {
+                tx.commit().await?;
+                return Ok(id);
+            }
+        }
+    }
+
+    /// The ID `next` would allocate, without allocating it
+    pub async fn peek(pool: &SqlitePool, project_id: &str, entity_type: &str) -> Result<String> {
+        let sequence = sequence(entity_type)?;
+        let mut conn = pool.acquire().await?;
+        let format = current(&mut conn, sequence, scope(sequence, project_id)).await?;
+        let mut value = format.next_value;
+        loop {
+            let id = format.format(value);
+            if !taken(&mut conn, sequence, &id).await? {
+                return Ok(id);
+            }
+            value += 1;
+        }
+    }
+
+    /// Set the prefix and width of an entity type's IDs in a project. The counter keeps
+    /// running, so earlier IDs stay valid and numbers are not reused.
+    pub async fn configure<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, entity_type: &str, prefix: &str, width: usize) -> Result<IdFormat> {
+        let sequence = sequence(entity_type)?;
+        if prefix.is_empty() || prefix.len() > 4 || !prefix.chars().all(|c| c.is_ascii_uppercase()) {
+            anyhow::bail!("ID prefixes are 1 to 4 upper case letters (got '{}')", prefix);
+        }
+        if !(1..=12).contains(&width) {
+            anyhow::bail!("ID widths range from 1 to 12 digits (got {})", width);
+        }
+
+        let scope = scope(sequence, project_id);
+        let mut tx = db.begin().await?;
+        check_storable(&mut tx, sequence, prefix, width).await?;
+        seed(&mut tx, sequence, scope, prefix, width).await?;
+        sqlx::query("UPDATE id_counters SET prefix = ?, width = ?, updated_at = ? WHERE scope = ? AND entity_type = ?")
+            .bind(prefix)
+            .bind(width as i64)
+            .bind(Utc::now().to_rfc3339())
+            .bind(scope)
+            .bind(entity_type)
+            .execute(&mut *tx)
+            .await?;
+        let format = current(&mut tx, sequence, scope).await?;
+        tx.commit().await?;
+        Ok(format)
+    }
+
+    /// ID formats of every entity type in a project
+    pub async fn formats(pool: &SqlitePool, project_id: &str) -> Result<Vec<IdFormat>> {
+        let mut conn = pool.acquire().await?;
+        let mut formats = Vec::new();
+        for sequence in &SEQUENCES {
+            formats.push(current(&mut conn, sequence, scope(sequence, project_id)).await?);
+        }
+        Ok(formats)
+    }
+}
+
+/// Create the counter of a sequence if missing, starting after the highest ID with that
+/// prefix already in the entity's table
+async fn seed(conn: &mut SqliteConnection, sequence: &Sequence, scope: &str, prefix: &str, width: usize) -> Result<()> {
+    sqlx::query(&format!(r#"
+        INSERT OR IGNORE INTO id_counters (scope, entity_type, prefix, width, next_value, updated_at)
+        SELECT ?, ?, ?, ?, COALESCE(MAX(CAST(SUBSTR(id, ?) AS INTEGER)), 0) + 1, ?
+        FROM {} WHERE id GLOB ?
+    "#, sequence.table))
+    .bind(scope)
+    .bind(sequence.entity_type)
+    .bind(prefix)
+    .bind(width as i64)
+    .bind(prefix.len() as i64 + 1)
+    .bind(Utc::now().to_rfc3339())
+    .bind(format!("{}[0-9]*", prefix))
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// The stored format of a sequence, or the default one it would be seeded with
+async fn current(conn: &mut SqliteConnection, sequence: &Sequence, scope: &str) -> Result<IdFormat> {
+    let row: Option<(String, i64, i64)> = sqlx::query_as(
+        "SELECT prefix, width, next_value FROM id_counters WHERE scope = ? AND entity_type = ?"
+    )
+    .bind(scope)
+    .bind(sequence.entity_type)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some((prefix, width, next_value)) = row {
+        return Ok(IdFormat { entity_type: sequence.entity_type.to_string(), prefix, width: width as usize, next_value, configured: true });
+    }
+    let highest: Option<i64> = sqlx::query_scalar(&format!(
+        "SELECT MAX(CAST(SUBSTR(id, ?) AS INTEGER)) FROM {} WHERE id GLOB ?", sequence.table
+    ))
+    .bind(sequence.prefix.len() as i64 + 1)
+    .bind(format!("{}[0-9]*", sequence.prefix))
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(IdFormat {
+        entity_type: sequence.entity_type.to_string(),
+        prefix: sequence.prefix.to_string(),
+        width: sequence.width,
+        next_value: highest.unwrap_or(0) + 1,
+        configured: false,
+    })
+}
+
+/// Tables not yet upgraded by migration 2 only accept the default pattern
+async fn check_storable(conn: &mut SqliteConnection, sequence: &Sequence, prefix: &str, width: usize) -> Result<()> {
+    if prefix == sequence.prefix && width == sequence.width {
+        return Ok(());
+    }
+    let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(sequence.table)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if sql.map_or(false, |sql| sql.contains(&format!("GLOB '{}[0-9]", sequence.prefix))) {
+        anyhow::bail!(
+            "The {} table of this database only accepts {}{:0width$}-style IDs; run `wsb db migrate` to upgrade it",
+            sequence.table, sequence.prefix, 1, width = sequence.width
+        );
+    }
+    Ok(())
+}
+
+async fn taken(conn: &mut SqliteConnection, sequence: &Sequence, id: &str) -> Result<bool> {
+    Ok(sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?)", sequence.table))
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use crate::entities::schema_models::Project;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_ids_are_sequential_and_configurable() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Ids".to_string(), "Sequences".to_string()).await.unwrap();
+        assert_eq!(project.id, "P001");
+
+        let first = crud::features::create(&pool, project.id.clone(), "One".to_string(), "First".to_string(), None).await.unwrap();
+        assert_eq!(first.id, "F00001");
+        assert_eq!(IdGenerator::peek(&pool, &project.id, "feature").await.unwrap(), "F00002");
+
+        let format = IdGenerator::configure(&pool, &project.id, "feature", "FT", 3).await.unwrap();
+        assert!(format.configured);
+        let second = crud::features::create(&pool, project.id.clone(), "Two".to_string(), "Second".to_string(), None).await.unwrap();
+        assert_eq!(second.id, "FT002");
+        assert!(crud::features::get_by_id(&pool, &second.id).await.unwrap().is_some());
+
+        assert!(IdGenerator::configure(&pool, &project.id, "feature", "ft", 3).await.is_err());
+        assert!(IdGenerator::configure(&pool, &project.id, "widget", "W", 3).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_projects_sharing_a_prefix_interleave() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let backend = crud::projects::create(&pool, "Backend".to_string(), "API".to_string()).await.unwrap();
+        let frontend = crud::projects::create(&pool, "Frontend".to_string(), "Web".to_string()).await.unwrap();
+        let feature = |project: &Project| crud::features::create(&pool, project.id.clone(), "Feature".to_string(), "Shared prefix".to_string(), None);
+
+        // Each project counts on its own, but numbers the other one took are skipped
+        assert_eq!(feature(&backend).await.unwrap().id, "F00001");
+        assert_eq!(feature(&backend).await.unwrap().id, "F00002");
+        assert_eq!(feature(&frontend).await.unwrap().id, "F00003");
+        assert_eq!(feature(&backend).await.unwrap().id, "F00004");
+        assert_eq!(feature(&frontend).await.unwrap().id, "F00005");
+
+        // With a prefix of its own a project's numbers stop skipping
+        IdGenerator::configure(&pool, &frontend.id, "feature", "WEB", 3).await.unwrap();
+        assert_eq!(feature(&frontend).await.unwrap().id, "WEB006");
+        assert_eq!(feature(&backend).await.unwrap().id, "F00006");
+        assert_eq!(feature(&frontend).await.unwrap().id, "WEB007");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_allocations_do_not_collide() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Ids".to_string(), "Sequences".to_string()).await.unwrap();
+
+        let allocations = (0..20).map(|_| {
+            let pool = pool.clone();
+            let project_id = project.id.clone();
+            tokio::spawn(async move { IdGenerator::next(&pool, &project_id, "task").await })
+        });
+        let mut ids = Vec::new();
+        for allocation in allocations.collect::<Vec<_>>() {
+            ids.push(allocation.await.unwrap().unwrap());
+        }
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 20);
+        assert_eq!(ids.first().map(String::as_str), Some("T000001"));
+    }
+}
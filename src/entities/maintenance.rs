@@ -0,0 +1,262 @@
+// Database maintenance - Single-file backups, restores and integrity checks
+// `wsb db backup --output FILE` writes a consistent copy of `.wsb/project.db` with
+// VACUUM INTO; `wsb db restore FILE` verifies a copy before swapping it in, keeping the
+// replaced database next to it; `wsb db check` runs PRAGMA integrity_check and lists rows
+// whose references point nowhere, each with a statement that would repair it.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use tokio::fs as async_fs;
+
+use crate::entities::database::{calculate_file_checksum, decompress_data};
+
+/// A backup written to a chosen file
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// Copy the database behind `pool` to `output`, which must not exist yet
+pub async fn backup_to(pool: &SqlitePool, output: &Path) -> Result<BackupFile> {
+    if output.exists() {
+        anyhow::bail!("Backup target already exists: {}", output.display());
+    }
+    if let Some(parent) = output.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        async_fs::create_dir_all(parent).await?;
+    }
+
+    // VACUUM INTO takes a string literal, so quotes in the path are doubled
+    let path = output.to_string_lossy().replace('\'', "''");
+    sqlx::query(&format!("VACUUM INTO '{}'", path))
+        .execute(pool)
+        .await
+        .with_context(|| format!("Failed to write backup to {}", output.display()))?;
+
+    Ok(BackupFile {
+        path: output.to_path_buf(),
+        size_bytes: async_fs::metadata(output).await?.len(),
+        sha256: calculate_file_checksum(output).await?,
+    })
+}
+
+/// What a restore replaced
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    /// Where the database that was replaced now lives
+    pub previous: Option<PathBuf>,
+}
+
+/// Replace `target` with the database in `source` (plain or gzip-compressed). The source
+/// must be a SQLite database that passes an integrity check; the replaced database is
+/// renamed to `<target>.before-restore-<timestamp>` rather than deleted.
+pub async fn restore_from(source: &Path, target: &Path) -> Result<RestoreReport> {
+    let mut data = async_fs::read(source).await
+        .with_context(|| format!("Failed to read backup {}", source.display()))?;
+    if data.starts_with(&[0x1f, 0x8b]) {
+        data = decompress_data(&data).await?;
+    }
+    if !data.starts_with(b"SQLite format 3\0") {
+        anyhow::bail!("Not a SQLite database: {}", source.display());
+    }
+
+    // Verify a staged copy next to the target, so the final swap is a rename
+    let staged = sibling(target, &format!("restore-{}", std::process::id()));
+    async_fs::write(&staged, &data).await?;
+    let problems = match staged_integrity(&staged).await {
+        Ok(problems) => problems,
+        Err(e) => {
+            async_fs::remove_file(&staged).await.ok();
+            return Err(e);
+        }
+    };
+    if !problems.is_empty() {
+        async_fs::remove_file(&staged).await.ok();
+        anyhow::bail!("Backup {} failed the integrity check: {}", source.display(), problems.join("; "));
+    }
+
+    let previous = if target.exists() {
+        let previous = sibling(target, &format!("before-restore-{}", Utc::now().format("%Y%m%d%H%M%S")));
+        async_fs::rename(target, &previous).await?;
+        Some(previous)
+    } else {
+        None
+    };
+    // Journal files of the replaced database would be replayed into the restored one
+    for suffix in ["-wal", "-shm"] {
+        let journal = PathBuf::from(format!("{}{}", target.display(), suffix));
+        if journal.exists() {
+            async_fs::remove_file(&journal).await?;
+        }
+    }
+    async_fs::rename(&staged, target).await?;
+
+    Ok(RestoreReport { source: source.to_path_buf(), target: target.to_path_buf(), previous })
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}.{}", path.display(), suffix))
+}
+
+async fn staged_integrity(path: &Path) -> Result<Vec<String>> {
+    let pool = SqlitePool::connect(&format!("sqlite:{}?mode=ro", path.display())).await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let problems = integrity_problems(&pool).await;
+    pool.close().await;
+    problems
+}
+
+/// Rows of PRAGMA integrity_check other than the lone "ok"
+async fn integrity_problems(pool: &SqlitePool) -> Result<Vec<String>> {
+    let rows: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check").fetch_all(pool).await?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+/// A row referencing something that does not exist
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanReference {
+    pub table: String,
+    pub rowid: i64,
+    pub column: String,
+    pub value: Option<String>,
+    /// Table the value should be found in
+    pub parent: String,
+    /// SQL that would remove the dangling reference
+    pub suggestion: String,
+}
+
+/// Result of `check`
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    /// Problems reported by PRAGMA integrity_check; empty when the file is sound
+    pub integrity: Vec<String>,
+    pub orphans: Vec<OrphanReference>,
+}
+
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.integrity.is_empty() && self.orphans.is_empty()
+    }
+}
+
+/// References kept in plain columns, without a foreign key: (table, type column, ID column)
+const SOFT_REFERENCES: [(&str, &str, &str); 1] = [("notes", "entity_type", "entity_id")];
+
+/// Run PRAGMA integrity_check and look for rows whose references point nowhere, both
+/// through declared foreign keys and through the entity references of notes
+pub async fn check(pool: &SqlitePool) -> Result<IntegrityReport> {
+    let integrity = integrity_problems(pool).await?;
+
+    let mut orphans = Vec::new();
+    for row in sqlx::query("PRAGMA foreign_key_check").fetch_all(pool).await? {
+        let table: String = row.get(0);
+        // Rows of WITHOUT ROWID tables have no rowid to point at
+        let Some(rowid) = row.get::<Option<i64>, _>(1) else { continue };
+        let parent: String = row.get(2);
+        let fk_index: i64 = row.get(3);
+
+        let (column, nullable) = foreign_key_column(pool, &table, fk_index).await?;
+        let value = sqlx::query_scalar::<_, Option<String>>(&format!("SELECT CAST(\"{}\" AS TEXT) FROM \"{}\" WHERE rowid = ?", column, table))
+            .bind(rowid)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+        let suggestion = if nullable {
+            format!("UPDATE \"{}\" SET \"{}\" = NULL WHERE rowid = {};", table, column, rowid)
+        } else {
+            format!("DELETE FROM \"{}\" WHERE rowid = {};", table, rowid)
+        };
+        orphans.push(OrphanReference { table, rowid, column, value, parent, suggestion });
+    }
+
+    for (table, type_column, id_column) in SOFT_REFERENCES {
+        for (entity_type, parent) in [("project", "projects"), ("feature", "features"), ("task", "tasks"), ("session", "sessions"), ("directive", "directives")] {
+            let rows = sqlx::query(&format!(
+                "SELECT rowid, {id} FROM {table} WHERE {ty} = ? AND {id} IS NOT NULL AND {id} NOT IN (SELECT id FROM {parent})",
+                id = id_column, table = table, ty = type_column, parent = parent
+            ))
+            .bind(entity_type)
+            .fetch_all(pool)
+            .await?;
+            for row in rows {
+                let rowid: i64 = row.get(0);
+                orphans.push(OrphanReference {
+                    table: table.to_string(),
+                    rowid,
+                    column: id_column.to_string(),
+                    value: row.get(1),
+                    parent: parent.to_string(),
+                    suggestion: format!("DELETE FROM \"{}\" WHERE rowid = {};", table, rowid),
+                });
+            }
+        }
+    }
+
+    Ok(IntegrityReport { integrity, orphans })
+}
+
+/// The referencing column of a table's foreign key, and whether it may be NULL
+async fn foreign_key_column(pool: &SqlitePool, table: &str, fk_index: i64) -> Result<(String, bool)> {
+    let column: String = sqlx::query(&format!("PRAGMA foreign_key_list(\"{}\")", table))
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .find(|row| row.get::<i64, _>("id") == fk_index)
+        .map(|row| row.get("from"))
+        .ok_or_else(|| anyhow::anyhow!("Foreign key {} of {} not found", fk_index, table))?;
+    let nullable = sqlx::query(&format!("PRAGMA table_info(\"{}\")", table))
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .find(|row| row.get::<String, _>("name") == column)
+        .map_or(false, |row| row.get::<i64, _>("notnull") == 0 && row.get::<i64, _>("pk") == 0);
+    Ok((column, nullable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_backup_restore_and_check() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("project.db");
+        let pool = crate::entities::database::initialize_database(&db_path).await.unwrap();
+        let project = crud::projects::create(&pool, "Maintenance".to_string(), "Backups".to_string()).await.unwrap();
+        crud::features::create(&pool, project.id.clone(), "Kept".to_string(), "In the backup".to_string(), None).await.unwrap();
+
+        let backup = backup_to(&pool, &temp.path().join("backups/copy.db")).await.unwrap();
+        assert!(backup.size_bytes > 0);
+        assert!(backup_to(&pool, &backup.path).await.is_err());
+        assert!(check(&pool).await.unwrap().is_healthy());
+
+        // A note about a feature that no longer exists is reported as an orphan
+        sqlx::query("DELETE FROM features").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO notes (id, project_id, entity_type, entity_id, note_type, title, content, created_at, updated_at) VALUES ('note-x', ?, 'feature', 'F00001', 'general', 'Left', 'Behind', datetime('now'), datetime('now'))")
+            .bind(&project.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = check(&pool).await.unwrap();
+        assert!(report.orphans.iter().any(|orphan| orphan.table == "notes" && orphan.value.as_deref() == Some("F00001")));
+        pool.close().await;
+
+        let restored = restore_from(&backup.path, &db_path).await.unwrap();
+        assert!(restored.previous.as_ref().map_or(false, |previous| previous.exists()));
+        let pool = crate::entities::database::initialize_database(&db_path).await.unwrap();
+        assert_eq!(crud::features::list_by_project(&pool, &project.id).await.unwrap().len(), 1);
+
+        let junk = temp.path().join("junk.db");
+        std::fs::write(&junk, b"not a database").unwrap();
+        assert!(restore_from(&junk, &db_path).await.is_err());
+    }
+}
@@ -0,0 +1,357 @@
+// Schema migrations - Versioned, embedded upgrades of existing project databases
+// `initialize_tables` creates the baseline schema (version 1) with CREATE ... IF NOT EXISTS,
+// which cannot change a table that already exists. Changes to existing tables are
+// migrations: each runs once per database, in version order, and is recorded in
+// `schema_version`. `initialize_database` applies pending migrations when it opens a
+// database for writing; `wsb db migrate --status` lists them without applying anything.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::{Acquire, SqliteConnection, SqlitePool};
+
+/// One schema change
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Every migration, oldest first. Append new ones here and in `apply`.
+pub const MIGRATIONS: [Migration; 7] = [
+    Migration {
+        version: 1,
+        name: "baseline",
+        description: "Schema created by initialize_tables",
+    },
+    Migration {
+        version: 2,
+        name: "configurable_entity_ids",
+        description: "Accept configured ID prefixes and widths in the project, feature, task, session and directive tables",
+    },
+    Migration {
+        version: 3,
+        name: "relationship_kinds",
+        description: "Accept depends_on and worked_in relationships in the dependencies table",
+    },
+    Migration {
+        version: 4,
+        name: "project_release_settings",
+        description: "Add version scheme, pre-release, changelog and package mode settings to projects",
+    },
+    Migration {
+        version: 5,
+        name: "soft_delete",
+        description: "Add deleted_at to projects, features and tasks",
+    },
+    Migration {
+        version: 6,
+        name: "milestone_tasks",
+        description: "Let milestones group tasks and be achieved before their target date",
+    },
+    Migration {
+        version: 7,
+        name: "event_sink_delivery",
+        description: "Record which sinks each outbox event was delivered to",
+    },
+];
+
+/// Version a fully migrated database is at
+pub fn latest_version() -> i32 {
+    MIGRATIONS[MIGRATIONS.len() - 1].version
+}
+
+async fn apply(migration: &Migration, conn: &mut SqliteConnection) -> Result<()> {
+    match migration.version {
+        1 => Ok(()),
+        2 => relax_entity_id_checks(conn).await,
+        3 => widen_dependency_types(conn).await,
+        4 => add_project_release_settings(conn).await,
+        5 => add_soft_delete_columns(conn).await,
+        6 => extend_milestones(conn).await,
+        7 => add_column(conn, "event_outbox", "delivered_sinks", "TEXT").await,
+        version => anyhow::bail!("Migration {} has no implementation", version),
+    }
+}
+
+/// A migration and when it was applied to this database
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i32,
+    pub name: String,
+    pub description: String,
+    /// None while the migration is pending
+    pub applied_at: Option<String>,
+}
+
+async fn ensure_version_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+    crate::entities::actors::ensure_column(pool, "schema_version", "name", "TEXT").await
+}
+
+/// Every known migration with its applied time; reads only, so it works on read-only pools
+pub async fn status(pool: &SqlitePool) -> Result<Vec<MigrationStatus>> {
+    let tracked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'")
+        .fetch_one(pool)
+        .await?;
+    let applied: Vec<(i32, String)> = if tracked > 0 {
+        sqlx::query_as("SELECT version, applied_at FROM schema_version").fetch_all(pool).await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(MIGRATIONS
+        .iter()
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            name: migration.name.to_string(),
+            description: migration.description.to_string(),
+            applied_at: applied.iter().find(|(version, _)| *version == migration.version).map(|(_, at)| at.clone()),
+        })
+        .collect())
+}
+
+/// Apply the pending migrations in order; returns the ones applied by this call
+pub async fn migrate(pool: &SqlitePool) -> Result<Vec<MigrationStatus>> {
+    ensure_version_table(pool).await?;
+    let pending: Vec<Migration> = status(pool)
+        .await?
+        .iter()
+        .filter(|status| status.applied_at.is_none())
+        .filter_map(|status| MIGRATIONS.iter().find(|migration| migration.version == status.version).copied())
+        .collect();
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = pool.acquire().await?;
+    // Rebuilding a table drops it, which must not cascade into the rows referencing it
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+    let result = apply_pending(&mut conn, &pending).await;
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+    let applied = result?;
+
+    Ok(status(pool).await?.into_iter().filter(|status| applied.contains(&status.version)).collect())
+}
+
+async fn apply_pending(conn: &mut SqliteConnection, pending: &[Migration]) -> Result<Vec<i32>> {
+    let mut applied = Vec::new();
+    for migration in pending {
+        let mut tx = Acquire::begin(&mut *conn).await?;
+        // Claiming the version first takes the write lock; another process that got there
+        // first leaves nothing to do
+        let claimed = sqlx::query("INSERT OR IGNORE INTO schema_version (version, name) VALUES (?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if claimed == 0 {
+            continue;
+        }
+        apply(migration, &mut tx)
+            .await
+            .with_context(|| format!("Migration {} ({}) failed", migration.version, migration.name))?;
+        tx.commit().await?;
+        log::info!("Applied schema migration {} ({})", migration.version, migration.name);
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// Recreate a table from `create_sql`, keeping its rows and indexes. Foreign keys must be
+/// off on the connection, and the new definition must keep the column order.
+pub(crate) async fn rebuild_table(conn: &mut SqliteConnection, table: &str, create_sql: &str) -> Result<()> {
+    let indexes: Vec<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'index' AND tbl_name = ? AND sql IS NOT NULL")
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let staging = format!("{}_rebuild", table);
+    let header = regex::Regex::new(r#"^\s*CREATE TABLE (IF NOT EXISTS )?["`\[]?\w+["`\]]?"#).unwrap();
+    if !header.is_match(create_sql) {
+        anyhow::bail!("Not a CREATE TABLE statement for {}", table);
+    }
+    let create = header.replace(create_sql, format!("CREATE TABLE {}", staging).as_str());
+
+    sqlx::query(&create).execute(&mut *conn).await?;
+    sqlx::query(&format!("INSERT INTO {} SELECT * FROM {}", staging, table)).execute(&mut *conn).await?;
+    sqlx::query(&format!("DROP TABLE {}", table)).execute(&mut *conn).await?;
+    sqlx::query(&format!("ALTER TABLE {} RENAME TO {}", staging, table)).execute(&mut *conn).await?;
+    for index in indexes {
+        sqlx::query(&index).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Add a column unless it is already there; databases that predate this framework may
+/// have gained it when it was added on open
+async fn add_column(conn: &mut SqliteConnection, table: &str, column: &str, declaration: &str) -> Result<()> {
+    let present: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(&mut *conn)
+        .await?;
+    if present == 0 {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, declaration))
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Version 2: tables created before `ids::IdGenerator` pinned IDs to the default patterns
+/// (`F[0-9][0-9][0-9][0-9][0-9]` and so on)
+async fn relax_entity_id_checks(conn: &mut SqliteConnection) -> Result<()> {
+    let pinned = regex::Regex::new(r"\b(id|code) GLOB '[A-Z](\[0-9\])+'").unwrap();
+    for table in ["projects", "features", "tasks", "sessions", "directives"] {
+        let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_optional(&mut *conn)
+            .await?;
+        let Some(sql) = sql.filter(|sql| pinned.is_match(sql)) else { continue };
+        let relaxed = pinned.replace_all(&sql, "$1 GLOB '[A-Z]*[0-9]' AND $1 NOT GLOB '*[^A-Z0-9]*'");
+        rebuild_table(conn, table, &relaxed).await?;
+    }
+    Ok(())
+}
+
+/// Version 3: `dependencies` tables created before feature-to-feature `depends_on` and
+/// session-to-task `worked_in` links were added to `chk_dependencies_type`
+async fn widen_dependency_types(conn: &mut SqliteConnection) -> Result<()> {
+    let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind("dependencies")
+        .fetch_optional(&mut *conn)
+        .await?;
+    let Some(sql) = sql.filter(|sql| !sql.contains("'worked_in'")) else { return Ok(()) };
+    let kinds = regex::Regex::new(r"(chk_dependencies_type CHECK \(dependency_type IN \([^)]*?)(\s*\)\))").unwrap();
+    if !kinds.is_match(&sql) {
+        anyhow::bail!("dependencies table has no chk_dependencies_type constraint to widen");
+    }
+    let widened = kinds.replace(&sql, "$1, 'depends_on', 'worked_in'$2");
+    rebuild_table(conn, "dependencies", &widened).await
+}
+
+/// Version 4: version scheme selection, the hand-set parts used by the semver scheme,
+/// changelog generation and workspace package versioning
+async fn add_project_release_settings(conn: &mut SqliteConnection) -> Result<()> {
+    let columns = [
+        ("version_scheme", "TEXT NOT NULL DEFAULT 'commit-count'"),
+        ("minor_version", "INTEGER NOT NULL DEFAULT 0"),
+        ("patch_version", "INTEGER NOT NULL DEFAULT 0"),
+        ("pre_release", "TEXT"),
+        ("version_base_commit", "TEXT"),
+        ("changelog_on_update", "BOOLEAN NOT NULL DEFAULT 0"),
+        ("changelog_file", "TEXT NOT NULL DEFAULT 'CHANGELOG.md'"),
+        ("changelog_template", "TEXT"),
+        ("package_mode", "TEXT NOT NULL DEFAULT 'lockstep'"),
+    ];
+    for (column, declaration) in columns {
+        add_column(conn, "projects", column, declaration).await?;
+    }
+    Ok(())
+}
+
+/// Version 5: rows with deleted_at set are hidden from standard queries until restored or purged
+async fn add_soft_delete_columns(conn: &mut SqliteConnection) -> Result<()> {
+    for table in ["projects", "features", "tasks"] {
+        add_column(conn, table, "deleted_at", "TEXT").await?;
+    }
+    Ok(())
+}
+
+/// Version 6: milestones tables whose date check required `achieved_date >= target_date`,
+/// which rejected milestones achieved ahead of their target, lose that check; every
+/// milestones table gains task_ids
+async fn extend_milestones(conn: &mut SqliteConnection) -> Result<()> {
+    let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind("milestones")
+        .fetch_optional(&mut *conn)
+        .await?;
+    if sql.is_some_and(|sql| sql.contains("achieved_date >= target_date")) {
+        rebuild_table(conn, "milestones", crate::entities::database::MILESTONES_TABLE).await?;
+    }
+    add_column(conn, "milestones", "task_ids", "TEXT").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use crate::entities::ids::IdGenerator;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_pending_migrations_upgrade_in_place() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Migrations".to_string(), "Upgrades".to_string()).await.unwrap();
+        assert!(status(&pool).await.unwrap().iter().all(|status| status.applied_at.is_some()));
+        assert!(migrate(&pool).await.unwrap().is_empty());
+
+        // Put the directives table back the way databases created before version 2 had it
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            let sql: String = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'directives'")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+            let legacy = sql.replace("id GLOB '[A-Z]*[0-9]' AND id NOT GLOB '*[^A-Z0-9]*'", "id GLOB 'D[0-9][0-9][0-9]'");
+            sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await.unwrap();
+            rebuild_table(&mut conn, "directives", &legacy).await.unwrap();
+            sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await.unwrap();
+            sqlx::query("DELETE FROM schema_version WHERE version = 2").execute(&mut *conn).await.unwrap();
+        }
+        assert!(IdGenerator::configure(&pool, &project.id, "directive", "DIR", 3).await.is_err());
+        assert_eq!(status(&pool).await.unwrap().iter().filter(|status| status.applied_at.is_none()).count(), 1);
+
+        let applied = migrate(&pool).await.unwrap();
+        assert_eq!(applied.iter().map(|status| status.version).collect::<Vec<_>>(), [2]);
+        IdGenerator::configure(&pool, &project.id, "directive", "DIR", 3).await.unwrap();
+        let indexes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND tbl_name = 'directives' AND sql IS NOT NULL")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(indexes > 0);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_milestones_accept_early_achievement_and_tasks() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Milestones".to_string(), "Upgrades".to_string()).await.unwrap();
+
+        // Put the milestones table back the way databases created before version 6 had it
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            let legacy = crate::entities::database::MILESTONES_TABLE.replace(
+                "completion_percentage >= 0.0 AND completion_percentage <= 100.0\n            )",
+                "completion_percentage >= 0.0 AND completion_percentage <= 100.0\n            ),\n            CONSTRAINT chk_milestones_dates CHECK (\n                achieved_date IS NULL OR target_date IS NULL OR achieved_date >= target_date OR status != 'achieved'\n            )",
+            );
+            assert!(legacy.contains("achieved_date >= target_date"));
+            sqlx::query("DROP TABLE milestones").execute(&mut *conn).await.unwrap();
+            sqlx::query(&legacy).execute(&mut *conn).await.unwrap();
+            sqlx::query("DELETE FROM schema_version WHERE version = 6").execute(&mut *conn).await.unwrap();
+        }
+        sqlx::query("INSERT INTO milestones (id, project_id, title, description, target_date, feature_ids, created_at, updated_at) VALUES ('M001', ?, 'Beta', 'First beta', '2999-01-01', '[]', ?, ?)")
+            .bind(&project.id)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let applied = migrate(&pool).await.unwrap();
+        assert_eq!(applied.iter().map(|status| status.version).collect::<Vec<_>>(), [6]);
+        let milestone = crud::milestones::close(&pool, "M001", crate::entities::MilestoneStatus::Achieved).await.unwrap();
+        assert_eq!(milestone.title, "Beta");
+        assert!(milestone.achieved_date.is_some());
+    }
+}
@@ -5,10 +5,43 @@ pub mod database;
 pub mod crud;
 pub mod schema_models;
 pub mod schema_traits;
+pub mod relationships;
+pub mod events;
+pub mod policy;
+pub mod bench;
+pub mod preview;
+pub mod actors;
+pub mod schedule;
+pub mod brief;
+pub mod doc_coverage;
+pub mod bootstrap;
+pub mod bodies;
+pub mod retention;
+pub mod code_coverage;
+pub mod calendar;
+pub mod recurrence;
+pub mod time_tracking;
+pub mod notes;
+pub mod views;
+pub mod tags;
+pub mod ids;
+pub mod maintenance;
+pub mod migrations;
+pub mod workspace;
+pub mod git_activity;
+pub mod sprints;
+pub mod directive_rules;
+pub mod graph;
+pub mod changes;
+pub mod transfer;
+pub mod transaction;
+pub mod audit;
+pub mod sync;
 
 // Re-export key types for easy access
 pub use schema_models::*;
 pub use schema_traits::*;
+pub use transaction::{EntityTransaction, TransactionFuture};
 
 use anyhow::Result;
 use sqlx::SqlitePool;
@@ -16,12 +49,13 @@ use sqlx::SqlitePool;
 /// Entity Manager - Unified interface for all entity operations
 pub struct EntityManager {
     pub pool: SqlitePool,
+    changes: changes::ChangeFeed,
 }
 
 impl EntityManager {
     /// Create new entity manager with database connection
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { pool, changes: changes::ChangeFeed::new() }
     }
 
     /// Get database pool reference
@@ -29,9 +63,61 @@ impl EntityManager {
         &self.pool
     }
 
+    /// Feed of the changes committed through this manager; see [`changes`]
+    pub fn changes(&self) -> &changes::ChangeFeed {
+        &self.changes
+    }
+
+    /// Tell change subscribers about a committed mutation. Failing to publish never fails
+    /// the mutation itself.
+    async fn announce(&self, entity_type: &str, entity_id: &str, event_type: &str) {
+        if let Err(e) = self.changes.announce(&self.pool, entity_type, entity_id, event_type).await {
+            log::warn!("Failed to publish {} for {}: {}", event_type, entity_id, e);
+        }
+    }
+
+    /// Tell change subscribers about the outbox events committed so far
+    async fn publish_recorded(&self) {
+        if let Err(e) = self.changes.pump(&self.pool).await {
+            log::warn!("Failed to publish recorded changes: {}", e);
+        }
+    }
+
+    /// Run several entity operations atomically. Everything done through the
+    /// [`EntityTransaction`] is committed if the closure returns `Ok` and rolled back
+    /// if it returns `Err`:
+    ///
+    /// ```ignore
+    /// let feature = manager.transaction(|tx| Box::pin(async move {
+    ///     let feature = tx.create_feature(project_id.clone(), name, description, None).await?;
+    ///     tx.create_task(project_id, feature.id.clone(), "Write tests".into(), "testing".into()).await?;
+    ///     Ok(feature)
+    /// })).await?;
+    /// ```
+    pub async fn transaction<T, F>(&self, operations: F) -> Result<T>
+    where
+        F: for<'t> FnOnce(&'t mut EntityTransaction) -> TransactionFuture<'t, T>,
+    {
+        let mut tx = EntityTransaction::new(self.pool.begin().await?);
+        match operations(&mut tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+                self.publish_recorded().await;
+                Ok(value)
+            }
+            Err(error) => {
+                // The operation's error is the one worth reporting
+                let _ = tx.rollback().await;
+                Err(error)
+            }
+        }
+    }
+
     /// Create a new project
     pub async fn create_project(&self, name: String, description: String) -> Result<Project> {
-        crud::projects::create(&self.pool, name, description).await
+        let project = crud::projects::create(&self.pool, name, description).await?;
+        self.announce("project", &project.id, "project.created").await;
+        Ok(project)
     }
 
     /// Get project by ID
@@ -54,7 +140,9 @@ impl EntityManager {
         // Use first active project if available
         let project = self.get_current_project().await?;
         let project_id = project.map(|p| p.id).unwrap_or_else(|| "P001".to_string());
-        crud::features::create(&self.pool, project_id, name, description, None).await
+        let feature = crud::features::create(&self.pool, project_id, name, description, None).await?;
+        self.announce("feature", &feature.id, "feature.created").await;
+        Ok(feature)
     }
 
     /// Create a new feature with full parameters
@@ -65,7 +153,9 @@ impl EntityManager {
         description: String,
         category: Option<String>,
     ) -> Result<Feature> {
-        crud::features::create(&self.pool, project_id, name, description, category).await
+        let feature = crud::features::create(&self.pool, project_id, name, description, category).await?;
+        self.announce("feature", &feature.id, "feature.created").await;
+        Ok(feature)
     }
 
     /// Get feature by ID
@@ -91,7 +181,9 @@ impl EntityManager {
 
     /// Update feature state
     pub async fn update_feature_state(&self, id: &str, new_state: FeatureState) -> Result<()> {
-        crud::features::update_state(&self.pool, id, new_state).await
+        crud::features::update_state(&self.pool, id, new_state).await?;
+        self.announce("feature", id, "feature.state_changed").await;
+        Ok(())
     }
 
     /// Create a new task (backward compatibility with 2-param signature)
@@ -105,7 +197,9 @@ impl EntityManager {
         let project_id = project.map(|p| p.id).unwrap_or_else(|| "P001".to_string());
         let features = self.list_features_by_project(&project_id).await?;
         let feature_id = features.first().map(|f| f.id.clone()).unwrap_or_else(|| "F00001".to_string());
-        crud::tasks::create(&self.pool, project_id, feature_id, title, "feature".to_string()).await
+        let task = crud::tasks::create(&self.pool, project_id, feature_id, title, "feature".to_string()).await?;
+        self.announce("task", &task.id, "task.created").await;
+        Ok(task)
     }
 
     /// Create a new task with full parameters
@@ -116,7 +210,9 @@ impl EntityManager {
         task_description: String,
         category: String,
     ) -> Result<Task> {
-        crud::tasks::create(&self.pool, project_id, feature_id, task_description, category).await
+        let task = crud::tasks::create(&self.pool, project_id, feature_id, task_description, category).await?;
+        self.announce("task", &task.id, "task.created").await;
+        Ok(task)
     }
 
     /// Get task by ID
@@ -146,12 +242,16 @@ impl EntityManager {
 
     /// Update task status
     pub async fn update_task_status(&self, id: &str, new_status: TaskStatus) -> Result<()> {
-        crud::tasks::update_status(&self.pool, id, new_status).await
+        crud::tasks::update_status(&self.pool, id, new_status).await?;
+        self.announce("task", id, "task.status_changed").await;
+        Ok(())
     }
 
     /// Update task (full object update)
     pub async fn update_task(&self, task: Task) -> Result<()> {
-        crud::tasks::update(&self.pool, &task).await
+        crud::tasks::update(&self.pool, &task).await?;
+        self.announce("task", &task.id, "task.updated").await;
+        Ok(())
     }
 
     /// Create a new session
@@ -161,7 +261,9 @@ impl EntityManager {
         title: String,
         focus: String,
     ) -> Result<Session> {
-        crud::sessions::create(&self.pool, project_id, title, Some(focus)).await
+        let session = crud::sessions::create(&self.pool, project_id, title, Some(focus)).await?;
+        self.announce("session", &session.id, "session.created").await;
+        Ok(session)
     }
 
     /// Get session by ID
@@ -177,7 +279,9 @@ impl EntityManager {
 
     /// Complete a session
     pub async fn complete_session(&self, id: &str, summary: String) -> Result<()> {
-        crud::sessions::complete(&self.pool, id, summary).await
+        crud::sessions::complete(&self.pool, id, summary).await?;
+        self.announce("session", id, "session.completed").await;
+        Ok(())
     }
 
     /// Create a new directive
@@ -189,7 +293,9 @@ impl EntityManager {
         category: DirectiveCategory,
         priority: Priority,
     ) -> Result<Directive> {
-        crud::directives::create(&self.pool, project_id, title, rule, category, priority).await
+        let directive = crud::directives::create(&self.pool, project_id, title, rule, category, priority).await?;
+        self.announce("directive", &directive.id, "directive.created").await;
+        Ok(directive)
     }
 
     /// Get directive by ID
@@ -204,34 +310,302 @@ impl EntityManager {
 
     /// Deactivate a directive
     pub async fn deactivate_directive(&self, id: &str) -> Result<()> {
-        crud::directives::deactivate(&self.pool, id).await
+        crud::directives::deactivate(&self.pool, id).await?;
+        self.announce("directive", id, "directive.deactivated").await;
+        Ok(())
+    }
+
+    /// Create a new milestone
+    pub async fn create_milestone(
+        &self,
+        project_id: String,
+        title: String,
+        description: String,
+        target_date: Option<String>,
+    ) -> Result<Milestone> {
+        let milestone = crud::milestones::create(&self.pool, project_id, title, description, target_date).await?;
+        self.announce("milestone", &milestone.id, "milestone.created").await;
+        Ok(milestone)
+    }
+
+    /// Get milestone by ID
+    pub async fn get_milestone(&self, id: &str) -> Result<Option<Milestone>> {
+        crud::milestones::get_by_id(&self.pool, id).await
+    }
+
+    /// List milestones by project
+    pub async fn list_milestones(&self, project_id: &str) -> Result<Vec<Milestone>> {
+        crud::milestones::list_by_project(&self.pool, project_id).await
+    }
+
+    /// Add features and tasks to a milestone
+    pub async fn add_milestone_members(&self, id: &str, feature_ids: &[String], task_ids: &[String]) -> Result<Milestone> {
+        let milestone = crud::milestones::add_members(&self.pool, id, feature_ids, task_ids).await?;
+        self.announce("milestone", id, "milestone.members_added").await;
+        Ok(milestone)
+    }
+
+    /// Close a milestone as achieved, missed or cancelled
+    pub async fn close_milestone(&self, id: &str, status: MilestoneStatus) -> Result<Milestone> {
+        let milestone = crud::milestones::close(&self.pool, id, status).await?;
+        self.announce("milestone", id, "milestone.closed").await;
+        Ok(milestone)
     }
 
-    /// Delete a project (CASCADE will handle dependent entities)
+    /// Soft-delete a project; `restore_project` undoes it, `purge_project` removes it for good
     pub async fn delete_project(&self, id: &str) -> Result<()> {
-        crud::projects::delete(&self.pool, id).await
+        crud::projects::delete(&self.pool, id).await?;
+        self.announce("project", id, "project.deleted").await;
+        Ok(())
     }
 
-    /// Delete a feature (SET NULL will update dependent tests)
+    /// Restore a soft-deleted project
+    pub async fn restore_project(&self, id: &str) -> Result<()> {
+        crud::projects::restore(&self.pool, id).await?;
+        self.announce("project", id, "project.restored").await;
+        Ok(())
+    }
+
+    /// Permanently delete a project (CASCADE will handle dependent entities)
+    pub async fn purge_project(&self, id: &str) -> Result<()> {
+        crud::projects::purge(&self.pool, id).await?;
+        self.announce("project", id, "project.purged").await;
+        Ok(())
+    }
+
+    /// Soft-delete a feature along with its tasks
     pub async fn delete_feature(&self, id: &str) -> Result<()> {
-        crud::features::delete(&self.pool, id).await
+        crud::features::delete(&self.pool, id).await?;
+        self.announce("feature", id, "feature.deleted").await;
+        Ok(())
+    }
+
+    /// Restore a soft-deleted feature and the tasks deleted with it
+    pub async fn restore_feature(&self, id: &str) -> Result<()> {
+        crud::features::restore(&self.pool, id).await?;
+        self.announce("feature", id, "feature.restored").await;
+        Ok(())
     }
 
-    /// Delete a task
+    /// Permanently delete a feature and the tasks that reference it
+    pub async fn purge_feature(&self, id: &str) -> Result<()> {
+        crud::features::purge(&self.pool, id).await?;
+        self.announce("feature", id, "feature.purged").await;
+        Ok(())
+    }
+
+    /// List soft-deleted features of a project
+    pub async fn list_deleted_features(&self, project_id: &str) -> Result<Vec<Feature>> {
+        crud::features::list_deleted(&self.pool, project_id).await
+    }
+
+    /// Soft-delete a task
     pub async fn delete_task(&self, id: &str) -> Result<()> {
-        crud::tasks::delete(&self.pool, id).await
+        crud::tasks::delete(&self.pool, id).await?;
+        self.announce("task", id, "task.deleted").await;
+        Ok(())
+    }
+
+    /// Restore a soft-deleted task
+    pub async fn restore_task(&self, id: &str) -> Result<()> {
+        crud::tasks::restore(&self.pool, id).await?;
+        self.announce("task", id, "task.restored").await;
+        Ok(())
+    }
+
+    /// Permanently delete a task
+    pub async fn purge_task(&self, id: &str) -> Result<()> {
+        crud::tasks::purge(&self.pool, id).await?;
+        self.announce("task", id, "task.purged").await;
+        Ok(())
+    }
+
+    /// List soft-deleted tasks of a project
+    pub async fn list_deleted_tasks(&self, project_id: &str) -> Result<Vec<Task>> {
+        crud::tasks::list_deleted(&self.pool, project_id).await
+    }
+
+    /// Make a task wait on another; rejected if it would create a dependency cycle
+    pub async fn add_task_dependency(&self, id: &str, prerequisite_id: &str) -> Result<relationships::Dependency> {
+        let dependency = crud::tasks::add_dependency(&self.pool, id, prerequisite_id).await?;
+        self.announce("task", id, "task.dependency_added").await;
+        Ok(dependency)
+    }
+
+    /// Pending tasks whose dependencies are all done
+    pub async fn list_ready_tasks(&self, project_id: &str) -> Result<Vec<Task>> {
+        crud::tasks::ready(&self.pool, project_id).await
+    }
+
+    /// Open tasks waiting on a task, with the prerequisites each is still waiting on
+    pub async fn list_tasks_blocked_by(&self, id: &str) -> Result<Vec<(Task, Vec<String>)>> {
+        crud::tasks::blocked_by(&self.pool, id).await
+    }
+
+    /// Recurring task templates with the instances created from them. Completing a
+    /// template or instance creates the next instance.
+    pub async fn list_recurring_tasks(&self, project_id: &str) -> Result<Vec<recurrence::Series>> {
+        recurrence::list_series(&self.pool, project_id).await
+    }
+
+    /// Start a timer on a task, stopping the actor's running timer; returns both entries
+    pub async fn start_task_timer(&self, task_id: &str) -> Result<(time_tracking::TimeEntry, Option<time_tracking::TimeEntry>)> {
+        let entries = time_tracking::start(&self.pool, task_id).await?;
+        self.announce("task", task_id, "task.timer_started").await;
+        Ok(entries)
+    }
+
+    /// Stop the actor's running timer
+    pub async fn stop_task_timer(&self) -> Result<time_tracking::TimeEntry> {
+        let entry = time_tracking::stop(&self.pool).await?;
+        self.announce("task", &entry.task_id, "task.timer_stopped").await;
+        Ok(entry)
+    }
+
+    /// Cumulative time tracked on a task
+    pub async fn get_task_time(&self, task_id: &str) -> Result<time_tracking::TaskTime> {
+        time_tracking::task_total(&self.pool, task_id).await
+    }
+
+    /// Create a note with files under `root` attached
+    pub async fn create_note(&self, note: notes::NewNote, root: &std::path::Path, files: &[std::path::PathBuf]) -> Result<notes::Note> {
+        let note = notes::create(&self.pool, note, root, files).await?;
+        self.announce("note", &note.id, "note.created").await;
+        Ok(note)
+    }
+
+    /// Get a note with its attachments
+    pub async fn get_note(&self, id: &str) -> Result<Option<notes::Note>> {
+        notes::get(&self.pool, id).await
+    }
+
+    /// Link tags to an entity; returns its tags afterwards
+    pub async fn tag_entity(&self, project_id: &str, entity_type: &str, entity_id: &str, names: &[String]) -> Result<Vec<String>> {
+        let tags = tags::add(&self.pool, project_id, entity_type, entity_id, names).await?;
+        self.announce(entity_type, entity_id, "tags.changed").await;
+        Ok(tags)
+    }
+
+    /// Unlink tags from an entity; returns its tags afterwards
+    pub async fn untag_entity(&self, project_id: &str, entity_type: &str, entity_id: &str, names: &[String]) -> Result<Vec<String>> {
+        let tags = tags::remove(&self.pool, project_id, entity_type, entity_id, names).await?;
+        self.announce(entity_type, entity_id, "tags.changed").await;
+        Ok(tags)
+    }
+
+    /// Tags of an entity
+    pub async fn get_entity_tags(&self, entity_type: &str, entity_id: &str) -> Result<Vec<String>> {
+        tags::for_entity(&self.pool, entity_type, entity_id).await
+    }
+
+    /// IDs of entities of a type carrying all of the given tags
+    pub async fn find_tagged(&self, project_id: &str, entity_type: &str, names: &[String]) -> Result<std::collections::HashSet<String>> {
+        tags::tagged_with(&self.pool, project_id, entity_type, names).await
+    }
+
+    /// A project's tags with their usage per entity type
+    pub async fn list_tags(&self, project_id: &str) -> Result<Vec<tags::TagCount>> {
+        tags::counts(&self.pool, project_id).await
+    }
+
+    /// Rename a tag, keeping its links
+    pub async fn rename_tag(&self, project_id: &str, old: &str, new: &str) -> Result<()> {
+        tags::rename(&self.pool, project_id, old, new).await?;
+        self.publish_recorded().await;
+        Ok(())
+    }
+
+    /// Merge tags into `target`; returns the number of links moved
+    pub async fn merge_tags(&self, project_id: &str, sources: &[String], target: &str) -> Result<u64> {
+        let moved = tags::merge(&self.pool, project_id, sources, target).await?;
+        self.publish_recorded().await;
+        Ok(moved)
+    }
+
+    /// ID formats of every entity type in a project
+    pub async fn id_formats(&self, project_id: &str) -> Result<Vec<ids::IdFormat>> {
+        ids::IdGenerator::formats(&self.pool, project_id).await
+    }
+
+    /// Set the prefix and width of an entity type's IDs in a project
+    pub async fn configure_ids(&self, project_id: &str, entity_type: &str, prefix: &str, width: usize) -> Result<ids::IdFormat> {
+        let format = ids::IdGenerator::configure(&self.pool, project_id, entity_type, prefix, width).await?;
+        self.announce("project", project_id, "ids.configured").await;
+        Ok(format)
+    }
+
+    /// Save a named filter, replacing an existing one with the same name
+    pub async fn save_view(&self, project_id: &str, name: &str, definition: views::ViewDefinition) -> Result<views::SavedView> {
+        let view = views::save(&self.pool, project_id, name, definition).await?;
+        self.announce("view", &view.id, "view.saved").await;
+        Ok(view)
+    }
+
+    /// Get a saved view by name
+    pub async fn get_view(&self, project_id: &str, name: &str) -> Result<Option<views::SavedView>> {
+        views::get(&self.pool, project_id, name).await
+    }
+
+    /// List a project's saved views
+    pub async fn list_views(&self, project_id: &str) -> Result<Vec<views::SavedView>> {
+        views::list(&self.pool, project_id).await
+    }
+
+    /// Delete a saved view; false when none has that name
+    pub async fn delete_view(&self, project_id: &str, name: &str) -> Result<bool> {
+        let deleted = views::delete(&self.pool, project_id, name).await?;
+        self.announce("view", name, "view.deleted").await;
+        Ok(deleted)
+    }
+
+    /// Snapshot of a project with the given entity kinds
+    pub async fn export_project(&self, project_id: &str, kinds: &[transfer::EntityKind]) -> Result<transfer::Snapshot> {
+        transfer::export(&self.pool, project_id, kinds).await
+    }
+
+    /// Load a snapshot, remapping IDs that are already taken
+    pub async fn import_snapshot(&self, snapshot: &transfer::Snapshot, mode: transfer::ImportMode) -> Result<transfer::ImportReport> {
+        let report = transfer::import(&self.pool, snapshot, mode).await?;
+        self.announce("project", &snapshot.project_id, "project.imported").await;
+        Ok(report)
     }
 
     /// Delete a session (SET NULL will update dependent tasks and audit trails)
     pub async fn delete_session(&self, id: &str) -> Result<()> {
-        crud::sessions::delete(&self.pool, id).await
+        crud::sessions::delete(&self.pool, id).await?;
+        self.announce("session", id, "session.deleted").await;
+        Ok(())
     }
 
-    /// Get current project (placeholder - needs project detection logic)
+    /// Get the project entity commands work on (see `workspace::current_project`)
     pub async fn get_current_project(&self) -> Result<Option<Project>> {
-        // For now, get the first active project
-        let projects = self.list_active_projects().await?;
-        Ok(projects.into_iter().next())
+        workspace::current_project(&self.pool).await
+    }
+
+    /// Make a project the current one
+    pub async fn switch_project(&self, id: &str) -> Result<Project> {
+        let project = workspace::switch_project(&self.pool, id).await?;
+        self.announce("project", id, "project.switched").await;
+        Ok(project)
+    }
+
+    /// List projects that are not deleted, archived ones included on request
+    pub async fn list_projects(&self, include_archived: bool) -> Result<Vec<Project>> {
+        crud::projects::list(&self.pool, include_archived).await
+    }
+
+    /// Archive a project
+    pub async fn archive_project(&self, id: &str) -> Result<()> {
+        crud::projects::archive(&self.pool, id).await?;
+        self.announce("project", id, "project.archived").await;
+        Ok(())
+    }
+
+    /// Make an archived project active again
+    pub async fn unarchive_project(&self, id: &str) -> Result<()> {
+        crud::projects::unarchive(&self.pool, id).await?;
+        self.announce("project", id, "project.unarchived").await;
+        Ok(())
     }
 
     /// Create note link (placeholder - needs note linking implementation)
@@ -260,6 +634,8 @@ impl EntityManager {
 
     /// Delete a directive
     pub async fn delete_directive(&self, id: &str) -> Result<()> {
-        crud::directives::delete(&self.pool, id).await
+        crud::directives::delete(&self.pool, id).await?;
+        self.announce("directive", id, "directive.deleted").await;
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,352 @@
+// Notes - Notes on entities or the whole project, with attached files
+// An attachment records a file's path relative to the project root together with its
+// SHA-256 and size, so `wsb note show` can tell whether the file is still there and
+// unchanged. Attachments travel with their notes through `wsb export`/`wsb import`,
+// which can also carry the file contents so a snapshot restores them elsewhere.
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Acquire, Row, Sqlite, SqliteConnection, SqlitePool};
+
+use crate::entities::audit::{self, AuditOperation, FieldChange};
+use crate::entities::{actors, events, tags};
+use crate::scrap::dedupe::hash_file;
+
+/// Note types allowed by the notes table
+pub const NOTE_TYPES: [&str; 7] = ["general", "implementation", "testing", "bug", "feature_request", "technical_debt", "decision"];
+
+/// Entity types a note can be attached to, and their tables
+const ENTITY_TABLES: [(&str, &str); 5] = [
+    ("project", "projects"),
+    ("feature", "features"),
+    ("task", "tasks"),
+    ("session", "sessions"),
+    ("directive", "directives"),
+];
+
+pub async fn initialize_note_attachments_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS note_attachments (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            note_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            sha256 TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            attached_at TEXT NOT NULL,
+
+            FOREIGN KEY (note_id) REFERENCES notes (id) ON DELETE CASCADE,
+            UNIQUE (note_id, path)
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_note_attachments_note ON note_attachments (note_id)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A file attached to a note
+#[derive(Debug, Clone, Serialize)]
+pub struct Attachment {
+    pub id: String,
+    pub note_id: String,
+    /// Path relative to the project root, with `/` separators
+    pub path: String,
+    pub sha256: String,
+    pub size_bytes: i64,
+    pub attached_at: String,
+}
+
+/// Whether an attachment still matches the file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentState {
+    Intact,
+    Modified,
+    Missing,
+}
+
+impl AttachmentState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentState::Intact => "intact",
+            AttachmentState::Modified => "modified",
+            AttachmentState::Missing => "missing",
+        }
+    }
+}
+
+impl Attachment {
+    /// Compare the recorded checksum with the file under `root`
+    pub fn verify(&self, root: &Path) -> AttachmentState {
+        let file = root.join(&self.path);
+        if !file.is_file() {
+            return AttachmentState::Missing;
+        }
+        match hash_file(&file) {
+            Ok(sha256) if sha256 == self.sha256 => AttachmentState::Intact,
+            _ => AttachmentState::Modified,
+        }
+    }
+}
+
+/// A stored note and its attachments
+#[derive(Debug, Clone, Serialize)]
+pub struct Note {
+    pub id: String,
+    pub project_id: String,
+    pub entity_id: Option<String>,
+    pub entity_type: Option<String>,
+    pub note_type: String,
+    pub title: String,
+    pub content: String,
+    pub tags: Option<String>,
+    pub author: Option<String>,
+    pub is_project_wide: bool,
+    pub is_pinned: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    pub attachments: Vec<Attachment>,
+}
+
+/// Fields of a note to create; project-wide when `entity` is None
+#[derive(Debug, Clone)]
+pub struct NewNote {
+    pub project_id: String,
+    /// Entity type and ID
+    pub entity: Option<(String, String)>,
+    pub note_type: String,
+    pub title: String,
+    pub content: String,
+    /// Comma-separated tags, linked through the tags table as well
+    pub tags: Option<String>,
+}
+
+/// `file` relative to `root`, which it must be inside of
+pub fn relative_path(root: &Path, file: &Path) -> Result<String> {
+    let root = root.canonicalize().with_context(|| format!("Failed to resolve {}", root.display()))?;
+    let file = root
+        .join(file)
+        .canonicalize()
+        .with_context(|| format!("Attachment not found: {}", file.display()))?;
+    if !file.is_file() {
+        anyhow::bail!("Attachment is not a file: {}", file.display());
+    }
+    let relative = file
+        .strip_prefix(&root)
+        .map_err(|_| anyhow::anyhow!("Attachment {} is outside the project root", file.display()))?;
+    Ok(relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Path, checksum and size of each file, checked before anything is written
+fn describe_files(root: &Path, files: &[PathBuf]) -> Result<Vec<(String, String, i64)>> {
+    let mut described: Vec<(String, String, i64)> = Vec::new();
+    for file in files {
+        let path = relative_path(root, file)?;
+        if described.iter().any(|(existing, _, _)| *existing == path) {
+            continue;
+        }
+        let absolute = root.join(&path);
+        let size = std::fs::metadata(&absolute)?.len() as i64;
+        described.push((path, hash_file(&absolute)?, size));
+    }
+    Ok(described)
+}
+
+fn attachment_from_row(row: &SqliteRow) -> Attachment {
+    Attachment {
+        id: row.get("id"),
+        note_id: row.get("note_id"),
+        path: row.get("path"),
+        sha256: row.get("sha256"),
+        size_bytes: row.get("size_bytes"),
+        attached_at: row.get("attached_at"),
+    }
+}
+
+async fn load_attachments(conn: &mut SqliteConnection, note_id: &str) -> Result<Vec<Attachment>> {
+    let rows = sqlx::query("SELECT * FROM note_attachments WHERE note_id = ? ORDER BY attached_at, path")
+        .bind(note_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(rows.iter().map(attachment_from_row).collect())
+}
+
+/// Create a note with files under `root` attached. Every file must exist inside the
+/// project root; nothing is written otherwise.
+pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(db: A, note: NewNote, root: &Path, files: &[PathBuf]) -> Result<Note> {
+    if !NOTE_TYPES.contains(&note.note_type.as_str()) {
+        anyhow::bail!("Invalid note type '{}' (expected one of: {})", note.note_type, NOTE_TYPES.join(", "));
+    }
+    let described = describe_files(root, files)?;
+    let tag_names = tags::parse_list(&note.tags.iter().cloned().collect::<Vec<_>>())?;
+
+    let mut tx = db.begin().await?;
+    if let Some((entity_type, entity_id)) = &note.entity {
+        let (_, table) = ENTITY_TABLES
+            .iter()
+            .find(|(name, _)| *name == entity_type.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Notes cannot be attached to {} entities", entity_type))?;
+        let found = if *table == "projects" {
+            *entity_id == note.project_id
+        } else {
+            sqlx::query_scalar::<_, String>(&format!("SELECT id FROM {} WHERE id = ? AND project_id = ?", table))
+                .bind(entity_id)
+                .bind(&note.project_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .is_some()
+        };
+        if !found {
+            anyhow::bail!("{} not found: {}", entity_type, entity_id);
+        }
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let actor = actors::current();
+    let id = format!("note-{}", uuid::Uuid::new_v4());
+    sqlx::query(r#"
+        INSERT INTO notes (id, project_id, entity_id, entity_type, note_type, title, content, tags, author, is_project_wide, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#)
+    .bind(&id)
+    .bind(&note.project_id)
+    .bind(note.entity.as_ref().map(|(_, entity_id)| entity_id))
+    .bind(note.entity.as_ref().map(|(entity_type, _)| entity_type))
+    .bind(&note.note_type)
+    .bind(&note.title)
+    .bind(&note.content)
+    .bind(Some(tag_names.join(",")).filter(|joined| !joined.is_empty()))
+    .bind(&actor.name)
+    .bind(note.entity.is_none())
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    for (path, sha256, size) in &described {
+        sqlx::query(r#"
+            INSERT INTO note_attachments (id, project_id, note_id, path, sha256, size_bytes, attached_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(format!("att-{}", uuid::Uuid::new_v4()))
+        .bind(&note.project_id)
+        .bind(&id)
+        .bind(path)
+        .bind(sha256)
+        .bind(size)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if !tag_names.is_empty() {
+        tags::add(&mut *tx, &note.project_id, "note", &id, &tag_names).await?;
+    }
+
+    let created = get_in(&mut *tx, &id).await?.context("Note vanished after insert")?;
+    events::record(&mut tx, &created.project_id, "note", &created.id, "note.created", serde_json::json!({
+        "title": created.title,
+        "entity_id": created.entity_id,
+        "attachments": created.attachments.iter().map(|attachment| &attachment.path).collect::<Vec<_>>(),
+    })).await?;
+    audit::record(&mut tx, &created.project_id, "note", &created.id, AuditOperation::Create, &[
+        FieldChange::snapshot(None, Some(serde_json::to_string(&created)?)),
+    ]).await?;
+    tx.commit().await?;
+
+    Ok(created)
+}
+
+async fn get_in(conn: &mut SqliteConnection, id: &str) -> Result<Option<Note>> {
+    let Some(row) = sqlx::query("SELECT * FROM notes WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(Note {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        entity_id: row.get("entity_id"),
+        entity_type: row.get("entity_type"),
+        note_type: row.get("note_type"),
+        title: row.get("title"),
+        content: row.get("content"),
+        tags: row.get("tags"),
+        author: row.get("author"),
+        is_project_wide: row.get("is_project_wide"),
+        is_pinned: row.get("is_pinned"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        attachments: load_attachments(conn, id).await?,
+    }))
+}
+
+/// Get a note with its attachments
+pub async fn get<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<Option<Note>> {
+    let mut conn = db.acquire().await?;
+    get_in(&mut *conn, id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_note_attachments_are_verified() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let pool = crate::entities::database::initialize_database(&root.join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Notes".to_string(), "Attachments".to_string()).await.unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/design.png"), b"png").unwrap();
+        std::fs::write(root.join("spec.md"), b"# Spec").unwrap();
+
+        let note = NewNote {
+            project_id: project.id.clone(),
+            entity: Some(("project".to_string(), project.id.clone())),
+            note_type: "decision".to_string(),
+            title: "Layout".to_string(),
+            content: "See the mockup".to_string(),
+            tags: None,
+        };
+        let files = [root.join("docs/design.png"), PathBuf::from("spec.md"), PathBuf::from("./docs/design.png")];
+        let created = create(&pool, note.clone(), root, &files).await.unwrap();
+        let paths: Vec<&str> = created.attachments.iter().map(|attachment| attachment.path.as_str()).collect();
+        assert_eq!(paths, ["docs/design.png", "spec.md"]);
+        assert!(created.attachments.iter().all(|attachment| attachment.verify(root) == AttachmentState::Intact));
+
+        std::fs::write(root.join("spec.md"), b"# Spec v2").unwrap();
+        std::fs::remove_file(root.join("docs/design.png")).unwrap();
+        let stored = get(&pool, &created.id).await.unwrap().unwrap();
+        let states: Vec<AttachmentState> = stored.attachments.iter().map(|attachment| attachment.verify(root)).collect();
+        assert_eq!(states, [AttachmentState::Missing, AttachmentState::Modified]);
+
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), b"x").unwrap();
+        let error = create(&pool, note.clone(), root, &[outside.path().join("secret.txt")]).await.unwrap_err();
+        assert!(error.to_string().contains("outside the project root"));
+        let error = create(&pool, NewNote { note_type: "observation".to_string(), ..note }, root, &[]).await.unwrap_err();
+        assert!(error.to_string().contains("Invalid note type"));
+    }
+}
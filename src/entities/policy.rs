@@ -0,0 +1,236 @@
+// WIP Policy - Work-in-progress limits for features and tasks
+// Checked on state changes (warn, or block when strict) and reported by `status`
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use super::crud;
+use super::schema_models::{Feature, FeatureState, Task, TaskStatus};
+
+/// Assignee bucket for tasks with no person or session assigned
+pub const UNASSIGNED: &str = "unassigned";
+
+/// WIP limits stored under `tools.policy` in `.wsb/state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WipPolicy {
+    /// Maximum features being implemented at once (no tests or failing tests)
+    pub max_features_in_progress: Option<usize>,
+    /// Maximum in-progress tasks per assignee (person or session ID)
+    pub max_tasks_in_progress_per_assignee: Option<usize>,
+    /// Block violating state changes instead of warning
+    pub strict: bool,
+}
+
+impl WipPolicy {
+    pub fn has_limits(&self) -> bool {
+        self.max_features_in_progress.is_some() || self.max_tasks_in_progress_per_assignee.is_some()
+    }
+}
+
+/// Current usage of one limit
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LimitUsage {
+    pub scope: String,
+    pub current: usize,
+    pub limit: usize,
+}
+
+impl LimitUsage {
+    pub fn is_over(&self) -> bool {
+        self.current > self.limit
+    }
+}
+
+/// Utilization of all configured limits
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WipUtilization {
+    pub features: Option<LimitUsage>,
+    pub tasks: Vec<LimitUsage>,
+}
+
+/// Whether a feature state counts towards the in-progress limit
+pub fn feature_in_progress(state: &FeatureState) -> bool {
+    matches!(state, FeatureState::ImplementedNoTests | FeatureState::ImplementedFailingTests)
+}
+
+fn task_assignee(task: &Task) -> &str {
+    task.assigned.as_deref().filter(|a| !a.trim().is_empty()).unwrap_or(UNASSIGNED)
+}
+
+fn features_in_progress<'a>(features: &'a [Feature]) -> impl Iterator<Item = &'a Feature> {
+    features
+        .iter()
+        .filter(|f| FeatureState::from_str(&f.state).map(|s| feature_in_progress(&s)).unwrap_or(false))
+}
+
+/// Violation message if moving `feature_id` to `new_state` would exceed the feature limit
+pub fn feature_violation(policy: &WipPolicy, features: &[Feature], feature_id: &str, new_state: &FeatureState) -> Option<String> {
+    let limit = policy.max_features_in_progress?;
+    if !feature_in_progress(new_state) {
+        return None;
+    }
+
+    let others = features_in_progress(features).filter(|f| f.id != feature_id).count();
+    if others + 1 > limit {
+        Some(format!(
+            "WIP limit exceeded: {} features would be in progress (limit {})",
+            others + 1,
+            limit
+        ))
+    } else {
+        None
+    }
+}
+
+/// Violation message if moving `task` to `new_status` would exceed its assignee's task limit
+pub fn task_violation(policy: &WipPolicy, tasks: &[Task], task: &Task, new_status: &TaskStatus) -> Option<String> {
+    let limit = policy.max_tasks_in_progress_per_assignee?;
+    if *new_status != TaskStatus::InProgress {
+        return None;
+    }
+
+    let assignee = task_assignee(task);
+    let others = tasks
+        .iter()
+        .filter(|t| t.id != task.id && t.status == TaskStatus::InProgress.as_str() && task_assignee(t) == assignee)
+        .count();
+
+    if others + 1 > limit {
+        Some(format!(
+            "WIP limit exceeded: {} would have {} tasks in progress (limit {})",
+            assignee,
+            others + 1,
+            limit
+        ))
+    } else {
+        None
+    }
+}
+
+/// Current utilization against the configured limits
+pub fn utilization(policy: &WipPolicy, features: &[Feature], tasks: &[Task]) -> WipUtilization {
+    let features_usage = policy.max_features_in_progress.map(|limit| LimitUsage {
+        scope: "features".to_string(),
+        current: features_in_progress(features).count(),
+        limit,
+    });
+
+    let mut tasks_usage = Vec::new();
+    if let Some(limit) = policy.max_tasks_in_progress_per_assignee {
+        let mut per_assignee: BTreeMap<&str, usize> = BTreeMap::new();
+        for task in tasks.iter().filter(|t| t.status == TaskStatus::InProgress.as_str()) {
+            *per_assignee.entry(task_assignee(task)).or_default() += 1;
+        }
+        tasks_usage = per_assignee
+            .into_iter()
+            .map(|(assignee, current)| LimitUsage { scope: assignee.to_string(), current, limit })
+            .collect();
+    }
+
+    WipUtilization { features: features_usage, tasks: tasks_usage }
+}
+
+/// Check a feature state change against the policy
+pub async fn check_feature_transition(pool: &SqlitePool, policy: &WipPolicy, feature_id: &str, new_state: &FeatureState) -> Result<Option<String>> {
+    if policy.max_features_in_progress.is_none() {
+        return Ok(None);
+    }
+
+    let feature = match crud::features::get_by_id(pool, feature_id).await? {
+        Some(feature) => feature,
+        None => return Ok(None),
+    };
+    let features = crud::features::list_by_project(pool, &feature.project_id).await?;
+    Ok(feature_violation(policy, &features, feature_id, new_state))
+}
+
+/// Check a task status change against the policy
+pub async fn check_task_transition(pool: &SqlitePool, policy: &WipPolicy, task_id: &str, new_status: &TaskStatus) -> Result<Option<String>> {
+    if policy.max_tasks_in_progress_per_assignee.is_none() {
+        return Ok(None);
+    }
+
+    let task = match crud::tasks::get_by_id(pool, task_id).await? {
+        Some(task) => task,
+        None => return Ok(None),
+    };
+    let tasks = crud::tasks::list_by_project(pool, &task.project_id, Some(TaskStatus::InProgress)).await?;
+    Ok(task_violation(policy, &tasks, &task, new_status))
+}
+
+/// Load utilization for a project
+pub async fn load_utilization(pool: &SqlitePool, policy: &WipPolicy, project_id: &str) -> Result<WipUtilization> {
+    let features = crud::features::list_by_project(pool, project_id).await?;
+    let tasks = crud::tasks::list_by_project(pool, project_id, Some(TaskStatus::InProgress)).await?;
+    Ok(utilization(policy, &features, &tasks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, state: FeatureState) -> Feature {
+        let mut feature = Feature::new(id.to_string(), "P001".to_string(), id.to_string(), id.to_string(), "desc".to_string(), None).unwrap();
+        feature.state = state.as_str().to_string();
+        feature
+    }
+
+    fn task(id: &str, assigned: Option<&str>, status: TaskStatus) -> Task {
+        let mut task = Task::new(id.to_string(), "P001".to_string(), "F00001".to_string(), "work".to_string(), "feature".to_string()).unwrap();
+        task.assigned = assigned.map(String::from);
+        task.status = status.as_str().to_string();
+        task
+    }
+
+    fn policy() -> WipPolicy {
+        WipPolicy { max_features_in_progress: Some(2), max_tasks_in_progress_per_assignee: Some(1), strict: false }
+    }
+
+    #[test]
+    fn test_feature_limit() {
+        let features = vec![
+            feature("F00001", FeatureState::ImplementedNoTests),
+            feature("F00002", FeatureState::ImplementedFailingTests),
+            feature("F00003", FeatureState::NotImplemented),
+        ];
+
+        assert!(feature_violation(&policy(), &features, "F00003", &FeatureState::ImplementedNoTests).is_some());
+        // Re-entering an in-progress state does not count the feature twice
+        assert!(feature_violation(&policy(), &features, "F00001", &FeatureState::ImplementedFailingTests).is_none());
+        assert!(feature_violation(&policy(), &features, "F00003", &FeatureState::ImplementedPassingTests).is_none());
+        assert!(feature_violation(&WipPolicy::default(), &features, "F00003", &FeatureState::ImplementedNoTests).is_none());
+    }
+
+    #[test]
+    fn test_task_limit_per_assignee() {
+        let tasks = vec![
+            task("T000001", Some("alice"), TaskStatus::InProgress),
+            task("T000002", Some("bob"), TaskStatus::Pending),
+            task("T000003", Some("alice"), TaskStatus::Pending),
+        ];
+
+        assert!(task_violation(&policy(), &tasks, &tasks[2], &TaskStatus::InProgress).is_some());
+        assert!(task_violation(&policy(), &tasks, &tasks[1], &TaskStatus::InProgress).is_none());
+        assert!(task_violation(&policy(), &tasks, &tasks[2], &TaskStatus::Blocked).is_none());
+    }
+
+    #[test]
+    fn test_utilization() {
+        let features = vec![feature("F00001", FeatureState::ImplementedNoTests)];
+        let tasks = vec![
+            task("T000001", Some("alice"), TaskStatus::InProgress),
+            task("T000002", Some("alice"), TaskStatus::InProgress),
+            task("T000003", None, TaskStatus::InProgress),
+        ];
+
+        let usage = utilization(&policy(), &features, &tasks);
+        assert_eq!(usage.features.as_ref().map(|u| u.current), Some(1));
+        assert_eq!(usage.tasks.len(), 2);
+        assert_eq!(usage.tasks[0].scope, "alice");
+        assert!(usage.tasks[0].is_over());
+        assert_eq!(usage.tasks[1].scope, UNASSIGNED);
+    }
+}
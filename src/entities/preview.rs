@@ -0,0 +1,339 @@
+// Mutation previews - report what a create/update/link would change without writing.
+// Used by the MCP tools' `dry_run` mode so an agent can confirm intent before committing.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{crud, ids::IdGenerator, relationships::validate_relationship, EntityManager, EntityType, Feature, FeatureState, TaskPriority, TaskStatus};
+
+/// One field that would change
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// What a mutation would do. Nothing has been written when this is returned.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangePreview {
+    pub operation: String,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub changes: Vec<FieldChange>,
+    /// Side effects beyond the entity itself (events recorded, dependent rows touched)
+    pub cascades: Vec<String>,
+    /// Problems that would make the mutation fail
+    pub errors: Vec<String>,
+    /// Problems that would not block the mutation
+    pub warnings: Vec<String>,
+}
+
+impl ChangePreview {
+    fn new(operation: &str, entity_type: &str, entity_id: Option<String>) -> Self {
+        Self {
+            operation: operation.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id,
+            changes: Vec::new(),
+            cascades: Vec::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Record a field change; unchanged values are skipped
+    fn set(&mut self, field: &str, from: Option<String>, to: Option<String>) {
+        if from != to {
+            self.changes.push(FieldChange { field: field.to_string(), from, to });
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Human-readable summary for tool output
+    pub fn render(&self) -> String {
+        let target = match &self.entity_id {
+            Some(id) => format!("{} {}", self.entity_type, id),
+            None => self.entity_type.clone(),
+        };
+        let mut out = format!("DRY RUN: {} {} (no changes written)\n", self.operation, target);
+
+        if self.changes.is_empty() && self.is_valid() {
+            out.push_str("No changes: values already match\n");
+        }
+        for change in &self.changes {
+            out.push_str(&format!(
+                "  {}: {} -> {}\n",
+                change.field,
+                change.from.as_deref().unwrap_or("(none)"),
+                change.to.as_deref().unwrap_or("(none)")
+            ));
+        }
+        for cascade in &self.cascades {
+            out.push_str(&format!("  also: {}\n", cascade));
+        }
+        for warning in &self.warnings {
+            out.push_str(&format!("  warning: {}\n", warning));
+        }
+        for error in &self.errors {
+            out.push_str(&format!("  error: {}\n", error));
+        }
+        out.push_str(if self.is_valid() { "Validation: OK\n" } else { "Validation: FAILED\n" });
+        out
+    }
+}
+
+/// Parse a feature state given either as its emoji or its name
+pub fn parse_feature_state(input: &str) -> Option<FeatureState> {
+    use FeatureState::*;
+    [NotImplemented, ImplementedNoTests, ImplementedFailingTests, ImplementedPassingTests, TestsBroken, CriticalIssue]
+        .into_iter()
+        .find(|state| state.emoji() == input || state.as_str() == input)
+}
+
+pub async fn create_feature(
+    manager: &EntityManager,
+    name: &str,
+    description: &str,
+    category: Option<&str>,
+) -> Result<ChangePreview> {
+    let project = manager.get_current_project().await?;
+    let next_id = IdGenerator::peek(&manager.pool, project.as_ref().map_or("", |project| project.id.as_str()), "feature").await?;
+    let mut preview = ChangePreview::new("create", EntityType::Feature.as_str(), Some(next_id.clone()));
+
+    let project = match project {
+        Some(project) => project,
+        None => {
+            preview.errors.push("No active project".to_string());
+            return Ok(preview);
+        }
+    };
+
+    match Feature::new(next_id.clone(), project.id.clone(), next_id, name.to_string(), description.to_string(), category.map(String::from)) {
+        Ok(feature) => {
+            preview.set("name", None, Some(feature.name));
+            preview.set("description", None, Some(feature.description));
+            preview.set("category", None, feature.category);
+            preview.set("state", None, Some(feature.state));
+            preview.set("project_id", None, Some(feature.project_id));
+        }
+        Err(e) => preview.errors.push(e),
+    }
+
+    if manager.list_features_by_project(&project.id).await?.iter().any(|f| f.name == name) {
+        preview.warnings.push(format!("A feature named '{}' already exists", name));
+    }
+    preview.cascades.push("event feature.created recorded".to_string());
+    Ok(preview)
+}
+
+pub async fn update_feature_state(manager: &EntityManager, id: &str, state: Option<&str>, notes: Option<&str>) -> Result<ChangePreview> {
+    let mut preview = ChangePreview::new("update", EntityType::Feature.as_str(), Some(id.to_string()));
+
+    let feature = match manager.get_feature(id).await? {
+        Some(feature) => feature,
+        None => {
+            preview.errors.push(format!("Feature not found: {}", id));
+            return Ok(preview);
+        }
+    };
+
+    if let Some(input) = state {
+        match parse_feature_state(input) {
+            Some(new_state) => {
+                if let Ok(current) = FeatureState::from_str(&feature.state) {
+                    if !current.can_transition_to(&new_state) {
+                        preview.warnings.push(format!("Unusual transition {} -> {}", current.emoji(), new_state.emoji()));
+                    }
+                }
+                if feature.state != new_state.as_str() {
+                    preview.cascades.push("event feature.state_changed recorded".to_string());
+                }
+                preview.set("state", Some(feature.state.clone()), Some(new_state.as_str().to_string()));
+            }
+            None => preview.errors.push(format!("Invalid feature state: {}", input)),
+        }
+    }
+    if let Some(notes) = notes {
+        preview.set("notes", feature.notes.clone(), Some(notes.to_string()));
+    }
+    Ok(preview)
+}
+
+pub async fn create_task(
+    manager: &EntityManager,
+    title: &str,
+    feature_id: Option<&str>,
+    priority: Option<&str>,
+) -> Result<ChangePreview> {
+    let project = manager.get_current_project().await?;
+    let next_id = IdGenerator::peek(&manager.pool, project.as_ref().map_or("", |project| project.id.as_str()), "task").await?;
+    let mut preview = ChangePreview::new("create", EntityType::Task.as_str(), Some(next_id));
+
+    let project = match project {
+        Some(project) => project,
+        None => {
+            preview.errors.push("No active project".to_string());
+            return Ok(preview);
+        }
+    };
+
+    if let Some(feature_id) = feature_id {
+        if manager.get_feature(feature_id).await?.is_none() {
+            preview.errors.push(format!("Feature not found: {}", feature_id));
+        }
+    }
+    if let Some(priority) = priority {
+        if let Err(e) = TaskPriority::from_str(priority) {
+            preview.errors.push(e);
+        }
+    }
+
+    if title.trim().is_empty() {
+        preview.errors.push("Task description cannot be empty".to_string());
+    }
+
+    preview.set("task", None, Some(title.to_string()));
+    preview.set("feature_id", None, feature_id.map(String::from));
+    preview.set("status", None, Some(TaskStatus::Pending.as_str().to_string()));
+    preview.set("priority", None, Some(priority.unwrap_or(TaskPriority::Medium.as_str()).to_string()));
+    preview.set("project_id", None, Some(project.id));
+
+    preview.cascades.push("event task.created recorded".to_string());
+    Ok(preview)
+}
+
+pub async fn update_task_status(manager: &EntityManager, id: &str, status: &str, notes: Option<&str>) -> Result<ChangePreview> {
+    let mut preview = ChangePreview::new("update", EntityType::Task.as_str(), Some(id.to_string()));
+
+    let task = match manager.get_task(id).await? {
+        Some(task) => task,
+        None => {
+            preview.errors.push(format!("Task not found: {}", id));
+            return Ok(preview);
+        }
+    };
+
+    match TaskStatus::from_str(status) {
+        Ok(new_status) => {
+            if let Ok(current) = TaskStatus::from_str(&task.status) {
+                if !current.can_transition_to(&new_status) {
+                    preview.errors.push(format!("Invalid status transition {} -> {}", current.as_str(), new_status.as_str()));
+                }
+            }
+            if task.status != new_status.as_str() {
+                preview.cascades.push("event task.status_changed recorded".to_string());
+            }
+            preview.set("status", Some(task.status.clone()), Some(new_status.as_str().to_string()));
+        }
+        Err(e) => preview.errors.push(e),
+    }
+    if let Some(notes) = notes {
+        preview.set("notes", task.notes.clone(), Some(notes.to_string()));
+    }
+    Ok(preview)
+}
+
+async fn entity_exists(manager: &EntityManager, entity_type: &EntityType, id: &str) -> Result<bool> {
+    Ok(match entity_type {
+        EntityType::Project => manager.get_project(id).await?.is_some(),
+        EntityType::Feature => manager.get_feature(id).await?.is_some(),
+        EntityType::Task => manager.get_task(id).await?.is_some(),
+        EntityType::Session => manager.get_session(id).await?.is_some(),
+        EntityType::Directive => manager.get_directive(id).await?.is_some(),
+    })
+}
+
+pub async fn link_entities(
+    manager: &EntityManager,
+    from_id: &str,
+    from_type: &str,
+    to_id: &str,
+    to_type: &str,
+    kind: &str,
+) -> Result<ChangePreview> {
+    let mut preview = ChangePreview::new("link", "relationship", None);
+
+    let types = EntityType::from_str(from_type).and_then(|from| EntityType::from_str(to_type).map(|to| (from, to)));
+    let (from, to) = match types.and_then(|(from, to)| validate_relationship(&from, &to, kind).map(|_| (from, to))) {
+        Ok(types) => types,
+        Err(e) => {
+            preview.errors.push(e);
+            return Ok(preview);
+        }
+    };
+
+    for (entity_type, id) in [(&from, from_id), (&to, to_id)] {
+        if !entity_exists(manager, entity_type, id).await? {
+            preview.errors.push(format!("{} not found: {}", entity_type.as_str(), id));
+        }
+    }
+
+    let existing = crud::dependencies::list_for_entity(&manager.pool, from_id, from.as_str()).await?;
+    if existing.iter().any(|d| d.to_entity_id == to_id && d.to_entity_type == to.as_str() && d.dependency_type == kind) {
+        preview.warnings.push(format!("{} already {} {}", from_id, kind, to_id));
+    }
+
+    preview.set("from", None, Some(format!("{} {}", from.as_str(), from_id)));
+    preview.set("to", None, Some(format!("{} {}", to.as_str(), to_id)));
+    preview.set("relationship_type", None, Some(kind.to_string()));
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::database::initialize_database;
+
+    async fn manager() -> (tempfile::TempDir, EntityManager) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let pool = initialize_database(&temp_dir.path().join("test.db")).await.unwrap();
+        (temp_dir, EntityManager::new(pool))
+    }
+
+    #[test]
+    fn test_parse_feature_state() {
+        assert_eq!(parse_feature_state("🟢"), Some(FeatureState::ImplementedPassingTests));
+        assert_eq!(parse_feature_state("tests_broken"), Some(FeatureState::TestsBroken));
+        assert_eq!(parse_feature_state("done"), None);
+    }
+
+    #[tokio::test]
+    async fn test_update_preview_writes_nothing() {
+        let (_temp_dir, manager) = manager().await;
+        let project = manager.create_project("Preview".to_string(), "Preview project".to_string()).await.unwrap();
+        let feature = manager
+            .create_feature_full(project.id.clone(), "Login".to_string(), "User login".to_string(), None)
+            .await
+            .unwrap();
+
+        let preview = update_feature_state(&manager, &feature.id, Some("🟠"), None).await.unwrap();
+        assert!(preview.is_valid());
+        assert_eq!(preview.changes, vec![FieldChange {
+            field: "state".to_string(),
+            from: Some("not_implemented".to_string()),
+            to: Some("implemented_no_tests".to_string()),
+        }]);
+        assert_eq!(preview.cascades, vec!["event feature.state_changed recorded".to_string()]);
+        assert_eq!(manager.get_feature(&feature.id).await.unwrap().unwrap().state, "not_implemented");
+    }
+
+    #[tokio::test]
+    async fn test_previews_report_validation_errors() {
+        let (_temp_dir, manager) = manager().await;
+        manager.create_project("Preview".to_string(), "Preview project".to_string()).await.unwrap();
+
+        let missing = update_task_status(&manager, "T999999", "completed", None).await.unwrap();
+        assert_eq!(missing.errors, vec!["Task not found: T999999".to_string()]);
+
+        let create = create_task(&manager, "Write docs", Some("F99999"), Some("urgent")).await.unwrap();
+        assert_eq!(create.errors.len(), 2);
+        assert!(manager.list_tasks().await.unwrap().is_empty());
+
+        let link = link_entities(&manager, "F00001", "feature", "T000001", "task", "not_a_kind").await.unwrap();
+        assert!(!link.is_valid());
+    }
+}
@@ -0,0 +1,431 @@
+// Recurring Tasks - Repeat rules and the task series they produce
+// A task with a due date and a repeat rule is the template of a series. Completing the
+// template, or any instance of it, creates the next instance: a copy of the task due on
+// the next date the rule allows. Instances point at their template through
+// `recurrence_parent`; the rule itself stays on the template, so clearing it there ends
+// the series. Rules are `daily`, `weekly`, `monthly`, `yearly`, `every N days|weeks|
+// months|years`, or `cron DOM MONTH WEEKDAY` with cron's date fields.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::Serialize;
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+
+use super::actors::ensure_column;
+use super::calendar::parse_date;
+use super::crud;
+use super::events;
+use super::schema_models::Task;
+
+/// Longest stretch searched for a cron date: covers a 29 February rule
+const CRON_SEARCH_DAYS: i64 = 366 * 8;
+
+const MONTH_NAMES: &[&str] = &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+const RRULE_WEEKDAYS: &[&str] = &["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+/// Link instances of a recurring task to their template
+pub async fn initialize_recurrence_columns(pool: &SqlitePool) -> Result<()> {
+    ensure_column(pool, "tasks", "recurrence_parent", "TEXT").await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// A parsed repeat rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recurrence {
+    /// Every `interval` units after the series start
+    Every { unit: Unit, interval: u32 },
+    /// Every day matching all three fields; `None` is cron's `*`
+    Cron {
+        days: Option<BTreeSet<u32>>,
+        months: Option<BTreeSet<u32>>,
+        weekdays: Option<BTreeSet<u32>>,
+        fields: [String; 3],
+    },
+}
+
+impl Recurrence {
+    pub fn parse(rule: &str) -> Result<Self> {
+        let rule = rule.trim().to_lowercase();
+        let words: Vec<&str> = rule.split_whitespace().collect();
+        let every = |unit, interval| Ok(Recurrence::Every { unit, interval });
+        match words.as_slice() {
+            ["daily"] => every(Unit::Day, 1),
+            ["weekly"] => every(Unit::Week, 1),
+            ["monthly"] => every(Unit::Month, 1),
+            ["yearly"] => every(Unit::Year, 1),
+            ["every", count, unit] => {
+                let interval: u32 = count.parse().ok().filter(|n| *n > 0)
+                    .with_context(|| format!("Invalid interval '{}' in repeat rule", count))?;
+                let unit = match unit.trim_end_matches('s') {
+                    "day" => Unit::Day,
+                    "week" => Unit::Week,
+                    "month" => Unit::Month,
+                    "year" => Unit::Year,
+                    other => anyhow::bail!("Invalid unit '{}' in repeat rule (expected days, weeks, months or years)", other),
+                };
+                every(unit, interval)
+            }
+            ["cron", days, months, weekdays] => Ok(Recurrence::Cron {
+                days: parse_field(days, 1, 31, &[]).context("Invalid day-of-month field")?,
+                months: parse_field(months, 1, 12, MONTH_NAMES).context("Invalid month field")?,
+                weekdays: parse_field(weekdays, 0, 7, WEEKDAY_NAMES)
+                    .context("Invalid weekday field")?
+                    .map(|set| set.into_iter().map(|d| d % 7).collect()),
+                fields: [days.to_string(), months.to_string(), weekdays.to_string()],
+            }),
+            _ => anyhow::bail!(
+                "Invalid repeat rule '{}' (expected daily, weekly, monthly, yearly, 'every N days|weeks|months|years' or 'cron DOM MONTH WEEKDAY')",
+                rule
+            ),
+        }
+    }
+
+    /// First date on or after `from` in the series starting at `start`
+    pub fn next_on_or_after(&self, start: NaiveDate, from: NaiveDate) -> Option<NaiveDate> {
+        if from <= start && self.matches(start) {
+            return Some(start);
+        }
+        let from = from.max(start);
+        match self {
+            Recurrence::Every { unit: unit @ (Unit::Day | Unit::Week), interval } => {
+                let step = *interval as i64 * if *unit == Unit::Week { 7 } else { 1 };
+                let behind = (from - start).num_days();
+                let steps = (behind + step - 1) / step;
+                start.checked_add_signed(Duration::days(steps * step))
+            }
+            Recurrence::Every { unit, interval } => {
+                let months = interval * if *unit == Unit::Year { 12 } else { 1 };
+                (0u32..)
+                    .map(|n| n.checked_mul(months).and_then(|m| start.checked_add_months(Months::new(m))))
+                    .take_while(|date| date.is_some())
+                    .flatten()
+                    .find(|date| *date >= from)
+            }
+            Recurrence::Cron { .. } => (0..CRON_SEARCH_DAYS)
+                .map_while(|n| from.checked_add_signed(Duration::days(n)))
+                .find(|date| self.matches(*date)),
+        }
+    }
+
+    /// Whether a cron rule allows `date`; interval rules allow any start date
+    fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Every { .. } => true,
+            Recurrence::Cron { days, months, weekdays, .. } => {
+                let allows = |set: &Option<BTreeSet<u32>>, value: u32| set.as_ref().map_or(true, |set| set.contains(&value));
+                allows(days, date.day())
+                    && allows(months, date.month())
+                    && allows(weekdays, date.weekday().num_days_from_sunday())
+            }
+        }
+    }
+
+    /// The rule as an RFC 5545 RRULE value, without UNTIL
+    pub fn rrule(&self) -> String {
+        let join = |set: &BTreeSet<u32>, name: &dyn Fn(u32) -> String| set.iter().map(|v| name(*v)).collect::<Vec<_>>().join(",");
+        match self {
+            Recurrence::Every { unit, interval } => {
+                let freq = match unit {
+                    Unit::Day => "DAILY",
+                    Unit::Week => "WEEKLY",
+                    Unit::Month => "MONTHLY",
+                    Unit::Year => "YEARLY",
+                };
+                if *interval > 1 {
+                    format!("FREQ={};INTERVAL={}", freq, interval)
+                } else {
+                    format!("FREQ={}", freq)
+                }
+            }
+            Recurrence::Cron { days, months, weekdays, .. } => {
+                let mut rule = "FREQ=DAILY".to_string();
+                if let Some(months) = months {
+                    rule.push_str(&format!(";BYMONTH={}", join(months, &|m: u32| m.to_string())));
+                }
+                if let Some(days) = days {
+                    rule.push_str(&format!(";BYMONTHDAY={}", join(days, &|d: u32| d.to_string())));
+                }
+                if let Some(weekdays) = weekdays {
+                    rule.push_str(&format!(";BYDAY={}", join(weekdays, &|d: u32| RRULE_WEEKDAYS[d as usize].to_string())));
+                }
+                rule
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Every { unit, interval: 1 } => f.write_str(match unit {
+                Unit::Day => "daily",
+                Unit::Week => "weekly",
+                Unit::Month => "monthly",
+                Unit::Year => "yearly",
+            }),
+            Recurrence::Every { unit, interval } => {
+                let unit = match unit {
+                    Unit::Day => "days",
+                    Unit::Week => "weeks",
+                    Unit::Month => "months",
+                    Unit::Year => "years",
+                };
+                write!(f, "every {} {}", interval, unit)
+            }
+            Recurrence::Cron { fields, .. } => write!(f, "cron {}", fields.join(" ")),
+        }
+    }
+}
+
+/// One cron field: `*`, or a comma list of values, `a-b` ranges and `/step`s.
+/// `names` map to values starting at `min`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Option<BTreeSet<u32>>> {
+    if field == "*" {
+        return Ok(None);
+    }
+    let value = |text: &str| -> Result<u32> {
+        let value = match names.iter().position(|name| *name == text) {
+            Some(index) => index as u32 + min,
+            None => text.parse().with_context(|| format!("'{}' is not a number", text))?,
+        };
+        if value < min || value > max {
+            anyhow::bail!("{} is outside {}-{}", value, min, max);
+        }
+        Ok(value)
+    };
+
+    let mut values = BTreeSet::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).with_context(|| format!("Invalid step in '{}'", item))?),
+            None => (item, 1),
+        };
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((first, last)) => (value(first)?, value(last)?),
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if first > last {
+            anyhow::bail!("Empty range '{}'", item);
+        }
+        values.extend((first..=last).step_by(step as usize));
+    }
+    Ok(Some(values))
+}
+
+/// A task of a recurring series as listed by `wsb task list --recurring`
+#[derive(Debug, Clone, Serialize)]
+pub struct Occurrence {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub due_date: Option<String>,
+}
+
+/// A template with its rule and the instances created from it, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct Series {
+    pub template: Occurrence,
+    pub rule: String,
+    pub instances: Vec<Occurrence>,
+}
+
+/// Create the next instance of the series `id` belongs to, after `id` was completed.
+/// Nothing is created when the series has no rule or due date, or already has another
+/// open occurrence. The next due date is the first one after the completed task's and
+/// no earlier than today, so finishing late does not leave a backlog of overdue copies.
+pub async fn materialize_next(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<Option<Task>> {
+    let Some(row) = sqlx::query(
+        "SELECT project_id, feature_ids, title, category, priority, due_date, recurrence_parent FROM tasks WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let template_id = row.get::<Option<String>, _>("recurrence_parent").unwrap_or_else(|| id.to_string());
+    let template = sqlx::query("SELECT due_date, repeat FROM tasks WHERE id = ? AND deleted_at IS NULL")
+        .bind(&template_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+    let (Some(template), Some(due)) = (template, row.get::<Option<String>, _>("due_date")) else {
+        return Ok(None);
+    };
+    let (Some(start), Some(rule)) = (template.get::<Option<String>, _>("due_date"), template.get::<Option<String>, _>("repeat")) else {
+        return Ok(None);
+    };
+
+    let open: i64 = sqlx::query_scalar(r#"
+        SELECT COUNT(*) FROM tasks
+        WHERE (id = ? OR recurrence_parent = ?) AND id != ? AND deleted_at IS NULL AND status NOT IN ('completed', 'cancelled')
+    "#)
+    .bind(&template_id)
+    .bind(&template_id)
+    .bind(id)
+    .fetch_one(&mut **tx)
+    .await?;
+    if open > 0 {
+        return Ok(None);
+    }
+
+    let rule = Recurrence::parse(&rule).with_context(|| format!("Task {} has an invalid repeat rule", template_id))?;
+    let after = (parse_date(&due)? + Duration::days(1)).max(chrono::Local::now().date_naive());
+    let Some(next_due) = rule.next_on_or_after(parse_date(&start)?, after) else {
+        return Ok(None);
+    };
+
+    let project_id: String = row.get("project_id");
+    let mut task = crud::tasks::create(
+        &mut **tx,
+        project_id.clone(),
+        row.get("feature_ids"),
+        row.get("title"),
+        row.get("category"),
+    )
+    .await?;
+    let next_due = next_due.format("%Y-%m-%d").to_string();
+    task.priority = row.get("priority");
+    sqlx::query("UPDATE tasks SET priority = ?, due_date = ?, recurrence_parent = ? WHERE id = ?")
+        .bind(&task.priority)
+        .bind(&next_due)
+        .bind(&template_id)
+        .bind(&task.id)
+        .execute(&mut **tx)
+        .await?;
+
+    events::record(tx, &project_id, "task", &task.id, "task.recurred", serde_json::json!({
+        "template": template_id,
+        "previous": id,
+        "due_date": next_due,
+    })).await?;
+    Ok(Some(task))
+}
+
+/// Every recurring template of the project with its instances
+pub async fn list_series(pool: &SqlitePool, project_id: &str) -> Result<Vec<Series>> {
+    let occurrence = |row: &sqlx::sqlite::SqliteRow| Occurrence {
+        id: row.get("id"),
+        title: row.get("title"),
+        status: row.get("status"),
+        due_date: row.get("due_date"),
+    };
+
+    let templates = sqlx::query(r#"
+        SELECT id, title, status, due_date, repeat FROM tasks
+        WHERE project_id = ? AND repeat IS NOT NULL AND recurrence_parent IS NULL AND deleted_at IS NULL
+        ORDER BY id
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut series = Vec::new();
+    for row in &templates {
+        let template = occurrence(row);
+        let instances = sqlx::query(r#"
+            SELECT id, title, status, due_date FROM tasks
+            WHERE recurrence_parent = ? AND deleted_at IS NULL
+            ORDER BY due_date, id
+        "#)
+        .bind(&template.id)
+        .fetch_all(pool)
+        .await?;
+        series.push(Series {
+            rule: row.get("repeat"),
+            instances: instances.iter().map(occurrence).collect(),
+            template,
+        });
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::schema_models::TaskStatus;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        assert_eq!(Recurrence::parse("Weekly").unwrap(), Recurrence::Every { unit: Unit::Week, interval: 1 });
+        assert_eq!(Recurrence::parse("every 2 weeks").unwrap().to_string(), "every 2 weeks");
+        assert_eq!(Recurrence::parse("every 1 day").unwrap().to_string(), "daily");
+        assert_eq!(Recurrence::parse("cron 1,15 * *").unwrap().rrule(), "FREQ=DAILY;BYMONTHDAY=1,15");
+        assert_eq!(Recurrence::parse("cron * jan-mar mon-fri/2").unwrap().rrule(), "FREQ=DAILY;BYMONTH=1,2,3;BYDAY=MO,WE,FR");
+        assert_eq!(Recurrence::parse("cron * * 7").unwrap().rrule(), "FREQ=DAILY;BYDAY=SU");
+        assert_eq!(Recurrence::parse("every 3 months").unwrap().rrule(), "FREQ=MONTHLY;INTERVAL=3");
+        for invalid in ["hourly", "every 0 days", "every 2 fortnights", "cron 32 * *", "cron * * *x", "cron 5-1 * *"] {
+            assert!(Recurrence::parse(invalid).is_err(), "{} should be rejected", invalid);
+        }
+    }
+
+    #[test]
+    fn test_next_on_or_after() {
+        let fortnightly = Recurrence::parse("every 2 weeks").unwrap();
+        assert_eq!(fortnightly.next_on_or_after(date("2026-03-02"), date("2026-03-03")), Some(date("2026-03-16")));
+        assert_eq!(fortnightly.next_on_or_after(date("2026-03-02"), date("2026-01-01")), Some(date("2026-03-02")));
+
+        let monthly = Recurrence::parse("monthly").unwrap();
+        assert_eq!(monthly.next_on_or_after(date("2026-01-31"), date("2026-02-01")), Some(date("2026-02-28")));
+
+        let weekdays = Recurrence::parse("cron * * mon-fri").unwrap();
+        // 2026-03-06 is a Friday
+        assert_eq!(weekdays.next_on_or_after(date("2026-03-06"), date("2026-03-07")), Some(date("2026-03-09")));
+        let leap = Recurrence::parse("cron 29 feb *").unwrap();
+        assert_eq!(leap.next_on_or_after(date("2026-01-01"), date("2026-01-01")), Some(date("2028-02-29")));
+        assert_eq!(Recurrence::parse("cron 31 feb *").unwrap().next_on_or_after(date("2026-01-01"), date("2026-01-01")), None);
+    }
+
+    #[tokio::test]
+    async fn test_completing_an_occurrence_creates_the_next() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Chores".to_string(), "Recurring work".to_string()).await.unwrap();
+        let template = crud::tasks::create(&pool, project.id.clone(), "F00001".to_string(), "Rotate logs".to_string(), "infrastructure".to_string()).await.unwrap();
+        let today = chrono::Local::now().date_naive();
+        crate::entities::calendar::schedule_task(&pool, &template.id, Some(today), Some("daily"), false).await.unwrap();
+
+        crud::tasks::update_status(&pool, &template.id, TaskStatus::Completed).await.unwrap();
+        let series = list_series(&pool, &project.id).await.unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].rule, "daily");
+        assert_eq!(series[0].instances.len(), 1);
+        let first = series[0].instances[0].clone();
+        assert_eq!(first.title, "Rotate logs");
+        assert_eq!(first.status, "pending");
+        assert_eq!(first.due_date, Some((today + Duration::days(1)).format("%Y-%m-%d").to_string()));
+
+        // Completing again, or an instance while another is open, adds nothing
+        crud::tasks::update_status(&pool, &template.id, TaskStatus::Completed).await.unwrap();
+        assert_eq!(list_series(&pool, &project.id).await.unwrap()[0].instances.len(), 1);
+
+        crud::tasks::complete(&pool, &first.id, None).await.unwrap();
+        let instances = list_series(&pool, &project.id).await.unwrap().remove(0).instances;
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[1].due_date, Some((today + Duration::days(2)).format("%Y-%m-%d").to_string()));
+
+        // Clearing the template's rule ends the series
+        crate::entities::calendar::schedule_task(&pool, &template.id, None, None, true).await.unwrap();
+        crud::tasks::update_status(&pool, &instances[1].id, TaskStatus::Completed).await.unwrap();
+        assert!(list_series(&pool, &project.id).await.unwrap().is_empty());
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks").fetch_one(&pool).await.unwrap();
+        assert_eq!(total, 3);
+    }
+}
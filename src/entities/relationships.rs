@@ -0,0 +1,112 @@
+// Typed Relationship Schema - Allowed relationship kinds per entity-type pair
+// Shared by the CLI and MCP paths so both reject the same invalid links
+
+use serde::{Deserialize, Serialize};
+
+use super::schema_traits::EntityType;
+
+/// Stored relationship between two entities (row of the dependencies table)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub id: String,
+    pub project_id: String,
+    pub from_entity_id: String,
+    pub from_entity_type: String,
+    pub to_entity_id: String,
+    pub to_entity_type: String,
+    pub dependency_type: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Allowed relationship kinds for a (from, to) entity-type pair
+#[derive(Debug, Clone)]
+pub struct RelationshipRule {
+    pub from: EntityType,
+    pub to: EntityType,
+    pub kinds: &'static [&'static str],
+}
+
+/// Full matrix of allowed relationships
+pub fn relationship_rules() -> Vec<RelationshipRule> {
+    vec![
+        RelationshipRule { from: EntityType::Feature, to: EntityType::Feature, kinds: &["depends_on", "blocks"] },
+        RelationshipRule { from: EntityType::Task, to: EntityType::Feature, kinds: &["implements"] },
+        RelationshipRule { from: EntityType::Task, to: EntityType::Task, kinds: &["depends_on", "blocks"] },
+        RelationshipRule { from: EntityType::Session, to: EntityType::Task, kinds: &["worked_in"] },
+    ]
+}
+
+/// For a task-to-task relationship that orders work, the (dependent, prerequisite) pair:
+/// "A depends_on/requires B" and "B blocks A" both mean B must finish before A starts.
+/// `None` for kinds that do not order tasks.
+pub fn task_ordering<'a>(from: &'a str, to: &'a str, kind: &str) -> Option<(&'a str, &'a str)> {
+    match kind {
+        "depends_on" | "requires" => Some((from, to)),
+        "blocks" => Some((to, from)),
+        _ => None,
+    }
+}
+
+/// Get the allowed relationship kinds for an entity-type pair (empty if the pair cannot be linked)
+pub fn allowed_kinds(from: &EntityType, to: &EntityType) -> &'static [&'static str] {
+    relationship_rules()
+        .into_iter()
+        .find(|rule| &rule.from == from && &rule.to == to)
+        .map(|rule| rule.kinds)
+        .unwrap_or(&[])
+}
+
+/// Validate a relationship before it is created
+pub fn validate_relationship(from: &EntityType, to: &EntityType, kind: &str) -> Result<(), String> {
+    let kinds = allowed_kinds(from, to);
+    if kinds.is_empty() {
+        return Err(format!(
+            "No relationships are allowed from {} to {}",
+            from.as_str(),
+            to.as_str()
+        ));
+    }
+
+    if !kinds.contains(&kind) {
+        return Err(format!(
+            "Invalid relationship type '{}' for {} → {}. Allowed: {}",
+            kind,
+            from.as_str(),
+            to.as_str(),
+            kinds.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_relationships() {
+        assert!(validate_relationship(&EntityType::Feature, &EntityType::Feature, "depends_on").is_ok());
+        assert!(validate_relationship(&EntityType::Feature, &EntityType::Feature, "blocks").is_ok());
+        assert!(validate_relationship(&EntityType::Task, &EntityType::Feature, "implements").is_ok());
+        assert!(validate_relationship(&EntityType::Session, &EntityType::Task, "worked_in").is_ok());
+    }
+
+    #[test]
+    fn test_rejected_relationships() {
+        // Wrong kind for a valid pair
+        assert!(validate_relationship(&EntityType::Task, &EntityType::Feature, "blocks").is_err());
+        // Pair with no allowed kinds
+        assert!(validate_relationship(&EntityType::Project, &EntityType::Session, "depends_on").is_err());
+        assert!(allowed_kinds(&EntityType::Feature, &EntityType::Task).is_empty());
+    }
+
+    #[test]
+    fn test_task_ordering() {
+        assert_eq!(task_ordering("T000002", "T000001", "depends_on"), Some(("T000002", "T000001")));
+        assert_eq!(task_ordering("T000001", "T000002", "blocks"), Some(("T000002", "T000001")));
+        assert_eq!(task_ordering("T000001", "F00001", "implements"), None);
+    }
+}
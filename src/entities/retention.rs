@@ -0,0 +1,323 @@
+// Data Retention - Age limits per table, enforced by `wsb db prune`
+// Policies live under `tools.retention` in `.wsb/state.json`, keyed by the logical table
+// names in `TABLES`. Old rows are either deleted or scrubbed: scrubbing redacts the
+// PII-prone text fields (whole values, or only the parts matching configured patterns)
+// and keeps the row, so history and relationships stay intact. Rows still in use
+// (active sessions, pending events) are never touched.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+
+/// Replacement for scrubbed values
+pub const REDACTED: &str = "[redacted]";
+
+/// What happens to rows older than the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    Delete,
+    Scrub,
+}
+
+impl RetentionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetentionAction::Delete => "delete",
+            RetentionAction::Scrub => "scrub",
+        }
+    }
+}
+
+/// Policy for one table
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TableRetention {
+    /// Rows older than this are pruned; no limit when unset
+    pub max_age_days: Option<u32>,
+    /// Defaults to the table's own default (scrub for sessions, delete otherwise)
+    pub action: Option<RetentionAction>,
+    /// Fields to scrub; defaults to the table's PII-prone fields
+    pub scrub_fields: Vec<String>,
+    /// Regexes redacted within the scrubbed fields; whole values are redacted when empty
+    pub patterns: Vec<String>,
+}
+
+/// Retention settings stored under `tools.retention` in `.wsb/state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Logical table name (see `TABLES`) → policy
+    pub tables: BTreeMap<String, TableRetention>,
+}
+
+/// A table that can carry a retention policy
+pub struct RetainedTable {
+    pub name: &'static str,
+    pub table: &'static str,
+    timestamp: &'static str,
+    /// Rows matching this are kept regardless of age
+    in_use: Option<&'static str>,
+    default_action: RetentionAction,
+    pii_fields: &'static [&'static str],
+}
+
+pub const TABLES: &[RetainedTable] = &[
+    RetainedTable {
+        name: "audit",
+        table: "entity_audit_trails",
+        timestamp: "timestamp",
+        in_use: None,
+        default_action: RetentionAction::Delete,
+        pii_fields: &["old_value", "new_value", "change_reason", "metadata"],
+    },
+    RetainedTable {
+        name: "sessions",
+        table: "sessions",
+        timestamp: "created_at",
+        in_use: Some("state = 'active'"),
+        default_action: RetentionAction::Scrub,
+        pii_fields: &["description", "summary", "reminder", "validation_evidence", "metadata"],
+    },
+    RetainedTable {
+        name: "transcripts",
+        table: "session_continuity_states",
+        timestamp: "timestamp",
+        in_use: None,
+        default_action: RetentionAction::Delete,
+        pii_fields: &["state_data"],
+    },
+    RetainedTable {
+        name: "events",
+        table: "event_outbox",
+        timestamp: "created_at",
+        in_use: Some("status = 'pending'"),
+        default_action: RetentionAction::Delete,
+        pii_fields: &["payload", "last_error"],
+    },
+];
+
+/// What pruning does, or did, to one table
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PruneOutcome {
+    pub name: String,
+    pub table: String,
+    pub action: RetentionAction,
+    pub max_age_days: u32,
+    /// Rows deleted or scrubbed (or that would be, for a dry run)
+    pub rows: u64,
+}
+
+/// `value` with every match of `patterns` redacted, or redacted whole without patterns.
+/// `None` when there is nothing left to redact.
+pub fn scrub_value(value: &str, patterns: &[Regex]) -> Option<String> {
+    if value == REDACTED {
+        return None;
+    }
+    let scrubbed = if patterns.is_empty() {
+        REDACTED.to_string()
+    } else {
+        patterns.iter().fold(value.to_string(), |text, pattern| pattern.replace_all(&text, REDACTED).into_owned())
+    };
+    (scrubbed != value).then_some(scrubbed)
+}
+
+fn find_table(name: &str) -> Result<&'static RetainedTable> {
+    TABLES.iter().find(|t| t.name == name).ok_or_else(|| {
+        let known: Vec<&str> = TABLES.iter().map(|t| t.name).collect();
+        anyhow::anyhow!("Unknown retention table '{}' (expected one of: {})", name, known.join(", "))
+    })
+}
+
+async fn table_exists(pool: &SqlitePool, table: &str) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+async fn columns(pool: &SqlitePool, table: &str) -> Result<Vec<String>> {
+    let rows = sqlx::query(&format!("PRAGMA table_info({})", table)).fetch_all(pool).await?;
+    Ok(rows.into_iter().map(|row| row.get::<String, _>("name")).collect())
+}
+
+/// Condition selecting the rows of `target` past their age limit
+fn expired_clause(target: &RetainedTable) -> String {
+    let mut clause = format!("julianday({}) < julianday('now', ?)", target.timestamp);
+    if let Some(in_use) = target.in_use {
+        clause.push_str(&format!(" AND NOT ({})", in_use));
+    }
+    clause
+}
+
+/// Prune every table with a policy. Without `apply` nothing is changed and the outcome
+/// says what would happen.
+pub async fn prune(pool: &SqlitePool, config: &RetentionConfig, apply: bool) -> Result<Vec<PruneOutcome>> {
+    let mut outcomes = Vec::new();
+    for (name, policy) in &config.tables {
+        let target = find_table(name)?;
+        let days = match policy.max_age_days {
+            Some(days) => days,
+            None => continue,
+        };
+        if !table_exists(pool, target.table).await? {
+            continue;
+        }
+        let action = policy.action.unwrap_or(target.default_action);
+        let age = format!("-{} days", days);
+
+        let rows = match action {
+            RetentionAction::Delete => {
+                let clause = expired_clause(target);
+                if apply {
+                    sqlx::query(&format!("DELETE FROM {} WHERE {}", target.table, clause))
+                        .bind(&age)
+                        .execute(pool)
+                        .await
+                        .with_context(|| format!("Failed to prune {}", target.table))?
+                        .rows_affected()
+                } else {
+                    let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE {}", target.table, clause))
+                        .bind(&age)
+                        .fetch_one(pool)
+                        .await?;
+                    count as u64
+                }
+            }
+            RetentionAction::Scrub => scrub(pool, target, policy, &age, apply).await?,
+        };
+
+        outcomes.push(PruneOutcome {
+            name: target.name.to_string(),
+            table: target.table.to_string(),
+            action,
+            max_age_days: days,
+            rows,
+        });
+    }
+    Ok(outcomes)
+}
+
+/// Redact the scrubbed fields of expired rows; returns the rows that changed
+async fn scrub(pool: &SqlitePool, target: &RetainedTable, policy: &TableRetention, age: &str, apply: bool) -> Result<u64> {
+    let fields: Vec<String> = if policy.scrub_fields.is_empty() {
+        target.pii_fields.iter().map(|f| f.to_string()).collect()
+    } else {
+        policy.scrub_fields.clone()
+    };
+    let known = columns(pool, target.table).await?;
+    if let Some(unknown) = fields.iter().find(|f| !known.contains(f)) {
+        anyhow::bail!("{} has no field '{}' to scrub", target.table, unknown);
+    }
+    let patterns = policy
+        .patterns
+        .iter()
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid scrub pattern: {}", p)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT rowid, {} FROM {} WHERE {}",
+        fields.join(", "),
+        target.table,
+        expired_clause(target)
+    ))
+    .bind(age)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tx = pool.begin().await?;
+    let mut changed = 0;
+    for row in rows {
+        let rowid: i64 = row.get("rowid");
+        let updates: Vec<(&String, String)> = fields
+            .iter()
+            .filter_map(|field| {
+                let value: Option<String> = row.get(field.as_str());
+                value.and_then(|v| scrub_value(&v, &patterns)).map(|scrubbed| (field, scrubbed))
+            })
+            .collect();
+        if updates.is_empty() {
+            continue;
+        }
+        changed += 1;
+        if !apply {
+            continue;
+        }
+
+        let assignments: Vec<String> = updates.iter().map(|(field, _)| format!("{} = ?", field)).collect();
+        let sql = format!("UPDATE {} SET {} WHERE rowid = ?", target.table, assignments.join(", "));
+        let mut query = sqlx::query(&sql);
+        for (_, scrubbed) in &updates {
+            query = query.bind(scrubbed);
+        }
+        query.bind(rowid).execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scrub_value() {
+        let email = Regex::new(r"[\w.+-]+@[\w-]+\.[\w.]+").unwrap();
+        assert_eq!(scrub_value("ask jane@example.com", &[email.clone()]), Some("ask [redacted]".to_string()));
+        assert_eq!(scrub_value("no contact details", &[email]), None);
+        assert_eq!(scrub_value("anything", &[]), Some(REDACTED.to_string()));
+        assert_eq!(scrub_value(REDACTED, &[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_prune_deletes_and_scrubs_old_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Retention".to_string(), "Retention project".to_string()).await.unwrap();
+
+        for (id, days_old) in [("A1", 400), ("A2", 10)] {
+            sqlx::query(r#"
+                INSERT INTO entity_audit_trails (id, entity_id, entity_type, project_id, operation_type, old_value, new_value, triggered_by, timestamp)
+                VALUES (?, ?, 'project', ?, 'update', 'jane@example.com', 'new', 'cli', datetime('now', ?))
+            "#)
+            .bind(id)
+            .bind(&project.id)
+            .bind(&project.id)
+            .bind(format!("-{} days", days_old))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let mut config = RetentionConfig::default();
+        config.tables.insert("audit".to_string(), TableRetention {
+            max_age_days: Some(90),
+            action: Some(RetentionAction::Scrub),
+            patterns: vec![r"[\w.+-]+@[\w-]+\.[\w.]+".to_string()],
+            ..Default::default()
+        });
+
+        let dry_run = prune(&pool, &config, false).await.unwrap();
+        assert_eq!(dry_run[0].rows, 1);
+        let old_value: String = sqlx::query_scalar("SELECT old_value FROM entity_audit_trails WHERE id = 'A1'").fetch_one(&pool).await.unwrap();
+        assert_eq!(old_value, "jane@example.com");
+
+        prune(&pool, &config, true).await.unwrap();
+        let old_value: String = sqlx::query_scalar("SELECT old_value FROM entity_audit_trails WHERE id = 'A1'").fetch_one(&pool).await.unwrap();
+        assert_eq!(old_value, REDACTED);
+        assert_eq!(prune(&pool, &config, false).await.unwrap()[0].rows, 0);
+
+        config.tables.get_mut("audit").unwrap().action = Some(RetentionAction::Delete);
+        assert_eq!(prune(&pool, &config, true).await.unwrap()[0].rows, 1);
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM entity_audit_trails WHERE id IN ('A1', 'A2')").fetch_one(&pool).await.unwrap();
+        assert_eq!(remaining, 1);
+
+        config.tables.insert("logs".to_string(), TableRetention { max_age_days: Some(1), ..Default::default() });
+        assert!(prune(&pool, &config, false).await.is_err());
+    }
+}
@@ -0,0 +1,385 @@
+// Critical Path - Dependency-aware scheduling over open tasks
+// Uses task dependencies (`tasks.depends_on` plus task-to-task rows in `dependencies`)
+// and `estimated_effort` to find the longest chain to completion, per-task slack,
+// and the blocked task that is actually holding up the end date.
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::entities::relationships::task_ordering;
+
+/// Effort assumed for open tasks without an estimate
+pub const DEFAULT_EFFORT: i64 = 1;
+
+/// An open task as seen by the scheduler
+#[derive(Debug, Clone)]
+pub struct PlanTask {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    /// Estimated effort; `None` falls back to [`DEFAULT_EFFORT`]
+    pub estimate: Option<i64>,
+    /// Tasks that must finish before this one can start
+    pub depends_on: Vec<String>,
+    pub feature_ids: Vec<String>,
+}
+
+impl PlanTask {
+    fn effort(&self) -> i64 {
+        self.estimate.filter(|e| *e >= 0).unwrap_or(DEFAULT_EFFORT)
+    }
+}
+
+/// Schedule figures for one task, in effort units from now
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScheduledTask {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub effort: i64,
+    pub estimated: bool,
+    pub earliest_start: i64,
+    pub earliest_finish: i64,
+    pub latest_start: i64,
+    pub latest_finish: i64,
+    pub slack: i64,
+}
+
+/// Result of a critical path calculation
+#[derive(Debug, Clone, Serialize)]
+pub struct CriticalPath {
+    /// Milestone the calculation was limited to, if any
+    pub milestone: Option<String>,
+    /// Effort remaining until the last task in scope can finish
+    pub total_effort: i64,
+    /// Tasks on the longest chain, in execution order
+    pub path: Vec<ScheduledTask>,
+    /// Every task in scope, critical ones first, then by slack
+    pub tasks: Vec<ScheduledTask>,
+    /// First blocked task on the critical path; unblocking it is what moves the end date
+    pub gating: Option<String>,
+    /// Blocked tasks with slack, which do not delay completion yet
+    pub blocked_off_path: Vec<String>,
+    /// Open tasks with no estimate (scheduled with [`DEFAULT_EFFORT`])
+    pub unestimated: Vec<String>,
+}
+
+/// Split a stored ID list: a JSON array or a comma/whitespace-separated string
+pub fn parse_id_list(raw: Option<&str>) -> Vec<String> {
+    let raw = match raw.map(str::trim) {
+        Some(raw) if !raw.is_empty() => raw,
+        _ => return Vec::new(),
+    };
+    if let Ok(ids) = serde_json::from_str::<Vec<String>>(raw) {
+        return ids.into_iter().map(|id| id.trim().to_string()).filter(|id| !id.is_empty()).collect();
+    }
+    raw.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|id| id.trim_matches(|c| c == '"' || c == '[' || c == ']').to_string())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Order `ids` so every task comes after its dependencies; fails on a cycle
+fn topological_order(ids: &BTreeSet<String>, predecessors: &HashMap<String, Vec<String>>) -> Result<Vec<String>> {
+    let mut remaining: BTreeMap<&str, usize> = ids
+        .iter()
+        .map(|id| (id.as_str(), predecessors.get(id).map_or(0, |p| p.len())))
+        .collect();
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for id in ids {
+        for pred in predecessors.get(id).into_iter().flatten() {
+            successors.entry(pred.as_str()).or_default().push(id.as_str());
+        }
+    }
+
+    let mut ready: BTreeSet<&str> = remaining.iter().filter(|(_, n)| **n == 0).map(|(id, _)| *id).collect();
+    let mut order = Vec::with_capacity(ids.len());
+    while let Some(id) = ready.iter().next().copied() {
+        ready.remove(id);
+        order.push(id.to_string());
+        for succ in successors.get(id).into_iter().flatten() {
+            let count = remaining.get_mut(succ).expect("successor is in scope");
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(*succ);
+            }
+        }
+    }
+
+    if order.len() != ids.len() {
+        let cyclic: Vec<&str> = remaining.iter().filter(|(_, n)| **n > 0).map(|(id, _)| *id).collect();
+        anyhow::bail!("Task dependencies form a cycle involving: {}", cyclic.join(", "));
+    }
+    Ok(order)
+}
+
+/// Compute the critical path over open `tasks`. With `targets`, only those tasks and
+/// everything they transitively depend on are scheduled.
+pub fn compute(tasks: &[PlanTask], targets: Option<&BTreeSet<String>>) -> Result<CriticalPath> {
+    let by_id: HashMap<&str, &PlanTask> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+
+    // Dependencies on finished or unknown tasks no longer constrain the schedule
+    let open_preds = |task: &PlanTask| -> Vec<String> {
+        let mut preds: Vec<String> = task.depends_on.iter().filter(|d| by_id.contains_key(d.as_str()) && **d != task.id).cloned().collect();
+        preds.sort();
+        preds.dedup();
+        preds
+    };
+
+    let mut scope: BTreeSet<String> = BTreeSet::new();
+    let mut stack: Vec<String> = match targets {
+        Some(targets) => targets.iter().filter(|id| by_id.contains_key(id.as_str())).cloned().collect(),
+        None => tasks.iter().map(|t| t.id.clone()).collect(),
+    };
+    while let Some(id) = stack.pop() {
+        if scope.insert(id.clone()) {
+            stack.extend(open_preds(by_id[id.as_str()]));
+        }
+    }
+
+    let predecessors: HashMap<String, Vec<String>> = scope.iter().map(|id| (id.clone(), open_preds(by_id[id.as_str()]))).collect();
+    let order = topological_order(&scope, &predecessors)?;
+
+    // Forward pass: earliest start is the latest earliest finish among dependencies
+    let mut earliest: HashMap<&str, (i64, i64)> = HashMap::new();
+    for id in &order {
+        let start = predecessors[id].iter().map(|p| earliest[p.as_str()].1).max().unwrap_or(0);
+        earliest.insert(id.as_str(), (start, start + by_id[id.as_str()].effort()));
+    }
+    let total_effort = earliest.values().map(|(_, finish)| *finish).max().unwrap_or(0);
+
+    // Backward pass: latest finish is the earliest latest start among dependents
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, preds) in &predecessors {
+        for pred in preds {
+            successors.entry(pred.as_str()).or_default().push(id.as_str());
+        }
+    }
+    let mut latest: HashMap<&str, (i64, i64)> = HashMap::new();
+    for id in order.iter().rev() {
+        let finish = successors
+            .get(id.as_str())
+            .into_iter()
+            .flatten()
+            .map(|s| latest[s].0)
+            .min()
+            .unwrap_or(total_effort);
+        latest.insert(id.as_str(), (finish - by_id[id.as_str()].effort(), finish));
+    }
+
+    let schedule = |id: &str| -> ScheduledTask {
+        let task = by_id[id];
+        let (earliest_start, earliest_finish) = earliest[id];
+        let (latest_start, latest_finish) = latest[id];
+        ScheduledTask {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            status: task.status.clone(),
+            effort: task.effort(),
+            estimated: task.estimate.is_some(),
+            earliest_start,
+            earliest_finish,
+            latest_start,
+            latest_finish,
+            slack: latest_start - earliest_start,
+        }
+    };
+
+    // Walk back from the task finishing last through zero-slack dependencies
+    let mut path = Vec::new();
+    let mut current = order.iter().filter(|id| earliest[id.as_str()].1 == total_effort).min().cloned();
+    while let Some(id) = current {
+        let start = earliest[id.as_str()].0;
+        current = predecessors[&id]
+            .iter()
+            .find(|p| earliest[p.as_str()].1 == start && latest[p.as_str()].0 == earliest[p.as_str()].0)
+            .cloned();
+        path.push(schedule(id.as_str()));
+    }
+    path.reverse();
+
+    let mut all: Vec<ScheduledTask> = order.iter().map(|id| schedule(id.as_str())).collect();
+    all.sort_by(|a, b| a.slack.cmp(&b.slack).then(a.earliest_start.cmp(&b.earliest_start)).then(a.id.cmp(&b.id)));
+
+    let gating = path.iter().find(|t| t.status == "blocked").map(|t| t.id.clone());
+    let blocked_off_path = all
+        .iter()
+        .filter(|t| t.status == "blocked" && !path.iter().any(|p| p.id == t.id))
+        .map(|t| t.id.clone())
+        .collect();
+    let unestimated = all.iter().filter(|t| !t.estimated).map(|t| t.id.clone()).collect();
+
+    Ok(CriticalPath {
+        milestone: None,
+        total_effort,
+        path,
+        tasks: all,
+        gating,
+        blocked_off_path,
+        unestimated,
+    })
+}
+
+/// Load open tasks of a project with their dependencies
+pub async fn load_open_tasks(pool: &SqlitePool, project_id: &str) -> Result<Vec<PlanTask>> {
+    let rows = sqlx::query(r#"
+        SELECT id, title, status, depends_on, estimated_effort, feature_ids
+        FROM tasks
+        WHERE project_id = ? AND status NOT IN ('completed', 'cancelled') AND deleted_at IS NULL
+        ORDER BY id
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tasks: Vec<PlanTask> = rows
+        .iter()
+        .map(|row| PlanTask {
+            id: row.get("id"),
+            title: row.get("title"),
+            status: row.get("status"),
+            estimate: row.get("estimated_effort"),
+            depends_on: parse_id_list(row.get::<Option<String>, _>("depends_on").as_deref()),
+            feature_ids: parse_id_list(row.get::<Option<String>, _>("feature_ids").as_deref()),
+        })
+        .collect();
+
+    // Task-to-task relationships: "A depends_on/requires B" and "B blocks A" both order B first
+    let edges = sqlx::query(r#"
+        SELECT from_entity_id, to_entity_id, dependency_type
+        FROM dependencies
+        WHERE project_id = ? AND from_entity_type = 'task' AND to_entity_type = 'task'
+            AND dependency_type IN ('depends_on', 'requires', 'blocks') AND resolved_at IS NULL
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let index: HashMap<String, usize> = tasks.iter().enumerate().map(|(i, t)| (t.id.clone(), i)).collect();
+    for edge in &edges {
+        let from: String = edge.get("from_entity_id");
+        let to: String = edge.get("to_entity_id");
+        let kind: String = edge.get("dependency_type");
+        let Some((dependent, prerequisite)) = task_ordering(&from, &to, &kind) else {
+            continue;
+        };
+        if let Some(&i) = index.get(dependent) {
+            if !tasks[i].depends_on.iter().any(|p| p == prerequisite) {
+                tasks[i].depends_on.push(prerequisite.to_string());
+            }
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Critical path for a project, optionally only up to a milestone (its tasks, its
+/// features' tasks and their prerequisites)
+pub async fn critical_path(pool: &SqlitePool, project_id: &str, milestone: Option<&str>) -> Result<CriticalPath> {
+    let tasks = load_open_tasks(pool, project_id).await?;
+
+    let milestone_targets = match milestone {
+        Some(milestone) => {
+            let row = sqlx::query("SELECT id, feature_ids, task_ids FROM milestones WHERE project_id = ? AND (id = ? OR title = ?)")
+                .bind(project_id)
+                .bind(milestone)
+                .bind(milestone)
+                .fetch_optional(pool)
+                .await?;
+            let row = match row {
+                Some(row) => row,
+                None => anyhow::bail!("Milestone not found: {}", milestone),
+            };
+            let features: BTreeSet<String> = parse_id_list(row.get::<Option<String>, _>("feature_ids").as_deref()).into_iter().collect();
+            let members: BTreeSet<String> = parse_id_list(row.get::<Option<String>, _>("task_ids").as_deref()).into_iter().collect();
+            let targets: BTreeSet<String> = tasks
+                .iter()
+                .filter(|t| members.contains(&t.id) || t.feature_ids.iter().any(|f| features.contains(f)))
+                .map(|t| t.id.clone())
+                .collect();
+            Some((row.get::<String, _>("id"), targets))
+        }
+        None => None,
+    };
+
+    let mut result = compute(&tasks, milestone_targets.as_ref().map(|(_, targets)| targets))?;
+    result.milestone = milestone_targets.map(|(id, _)| id);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, status: &str, estimate: Option<i64>, depends_on: &[&str]) -> PlanTask {
+        PlanTask {
+            id: id.to_string(),
+            title: format!("Task {}", id),
+            status: status.to_string(),
+            estimate,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            feature_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_id_list() {
+        assert_eq!(parse_id_list(Some(r#"["T000001", "T000002"]"#)), vec!["T000001", "T000002"]);
+        assert_eq!(parse_id_list(Some("T000001, T000002")), vec!["T000001", "T000002"]);
+        assert!(parse_id_list(Some("  ")).is_empty());
+        assert!(parse_id_list(None).is_empty());
+    }
+
+    #[test]
+    fn test_critical_path_and_slack() {
+        // A(3) -> C(4) -> D(1) is the long chain; B(2) -> D can start as late as 5
+        let tasks = vec![
+            task("A", "in_progress", Some(3), &[]),
+            task("B", "blocked", Some(2), &[]),
+            task("C", "blocked", Some(4), &["A"]),
+            task("D", "pending", Some(1), &["B", "C"]),
+        ];
+        let result = compute(&tasks, None).unwrap();
+
+        assert_eq!(result.total_effort, 8);
+        let path: Vec<&str> = result.path.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(path, vec!["A", "C", "D"]);
+
+        let slack: HashMap<&str, i64> = result.tasks.iter().map(|t| (t.id.as_str(), t.slack)).collect();
+        assert_eq!(slack["A"], 0);
+        assert_eq!(slack["B"], 5);
+        assert_eq!(slack["D"], 0);
+
+        // C is blocked on the critical path; B is blocked but has slack
+        assert_eq!(result.gating.as_deref(), Some("C"));
+        assert_eq!(result.blocked_off_path, vec!["B"]);
+    }
+
+    #[test]
+    fn test_targets_limit_scope_and_unestimated_default() {
+        let tasks = vec![
+            task("A", "pending", None, &[]),
+            task("B", "pending", Some(2), &["A"]),
+            task("Z", "pending", Some(50), &[]),
+            // Dependency on a finished task is ignored
+            task("C", "pending", Some(1), &["DONE"]),
+        ];
+        let targets: BTreeSet<String> = ["B".to_string()].into_iter().collect();
+        let result = compute(&tasks, Some(&targets)).unwrap();
+
+        assert_eq!(result.total_effort, DEFAULT_EFFORT + 2);
+        assert_eq!(result.tasks.len(), 2);
+        assert_eq!(result.unestimated, vec!["A"]);
+        assert_eq!(result.gating, None);
+    }
+
+    #[test]
+    fn test_cycle_is_reported() {
+        let tasks = vec![task("A", "pending", Some(1), &["B"]), task("B", "pending", Some(1), &["A"])];
+        let err = compute(&tasks, None).unwrap_err().to_string();
+        assert!(err.contains("cycle"));
+        assert!(err.contains("A") && err.contains("B"));
+    }
+}
@@ -104,6 +104,29 @@ impl FeatureState {
         }
     }
 
+    /// Next state along the normal progression, used when a reviewer advances a feature
+    pub fn advance(&self) -> Option<FeatureState> {
+        use FeatureState::*;
+        match self {
+            NotImplemented => Some(ImplementedNoTests),
+            ImplementedNoTests => Some(ImplementedFailingTests),
+            ImplementedFailingTests => Some(ImplementedPassingTests),
+            TestsBroken => Some(ImplementedPassingTests),
+            CriticalIssue => Some(ImplementedNoTests),
+            ImplementedPassingTests => None,
+        }
+    }
+
+    /// Regression state, used when a reviewer demotes a feature
+    pub fn demote(&self) -> Option<FeatureState> {
+        use FeatureState::*;
+        match self {
+            ImplementedNoTests | ImplementedFailingTests | ImplementedPassingTests => Some(TestsBroken),
+            NotImplemented | TestsBroken => Some(CriticalIssue),
+            CriticalIssue => None,
+        }
+    }
+
     /// Get the emoji representation for display
     pub fn emoji(&self) -> &'static str {
         match self {
@@ -432,6 +455,17 @@ impl IdValidator {
         regex::Regex::new(r"^D\d{3}$").unwrap().is_match(id)
     }
 
+    /// Validate Milestone ID pattern: M### (M001, M002, M003...)
+    pub fn validate_milestone_id(id: &str) -> bool {
+        regex::Regex::new(r"^M\d{3}$").unwrap().is_match(id)
+    }
+
+    /// Validate an ID as allocated by `ids::IdGenerator`: any of the patterns above, or a
+    /// configured prefix of 1-4 upper case letters followed by up to 12 digits
+    pub fn validate_entity_id(id: &str) -> bool {
+        regex::Regex::new(r"^[A-Z]{1,4}\d{1,12}$").unwrap().is_match(id)
+    }
+
     /// Validate Metric code pattern: M## or P## (M01, M02, P01, P02...)
     pub fn validate_metric_code(code: &str) -> bool {
         let m_pattern = regex::Regex::new(r"^M\d{2}$").unwrap();
@@ -457,12 +491,15 @@ pub struct Project {
     pub created_at: DateTime<Utc>,
     /// Project last update timestamp
     pub updated_at: DateTime<Utc>,
+    /// When the project was soft-deleted; None while it is live
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Project {
     /// Create new project with validation
     pub fn new(id: String, name: String, description: String) -> Result<Self, String> {
-        if !IdValidator::validate_project_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid project ID pattern: {}. Must be P### format (P001, P002, etc.)", id));
         }
 
@@ -483,6 +520,7 @@ impl Project {
             current_phase: None,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
         })
     }
 
@@ -496,7 +534,7 @@ impl Project {
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, String> {
-        if !IdValidator::validate_project_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid project ID pattern: {}", id));
         }
 
@@ -508,6 +546,7 @@ impl Project {
             current_phase,
             created_at,
             updated_at,
+            deleted_at: None,
         })
     }
 
@@ -574,16 +613,19 @@ pub struct Feature {
     pub created_at: DateTime<Utc>,
     /// Feature last update timestamp
     pub updated_at: DateTime<Utc>,
+    /// When the feature was soft-deleted; None while it is live
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Feature {
     /// Create new feature with validation
     pub fn new(id: String, project_id: String, code: String, name: String, description: String, category: Option<String>) -> Result<Self, String> {
-        if !IdValidator::validate_feature_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid feature ID pattern: {}. Must be F##### format (F00001, F00002, etc.)", id));
         }
 
-        if !IdValidator::validate_project_id(&project_id) {
+        if !IdValidator::validate_entity_id(&project_id) {
             return Err(format!("Invalid project ID pattern: {}. Must be P### format", project_id));
         }
 
@@ -609,6 +651,7 @@ impl Feature {
             notes: None,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
         })
     }
 
@@ -627,7 +670,7 @@ impl Feature {
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, String> {
-        if !IdValidator::validate_feature_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid feature ID pattern: {}", id));
         }
 
@@ -644,6 +687,7 @@ impl Feature {
             notes,
             created_at,
             updated_at,
+            deleted_at: None,
         })
     }
 
@@ -724,6 +768,9 @@ pub struct Task {
     pub created_at: DateTime<Utc>,
     /// Task last update timestamp
     pub updated_at: DateTime<Utc>,
+    /// When the task was soft-deleted; None while it is live
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Task {
@@ -735,15 +782,15 @@ impl Task {
         task: String,
         category: String,
     ) -> Result<Self, String> {
-        if !IdValidator::validate_task_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid task ID pattern: {}. Must be T###### format (T000001, T000002, etc.)", id));
         }
 
-        if !IdValidator::validate_project_id(&project_id) {
+        if !IdValidator::validate_entity_id(&project_id) {
             return Err(format!("Invalid project ID pattern: {}. Must be P### format", project_id));
         }
 
-        if !IdValidator::validate_feature_id(&feature_id) {
+        if !IdValidator::validate_entity_id(&feature_id) {
             return Err(format!("Invalid feature ID pattern: {}. Must be F##### format", feature_id));
         }
 
@@ -769,6 +816,7 @@ impl Task {
             notes: None,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
         })
     }
 
@@ -787,7 +835,7 @@ impl Task {
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, String> {
-        if !IdValidator::validate_task_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid task ID pattern: {}", id));
         }
 
@@ -804,6 +852,7 @@ impl Task {
             notes,
             created_at,
             updated_at,
+            deleted_at: None,
         })
     }
 
@@ -855,7 +904,7 @@ impl Task {
     pub fn set_dependencies(&mut self, dependencies: Vec<String>) -> Result<(), String> {
         // Validate all dependency IDs
         for dep_id in &dependencies {
-            if !IdValidator::validate_task_id(dep_id) {
+            if !IdValidator::validate_entity_id(dep_id) {
                 return Err(format!("Invalid dependency task ID pattern: {}. Must be T###### format", dep_id));
             }
         }
@@ -968,6 +1017,28 @@ mod tests {
         assert!(!ImplementedFailingTests.can_transition_to(&NotImplemented));
     }
 
+    #[test]
+    fn test_feature_state_review_moves() {
+        use FeatureState::*;
+
+        assert_eq!(NotImplemented.advance(), Some(ImplementedNoTests));
+        assert_eq!(ImplementedFailingTests.advance(), Some(ImplementedPassingTests));
+        assert_eq!(ImplementedPassingTests.advance(), None);
+        assert_eq!(ImplementedPassingTests.demote(), Some(TestsBroken));
+        assert_eq!(TestsBroken.demote(), Some(CriticalIssue));
+        assert_eq!(CriticalIssue.demote(), None);
+
+        // Every review move must be a legal transition
+        for state in [NotImplemented, ImplementedNoTests, ImplementedFailingTests, ImplementedPassingTests, TestsBroken, CriticalIssue] {
+            if let Some(next) = state.advance() {
+                assert!(state.can_transition_to(&next));
+            }
+            if let Some(prev) = state.demote() {
+                assert!(state.can_transition_to(&prev));
+            }
+        }
+    }
+
     #[test]
     fn test_task_status_transitions() {
         use TaskStatus::*;
@@ -1087,7 +1158,7 @@ pub struct CompletedTask {
 
 impl CompletedTask {
     pub fn new(task_id: String, description: String) -> Result<Self, String> {
-        if !IdValidator::validate_task_id(&task_id) {
+        if !IdValidator::validate_entity_id(&task_id) {
             return Err(format!("Invalid task ID pattern: {}. Must be T###### format", task_id));
         }
 
@@ -1171,7 +1242,7 @@ impl FileModification {
     }
 
     pub fn set_feature_id(&mut self, feature_id: String) -> Result<(), String> {
-        if !IdValidator::validate_feature_id(&feature_id) {
+        if !IdValidator::validate_entity_id(&feature_id) {
             return Err(format!("Invalid feature ID pattern: {}. Must be F##### format", feature_id));
         }
         self.feature_id = Some(feature_id);
@@ -1211,6 +1282,39 @@ impl IssueResolution {
     }
 }
 
+/// A commit made while a session was active
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionCommit {
+    pub sha: String,
+    pub author: String,
+    /// Commit time (RFC 3339)
+    pub committed_at: String,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+/// Lines added and removed in one file across a session's commits
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileDiffStat {
+    pub path: String,
+    pub insertions: u64,
+    pub deletions: u64,
+    /// Binary files have no line counts
+    #[serde(default)]
+    pub binary: bool,
+}
+
+/// Git history of a session window, captured when the session completes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitActivity {
+    /// Commits, newest first
+    pub commits: Vec<SessionCommit>,
+    /// Files touched by those commits, by path
+    pub files: Vec<FileDiffStat>,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
 /// Session Entity - Development Activity Tracking
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Session {
@@ -1240,6 +1344,9 @@ pub struct Session {
     pub files_modified: Option<String>,
     /// JSON array of IssueResolution objects
     pub issues_resolved: Option<String>,
+    /// JSON GitActivity object, set when the session completes
+    #[serde(default)]
+    pub git_activity: Option<String>,
     /// Session creation timestamp
     pub created_at: DateTime<Utc>,
     /// Session last update timestamp
@@ -1249,11 +1356,11 @@ pub struct Session {
 impl Session {
     /// Create new session with validation
     pub fn new(id: String, project_id: String, title: String, focus: String) -> Result<Self, String> {
-        if !IdValidator::validate_session_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid session ID pattern: {}. Must be S###### format (S000001, S000002, etc.)", id));
         }
 
-        if !IdValidator::validate_project_id(&project_id) {
+        if !IdValidator::validate_entity_id(&project_id) {
             return Err(format!("Invalid project ID pattern: {}. Must be P### format", project_id));
         }
 
@@ -1282,6 +1389,7 @@ impl Session {
             key_achievements: None,
             files_modified: None,
             issues_resolved: None,
+            git_activity: None,
             created_at: now,
             updated_at: now,
         })
@@ -1305,7 +1413,7 @@ impl Session {
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, String> {
-        if !IdValidator::validate_session_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid session ID pattern: {}", id));
         }
 
@@ -1323,6 +1431,7 @@ impl Session {
             key_achievements,
             files_modified,
             issues_resolved,
+            git_activity: None,
             created_at,
             updated_at,
         })
@@ -1393,6 +1502,17 @@ impl Session {
         Ok(())
     }
 
+    /// Get the git activity captured at completion; None before the session completes or
+    /// when it ran outside a git repository
+    pub fn get_git_activity(&self) -> Result<Option<GitActivity>, String> {
+        match &self.git_activity {
+            Some(activity_json) => serde_json::from_str(activity_json)
+                .map(Some)
+                .map_err(|e| format!("Failed to parse git_activity JSON: {}", e)),
+            None => Ok(None),
+        }
+    }
+
     /// Get files modified as vector
     pub fn get_files_modified(&self) -> Result<Vec<FileModification>, String> {
         match &self.files_modified {
@@ -1494,11 +1614,11 @@ pub struct Directive {
 impl Directive {
     /// Create new directive with validation
     pub fn new(id: String, project_id: String, title: String, rule: String) -> Result<Self, String> {
-        if !IdValidator::validate_directive_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid directive ID pattern: {}. Must be D### format (D001, D002, etc.)", id));
         }
 
-        if !IdValidator::validate_project_id(&project_id) {
+        if !IdValidator::validate_entity_id(&project_id) {
             return Err(format!("Invalid project ID pattern: {}. Must be P### format", project_id));
         }
 
@@ -1540,7 +1660,7 @@ impl Directive {
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, String> {
-        if !IdValidator::validate_directive_id(&id) {
+        if !IdValidator::validate_entity_id(&id) {
             return Err(format!("Invalid directive ID pattern: {}", id));
         }
 
@@ -1621,6 +1741,117 @@ impl Directive {
     }
 }
 
+/// Milestone Status Enumeration
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Planned,
+    InProgress,
+    Achieved,
+    Missed,
+    Cancelled,
+}
+
+impl MilestoneStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MilestoneStatus::Planned => "planned",
+            MilestoneStatus::InProgress => "in_progress",
+            MilestoneStatus::Achieved => "achieved",
+            MilestoneStatus::Missed => "missed",
+            MilestoneStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "planned" => Ok(MilestoneStatus::Planned),
+            "in_progress" => Ok(MilestoneStatus::InProgress),
+            "achieved" => Ok(MilestoneStatus::Achieved),
+            "missed" => Ok(MilestoneStatus::Missed),
+            "cancelled" => Ok(MilestoneStatus::Cancelled),
+            _ => Err(format!("Invalid milestone status: {}", s)),
+        }
+    }
+
+    /// Whether the milestone has been closed
+    pub fn is_closed(&self) -> bool {
+        matches!(self, MilestoneStatus::Achieved | MilestoneStatus::Missed | MilestoneStatus::Cancelled)
+    }
+}
+
+/// Milestone Entity - A target grouping features and tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    /// Milestone ID in M### format (M001, M002, M003...)
+    pub id: String,
+    /// Foreign key to Project
+    pub project_id: String,
+    /// Milestone title
+    pub title: String,
+    /// Milestone description
+    pub description: String,
+    /// Target date (YYYY-MM-DD)
+    pub target_date: Option<String>,
+    /// Date the milestone was achieved (YYYY-MM-DD)
+    pub achieved_date: Option<String>,
+    /// Milestone status (stored as string, converted to enum)
+    pub status: String,
+    /// Member features
+    pub feature_ids: Vec<String>,
+    /// Member tasks
+    pub task_ids: Vec<String>,
+    /// Share of live members that are done, 0-100
+    pub completion_percentage: f64,
+    /// Milestone creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Milestone last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Milestone {
+    /// Create new milestone with validation
+    pub fn new(id: String, project_id: String, title: String, description: String, target_date: Option<String>) -> Result<Self, String> {
+        if !IdValidator::validate_entity_id(&id) {
+            return Err(format!("Invalid milestone ID pattern: {}. Must be M### format (M001, M002, etc.)", id));
+        }
+
+        if !IdValidator::validate_entity_id(&project_id) {
+            return Err(format!("Invalid project ID pattern: {}. Must be P### format", project_id));
+        }
+
+        if title.trim().is_empty() {
+            return Err("Milestone title cannot be empty".to_string());
+        }
+
+        if let Some(ref date) = target_date {
+            if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                return Err(format!("Invalid target date: {}. Use YYYY-MM-DD", date));
+            }
+        }
+
+        let now = Utc::now();
+        Ok(Milestone {
+            id,
+            project_id,
+            title,
+            description,
+            target_date,
+            achieved_date: None,
+            status: MilestoneStatus::Planned.as_str().to_string(),
+            feature_ids: Vec::new(),
+            task_ids: Vec::new(),
+            completion_percentage: 0.0,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Get milestone status as enum
+    pub fn get_status(&self) -> Result<MilestoneStatus, String> {
+        MilestoneStatus::from_str(&self.status)
+    }
+}
+
 /// Additional test cases for Session and Directive entities
 #[cfg(test)]
 mod session_directive_tests {
@@ -1750,6 +1981,18 @@ mod session_directive_tests {
         assert!(!IdValidator::validate_metric_code("X01"));
         assert!(!IdValidator::validate_metric_code("m01"));
     }
+
+    #[test]
+    fn test_milestone_validation() {
+        assert!(IdValidator::validate_milestone_id("M001"));
+        assert!(!IdValidator::validate_milestone_id("M01"));
+        assert!(!IdValidator::validate_milestone_id("milestone-001"));
+
+        let milestone = Milestone::new("M001".to_string(), "P001".to_string(), "Beta".to_string(), String::new(), Some("2026-03-31".to_string())).unwrap();
+        assert_eq!(milestone.get_status().unwrap(), MilestoneStatus::Planned);
+        assert!(Milestone::new("M001".to_string(), "P001".to_string(), "Beta".to_string(), String::new(), Some("31/03/2026".to_string())).is_err());
+        assert!(Milestone::new("M001".to_string(), "P001".to_string(), " ".to_string(), String::new(), None).is_err());
+    }
 }
 
 // Entity Trait Implementations - Complete replacement with new trait system
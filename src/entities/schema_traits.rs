@@ -265,35 +265,35 @@ pub trait ValidatedEntity: Entity {
         
         match self.entity_type() {
             EntityType::Project => {
-                if IdValidator::validate_project_id(self.id()) {
+                if IdValidator::validate_entity_id(self.id()) {
                     Ok(())
                 } else {
                     Err(format!("Invalid project ID pattern: {}", self.id()))
                 }
             },
             EntityType::Feature => {
-                if IdValidator::validate_feature_id(self.id()) {
+                if IdValidator::validate_entity_id(self.id()) {
                     Ok(())
                 } else {
                     Err(format!("Invalid feature ID pattern: {}", self.id()))
                 }
             },
             EntityType::Task => {
-                if IdValidator::validate_task_id(self.id()) {
+                if IdValidator::validate_entity_id(self.id()) {
                     Ok(())
                 } else {
                     Err(format!("Invalid task ID pattern: {}", self.id()))
                 }
             },
             EntityType::Session => {
-                if IdValidator::validate_session_id(self.id()) {
+                if IdValidator::validate_entity_id(self.id()) {
                     Ok(())
                 } else {
                     Err(format!("Invalid session ID pattern: {}", self.id()))
                 }
             },
             EntityType::Directive => {
-                if IdValidator::validate_directive_id(self.id()) {
+                if IdValidator::validate_entity_id(self.id()) {
                     Ok(())
                 } else {
                     Err(format!("Invalid directive ID pattern: {}", self.id()))
@@ -306,7 +306,7 @@ pub trait ValidatedEntity: Entity {
     fn validate_project_reference(&self) -> Result<(), String> {
         use crate::entities::schema_models::IdValidator;
         
-        if IdValidator::validate_project_id(self.project_id()) {
+        if IdValidator::validate_entity_id(self.project_id()) {
             Ok(())
         } else {
             Err(format!("Invalid project ID reference: {}", self.project_id()))
@@ -0,0 +1,565 @@
+// Sprints - Time-boxed iterations with a capacity and planned tasks
+// `wsb sprint create` opens a sprint over a date range with a capacity in points;
+// `wsb sprint plan` adds tasks to it, each worth its estimated effort (or 1 point) unless
+// `--points` says otherwise. `wsb sprint close` stores the points completed, optionally
+// carrying unfinished tasks into another sprint. Velocity averages the completed points
+// of recent closed sprints; the burndown lists the points left on each day of a sprint,
+// as JSON for the dashboard.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Acquire, Executor, Row, Sqlite, SqliteConnection, SqlitePool};
+
+use crate::entities::events;
+use crate::entities::ids::IdGenerator;
+
+pub async fn initialize_sprint_tables(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS sprints (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            goal TEXT,
+            start_date TEXT NOT NULL,
+            end_date TEXT NOT NULL,
+            capacity INTEGER,
+            status TEXT NOT NULL DEFAULT 'open',
+            completed_points INTEGER,
+            closed_at TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            UNIQUE (project_id, name),
+            CONSTRAINT chk_sprints_status CHECK (status IN ('open', 'closed')),
+            CONSTRAINT chk_sprints_dates CHECK (end_date >= start_date),
+            CONSTRAINT chk_sprints_capacity CHECK (capacity IS NULL OR capacity >= 0)
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS sprint_tasks (
+            sprint_id TEXT NOT NULL,
+            task_id TEXT NOT NULL,
+            points INTEGER NOT NULL DEFAULT 1,
+            added_at TEXT NOT NULL,
+
+            PRIMARY KEY (sprint_id, task_id),
+            FOREIGN KEY (sprint_id) REFERENCES sprints (id) ON DELETE CASCADE,
+            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+            CONSTRAINT chk_sprint_tasks_points CHECK (points >= 0)
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_sprint_tasks_task ON sprint_tasks (task_id)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A stored sprint
+#[derive(Debug, Clone, Serialize)]
+pub struct Sprint {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub goal: Option<String>,
+    /// First day (YYYY-MM-DD)
+    pub start_date: String,
+    /// Last day (YYYY-MM-DD)
+    pub end_date: String,
+    /// Points the team expects to finish; None when not set
+    pub capacity: Option<i64>,
+    /// open or closed
+    pub status: String,
+    /// Points of the tasks completed when the sprint closed
+    pub completed_points: Option<i64>,
+    pub closed_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Sprint {
+    pub fn is_open(&self) -> bool {
+        self.status == "open"
+    }
+}
+
+/// A task planned into a sprint
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintTask {
+    pub task_id: String,
+    pub title: String,
+    pub status: String,
+    pub points: i64,
+    pub completed_at: Option<String>,
+}
+
+impl SprintTask {
+    pub fn is_done(&self) -> bool {
+        self.status == "completed"
+    }
+}
+
+/// A sprint with its tasks and point totals
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintSummary {
+    pub sprint: Sprint,
+    pub tasks: Vec<SprintTask>,
+    pub planned_points: i64,
+    pub completed_points: i64,
+    pub remaining_points: i64,
+    /// Planned points exceed the capacity
+    pub over_capacity: bool,
+}
+
+/// Points left at the end of one sprint day
+#[derive(Debug, Clone, Serialize)]
+pub struct BurndownDay {
+    pub date: String,
+    pub remaining: i64,
+    /// Remaining points of a steady pace from the planned total to zero
+    pub ideal: f64,
+}
+
+/// Remaining points per day, from the sprint's start up to today or its end
+#[derive(Debug, Clone, Serialize)]
+pub struct Burndown {
+    pub sprint_id: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub total_points: i64,
+    pub days: Vec<BurndownDay>,
+}
+
+/// Planned and completed points of one closed sprint
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintVelocity {
+    pub sprint_id: String,
+    pub name: String,
+    pub end_date: String,
+    pub planned_points: i64,
+    pub completed_points: i64,
+}
+
+/// Completed points of recent closed sprints, newest first, and their average
+#[derive(Debug, Clone, Serialize)]
+pub struct Velocity {
+    pub sprints: Vec<SprintVelocity>,
+    pub average: f64,
+}
+
+/// Parse a YYYY-MM-DD date
+pub fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}': expected YYYY-MM-DD", date))
+}
+
+fn from_row(row: &SqliteRow) -> Sprint {
+    Sprint {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        name: row.get("name"),
+        goal: row.get("goal"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        capacity: row.get("capacity"),
+        status: row.get("status"),
+        completed_points: row.get("completed_points"),
+        closed_at: row.get("closed_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// Create an open sprint
+pub async fn create<'c, A: Acquire<'c, Database = Sqlite>>(
+    db: A,
+    project_id: &str,
+    name: &str,
+    goal: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    capacity: Option<i64>,
+) -> Result<Sprint> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Sprint name cannot be empty");
+    }
+    if end_date < start_date {
+        anyhow::bail!("Sprint ends ({}) before it starts ({})", end_date, start_date);
+    }
+    if capacity.map_or(false, |capacity| capacity < 0) {
+        anyhow::bail!("Sprint capacity cannot be negative");
+    }
+
+    let mut tx = db.begin().await?;
+    let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sprints WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(name)
+        .fetch_one(&mut *tx)
+        .await?;
+    if taken > 0 {
+        anyhow::bail!("A sprint named '{}' already exists", name);
+    }
+
+    let id = IdGenerator::next(&mut *tx, project_id, "sprint").await?;
+    let now = Utc::now().to_rfc3339();
+    sqlx::query(r#"
+        INSERT INTO sprints (id, project_id, name, goal, start_date, end_date, capacity, status, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, 'open', ?, ?)
+    "#)
+    .bind(&id)
+    .bind(project_id)
+    .bind(name)
+    .bind(&goal)
+    .bind(start_date.to_string())
+    .bind(end_date.to_string())
+    .bind(capacity)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    events::record(&mut tx, project_id, "sprint", &id, "sprint.created", serde_json::json!({
+        "name": name,
+        "start_date": start_date.to_string(),
+        "end_date": end_date.to_string(),
+        "capacity": capacity,
+    })).await?;
+    let sprint = get(&mut *tx, &id).await?.ok_or_else(|| anyhow::anyhow!("Sprint vanished after create: {}", id))?;
+    tx.commit().await?;
+    Ok(sprint)
+}
+
+/// Sprint by ID
+pub async fn get<'e, E: Executor<'e, Database = Sqlite>>(db: E, id: &str) -> Result<Option<Sprint>> {
+    let row = sqlx::query("SELECT * FROM sprints WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.as_ref().map(from_row))
+}
+
+/// A project's sprints, latest start first
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<Sprint>> {
+    let rows = sqlx::query("SELECT * FROM sprints WHERE project_id = ? ORDER BY start_date DESC, id DESC")
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(from_row).collect())
+}
+
+/// Live tasks of a sprint; cancelled and deleted tasks drop out of its points
+async fn sprint_tasks(conn: &mut SqliteConnection, sprint_id: &str) -> Result<Vec<SprintTask>> {
+    let rows = sqlx::query(r#"
+        SELECT st.task_id, st.points, t.title, t.status, t.completed_at
+        FROM sprint_tasks st JOIN tasks t ON t.id = st.task_id
+        WHERE st.sprint_id = ? AND t.deleted_at IS NULL AND t.status != 'cancelled'
+        ORDER BY st.added_at, st.task_id
+    "#)
+    .bind(sprint_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SprintTask {
+            task_id: row.get("task_id"),
+            title: row.get("title"),
+            status: row.get("status"),
+            points: row.get("points"),
+            completed_at: row.get("completed_at"),
+        })
+        .collect())
+}
+
+async fn summarize(conn: &mut SqliteConnection, sprint: Sprint) -> Result<SprintSummary> {
+    let tasks = sprint_tasks(conn, &sprint.id).await?;
+    let planned_points: i64 = tasks.iter().map(|task| task.points).sum();
+    let completed_points: i64 = tasks.iter().filter(|task| task.is_done()).map(|task| task.points).sum();
+    let over_capacity = sprint.capacity.map_or(false, |capacity| planned_points > capacity);
+    Ok(SprintSummary {
+        sprint,
+        tasks,
+        planned_points,
+        completed_points,
+        remaining_points: planned_points - completed_points,
+        over_capacity,
+    })
+}
+
+/// A sprint with its tasks and point totals
+pub async fn summary<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<SprintSummary> {
+    let mut conn = db.acquire().await?;
+    let sprint = get(&mut *conn, id).await?.ok_or_else(|| anyhow::anyhow!("Sprint not found: {}", id))?;
+    summarize(&mut conn, sprint).await
+}
+
+async fn open_sprint(conn: &mut SqliteConnection, id: &str) -> Result<Sprint> {
+    let sprint = get(&mut *conn, id).await?.ok_or_else(|| anyhow::anyhow!("Sprint not found: {}", id))?;
+    if !sprint.is_open() {
+        anyhow::bail!("Sprint {} is closed", id);
+    }
+    Ok(sprint)
+}
+
+/// Add tasks to an open sprint. A task is worth `points`, or else its estimated effort,
+/// or else 1 point; tasks already in the sprint get `points` when it is given. A task can
+/// be in one open sprint at a time.
+pub async fn plan<'c, A: Acquire<'c, Database = Sqlite>>(db: A, sprint_id: &str, task_ids: &[String], points: Option<i64>) -> Result<SprintSummary> {
+    if points.map_or(false, |points| points < 0) {
+        anyhow::bail!("Task points cannot be negative");
+    }
+    let mut tx = db.begin().await?;
+    let sprint = open_sprint(&mut tx, sprint_id).await?;
+
+    for task_id in task_ids {
+        let task = sqlx::query("SELECT project_id, estimated_effort FROM tasks WHERE id = ? AND deleted_at IS NULL")
+            .bind(task_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}", task_id))?;
+        if task.get::<String, _>("project_id") != sprint.project_id {
+            anyhow::bail!("Task {} belongs to another project", task_id);
+        }
+        let elsewhere: Option<String> = sqlx::query_scalar(r#"
+            SELECT s.id FROM sprint_tasks st JOIN sprints s ON s.id = st.sprint_id
+            WHERE st.task_id = ? AND s.status = 'open' AND s.id != ?
+        "#)
+        .bind(task_id)
+        .bind(sprint_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(other) = elsewhere {
+            anyhow::bail!("Task {} is already planned in open sprint {}", task_id, other);
+        }
+
+        let task_points = points.unwrap_or_else(|| task.get::<Option<i64>, _>("estimated_effort").unwrap_or(1).max(0));
+        let query = if points.is_some() {
+            "INSERT INTO sprint_tasks (sprint_id, task_id, points, added_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT (sprint_id, task_id) DO UPDATE SET points = excluded.points"
+        } else {
+            "INSERT OR IGNORE INTO sprint_tasks (sprint_id, task_id, points, added_at) VALUES (?, ?, ?, ?)"
+        };
+        sqlx::query(query)
+            .bind(sprint_id)
+            .bind(task_id)
+            .bind(task_points)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    events::record(&mut tx, &sprint.project_id, "sprint", sprint_id, "sprint.planned", serde_json::json!({
+        "added": task_ids,
+    })).await?;
+    let summary = summarize(&mut tx, sprint).await?;
+    tx.commit().await?;
+    Ok(summary)
+}
+
+/// Take tasks out of an open sprint
+pub async fn unplan<'c, A: Acquire<'c, Database = Sqlite>>(db: A, sprint_id: &str, task_ids: &[String]) -> Result<SprintSummary> {
+    let mut tx = db.begin().await?;
+    let sprint = open_sprint(&mut tx, sprint_id).await?;
+    for task_id in task_ids {
+        let removed = sqlx::query("DELETE FROM sprint_tasks WHERE sprint_id = ? AND task_id = ?")
+            .bind(sprint_id)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if removed == 0 {
+            anyhow::bail!("Task {} is not planned in sprint {}", task_id, sprint_id);
+        }
+    }
+
+    events::record(&mut tx, &sprint.project_id, "sprint", sprint_id, "sprint.planned", serde_json::json!({
+        "removed": task_ids,
+    })).await?;
+    let summary = summarize(&mut tx, sprint).await?;
+    tx.commit().await?;
+    Ok(summary)
+}
+
+/// Close an open sprint, storing the points completed. Unfinished tasks stay in the
+/// closed sprint's history and, with `carry_over`, are also planned into that open sprint.
+pub async fn close<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str, carry_over: Option<&str>) -> Result<SprintSummary> {
+    let mut tx = db.begin().await?;
+    let sprint = open_sprint(&mut tx, id).await?;
+    let before = summarize(&mut tx, sprint).await?;
+
+    let mut carried = Vec::new();
+    if let Some(target_id) = carry_over {
+        if target_id == id {
+            anyhow::bail!("Cannot carry tasks over into the sprint being closed");
+        }
+        let target = open_sprint(&mut tx, target_id).await?;
+        if target.project_id != before.sprint.project_id {
+            anyhow::bail!("Sprint {} belongs to another project", target_id);
+        }
+        for task in before.tasks.iter().filter(|task| !task.is_done()) {
+            sqlx::query("INSERT OR IGNORE INTO sprint_tasks (sprint_id, task_id, points, added_at) VALUES (?, ?, ?, ?)")
+                .bind(target_id)
+                .bind(&task.task_id)
+                .bind(task.points)
+                .bind(Utc::now().to_rfc3339())
+                .execute(&mut *tx)
+                .await?;
+            carried.push(task.task_id.clone());
+        }
+    }
+
+    let now = Utc::now().to_rfc3339();
+    sqlx::query("UPDATE sprints SET status = 'closed', completed_points = ?, closed_at = ?, updated_at = ? WHERE id = ?")
+        .bind(before.completed_points)
+        .bind(&now)
+        .bind(&now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    events::record(&mut tx, &before.sprint.project_id, "sprint", id, "sprint.closed", serde_json::json!({
+        "planned_points": before.planned_points,
+        "completed_points": before.completed_points,
+        "carried_over": carried,
+        "carried_into": carry_over,
+    })).await?;
+    let sprint = get(&mut *tx, id).await?.ok_or_else(|| anyhow::anyhow!("Sprint vanished after close: {}", id))?;
+    let summary = summarize(&mut tx, sprint).await?;
+    tx.commit().await?;
+    Ok(summary)
+}
+
+/// Completed points of the project's `last` most recent closed sprints
+pub async fn velocity(pool: &SqlitePool, project_id: &str, last: usize) -> Result<Velocity> {
+    let rows = sqlx::query(r#"
+        SELECT s.id, s.name, s.end_date, COALESCE(s.completed_points, 0) AS completed_points,
+               (SELECT COALESCE(SUM(st.points), 0) FROM sprint_tasks st WHERE st.sprint_id = s.id) AS planned_points
+        FROM sprints s
+        WHERE s.project_id = ? AND s.status = 'closed'
+        ORDER BY s.end_date DESC, s.id DESC
+        LIMIT ?
+    "#)
+    .bind(project_id)
+    .bind(last as i64)
+    .fetch_all(pool)
+    .await?;
+
+    let sprints: Vec<SprintVelocity> = rows
+        .iter()
+        .map(|row| SprintVelocity {
+            sprint_id: row.get("id"),
+            name: row.get("name"),
+            end_date: row.get("end_date"),
+            planned_points: row.get("planned_points"),
+            completed_points: row.get("completed_points"),
+        })
+        .collect();
+    let average = if sprints.is_empty() {
+        0.0
+    } else {
+        let total: i64 = sprints.iter().map(|sprint| sprint.completed_points).sum();
+        (total as f64 / sprints.len() as f64 * 10.0).round() / 10.0
+    };
+    Ok(Velocity { sprints, average })
+}
+
+/// Remaining points at the end of each sprint day, through today for a running sprint.
+/// Tasks count as burnt down on the day they were completed (UTC); ones completed before
+/// the sprint started are done from its first day.
+pub async fn burndown<'c, A: Acquire<'c, Database = Sqlite>>(db: A, id: &str) -> Result<Burndown> {
+    let summary = summary(db, id).await?;
+    let start = parse_date(&summary.sprint.start_date)?;
+    let end = parse_date(&summary.sprint.end_date)?;
+    let last = if summary.sprint.is_open() { end.min(Utc::now().date_naive()) } else { end };
+
+    let sprint_days = (end - start).num_days();
+    let mut days = Vec::new();
+    let mut day = start;
+    while day <= last {
+        let date = day.to_string();
+        let burnt: i64 = summary
+            .tasks
+            .iter()
+            .filter(|task| task.is_done())
+            .filter(|task| task.completed_at.as_deref().map_or(true, |completed| completed.get(..10).map_or(true, |completed| completed <= date.as_str())))
+            .map(|task| task.points)
+            .sum();
+        let elapsed = (day - start).num_days();
+        let ideal = if sprint_days == 0 {
+            0.0
+        } else {
+            let left = summary.planned_points as f64 * (1.0 - elapsed as f64 / sprint_days as f64);
+            (left * 10.0).round() / 10.0
+        };
+        days.push(BurndownDay { date, remaining: summary.planned_points - burnt, ideal });
+        day += Duration::days(1);
+    }
+
+    Ok(Burndown {
+        sprint_id: summary.sprint.id,
+        start_date: summary.sprint.start_date,
+        end_date: summary.sprint.end_date,
+        total_points: summary.planned_points,
+        days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use crate::entities::schema_models::TaskStatus;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_plan_close_velocity_and_burndown() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Sprints".to_string(), "Iterations".to_string()).await.unwrap();
+        let feature = crud::features::create(&pool, project.id.clone(), "Planning".to_string(), "Sprints".to_string(), None).await.unwrap();
+        let mut task_ids = Vec::new();
+        for title in ["Schema", "Commands", "Docs"] {
+            let task = crud::tasks::create(&pool, project.id.clone(), feature.id.clone(), title.to_string(), "feature".to_string()).await.unwrap();
+            task_ids.push(task.id);
+        }
+
+        let today = Utc::now().date_naive();
+        let first = create(&pool, &project.id, "Sprint 1", None, today - Duration::days(2), today + Duration::days(2), Some(4)).await.unwrap();
+        assert!(create(&pool, &project.id, "Sprint 1", None, today, today, None).await.is_err());
+        assert!(create(&pool, &project.id, "Backwards", None, today, today - Duration::days(1), None).await.is_err());
+
+        let planned = plan(&pool, &first.id, &task_ids[..2], None).await.unwrap();
+        assert_eq!(planned.planned_points, 2);
+        let planned = plan(&pool, &first.id, &task_ids[2..], Some(3)).await.unwrap();
+        assert_eq!(planned.planned_points, 5);
+        assert!(planned.over_capacity);
+
+        let second = create(&pool, &project.id, "Sprint 2", None, today + Duration::days(3), today + Duration::days(9), None).await.unwrap();
+        assert!(plan(&pool, &second.id, &task_ids[..1], None).await.is_err());
+
+        crud::tasks::update_status(&pool, &task_ids[2], TaskStatus::Completed).await.unwrap();
+        let burndown = burndown(&pool, &first.id).await.unwrap();
+        assert_eq!(burndown.days.len(), 3);
+        assert_eq!(burndown.days[0].ideal, 5.0);
+        assert_eq!(burndown.days.last().unwrap().remaining, 2);
+
+        let closed = close(&pool, &first.id, Some(&second.id)).await.unwrap();
+        assert_eq!(closed.sprint.completed_points, Some(3));
+        assert!(plan(&pool, &first.id, &task_ids[..1], None).await.is_err());
+        assert_eq!(summary(&pool, &second.id).await.unwrap().tasks.len(), 2);
+
+        let velocity = velocity(&pool, &project.id, 3).await.unwrap();
+        assert_eq!(velocity.sprints.len(), 1);
+        assert_eq!(velocity.average, 3.0);
+    }
+}
@@ -0,0 +1,700 @@
+// Markdown Sync - Keeps internal/features.md and internal/task_backlog.md in step with the database
+// Some commands read the markdown files and others the database, so edits made through one
+// drift from the other. `wsb sync` compares both sides with what they held after the last
+// sync of the project (its base, kept in workspace_state): a record changed on one side only
+// is copied to the other, one changed on both is a conflict. The canonical source settles
+// conflicts when one is set; otherwise both sides keep their version until the user picks
+// one. The one-way directions copy everything from their source side and ignore the base.
+// Sync owns the layout of both files and rewrites them whole from the merged records.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::entities::crud;
+use crate::entities::schema_models::{Feature, FeatureState, Priority, Project, Task, TaskStatus};
+use crate::entities::workspace;
+
+/// Key of the side that wins conflicts in two-way syncs (`db` or `md`)
+pub const CANONICAL_SOURCE: &str = "sync.canonical_source";
+/// Key prefix of each project's base, the records as of its last sync
+const BASE_PREFIX: &str = "sync.base.";
+
+pub const FEATURES_FILE: &str = "internal/features.md";
+pub const TASKS_FILE: &str = "internal/task_backlog.md";
+
+const FEATURE_STATES: [FeatureState; 6] = [
+    FeatureState::NotImplemented,
+    FeatureState::ImplementedNoTests,
+    FeatureState::ImplementedFailingTests,
+    FeatureState::ImplementedPassingTests,
+    FeatureState::TestsBroken,
+    FeatureState::CriticalIssue,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    DbToMd,
+    MdToDb,
+    TwoWay,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::DbToMd => "db-to-md",
+            Direction::MdToDb => "md-to-db",
+            Direction::TwoWay => "two-way",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "db-to-md" => Ok(Direction::DbToMd),
+            "md-to-db" => Ok(Direction::MdToDb),
+            "two-way" => Ok(Direction::TwoWay),
+            _ => anyhow::bail!("Unknown sync direction '{}' (expected db-to-md, md-to-db or two-way)", s),
+        }
+    }
+}
+
+/// One side of a sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Db,
+    Md,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Db => "db",
+            Source::Md => "md",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "db" => Ok(Source::Db),
+            "md" => Ok(Source::Md),
+            _ => anyhow::bail!("Unknown sync source '{}' (expected db or md)", s),
+        }
+    }
+}
+
+/// The side that wins conflicts in two-way syncs, if one is set
+pub async fn canonical_source(pool: &SqlitePool) -> Result<Option<Source>> {
+    workspace::get(pool, CANONICAL_SOURCE).await?.map(|s| Source::from_str(&s)).transpose()
+}
+
+/// Set or, with None, clear the side that wins conflicts
+pub async fn set_canonical_source(pool: &SqlitePool, source: Option<Source>) -> Result<()> {
+    match source {
+        Some(source) => workspace::set(pool, CANONICAL_SOURCE, source.as_str()).await,
+        None => workspace::clear(pool, CANONICAL_SOURCE).await,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Feature,
+    Task,
+}
+
+impl Kind {
+    pub fn file(&self) -> &'static str {
+        match self {
+            Kind::Feature => FEATURES_FILE,
+            Kind::Task => TASKS_FILE,
+        }
+    }
+}
+
+/// The fields of a feature or task that both sides carry, flattened to one line each
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub kind: Kind,
+    pub id: String,
+    /// Feature name or task title
+    pub title: String,
+    /// Feature state or task status, as the database stores it
+    pub state: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub category: String,
+    /// Task priority
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub priority: String,
+    /// Feature a task belongs to
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub feature: String,
+}
+
+impl Record {
+    fn new(kind: Kind, id: &str) -> Self {
+        Self {
+            kind,
+            id: id.to_string(),
+            title: String::new(),
+            state: String::new(),
+            description: String::new(),
+            category: String::new(),
+            priority: String::new(),
+            feature: String::new(),
+        }
+    }
+
+    fn from_feature(feature: &Feature) -> Self {
+        Self {
+            title: one_line(&feature.name),
+            state: feature.state.clone(),
+            description: one_line(&feature.description),
+            category: one_line(feature.category.as_deref().unwrap_or("")),
+            ..Self::new(Kind::Feature, &feature.id)
+        }
+    }
+
+    fn from_task(task: &Task) -> Self {
+        Self {
+            title: one_line(&task.task),
+            state: task.status.clone(),
+            priority: task.priority.clone(),
+            feature: task.feature_id.clone(),
+            ..Self::new(Kind::Task, &task.id)
+        }
+    }
+
+    /// Names of the fields that differ from `other`
+    pub fn changed_fields(&self, other: &Record) -> Vec<&'static str> {
+        let fields = [
+            ("title", &self.title, &other.title),
+            ("state", &self.state, &other.state),
+            ("description", &self.description, &other.description),
+            ("category", &self.category, &other.category),
+            ("priority", &self.priority, &other.priority),
+            ("feature", &self.feature, &other.feature),
+        ];
+        fields.into_iter().filter(|(_, a, b)| a != b).map(|(name, _, _)| name).collect()
+    }
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Where a record is copied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    ToDb,
+    ToMd,
+    /// Changed on both sides since the last sync, with no canonical source to settle it
+    Conflict,
+}
+
+/// A record the two sides disagree on
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub kind: Kind,
+    /// As written in the markdown for records added there
+    pub id: String,
+    pub db: Option<Record>,
+    pub md: Option<Record>,
+    pub action: Action,
+}
+
+impl Change {
+    /// One line for the user, e.g. `F00003 updated in the database (state)`
+    pub fn describe(&self) -> String {
+        let (target, before, after) = match self.action {
+            Action::ToDb => ("the database", &self.db, &self.md),
+            Action::ToMd => (self.kind.file(), &self.md, &self.db),
+            Action::Conflict => {
+                return match (&self.db, &self.md) {
+                    (Some(db), Some(md)) => format!("{} changed on both sides ({})", self.id, db.changed_fields(md).join(", ")),
+                    (Some(_), None) => format!("{} changed in the database but removed from {}", self.id, self.kind.file()),
+                    _ => format!("{} changed in {} but deleted from the database", self.id, self.kind.file()),
+                };
+            }
+        };
+        match (before, after) {
+            (None, _) => format!("{} added to {}", self.id, target),
+            (Some(_), None) => format!("{} removed from {}", self.id, target),
+            (Some(before), Some(after)) => format!("{} updated in {} ({})", self.id, target, before.changed_fields(after).join(", ")),
+        }
+    }
+}
+
+/// What a sync of the current project would do
+#[derive(Debug)]
+pub struct Plan {
+    pub project: Project,
+    pub direction: Direction,
+    pub canonical: Option<Source>,
+    pub changes: Vec<Change>,
+    /// Records both sides agree on
+    pub unchanged: usize,
+    base: BTreeMap<String, Record>,
+}
+
+impl Plan {
+    pub fn conflicts(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.action == Action::Conflict)
+    }
+}
+
+/// What applying a plan did beyond its changes
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// Markdown IDs of added records and the database IDs they were given
+    pub assigned: Vec<(String, String)>,
+    /// Files whose content changed
+    pub written: Vec<&'static str>,
+}
+
+/// Compare the current project's database records with the markdown files under `root`
+pub async fn plan(pool: &SqlitePool, root: &Path, direction: Direction) -> Result<Plan> {
+    let project = workspace::current_project(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+    let canonical = canonical_source(pool).await?;
+    let base = load_base(pool, &project.id).await?;
+    let db = load_db(pool, &project.id).await?;
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    for kind in [Kind::Feature, Kind::Task] {
+        let path = root.join(kind.file());
+        let md = if path.exists() {
+            let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            match kind {
+                Kind::Feature => parse_features(&text)?,
+                Kind::Task => parse_tasks(&text)?,
+            }
+        } else if direction == Direction::MdToDb {
+            anyhow::bail!("{} not found; there is nothing to copy to the database", kind.file());
+        } else {
+            // Written from the database as if it had always agreed with it
+            db.values().filter(|record| record.kind == kind).cloned().collect()
+        };
+
+        let mut known = BTreeMap::new();
+        for record in md {
+            if db.contains_key(&record.id) || base.contains_key(&record.id) {
+                if known.insert(record.id.clone(), record.clone()).is_some() {
+                    anyhow::bail!("{} lists {} more than once", kind.file(), record.id);
+                }
+            } else {
+                let action = if direction == Direction::DbToMd { Action::ToMd } else { Action::ToDb };
+                changes.push(Change { kind, id: record.id.clone(), db: None, md: Some(record), action });
+            }
+        }
+
+        let ids: BTreeSet<&String> = db.iter()
+            .chain(base.iter())
+            .filter(|(_, record)| record.kind == kind)
+            .map(|(id, _)| id)
+            .chain(known.keys())
+            .collect();
+        for id in ids {
+            let (db_side, md_side, base_side) = (db.get(id), known.get(id), base.get(id));
+            if db_side == md_side {
+                unchanged += usize::from(db_side.is_some());
+                continue;
+            }
+            let action = match direction {
+                Direction::DbToMd => Action::ToMd,
+                Direction::MdToDb => Action::ToDb,
+                Direction::TwoWay => match (db_side != base_side, md_side != base_side) {
+                    (true, false) => Action::ToMd,
+                    (false, true) => Action::ToDb,
+                    _ => match canonical {
+                        Some(Source::Db) => Action::ToMd,
+                        Some(Source::Md) => Action::ToDb,
+                        None => Action::Conflict,
+                    },
+                },
+            };
+            changes.push(Change { kind, id: id.clone(), db: db_side.cloned(), md: md_side.cloned(), action });
+        }
+    }
+
+    Ok(Plan { project, direction, canonical, changes, unchanged, base })
+}
+
+/// Carry out `plan`: database changes in one transaction, then the markdown files are
+/// rewritten from the database, keeping their own version of conflicting records. The base
+/// is saved last, so a run that fails part way is picked up again by the next one.
+pub async fn apply(pool: &SqlitePool, root: &Path, plan: &Plan) -> Result<Outcome> {
+    let mut outcome = Outcome::default();
+    let mut tx = pool.begin().await?;
+    // Features come first, so tasks can link to features added in the same run
+    for change in plan.changes.iter().filter(|change| change.action == Action::ToDb) {
+        match (&change.db, &change.md) {
+            (_, None) => delete(&mut tx, change.kind, &change.id).await?,
+            (None, Some(md)) => {
+                let id = create(&mut tx, &plan.project.id, md, &outcome.assigned).await?;
+                if id != md.id {
+                    outcome.assigned.push((md.id.clone(), id));
+                }
+            }
+            (Some(db), Some(md)) => update(&mut tx, db, md, &outcome.assigned).await?,
+        }
+    }
+    tx.commit().await?;
+
+    let mut merged = load_db(pool, &plan.project.id).await?;
+    let mut base = merged.clone();
+    for conflict in plan.conflicts() {
+        merged.remove(&conflict.id);
+        base.remove(&conflict.id);
+        if let Some(md) = &conflict.md {
+            merged.insert(conflict.id.clone(), md.clone());
+        }
+        if let Some(previous) = plan.base.get(&conflict.id) {
+            base.insert(conflict.id.clone(), previous.clone());
+        }
+    }
+
+    for kind in [Kind::Feature, Kind::Task] {
+        let records: Vec<&Record> = merged.values().filter(|record| record.kind == kind).collect();
+        let text = match kind {
+            Kind::Feature => render_features(&plan.project, &records),
+            Kind::Task => render_tasks(&plan.project, &records),
+        };
+        let path = root.join(kind.file());
+        if fs::read_to_string(&path).ok().as_deref() == Some(text.as_str()) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+        outcome.written.push(kind.file());
+    }
+
+    workspace::set(pool, &format!("{}{}", BASE_PREFIX, plan.project.id), &serde_json::to_string(&base)?).await?;
+    Ok(outcome)
+}
+
+async fn load_base(pool: &SqlitePool, project_id: &str) -> Result<BTreeMap<String, Record>> {
+    match workspace::get(pool, &format!("{}{}", BASE_PREFIX, project_id)).await? {
+        Some(json) => serde_json::from_str(&json).context("Unreadable sync base; reset it with a one-way sync"),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+async fn load_db(pool: &SqlitePool, project_id: &str) -> Result<BTreeMap<String, Record>> {
+    let features = crud::features::list_by_project(pool, project_id).await?;
+    let tasks = crud::tasks::list_by_project(pool, project_id, None).await?;
+    Ok(features.iter().map(Record::from_feature)
+        .chain(tasks.iter().map(Record::from_task))
+        .map(|record| (record.id.clone(), record))
+        .collect())
+}
+
+/// The database ID a markdown feature reference stands for
+fn resolve_feature(feature: &str, assigned: &[(String, String)]) -> String {
+    assigned.iter()
+        .find(|(md_id, _)| md_id == feature)
+        .map(|(_, id)| id.clone())
+        .unwrap_or_else(|| feature.to_string())
+}
+
+fn parse_feature_state(state: &str) -> Result<FeatureState> {
+    FeatureState::from_str(state).map_err(|e| anyhow::anyhow!(e))
+}
+
+async fn create(conn: &mut SqliteConnection, project_id: &str, md: &Record, assigned: &[(String, String)]) -> Result<String> {
+    match md.kind {
+        Kind::Feature => {
+            let category = Some(md.category.clone()).filter(|category| !category.is_empty());
+            let feature = crud::features::create(&mut *conn, project_id.to_string(), md.title.clone(), md.description.clone(), category)
+                .await
+                .with_context(|| format!("Failed to add {} from {}", md.id, FEATURES_FILE))?;
+            if feature.state != md.state {
+                crud::features::update_state(&mut *conn, &feature.id, parse_feature_state(&md.state)?).await?;
+            }
+            Ok(feature.id)
+        }
+        Kind::Task => {
+            let feature = resolve_feature(&md.feature, assigned);
+            require_feature(conn, md, &feature).await?;
+            let task = crud::tasks::create(&mut *conn, project_id.to_string(), feature, md.title.clone(), "feature".to_string())
+                .await
+                .with_context(|| format!("Failed to add {} from {}", md.id, TASKS_FILE))?;
+            update(conn, &Record::from_task(&task), &Record { id: task.id.clone(), ..md.clone() }, assigned).await?;
+            Ok(task.id)
+        }
+    }
+}
+
+/// Copy the fields in which `md` differs from `db` to the database
+async fn update(conn: &mut SqliteConnection, db: &Record, md: &Record, assigned: &[(String, String)]) -> Result<()> {
+    match md.kind {
+        Kind::Feature => {
+            let mut feature = crud::features::get_by_id(&mut *conn, &md.id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Feature not found: {}", md.id))?;
+            if md.title != db.title {
+                feature.name = md.title.clone();
+            }
+            if md.description != db.description {
+                feature.description = md.description.clone();
+            }
+            if md.category != db.category {
+                feature.category = Some(md.category.clone()).filter(|category| !category.is_empty());
+            }
+            crud::features::update(&mut *conn, &feature).await?;
+            if md.state != db.state {
+                crud::features::update_state(&mut *conn, &md.id, parse_feature_state(&md.state)?).await?;
+            }
+        }
+        Kind::Task => {
+            let mut task = crud::tasks::get_by_id(&mut *conn, &md.id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Task not found: {}", md.id))?;
+            if md.title != db.title {
+                task.task = md.title.clone();
+            }
+            if md.feature != db.feature {
+                task.feature_id = resolve_feature(&md.feature, assigned);
+                require_feature(conn, md, &task.feature_id).await?;
+            }
+            task.priority = md.priority.clone();
+            crud::tasks::update(&mut *conn, &task).await?;
+            if md.state != db.state {
+                let status = TaskStatus::from_str(&md.state).map_err(|e| anyhow::anyhow!(e))?;
+                crud::tasks::update_status(&mut *conn, &md.id, status).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn require_feature(conn: &mut SqliteConnection, task: &Record, feature: &str) -> Result<()> {
+    if crud::features::get_by_id(&mut *conn, feature).await?.is_none() {
+        anyhow::bail!("{} in {} belongs to feature '{}', which is not in the database", task.id, TASKS_FILE, task.feature);
+    }
+    Ok(())
+}
+
+async fn delete(conn: &mut SqliteConnection, kind: Kind, id: &str) -> Result<()> {
+    match kind {
+        Kind::Feature => crud::features::delete(&mut *conn, id).await,
+        // Deleting a feature earlier in the run takes its tasks with it
+        Kind::Task if crud::tasks::get_by_id(&mut *conn, id).await?.is_none() => Ok(()),
+        Kind::Task => crud::tasks::delete(&mut *conn, id).await,
+    }
+}
+
+/// Features in the table rows of features.md: `| ID | **Name** | Description | State | Category |`.
+/// The state may be its emoji or its name; rows whose first cell is not a feature ID are skipped.
+pub fn parse_features(text: &str) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if !line.starts_with('|') {
+            continue;
+        }
+        let cells = split_row(line);
+        let is_feature_id = cells[0].strip_prefix('F').is_some_and(|digits| digits.chars().all(|c| c.is_ascii_digit()));
+        if cells.len() < 5 || !is_feature_id {
+            continue;
+        }
+        let state = FEATURE_STATES.iter()
+            .find(|state| cells[3] == state.as_str() || cells[3].starts_with(state.emoji().trim_end_matches('\u{fe0f}')))
+            .ok_or_else(|| anyhow::anyhow!("{} line {}: unknown feature state '{}'", FEATURES_FILE, number + 1, cells[3]))?;
+        records.push(Record {
+            title: one_line(cells[1].trim_matches('*')),
+            state: state.as_str().to_string(),
+            description: one_line(&cells[2]),
+            category: one_line(&cells[4]),
+            ..Record::new(Kind::Feature, &cells[0])
+        });
+    }
+    Ok(records)
+}
+
+/// Cells of a table row, with `\|` standing for a literal pipe
+fn split_row(line: &str) -> Vec<String> {
+    let inner = line.strip_prefix('|').unwrap_or(line);
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// Tasks under `### ID - Title (priority)` headings of task_backlog.md, with `**Status**`,
+/// `**Priority**` and `**Feature**` lines below the heading
+pub fn parse_tasks(text: &str) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    let mut current: Option<Record> = None;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            records.extend(current.take());
+            let Some((id, heading)) = line.strip_prefix("### ").and_then(|heading| heading.split_once(" - ")) else {
+                continue;
+            };
+            let mut record = Record { state: TaskStatus::Pending.as_str().to_string(), ..Record::new(Kind::Task, id.trim()) };
+            record.title = one_line(heading);
+            if let Some((title, priority)) = heading.rsplit_once(" (") {
+                let priority = priority.trim_end_matches(')').to_lowercase();
+                if Priority::from_str(&priority).is_ok() {
+                    record.title = one_line(title);
+                    record.priority = priority;
+                }
+            }
+            if record.priority.is_empty() {
+                record.priority = Priority::Medium.as_str().to_string();
+            }
+            current = Some(record);
+            continue;
+        }
+        let Some(record) = current.as_mut() else {
+            continue;
+        };
+        let location = || format!("{} line {}", TASKS_FILE, number + 1);
+        if let Some(status) = field(line, "Status") {
+            let status = TaskStatus::from_str(&status.to_lowercase()).map_err(|e| anyhow::anyhow!("{}: {}", location(), e))?;
+            record.state = status.as_str().to_string();
+        } else if let Some(priority) = field(line, "Priority") {
+            let priority = Priority::from_str(&priority.to_lowercase()).map_err(|e| anyhow::anyhow!("{}: {}", location(), e))?;
+            record.priority = priority.as_str().to_string();
+        } else if let Some(feature) = field(line, "Feature") {
+            record.feature = if feature == "None" { String::new() } else { feature.to_string() };
+        }
+    }
+    records.extend(current);
+    Ok(records)
+}
+
+fn field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    line.strip_prefix("**")?.strip_prefix(name)?.strip_prefix("**:").map(str::trim)
+}
+
+pub fn render_features(project: &Project, features: &[&Record]) -> String {
+    let mut out = format!("# {} - Features\n\n", project.name);
+    out.push_str("Kept in step with the project database by `wsb sync`; edit either side and sync again.\n");
+    out.push_str("States: ❌ not implemented, 🟠 no tests, 🟡 failing tests, 🟢 passing tests, ⚠️ tests broken, 🔴 critical issue.\n\n");
+    out.push_str("| ID | Feature | Description | State | Category |\n|---|---|---|---|---|\n");
+    for feature in features {
+        let state = parse_feature_state(&feature.state).map(|state| state.emoji()).unwrap_or("❌");
+        out.push_str(&format!(
+            "| {} | **{}** | {} | {} | {} |\n",
+            feature.id,
+            escape_cell(&feature.title),
+            escape_cell(&feature.description),
+            state,
+            escape_cell(&feature.category)
+        ));
+    }
+    out
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+pub fn render_tasks(project: &Project, tasks: &[&Record]) -> String {
+    let mut out = format!("# {} - Task Backlog\n\n", project.name);
+    out.push_str("Kept in step with the project database by `wsb sync`; edit either side and sync again.\n");
+    for (heading, done) in [("Active Tasks", false), ("Completed Tasks", true)] {
+        out.push_str(&format!("\n## {}\n", heading));
+        for task in tasks.iter().filter(|task| matches!(task.state.as_str(), "completed" | "cancelled") == done) {
+            out.push_str(&format!(
+                "\n### {} - {} ({})\n**Priority**: {}\n**Status**: {}\n**Feature**: {}\n",
+                task.id,
+                task.title,
+                task.priority,
+                task.priority,
+                task.state,
+                if task.feature.is_empty() { "None" } else { &task.feature }
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_two_way_sync_and_conflicts() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let pool = crate::entities::database::initialize_database(&root.join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Sync".to_string(), "Drift".to_string()).await.unwrap();
+        let login = crud::features::create(&pool, project.id.clone(), "Login".to_string(), "Sign in".to_string(), None).await.unwrap();
+        let task = crud::tasks::create(&pool, project.id.clone(), login.id.clone(), "Form".to_string(), "feature".to_string()).await.unwrap();
+
+        // First sync writes both files from the database
+        let first = plan(&pool, root, Direction::TwoWay).await.unwrap();
+        assert!(first.changes.is_empty());
+        assert_eq!(apply(&pool, root, &first).await.unwrap().written, vec![FEATURES_FILE, TASKS_FILE]);
+        let features_md = fs::read_to_string(root.join(FEATURES_FILE)).unwrap();
+        assert!(features_md.contains(&format!("| {} | **Login** | Sign in | ❌ |  |", login.id)));
+        let tasks_md = fs::read_to_string(root.join(TASKS_FILE)).unwrap();
+        assert!(tasks_md.contains(&format!("### {} - Form (medium)", task.id)));
+        assert_eq!(parse_tasks(&tasks_md).unwrap()[0].feature, login.id);
+
+        // A markdown edit and a new row go to the database; a database edit comes back
+        let edited = features_md.replace("| ❌ |", "| 🟠 |") + "| F | **Audit \\| trail** | Who did what | 🟢 | Security |\n";
+        fs::write(root.join(FEATURES_FILE), edited).unwrap();
+        crud::tasks::update_status(&pool, &task.id, TaskStatus::Completed).await.unwrap();
+        let second = plan(&pool, root, Direction::TwoWay).await.unwrap();
+        assert_eq!(second.changes.len(), 3);
+        let outcome = apply(&pool, root, &second).await.unwrap();
+        assert_eq!(outcome.assigned.len(), 1);
+        assert_eq!(crud::features::get_by_id(&pool, &login.id).await.unwrap().unwrap().state, "implemented_no_tests");
+        let audit = crud::features::get_by_id(&pool, &outcome.assigned[0].1).await.unwrap().unwrap();
+        assert_eq!((audit.name.as_str(), audit.state.as_str()), ("Audit | trail", "implemented_passing_tests"));
+        assert!(fs::read_to_string(root.join(TASKS_FILE)).unwrap().contains("**Status**: completed"));
+        assert!(plan(&pool, root, Direction::TwoWay).await.unwrap().changes.is_empty());
+
+        // Both sides changing the same feature is a conflict until a canonical source is set
+        let features_md = fs::read_to_string(root.join(FEATURES_FILE)).unwrap();
+        fs::write(root.join(FEATURES_FILE), features_md.replace("**Login**", "**Sign-in**")).unwrap();
+        crud::features::update_state(&pool, &login.id, FeatureState::CriticalIssue).await.unwrap();
+        let conflicted = plan(&pool, root, Direction::TwoWay).await.unwrap();
+        assert_eq!(conflicted.conflicts().count(), 1);
+        assert!(conflicted.changes[0].describe().contains("changed on both sides (title, state)"));
+        apply(&pool, root, &conflicted).await.unwrap();
+        assert!(fs::read_to_string(root.join(FEATURES_FILE)).unwrap().contains("**Sign-in** | Sign in | 🟠"));
+        assert_eq!(plan(&pool, root, Direction::TwoWay).await.unwrap().conflicts().count(), 1);
+
+        set_canonical_source(&pool, Some(Source::Md)).await.unwrap();
+        let settled = plan(&pool, root, Direction::TwoWay).await.unwrap();
+        assert_eq!(settled.changes[0].action, Action::ToDb);
+        apply(&pool, root, &settled).await.unwrap();
+        let login = crud::features::get_by_id(&pool, &login.id).await.unwrap().unwrap();
+        assert_eq!((login.name.as_str(), login.state.as_str()), ("Sign-in", "implemented_no_tests"));
+
+        // A one-way sync overwrites regardless of the base
+        fs::write(root.join(TASKS_FILE), "# Backlog\n").unwrap();
+        let restore = plan(&pool, root, Direction::DbToMd).await.unwrap();
+        assert_eq!(restore.changes.len(), 1);
+        apply(&pool, root, &restore).await.unwrap();
+        assert_eq!(parse_tasks(&fs::read_to_string(root.join(TASKS_FILE)).unwrap()).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,367 @@
+// Tags - Project-wide labels linked to features, tasks, notes and sessions
+// Tag names are normalized (trimmed, lower case, inner whitespace as `-`) and stored
+// once per project; `entity_tags` links them to entities many-to-many. Renaming a tag
+// keeps its links, and merging moves the links of the merged tags onto the target.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{Acquire, Executor, Row, Sqlite, SqliteConnection, SqlitePool};
+
+use crate::entities::events;
+
+/// Entity types tags can be linked to
+pub const TAGGABLE: [&str; 4] = ["feature", "task", "note", "session"];
+
+pub async fn initialize_tag_tables(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS tags (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            UNIQUE (project_id, name)
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS entity_tags (
+            tag_id TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+
+            PRIMARY KEY (tag_id, entity_type, entity_id),
+            FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE,
+            CONSTRAINT chk_entity_tags_entity_type CHECK (entity_type IN ('feature', 'task', 'note', 'session'))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_entity_tags_entity ON entity_tags (entity_type, entity_id)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// How often a tag is used, per entity type
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TagCount {
+    pub name: String,
+    pub total: i64,
+    /// Entity type → number of tagged entities
+    pub by_type: BTreeMap<String, i64>,
+}
+
+/// Canonical form of a tag name
+pub fn normalize(name: &str) -> Result<String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join("-").to_lowercase();
+    if name.is_empty() {
+        anyhow::bail!("Tag names cannot be empty");
+    }
+    if name.contains(',') {
+        anyhow::bail!("Tag names cannot contain commas: {}", name);
+    }
+    Ok(name)
+}
+
+/// Normalized, de-duplicated tags from flag values that may hold comma-separated lists
+pub fn parse_list(values: &[String]) -> Result<Vec<String>> {
+    let mut tags: Vec<String> = Vec::new();
+    for part in values.iter().flat_map(|value| value.split(',')).filter(|part| !part.trim().is_empty()) {
+        let tag = normalize(part)?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok(tags)
+}
+
+/// Entity type of an ID such as `F00001`, `T000001`, `TASK-20250101-120000`, `S000001` or `note-…`
+pub fn entity_type_of(entity_id: &str) -> Result<&'static str> {
+    let numbered = |prefix: char| {
+        entity_id.strip_prefix(prefix).map_or(false, |rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+    };
+    if numbered('F') {
+        Ok("feature")
+    } else if numbered('T') || entity_id.starts_with("TASK-") {
+        Ok("task")
+    } else if numbered('S') {
+        Ok("session")
+    } else if entity_id.starts_with("note-") {
+        Ok("note")
+    } else {
+        anyhow::bail!("Cannot tell what '{}' is; expected a feature, task, note or session ID", entity_id)
+    }
+}
+
+fn check_taggable(entity_type: &str) -> Result<()> {
+    if !TAGGABLE.contains(&entity_type) {
+        anyhow::bail!("Cannot tag {} entities (taggable: {})", entity_type, TAGGABLE.join(", "));
+    }
+    Ok(())
+}
+
+async fn tag_id(conn: &mut SqliteConnection, project_id: &str, name: &str) -> Result<Option<String>> {
+    Ok(sqlx::query_scalar("SELECT id FROM tags WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await?)
+}
+
+async fn ensure_tag(conn: &mut SqliteConnection, project_id: &str, name: &str) -> Result<String> {
+    if let Some(id) = tag_id(conn, project_id, name).await? {
+        return Ok(id);
+    }
+    let id = format!("tag-{}", uuid::Uuid::new_v4());
+    sqlx::query("INSERT INTO tags (id, project_id, name, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&id)
+        .bind(project_id)
+        .bind(name)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+    Ok(id)
+}
+
+/// Link tags to an entity, creating the tags that do not exist yet. Returns the
+/// entity's tags afterwards.
+pub async fn add<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, entity_type: &str, entity_id: &str, names: &[String]) -> Result<Vec<String>> {
+    check_taggable(entity_type)?;
+    let names = parse_list(names)?;
+    let mut tx = db.begin().await?;
+    for name in &names {
+        let id = ensure_tag(&mut *tx, project_id, name).await?;
+        sqlx::query("INSERT OR IGNORE INTO entity_tags (tag_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?)")
+            .bind(&id)
+            .bind(entity_type)
+            .bind(entity_id)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+    }
+    let tags = for_entity(&mut *tx, entity_type, entity_id).await?;
+    tx.commit().await?;
+    Ok(tags)
+}
+
+/// Unlink tags from an entity; the tags themselves stay. Returns the entity's tags afterwards.
+pub async fn remove<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, entity_type: &str, entity_id: &str, names: &[String]) -> Result<Vec<String>> {
+    check_taggable(entity_type)?;
+    let names = parse_list(names)?;
+    let mut tx = db.begin().await?;
+    for name in &names {
+        sqlx::query(r#"
+            DELETE FROM entity_tags
+            WHERE entity_type = ? AND entity_id = ? AND tag_id IN (SELECT id FROM tags WHERE project_id = ? AND name = ?)
+        "#)
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(project_id)
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
+    }
+    let tags = for_entity(&mut *tx, entity_type, entity_id).await?;
+    tx.commit().await?;
+    Ok(tags)
+}
+
+/// Tags of an entity, by name
+pub async fn for_entity<'e, E: Executor<'e, Database = Sqlite>>(db: E, entity_type: &str, entity_id: &str) -> Result<Vec<String>> {
+    Ok(sqlx::query_scalar(r#"
+        SELECT t.name FROM entity_tags et
+        JOIN tags t ON t.id = et.tag_id
+        WHERE et.entity_type = ? AND et.entity_id = ?
+        ORDER BY t.name
+    "#)
+    .bind(entity_type)
+    .bind(entity_id)
+    .fetch_all(db)
+    .await?)
+}
+
+/// IDs of a project's entities of one type that carry every one of `names`
+pub async fn tagged_with(pool: &SqlitePool, project_id: &str, entity_type: &str, names: &[String]) -> Result<HashSet<String>> {
+    let names = parse_list(names)?;
+    let mut matching: Option<HashSet<String>> = None;
+    for name in &names {
+        let ids: HashSet<String> = sqlx::query_scalar::<_, String>(r#"
+            SELECT et.entity_id FROM entity_tags et
+            JOIN tags t ON t.id = et.tag_id
+            WHERE t.project_id = ? AND t.name = ? AND et.entity_type = ?
+        "#)
+        .bind(project_id)
+        .bind(name)
+        .bind(entity_type)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+        matching = Some(match matching {
+            Some(previous) => previous.intersection(&ids).cloned().collect(),
+            None => ids,
+        });
+    }
+    Ok(matching.unwrap_or_default())
+}
+
+/// Every tag of a project with its usage, most used first
+pub async fn counts(pool: &SqlitePool, project_id: &str) -> Result<Vec<TagCount>> {
+    let rows = sqlx::query(r#"
+        SELECT t.name, et.entity_type, COUNT(et.entity_id) AS uses
+        FROM tags t
+        LEFT JOIN entity_tags et ON et.tag_id = t.id
+        WHERE t.project_id = ?
+        GROUP BY t.name, et.entity_type
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+    for row in rows {
+        let name: String = row.get("name");
+        let count = counts.entry(name.clone()).or_insert_with(|| TagCount { name, ..Default::default() });
+        if let Some(entity_type) = row.get::<Option<String>, _>("entity_type") {
+            let uses: i64 = row.get("uses");
+            count.total += uses;
+            count.by_type.insert(entity_type, uses);
+        }
+    }
+    let mut counts: Vec<TagCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+    Ok(counts)
+}
+
+/// Rename a tag, keeping its links. Renaming onto an existing tag is refused; merge instead.
+pub async fn rename<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, old: &str, new: &str) -> Result<()> {
+    let (old, new) = (normalize(old)?, normalize(new)?);
+    let mut tx = db.begin().await?;
+    let id = tag_id(&mut *tx, project_id, &old).await?.ok_or_else(|| anyhow::anyhow!("Tag not found: {}", old))?;
+    if old == new {
+        return Ok(());
+    }
+    if tag_id(&mut *tx, project_id, &new).await?.is_some() {
+        anyhow::bail!("Tag {} already exists; merge {} into it instead", new, old);
+    }
+    sqlx::query("UPDATE tags SET name = ? WHERE id = ?")
+        .bind(&new)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await?;
+    events::record(&mut tx, project_id, "tag", &id, "tag.renamed", serde_json::json!({
+        "from": old,
+        "to": new,
+    })).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Move the links of `sources` onto `target` (created if needed) and delete the
+/// sources. Returns the number of entities newly tagged with `target`.
+pub async fn merge<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, sources: &[String], target: &str) -> Result<u64> {
+    let target = normalize(target)?;
+    let sources: Vec<String> = parse_list(sources)?.into_iter().filter(|source| *source != target).collect();
+    if sources.is_empty() {
+        anyhow::bail!("Nothing to merge into {}", target);
+    }
+
+    let mut tx = db.begin().await?;
+    let mut source_ids = Vec::new();
+    for source in &sources {
+        source_ids.push(tag_id(&mut *tx, project_id, source).await?.ok_or_else(|| anyhow::anyhow!("Tag not found: {}", source))?);
+    }
+    let target_id = ensure_tag(&mut *tx, project_id, &target).await?;
+
+    let mut moved = 0;
+    for source_id in &source_ids {
+        moved += sqlx::query(r#"
+            INSERT OR IGNORE INTO entity_tags (tag_id, entity_type, entity_id, created_at)
+            SELECT ?, entity_type, entity_id, created_at FROM entity_tags WHERE tag_id = ?
+        "#)
+        .bind(&target_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query("DELETE FROM entity_tags WHERE tag_id = ?")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    events::record(&mut tx, project_id, "tag", &target_id, "tag.merged", serde_json::json!({
+        "from": sources,
+        "into": target,
+        "moved": moved,
+    })).await?;
+    tx.commit().await?;
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_tags() {
+        assert_eq!(normalize("  Tech  Debt ").unwrap(), "tech-debt");
+        assert!(normalize("   ").is_err());
+        assert_eq!(parse_list(&["ui, Backend".to_string(), "UI".to_string()]).unwrap(), ["ui", "backend"]);
+        assert_eq!(entity_type_of("F00012").unwrap(), "feature");
+        assert_eq!(entity_type_of("TASK-20250101-120000").unwrap(), "task");
+        assert_eq!(entity_type_of("note-1f2e").unwrap(), "note");
+        assert!(entity_type_of("Fancy").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tag_counts_rename_and_merge() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Tags".to_string(), "Labels".to_string()).await.unwrap();
+        let tags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+        add(&pool, &project.id, "feature", "F00001", &tags(&["ui", "backend"])).await.unwrap();
+        add(&pool, &project.id, "task", "T000001", &tags(&["ui"])).await.unwrap();
+        add(&pool, &project.id, "task", "T000002", &tags(&["frontend", "ui"])).await.unwrap();
+        assert!(add(&pool, &project.id, "directive", "D001", &tags(&["ui"])).await.is_err());
+
+        let ui_tasks = tagged_with(&pool, &project.id, "task", &tags(&["ui"])).await.unwrap();
+        assert_eq!(ui_tasks.len(), 2);
+        let both = tagged_with(&pool, &project.id, "task", &tags(&["ui", "frontend"])).await.unwrap();
+        assert_eq!(both, HashSet::from(["T000002".to_string()]));
+
+        let usage = counts(&pool, &project.id).await.unwrap();
+        assert_eq!(usage[0].name, "ui");
+        assert_eq!(usage[0].total, 3);
+        assert_eq!(usage[0].by_type["task"], 2);
+
+        assert!(rename(&pool, &project.id, "ui", "frontend").await.is_err());
+        rename(&pool, &project.id, "backend", "api").await.unwrap();
+        assert_eq!(for_entity(&pool, "feature", "F00001").await.unwrap(), ["api", "ui"]);
+
+        // T000002 already has frontend, so only the other two links move
+        assert_eq!(merge(&pool, &project.id, &tags(&["ui"]), "frontend").await.unwrap(), 2);
+        let usage = counts(&pool, &project.id).await.unwrap();
+        assert_eq!(usage.iter().map(|count| count.name.as_str()).collect::<Vec<_>>(), ["frontend", "api"]);
+        assert_eq!(usage[0].total, 3);
+
+        remove(&pool, &project.id, "feature", "F00001", &tags(&["frontend"])).await.unwrap();
+        assert_eq!(for_entity(&pool, "feature", "F00001").await.unwrap(), ["api"]);
+    }
+}
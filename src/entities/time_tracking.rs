@@ -0,0 +1,324 @@
+// Time Tracking - Start/stop timers on tasks
+// `wsb task start` opens a time entry for the task, linked to the project's active
+// session and attributed to the process actor; `wsb task stop` closes it. Each actor
+// runs at most one timer: starting another task stops the running one first. Totals
+// count running entries up to now, per task (`wsb task show`) and per feature
+// (`wsb status --include-metrics`).
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Acquire, Executor, Row, Sqlite, SqlitePool};
+
+use crate::entities::actors;
+use crate::entities::events;
+use crate::entities::schema_models::TaskStatus;
+
+pub async fn initialize_time_entries_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS time_entries (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            task_id TEXT NOT NULL,
+            session_id TEXT,
+            actor TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            duration_seconds INTEGER
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_time_entries_task ON time_entries (task_id)")
+        .execute(pool)
+        .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_time_entries_running ON time_entries (actor) WHERE ended_at IS NULL")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A stored timer; `ended_at` is None while it runs
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub project_id: String,
+    pub task_id: String,
+    pub session_id: Option<String>,
+    pub actor: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_seconds: Option<i64>,
+}
+
+impl TimeEntry {
+    /// Recorded duration, or the time since the start while the timer runs
+    pub fn elapsed_seconds(&self, now: DateTime<Utc>) -> i64 {
+        match self.duration_seconds {
+            Some(seconds) => seconds,
+            None => seconds_between(&self.started_at, now),
+        }
+    }
+}
+
+/// Time tracked on one task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTime {
+    pub task_id: String,
+    pub total_seconds: i64,
+    pub entries: usize,
+    /// Start of the running timer, if any
+    pub running_since: Option<String>,
+}
+
+/// Time tracked on the tasks of one feature
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureTime {
+    pub feature_id: String,
+    pub feature_name: Option<String>,
+    pub total_seconds: i64,
+    pub tasks: usize,
+}
+
+fn seconds_between(started_at: &str, end: DateTime<Utc>) -> i64 {
+    DateTime::parse_from_rfc3339(started_at)
+        .map(|start| (end - start.with_timezone(&Utc)).num_seconds().max(0))
+        .unwrap_or(0)
+}
+
+fn from_row(row: &sqlx::sqlite::SqliteRow) -> TimeEntry {
+    TimeEntry {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        task_id: row.get("task_id"),
+        session_id: row.get("session_id"),
+        actor: row.get("actor"),
+        started_at: row.get("started_at"),
+        ended_at: row.get("ended_at"),
+        duration_seconds: row.get("duration_seconds"),
+    }
+}
+
+/// Hours and minutes, e.g. `2h 05m`, or `45s` under a minute
+pub fn format_duration(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s => format!("{}h {:02}m", s / 3600, s % 3600 / 60),
+    }
+}
+
+/// The current actor's running timer
+pub async fn running<'e, E: Executor<'e, Database = Sqlite>>(db: E) -> Result<Option<TimeEntry>> {
+    let row = sqlx::query("SELECT * FROM time_entries WHERE actor = ? AND ended_at IS NULL ORDER BY started_at DESC LIMIT 1")
+        .bind(actors::current().name)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.as_ref().map(from_row))
+}
+
+/// Start a timer on a live task, stopping the actor's running timer first. A pending
+/// task moves to in progress. Returns the new entry and the one that was stopped.
+pub async fn start<'c, A: Acquire<'c, Database = Sqlite>>(db: A, task_id: &str) -> Result<(TimeEntry, Option<TimeEntry>)> {
+    let mut tx = db.begin().await?;
+    let task = sqlx::query("SELECT project_id, status FROM tasks WHERE id = ? AND deleted_at IS NULL")
+        .bind(task_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", task_id))?;
+    let project_id: String = task.get("project_id");
+    let status: String = task.get("status");
+
+    let stopped = match running(&mut *tx).await? {
+        Some(entry) if entry.task_id == task_id => anyhow::bail!("Timer already running on {} since {}", task_id, entry.started_at),
+        Some(_) => Some(stop(&mut *tx).await?),
+        None => None,
+    };
+
+    let session_id: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM sessions WHERE project_id = ? AND state = 'active' ORDER BY started_at DESC LIMIT 1"
+    )
+    .bind(&project_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let actor = actors::current();
+    actors::touch(&mut tx, &actor).await?;
+
+    let entry = TimeEntry {
+        id: format!("time-{}", uuid::Uuid::new_v4()),
+        project_id: project_id.clone(),
+        task_id: task_id.to_string(),
+        session_id,
+        actor: actor.name.clone(),
+        started_at: Utc::now().to_rfc3339(),
+        ended_at: None,
+        duration_seconds: None,
+    };
+    sqlx::query(r#"
+        INSERT INTO time_entries (id, project_id, task_id, session_id, actor, started_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+    "#)
+    .bind(&entry.id)
+    .bind(&entry.project_id)
+    .bind(&entry.task_id)
+    .bind(&entry.session_id)
+    .bind(&entry.actor)
+    .bind(&entry.started_at)
+    .execute(&mut *tx)
+    .await?;
+
+    if status == TaskStatus::Pending.as_str() {
+        crate::entities::crud::tasks::update_status(&mut *tx, task_id, TaskStatus::InProgress).await?;
+    }
+    events::record(&mut tx, &project_id, "task", task_id, "task.timer_started", serde_json::json!({
+        "entry": entry.id,
+        "session_id": entry.session_id,
+    })).await?;
+    tx.commit().await?;
+    Ok((entry, stopped))
+}
+
+/// Stop the current actor's running timer
+pub async fn stop<'c, A: Acquire<'c, Database = Sqlite>>(db: A) -> Result<TimeEntry> {
+    let mut tx = db.begin().await?;
+    let mut entry = running(&mut *tx)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No timer running; start one with `wsb task start <id>`"))?;
+
+    let now = Utc::now();
+    entry.ended_at = Some(now.to_rfc3339());
+    entry.duration_seconds = Some(seconds_between(&entry.started_at, now));
+    sqlx::query("UPDATE time_entries SET ended_at = ?, duration_seconds = ? WHERE id = ?")
+        .bind(&entry.ended_at)
+        .bind(entry.duration_seconds)
+        .bind(&entry.id)
+        .execute(&mut *tx)
+        .await?;
+
+    events::record(&mut tx, &entry.project_id, "task", &entry.task_id, "task.timer_stopped", serde_json::json!({
+        "entry": entry.id,
+        "duration_seconds": entry.duration_seconds,
+    })).await?;
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Every entry of a task, oldest first
+pub async fn list_for_task(pool: &SqlitePool, task_id: &str) -> Result<Vec<TimeEntry>> {
+    let rows = sqlx::query("SELECT * FROM time_entries WHERE task_id = ? ORDER BY started_at")
+        .bind(task_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(from_row).collect())
+}
+
+/// Cumulative time on a task, counting a running timer up to now
+pub async fn task_total(pool: &SqlitePool, task_id: &str) -> Result<TaskTime> {
+    let entries = list_for_task(pool, task_id).await?;
+    let now = Utc::now();
+    Ok(TaskTime {
+        task_id: task_id.to_string(),
+        total_seconds: entries.iter().map(|entry| entry.elapsed_seconds(now)).sum(),
+        entries: entries.len(),
+        running_since: entries.iter().find(|entry| entry.ended_at.is_none()).map(|entry| entry.started_at.clone()),
+    })
+}
+
+/// Time per feature over the project's live tasks, most time first
+pub async fn by_feature(pool: &SqlitePool, project_id: &str) -> Result<Vec<FeatureTime>> {
+    let rows = sqlx::query(r#"
+        SELECT e.task_id, e.started_at, e.duration_seconds, t.feature_ids, f.name
+        FROM time_entries e
+        JOIN tasks t ON t.id = e.task_id AND t.deleted_at IS NULL
+        LEFT JOIN features f ON f.id = t.feature_ids
+        WHERE e.project_id = ?
+    "#)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let now = Utc::now();
+    let mut features: Vec<(FeatureTime, std::collections::BTreeSet<String>)> = Vec::new();
+    for row in &rows {
+        let feature_id: String = row.get("feature_ids");
+        let seconds = match row.get::<Option<i64>, _>("duration_seconds") {
+            Some(seconds) => seconds,
+            None => seconds_between(&row.get::<String, _>("started_at"), now),
+        };
+        let index = match features.iter().position(|(feature, _)| feature.feature_id == feature_id) {
+            Some(index) => index,
+            None => {
+                features.push((
+                    FeatureTime { feature_id, feature_name: row.get("name"), total_seconds: 0, tasks: 0 },
+                    Default::default(),
+                ));
+                features.len() - 1
+            }
+        };
+        let (feature, tasks) = &mut features[index];
+        feature.total_seconds += seconds;
+        tasks.insert(row.get("task_id"));
+        feature.tasks = tasks.len();
+    }
+
+    let mut features: Vec<FeatureTime> = features.into_iter().map(|(feature, _)| feature).collect();
+    features.sort_by(|a, b| b.total_seconds.cmp(&a.total_seconds).then_with(|| a.feature_id.cmp(&b.feature_id)));
+    Ok(features)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(5 * 60 + 9), "5m");
+        assert_eq!(format_duration(2 * 3600 + 5 * 60), "2h 05m");
+    }
+
+    #[tokio::test]
+    async fn test_start_stop_and_totals() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Timers".to_string(), "Time tracking".to_string()).await.unwrap();
+        let feature = crud::features::create(&pool, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let session = crud::sessions::create(&pool, project.id.clone(), "Work".to_string(), None).await.unwrap();
+        let first = crud::tasks::create(&pool, project.id.clone(), feature.id.clone(), "Write exporter".to_string(), "feature".to_string()).await.unwrap();
+        let second = crud::tasks::create(&pool, project.id.clone(), feature.id.clone(), "Test exporter".to_string(), "testing".to_string()).await.unwrap();
+
+        assert!(stop(&pool).await.is_err());
+        let (entry, stopped) = start(&pool, &first.id).await.unwrap();
+        assert!(stopped.is_none());
+        assert_eq!(entry.session_id.as_deref(), Some(session.id.as_str()));
+        assert!(start(&pool, &first.id).await.unwrap_err().to_string().contains("already running"));
+        let task = crud::tasks::get_by_id(&pool, &first.id).await.unwrap().unwrap();
+        assert_eq!(task.status, "in_progress");
+
+        // Backdate the running entry so the totals have something to add up
+        let an_hour_ago = (Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        sqlx::query("UPDATE time_entries SET started_at = ? WHERE id = ?").bind(&an_hour_ago).bind(&entry.id).execute(&pool).await.unwrap();
+
+        let (_, stopped) = start(&pool, &second.id).await.unwrap();
+        let stopped = stopped.unwrap();
+        assert_eq!(stopped.task_id, first.id);
+        assert!(stopped.duration_seconds.unwrap() >= 3600);
+        assert_eq!(stop(&pool).await.unwrap().task_id, second.id);
+        assert!(running(&pool).await.unwrap().is_none());
+
+        let total = task_total(&pool, &first.id).await.unwrap();
+        assert_eq!(total.entries, 1);
+        assert!(total.total_seconds >= 3600);
+        assert!(total.running_since.is_none());
+
+        let features = by_feature(&pool, &project.id).await.unwrap();
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].feature_name.as_deref(), Some("Export"));
+        assert_eq!(features[0].tasks, 2);
+        assert!(features[0].total_seconds >= 3600);
+    }
+}
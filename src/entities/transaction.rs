@@ -0,0 +1,238 @@
+// Entity Transactions - Atomic multi-step entity operations
+// A closure passed to `EntityManager::transaction` gets an `EntityTransaction`; every
+// create/update/delete made through it commits together or not at all. The crud
+// functions nest inside it as savepoints, so outbox events and actor stamps written
+// by an operation are rolled back with it.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::Result;
+use sqlx::{Sqlite, SqliteConnection, Transaction};
+
+use crate::entities::crud;
+use crate::entities::relationships::Dependency;
+use crate::entities::schema_models::{Directive, DirectiveCategory, Feature, FeatureState, Priority, Project, Session, Task, TaskStatus};
+use crate::entities::schema_traits::EntityType;
+
+/// Future returned by the closure passed to [`crate::entities::EntityManager::transaction`]
+pub type TransactionFuture<'t, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 't>>;
+
+/// Entity operations bound to one open database transaction
+pub struct EntityTransaction {
+    tx: Transaction<'static, Sqlite>,
+}
+
+impl EntityTransaction {
+    pub(crate) fn new(tx: Transaction<'static, Sqlite>) -> Self {
+        Self { tx }
+    }
+
+    pub(crate) async fn commit(self) -> Result<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+
+    pub(crate) async fn rollback(self) -> Result<()> {
+        self.tx.rollback().await?;
+        Ok(())
+    }
+
+    /// The transaction's connection, for crud functions and queries without a wrapper here
+    pub fn connection(&mut self) -> &mut SqliteConnection {
+        &mut self.tx
+    }
+
+    /// Create a new project
+    pub async fn create_project(&mut self, name: String, description: String) -> Result<Project> {
+        crud::projects::create(&mut *self.tx, name, description).await
+    }
+
+    /// Get project by ID, including changes made earlier in this transaction
+    pub async fn get_project(&mut self, id: &str) -> Result<Option<Project>> {
+        crud::projects::get_by_id(&mut *self.tx, id).await
+    }
+
+    /// Create a new feature
+    pub async fn create_feature(
+        &mut self,
+        project_id: String,
+        name: String,
+        description: String,
+        category: Option<String>,
+    ) -> Result<Feature> {
+        crud::features::create(&mut *self.tx, project_id, name, description, category).await
+    }
+
+    /// Get feature by ID, including changes made earlier in this transaction
+    pub async fn get_feature(&mut self, id: &str) -> Result<Option<Feature>> {
+        crud::features::get_by_id(&mut *self.tx, id).await
+    }
+
+    /// Update feature state
+    pub async fn update_feature_state(&mut self, id: &str, new_state: FeatureState) -> Result<()> {
+        crud::features::update_state(&mut *self.tx, id, new_state).await
+    }
+
+    /// Soft-delete a feature and the tasks that reference it
+    pub async fn delete_feature(&mut self, id: &str) -> Result<()> {
+        crud::features::delete(&mut *self.tx, id).await
+    }
+
+    /// Restore a soft-deleted feature and the tasks deleted with it
+    pub async fn restore_feature(&mut self, id: &str) -> Result<()> {
+        crud::features::restore(&mut *self.tx, id).await
+    }
+
+    /// Create a new task
+    pub async fn create_task(
+        &mut self,
+        project_id: String,
+        feature_id: String,
+        task_description: String,
+        category: String,
+    ) -> Result<Task> {
+        crud::tasks::create(&mut *self.tx, project_id, feature_id, task_description, category).await
+    }
+
+    /// Get task by ID, including changes made earlier in this transaction
+    pub async fn get_task(&mut self, id: &str) -> Result<Option<Task>> {
+        crud::tasks::get_by_id(&mut *self.tx, id).await
+    }
+
+    /// Update task status
+    pub async fn update_task_status(&mut self, id: &str, new_status: TaskStatus) -> Result<()> {
+        crud::tasks::update_status(&mut *self.tx, id, new_status).await
+    }
+
+    /// Update task (full object update)
+    pub async fn update_task(&mut self, task: &Task) -> Result<()> {
+        crud::tasks::update(&mut *self.tx, task).await
+    }
+
+    /// Soft-delete a task
+    pub async fn delete_task(&mut self, id: &str) -> Result<()> {
+        crud::tasks::delete(&mut *self.tx, id).await
+    }
+
+    /// Restore a soft-deleted task
+    pub async fn restore_task(&mut self, id: &str) -> Result<()> {
+        crud::tasks::restore(&mut *self.tx, id).await
+    }
+
+    /// Create a new session
+    pub async fn create_session(&mut self, project_id: String, title: String, focus: String) -> Result<Session> {
+        crud::sessions::create(&mut *self.tx, project_id, title, Some(focus)).await
+    }
+
+    /// Create a new directive
+    pub async fn create_directive(
+        &mut self,
+        project_id: String,
+        title: String,
+        rule: String,
+        category: DirectiveCategory,
+        priority: Priority,
+    ) -> Result<Directive> {
+        crud::directives::create(&mut *self.tx, project_id, title, rule, category, priority).await
+    }
+
+    /// Deactivate a directive
+    pub async fn deactivate_directive(&mut self, id: &str) -> Result<()> {
+        crud::directives::deactivate(&mut *self.tx, id).await
+    }
+
+    /// Create a relationship after validating it against the typed relationship schema
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_relationship(
+        &mut self,
+        project_id: &str,
+        from_entity_id: &str,
+        from_entity_type: EntityType,
+        to_entity_id: &str,
+        to_entity_type: EntityType,
+        dependency_type: &str,
+        description: Option<String>,
+    ) -> Result<Dependency> {
+        crud::dependencies::create(
+            &mut *self.tx,
+            project_id,
+            from_entity_id,
+            from_entity_type,
+            to_entity_id,
+            to_entity_type,
+            dependency_type,
+            description,
+        )
+        .await
+    }
+
+    /// Delete a relationship
+    pub async fn delete_relationship(&mut self, id: &str) -> Result<()> {
+        crud::dependencies::delete(&mut *self.tx, id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::EntityManager;
+    use tempfile::TempDir;
+
+    async fn manager(temp_dir: &TempDir) -> EntityManager {
+        let pool = crate::entities::database::initialize_database(&temp_dir.path().join("project.db")).await.unwrap();
+        EntityManager::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_transaction_commits_all_steps() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = manager(&temp_dir).await;
+        let project = manager.create_project("Tx".to_string(), "Transactions".to_string()).await.unwrap();
+
+        let project_id = project.id.clone();
+        let feature = manager
+            .transaction(|tx| Box::pin(async move {
+                let feature = tx.create_feature(project_id.clone(), "Export".to_string(), "CSV export".to_string(), None).await?;
+                let first = tx.create_task(project_id.clone(), feature.id.clone(), "Write exporter".to_string(), "feature".to_string()).await?;
+                let second = tx.create_task(project_id.clone(), feature.id.clone(), "Test exporter".to_string(), "testing".to_string()).await?;
+                assert_ne!(first.id, second.id, "IDs must account for rows written earlier in the transaction");
+                tx.create_relationship(&project_id, &second.id, EntityType::Task, &first.id, EntityType::Task, "blocks", None).await?;
+                Ok(feature)
+            }))
+            .await
+            .unwrap();
+
+        assert!(manager.get_feature(&feature.id).await.unwrap().is_some());
+        assert_eq!(manager.list_tasks_by_project(&project.id, None).await.unwrap().len(), 2);
+        let relationships = crud::dependencies::list_by_project(&manager.pool, &project.id).await.unwrap();
+        assert_eq!(relationships.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_on_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = manager(&temp_dir).await;
+        let project = manager.create_project("Tx".to_string(), "Transactions".to_string()).await.unwrap();
+
+        let project_id = project.id.clone();
+        let result: Result<()> = manager
+            .transaction(|tx| Box::pin(async move {
+                let feature = tx.create_feature(project_id.clone(), "Import".to_string(), "CSV import".to_string(), None).await?;
+                tx.create_task(project_id.clone(), feature.id.clone(), "Write importer".to_string(), "feature".to_string()).await?;
+                // Invalid relationship: fails validation after two successful writes
+                tx.create_relationship(&project_id, &feature.id, EntityType::Feature, &project_id, EntityType::Project, "no-such-kind", None).await?;
+                Ok(())
+            }))
+            .await;
+
+        assert!(result.is_err());
+        assert!(manager.list_features_by_project(&project.id).await.unwrap().is_empty());
+        assert!(manager.list_tasks_by_project(&project.id, None).await.unwrap().is_empty());
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM event_outbox WHERE entity_type IN ('feature', 'task')")
+            .fetch_one(&manager.pool)
+            .await
+            .unwrap();
+        assert_eq!(events, 0, "outbox events must roll back with the changes");
+    }
+}
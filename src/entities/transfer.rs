@@ -0,0 +1,579 @@
+// Entity Transfer - Project snapshots as JSON or YAML
+// `wsb export` writes the active project and its entities to a snapshot file that can
+// be moved to another machine or checked into git; `wsb import` loads one. Rows are
+// copied column by column, so columns the target schema does not have are skipped.
+// Imported IDs that are already taken get the next free ID of their kind, and every
+// reference to them (project, feature and task lists, relationships, notes) follows.
+// Note attachments go with their notes; with `--files` the attached files themselves are
+// embedded too, and written back on import where they are missing.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::sqlite::{SqliteArguments, SqliteRow};
+use sqlx::{Column, Row, Sqlite, SqliteConnection, SqlitePool, TypeInfo, ValueRef};
+
+use crate::entities::events;
+
+/// Snapshot layout version; newer snapshots are rejected
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Entity kinds a snapshot can carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Project,
+    Session,
+    Feature,
+    Task,
+    Directive,
+    Milestone,
+    Relationship,
+    Note,
+    Attachment,
+}
+
+impl EntityKind {
+    /// Import order: rows come after the rows they reference
+    pub const ALL: [EntityKind; 9] = [
+        EntityKind::Project,
+        EntityKind::Session,
+        EntityKind::Feature,
+        EntityKind::Task,
+        EntityKind::Directive,
+        EntityKind::Milestone,
+        EntityKind::Relationship,
+        EntityKind::Note,
+        EntityKind::Attachment,
+    ];
+
+    /// Key of the kind in a snapshot
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityKind::Project => "projects",
+            EntityKind::Session => "sessions",
+            EntityKind::Feature => "features",
+            EntityKind::Task => "tasks",
+            EntityKind::Directive => "directives",
+            EntityKind::Milestone => "milestones",
+            EntityKind::Relationship => "relationships",
+            EntityKind::Note => "notes",
+            EntityKind::Attachment => "attachments",
+        }
+    }
+
+    /// Singular or plural kind name
+    pub fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        EntityKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == name || kind.as_str().trim_end_matches('s') == name)
+            .with_context(|| format!(
+                "Unknown entity kind '{}' (expected one of: {})",
+                s,
+                EntityKind::ALL.map(|kind| kind.as_str()).join(", ")
+            ))
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            EntityKind::Relationship => "dependencies",
+            EntityKind::Attachment => "note_attachments",
+            other => other.as_str(),
+        }
+    }
+
+    /// Prefix and digit count of sequential IDs; None for UUID-based ones
+    fn sequence(&self) -> Option<(&'static str, usize)> {
+        match self {
+            EntityKind::Project => Some(("P", 3)),
+            EntityKind::Session => Some(("S", 6)),
+            EntityKind::Feature => Some(("F", 5)),
+            EntityKind::Task => Some(("T", 6)),
+            EntityKind::Directive => Some(("D", 3)),
+            EntityKind::Milestone => Some(("M", 3)),
+            EntityKind::Relationship | EntityKind::Note | EntityKind::Attachment => None,
+        }
+    }
+
+    /// Text columns holding other entities' IDs, alone or as a JSON or comma-separated list
+    fn reference_columns(&self) -> &'static [&'static str] {
+        match self {
+            EntityKind::Project => &[],
+            EntityKind::Session | EntityKind::Directive => &["project_id"],
+            EntityKind::Feature => &["project_id", "dependencies"],
+            EntityKind::Task => &["project_id", "feature_ids", "depends_on", "session_id", "recurrence_parent"],
+            EntityKind::Milestone => &["project_id", "feature_ids", "task_ids"],
+            EntityKind::Relationship => &["project_id", "from_entity_id", "to_entity_id"],
+            EntityKind::Note => &["project_id", "entity_id"],
+            EntityKind::Attachment => &["project_id", "note_id"],
+        }
+    }
+}
+
+/// Serialization of a snapshot file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            other => anyhow::bail!("Unsupported format '{}' (expected json or yaml)", other),
+        }
+    }
+
+    /// YAML for `.yaml`/`.yml` files, JSON otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => Format::Yaml,
+            _ => Format::Json,
+        }
+    }
+
+    pub fn render(&self, snapshot: &Snapshot) -> Result<String> {
+        Ok(match self {
+            Format::Json => serde_json::to_string_pretty(snapshot)? + "\n",
+            Format::Yaml => serde_yaml::to_string(snapshot)?,
+        })
+    }
+
+    pub fn parse(&self, text: &str) -> Result<Snapshot> {
+        Ok(match self {
+            Format::Json => serde_json::from_str(text).context("Invalid JSON snapshot")?,
+            Format::Yaml => serde_yaml::from_str(text).context("Invalid YAML snapshot")?,
+        })
+    }
+}
+
+/// A project and its entities, one list of rows per kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub project_id: String,
+    pub entities: BTreeMap<String, Vec<Map<String, Value>>>,
+    /// Base64 contents of attached files, by path relative to the project root
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    fn rows(&self, kind: EntityKind) -> &[Map<String, Value>] {
+        self.entities.get(kind.as_str()).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// How an import treats data already in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep existing rows; the snapshot is added alongside them
+    Merge,
+    /// Delete the snapshot project's rows of the imported kinds first
+    Replace,
+}
+
+/// What an import changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    /// Rows written per kind
+    pub imported: BTreeMap<String, usize>,
+    /// Rows deleted by `--replace`
+    pub deleted: u64,
+    /// Snapshot IDs that were taken, and the IDs they were given
+    pub remapped: BTreeMap<String, String>,
+}
+
+fn row_to_object(row: &SqliteRow) -> Result<Map<String, Value>> {
+    let mut object = Map::new();
+    for (index, column) in row.columns().iter().enumerate() {
+        let raw = row.try_get_raw(index)?;
+        let value = if raw.is_null() {
+            Value::Null
+        } else {
+            match raw.type_info().name() {
+                "INTEGER" | "BOOLEAN" => Value::from(row.try_get_unchecked::<i64, _>(index)?),
+                "REAL" | "NUMERIC" => Value::from(row.try_get_unchecked::<f64, _>(index)?),
+                _ => Value::from(row.try_get_unchecked::<String, _>(index)?),
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(object)
+}
+
+/// Snapshot of a project with the given kinds; the project row is always included,
+/// and note attachments whenever notes are
+pub async fn export(pool: &SqlitePool, project_id: &str, kinds: &[EntityKind]) -> Result<Snapshot> {
+    let project = sqlx::query("SELECT * FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", project_id))?;
+
+    let mut entities = BTreeMap::new();
+    entities.insert(EntityKind::Project.as_str().to_string(), vec![row_to_object(&project)?]);
+    let included = |kind: &EntityKind| kinds.contains(kind) || (*kind == EntityKind::Attachment && kinds.contains(&EntityKind::Note));
+    for kind in EntityKind::ALL.into_iter().filter(|kind| *kind != EntityKind::Project && included(kind)) {
+        let rows = sqlx::query(&format!("SELECT * FROM {} WHERE project_id = ? ORDER BY rowid", kind.table()))
+            .bind(project_id)
+            .fetch_all(pool)
+            .await?;
+        entities.insert(kind.as_str().to_string(), rows.iter().map(row_to_object).collect::<Result<_>>()?);
+    }
+
+    Ok(Snapshot {
+        format_version: FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        project_id: project_id.to_string(),
+        entities,
+        files: BTreeMap::new(),
+    })
+}
+
+/// Embed the attached files under `root` whose content still matches the recorded
+/// checksum. Returns the paths left out because they are missing or modified.
+pub fn embed_files(snapshot: &mut Snapshot, root: &Path) -> Result<Vec<String>> {
+    let mut skipped = Vec::new();
+    for row in snapshot.rows(EntityKind::Attachment).to_vec() {
+        let (Some(path), Some(sha256)) = (row.get("path").and_then(Value::as_str), row.get("sha256").and_then(Value::as_str)) else {
+            continue;
+        };
+        if snapshot.files.contains_key(path) {
+            continue;
+        }
+        let file = root.join(path);
+        match crate::scrap::dedupe::hash_file(&file) {
+            Ok(hash) if hash == sha256 => {
+                let data = std::fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+                snapshot.files.insert(path.to_string(), STANDARD.encode(data));
+            }
+            _ => skipped.push(path.to_string()),
+        }
+    }
+    Ok(skipped)
+}
+
+fn check_file_path(path: &str) -> Result<()> {
+    if !Path::new(path).components().all(|component| matches!(component, Component::Normal(_))) {
+        anyhow::bail!("Snapshot file path must stay inside the project: {}", path);
+    }
+    Ok(())
+}
+
+/// Write the embedded files that do not exist under `root` yet; existing files are
+/// never overwritten. Returns the paths written.
+pub fn restore_files(snapshot: &Snapshot, root: &Path) -> Result<Vec<String>> {
+    let mut restored = Vec::new();
+    for (path, data) in &snapshot.files {
+        check_file_path(path)?;
+        let file = root.join(path);
+        if file.exists() {
+            continue;
+        }
+        let data = STANDARD.decode(data).with_context(|| format!("Invalid file content for {}", path))?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&file, data).with_context(|| format!("Failed to write {}", file.display()))?;
+        restored.push(path.clone());
+    }
+    Ok(restored)
+}
+
+/// Next free ID of a kind, not in `taken`; UUID-based kinds keep the old ID's prefix
+fn allocate(kind: EntityKind, old: &str, taken: &mut HashSet<String>) -> Result<String> {
+    let id = match kind.sequence() {
+        Some((prefix, digits)) => {
+            let next = taken
+                .iter()
+                .filter_map(|id| id.strip_prefix(prefix)?.parse::<u64>().ok())
+                .max()
+                .unwrap_or(0)
+                + 1;
+            if next.to_string().len() > digits {
+                anyhow::bail!("No free {} IDs left for {}", kind.as_str(), old);
+            }
+            format!("{}{:0width$}", prefix, next, width = digits)
+        }
+        None => {
+            let prefix = old.split_once('-').map_or(kind.as_str().trim_end_matches('s'), |(prefix, _)| prefix);
+            format!("{}-{}", prefix, uuid::Uuid::new_v4())
+        }
+    };
+    taken.insert(id.clone());
+    Ok(id)
+}
+
+/// `value` with every remapped ID replaced, keeping its list layout
+fn remap_references(value: &str, remapped: &HashMap<String, String>) -> String {
+    if value.trim_start().starts_with('[') {
+        return match serde_json::from_str::<Vec<String>>(value) {
+            Ok(ids) => {
+                let ids: Vec<&str> = ids.iter().map(|id| remapped.get(id).map_or(id.as_str(), String::as_str)).collect();
+                serde_json::to_string(&ids).unwrap_or_else(|_| value.to_string())
+            }
+            Err(_) => value.to_string(),
+        };
+    }
+    value
+        .split(',')
+        .map(|part| match remapped.get(part.trim()) {
+            Some(new) => part.replacen(part.trim(), new, 1),
+            None => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+async fn column_names(conn: &mut SqliteConnection, table: &str) -> Result<HashSet<String>> {
+    Ok(sqlx::query(&format!("PRAGMA table_info({})", table))
+        .fetch_all(&mut *conn)
+        .await?
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .collect())
+}
+
+fn bind_value<'q>(query: sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>>, value: &Value) -> sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(flag) => query.bind(*flag),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => query.bind(integer),
+            None => query.bind(number.as_f64()),
+        },
+        Value::String(text) => query.bind(text.clone()),
+        other => query.bind(other.to_string()),
+    }
+}
+
+/// Load a snapshot in one transaction
+pub async fn import(pool: &SqlitePool, snapshot: &Snapshot, mode: ImportMode) -> Result<ImportReport> {
+    if snapshot.format_version > FORMAT_VERSION {
+        anyhow::bail!("Snapshot format {} is newer than this wsb supports ({})", snapshot.format_version, FORMAT_VERSION);
+    }
+    for key in snapshot.entities.keys() {
+        EntityKind::from_str(key)?;
+    }
+    for path in snapshot.files.keys() {
+        check_file_path(path)?;
+    }
+    let kinds: Vec<EntityKind> = EntityKind::ALL.into_iter().filter(|kind| snapshot.entities.contains_key(kind.as_str())).collect();
+    let project_ids: Vec<String> = snapshot
+        .rows(EntityKind::Project)
+        .iter()
+        .filter_map(|row| row.get("id")?.as_str().map(str::to_string))
+        .collect();
+    if project_ids.is_empty() {
+        anyhow::bail!("Snapshot has no project");
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut report = ImportReport::default();
+
+    if mode == ImportMode::Replace {
+        for kind in kinds.iter().rev().filter(|kind| **kind != EntityKind::Project) {
+            for project_id in &project_ids {
+                report.deleted += sqlx::query(&format!("DELETE FROM {} WHERE project_id = ?", kind.table()))
+                    .bind(project_id)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            }
+        }
+    }
+
+    // Decide every row's ID before writing, so references can point forward
+    let mut remapped: HashMap<String, String> = HashMap::new();
+    let mut replaced_projects: HashSet<String> = HashSet::new();
+    for kind in &kinds {
+        let existing: HashSet<String> = sqlx::query_scalar::<_, String>(&format!("SELECT id FROM {}", kind.table()))
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+        let ids: Vec<String> = snapshot.rows(*kind).iter().filter_map(|row| row.get("id")?.as_str().map(str::to_string)).collect();
+        let mut taken: HashSet<String> = existing.iter().cloned().chain(ids.iter().cloned()).collect();
+        for id in ids.iter().filter(|id| existing.contains(*id)) {
+            if *kind == EntityKind::Project && mode == ImportMode::Replace {
+                replaced_projects.insert(id.clone());
+            } else {
+                remapped.insert(id.clone(), allocate(*kind, id, &mut taken)?);
+            }
+        }
+    }
+
+    for kind in &kinds {
+        let columns = column_names(&mut tx, kind.table()).await?;
+        let mut count = 0;
+        for row in snapshot.rows(*kind) {
+            let mut row = row.clone();
+            row.retain(|column, _| columns.contains(column));
+            let old_id = row.get("id").and_then(Value::as_str).map(str::to_string).context("Snapshot row without an id")?;
+            let new_id = remapped.get(&old_id).cloned().unwrap_or_else(|| old_id.clone());
+            row.insert("id".to_string(), Value::from(new_id.clone()));
+            if row.get("code").and_then(Value::as_str) == Some(old_id.as_str()) {
+                row.insert("code".to_string(), Value::from(new_id.clone()));
+            }
+            for column in kind.reference_columns() {
+                if let Some(Value::String(value)) = row.get(*column) {
+                    let value = remap_references(value, &remapped);
+                    row.insert(column.to_string(), Value::from(value));
+                }
+            }
+            if let Some(Value::String(session_id)) = row.get("session_id").filter(|_| *kind == EntityKind::Task) {
+                let exists: Option<String> = sqlx::query_scalar("SELECT id FROM sessions WHERE id = ?")
+                    .bind(session_id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+                if exists.is_none() {
+                    row.insert("session_id".to_string(), Value::Null);
+                }
+            }
+
+            let names: Vec<&String> = row.keys().collect();
+            let sql = if replaced_projects.contains(&new_id) {
+                let assignments: Vec<String> = names.iter().map(|name| format!("{} = ?", name)).collect();
+                format!("UPDATE {} SET {} WHERE id = ?", kind.table(), assignments.join(", "))
+            } else {
+                let placeholders = vec!["?"; names.len()].join(", ");
+                let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+                format!("INSERT INTO {} ({}) VALUES ({})", kind.table(), names.join(", "), placeholders)
+            };
+            let mut query = sqlx::query(&sql);
+            for value in row.values() {
+                query = bind_value(query, value);
+            }
+            if replaced_projects.contains(&new_id) {
+                query = query.bind(new_id.clone());
+            }
+            query
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to import {} {}", kind.as_str().trim_end_matches('s'), old_id))?;
+            count += 1;
+        }
+        report.imported.insert(kind.as_str().to_string(), count);
+    }
+
+    for project_id in &project_ids {
+        let project_id = remapped.get(project_id).unwrap_or(project_id);
+        events::record(&mut tx, project_id, "project", project_id, "project.imported", serde_json::json!({
+            "mode": if mode == ImportMode::Replace { "replace" } else { "merge" },
+            "imported": report.imported,
+        })).await?;
+    }
+    tx.commit().await?;
+
+    report.remapped = remapped.into_iter().collect();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use crate::entities::schema_traits::EntityType;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remap_references() {
+        let remapped = HashMap::from([("T000001".to_string(), "T000009".to_string())]);
+        assert_eq!(remap_references(r#"["T000001","T000002"]"#, &remapped), r#"["T000009","T000002"]"#);
+        assert_eq!(remap_references("T000001, T000002", &remapped), "T000009, T000002");
+        assert_eq!(remap_references("T000001", &remapped), "T000009");
+        assert_eq!(EntityKind::from_str("Task").unwrap(), EntityKind::Task);
+        assert!(EntityKind::from_str("widgets").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_import_roundtrip_with_remapping() {
+        let temp = TempDir::new().unwrap();
+        let source = crate::entities::database::initialize_database(&temp.path().join("source.db")).await.unwrap();
+        let project = crud::projects::create(&source, "Transfer".to_string(), "Snapshots".to_string()).await.unwrap();
+        let feature = crud::features::create(&source, project.id.clone(), "Export".to_string(), "CSV export".to_string(), None).await.unwrap();
+        let first = crud::tasks::create(&source, project.id.clone(), feature.id.clone(), "Write exporter".to_string(), "feature".to_string()).await.unwrap();
+        let second = crud::tasks::create(&source, project.id.clone(), feature.id.clone(), "Test exporter".to_string(), "testing".to_string()).await.unwrap();
+        crud::dependencies::create(&source, &project.id, &second.id, EntityType::Task, &first.id, EntityType::Task, "depends_on", None).await.unwrap();
+
+        let snapshot = export(&source, &project.id, &EntityKind::ALL).await.unwrap();
+        assert_eq!(snapshot.rows(EntityKind::Task).len(), 2);
+        let yaml = Format::Yaml.render(&snapshot).unwrap();
+        let snapshot = Format::Yaml.parse(&yaml).unwrap();
+
+        // The target already has P001, F00001 and T000001 of its own
+        let target = crate::entities::database::initialize_database(&temp.path().join("target.db")).await.unwrap();
+        let other = crud::projects::create(&target, "Other".to_string(), "Existing".to_string()).await.unwrap();
+        let other_feature = crud::features::create(&target, other.id.clone(), "Login".to_string(), "Login page".to_string(), None).await.unwrap();
+        crud::tasks::create(&target, other.id.clone(), other_feature.id.clone(), "Build form".to_string(), "feature".to_string()).await.unwrap();
+
+        let report = import(&target, &snapshot, ImportMode::Merge).await.unwrap();
+        assert_eq!(report.imported["tasks"], 2);
+        assert_eq!(report.remapped.get(&project.id).map(String::as_str), Some("P002"));
+        assert_eq!(report.remapped.get(&feature.id).map(String::as_str), Some("F00002"));
+        let new_first = report.remapped[&first.id].clone();
+
+        let imported = crud::tasks::list_by_project(&target, "P002", None).await.unwrap();
+        assert_eq!(imported.len(), 2);
+        assert!(imported.iter().all(|task| task.feature_id == "F00002"));
+        let relationships = crud::dependencies::list_by_project(&target, "P002").await.unwrap();
+        assert_eq!(relationships[0].to_entity_id, new_first);
+        assert_eq!(crud::tasks::list_by_project(&target, &other.id, None).await.unwrap().len(), 1);
+
+        // Replacing the imported project swaps its rows instead of adding more
+        let mut again = export(&target, "P002", &[EntityKind::Task]).await.unwrap();
+        again.entities.get_mut("tasks").unwrap().truncate(1);
+        let report = import(&target, &again, ImportMode::Replace).await.unwrap();
+        assert_eq!(report.deleted, 2);
+        assert!(report.remapped.is_empty());
+        assert_eq!(crud::tasks::list_by_project(&target, "P002", None).await.unwrap().len(), 1);
+        assert_eq!(crud::features::list_by_project(&target, "P002").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attachments_travel_with_notes() {
+        use crate::entities::notes::{self, AttachmentState, NewNote};
+
+        let source_root = TempDir::new().unwrap();
+        let source = crate::entities::database::initialize_database(&source_root.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&source, "Transfer".to_string(), "Attachments".to_string()).await.unwrap();
+        std::fs::write(source_root.path().join("design.png"), b"png").unwrap();
+        let note = notes::create(&source, NewNote {
+            project_id: project.id.clone(),
+            entity: None,
+            note_type: "decision".to_string(),
+            title: "Mockup".to_string(),
+            content: "Agreed layout".to_string(),
+            tags: None,
+        }, source_root.path(), &[std::path::PathBuf::from("design.png")]).await.unwrap();
+
+        // Asking for notes brings their attachments along
+        let mut snapshot = export(&source, &project.id, &[EntityKind::Note]).await.unwrap();
+        assert_eq!(snapshot.rows(EntityKind::Attachment).len(), 1);
+        assert!(embed_files(&mut snapshot, source_root.path()).unwrap().is_empty());
+        let snapshot = Format::Json.parse(&Format::Json.render(&snapshot).unwrap()).unwrap();
+
+        let target_root = TempDir::new().unwrap();
+        let target = crate::entities::database::initialize_database(&target_root.path().join("project.db")).await.unwrap();
+        import(&target, &snapshot, ImportMode::Merge).await.unwrap();
+        assert_eq!(restore_files(&snapshot, target_root.path()).unwrap(), ["design.png"]);
+        assert!(restore_files(&snapshot, target_root.path()).unwrap().is_empty());
+
+        let imported = notes::get(&target, &note.id).await.unwrap().unwrap();
+        assert_eq!(imported.attachments.len(), 1);
+        assert_eq!(imported.attachments[0].verify(target_root.path()), AttachmentState::Intact);
+
+        let mut escaping = snapshot.clone();
+        escaping.files.insert("../outside.txt".to_string(), STANDARD.encode(b"x"));
+        assert!(import(&target, &escaping, ImportMode::Merge).await.is_err());
+    }
+}
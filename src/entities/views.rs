@@ -0,0 +1,230 @@
+// Saved Views - Named filters for entity lists
+// `wsb view save sprint --entity task --status in_progress --priority high` stores a
+// filter definition per project; `wsb task list --view sprint` (or `feature list`) and
+// the MCP list tools apply it. Flags given next to `--view` override the saved ones.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Acquire, Executor, Row, Sqlite, SqlitePool};
+
+use crate::entities::schema_models::{Priority, TaskStatus};
+
+/// Entity types a view can list
+pub const VIEW_ENTITIES: [&str; 2] = ["task", "feature"];
+
+pub async fn initialize_views_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS views (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            entity_type TEXT NOT NULL,
+            status TEXT,
+            priority TEXT,
+            tags TEXT NOT NULL DEFAULT '[]',
+            sort TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            UNIQUE (project_id, name),
+            CONSTRAINT chk_views_entity_type CHECK (entity_type IN ('task', 'feature'))
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// A stored filter definition
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedView {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub entity_type: String,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Vec<String>,
+    /// Sort field, `-` prefixed for descending order
+    pub sort: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl SavedView {
+    /// Sort field and whether it is descending
+    pub fn sort_key(&self) -> Option<(&str, bool)> {
+        self.sort.as_deref().map(split_sort)
+    }
+}
+
+/// Filters of a view to save
+#[derive(Debug, Clone, Default)]
+pub struct ViewDefinition {
+    pub entity_type: String,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Vec<String>,
+    pub sort: Option<String>,
+}
+
+fn split_sort(sort: &str) -> (&str, bool) {
+    match sort.strip_prefix('-') {
+        Some(field) => (field, true),
+        None => (sort, false),
+    }
+}
+
+/// Fields an entity's lists can be sorted by
+pub fn sort_fields(entity_type: &str) -> &'static [&'static str] {
+    match entity_type {
+        "task" => &["id", "title", "status", "priority", "created"],
+        _ => &["id", "title", "status"],
+    }
+}
+
+fn validate(definition: &ViewDefinition) -> Result<()> {
+    let entity = definition.entity_type.as_str();
+    if !VIEW_ENTITIES.contains(&entity) {
+        anyhow::bail!("Views can list {} (not '{}')", VIEW_ENTITIES.join(" or "), entity);
+    }
+    if entity == "task" {
+        if let Some(status) = &definition.status {
+            TaskStatus::from_str(status).map_err(anyhow::Error::msg)?;
+        }
+        if let Some(priority) = &definition.priority {
+            Priority::from_str(priority).map_err(anyhow::Error::msg)?;
+        }
+    } else if definition.priority.is_some() {
+        anyhow::bail!("Feature views cannot filter by priority");
+    }
+    if let Some(sort) = &definition.sort {
+        let (field, _) = split_sort(sort);
+        if !sort_fields(entity).contains(&field) {
+            anyhow::bail!("Cannot sort {} views by '{}' (expected one of: {})", entity, field, sort_fields(entity).join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn from_row(row: &SqliteRow) -> SavedView {
+    SavedView {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        name: row.get("name"),
+        entity_type: row.get("entity_type"),
+        status: row.get("status"),
+        priority: row.get("priority"),
+        tags: serde_json::from_str(&row.get::<String, _>("tags")).unwrap_or_default(),
+        sort: row.get("sort"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// Save a view under `name`, replacing the filters of an existing view with that name
+pub async fn save<'c, A: Acquire<'c, Database = Sqlite>>(db: A, project_id: &str, name: &str, definition: ViewDefinition) -> Result<SavedView> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("View name cannot be empty");
+    }
+    validate(&definition)?;
+
+    let mut conn = db.acquire().await?;
+    let now = Utc::now().to_rfc3339();
+    sqlx::query(r#"
+        INSERT INTO views (id, project_id, name, entity_type, status, priority, tags, sort, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (project_id, name) DO UPDATE SET
+            entity_type = excluded.entity_type,
+            status = excluded.status,
+            priority = excluded.priority,
+            tags = excluded.tags,
+            sort = excluded.sort,
+            updated_at = excluded.updated_at
+    "#)
+    .bind(format!("view-{}", uuid::Uuid::new_v4()))
+    .bind(project_id)
+    .bind(name)
+    .bind(&definition.entity_type)
+    .bind(&definition.status)
+    .bind(&definition.priority)
+    .bind(serde_json::to_string(&definition.tags)?)
+    .bind(&definition.sort)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *conn)
+    .await?;
+
+    get(&mut *conn, project_id, name).await?.ok_or_else(|| anyhow::anyhow!("View vanished after save: {}", name))
+}
+
+/// A project's view by name
+pub async fn get<'e, E: Executor<'e, Database = Sqlite>>(db: E, project_id: &str, name: &str) -> Result<Option<SavedView>> {
+    let row = sqlx::query("SELECT * FROM views WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(name.trim())
+        .fetch_optional(db)
+        .await?;
+    Ok(row.as_ref().map(from_row))
+}
+
+/// A project's views by name
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<SavedView>> {
+    let rows = sqlx::query("SELECT * FROM views WHERE project_id = ? ORDER BY name")
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(from_row).collect())
+}
+
+/// Delete a view; false when there was none with that name
+pub async fn delete<'e, E: Executor<'e, Database = Sqlite>>(db: E, project_id: &str, name: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM views WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(name.trim())
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_save_replaces_and_validates_views() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Views".to_string(), "Saved filters".to_string()).await.unwrap();
+
+        let sprint = ViewDefinition {
+            entity_type: "task".to_string(),
+            status: Some("in_progress".to_string()),
+            priority: Some("high".to_string()),
+            sort: Some("-created".to_string()),
+            ..Default::default()
+        };
+        let first = save(&pool, &project.id, "sprint", sprint.clone()).await.unwrap();
+        assert_eq!(first.sort_key(), Some(("created", true)));
+
+        let second = save(&pool, &project.id, "sprint", ViewDefinition { priority: None, ..sprint.clone() }).await.unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.priority, None);
+        assert_eq!(list(&pool, &project.id).await.unwrap().len(), 1);
+
+        assert!(save(&pool, &project.id, "bad", ViewDefinition { status: Some("done".to_string()), ..sprint.clone() }).await.is_err());
+        assert!(save(&pool, &project.id, "bad", ViewDefinition { sort: Some("owner".to_string()), ..sprint.clone() }).await.is_err());
+        assert!(save(&pool, &project.id, "bad", ViewDefinition { entity_type: "feature".to_string(), ..sprint }).await.is_err());
+
+        assert!(delete(&pool, &project.id, "sprint").await.unwrap());
+        assert!(get(&pool, &project.id, "sprint").await.unwrap().is_none());
+        assert!(!delete(&pool, &project.id, "sprint").await.unwrap());
+    }
+}
@@ -0,0 +1,121 @@
+// Workspace State - Settings of the database as a whole rather than of one project
+// One database can hold several projects. `wsb project switch P002` records which one
+// entity commands work on under the `current_project` key; without a recorded choice, or
+// once that project is archived or deleted, the newest active project is used as before.
+
+use anyhow::Result;
+use sqlx::{Executor, Sqlite, SqlitePool};
+
+use crate::entities::crud;
+use crate::entities::schema_models::Project;
+
+/// Key of the project entity commands work on
+pub const CURRENT_PROJECT: &str = "current_project";
+
+pub async fn initialize_workspace_state_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS workspace_state (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )
+    "#)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Value stored under `key`. Databases opened read-only before the table existed have none.
+pub async fn get(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+    let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'workspace_state'")
+        .fetch_one(pool)
+        .await?;
+    if exists == 0 {
+        return Ok(None);
+    }
+    Ok(sqlx::query_scalar("SELECT value FROM workspace_state WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await?)
+}
+
+pub async fn set<'e, E: Executor<'e, Database = Sqlite>>(db: E, key: &str, value: &str) -> Result<()> {
+    sqlx::query(r#"
+        INSERT INTO workspace_state (key, value, updated_at) VALUES (?, ?, ?)
+        ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+    "#)
+    .bind(key)
+    .bind(value)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn clear<'e, E: Executor<'e, Database = Sqlite>>(db: E, key: &str) -> Result<()> {
+    sqlx::query("DELETE FROM workspace_state WHERE key = ?")
+        .bind(key)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// The project entity commands work on: the switched-to one while it is active, otherwise
+/// the newest active project
+pub async fn current_project(pool: &SqlitePool) -> Result<Option<Project>> {
+    if let Some(id) = get(pool, CURRENT_PROJECT).await? {
+        if let Some(project) = crud::projects::get_by_id(pool, &id).await? {
+            if project.status == "active" {
+                return Ok(Some(project));
+            }
+        }
+    }
+    Ok(crud::projects::list_active(pool).await?.into_iter().next())
+}
+
+/// Make `id` the current project
+pub async fn switch_project(pool: &SqlitePool, id: &str) -> Result<Project> {
+    let project = crud::projects::get_by_id(pool, id).await?
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+    if project.status != "active" {
+        anyhow::bail!("Project {} is {}; only active projects can be switched to", id, project.status);
+    }
+    set(pool, CURRENT_PROJECT, id).await?;
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_switch_and_archive_projects() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        assert!(current_project(&pool).await.unwrap().is_none());
+
+        let first = crud::projects::create(&pool, "First".to_string(), "One".to_string()).await.unwrap();
+        let second = crud::projects::create(&pool, "Second".to_string(), "Two".to_string()).await.unwrap();
+        let fallback = current_project(&pool).await.unwrap().unwrap();
+        assert!(fallback.id == first.id || fallback.id == second.id);
+
+        switch_project(&pool, &first.id).await.unwrap();
+        assert_eq!(current_project(&pool).await.unwrap().unwrap().id, first.id);
+        switch_project(&pool, &second.id).await.unwrap();
+        assert_eq!(current_project(&pool).await.unwrap().unwrap().id, second.id);
+        assert!(switch_project(&pool, "P999").await.is_err());
+
+        // Archiving the current project hands over to the remaining active one
+        crud::projects::archive(&pool, &second.id).await.unwrap();
+        assert_eq!(current_project(&pool).await.unwrap().unwrap().id, first.id);
+        assert!(switch_project(&pool, &second.id).await.is_err());
+        assert!(crud::projects::archive(&pool, &second.id).await.is_err());
+        assert_eq!(crud::projects::list(&pool, false).await.unwrap().len(), 1);
+        assert_eq!(crud::projects::list(&pool, true).await.unwrap().len(), 2);
+
+        crud::projects::unarchive(&pool, &second.id).await.unwrap();
+        assert_eq!(current_project(&pool).await.unwrap().unwrap().id, second.id);
+    }
+}
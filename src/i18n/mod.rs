@@ -0,0 +1,294 @@
+//! Localization of user-facing CLI messages.
+//!
+//! Messages live in TOML catalogs keyed by `table.key`. English is built in and
+//! is the fallback for any key a translation leaves out, so a catalog can be
+//! partial. Further catalogs are picked up from `.wsb/locales/<locale>.toml` in
+//! the project or from the directory named by `WSB_LOCALE_DIR`, which lets a
+//! team localize the tool without rebuilding it.
+//!
+//! The locale is `WSB_LANG`, then the project's `i18n` tool config, then the
+//! system locale (`LC_ALL`, `LC_MESSAGES`, `LANG`), then English. Messages are
+//! looked up with [`t!`](crate::t).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Environment variable that selects the message locale
+pub const LOCALE_ENV: &str = "WSB_LANG";
+
+/// Environment variable naming an extra directory of message catalogs
+pub const LOCALE_DIR_ENV: &str = "WSB_LOCALE_DIR";
+
+/// Locale used when nothing else is configured, and for missing keys
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Catalogs compiled into the binary
+const BUILTIN: &[(&str, &str)] = &[("en", include_str!("locales/en.toml"))];
+
+/// The `i18n` section of the workspace tool config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct I18nConfig {
+    pub locale: Option<String>,
+}
+
+/// Messages for one locale, layered over the English fallback
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    locale: String,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// The English catalog
+    pub fn english() -> Self {
+        let fallback = builtin(DEFAULT_LOCALE).unwrap_or_default();
+        Catalog { locale: DEFAULT_LOCALE.to_string(), messages: HashMap::new(), fallback }
+    }
+
+    /// Load the catalog for `locale`, searching the built-in catalogs and `dirs` in order.
+    /// Unknown locales fall back to English.
+    pub fn load(locale: &str, dirs: &[PathBuf]) -> Result<Self> {
+        let mut catalog = Self::english();
+        for candidate in candidates(locale) {
+            if let Some(messages) = builtin(&candidate) {
+                catalog.locale = candidate;
+                catalog.messages = messages;
+                return Ok(catalog);
+            }
+            for dir in dirs {
+                if let Some(path) = find_catalog(dir, &candidate) {
+                    let content = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read message catalog: {}", path.display()))?;
+                    catalog.messages = parse_catalog(&content)
+                        .with_context(|| format!("Invalid message catalog: {}", path.display()))?;
+                    catalog.locale = candidate;
+                    return Ok(catalog);
+                }
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// The locale this catalog was loaded for
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// The message for `key`, falling back to English and then to the key itself
+    pub fn get(&self, key: &str) -> String {
+        self.messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    /// Keys present in English that this catalog does not translate
+    pub fn missing_keys(&self) -> Vec<String> {
+        let mut missing: Vec<String> = self.fallback
+            .keys()
+            .filter(|key| !self.messages.contains_key(*key))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing
+    }
+}
+
+static CURRENT: Mutex<Option<Catalog>> = Mutex::new(None);
+
+/// Flatten a TOML catalog into `table.key` → message
+pub fn parse_catalog(content: &str) -> Result<HashMap<String, String>> {
+    let table: toml::Table = toml::from_str(content)?;
+    let mut messages = HashMap::new();
+    flatten("", &table, &mut messages)?;
+    Ok(messages)
+}
+
+fn flatten(prefix: &str, table: &toml::Table, messages: &mut HashMap<String, String>) -> Result<()> {
+    for (key, value) in table {
+        let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::String(message) => {
+                messages.insert(key, message.clone());
+            }
+            toml::Value::Table(nested) => flatten(&key, nested, messages)?,
+            _ => anyhow::bail!("Message '{}' must be a string", key),
+        }
+    }
+    Ok(())
+}
+
+fn builtin(locale: &str) -> Option<HashMap<String, String>> {
+    BUILTIN
+        .iter()
+        .find(|(name, _)| *name == locale)
+        .map(|(_, content)| parse_catalog(content).expect("built-in message catalog is valid"))
+}
+
+fn find_catalog(dir: &Path, locale: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?.flatten().map(|entry| entry.path()).find(|path| {
+        path.extension().and_then(|e| e.to_str()) == Some("toml")
+            && path.file_stem().and_then(|s| s.to_str()).map(normalize).as_deref() == Some(locale)
+    })
+}
+
+/// Normalize a locale tag: `de_DE.UTF-8@euro` → `de-de`
+pub fn normalize(locale: &str) -> String {
+    let tag = locale.split(['.', '@']).next().unwrap_or("");
+    tag.trim().replace('_', "-").to_lowercase()
+}
+
+/// Catalog names to try for a locale, most specific first: `pt-br`, then `pt`
+fn candidates(locale: &str) -> Vec<String> {
+    let tag = normalize(locale);
+    if tag.is_empty() || tag == "c" || tag == "posix" {
+        return vec![DEFAULT_LOCALE.to_string()];
+    }
+    let mut candidates = vec![tag.clone()];
+    if let Some((language, _)) = tag.split_once('-') {
+        candidates.push(language.to_string());
+    }
+    candidates
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Resolve the locale: `WSB_LANG`, then project config, then the system locale, then English
+pub fn resolve_locale(project_root: &Path) -> String {
+    if let Some(locale) = non_empty(std::env::var(LOCALE_ENV).ok()) {
+        return locale;
+    }
+
+    if project_root.join(".wsb").join("state.json").exists() {
+        let configured = crate::workspace_state::WorkspaceState::peek(project_root)
+            .ok()
+            .and_then(|state| state.get_tool_config::<I18nConfig>("i18n"))
+            .and_then(|config| non_empty(config.locale));
+        if let Some(locale) = configured {
+            return locale;
+        }
+    }
+
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| non_empty(std::env::var(var).ok()))
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Directories searched for catalogs that are not built in
+pub fn catalog_dirs(project_root: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = non_empty(std::env::var(LOCALE_DIR_ENV).ok()) {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs.push(project_root.join(".wsb").join("locales"));
+    dirs
+}
+
+/// Resolve and load the catalog for the rest of the process.
+/// A catalog that fails to load is reported and English is used instead.
+pub fn init(project_root: &Path) -> String {
+    let locale = resolve_locale(project_root);
+    let catalog = match Catalog::load(&locale, &catalog_dirs(project_root)) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            log::warn!("Falling back to English messages: {:#}", e);
+            Catalog::english()
+        }
+    };
+    let loaded = catalog.locale().to_string();
+    *CURRENT.lock().unwrap() = Some(catalog);
+    loaded
+}
+
+/// Look up a message in the process catalog; English if [`init`] was never called
+pub fn lookup(key: &str) -> String {
+    let mut current = CURRENT.lock().unwrap();
+    current.get_or_insert_with(Catalog::english).get(key)
+}
+
+/// Fill `{name}` placeholders in a message
+pub fn fill(message: &str, args: &[(&str, String)]) -> String {
+    let mut filled = message.to_string();
+    for (name, value) in args {
+        filled = filled.replace(&format!("{{{}}}", name), value);
+    }
+    filled
+}
+
+/// Look up a localized message, filling any named placeholders:
+/// `t!("status.title")` or `t!("status.features_total", count = 3)`.
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::lookup($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            &$crate::i18n::lookup($key),
+            &[$((stringify!($name), ($value).to_string())),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_and_candidates() {
+        assert_eq!(normalize("de_DE.UTF-8@euro"), "de-de");
+        assert_eq!(candidates("pt_BR.UTF-8"), vec!["pt-br".to_string(), "pt".to_string()]);
+        assert_eq!(candidates("C"), vec!["en".to_string()]);
+        assert_eq!(candidates("POSIX.UTF-8"), vec!["en".to_string()]);
+    }
+
+    #[test]
+    fn test_english_is_built_in() {
+        let catalog = Catalog::load("en_US.UTF-8", &[]).unwrap();
+        assert_eq!(catalog.locale(), "en");
+        assert_eq!(catalog.get("status.title"), "Project Status Report");
+        assert!(catalog.missing_keys().is_empty());
+    }
+
+    #[test]
+    fn test_external_catalog_falls_back_to_english() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("de.toml"),
+            "[status]\ntitle = \"Projektstatusbericht\"\n",
+        ).unwrap();
+
+        let catalog = Catalog::load("de_AT", &[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(catalog.locale(), "de");
+        assert_eq!(catalog.get("status.title"), "Projektstatusbericht");
+        assert_eq!(catalog.get("status.project"), "Project");
+        assert_eq!(catalog.get("no.such.key"), "no.such.key");
+        assert!(catalog.missing_keys().contains(&"status.project".to_string()));
+
+        let unknown = Catalog::load("fr", &[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(unknown.locale(), "en");
+    }
+
+    #[test]
+    fn test_invalid_catalog_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("de.toml"), "[status]\ntitle = 3\n").unwrap();
+        assert!(Catalog::load("de", &[dir.path().to_path_buf()]).is_err());
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let message = fill("{failed}/{total} failed", &[("failed", "2".to_string()), ("total", "10".to_string())]);
+        assert_eq!(message, "2/10 failed");
+        assert_eq!(crate::t!("status.features_total", count = 4), "4 features total");
+    }
+}
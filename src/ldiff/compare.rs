@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use super::ldiff_common::LineDiffer;
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+/// Compare two files line by line, writing the second one with every token that
+/// matches the same position in the first file's line substituted.
+///
+/// Line N of `second` is rendered against line N of `first`; lines past the end of
+/// `first` have nothing to match and are written as they are. Returns the line counts
+/// of both files so callers can mention a length mismatch.
+pub fn compare_files<W: Write>(first: &Path, second: &Path, differ: &mut LineDiffer, out: &mut W) -> Result<(usize, usize)> {
+    let mut reference = open(first)?.lines();
+    let mut first_count = 0;
+    let mut second_count = 0;
+
+    for line in open(second)?.lines() {
+        let line = line?;
+        second_count += 1;
+        let rendered = match reference.next() {
+            Some(previous) => {
+                first_count += 1;
+                differ.process_against(&previous?, &line)?
+            }
+            None => differ.process_against("", &line)?,
+        };
+        writeln!(out, "{}", rendered)?;
+    }
+    for line in reference {
+        line?;
+        first_count += 1;
+    }
+
+    out.flush()?;
+    Ok((first_count, second_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn file(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_compare_files_aligns_by_line() {
+        let first = file("run 1 started\nloaded 42 items\ndone\n");
+        let second = file("run 2 started\nloaded 40 items\ndone\nextra line\n");
+        let mut out = Vec::new();
+
+        let counts = compare_files(first.path(), second.path(), &mut LineDiffer::new('░'), &mut out).unwrap();
+
+        assert_eq!(counts, (3, 4));
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "░░░ 2 ░░░░░░░\n░░░░░░ 40 ░░░░░\n░░░░\nextra line\n"
+        );
+    }
+}
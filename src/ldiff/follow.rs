@@ -0,0 +1,281 @@
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::ldiff_common::LineDiffer;
+
+/// Lines of existing content shown before following, as `tail -f` does
+pub const INITIAL_LINES: usize = 10;
+
+/// How far back from the end to look for the initial lines
+const TAIL_WINDOW: u64 = 64 * 1024;
+
+/// Longest wait between checks, so an interrupt is noticed promptly
+const WAKE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Settings for `ldiff --follow`
+#[derive(Debug, Clone, Default)]
+pub struct FollowOptions {
+    /// Poll at this interval instead of waiting for file system events
+    pub poll: Option<Duration>,
+}
+
+/// Set a flag on Ctrl+C instead of letting it end the process mid-line
+pub fn stop_on_interrupt() -> Result<Arc<AtomicBool>> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stop);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to set up the interrupt handler")?;
+    thread::Builder::new()
+        .name("wsb-ldiff-signal".to_string())
+        .spawn(move || {
+            runtime.block_on(async {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    flag.store(true, Ordering::SeqCst);
+                }
+            })
+        })
+        .context("Failed to start the interrupt handler")?;
+    Ok(stop)
+}
+
+/// Blocks until the followed file may have changed, or the wake interval passes
+enum Waiter {
+    Events {
+        events: Receiver<notify::Result<notify::Event>>,
+        /// Never read; holding it keeps the watcher running until the waiter is dropped
+        _watcher: RecommendedWatcher,
+    },
+    Poll(Duration),
+}
+
+impl Waiter {
+    fn new(path: &Path, poll: Option<Duration>) -> Self {
+        if let Some(interval) = poll {
+            return Waiter::Poll(interval);
+        }
+        // Watch the directory so rotation (a new file at the same path) is seen too
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let (tx, rx) = mpsc::channel();
+        match notify::recommended_watcher(tx).and_then(|mut watcher| {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        }) {
+            Ok(watcher) => Waiter::Events { events: rx, _watcher: watcher },
+            Err(e) => {
+                log::warn!("File events unavailable ({}); polling every {}ms", e, WAKE_INTERVAL.as_millis());
+                Waiter::Poll(WAKE_INTERVAL)
+            }
+        }
+    }
+
+    fn wait(&self, stop: &AtomicBool) {
+        match self {
+            Waiter::Events { events, .. } => {
+                if events.recv_timeout(WAKE_INTERVAL).is_ok() {
+                    // Coalesce a burst of events into one read
+                    while events.try_recv().is_ok() {}
+                }
+            }
+            Waiter::Poll(interval) => {
+                // Sleep in short steps so an interrupt does not wait out a long interval
+                let mut remaining = *interval;
+                while !remaining.is_zero() && !stop.load(Ordering::SeqCst) {
+                    let step = remaining.min(WAKE_INTERVAL);
+                    thread::sleep(step);
+                    remaining -= step;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn identity(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn identity(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// The followed file and how far into it has been read
+struct Tail {
+    path: PathBuf,
+    file: File,
+    position: u64,
+    identity: Option<(u64, u64)>,
+    /// Bytes after the last newline, waiting for the rest of their line
+    partial: Vec<u8>,
+}
+
+impl Tail {
+    fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let identity = identity(&file.metadata()?);
+        Ok(Self { path: path.to_path_buf(), file, position: 0, identity, partial: Vec::new() })
+    }
+
+    /// Complete lines among the last `count` of the file, leaving the position at its end
+    fn last_lines(&mut self, count: usize) -> Result<Vec<String>> {
+        let len = self.file.metadata()?.len();
+        let start = len.saturating_sub(TAIL_WINDOW);
+        self.file.seek(SeekFrom::Start(start))?;
+        let mut buffer = Vec::new();
+        self.file.read_to_end(&mut buffer)?;
+        self.position = start + buffer.len() as u64;
+
+        let mut lines = self.split_lines(buffer);
+        if start > 0 && !lines.is_empty() {
+            // The window most likely starts mid-line
+            lines.remove(0);
+        }
+        let skip = lines.len().saturating_sub(count);
+        Ok(lines.split_off(skip))
+    }
+
+    /// Whatever has been appended since the last read, as complete lines
+    fn read_new(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        let current = fs::metadata(&self.path).ok();
+
+        // Rotated: finish the old file, then start the new one from its beginning
+        if let Some(current) = &current {
+            if identity(current).is_some() && identity(current) != self.identity {
+                lines.extend(self.read_appended()?);
+                if let Ok(next) = Tail::open(&self.path) {
+                    lines.extend(self.take_partial());
+                    *self = next;
+                    eprintln!("ldiff: {} was replaced; following the new file", self.path.display());
+                }
+            } else if current.len() < self.position {
+                eprintln!("ldiff: {} was truncated; following from its start", self.path.display());
+                self.position = 0;
+                self.partial.clear();
+            }
+        }
+
+        lines.extend(self.read_appended()?);
+        Ok(lines)
+    }
+
+    fn read_appended(&mut self) -> Result<Vec<String>> {
+        self.file.seek(SeekFrom::Start(self.position))?;
+        let mut buffer = Vec::new();
+        self.file.read_to_end(&mut buffer)?;
+        self.position += buffer.len() as u64;
+        Ok(self.split_lines(buffer))
+    }
+
+    /// Split `buffer`, prefixed by any pending partial line, keeping the unterminated rest
+    fn split_lines(&mut self, buffer: Vec<u8>) -> Vec<String> {
+        let mut data = std::mem::take(&mut self.partial);
+        data.extend(buffer);
+        let end = match data.iter().rposition(|&b| b == b'\n') {
+            Some(end) => end,
+            None => {
+                self.partial = data;
+                return Vec::new();
+            }
+        };
+        self.partial = data.split_off(end + 1);
+        data[..end]
+            .split(|&b| b == b'\n')
+            .map(|line| String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(line)).into_owned())
+            .collect()
+    }
+
+    /// The unterminated last line, if any, once nothing more will arrive
+    fn take_partial(&mut self) -> Option<String> {
+        if self.partial.is_empty() {
+            return None;
+        }
+        let line = std::mem::take(&mut self.partial);
+        Some(String::from_utf8_lossy(&line).into_owned())
+    }
+}
+
+/// Follow `path` like `tail -f`: print its last lines, then every line appended to it,
+/// each compared with the one before, until `stop` is set. A truncated file is followed
+/// from its start again, and a file replaced at the same path (log rotation) is switched
+/// to once the old one has been read to its end.
+pub fn follow<W: Write>(path: &Path, differ: &mut LineDiffer, options: &FollowOptions, stop: &AtomicBool, out: &mut W) -> Result<()> {
+    let mut tail = Tail::open(path)?;
+    let waiter = Waiter::new(path, options.poll);
+
+    for line in tail.last_lines(INITIAL_LINES)? {
+        writeln!(out, "{}", differ.process(&line)?)?;
+    }
+    out.flush()?;
+
+    while !stop.load(Ordering::SeqCst) {
+        waiter.wait(stop);
+        for line in tail.read_new()? {
+            writeln!(out, "{}", differ.process(&line)?)?;
+        }
+        out.flush()?;
+    }
+
+    // Anything written without its newline yet would otherwise be lost
+    for line in tail.read_new()?.into_iter().chain(tail.take_partial()) {
+        writeln!(out, "{}", differ.process(&line)?)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_follow_processes_appended_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("app.log");
+        let old: String = (1..=12).map(|n| format!("old line {}\n", n)).collect();
+        fs::write(&path, old).unwrap();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let path = path.clone();
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+                file.write_all(b"new line 13\nnew li").unwrap();
+                file.flush().unwrap();
+                thread::sleep(Duration::from_millis(100));
+                file.write_all(b"ne 14\nunfinished").unwrap();
+                thread::sleep(Duration::from_millis(100));
+                stop.store(true, Ordering::SeqCst);
+            })
+        };
+
+        let options = FollowOptions { poll: Some(Duration::from_millis(20)) };
+        let mut out = Vec::new();
+        follow(&path, &mut LineDiffer::new('░'), &options, &stop, &mut out).unwrap();
+        writer.join().unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), INITIAL_LINES + 3);
+        assert_eq!(lines[0], "old line 3");
+        assert_eq!(lines[1], "░░░ ░░░░ 4");
+        assert_eq!(lines[INITIAL_LINES], "new ░░░░ 13");
+        assert_eq!(lines[INITIAL_LINES + 1], "░░░ ░░░░ 14");
+        assert_eq!(lines[INITIAL_LINES + 2], "unfinished");
+    }
+}
@@ -1,20 +1,131 @@
 use anyhow::Result;
 use regex::Regex;
 use std::io::{self, BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
 
-/// Processes a line, replacing repeated tokens with a substitute character
-/// Returns the processed line and the current words for the next iteration
-pub fn process_line(
+use super::stats::LdiffStats;
+
+/// SGR sequences for `--color`; each turns off only what it turned on, so colors
+/// already present in the input survive
+const DIM: (&str, &str) = ("\x1b[2m", "\x1b[22m");
+const HIGHLIGHT: (&str, &str) = ("\x1b[1;33m", "\x1b[22;39m");
+const PLACEHOLDER: (&str, &str) = ("\x1b[36m", "\x1b[39m");
+
+/// Value classes recognized by `--normalize`, as (regex group, placeholder). Earlier
+/// classes win when two match at the same position.
+const VALUE_CLASSES: &[(&str, &str)] = &[
+    ("ts", "⟨ts⟩"),
+    ("uuid", "⟨uuid⟩"),
+    ("ip", "⟨ip⟩"),
+    ("hex", "⟨hex⟩"),
+];
+
+/// Collapses timestamps, UUIDs, IP addresses and hex IDs into class placeholders
+#[derive(Debug, Clone)]
+pub struct Normalizer {
+    pattern: Regex,
+}
+
+impl Normalizer {
+    pub fn new() -> Result<Self> {
+        let ts = [
+            // ISO 8601 / RFC 3339, with a space or T between date and time
+            r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?",
+            // Apache / nginx: 10/Oct/2023:13:55:36 +0000
+            r"\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2}(?: [+-]\d{4})?",
+            // Syslog: Oct 10 13:55:36
+            r"\b[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}",
+            r"\b\d{4}-\d{2}-\d{2}\b",
+            r"\b\d{2}:\d{2}:\d{2}(?:[.,]\d+)?\b",
+        ]
+        .join("|");
+        let uuid = r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b";
+        let ip = r"\b(?:\d{1,3}\.){3}\d{1,3}\b|\b(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}\b";
+        let hex = r"\b0[xX][0-9a-fA-F]+\b|\b[0-9a-fA-F]{8,}\b";
+        let pattern = Regex::new(&format!(
+            "(?P<ts>{})|(?P<uuid>{})|(?P<ip>{})|(?P<hex>{})",
+            ts, uuid, ip, hex
+        ))?;
+        Ok(Self { pattern })
+    }
+
+    /// Byte ranges of recognized values in `line`, with their placeholders
+    pub fn spans(&self, line: &str) -> Vec<(std::ops::Range<usize>, &'static str)> {
+        self.pattern
+            .captures_iter(line)
+            .filter_map(|caps| {
+                let (group, placeholder) = VALUE_CLASSES.iter().find(|(group, _)| caps.name(group).is_some())?;
+                let value = caps.name(group)?;
+                // Long runs of digits alone are counters or epoch times, not hex IDs
+                let is_hex = *group != "hex"
+                    || value.as_str().starts_with("0x")
+                    || value.as_str().starts_with("0X")
+                    || value.as_str().chars().any(|c| c.is_ascii_alphabetic());
+                is_hex.then(|| (value.range(), *placeholder))
+            })
+            .collect()
+    }
+}
+
+/// How a token of a line relates to the previous line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Separators, whitespace and color codes, always kept as they are
+    Literal,
+    /// The same word or number as at this position in the previous line
+    Repeated,
+    /// A different word or number than at this position in the previous line
+    Changed,
+    /// Past the end of the previous line
+    New,
+    /// A value collapsed into its class placeholder by `--normalize`
+    Normalized,
+}
+
+/// Split a line into tokens and classify each word or number against the previous line.
+/// Returns the tokens and the line's words for the next comparison.
+pub fn classify_line(line: &str, previous_words: &[String]) -> Result<(Vec<(TokenKind, String)>, Vec<String>)> {
+    classify_line_with(line, previous_words, None)
+}
+
+/// `classify_line`, first collapsing the values `normalizer` recognizes. A placeholder
+/// takes one word position, so the words after it still line up with the previous line.
+pub fn classify_line_with(
     line: &str,
     previous_words: &[String],
-    substitute_char: char,
-) -> Result<(String, Vec<String>)> {
+    normalizer: Option<&Normalizer>,
+) -> Result<(Vec<(TokenKind, String)>, Vec<String>)> {
+    let current_line = line.trim_end_matches('\n');
+    let spans = normalizer.map(|n| n.spans(current_line)).unwrap_or_default();
+
+    let mut tokens = Vec::new();
+    let mut current_words = Vec::new();
+    let mut start = 0;
+    for (range, placeholder) in spans {
+        classify_segment(&current_line[start..range.start], previous_words, &mut tokens, &mut current_words)?;
+        tokens.push((TokenKind::Normalized, placeholder.to_string()));
+        current_words.push(placeholder.to_string());
+        start = range.end;
+    }
+    classify_segment(&current_line[start..], previous_words, &mut tokens, &mut current_words)?;
+
+    Ok((tokens, current_words))
+}
+
+/// Tokenize part of a line, continuing the word positions in `current_words`
+fn classify_segment(
+    current_line: &str,
+    previous_words: &[String],
+    tokens: &mut Vec<(TokenKind, String)>,
+    current_words: &mut Vec<String>,
+) -> Result<()> {
     // ANSI color code pattern
     let color_pattern = Regex::new(r"\x1b\[[0-9;]*[mGK]")?;
 
     // Comprehensive separators pattern - matches Python original
     let separators = r#"[:\.,:;!?@#$%^&*()+=\[\]{}<>~/\\|"'\-]"#;
-    
+    let literal_pattern = Regex::new(&format!(r"^[{}]$|^\s+$", separators))?;
+
     // Token pattern that closely matches the Python original
     // Captures: ANSI codes, separators/whitespace, numbers, and words
     let token_pattern = Regex::new(&format!(
@@ -23,58 +134,152 @@ pub fn process_line(
         separators
     ))?;
 
-    let current_line = line.trim_end_matches('\n');
-    let mut output = Vec::new();
-    let mut current_words = Vec::new();
-
-    // Find all matches and extract them
     for mat in token_pattern.find_iter(current_line) {
         let token = mat.as_str();
-        
-        if color_pattern.is_match(token) {
-            // Preserve ANSI color codes
-            output.push(token.to_string());
-        } else if Regex::new(&format!(r"^[{}]$|^\s+$", separators))?.is_match(token) {
-            // Preserve separators and whitespace exactly
-            output.push(token.to_string());
+
+        if color_pattern.is_match(token) || literal_pattern.is_match(token) {
+            // Preserve ANSI color codes, separators and whitespace exactly
+            tokens.push((TokenKind::Literal, token.to_string()));
         } else {
             // Word or number token - these are the ones we compare and potentially replace
-            if current_words.len() < previous_words.len() 
-                && token == previous_words[current_words.len()] {
-                // Replace with substitute character pattern
-                output.push(substitute_char.to_string().repeat(token.len()));
-            } else {
-                output.push(token.to_string());
-            }
+            let kind = match previous_words.get(current_words.len()) {
+                Some(previous) if previous == token => TokenKind::Repeated,
+                Some(_) => TokenKind::Changed,
+                None => TokenKind::New,
+            };
+            tokens.push((kind, token.to_string()));
             current_words.push(token.to_string());
         }
     }
 
-    let output_line = output.join("");
-    Ok((output_line, current_words))
+    Ok(())
 }
 
-/// Processes input from stdin line by line, writing to stdout
-pub fn process_stdin(substitute_char: char) -> Result<()> {
-    let stdin = io::stdin();
-    let reader = BufReader::new(stdin.lock());
-    let mut stdout = io::stdout();
-    
-    let mut previous_words = Vec::new();
+/// Render classified tokens: repeated ones become substitute characters, dimmed with
+/// `color`, and changed ones are highlighted with `color`
+pub fn render_tokens(tokens: &[(TokenKind, String)], substitute_char: char, color: bool) -> String {
+    let mut output = String::new();
+    for (kind, token) in tokens {
+        match (kind, color) {
+            (TokenKind::Repeated, false) => output.push_str(&substitute_char.to_string().repeat(token.len())),
+            (TokenKind::Repeated, true) => {
+                output.push_str(DIM.0);
+                output.push_str(&substitute_char.to_string().repeat(token.len()));
+                output.push_str(DIM.1);
+            }
+            (TokenKind::Changed, true) => {
+                output.push_str(HIGHLIGHT.0);
+                output.push_str(token);
+                output.push_str(HIGHLIGHT.1);
+            }
+            (TokenKind::Normalized, true) => {
+                output.push_str(PLACEHOLDER.0);
+                output.push_str(token);
+                output.push_str(PLACEHOLDER.1);
+            }
+            _ => output.push_str(token),
+        }
+    }
+    output
+}
+
+/// Processes a line, replacing repeated tokens with a substitute character
+/// Returns the processed line and the current words for the next iteration
+pub fn process_line(
+    line: &str,
+    previous_words: &[String],
+    substitute_char: char,
+) -> Result<(String, Vec<String>)> {
+    let (tokens, current_words) = classify_line(line, previous_words)?;
+    Ok((render_tokens(&tokens, substitute_char, false), current_words))
+}
+
+/// Whether to color output: asked for with `--color`, not turned off with `--no-color`
+/// and no `NO_COLOR` in the environment (https://no-color.org)
+pub fn color_enabled(color: bool, no_color: bool) -> bool {
+    color && !no_color && std::env::var_os("NO_COLOR").map_or(true, |value| value.is_empty())
+}
+
+/// Compares each line with the one before it, carrying the token history along
+#[derive(Debug, Clone)]
+pub struct LineDiffer {
+    substitute_char: char,
+    color: bool,
+    normalizer: Option<Normalizer>,
+    /// Shared by clones, so per-source copies add up to one summary
+    stats: Option<Arc<Mutex<LdiffStats>>>,
+    previous_words: Vec<String>,
+}
+
+impl LineDiffer {
+    pub fn new(substitute_char: char) -> Self {
+        Self { substitute_char, color: false, normalizer: None, stats: None, previous_words: Vec::new() }
+    }
+
+    /// Dim repeated tokens and highlight changed ones with ANSI colors
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Collapse timestamps, UUIDs, IP addresses and hex IDs into placeholders (`⟨ts⟩`, ...)
+    pub fn with_normalizer(mut self, normalizer: Option<Normalizer>) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Count lines, suppressed tokens and line templates for a `--stats` summary
+    pub fn with_stats(mut self, stats: bool) -> Self {
+        self.stats = stats.then(|| Arc::new(Mutex::new(LdiffStats::default())));
+        self
+    }
 
+    /// The figures so far, when collecting them
+    pub fn stats(&self) -> Option<LdiffStats> {
+        self.stats.as_ref().map(|stats| stats.lock().unwrap().clone())
+    }
+
+    fn render(&self, tokens: &[(TokenKind, String)]) -> String {
+        if let Some(stats) = &self.stats {
+            stats.lock().unwrap().record(tokens);
+        }
+        render_tokens(tokens, self.substitute_char, self.color)
+    }
+
+    /// Render `line` against the previous one and remember it for the next
+    pub fn process(&mut self, line: &str) -> Result<String> {
+        let (tokens, words) = classify_line_with(line, &self.previous_words, self.normalizer.as_ref())?;
+        self.previous_words = words;
+        Ok(self.render(&tokens))
+    }
+
+    /// Render `line` against `reference` instead of the previous line, leaving the
+    /// history untouched
+    pub fn process_against(&mut self, reference: &str, line: &str) -> Result<String> {
+        let (_, reference_words) = classify_line_with(reference, &[], self.normalizer.as_ref())?;
+        let (tokens, _) = classify_line_with(line, &reference_words, self.normalizer.as_ref())?;
+        Ok(self.render(&tokens))
+    }
+}
+
+/// Processes every line of `reader`, writing the rendered lines to `out`
+pub fn process_reader<R: BufRead, W: Write>(reader: R, differ: &mut LineDiffer, out: &mut W) -> Result<()> {
     for line in reader.lines() {
         let line = line?;
-        let (processed_line, current_words) = process_line(&line, &previous_words, substitute_char)?;
-        
-        writeln!(stdout, "{}", processed_line)?;
-        stdout.flush()?;
-        
-        previous_words = current_words;
+        writeln!(out, "{}", differ.process(&line)?)?;
+        out.flush()?;
     }
-
     Ok(())
 }
 
+/// Processes input from stdin line by line, writing to stdout
+pub fn process_stdin(substitute_char: char) -> Result<()> {
+    let stdin = io::stdin();
+    let reader = BufReader::new(stdin.lock());
+    let mut stdout = io::stdout();
+    process_reader(reader, &mut LineDiffer::new(substitute_char), &mut stdout)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,4 +383,32 @@ mod tests {
         assert_eq!(output, "***** world");
         assert_eq!(words, vec!["hello", "world"]);
     }
+
+    #[test]
+    fn test_color_dims_repeated_and_highlights_changed() {
+        let mut differ = LineDiffer::new('░').with_color(true);
+        assert_eq!(differ.process("GET /users 200").unwrap(), "GET /users 200");
+        assert_eq!(
+            differ.process("GET /users 404 slow").unwrap(),
+            "\x1b[2m░░░\x1b[22m /\x1b[2m░░░░░\x1b[22m \x1b[1;33m404\x1b[22;39m slow"
+        );
+    }
+
+    #[test]
+    fn test_normalize_collapses_value_classes() {
+        let normalizer = Normalizer::new().unwrap();
+        let mut differ = LineDiffer::new('░').with_normalizer(Some(normalizer));
+        assert_eq!(
+            differ.process("2024-01-01T10:00:00Z req 3f2a9c1e-8b4d-4e6f-9a0b-1c2d3e4f5a6b from 10.0.0.7 obj 0x7ffd12").unwrap(),
+            "⟨ts⟩ req ⟨uuid⟩ from ⟨ip⟩ obj ⟨hex⟩"
+        );
+        // Placeholders keep their word positions, so the rest still lines up
+        assert_eq!(
+            differ.process("2024-01-01T10:00:05Z req 9e1b2c3d-0000-4e6f-9a0b-1c2d3e4f5a6b from 10.0.0.9 obj 0x1 done").unwrap(),
+            "⟨ts⟩ ░░░ ⟨uuid⟩ ░░░░ ⟨ip⟩ ░░░ ⟨hex⟩ done"
+        );
+        // Digits alone are not a hex ID
+        assert_eq!(differ.process("count 12345678").unwrap(), "count 12345678");
+        assert_eq!(differ.process("commit deadbeef42").unwrap(), "commit ⟨hex⟩");
+    }
 }
\ No newline at end of file
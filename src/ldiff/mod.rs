@@ -1,6 +1,14 @@
+pub mod compare;
+pub mod follow;
 pub mod ldiff_common;
+pub mod stats;
+pub mod streams;
 
+pub use compare::compare_files;
+pub use follow::{follow, stop_on_interrupt, FollowOptions};
 pub use ldiff_common::*;
+pub use stats::LdiffStats;
+pub use streams::{process_labelled_streams, process_labelled_streams_with, StreamSource, TimestampParser};
 
 use anyhow::Result;
 use std::process::Command;
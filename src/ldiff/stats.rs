@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use super::ldiff_common::TokenKind;
+
+/// Distinct templates tracked before new ones are only counted as "other"
+const MAX_TEMPLATES: usize = 10_000;
+
+/// Templates listed in the summary
+pub const TOP_TEMPLATES: usize = 10;
+
+/// End-of-stream figures for `ldiff --stats`
+#[derive(Debug, Clone, Default)]
+pub struct LdiffStats {
+    pub lines: usize,
+    /// Word and number tokens compared
+    pub tokens: usize,
+    /// Tokens replaced because they repeated the previous line
+    pub suppressed: usize,
+    templates: HashMap<String, usize>,
+    /// Lines whose template arrived after `MAX_TEMPLATES` distinct ones
+    untracked: usize,
+}
+
+impl LdiffStats {
+    /// Count one classified line
+    pub fn record(&mut self, tokens: &[(TokenKind, String)]) {
+        self.lines += 1;
+        for (kind, _) in tokens {
+            match kind {
+                TokenKind::Literal => {}
+                TokenKind::Repeated => {
+                    self.tokens += 1;
+                    self.suppressed += 1;
+                }
+                _ => self.tokens += 1,
+            }
+        }
+
+        let template = template(tokens);
+        if let Some(count) = self.templates.get_mut(&template) {
+            *count += 1;
+        } else if self.templates.len() < MAX_TEMPLATES {
+            self.templates.insert(template, 1);
+        } else {
+            self.untracked += 1;
+        }
+    }
+
+    /// Share of tokens suppressed, in percent
+    pub fn suppressed_percent(&self) -> f64 {
+        if self.tokens == 0 {
+            0.0
+        } else {
+            self.suppressed as f64 * 100.0 / self.tokens as f64
+        }
+    }
+
+    /// The most frequent templates, most frequent first (ties in template order)
+    pub fn top_templates(&self, count: usize) -> Vec<(&str, usize)> {
+        let mut templates: Vec<(&str, usize)> = self.templates.iter().map(|(t, n)| (t.as_str(), *n)).collect();
+        templates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        templates.truncate(count);
+        templates
+    }
+
+    pub fn write_summary<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        writeln!(out, "ldiff statistics")?;
+        writeln!(out, "  Lines processed:   {}", self.lines)?;
+        writeln!(
+            out,
+            "  Tokens suppressed: {} of {} ({:.1}%)",
+            self.suppressed,
+            self.tokens,
+            self.suppressed_percent()
+        )?;
+        let top = self.top_templates(TOP_TEMPLATES);
+        if !top.is_empty() {
+            writeln!(out, "  Top line templates:")?;
+            let width = top.first().map(|(_, n)| n.to_string().len()).unwrap_or(1);
+            for (template, count) in top {
+                writeln!(out, "    {:>width$}  {}", count, template, width = width)?;
+            }
+        }
+        if self.untracked > 0 {
+            writeln!(out, "  ({} lines beyond {} distinct templates not ranked)", self.untracked, MAX_TEMPLATES)?;
+        }
+        Ok(())
+    }
+}
+
+/// The line's shape: numbers become `#`, color codes are dropped and whitespace runs
+/// collapse to one space; words, separators and `--normalize` placeholders stay
+fn template(tokens: &[(TokenKind, String)]) -> String {
+    let mut template = String::new();
+    for (kind, token) in tokens {
+        match kind {
+            TokenKind::Literal if token.starts_with('\x1b') => {}
+            TokenKind::Literal if token.trim().is_empty() => {
+                if !template.is_empty() {
+                    template.push(' ');
+                }
+            }
+            TokenKind::Normalized => template.push_str(token),
+            _ if token.chars().all(|c| c.is_ascii_digit()) => template.push('#'),
+            _ => template.push_str(token),
+        }
+    }
+    template.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldiff::LineDiffer;
+
+    #[test]
+    fn test_stats_count_suppression_and_templates() {
+        let mut differ = LineDiffer::new('░').with_stats(true);
+        for line in ["GET /users/1 200", "GET /users/2 200", "POST /login 302", "GET /users/3   200"] {
+            differ.process(line).unwrap();
+        }
+
+        let stats = differ.stats().unwrap();
+        assert_eq!(stats.lines, 4);
+        assert_eq!(stats.tokens, 15);
+        // GET, users and 200 on line 2; nothing lines up with the line before after that
+        assert_eq!(stats.suppressed, 3);
+        assert_eq!(format!("{:.1}", stats.suppressed_percent()), "20.0");
+        assert_eq!(stats.top_templates(2), vec![("GET /users/# #", 3), ("POST /login #", 1)]);
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines, Write};
+use std::path::PathBuf;
+
+use super::ldiff_common::LineDiffer;
+
+/// A labelled input for `--label-streams`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSource {
+    pub label: String,
+    /// `None` reads from stdin
+    pub path: Option<PathBuf>,
+}
+
+impl StreamSource {
+    /// Parse a `[LABEL=]PATH` argument; `-` means stdin and the label defaults to the file stem
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (label, path) = match spec.split_once('=') {
+            Some((label, path)) if !label.is_empty() => (Some(label.to_string()), path),
+            _ => (None, spec),
+        };
+
+        if path.is_empty() {
+            anyhow::bail!("Empty input path in '{}'", spec);
+        }
+
+        let path = if path == "-" { None } else { Some(PathBuf::from(path)) };
+        let label = label.unwrap_or_else(|| match &path {
+            Some(p) => p
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| p.display().to_string()),
+            None => "stdin".to_string(),
+        });
+
+        Ok(Self { label, path })
+    }
+
+    fn open(&self) -> Result<Box<dyn BufRead>> {
+        match &self.path {
+            Some(path) => {
+                let file = File::open(path)
+                    .with_context(|| format!("Failed to open input {}", path.display()))?;
+                Ok(Box::new(BufReader::new(file)))
+            }
+            None => Ok(Box::new(BufReader::new(io::stdin()))),
+        }
+    }
+}
+
+/// Parses leading timestamps from log lines using a chrono format string
+pub struct TimestampParser {
+    format: String,
+    /// Number of whitespace-separated fields the format spans
+    fields: usize,
+    ansi: Regex,
+}
+
+impl TimestampParser {
+    pub fn new(format: &str) -> Result<Self> {
+        let fields = format.split_whitespace().count();
+        if fields == 0 {
+            anyhow::bail!("Timestamp format must not be empty");
+        }
+
+        Ok(Self {
+            format: format.to_string(),
+            fields,
+            ansi: Regex::new(r"\x1b\[[0-9;]*[mGK]")?,
+        })
+    }
+
+    /// Parse the timestamp at the start of `line`, ignoring color codes and surrounding brackets
+    pub fn parse(&self, line: &str) -> Option<NaiveDateTime> {
+        let plain = self.ansi.replace_all(line, "");
+        let head: Vec<&str> = plain.split_whitespace().take(self.fields).collect();
+        if head.len() < self.fields {
+            return None;
+        }
+
+        let candidate = head.join(" ");
+        let candidate = candidate.trim_start_matches('[').trim_end_matches(']');
+        NaiveDateTime::parse_from_str(candidate, &self.format).ok()
+    }
+}
+
+/// One open input with its own token history
+struct LabelledStream {
+    label: String,
+    lines: Lines<Box<dyn BufRead>>,
+    pending: Option<(Option<NaiveDateTime>, String)>,
+    last_timestamp: Option<NaiveDateTime>,
+    differ: LineDiffer,
+}
+
+impl LabelledStream {
+    /// Make sure the next line (if any) is buffered; lines without a timestamp
+    /// inherit the previous one so continuation lines stay with their entry
+    fn fill(&mut self, parser: Option<&TimestampParser>) -> Result<()> {
+        if self.pending.is_some() {
+            return Ok(());
+        }
+
+        if let Some(line) = self.lines.next() {
+            let line = line?;
+            if let Some(timestamp) = parser.and_then(|p| p.parse(&line)) {
+                self.last_timestamp = Some(timestamp);
+            }
+            self.pending = Some((self.last_timestamp, line));
+        }
+
+        Ok(())
+    }
+}
+
+/// Merge several inputs into one output, prefixing each line with its source label.
+///
+/// Token normalization runs per source, so a line is only compared with the
+/// previous line from the same input. With a timestamp parser the inputs are
+/// interleaved by timestamp (ties keep input order); otherwise they are emitted
+/// one after another in the order given.
+pub fn process_labelled_streams<W: Write>(
+    sources: &[StreamSource],
+    substitute_char: char,
+    timestamps: Option<&TimestampParser>,
+    out: &mut W,
+) -> Result<()> {
+    process_labelled_streams_with(sources, &LineDiffer::new(substitute_char), timestamps, out)
+}
+
+/// `process_labelled_streams`, rendering each source with a copy of `differ`
+pub fn process_labelled_streams_with<W: Write>(
+    sources: &[StreamSource],
+    differ: &LineDiffer,
+    timestamps: Option<&TimestampParser>,
+    out: &mut W,
+) -> Result<()> {
+    if sources.iter().filter(|s| s.path.is_none()).count() > 1 {
+        anyhow::bail!("stdin ('-') can only be used as one input");
+    }
+
+    let width = sources.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+    let mut streams = sources
+        .iter()
+        .map(|source| {
+            Ok(LabelledStream {
+                label: source.label.clone(),
+                lines: source.open()?.lines(),
+                pending: None,
+                last_timestamp: None,
+                differ: differ.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    loop {
+        for stream in streams.iter_mut() {
+            stream.fill(timestamps)?;
+        }
+
+        let next = if timestamps.is_some() {
+            // Earliest pending line wins; min_by_key keeps the first on ties
+            streams
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| s.pending.as_ref().map(|(ts, _)| (i, *ts)))
+                .min_by_key(|(_, ts)| *ts)
+                .map(|(i, _)| i)
+        } else {
+            streams.iter().position(|s| s.pending.is_some())
+        };
+
+        let index = match next {
+            Some(index) => index,
+            None => break,
+        };
+        let stream = &mut streams[index];
+        let (_, line) = stream.pending.take().expect("selected stream has a pending line");
+
+        let processed = stream.differ.process(&line)?;
+
+        writeln!(out, "[{:<width$}] {}", stream.label, processed, width = width)?;
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn source(label: &str, content: &str) -> (StreamSource, NamedTempFile) {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        let source = StreamSource { label: label.to_string(), path: Some(file.path().to_path_buf()) };
+        (source, file)
+    }
+
+    #[test]
+    fn test_parse_stream_source() {
+        let labelled = StreamSource::parse("api=/var/log/api.log").unwrap();
+        assert_eq!(labelled.label, "api");
+        assert_eq!(labelled.path, Some(PathBuf::from("/var/log/api.log")));
+
+        let unlabelled = StreamSource::parse("logs/worker.log").unwrap();
+        assert_eq!(unlabelled.label, "worker");
+
+        let stdin = StreamSource::parse("-").unwrap();
+        assert_eq!(stdin.label, "stdin");
+        assert!(stdin.path.is_none());
+    }
+
+    #[test]
+    fn test_per_source_normalization() {
+        let (a, _fa) = source("a", "hello world\nhello there\n");
+        let (b, _fb) = source("b", "hello world\n");
+        let mut out = Vec::new();
+
+        process_labelled_streams(&[a, b], '░', None, &mut out).unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        assert_eq!(output, "[a] hello world\n[a] ░░░░░ there\n[b] hello world\n");
+    }
+
+    #[test]
+    fn test_interleave_by_timestamp() {
+        let (api, _fa) = source("api", "2024-01-01 10:00:00 start\n2024-01-01 10:00:02 done\n  trace\n");
+        let (db, _fb) = source("db", "2024-01-01 10:00:01 query\n");
+        let parser = TimestampParser::new("%Y-%m-%d %H:%M:%S").unwrap();
+        let mut out = Vec::new();
+
+        process_labelled_streams(&[api, db], '░', Some(&parser), &mut out).unwrap();
+
+        let lines: Vec<String> = String::from_utf8(out).unwrap().lines().map(String::from).collect();
+        assert!(lines[0].starts_with("[api]") && lines[0].ends_with("start"));
+        assert!(lines[1].starts_with("[db ]") && lines[1].ends_with("query"));
+        assert!(lines[2].starts_with("[api]") && lines[2].ends_with("done"));
+        assert!(lines[3].starts_with("[api]") && lines[3].ends_with("trace"));
+    }
+
+    #[test]
+    fn test_timestamp_parser_brackets() {
+        let parser = TimestampParser::new("%Y-%m-%dT%H:%M:%S").unwrap();
+        assert!(parser.parse("[2024-01-01T10:00:00] message").is_some());
+        assert!(parser.parse("no timestamp here").is_none());
+    }
+}
@@ -2,18 +2,29 @@ pub mod refac;
 pub mod scrap;
 pub mod st8;
 pub mod ldiff;
+pub mod i18n;
+pub mod locks;
 pub mod logging;
+pub mod read_only;
+pub mod secrets;
+pub mod suggest;
 pub mod workspace_state;
+// Shared file watching service
+pub mod watcher;
 // Entity system
 pub mod entities;
-// MCP server - temporarily disabled during schema-based refactor
-// pub mod mcp_server;
+// MCP server HTTP side: dashboard API and live change stream
+pub mod mcp_server;
 // MCP protocol
 pub mod mcp_protocol;
 // Code analysis with ast-grep
 pub mod code_analysis;
 // Interactive tree navigation
 pub mod interactive_tree;
+// Quick navigation (`wsb open`)
+pub mod open;
+// Full-screen terminal views (`wsb board`, `wsb dashboard --tui`)
+pub mod tui;
 
 use anyhow::{Context, Result};
 use std::path::Path;
@@ -69,6 +80,20 @@ pub struct RenameConfig {
     pub verbose: bool,
     pub follow_symlinks: bool,
     pub backup: bool,
+    /// Only process paths matching one of these globs (relative to the root)
+    pub include_patterns: Vec<String>,
+    /// Skip paths matching any of these globs (relative to the root)
+    pub exclude_patterns: Vec<String>,
+    /// Honour `.gitignore`, `.ignore` and `.refacignore` files during traversal
+    pub respect_ignore_files: bool,
+    /// Also replace the snake/SCREAMING/kebab/camel/Pascal case variants of the pattern
+    pub match_case_styles: bool,
+    /// Only replace content in files with these extensions (empty = all text files)
+    pub content_extensions: Vec<String>,
+    /// Replace content at all; paths are still renamed when this is off
+    pub replace_content: bool,
+    /// Every pattern/substitute pair applied in the pass (`pattern`/`substitute` is the first)
+    pub pairs: Vec<(String, String)>,
 }
 
 impl RenameConfig {
@@ -91,12 +116,19 @@ impl RenameConfig {
         
         Ok(Self {
             root_dir: root_path,
+            pairs: vec![(pattern.clone(), substitute.clone())],
             pattern,
             substitute,
             assume_yes: false,
             verbose: false,
             follow_symlinks: false,
             backup: false,
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
+            respect_ignore_files: true,
+            match_case_styles: false,
+            content_extensions: Vec::new(),
+            replace_content: true,
         })
     }
     
@@ -120,6 +152,49 @@ impl RenameConfig {
         self.backup = backup;
         self
     }
+    
+    pub fn with_include_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.include_patterns = patterns;
+        self
+    }
+    
+    pub fn with_exclude_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.exclude_patterns = patterns;
+        self
+    }
+    
+    pub fn with_respect_ignore_files(mut self, respect_ignore_files: bool) -> Self {
+        self.respect_ignore_files = respect_ignore_files;
+        self
+    }
+    
+    pub fn with_match_case_styles(mut self, match_case_styles: bool) -> Self {
+        self.match_case_styles = match_case_styles;
+        self
+    }
+
+    /// Apply several pairs in one pass; the first one becomes `pattern`/`substitute`
+    pub fn with_pairs(mut self, pairs: Vec<(String, String)>) -> Result<Self> {
+        match pairs.first() {
+            Some((pattern, substitute)) => {
+                self.pattern = pattern.clone();
+                self.substitute = substitute.clone();
+            }
+            None => anyhow::bail!("At least one pattern/substitute pair is required"),
+        }
+        self.pairs = pairs;
+        Ok(self)
+    }
+
+    pub fn with_content_extensions(mut self, content_extensions: Vec<String>) -> Self {
+        self.content_extensions = content_extensions;
+        self
+    }
+
+    pub fn with_replace_content(mut self, replace_content: bool) -> Self {
+        self.replace_content = replace_content;
+        self
+    }
 }
 
 /// Statistics about the rename operation
@@ -128,13 +203,26 @@ pub struct RenameStats {
     pub files_with_content_changes: usize,
     pub files_renamed: usize,
     pub directories_renamed: usize,
+    /// Content files processed during execution, including ones that failed
     pub files_processed: usize,
+    /// Entries examined while discovering changes
+    pub entries_scanned: usize,
+    /// Symlinks whose target path is rewritten (`--rewrite-link-targets`)
+    pub links_retargeted: usize,
     pub errors: Vec<String>,
+    /// Entries skipped rather than failing the run, such as symlink cycles
+    pub warnings: Vec<String>,
+    /// Text files whose content was left alone because their encoding is unknown
+    pub skipped_encodings: Vec<String>,
+    /// Rewritten files whose permissions, owner, xattrs or mtime could not be restored
+    pub metadata_not_preserved: Vec<String>,
+    /// Replacements by region (code, comments, strings) when a region is skipped
+    pub replacements_by_region: Option<crate::refac::regions::RegionCounts>,
 }
 
 impl RenameStats {
     pub fn total_changes(&self) -> usize {
-        self.files_with_content_changes + self.files_renamed + self.directories_renamed
+        self.files_with_content_changes + self.files_renamed + self.directories_renamed + self.links_retargeted
     }
     
     pub fn add_error(&mut self, error: String) {
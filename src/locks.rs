@@ -0,0 +1,362 @@
+//! Advisory locks serializing document-writing operations.
+//!
+//! `wsb update`, `wsb end`, consolidation and the MCP server's session tools all rewrite
+//! markdown docs and rendered templates. Run at the same time, their writes interleave.
+//! Each takes a named lock first: a file under `.wsb/locks` recording the holder's pid,
+//! host, command and start time, created atomically so only one process holds it.
+//!
+//! Waiters queue behind the holder with a ticket in `<name>.queue`, oldest first, and
+//! take the lock in turn. With `--no-wait` (or `WSB_LOCK_NO_WAIT=1`) a held lock fails
+//! the command at once instead. A lock whose holder has exited on this host, or that is
+//! older than [`STALE_AFTER_SECS`], is stale and is taken over; `wsb locks` shows every
+//! lock, its queue and whether it is stale.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Directory holding the lock files, relative to the project root
+pub const LOCK_DIR: &str = ".wsb/locks";
+
+/// Lock shared by everything that writes markdown docs or rendered templates
+pub const DOCUMENTS: &str = "documents";
+
+/// Environment variable that makes a held lock fail instead of waiting
+pub const NO_WAIT_ENV: &str = "WSB_LOCK_NO_WAIT";
+
+/// Locks held longer than this are assumed abandoned, whoever holds them
+pub const STALE_AFTER_SECS: i64 = 60 * 60;
+
+/// How long a queued command waits before giving up
+pub const WAIT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+static NO_WAIT: AtomicBool = AtomicBool::new(false);
+
+/// Locks this process holds, so nested operations do not wait on themselves
+static HELD: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Fail fast on held locks for the rest of the process
+pub fn set_no_wait() {
+    NO_WAIT.store(true, Ordering::SeqCst);
+}
+
+fn no_wait() -> bool {
+    NO_WAIT.load(Ordering::SeqCst)
+        || std::env::var(NO_WAIT_ENV).map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false)
+}
+
+/// Who holds (or is queued for) a lock
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub host: String,
+    pub command: String,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl LockInfo {
+    fn current(command: &str) -> Self {
+        Self { pid: std::process::id(), host: host_name(), command: command.to_string(), acquired_at: Utc::now() }
+    }
+
+    /// The holder has exited on this host, or has held the lock too long to be trusted
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        (self.host == host_name() && !process_alive(self.pid))
+            || (now - self.acquired_at).num_seconds() > STALE_AFTER_SECS
+    }
+
+    fn describe(&self) -> String {
+        format!("pid {} on {} ({}) since {}", self.pid, self.host, self.command, self.acquired_at.format("%Y-%m-%d %H:%M:%S UTC"))
+    }
+}
+
+/// A held lock, released when dropped
+#[derive(Debug)]
+pub struct LockGuard {
+    name: String,
+    /// `None` for a lock this process already held, or when nothing may be written
+    path: Option<PathBuf>,
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            if let Err(e) = fs::remove_file(path) {
+                log::warn!("Failed to release lock {}: {}", path.display(), e);
+            }
+            if let Some(held) = HELD.lock().unwrap().as_mut() {
+                held.remove(&self.name);
+            }
+        }
+    }
+}
+
+fn lock_path(root: &Path, name: &str) -> PathBuf {
+    root.join(LOCK_DIR).join(format!("{}.lock", name))
+}
+
+fn queue_dir(root: &Path, name: &str) -> PathBuf {
+    root.join(LOCK_DIR).join(format!("{}.queue", name))
+}
+
+fn read_info(path: &Path) -> Option<LockInfo> {
+    fs::read_to_string(path).ok().and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Take the lock `name` for `command`, queueing behind its holder unless `--no-wait` is set
+pub fn acquire(root: &Path, name: &str, command: &str) -> Result<LockGuard> {
+    if crate::read_only::is_enabled() {
+        return Ok(LockGuard { name: name.to_string(), path: None });
+    }
+    {
+        let mut held = HELD.lock().unwrap();
+        let held = held.get_or_insert_with(HashSet::new);
+        if held.contains(name) {
+            return Ok(LockGuard { name: name.to_string(), path: None });
+        }
+    }
+
+    fs::create_dir_all(root.join(LOCK_DIR)).with_context(|| format!("Failed to create {}", root.join(LOCK_DIR).display()))?;
+    let info = LockInfo::current(command);
+
+    if no_wait() {
+        return match try_lock(root, name, &info)? {
+            Some(guard) => Ok(guard),
+            None => {
+                let holder = read_info(&lock_path(root, name)).map(|h| h.describe()).unwrap_or_else(|| "another process".to_string());
+                anyhow::bail!("Lock '{}' is held by {}; retry later, or run without --no-wait to queue", name, holder)
+            }
+        };
+    }
+
+    let ticket = Ticket::join(root, name, &info)?;
+    let started = Instant::now();
+    let mut announced = false;
+    loop {
+        if ticket.is_next()? {
+            if let Some(guard) = try_lock(root, name, &info)? {
+                return Ok(guard);
+            }
+        }
+        if !announced {
+            let holder = read_info(&lock_path(root, name)).map(|h| h.describe()).unwrap_or_else(|| "another process".to_string());
+            eprintln!("Waiting for lock '{}' held by {}...", name, holder);
+            announced = true;
+        }
+        if started.elapsed() > WAIT_TIMEOUT {
+            anyhow::bail!("Timed out after {}s waiting for lock '{}' (see `wsb locks`)", WAIT_TIMEOUT.as_secs(), name);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Create the lock file if it is free or stale
+fn try_lock(root: &Path, name: &str, info: &LockInfo) -> Result<Option<LockGuard>> {
+    let path = lock_path(root, name);
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(serde_json::to_string_pretty(info)?.as_bytes())?;
+                file.sync_all()?;
+                HELD.lock().unwrap().get_or_insert_with(HashSet::new).insert(name.to_string());
+                return Ok(Some(LockGuard { name: name.to_string(), path: Some(path) }));
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => match read_info(&path) {
+                Some(holder) if holder.is_stale(Utc::now()) => {
+                    log::warn!("Taking over stale lock '{}' held by {}", name, holder.describe());
+                    let _ = fs::remove_file(&path);
+                }
+                None if unwritten_and_old(&path) => {
+                    log::warn!("Taking over unreadable lock '{}'", name);
+                    let _ = fs::remove_file(&path);
+                }
+                // Held, or just created and not yet written
+                _ => return Ok(None),
+            },
+            Err(e) => return Err(e).with_context(|| format!("Failed to create lock {}", path.display())),
+        }
+    }
+    Ok(None)
+}
+
+/// A lock file left unreadable for a while, by a holder that died while creating it
+fn unwritten_and_old(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_or(false, |modified| modified.elapsed().map_or(false, |age| age > Duration::from_secs(10)))
+}
+
+/// A place in a lock's queue, left when dropped
+struct Ticket {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl Ticket {
+    fn join(root: &Path, name: &str, info: &LockInfo) -> Result<Self> {
+        let dir = queue_dir(root, name);
+        fs::create_dir_all(&dir)?;
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let path = dir.join(format!("{:020}-{}", nanos, info.pid));
+        fs::write(&path, serde_json::to_string_pretty(info)?)?;
+        Ok(Self { dir, path })
+    }
+
+    /// Whether this is the oldest live ticket; tickets of exited waiters are dropped
+    fn is_next(&self) -> Result<bool> {
+        for (path, info) in queued(&self.dir) {
+            if path == self.path {
+                return Ok(true);
+            }
+            match info {
+                Some(info) if info.is_stale(Utc::now()) => {
+                    let _ = fs::remove_file(&path);
+                }
+                None if unwritten_and_old(&path) => {
+                    let _ = fs::remove_file(&path);
+                }
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Queue tickets, oldest first
+fn queued(dir: &Path) -> Vec<(PathBuf, Option<LockInfo>)> {
+    let mut tickets: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return Vec::new(),
+    };
+    tickets.sort();
+    tickets.into_iter().map(|path| {
+        let info = read_info(&path);
+        (path, info)
+    }).collect()
+}
+
+/// A lock as shown by `wsb locks`
+#[derive(Debug, Clone, Serialize)]
+pub struct LockStatus {
+    pub name: String,
+    /// `None` when the lock file cannot be read
+    pub holder: Option<LockInfo>,
+    pub stale: bool,
+    pub waiting: Vec<LockInfo>,
+}
+
+/// Every lock currently held under `root`
+pub fn list(root: &Path) -> Vec<LockStatus> {
+    let dir = root.join(LOCK_DIR);
+    let mut names: Vec<String> = match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().to_str().and_then(|n| n.strip_suffix(".lock")).map(str::to_string))
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    names.sort();
+
+    let now = Utc::now();
+    names
+        .into_iter()
+        .map(|name| {
+            let path = lock_path(root, &name);
+            let holder = read_info(&path);
+            let stale = match &holder {
+                Some(holder) => holder.is_stale(now),
+                None => unwritten_and_old(&path),
+            };
+            let waiting = queued(&queue_dir(root, &name)).into_iter().filter_map(|(_, info)| info).collect();
+            LockStatus { name, holder, stale, waiting }
+        })
+        .collect()
+}
+
+/// Remove stale locks, returning their names
+pub fn clear_stale(root: &Path) -> Result<Vec<String>> {
+    let mut cleared = Vec::new();
+    for status in list(root) {
+        if status.stale {
+            fs::remove_file(lock_path(root, &status.name))?;
+            cleared.push(status.name);
+        }
+    }
+    Ok(cleared)
+}
+
+fn host_name() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
+
+/// Without a cheap liveness check, only age makes a lock stale
+#[cfg(not(unix))]
+pub(crate) fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive_and_stale_locks_are_taken_over() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(LOCK_DIR)).unwrap();
+
+        let other = LockInfo { pid: std::process::id(), host: "elsewhere".to_string(), command: "wsb update".to_string(), acquired_at: Utc::now() };
+        fs::write(lock_path(root, "docs"), serde_json::to_string(&other).unwrap()).unwrap();
+        assert!(try_lock(root, "docs", &LockInfo::current("wsb end")).unwrap().is_none());
+        assert!(!list(root)[0].stale);
+
+        let abandoned = LockInfo { acquired_at: Utc::now() - chrono::Duration::hours(2), ..other };
+        fs::write(lock_path(root, "docs"), serde_json::to_string(&abandoned).unwrap()).unwrap();
+        assert!(list(root)[0].stale);
+
+        let guard = try_lock(root, "docs", &LockInfo::current("wsb end")).unwrap().unwrap();
+        assert_eq!(read_info(&lock_path(root, "docs")).unwrap().command, "wsb end");
+        // Nested acquisition in the same process does not wait on itself
+        drop(acquire(root, "docs", "wsb consolidate").unwrap());
+        assert!(lock_path(root, "docs").exists());
+        drop(guard);
+        assert!(!lock_path(root, "docs").exists());
+        assert!(list(root).is_empty());
+    }
+}
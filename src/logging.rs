@@ -113,6 +113,22 @@ pub fn init_simple_logging(debug_mode: bool) -> Result<()> {
     Ok(())
 }
 
+/// Initialize console-only logging without touching the filesystem
+pub fn init_console_logging(debug_mode: bool) -> Result<()> {
+    let level = if debug_mode {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Warn
+    };
+
+    env_logger::Builder::from_default_env()
+        .filter_level(level)
+        .format_timestamp_secs()
+        .init();
+
+    Ok(())
+}
+
 /// Get the current workspace root directory
 pub fn detect_workspace_root() -> Option<PathBuf> {
     let current_dir = std::env::current_dir().ok()?;
@@ -133,6 +149,11 @@ pub fn detect_workspace_root() -> Option<PathBuf> {
 
 /// Initialize logging with automatic workspace detection
 pub fn setup_logging(debug_mode: bool) -> Result<()> {
+    // Read-only runs must not create .wsb/logs, so log to the console only
+    if crate::read_only::is_enabled() {
+        return init_console_logging(debug_mode);
+    }
+
     if let Some(workspace_root) = detect_workspace_root() {
         init_logging(&workspace_root, debug_mode)
     } else {
@@ -61,6 +61,24 @@ pub struct Tool {
     pub input_schema: serde_json::Value,
 }
 
+/// Read-only project data a client can load without calling a tool
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ToolCallRequest {
     pub name: String,
@@ -80,6 +98,30 @@ pub struct ToolContent {
     pub text: String,
 }
 
+/// Mutating tools that accept `dry_run: true`. Any other tool called with `dry_run`
+/// is refused rather than executed.
+const PREVIEWABLE_TOOLS: &[&str] = &["add_feature", "update_feature_state", "add_task", "update_task_status", "link_entities"];
+
+const DIRECTIVES_URI: &str = "wsb://directives";
+const NOTES_URI: &str = "wsb://notes";
+const FILES_URI: &str = "wsb://files";
+
+/// Files listed by the file-tree resource before it is cut short
+const MAX_TREE_FILES: usize = 5000;
+
+/// Attribute the `wsb` commands this server runs to the connected client. The client name
+/// from `initialize` becomes `WSB_ACTOR` for child processes unless an actor is already set.
+fn attribute_to_client(params: &serde_json::Value) {
+    if std::env::var(crate::entities::actors::ACTOR_ENV).is_ok() {
+        return;
+    }
+    if let Some(name) = params.pointer("/clientInfo/name").and_then(|v| v.as_str()) {
+        if !name.trim().is_empty() {
+            std::env::set_var(crate::entities::actors::ACTOR_ENV, format!("agent:{}", name.trim()));
+        }
+    }
+}
+
 /// Complete MCP Protocol handler for wsb integration with Claude
 pub struct McpProtocolHandler {
     message_id_counter: u64,
@@ -210,6 +252,38 @@ impl McpProtocolHandler {
         self.session_metrics.total_messages += 1;
         self.track_context_usage(&message).await?;
         
+        let method = message.method.clone();
+        let tool = match method.as_deref() {
+            Some("tools/call") => message.params.as_ref().and_then(|p| p.get("name")).and_then(|v| v.as_str()).map(str::to_string),
+            _ => None,
+        };
+        let outcome = self.respond(message).await;
+
+        if let Some(method) = &method {
+            crate::mcp_server::metrics::RequestLog {
+                transport: "mcp",
+                method,
+                path: None,
+                tool: tool.as_deref(),
+                status: if matches!(outcome, Ok(true)) { "ok" } else { "error" }.to_string(),
+                duration_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+            }
+            .emit();
+        }
+        outcome?;
+
+        // Record response time
+        let response_time = start_time.elapsed().as_millis() as u64;
+        self.session_metrics.response_times.push(response_time);
+        
+        // Check context threshold after processing message
+        self.check_context_threshold().await?;
+        
+        Ok(())
+    }
+
+    /// Answer one message; false when it was answered with an error
+    async fn respond(&mut self, message: McpMessage) -> Result<bool> {
         match message.method.as_deref() {
             Some("tools/list") => {
                 let response = self.handle_tools_list(message.id).await?;
@@ -220,9 +294,24 @@ impl McpProtocolHandler {
                 if let Some(params) = message.params {
                     let response = self.handle_tools_call(message.id, params).await?;
                     self.send_message_to_claude(&response).await?;
+                    let failed = response.result.as_ref().and_then(|r| r.get("is_error")).and_then(|v| v.as_bool());
+                    return Ok(failed != Some(true));
                 }
             }
+            Some("resources/list") => {
+                let response = self.handle_resources_list(message.id).await?;
+                self.send_message_to_claude(&response).await?;
+            }
+            Some("resources/read") => {
+                let uri = message.params.as_ref().and_then(|p| p.get("uri")).and_then(|v| v.as_str()).unwrap_or_default();
+                let response = self.handle_resources_read(message.id, uri).await?;
+                self.send_message_to_claude(&response).await?;
+                return Ok(response.error.is_none());
+            }
             Some("initialize") => {
+                if let Some(params) = &message.params {
+                    attribute_to_client(params);
+                }
                 let response = self.handle_initialize_response(message.id).await?;
                 self.send_message_to_claude(&response).await?;
             }
@@ -230,15 +319,7 @@ impl McpProtocolHandler {
                 log::debug!("Unhandled MCP method: {:?}", message.method);
             }
         }
-        
-        // Record response time
-        let response_time = start_time.elapsed().as_millis() as u64;
-        self.session_metrics.response_times.push(response_time);
-        
-        // Check context threshold after processing message
-        self.check_context_threshold().await?;
-        
-        Ok(())
+        Ok(true)
     }
 
     /// Handle tools/list request from Claude
@@ -274,13 +355,53 @@ impl McpProtocolHandler {
         })
     }
 
+    /// Handle resources/list request from Claude
+    async fn handle_resources_list(&self, request_id: Option<u64>) -> Result<McpMessage> {
+        let resources = self.get_available_resources().await?;
+
+        Ok(McpMessage {
+            jsonrpc: "2.0".to_string(),
+            id: request_id,
+            method: None,
+            params: None,
+            result: Some(serde_json::json!({
+                "resources": resources
+            })),
+            error: None,
+        })
+    }
+
+    /// Handle resources/read request from Claude
+    async fn handle_resources_read(&self, request_id: Option<u64>, uri: &str) -> Result<McpMessage> {
+        let (result, error) = match self.read_resource(uri).await {
+            Ok(contents) => (Some(serde_json::json!({ "contents": [contents] })), None),
+            Err(e) => (None, Some(McpError {
+                code: -32002,
+                message: e.to_string(),
+                data: Some(serde_json::json!({ "uri": uri })),
+            })),
+        };
+
+        Ok(McpMessage {
+            jsonrpc: "2.0".to_string(),
+            id: request_id,
+            method: None,
+            params: None,
+            result,
+            error,
+        })
+    }
+
     /// Handle initialize response
     async fn handle_initialize_response(&self, request_id: Option<u64>) -> Result<McpMessage> {
         let capabilities = ServerCapabilities {
             tools: Some(ToolsCapability {
                 list_changed: Some(true),
             }),
-            resources: None,
+            resources: Some(ResourcesCapability {
+                subscribe: Some(false),
+                list_changed: Some(false),
+            }),
             prompts: None,
             logging: Some(LoggingCapability {
                 level: Some("info".to_string()),
@@ -310,7 +431,10 @@ impl McpProtocolHandler {
             tools: Some(ToolsCapability {
                 list_changed: Some(true),
             }),
-            resources: None,
+            resources: Some(ResourcesCapability {
+                subscribe: Some(false),
+                list_changed: Some(false),
+            }),
             prompts: None,
             logging: Some(LoggingCapability {
                 level: Some("info".to_string()),
@@ -357,6 +481,10 @@ impl McpProtocolHandler {
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Validate and report what would change without writing anything"
+                        },
                         "name": {
                             "type": "string",
                             "description": "Feature name"
@@ -385,6 +513,10 @@ impl McpProtocolHandler {
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Validate and report what would change without writing anything"
+                        },
                         "feature_id": {
                             "type": "string",
                             "description": "Feature ID (e.g., F0111)"
@@ -424,16 +556,67 @@ impl McpProtocolHandler {
                         "recent": {
                             "type": "boolean",
                             "description": "Show only recently updated features"
+                        },
+                        "tag": {
+                            "type": "string",
+                            "description": "Only features carrying these tags (comma-separated)"
+                        },
+                        "view": {
+                            "type": "string",
+                            "description": "Name of a saved feature view to apply (see list_views)"
+                        }
+                    }
+                }),
+            },
+            Tool {
+                name: "list_tasks".to_string(),
+                description: "List tasks with optional filtering by status, priority, feature, or a saved view".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "status": {
+                            "type": "string",
+                            "description": "Filter by task status",
+                            "enum": ["pending", "in_progress", "completed", "blocked"]
+                        },
+                        "priority": {
+                            "type": "string",
+                            "description": "Filter by priority",
+                            "enum": ["high", "medium", "low"]
+                        },
+                        "feature_id": {
+                            "type": "string",
+                            "description": "Filter by feature ID"
+                        },
+                        "tag": {
+                            "type": "string",
+                            "description": "Only tasks carrying these tags (comma-separated)"
+                        },
+                        "view": {
+                            "type": "string",
+                            "description": "Name of a saved task view to apply; the other filters override it"
                         }
                     }
                 }),
             },
+            Tool {
+                name: "list_views".to_string(),
+                description: "List the saved views (named task and feature filters) of the project".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+            },
             Tool {
                 name: "add_task".to_string(),
                 description: "Add a new task to the project task management system".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Validate and report what would change without writing anything"
+                        },
                         "title": {
                             "type": "string",
                             "description": "Task title"
@@ -461,6 +644,10 @@ impl McpProtocolHandler {
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Validate and report what would change without writing anything"
+                        },
                         "task_id": {
                             "type": "string",
                             "description": "Task ID"
@@ -591,6 +778,29 @@ impl McpProtocolHandler {
                     "required": ["name"]
                 }),
             },
+            Tool {
+                name: "bootstrap_project".to_string(),
+                description: "Bootstrap a new ws-managed project in the current directory: workspace state, database, project row, starter directives and templates, and git exclusions. Returns a summary of what was created".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "Project name"
+                        },
+                        "profile": {
+                            "type": "string",
+                            "description": "Project profile; selects the starter directives",
+                            "enum": ["general", "web", "api", "cli", "library"]
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "Project description"
+                        }
+                    },
+                    "required": ["name"]
+                }),
+            },
             Tool {
                 name: "add_milestone".to_string(),
                 description: "Add a new project milestone with feature linkage".to_string(),
@@ -712,40 +922,396 @@ impl McpProtocolHandler {
                     "properties": {
                         "milestone_id": {
                             "type": "string",
-                            "description": "Milestone ID to retrieve details for"
+                            "description": "Milestone ID to retrieve details for"
+                        }
+                    },
+                    "required": ["milestone_id"]
+                }),
+            },
+            Tool {
+                name: "remove_milestone".to_string(),
+                description: "Remove a milestone from the project".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "milestone_id": {
+                            "type": "string",
+                            "description": "Milestone ID to remove"
+                        },
+                        "force": {
+                            "type": "boolean",
+                            "description": "Force removal without confirmation",
+                            "default": false
+                        }
+                    },
+                    "required": ["milestone_id"]
+                }),
+            },
+            Tool {
+                name: "link_entities".to_string(),
+                description: "Create a typed relationship between two entities (validated against the allowed relationship matrix)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Validate and report what would change without writing anything"
+                        },
+                        "from_entity": {
+                            "type": "string",
+                            "description": "Source entity ID"
+                        },
+                        "from_type": {
+                            "type": "string",
+                            "description": "Source entity type",
+                            "enum": ["project", "feature", "task", "session", "directive"]
+                        },
+                        "to_entity": {
+                            "type": "string",
+                            "description": "Target entity ID"
+                        },
+                        "to_type": {
+                            "type": "string",
+                            "description": "Target entity type",
+                            "enum": ["project", "feature", "task", "session", "directive"]
+                        },
+                        "relationship_type": {
+                            "type": "string",
+                            "description": "Relationship type (depends_on, blocks, implements, worked_in)"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "Optional description of the relationship"
+                        }
+                    },
+                    "required": ["from_entity", "from_type", "to_entity", "to_type", "relationship_type"]
+                }),
+            },
+            Tool {
+                name: "list_directives".to_string(),
+                description: "List project directives (development rules), optionally filtered".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "category": {
+                            "type": "string",
+                            "description": "Filter by directive category",
+                            "enum": ["security", "testing", "coding", "methodology", "deployment"]
+                        },
+                        "enforcement": {
+                            "type": "string",
+                            "description": "Filter by enforcement level",
+                            "enum": ["mandatory", "recommended", "optional"]
+                        },
+                        "priority": {
+                            "type": "string",
+                            "description": "Filter by priority",
+                            "enum": ["critical", "high", "medium", "low"]
+                        }
+                    }
+                }),
+            },
+            Tool {
+                name: "search_directives".to_string(),
+                description: "Find directives whose ID, title or description contains the query".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Text to look for (case-insensitive)"
+                        },
+                        "category": {
+                            "type": "string",
+                            "description": "Only search this category",
+                            "enum": ["security", "testing", "coding", "methodology", "deployment"]
+                        }
+                    },
+                    "required": ["query"]
+                }),
+            },
+            Tool {
+                name: "add_directive".to_string(),
+                description: "Add a project directive (a rule future work must follow)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Short name of the rule"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "What the rule requires and why"
+                        },
+                        "category": {
+                            "type": "string",
+                            "enum": ["security", "testing", "coding", "methodology", "deployment"],
+                            "default": "methodology"
+                        },
+                        "enforcement": {
+                            "type": "string",
+                            "enum": ["mandatory", "recommended", "optional"],
+                            "default": "recommended"
+                        },
+                        "priority": {
+                            "type": "string",
+                            "enum": ["critical", "high", "medium", "low"],
+                            "default": "medium"
+                        }
+                    },
+                    "required": ["title", "description"]
+                }),
+            },
+            Tool {
+                name: "update_directive".to_string(),
+                description: "Change a directive's description, category, enforcement or priority".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "directive_id": {
+                            "type": "string",
+                            "description": "Directive ID (DIR-...)"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "New description"
+                        },
+                        "category": {
+                            "type": "string",
+                            "enum": ["security", "testing", "coding", "methodology", "deployment"]
+                        },
+                        "enforcement": {
+                            "type": "string",
+                            "enum": ["mandatory", "recommended", "optional"]
+                        },
+                        "priority": {
+                            "type": "string",
+                            "enum": ["critical", "high", "medium", "low"]
+                        }
+                    },
+                    "required": ["directive_id"]
+                }),
+            },
+            Tool {
+                name: "code_search".to_string(),
+                description: "Search the project's source files for a pattern; returns file, line and snippet for each match".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "pattern": {
+                            "type": "string",
+                            "description": "Pattern to search for"
+                        },
+                        "languages": {
+                            "type": "array",
+                            "items": {
+                                "type": "string",
+                                "enum": ["rust", "javascript", "typescript", "python", "go", "java", "c", "cpp"]
+                            },
+                            "description": "Only search files in these languages (default: every supported language)"
+                        },
+                        "max_results": {
+                            "type": "integer",
+                            "description": "Stop after this many matches",
+                            "minimum": 1,
+                            "default": 200
+                        }
+                    },
+                    "required": ["pattern"]
+                }),
+            },
+            Tool {
+                name: "list_notes".to_string(),
+                description: "List notes, optionally only those attached to one entity".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "entity_type": {
+                            "type": "string",
+                            "description": "Entity type the notes are attached to (feature, task, session, project)"
+                        },
+                        "entity_id": {
+                            "type": "string",
+                            "description": "Entity ID the notes are attached to"
+                        },
+                        "note_type": {
+                            "type": "string",
+                            "description": "Filter by note type",
+                            "enum": ["general", "implementation", "testing", "bug", "feature_request", "technical_debt", "decision"]
+                        },
+                        "project_wide": {
+                            "type": "boolean",
+                            "description": "Only project-wide notes"
+                        },
+                        "pinned": {
+                            "type": "boolean",
+                            "description": "Only pinned notes"
+                        }
+                    }
+                }),
+            },
+            Tool {
+                name: "search_notes".to_string(),
+                description: "Find notes whose title or content contains the query; returns JSON".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Text to look for in titles and content"
+                        },
+                        "note_type": {
+                            "type": "string",
+                            "description": "Filter by note type",
+                            "enum": ["general", "implementation", "testing", "bug", "feature_request", "technical_debt", "decision"]
+                        }
+                    },
+                    "required": ["query"]
+                }),
+            },
+            Tool {
+                name: "add_note".to_string(),
+                description: "Record an observation as a note on an entity, or project-wide when no entity is given".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Note title"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "Note content"
+                        },
+                        "entity_type": {
+                            "type": "string",
+                            "description": "Type of the entity to attach the note to (feature, task, session, project); requires entity_id"
+                        },
+                        "entity_id": {
+                            "type": "string",
+                            "description": "ID of the entity to attach the note to; requires entity_type"
+                        },
+                        "note_type": {
+                            "type": "string",
+                            "enum": ["general", "implementation", "testing", "bug", "feature_request", "technical_debt", "decision"],
+                            "default": "general"
+                        },
+                        "tags": {
+                            "type": "string",
+                            "description": "Comma-separated tags"
                         }
                     },
-                    "required": ["milestone_id"]
+                    "required": ["title", "content"]
                 }),
             },
             Tool {
-                name: "remove_milestone".to_string(),
-                description: "Remove a milestone from the project".to_string(),
+                name: "update_note".to_string(),
+                description: "Change a note's title, content or tags".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "milestone_id": {
+                        "note_id": {
                             "type": "string",
-                            "description": "Milestone ID to remove"
+                            "description": "Note ID"
                         },
-                        "force": {
-                            "type": "boolean",
-                            "description": "Force removal without confirmation",
-                            "default": false
+                        "title": {
+                            "type": "string",
+                            "description": "New title"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "New content"
+                        },
+                        "tags": {
+                            "type": "string",
+                            "description": "New comma-separated tags, replacing the old ones"
                         }
                     },
-                    "required": ["milestone_id"]
+                    "required": ["note_id"]
                 }),
             },
         ])
     }
 
+    /// Get the resources Claude can read
+    pub async fn get_available_resources(&self) -> Result<Vec<Resource>> {
+        Ok(vec![
+            Resource {
+                uri: DIRECTIVES_URI.to_string(),
+                name: "Project directives".to_string(),
+                description: "The rules recorded for this project (internal/directives.md)".to_string(),
+                mime_type: "text/markdown".to_string(),
+            },
+            Resource {
+                uri: NOTES_URI.to_string(),
+                name: "Project notes".to_string(),
+                description: "Every note in the current project".to_string(),
+                mime_type: "text/plain".to_string(),
+            },
+            Resource {
+                uri: FILES_URI.to_string(),
+                name: "Project file tree".to_string(),
+                description: "Files in the project, skipping hidden and gitignored ones".to_string(),
+                mime_type: "text/plain".to_string(),
+            },
+        ])
+    }
+
+    /// Read one of the resources from `get_available_resources`
+    pub async fn read_resource(&self, uri: &str) -> Result<ResourceContents> {
+        let (mime_type, text) = match uri {
+            DIRECTIVES_URI => {
+                let path = std::env::current_dir()?.join("internal").join("directives.md");
+                let text = if path.exists() {
+                    tokio::fs::read_to_string(&path).await.with_context(|| format!("Failed to read {}", path.display()))?
+                } else {
+                    "No directives recorded yet.".to_string()
+                };
+                ("text/markdown", text)
+            }
+            NOTES_URI => {
+                let result = self.exec_wsb(&["note", "list"], "list notes").await?;
+                if result.is_error == Some(true) {
+                    anyhow::bail!("{}", result.content[0].text);
+                }
+                ("text/plain", result.content.into_iter().next().map(|c| c.text).unwrap_or_default())
+            }
+            FILES_URI => {
+                let root = std::env::current_dir()?;
+                let files = tokio::task::spawn_blocking(move || crate::code_analysis::tree::project_files(&root)).await??;
+                let mut text = crate::code_analysis::tree::render_tree(&files[..files.len().min(MAX_TREE_FILES)]);
+                if files.len() > MAX_TREE_FILES {
+                    text.push_str(&format!("... {} more files\n", files.len() - MAX_TREE_FILES));
+                }
+                ("text/plain", text)
+            }
+            _ => anyhow::bail!("Unknown resource: {}", uri),
+        };
+        Ok(ResourceContents { uri: uri.to_string(), mime_type: mime_type.to_string(), text })
+    }
+
     /// Execute tool call request
     pub async fn execute_tool_call(&self, request: ToolCallRequest) -> Result<ToolCallResult> {
+        if request.arguments.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return self.exec_preview(&request.name, request.arguments).await;
+        }
+
+        // Tools that write markdown docs wait their turn behind `wsb update` and `wsb end`.
+        // end_session and trigger_consolidation run `wsb end` and `wsb consolidate`, which take
+        // the lock themselves; holding it here would leave them queued behind this process.
+        let _lock = if matches!(request.name.as_str(), "setup_project" | "bootstrap_project") {
+            Some(crate::locks::acquire(&std::env::current_dir()?, crate::locks::DOCUMENTS, &format!("mcp {}", request.name))?)
+        } else {
+            None
+        };
+
         match request.name.as_str() {
             "add_feature" => self.exec_add_feature(request.arguments).await,
             "update_feature_state" => self.exec_update_feature_state(request.arguments).await,
             "list_features" => self.exec_list_features(request.arguments).await,
+            "list_tasks" => self.exec_list_tasks(request.arguments).await,
+            "list_views" => self.exec_list_views().await,
             "add_task" => self.exec_add_task(request.arguments).await,
             "update_task_status" => self.exec_update_task_status(request.arguments).await,
             "project_status" => self.exec_project_status(request.arguments).await,
@@ -755,12 +1321,23 @@ impl McpProtocolHandler {
             "trigger_consolidation" => self.exec_trigger_consolidation(request.arguments).await,
             "get_consolidation_status" => self.exec_get_consolidation_status(request.arguments).await,
             "setup_project" => self.exec_setup_project(request.arguments).await,
+            "bootstrap_project" => self.exec_bootstrap_project(request.arguments).await,
             "add_milestone" => self.exec_add_milestone(request.arguments).await,
             "update_milestone" => self.exec_update_milestone(request.arguments).await,
             "list_milestones" => self.exec_list_milestones(request.arguments).await,
             "achieve_milestone" => self.exec_achieve_milestone(request.arguments).await,
             "get_milestone_details" => self.exec_get_milestone_details(request.arguments).await,
             "remove_milestone" => self.exec_remove_milestone(request.arguments).await,
+            "link_entities" => self.exec_link_entities(request.arguments).await,
+            "list_directives" => self.exec_list_directives(request.arguments).await,
+            "search_directives" => self.exec_search_directives(request.arguments).await,
+            "add_directive" => self.exec_add_directive(request.arguments).await,
+            "update_directive" => self.exec_update_directive(request.arguments).await,
+            "code_search" => self.exec_code_search(request.arguments).await,
+            "list_notes" => self.exec_list_notes(request.arguments).await,
+            "search_notes" => self.exec_search_notes(request.arguments).await,
+            "add_note" => self.exec_add_note(request.arguments).await,
+            "update_note" => self.exec_update_note(request.arguments).await,
             _ => Ok(ToolCallResult {
                 content: vec![ToolContent {
                     content_type: "text".to_string(),
@@ -771,6 +1348,69 @@ impl McpProtocolHandler {
         }
     }
 
+    /// Preview a mutating tool call: validate the payload against the current database
+    /// (opened read-only) and report what would change, without running the tool
+    async fn exec_preview(&self, tool: &str, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        use crate::entities::{database::open_database_read_only, preview, EntityManager};
+
+        let str_arg = |name: &str| args.get(name).and_then(|v| v.as_str());
+        let required = |name: &str| str_arg(name).ok_or_else(|| anyhow::anyhow!("Missing required field: {}", name));
+        let error = |text: String| -> Result<ToolCallResult> {
+            Ok(ToolCallResult {
+                content: vec![ToolContent {
+                    content_type: "text".to_string(),
+                    text,
+                }],
+                is_error: Some(true),
+            })
+        };
+
+        if !PREVIEWABLE_TOOLS.contains(&tool) {
+            return error(format!("{} does not support dry_run; nothing was changed", tool));
+        }
+
+        let db_path = std::env::current_dir()?.join(".wsb").join("project.db");
+        let pool = match open_database_read_only(&db_path).await {
+            Ok(pool) => pool,
+            Err(e) => return error(format!("Cannot preview {}: {}", tool, e)),
+        };
+        let manager = EntityManager::new(pool);
+
+        let result = match tool {
+            "add_feature" => preview::create_feature(&manager, required("name")?, required("description")?, str_arg("category")).await,
+            "update_feature_state" => preview::update_feature_state(&manager, required("feature_id")?, str_arg("state"), str_arg("notes")).await,
+            "add_task" => {
+                required("description")?;
+                preview::create_task(&manager, required("title")?, str_arg("feature_id"), str_arg("priority")).await
+            }
+            "update_task_status" => preview::update_task_status(&manager, required("task_id")?, required("status")?, str_arg("notes")).await,
+            _ => preview::link_entities(
+                &manager,
+                required("from_entity")?,
+                required("from_type")?,
+                required("to_entity")?,
+                required("to_type")?,
+                required("relationship_type")?,
+            ).await,
+        };
+        manager.pool.close().await;
+        let preview = result?;
+
+        Ok(ToolCallResult {
+            content: vec![
+                ToolContent {
+                    content_type: "text".to_string(),
+                    text: preview.render(),
+                },
+                ToolContent {
+                    content_type: "text".to_string(),
+                    text: serde_json::to_string_pretty(&preview)?,
+                },
+            ],
+            is_error: Some(!preview.is_valid()),
+        })
+    }
+
     /// Automatic session initialization on MCP server startup
     pub async fn initialize_session_automatically(&self) -> Result<()> {
         // Trigger start_session tool automatically when MCP server starts
@@ -1001,6 +1641,14 @@ impl McpProtocolHandler {
             cmd_args.push("--recent");
         }
 
+        if let Some(tag) = args.get("tag").and_then(|v| v.as_str()) {
+            cmd_args.extend_from_slice(&["--tag", tag]);
+        }
+
+        if let Some(view) = args.get("view").and_then(|v| v.as_str()) {
+            cmd_args.extend_from_slice(&["--view", view]);
+        }
+
         let output = Command::new("wsb")
             .args(&cmd_args)
             .output()
@@ -1022,6 +1670,58 @@ impl McpProtocolHandler {
         })
     }
 
+    async fn exec_list_tasks(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let mut cmd_args = vec!["task", "list"];
+
+        for (field, flag) in [("status", "--status"), ("priority", "--priority"), ("feature_id", "--feature"), ("tag", "--tag"), ("view", "--view")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+
+        let output = Command::new("wsb")
+            .args(&cmd_args)
+            .output()
+            .await
+            .context("Failed to execute task list command")?;
+
+        let result_text = if output.status.success() {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        } else {
+            format!("Failed to list tasks: {}", String::from_utf8_lossy(&output.stderr))
+        };
+
+        Ok(ToolCallResult {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: result_text,
+            }],
+            is_error: Some(!output.status.success()),
+        })
+    }
+
+    async fn exec_list_views(&self) -> Result<ToolCallResult> {
+        let output = Command::new("wsb")
+            .args(["view", "list", "--format", "json"])
+            .output()
+            .await
+            .context("Failed to execute view list command")?;
+
+        let result_text = if output.status.success() {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        } else {
+            format!("Failed to list views: {}", String::from_utf8_lossy(&output.stderr))
+        };
+
+        Ok(ToolCallResult {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: result_text,
+            }],
+            is_error: Some(!output.status.success()),
+        })
+    }
+
     async fn exec_add_task(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
         let title = args.get("title")
             .and_then(|v| v.as_str())
@@ -1098,6 +1798,260 @@ impl McpProtocolHandler {
         })
     }
 
+    async fn exec_link_entities(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        use crate::entities::{relationships::validate_relationship, EntityType};
+
+        let field = |name: &str| args.get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: {}", name));
+        let from_entity = field("from_entity")?;
+        let from_type = field("from_type")?;
+        let to_entity = field("to_entity")?;
+        let to_type = field("to_type")?;
+        let relationship_type = field("relationship_type")?;
+
+        // Reject invalid links before touching the database
+        let validation = EntityType::from_str(from_type)
+            .and_then(|from| EntityType::from_str(to_type).map(|to| (from, to)))
+            .and_then(|(from, to)| validate_relationship(&from, &to, relationship_type));
+        if let Err(e) = validation {
+            return Ok(ToolCallResult {
+                content: vec![ToolContent {
+                    content_type: "text".to_string(),
+                    text: format!("Invalid relationship: {}", e),
+                }],
+                is_error: Some(true),
+            });
+        }
+
+        let mut cmd_args = vec![
+            "relationship", "link", from_entity, to_entity,
+            "--from-type", from_type, "--to-type", to_type,
+            "--relationship-type", relationship_type,
+        ];
+        if let Some(description) = args.get("description").and_then(|v| v.as_str()) {
+            cmd_args.extend_from_slice(&["--description", description]);
+        }
+
+        let output = Command::new("wsb")
+            .args(&cmd_args)
+            .output()
+            .await
+            .context("Failed to execute relationship link command")?;
+
+        let result_text = if output.status.success() {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        } else {
+            format!("Failed to link entities: {}", String::from_utf8_lossy(&output.stderr))
+        };
+
+        Ok(ToolCallResult {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: result_text,
+            }],
+            is_error: Some(!output.status.success()),
+        })
+    }
+
+    /// Run `wsb` with `args` and return its output as the tool result
+    async fn exec_wsb(&self, args: &[&str], action: &str) -> Result<ToolCallResult> {
+        let output = Command::new("wsb")
+            .args(args)
+            .output()
+            .await
+            .with_context(|| format!("Failed to execute command to {}", action))?;
+
+        let result_text = if output.status.success() {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        } else {
+            format!("Failed to {}: {}", action, String::from_utf8_lossy(&output.stderr))
+        };
+
+        Ok(ToolCallResult {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: result_text,
+            }],
+            is_error: Some(!output.status.success()),
+        })
+    }
+
+    async fn exec_list_directives(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let mut cmd_args = vec!["directive", "list"];
+        for (field, flag) in [("category", "--category"), ("enforcement", "--enforcement"), ("priority", "--priority")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+        self.exec_wsb(&cmd_args, "list directives").await
+    }
+
+    async fn exec_search_directives(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let query = args.get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: query"))?;
+
+        let mut cmd_args = vec!["directive", "search", query];
+        if let Some(category) = args.get("category").and_then(|v| v.as_str()) {
+            cmd_args.extend_from_slice(&["--category", category]);
+        }
+        self.exec_wsb(&cmd_args, "search directives").await
+    }
+
+    async fn exec_add_directive(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let title = args.get("title")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: title"))?;
+        let description = args.get("description")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: description"))?;
+
+        let mut cmd_args = vec!["directive", "add", title, description];
+        for (field, flag) in [("category", "--category"), ("enforcement", "--enforcement"), ("priority", "--priority")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+        self.exec_wsb(&cmd_args, &format!("add directive {}", title)).await
+    }
+
+    async fn exec_update_directive(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let directive_id = args.get("directive_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: directive_id"))?;
+
+        let mut cmd_args = vec!["directive", "update", directive_id];
+        for (field, flag) in [("description", "--description"), ("category", "--category"), ("enforcement", "--enforcement"), ("priority", "--priority")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+        self.exec_wsb(&cmd_args, &format!("update directive {}", directive_id)).await
+    }
+
+    async fn exec_code_search(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        use crate::code_analysis::{search::search_project, SupportedLanguage};
+
+        let pattern = args.get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: pattern"))?
+            .to_string();
+        let max_results = args.get("max_results").and_then(|v| v.as_u64()).unwrap_or(200).max(1) as usize;
+
+        let mut languages = Vec::new();
+        for name in args.get("languages").and_then(|v| v.as_array()).into_iter().flatten() {
+            match name.as_str().and_then(SupportedLanguage::from_name) {
+                Some(language) => languages.push(language),
+                None => {
+                    return Ok(ToolCallResult {
+                        content: vec![ToolContent {
+                            content_type: "text".to_string(),
+                            text: format!("Unsupported language: {} (use rust, javascript, typescript, python, go, java, c or cpp)", name),
+                        }],
+                        is_error: Some(true),
+                    });
+                }
+            }
+        }
+
+        let root = std::env::current_dir()?;
+        let search_pattern = pattern.clone();
+        let (hits, truncated) =
+            tokio::task::spawn_blocking(move || search_project(&root, &search_pattern, &languages, max_results)).await??;
+
+        let mut summary = format!("{} matches for '{}'{}\n", hits.len(), pattern, if truncated { " (limit reached)" } else { "" });
+        for hit in &hits {
+            summary.push_str(&format!("{}:{}: {}\n", hit.file.display(), hit.line, hit.snippet));
+        }
+
+        Ok(ToolCallResult {
+            content: vec![
+                ToolContent {
+                    content_type: "text".to_string(),
+                    text: summary,
+                },
+                ToolContent {
+                    content_type: "text".to_string(),
+                    text: serde_json::to_string_pretty(&serde_json::json!({ "matches": hits, "truncated": truncated }))?,
+                },
+            ],
+            is_error: Some(false),
+        })
+    }
+
+    async fn exec_list_notes(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let mut cmd_args = vec!["note", "list"];
+        for (field, flag) in [("entity_type", "--entity-type"), ("entity_id", "--entity-id"), ("note_type", "--note-type")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+        for (field, flag) in [("project_wide", "--project-wide"), ("pinned", "--pinned")] {
+            if args.get(field).and_then(|v| v.as_bool()).unwrap_or(false) {
+                cmd_args.push(flag);
+            }
+        }
+        self.exec_wsb(&cmd_args, "list notes").await
+    }
+
+    async fn exec_search_notes(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let query = args.get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: query"))?;
+
+        let mut cmd_args = vec!["note", "search", query, "--format", "json"];
+        if let Some(note_type) = args.get("note_type").and_then(|v| v.as_str()) {
+            cmd_args.extend_from_slice(&["--note-type", note_type]);
+        }
+        self.exec_wsb(&cmd_args, "search notes").await
+    }
+
+    async fn exec_add_note(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let title = args.get("title")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: title"))?;
+        let content = args.get("content")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: content"))?;
+
+        let entity_type = args.get("entity_type").and_then(|v| v.as_str());
+        let entity_id = args.get("entity_id").and_then(|v| v.as_str());
+        let mut cmd_args = match (entity_type, entity_id) {
+            (Some(entity_type), Some(entity_id)) => vec!["note", "add", "--entity-type", entity_type, "--entity-id", entity_id, title, content],
+            (None, None) => vec!["note", "add-project", title, content],
+            _ => {
+                return Ok(ToolCallResult {
+                    content: vec![ToolContent {
+                        content_type: "text".to_string(),
+                        text: "entity_type and entity_id must be given together; omit both for a project-wide note".to_string(),
+                    }],
+                    is_error: Some(true),
+                });
+            }
+        };
+        for (field, flag) in [("note_type", "--note-type"), ("tags", "--tags")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+        self.exec_wsb(&cmd_args, &format!("add note {}", title)).await
+    }
+
+    async fn exec_update_note(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        let note_id = args.get("note_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required field: note_id"))?;
+
+        let mut cmd_args = vec!["note", "update", note_id];
+        for (field, flag) in [("title", "--title"), ("content", "--content"), ("tags", "--tags")] {
+            if let Some(value) = args.get(field).and_then(|v| v.as_str()) {
+                cmd_args.extend_from_slice(&[flag, value]);
+            }
+        }
+        self.exec_wsb(&cmd_args, &format!("update note {}", note_id)).await
+    }
+
     async fn exec_project_status(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
         let mut cmd_args = vec!["status"];
         
@@ -1425,6 +2379,47 @@ impl McpProtocolHandler {
         })
     }
 
+    /// Run the full init flow in-process and return the summary as text and JSON
+    async fn exec_bootstrap_project(&self, args: HashMap<String, serde_json::Value>) -> Result<ToolCallResult> {
+        use crate::entities::bootstrap::{self, Profile};
+
+        let name = args.get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Project name is required"))?;
+        let description = args.get("description").and_then(|v| v.as_str());
+        let error = |text: String| ToolCallResult {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text,
+            }],
+            is_error: Some(true),
+        };
+
+        let profile = match args.get("profile").and_then(|v| v.as_str()).unwrap_or("general").parse::<Profile>() {
+            Ok(profile) => profile,
+            Err(e) => return Ok(error(e.to_string())),
+        };
+        let root = std::env::current_dir()?;
+        let summary = match bootstrap::bootstrap(&root, name, description, profile).await {
+            Ok(summary) => summary,
+            Err(e) => return Ok(error(format!("Bootstrap failed: {}", e))),
+        };
+
+        Ok(ToolCallResult {
+            content: vec![
+                ToolContent {
+                    content_type: "text".to_string(),
+                    text: summary.render(),
+                },
+                ToolContent {
+                    content_type: "text".to_string(),
+                    text: serde_json::to_string_pretty(&summary)?,
+                },
+            ],
+            is_error: Some(false),
+        })
+    }
+
     /// Create project structure with methodology templates
     async fn create_project_structure(
         &self, 
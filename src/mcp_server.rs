@@ -0,0 +1,511 @@
+//! HTTP side of `wsb mcp-server`: the dashboard API and a live stream of entity changes.
+//!
+//! `GET /api/dashboard` returns the same snapshot as `wsb dashboard --format json`.
+//! `GET /api/events` upgrades to a WebSocket that pushes a typed JSON message for every
+//! committed change, so a dashboard can update as things happen instead of polling.
+//! Changes made through the server's own [`EntityManager`] are announced as they commit;
+//! changes made by other processes (the CLI, MCP tools that shell out to `wsb`) are picked
+//! up from the event outbox every [`RELAY_INTERVAL`].
+//!
+//! The server listens on localhost unless told otherwise. To expose it beyond the machine,
+//! give it a certificate: with [`TlsFiles`] it speaks HTTPS only, and can answer plain HTTP
+//! on a second port with a redirect. [`generate_self_signed`] makes a certificate good
+//! enough for development.
+//!
+//! Ctrl-C or SIGTERM stops accepting connections, lets requests in flight finish, closes
+//! event streams and then the database pool. [`daemon`] runs the server in the background.
+//!
+//! Requests are logged and measured by [`metrics`]; `GET /metrics` serves the numbers to
+//! Prometheus.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{MatchedPath, State};
+use axum::http::{header, HeaderMap, Request, StatusCode, Uri};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Json, Redirect, Response};
+use axum::routing::get;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::watch;
+
+use crate::entities::changes::EntityChange;
+use crate::entities::{workspace, EntityManager};
+use crate::tui::dashboard;
+use metrics::{Metrics, RequestLog};
+
+pub mod daemon;
+pub mod metrics;
+
+/// How often changes committed by other processes are picked up
+pub const RELAY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long HTTPS connections get to finish after a shutdown signal
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+pub struct ServerState {
+    pub manager: Arc<EntityManager>,
+    pub debug: bool,
+    /// Becomes true when the server starts shutting down
+    pub shutdown: watch::Receiver<bool>,
+    pub metrics: Arc<Metrics>,
+}
+
+/// A message pushed over `/api/events`
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage {
+    /// First message on every connection
+    Connected { project_id: Option<String> },
+    EntityChange(EntityChange),
+    /// The client fell behind and missed this many changes; refetch to resync
+    Lagged { missed: u64 },
+}
+
+pub fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/metrics", get(get_metrics))
+        .route("/api/dashboard", get(get_dashboard))
+        .route("/api/events", get(stream_events))
+        .layer(middleware::from_fn_with_state(state.clone(), track_requests))
+        .with_state(state)
+}
+
+/// Where and how [`serve`] listens
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub bind: IpAddr,
+    pub port: u16,
+    /// Serve HTTPS with this certificate instead of plain HTTP
+    pub tls: Option<TlsFiles>,
+    /// With TLS, also listen for plain HTTP here and redirect it to HTTPS
+    pub redirect_port: Option<u16>,
+    pub debug: bool,
+    /// Record the running server here (see [`daemon`]) and refuse to start if another is
+    pub pidfile: Option<PathBuf>,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self { bind: IpAddr::V4(Ipv4Addr::LOCALHOST), port: 3000, tls: None, redirect_port: None, debug: false, pidfile: None }
+    }
+}
+
+/// PEM encoded certificate chain and private key
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Serve the project database at `db_path` until Ctrl-C or SIGTERM
+pub async fn serve(db_path: &Path, options: ServeOptions) -> Result<()> {
+    if options.redirect_port.is_some() && options.tls.is_none() {
+        anyhow::bail!("An HTTP redirect port needs a TLS certificate and key to redirect to");
+    }
+    if options.redirect_port == Some(options.port) {
+        anyhow::bail!("The HTTP redirect port must differ from the server port {}", options.port);
+    }
+    if let Some(pidfile) = &options.pidfile {
+        daemon::ensure_not_running(pidfile)?;
+    }
+    if options.tls.is_none() && !options.bind.is_loopback() {
+        eprintln!(
+            "Warning: serving plain HTTP on {}; anyone on the network can read and change project data. \
+             Pass --tls-cert and --tls-key (or --self-signed) to encrypt it.",
+            options.bind
+        );
+    }
+
+    let tls_config = match &options.tls {
+        Some(tls) => Some(RustlsConfig::from_pem_file(&tls.cert, &tls.key).await.with_context(|| {
+            format!("Failed to load TLS certificate {} and key {}", tls.cert.display(), tls.key.display())
+        })?),
+        None => None,
+    };
+    let addr = SocketAddr::new(options.bind, options.port);
+    let listener = std::net::TcpListener::bind(addr).with_context(|| format!("Failed to bind {}", addr))?;
+    let redirect = match options.redirect_port {
+        Some(port) => {
+            let redirect_addr = SocketAddr::new(options.bind, port);
+            let builder = axum::Server::try_bind(&redirect_addr).with_context(|| format!("Failed to bind {}", redirect_addr))?;
+            Some((redirect_addr, builder))
+        }
+        None => None,
+    };
+    let url = format!("{}://{}", if tls_config.is_some() { "https" } else { "http" }, addr);
+
+    let pool = crate::entities::database::initialize_database(db_path).await?;
+    let manager = Arc::new(EntityManager::new(pool.clone()));
+
+    let feed = manager.changes().clone();
+    let relay_pool = pool.clone();
+    let relay = tokio::spawn(async move { feed.relay(relay_pool, RELAY_INTERVAL).await });
+
+    let (stopping, shutdown) = watch::channel(false);
+    let state = ServerState { manager, debug: options.debug, shutdown, metrics: Arc::new(Metrics::default()) };
+    let app = router(state).into_make_service();
+    let signal = shutdown_signal();
+    let signalled = async move {
+        signal.await;
+        let _ = stopping.send(true);
+    };
+    // `wsb mcp-server stop` may signal as soon as the pidfile appears, so only write it once SIGTERM is handled
+    let _registration = match &options.pidfile {
+        Some(pidfile) => Some(daemon::register(pidfile, url.clone())?),
+        None => None,
+    };
+    eprintln!("MCP server listening on {}", url);
+
+    let result = match tls_config {
+        None => match axum::Server::from_tcp(listener) {
+            Ok(builder) => builder.serve(app).with_graceful_shutdown(signalled).await.context("MCP server failed"),
+            Err(e) => Err(e).with_context(|| format!("Failed to listen on {}", addr)),
+        },
+        Some(config) => {
+            let redirect = redirect.map(|(redirect_addr, builder)| {
+                eprintln!("Redirecting http://{} to HTTPS", redirect_addr);
+                tokio::spawn(builder.serve(redirect_router(options.port).into_make_service()))
+            });
+            let handle = axum_server::Handle::new();
+            let drain = handle.clone();
+            tokio::spawn(async move {
+                signalled.await;
+                drain.graceful_shutdown(Some(DRAIN_TIMEOUT));
+            });
+            let result = axum_server::from_tcp_rustls(listener, config).handle(handle).serve(app).await.context("MCP server failed");
+            if let Some(redirect) = redirect {
+                redirect.abort();
+            }
+            result
+        }
+    };
+
+    relay.abort();
+    pool.close().await;
+    eprintln!("MCP server stopped");
+    result
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM; the SIGTERM handler is installed before this returns
+fn shutdown_signal() -> impl std::future::Future<Output = ()> {
+    let interrupt = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::warn!("Cannot listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = {
+        let signal = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate());
+        async move {
+            match signal {
+                Ok(mut signal) => {
+                    signal.recv().await;
+                }
+                Err(e) => {
+                    log::warn!("Cannot listen for SIGTERM: {}", e);
+                    std::future::pending::<()>().await;
+                }
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    async move {
+        tokio::select! {
+            _ = interrupt => {}
+            _ = terminate => {}
+        }
+        eprintln!("Shutting down MCP server; finishing requests in flight");
+    }
+}
+
+/// Answers every request with a permanent redirect to the same path on the HTTPS port
+pub fn redirect_router(https_port: u16) -> Router {
+    Router::new().fallback(move |headers: HeaderMap, uri: Uri| async move {
+        let host = headers.get(header::HOST).and_then(|h| h.to_str().ok());
+        Redirect::permanent(&https_location(host, &uri, https_port))
+    })
+}
+
+/// The HTTPS URL for a plain HTTP request that arrived with this `Host` header
+fn https_location(host: Option<&str>, uri: &Uri, https_port: u16) -> String {
+    let host = host.unwrap_or("localhost");
+    // Drop the port, keeping IPv6 literals such as [::1] intact
+    let hostname = match host.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map(|(ip, _)| format!("[{}]", ip)).unwrap_or_else(|| host.to_string()),
+        None => host.split(':').next().unwrap_or(host).to_string(),
+    };
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    if https_port == 443 {
+        format!("https://{}{}", hostname, path)
+    } else {
+        format!("https://{}:{}{}", hostname, https_port, path)
+    }
+}
+
+/// Write a self-signed certificate for development to `cert.pem` and `key.pem` in `dir`.
+/// It is valid for localhost, the loopback addresses and `extra_hosts` (names or IPs).
+/// Browsers will warn about it; use a real certificate for anything shared.
+pub fn generate_self_signed(dir: &Path, extra_hosts: &[String]) -> Result<TlsFiles> {
+    let mut hosts = vec!["localhost".to_string(), Ipv4Addr::LOCALHOST.to_string(), Ipv6Addr::LOCALHOST.to_string()];
+    for host in extra_hosts {
+        if !hosts.contains(host) {
+            hosts.push(host.clone());
+        }
+    }
+    let cert = rcgen::generate_simple_self_signed(hosts).context("Failed to generate a self-signed certificate")?;
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let files = TlsFiles { cert: dir.join("cert.pem"), key: dir.join("key.pem") };
+    std::fs::write(&files.cert, cert.serialize_pem()?)
+        .with_context(|| format!("Failed to write {}", files.cert.display()))?;
+    write_private(&files.key, cert.serialize_private_key_pem().as_bytes())
+        .with_context(|| format!("Failed to write {}", files.key.display()))?;
+    Ok(files)
+}
+
+/// Write a file only the current user can read
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// Count, time and log every request
+async fn track_requests<B>(State(state): State<ServerState>, request: Request<B>, next: Next<B>) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    // The route pattern rather than the path, so metric labels stay bounded
+    let route = request.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string()).unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+    let elapsed = started.elapsed();
+    let status = response.status().as_u16();
+    state.metrics.record_request(&method, &route, status, elapsed);
+
+    // Health checks and scrapes arrive all day; only log them when debugging
+    if state.debug || !matches!(route.as_str(), "/health" | "/metrics") {
+        RequestLog {
+            transport: "http",
+            method: &method,
+            path: Some(&path),
+            tool: None,
+            status: status.to_string(),
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+        }
+        .emit();
+    }
+    response
+}
+
+async fn get_metrics(State(state): State<ServerState>) -> Response {
+    match state.metrics.render(state.manager.get_pool()).await {
+        Ok(text) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], text).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+async fn get_dashboard(State(state): State<ServerState>) -> Response {
+    let pool = state.manager.get_pool();
+    let project = match state.metrics.time_query("current_project", workspace::current_project(pool)).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "No active project found".to_string()),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    match state.metrics.time_query("dashboard", dashboard::load(pool, &project)).await {
+        Ok(snapshot) => Json(snapshot).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn stream_events(ws: WebSocketUpgrade, State(state): State<ServerState>) -> Response {
+    // Subscribe before upgrading so nothing committed during the handshake is missed
+    match state.manager.changes().subscribe(state.manager.get_pool()).await {
+        Ok(changes) => ws.on_upgrade(move |socket| forward_changes(socket, changes, state)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &StreamMessage) -> Result<(), axum::Error> {
+    match serde_json::to_string(message) {
+        Ok(text) => socket.send(Message::Text(text)).await,
+        Err(e) => {
+            log::warn!("Skipping change that could not be serialized: {}", e);
+            Ok(())
+        }
+    }
+}
+
+async fn forward_changes(mut socket: WebSocket, mut changes: broadcast::Receiver<EntityChange>, state: ServerState) {
+    let mut shutdown = state.shutdown.clone();
+    let project_id = workspace::current_project(state.manager.get_pool()).await.ok().flatten().map(|p| p.id);
+    if send(&mut socket, &StreamMessage::Connected { project_id }).await.is_err() {
+        return;
+    }
+    if state.debug {
+        eprintln!("Event stream client connected");
+    }
+
+    loop {
+        tokio::select! {
+            change = changes.recv() => {
+                let message = match change {
+                    Ok(change) => StreamMessage::EntityChange(change),
+                    Err(RecvError::Lagged(missed)) => StreamMessage::Lagged { missed },
+                    Err(RecvError::Closed) => break,
+                };
+                if send(&mut socket, &message).await.is_err() {
+                    break;
+                }
+            }
+            // The flag only ever changes to true
+            Ok(()) = shutdown.changed() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            incoming = socket.recv() => match incoming {
+                // Clients only listen; anything but a close is ignored
+                None | Some(Err(_)) | Some(Ok(Message::Close(_))) => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+
+    if state.debug {
+        eprintln!("Event stream client disconnected");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::crud;
+    use futures_util::StreamExt;
+    use tempfile::TempDir;
+    use tokio_tungstenite::tungstenite;
+
+    type Socket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+    async fn next_json(socket: &mut Socket) -> serde_json::Value {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next()).await.unwrap().unwrap().unwrap();
+        match message {
+            tungstenite::Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_and_event_stream() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("project.db");
+        let pool = crate::entities::database::initialize_database(&db_path).await.unwrap();
+        let manager = Arc::new(EntityManager::new(pool.clone()));
+        let project = manager.create_project("Push".to_string(), "Events".to_string()).await.unwrap();
+
+        let (stopping, shutdown) = watch::channel(false);
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(router(ServerState { manager: manager.clone(), debug: false, shutdown, metrics: Default::default() }).into_make_service());
+        tokio::spawn(server);
+        let feed = manager.changes().clone();
+        tokio::spawn(async move { feed.relay(pool, Duration::from_millis(50)).await });
+
+        let snapshot: serde_json::Value = reqwest::get(format!("http://{}/api/dashboard", addr)).await.unwrap().json().await.unwrap();
+        assert_eq!(snapshot["project_id"], project.id.as_str());
+        let scrape = reqwest::get(format!("http://{}/metrics", addr)).await.unwrap().text().await.unwrap();
+        assert!(scrape.contains("wsb_http_requests_total{method=\"GET\",route=\"/api/dashboard\",status=\"200\"} 1"));
+        assert!(scrape.contains("wsb_db_query_duration_seconds_count{query=\"dashboard\"} 1"));
+        assert!(scrape.contains("wsb_entities{type=\"project\"} 1"));
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/api/events", addr)).await.unwrap();
+        let connected = next_json(&mut socket).await;
+        assert_eq!(connected["type"], "connected");
+        assert_eq!(connected["project_id"], project.id.as_str());
+
+        let feature = manager.create_feature_full(project.id.clone(), "Storage".to_string(), "Data".to_string(), None).await.unwrap();
+        let change = next_json(&mut socket).await;
+        assert_eq!(change["type"], "entity_change");
+        assert_eq!(change["event_type"], "feature.created");
+        assert_eq!(change["entity_id"], feature.id.as_str());
+
+        // A change committed by another process reaches the stream through the outbox
+        let other = crate::entities::database::initialize_database(&db_path).await.unwrap();
+        let task = crud::tasks::create(&other, project.id.clone(), feature.id.clone(), "Schema".to_string(), "feature".to_string()).await.unwrap();
+        let change = next_json(&mut socket).await;
+        assert_eq!(change["event_type"], "task.created");
+        assert_eq!(change["entity_id"], task.id.as_str());
+
+        // Shutting down closes the stream instead of dropping it
+        stopping.send(true).unwrap();
+        let closed = tokio::time::timeout(Duration::from_secs(5), socket.next()).await.unwrap();
+        assert!(matches!(closed, Some(Ok(tungstenite::Message::Close(_))) | None));
+    }
+
+    #[test]
+    fn test_https_location() {
+        let uri: Uri = "/api/dashboard?x=1".parse().unwrap();
+        assert_eq!(https_location(Some("example.lan:8080"), &uri, 3443), "https://example.lan:3443/api/dashboard?x=1");
+        assert_eq!(https_location(Some("[::1]:8080"), &uri, 443), "https://[::1]/api/dashboard?x=1");
+        assert_eq!(https_location(None, &"/".parse().unwrap(), 3443), "https://localhost:3443/");
+    }
+
+    #[tokio::test]
+    async fn test_https_with_self_signed_certificate() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let manager = Arc::new(EntityManager::new(pool));
+        let tls = generate_self_signed(&temp.path().join("tls"), &[]).unwrap();
+        let config = RustlsConfig::from_pem_file(&tls.cert, &tls.key).await.unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (_stopping, shutdown) = watch::channel(false);
+        let app = router(ServerState { manager, debug: false, shutdown, metrics: Default::default() }).into_make_service();
+        tokio::spawn(axum_server::from_tcp_rustls(listener, config).serve(app));
+
+        let redirect_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let redirect_addr = redirect_listener.local_addr().unwrap();
+        let redirect = axum::Server::from_tcp(redirect_listener).unwrap().serve(redirect_router(port).into_make_service());
+        tokio::spawn(redirect);
+
+        let client = reqwest::Client::builder().danger_accept_invalid_certs(true).build().unwrap();
+        let health: serde_json::Value =
+            client.get(format!("https://localhost:{}/health", port)).send().await.unwrap().json().await.unwrap();
+        assert_eq!(health["status"], "ok");
+
+        // Plain HTTP on the redirect port lands on the HTTPS server
+        let health: serde_json::Value =
+            client.get(format!("http://{}/health", redirect_addr)).send().await.unwrap().json().await.unwrap();
+        assert_eq!(health["status"], "ok");
+    }
+}
@@ -0,0 +1,198 @@
+//! Background `wsb mcp-server --daemon` processes and the pidfile that tracks them.
+//!
+//! A running server records itself in `.wsb/mcp-server.pid` (pid, URL and the arguments it
+//! was started with) once it is listening, and removes the record when it shuts down,
+//! whether it runs in the foreground or in the background. `--daemon` runs the same command
+//! again as a detached child writing to `.wsb/mcp-server.log` and returns once the child has
+//! registered. `stop` sends SIGTERM and waits for the server to drain and deregister.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Pidfile name inside `.wsb/`
+pub const PIDFILE: &str = "mcp-server.pid";
+/// Output of a daemonized server, inside `.wsb/`
+pub const LOGFILE: &str = "mcp-server.log";
+
+/// How long `spawn` waits for the child to start listening
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long `stop` waits for in-flight requests to finish
+pub const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What a running server writes to its pidfile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServerRecord {
+    pub pid: u32,
+    pub url: String,
+    /// Arguments after the program name, reused by `restart`
+    pub args: Vec<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Keeps this process's record in the pidfile; removes it when dropped
+#[derive(Debug)]
+pub struct Registration {
+    pidfile: PathBuf,
+    pid: u32,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if matches!(read_record(&self.pidfile), Some(record) if record.pid == self.pid) {
+            let _ = fs::remove_file(&self.pidfile);
+        }
+    }
+}
+
+fn read_record(pidfile: &Path) -> Option<ServerRecord> {
+    serde_json::from_str(&fs::read_to_string(pidfile).ok()?).ok()
+}
+
+/// The server recorded in `pidfile`, if its process is still alive. A record left behind
+/// by a server that crashed is removed.
+pub fn running(pidfile: &Path) -> Result<Option<ServerRecord>> {
+    if !pidfile.exists() {
+        return Ok(None);
+    }
+    match read_record(pidfile) {
+        Some(record) if crate::locks::process_alive(record.pid) => Ok(Some(record)),
+        _ => {
+            fs::remove_file(pidfile).with_context(|| format!("Failed to remove stale {}", pidfile.display()))?;
+            Ok(None)
+        }
+    }
+}
+
+/// Fail if another live server is recorded in `pidfile`
+pub fn ensure_not_running(pidfile: &Path) -> Result<()> {
+    match running(pidfile)? {
+        Some(other) if other.pid != std::process::id() => anyhow::bail!(
+            "An MCP server is already running for this project (pid {}, {}); stop it with `wsb mcp-server stop`",
+            other.pid,
+            other.url
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Record the current process as the project's server, listening on `url`
+pub fn register(pidfile: &Path, url: String) -> Result<Registration> {
+    ensure_not_running(pidfile)?;
+    let record = ServerRecord {
+        pid: std::process::id(),
+        url,
+        args: std::env::args().skip(1).collect(),
+        started_at: Utc::now(),
+    };
+    fs::write(pidfile, serde_json::to_string_pretty(&record)?)
+        .with_context(|| format!("Failed to write {}", pidfile.display()))?;
+    Ok(Registration { pidfile: pidfile.to_path_buf(), pid: record.pid })
+}
+
+/// Run this executable with `args` as a detached background process appending its output
+/// to `log`, and wait until it has registered in `pidfile`
+pub fn spawn(pidfile: &Path, log: &Path, args: &[String]) -> Result<ServerRecord> {
+    ensure_not_running(pidfile)?;
+
+    let log_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .with_context(|| format!("Failed to open {}", log.display()))?;
+    let mut command = Command::new(std::env::current_exe()?);
+    command.args(args).stdin(Stdio::null()).stdout(log_file.try_clone()?).stderr(log_file);
+    // Out of the terminal's process group, so Ctrl-C in the shell does not reach it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn().context("Failed to start the MCP server")?;
+
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            anyhow::bail!("The MCP server exited during startup ({}); see {}", status, log.display());
+        }
+        if let Some(record) = read_record(pidfile) {
+            if record.pid == child.id() {
+                return Ok(record);
+            }
+        }
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            anyhow::bail!("The MCP server did not start within {}s; see {}", STARTUP_TIMEOUT.as_secs(), log.display());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Ask the recorded server to shut down and wait until it has. None if none was running.
+pub fn stop(pidfile: &Path, timeout: Duration) -> Result<Option<ServerRecord>> {
+    let Some(record) = running(pidfile)? else {
+        return Ok(None);
+    };
+    terminate(record.pid)?;
+
+    // The server deregisters as its last step; a dead process that could not is done too
+    let deadline = Instant::now() + timeout;
+    while matches!(read_record(pidfile), Some(current) if current.pid == record.pid) && crate::locks::process_alive(record.pid) {
+        if Instant::now() > deadline {
+            anyhow::bail!("The MCP server (pid {}) did not stop within {}s", record.pid, timeout.as_secs());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    let _ = running(pidfile)?;
+    Ok(Some(record))
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32) -> Result<()> {
+    let status = Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to run kill")?;
+    if !status.success() {
+        anyhow::bail!("Failed to signal the MCP server (pid {})", pid);
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn terminate(pid: u32) -> Result<()> {
+    anyhow::bail!("Stopping the MCP server (pid {}) is only supported on Unix; end the process instead", pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_registration_lifecycle() {
+        let temp = TempDir::new().unwrap();
+        let pidfile = temp.path().join(PIDFILE);
+        assert!(running(&pidfile).unwrap().is_none());
+
+        let registration = register(&pidfile, "http://127.0.0.1:3000".to_string()).unwrap();
+        let record = running(&pidfile).unwrap().unwrap();
+        assert_eq!((record.pid, record.url.as_str()), (std::process::id(), "http://127.0.0.1:3000"));
+        drop(registration);
+        assert!(!pidfile.exists());
+
+        // A record whose process is gone is cleared rather than reported
+        let dead = ServerRecord { pid: u32::MAX, url: "http://127.0.0.1:3000".to_string(), args: Vec::new(), started_at: Utc::now() };
+        fs::write(&pidfile, serde_json::to_string(&dead).unwrap()).unwrap();
+        assert!(running(&pidfile).unwrap().is_none());
+        assert!(!pidfile.exists());
+        assert!(stop(&pidfile, STOP_TIMEOUT).unwrap().is_none());
+    }
+}
@@ -0,0 +1,200 @@
+//! Request logging and the Prometheus metrics served at `/metrics`.
+//!
+//! Every request, HTTP or MCP tool call, is logged as one JSON line under [`LOG_TARGET`],
+//! which lands in `.wsb/logs/wsb.log`. Counters and histograms live in a [`Metrics`]
+//! shared by the handlers; entity counts are read from the database on each scrape.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+/// Log target of request lines, so they can be filtered or routed separately
+pub const LOG_TARGET: &str = "wsb::requests";
+
+/// Upper bounds in seconds of the latency histogram buckets
+const BUCKETS: [f64; 11] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// Entity types counted on each scrape, with the query that counts the live rows
+const ENTITY_COUNTS: &[(&str, &str)] = &[
+    ("project", "SELECT COUNT(*) FROM projects WHERE deleted_at IS NULL"),
+    ("feature", "SELECT COUNT(*) FROM features WHERE deleted_at IS NULL"),
+    ("task", "SELECT COUNT(*) FROM tasks WHERE deleted_at IS NULL"),
+    ("session", "SELECT COUNT(*) FROM sessions"),
+    ("milestone", "SELECT COUNT(*) FROM milestones"),
+    ("note", "SELECT COUNT(*) FROM notes"),
+    ("event", "SELECT COUNT(*) FROM event_outbox"),
+];
+
+/// One handled request
+#[derive(Debug, Serialize)]
+pub struct RequestLog<'a> {
+    /// `http` or `mcp`
+    pub transport: &'a str,
+    /// HTTP method, or the JSON-RPC method of an MCP message
+    pub method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<&'a str>,
+    /// HTTP status code, or `ok`/`error` for MCP messages
+    pub status: String,
+    pub duration_ms: f64,
+}
+
+impl RequestLog<'_> {
+    pub fn emit(&self) {
+        match serde_json::to_string(self) {
+            Ok(line) => log::info!(target: LOG_TARGET, "{}", line),
+            Err(e) => log::warn!("Could not serialize request log: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    /// Observations at or below each bucket bound (not cumulative)
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, count) in BUCKETS.iter().zip(self.buckets) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{{}{}le=\"{}\"}} {}", name, labels, separator, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{{}{}le=\"+Inf\"}} {}", name, labels, separator, self.count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, self.count);
+    }
+}
+
+/// Request and query measurements since the server started
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Keyed by method, route and status
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    /// Keyed by method and route
+    request_durations: Mutex<BTreeMap<(String, String), Histogram>>,
+    /// Keyed by query name
+    queries: Mutex<BTreeMap<String, Histogram>>,
+}
+
+impl Metrics {
+    pub fn record_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        if let Ok(mut requests) = self.requests.lock() {
+            *requests.entry((method.to_string(), route.to_string(), status)).or_default() += 1;
+        }
+        if let Ok(mut durations) = self.request_durations.lock() {
+            durations.entry((method.to_string(), route.to_string())).or_default().observe(duration.as_secs_f64());
+        }
+    }
+
+    pub fn record_query(&self, query: &str, duration: Duration) {
+        if let Ok(mut queries) = self.queries.lock() {
+            queries.entry(query.to_string()).or_default().observe(duration.as_secs_f64());
+        }
+    }
+
+    /// Run `work` and record how long it took under `query`, whether or not it failed
+    pub async fn time_query<T>(&self, query: &str, work: impl std::future::Future<Output = T>) -> T {
+        let started = Instant::now();
+        let result = work.await;
+        self.record_query(query, started.elapsed());
+        result
+    }
+
+    /// Everything in the Prometheus text exposition format
+    pub async fn render(&self, pool: &SqlitePool) -> Result<String> {
+        let mut entities = Vec::with_capacity(ENTITY_COUNTS.len());
+        for (entity, sql) in ENTITY_COUNTS {
+            let count: i64 = self.time_query("entity_count", sqlx::query_scalar(sql).fetch_one(pool)).await?;
+            entities.push((*entity, count));
+        }
+
+        let mut out = String::new();
+        out.push_str("# HELP wsb_http_requests_total HTTP requests handled, by method, route and status\n");
+        out.push_str("# TYPE wsb_http_requests_total counter\n");
+        if let Ok(requests) = self.requests.lock() {
+            for ((method, route, status), count) in requests.iter() {
+                let _ = writeln!(
+                    out,
+                    "wsb_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                    method, escape(route), status, count
+                );
+            }
+        }
+
+        out.push_str("# HELP wsb_http_request_duration_seconds Time to produce a response\n");
+        out.push_str("# TYPE wsb_http_request_duration_seconds histogram\n");
+        if let Ok(durations) = self.request_durations.lock() {
+            for ((method, route), histogram) in durations.iter() {
+                let labels = format!("method=\"{}\",route=\"{}\"", method, escape(route));
+                histogram.render(&mut out, "wsb_http_request_duration_seconds", &labels);
+            }
+        }
+
+        out.push_str("# HELP wsb_db_query_duration_seconds Time spent in database queries made by the server\n");
+        out.push_str("# TYPE wsb_db_query_duration_seconds histogram\n");
+        if let Ok(queries) = self.queries.lock() {
+            for (query, histogram) in queries.iter() {
+                histogram.render(&mut out, "wsb_db_query_duration_seconds", &format!("query=\"{}\"", escape(query)));
+            }
+        }
+
+        out.push_str("# HELP wsb_entities Live entities in the project database, by type\n");
+        out.push_str("# TYPE wsb_entities gauge\n");
+        for (entity, count) in entities {
+            let _ = writeln!(out, "wsb_entities{{type=\"{}\"}} {}", entity, count);
+        }
+        Ok(out)
+    }
+}
+
+/// Escape a label value for the exposition format
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_render_counts_requests_queries_and_entities() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        crate::entities::crud::projects::create(&pool, "Metrics".to_string(), "Scrape".to_string()).await.unwrap();
+
+        let metrics = Metrics::default();
+        metrics.record_request("GET", "/health", 200, Duration::from_millis(2));
+        metrics.record_request("GET", "/health", 200, Duration::from_millis(30));
+        metrics.record_query("dashboard", Duration::from_millis(7));
+
+        let text = metrics.render(&pool).await.unwrap();
+        assert!(text.contains("wsb_http_requests_total{method=\"GET\",route=\"/health\",status=\"200\"} 2"));
+        assert!(text.contains("wsb_http_request_duration_seconds_bucket{method=\"GET\",route=\"/health\",le=\"0.0025\"} 1"));
+        assert!(text.contains("wsb_http_request_duration_seconds_bucket{method=\"GET\",route=\"/health\",le=\"+Inf\"} 2"));
+        assert!(text.contains("wsb_db_query_duration_seconds_count{query=\"dashboard\"} 1"));
+        assert!(text.contains("wsb_db_query_duration_seconds_count{query=\"entity_count\"} 7"));
+        assert!(text.contains("wsb_entities{type=\"project\"} 1"));
+        assert!(text.contains("wsb_entities{type=\"task\"} 0"));
+    }
+}
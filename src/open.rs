@@ -0,0 +1,235 @@
+//! Quick navigation for `wsb open`: resolve a target to the artifact behind it and open
+//! it with the right program.
+//!
+//! Features resolve to the files mapped to them under `tools.open.feature_files`, then
+//! to the doc files mapped under `tools.doc_coverage`, then to the first source file
+//! that mentions the feature ID. Tasks resolve through their feature.
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::entities::doc_coverage::DocCoverageConfig;
+use crate::entities::schema_models::IdValidator;
+
+/// Settings stored under `tools.open` in `.wsb/state.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenConfig {
+    /// Where `wsb open dashboard` points the browser
+    pub dashboard_url: String,
+    /// Editor command; `$VISUAL` and then `$EDITOR` when unset
+    pub editor: Option<String>,
+    /// Feature ID → files (relative to the project root) that implement it
+    pub feature_files: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for OpenConfig {
+    fn default() -> Self {
+        Self {
+            dashboard_url: "http://localhost:3000".to_string(),
+            editor: None,
+            feature_files: BTreeMap::new(),
+        }
+    }
+}
+
+/// Generated docs, in the order `wsb open docs` tries them
+pub const GENERATED_DOCS: &[&str] = &["CLAUDE.md", "internal/FEATURES.md", "internal/PROGRESS_TRACKING.md"];
+
+/// What `wsb open` was asked to open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Dashboard,
+    Docs,
+    Scrap,
+    Feature(String),
+    Task(String),
+}
+
+impl Target {
+    pub fn parse(target: &str) -> Result<Self> {
+        match target {
+            "dashboard" => Ok(Target::Dashboard),
+            "docs" => Ok(Target::Docs),
+            "scrap" => Ok(Target::Scrap),
+            id if IdValidator::validate_feature_id(id) => Ok(Target::Feature(id.to_string())),
+            id if IdValidator::validate_task_id(id) => Ok(Target::Task(id.to_string())),
+            other => anyhow::bail!("Cannot open '{}': expected a feature or task ID, dashboard, docs or scrap", other),
+        }
+    }
+}
+
+/// How to open a resolved target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Browser(String),
+    Editor(PathBuf),
+    FileManager(PathBuf),
+}
+
+impl Action {
+    /// The URL or path being opened
+    pub fn location(&self) -> String {
+        match self {
+            Action::Browser(url) => url.clone(),
+            Action::Editor(path) | Action::FileManager(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// First source file under `root` mentioning `feature_id`, skipping `.wsb` and ignored files
+fn find_mention(root: &Path, feature_id: &str) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkBuilder::new(root)
+        .filter_entry(|entry| entry.file_name() != ".wsb" && entry.file_name() != ".scrap")
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map_or(false, |t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files.into_iter().find(|path| fs::read_to_string(path).map_or(false, |content| content.contains(feature_id)))
+}
+
+/// The file behind `feature_id`, from the mappings or a mention in the source
+pub fn feature_file(root: &Path, feature_id: &str, config: &OpenConfig, docs: &DocCoverageConfig) -> Option<PathBuf> {
+    config
+        .feature_files
+        .get(feature_id)
+        .into_iter()
+        .chain(docs.doc_files.get(feature_id))
+        .flatten()
+        .map(|file| root.join(file))
+        .find(|path| path.exists())
+        .or_else(|| find_mention(root, feature_id))
+}
+
+/// Resolve `target`. A task must already have been resolved to its feature by the caller.
+pub fn resolve(root: &Path, target: &Target, config: &OpenConfig, docs: &DocCoverageConfig) -> Result<Action> {
+    match target {
+        Target::Dashboard => Ok(Action::Browser(config.dashboard_url.clone())),
+        Target::Docs => GENERATED_DOCS
+            .iter()
+            .map(|doc| root.join(doc))
+            .find(|path| path.exists())
+            .map(Action::Editor)
+            .ok_or_else(|| anyhow::anyhow!("No generated docs yet; run `wsb template generate-docs` first")),
+        Target::Scrap => {
+            let scrap = root.join(".scrap");
+            if !scrap.is_dir() {
+                anyhow::bail!("Nothing has been scrapped in {}", root.display());
+            }
+            Ok(Action::FileManager(scrap))
+        }
+        Target::Feature(id) => feature_file(root, id, config, docs).map(Action::Editor).ok_or_else(|| {
+            anyhow::anyhow!("No file is mapped to {}; add it under tools.open.feature_files or mention {} in the code", id, id)
+        }),
+        Target::Task(id) => anyhow::bail!("Task {} must be resolved to its feature first", id),
+    }
+}
+
+/// The platform's "open with the default application" command
+fn system_opener() -> (&'static str, &'static [&'static str]) {
+    if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else if cfg!(windows) {
+        ("cmd", &["/C", "start", ""])
+    } else {
+        ("xdg-open", &[])
+    }
+}
+
+/// Open `action`: URLs and directories with the system opener, files in the editor
+pub fn launch(action: &Action, config: &OpenConfig) -> Result<()> {
+    let editor = config
+        .editor
+        .clone()
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty());
+
+    let mut command = match (action, editor) {
+        (Action::Editor(path), Some(editor)) => {
+            // The editor setting may carry arguments, e.g. "code --wait"
+            let mut parts = editor.split_whitespace();
+            let mut command = Command::new(parts.next().unwrap());
+            command.args(parts).arg(path);
+            command
+        }
+        _ => {
+            let (program, args) = system_opener();
+            let mut command = Command::new(program);
+            command.args(args).arg(action.location());
+            command
+        }
+    };
+
+    let status = command
+        .status()
+        .with_context(|| format!("Failed to launch {:?}", command.get_program()))?;
+    if !status.success() {
+        anyhow::bail!("{:?} exited with {}", command.get_program(), status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(Target::parse("dashboard").unwrap(), Target::Dashboard);
+        assert_eq!(Target::parse("F00012").unwrap(), Target::Feature("F00012".to_string()));
+        assert_eq!(Target::parse("T000003").unwrap(), Target::Task("T000003".to_string()));
+        assert!(Target::parse("F12").is_err());
+    }
+
+    #[test]
+    fn test_feature_file_resolution_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("src/login.rs"), "// Implements F00001\nfn login() {}\n").unwrap();
+        fs::write(root.join("docs/login.md"), "# Login\n").unwrap();
+
+        let mut config = OpenConfig::default();
+        let mut docs = DocCoverageConfig::default();
+        assert_eq!(feature_file(root, "F00001", &config, &docs), Some(root.join("src/login.rs")));
+
+        docs.doc_files.insert("F00001".to_string(), vec!["docs/login.md".to_string()]);
+        assert_eq!(feature_file(root, "F00001", &config, &docs), Some(root.join("docs/login.md")));
+
+        config.feature_files.insert("F00001".to_string(), vec!["missing.rs".to_string(), "src/login.rs".to_string()]);
+        assert_eq!(feature_file(root, "F00001", &config, &docs), Some(root.join("src/login.rs")));
+
+        assert_eq!(feature_file(root, "F00002", &config, &docs), None);
+    }
+
+    #[test]
+    fn test_resolve_docs_and_scrap() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let config = OpenConfig::default();
+        let docs = DocCoverageConfig::default();
+
+        assert!(resolve(root, &Target::Docs, &config, &docs).is_err());
+        assert!(resolve(root, &Target::Scrap, &config, &docs).is_err());
+
+        fs::create_dir_all(root.join("internal")).unwrap();
+        fs::write(root.join("internal/FEATURES.md"), "# Features\n").unwrap();
+        fs::create_dir_all(root.join(".scrap")).unwrap();
+        assert_eq!(resolve(root, &Target::Docs, &config, &docs).unwrap(), Action::Editor(root.join("internal/FEATURES.md")));
+        assert_eq!(resolve(root, &Target::Scrap, &config, &docs).unwrap(), Action::FileManager(root.join(".scrap")));
+        assert_eq!(
+            resolve(root, &Target::Dashboard, &config, &docs).unwrap(),
+            Action::Browser("http://localhost:3000".to_string())
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Process-wide read-only execution mode.
+//!
+//! Query commands (status, list, show, ...) run with read-only mode enabled so
+//! that inspecting a repository never creates `.wsb/`, rewrites `state.json`,
+//! writes log files, or touches the project database. Code paths that would
+//! write call [`ensure_writable`] first and fail instead of mutating anything.
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Environment variable that forces read-only mode for any command
+pub const READ_ONLY_ENV: &str = "WSB_READ_ONLY";
+
+/// Enable read-only mode for the rest of the process
+pub fn enable() {
+    READ_ONLY.store(true, Ordering::SeqCst);
+}
+
+/// Whether read-only mode is active
+pub fn is_enabled() -> bool {
+    READ_ONLY.load(Ordering::SeqCst)
+}
+
+/// Whether the environment requests read-only mode
+pub fn requested_by_env() -> bool {
+    std::env::var(READ_ONLY_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Fail if read-only mode is active; `action` describes the blocked write
+pub fn ensure_writable(action: &str) -> Result<()> {
+    if is_enabled() {
+        anyhow::bail!("Refusing to {}: running in read-only mode", action);
+    }
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::cli::ArchiveFormat;
+use super::path_filter::ExtensionFilter;
+use super::replacements::ReplacementSet;
+
+/// Archive container recognised by file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Archive kind of `path`, if its format is one of `enabled`
+    pub fn detect(path: &Path, enabled: &[ArchiveFormat]) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        let kind = if name.ends_with(".zip") {
+            ArchiveKind::Zip
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            ArchiveKind::TarGz
+        } else if name.ends_with(".tar") {
+            ArchiveKind::Tar
+        } else {
+            return None;
+        };
+
+        let format = match kind {
+            ArchiveKind::Zip => ArchiveFormat::Zip,
+            ArchiveKind::Tar | ArchiveKind::TarGz => ArchiveFormat::Tar,
+        };
+        if enabled.contains(&format) { Some(kind) } else { None }
+    }
+}
+
+/// Changes made (or planned) inside one archive
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ArchiveChanges {
+    /// Members whose content was rewritten
+    pub content_changes: usize,
+    /// Members whose name was rewritten
+    pub member_renames: usize,
+}
+
+impl ArchiveChanges {
+    pub fn is_empty(&self) -> bool {
+        self.content_changes == 0 && self.member_renames == 0
+    }
+
+    pub fn total(&self) -> usize {
+        self.content_changes + self.member_renames
+    }
+}
+
+/// Apply the replacements to one member's name and (UTF-8 text) content. Content is only
+/// replaced in members `content_filter` allows.
+fn transform_member(name: &str, data: &[u8], replacements: &ReplacementSet, content_filter: &ExtensionFilter, changes: &mut ArchiveChanges) -> (String, Option<Vec<u8>>) {
+    let new_name = if replacements.is_match(name) {
+        changes.member_renames += 1;
+        replacements.replace(name)
+    } else {
+        name.to_string()
+    };
+
+    if !content_filter.allows(Path::new(name)) {
+        return (new_name, None);
+    }
+
+    let new_data = match std::str::from_utf8(data) {
+        Ok(text) if content_inspector::inspect(data).is_text() && replacements.is_match(text) => {
+            changes.content_changes += 1;
+            Some(replacements.replace(text).into_bytes())
+        }
+        _ => None,
+    };
+
+    (new_name, new_data)
+}
+
+/// Count the changes the replacements would make inside an archive
+pub fn scan(path: &Path, kind: ArchiveKind, replacements: &ReplacementSet, content_filter: &ExtensionFilter) -> Result<ArchiveChanges> {
+    process(path, kind, replacements, content_filter, false)
+}
+
+/// Rewrite an archive with the replacements applied. The new archive is written next to
+/// the original and renamed over it, so a failure never leaves a half-written archive.
+pub fn rewrite(path: &Path, kind: ArchiveKind, replacements: &ReplacementSet, content_filter: &ExtensionFilter) -> Result<ArchiveChanges> {
+    process(path, kind, replacements, content_filter, true)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("archive");
+    path.with_file_name(format!(".{}.refac-tmp", name))
+}
+
+fn process(path: &Path, kind: ArchiveKind, replacements: &ReplacementSet, content_filter: &ExtensionFilter, write: bool) -> Result<ArchiveChanges> {
+    let temp = temp_path(path);
+    let output = if write {
+        Some(File::create(&temp).with_context(|| format!("Failed to create {}", temp.display()))?)
+    } else {
+        None
+    };
+
+    let result = match kind {
+        ArchiveKind::Zip => process_zip(path, replacements, content_filter, output),
+        ArchiveKind::Tar | ArchiveKind::TarGz => process_tar(path, kind == ArchiveKind::TarGz, replacements, content_filter, output),
+    };
+
+    if !write {
+        return result;
+    }
+
+    match result {
+        Ok(changes) if !changes.is_empty() => {
+            // Anything lost here is reported by the engine, which restores the metadata again
+            super::preserve::replace_with(&temp, path, false)
+                .with_context(|| format!("Failed to replace archive {}", path.display()))?;
+            Ok(changes)
+        }
+        other => {
+            let _ = fs::remove_file(&temp);
+            other
+        }
+    }
+}
+
+fn process_zip(path: &Path, replacements: &ReplacementSet, content_filter: &ExtensionFilter, output: Option<File>) -> Result<ArchiveChanges> {
+    use zip::write::FileOptions;
+    use zip::CompressionMethod;
+
+    let file = File::open(path).with_context(|| format!("Failed to open archive {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Failed to read zip archive {}", path.display()))?;
+    let mut writer = output.map(zip::ZipWriter::new);
+    let mut changes = ArchiveChanges::default();
+
+    for index in 0..archive.len() {
+        let mut member = archive.by_index(index)?;
+        let name = member.name().to_string();
+        let mut data = Vec::new();
+        member.read_to_end(&mut data)
+            .with_context(|| format!("Failed to read {} from {}", name, path.display()))?;
+
+        let (new_name, new_data) = transform_member(&name, &data, replacements, content_filter, &mut changes);
+
+        if let Some(writer) = writer.as_mut() {
+            let compression = match member.compression() {
+                CompressionMethod::Stored => CompressionMethod::Stored,
+                _ => CompressionMethod::Deflated,
+            };
+            let mut options = FileOptions::default()
+                .compression_method(compression)
+                .last_modified_time(member.last_modified());
+            if let Some(mode) = member.unix_mode() {
+                options = options.unix_permissions(mode);
+            }
+
+            if member.is_dir() {
+                writer.add_directory(new_name, options)?;
+            } else {
+                writer.start_file(new_name, options)?;
+                writer.write_all(new_data.as_deref().unwrap_or(&data))?;
+            }
+        }
+    }
+
+    if let Some(mut writer) = writer {
+        writer.finish()?;
+    }
+    Ok(changes)
+}
+
+fn process_tar(path: &Path, gzip: bool, replacements: &ReplacementSet, content_filter: &ExtensionFilter, output: Option<File>) -> Result<ArchiveChanges> {
+    let file = File::open(path).with_context(|| format!("Failed to open archive {}", path.display()))?;
+    let reader: Box<dyn Read> = if gzip { Box::new(flate2::read::GzDecoder::new(file)) } else { Box::new(file) };
+    let mut archive = tar::Archive::new(reader);
+
+    let mut builder = output.map(|file| {
+        let writer: Box<dyn Write> = if gzip {
+            Box::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()))
+        } else {
+            Box::new(file)
+        };
+        tar::Builder::new(writer)
+    });
+    let mut changes = ArchiveChanges::default();
+
+    for entry in archive.entries().with_context(|| format!("Failed to read tar archive {}", path.display()))? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut header = entry.header().clone();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)
+            .with_context(|| format!("Failed to read {} from {}", name, path.display()))?;
+
+        let (new_name, new_data) = transform_member(&name, &data, replacements, content_filter, &mut changes);
+
+        if let Some(builder) = builder.as_mut() {
+            let data = new_data.as_deref().unwrap_or(&data);
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, &new_name, data)
+                .with_context(|| format!("Failed to write {} to {}", new_name, path.display()))?;
+        }
+    }
+
+    if let Some(builder) = builder {
+        builder.into_inner()?.flush()?;
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn build_tar(path: &Path, members: &[(&str, &str)]) {
+        let file = File::create(path).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+        for (name, content) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn read_tar(path: &Path) -> Vec<(String, String)> {
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(File::open(path).unwrap()));
+        archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let mut e = e.unwrap();
+                let name = e.path().unwrap().to_string_lossy().into_owned();
+                let mut content = String::new();
+                e.read_to_string(&mut content).unwrap();
+                (name, content)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_respects_enabled_formats() {
+        assert_eq!(ArchiveKind::detect(Path::new("a/fixtures.tar.gz"), &[ArchiveFormat::Tar]), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::detect(Path::new("bundle.ZIP"), &[ArchiveFormat::Zip]), Some(ArchiveKind::Zip));
+        assert_eq!(ArchiveKind::detect(Path::new("bundle.zip"), &[ArchiveFormat::Tar]), None);
+        assert_eq!(ArchiveKind::detect(Path::new("notes.txt"), &[ArchiveFormat::Zip, ArchiveFormat::Tar]), None);
+    }
+
+    #[test]
+    fn test_rewrite_tar_gz() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("fixtures.tar.gz");
+        build_tar(&archive, &[("oldname/config.toml", "name = \"oldname\""), ("readme.md", "unrelated")]);
+
+        let replacements = ReplacementSet::literal("oldname", "newname", false).unwrap();
+        let planned = scan(&archive, ArchiveKind::TarGz, &replacements, &ExtensionFilter::default()).unwrap();
+        assert_eq!(planned, ArchiveChanges { content_changes: 1, member_renames: 1 });
+
+        let applied = rewrite(&archive, ArchiveKind::TarGz, &replacements, &ExtensionFilter::default()).unwrap();
+        assert_eq!(applied, planned);
+        assert_eq!(
+            read_tar(&archive),
+            vec![
+                ("newname/config.toml".to_string(), "name = \"newname\"".to_string()),
+                ("readme.md".to_string(), "unrelated".to_string()),
+            ]
+        );
+        assert!(!temp_path(&archive).exists());
+    }
+
+    #[test]
+    fn test_content_filter_applies_to_members() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("fixtures.tar.gz");
+        build_tar(&archive, &[("lib.rs", "use oldname;"), ("notes.md", "oldname notes")]);
+
+        let replacements = ReplacementSet::literal("oldname", "newname", false).unwrap();
+        let only_rust = ExtensionFilter::new(&["rs".to_string()]);
+        let changes = rewrite(&archive, ArchiveKind::TarGz, &replacements, &only_rust).unwrap();
+        assert_eq!(changes, ArchiveChanges { content_changes: 1, member_renames: 0 });
+        assert_eq!(
+            read_tar(&archive),
+            vec![
+                ("lib.rs".to_string(), "use newname;".to_string()),
+                ("notes.md".to_string(), "oldname notes".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("bundle.zip");
+        {
+            let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let options = zip::write::FileOptions::default();
+            writer.add_directory("oldname/", options).unwrap();
+            writer.start_file("oldname/main.rs", options).unwrap();
+            writer.write_all(b"mod oldname;").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let replacements = ReplacementSet::literal("oldname", "newname", false).unwrap();
+        let changes = rewrite(&archive, ArchiveKind::Zip, &replacements, &ExtensionFilter::default()).unwrap();
+        assert_eq!(changes, ArchiveChanges { content_changes: 1, member_renames: 2 });
+
+        let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        let mut content = String::new();
+        zip.by_name("newname/main.rs").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "mod newname;");
+    }
+}
@@ -48,7 +48,8 @@ impl BinaryDetector {
         // Check using content_inspector crate (fast method)
         if let Ok(content_type) = self.detect_by_content_inspector(path) {
             match content_type {
-                ContentType::BINARY => return Ok(true),
+                // UTF-16 without a BOM is full of null bytes but still text
+                ContentType::BINARY => return Ok(!self.is_utf16_without_bom(path)),
                 ContentType::UTF_8 | ContentType::UTF_8_BOM | 
                 ContentType::UTF_16LE | ContentType::UTF_16BE |
                 ContentType::UTF_32LE | ContentType::UTF_32BE => return Ok(false),
@@ -103,6 +104,17 @@ impl BinaryDetector {
         Ok(inspect(&buffer))
     }
 
+    /// Whether the file's sample reads as BOM-less UTF-16 text
+    fn is_utf16_without_bom(&self, path: &Path) -> bool {
+        let mut buffer = vec![0; self.max_sample_size];
+        let bytes_read = match File::open(path).and_then(|mut file| file.read(&mut buffer)) {
+            Ok(bytes_read) => bytes_read,
+            Err(_) => return false,
+        };
+        buffer.truncate(bytes_read);
+        detect_utf16_without_bom(&buffer).is_some()
+    }
+
     /// Manual content analysis for edge cases
     fn is_binary_by_content_analysis(&self, path: &Path) -> Result<bool> {
         let file = File::open(path)
@@ -213,6 +225,12 @@ impl BinaryDetector {
             return false; // Too much readable text or valid UTF-8, unlikely to be protobuf
         }
 
+        // UTF-16 text alternates characters with null bytes, which also reads as varints
+        let has_utf16_bom = matches!(&buffer[0..2], [0xFF, 0xFE] | [0xFE, 0xFF]);
+        if has_utf16_bom || detect_utf16_without_bom(buffer).is_some() {
+            return false;
+        }
+
         // Protocol Buffers use varint encoding and have specific wire type patterns
         let mut valid_proto_sequences = 0;
         let mut i = 0;
@@ -342,6 +360,40 @@ impl BinaryDetector {
     }
 }
 
+/// Detect UTF-16 text without a byte order mark from where its null bytes fall: mostly
+/// Latin text leaves every other byte zero (odd offsets for LE, even offsets for BE).
+/// The guess is only accepted if the sample then decodes cleanly.
+pub fn detect_utf16_without_bom(buffer: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    // Only whole code units; a sample may end mid-character
+    let len = buffer.len() & !1;
+    if len < 4 {
+        return None;
+    }
+    let sample = &buffer[..len];
+    let units = len / 2;
+    let even_zeros = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+
+    let mostly = |zeros: usize| zeros * 10 >= units * 3;
+    let rarely = |zeros: usize| zeros * 20 <= units;
+    let encoding = if mostly(odd_zeros) && rarely(even_zeros) {
+        encoding_rs::UTF_16LE
+    } else if mostly(even_zeros) && rarely(odd_zeros) {
+        encoding_rs::UTF_16BE
+    } else {
+        return None;
+    };
+
+    // A sample cut inside a surrogate pair leaves one malformed unit at the end
+    let (text, _) = encoding.decode_without_bom_handling(sample);
+    let body = text.trim_end_matches('\u{FFFD}');
+    let is_text = body.chars().all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r' | '\u{0C}'));
+    if body.contains('\u{FFFD}') || text.len() - body.len() > '\u{FFFD}'.len_utf8() || !is_text {
+        return None;
+    }
+    Some(encoding)
+}
+
 /// Check if a byte is printable ASCII
 fn is_printable_ascii(byte: u8) -> bool {
     matches!(byte, 0x20..=0x7E | 0x09 | 0x0A | 0x0D) // printable ASCII + tab, newline, carriage return
@@ -451,6 +503,23 @@ mod tests {
     use std::io::Write;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_utf16_without_bom_detection() -> Result<()> {
+        let text = "fn main() {\n    println!(\"oldname\");\n}\n";
+        let le: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        let be: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+        assert!(std::ptr::eq(detect_utf16_without_bom(&le).unwrap(), encoding_rs::UTF_16LE));
+        assert!(std::ptr::eq(detect_utf16_without_bom(&be).unwrap(), encoding_rs::UTF_16BE));
+        assert!(detect_utf16_without_bom(text.as_bytes()).is_none());
+        assert!(detect_utf16_without_bom(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04]).is_none());
+
+        let temp_dir = TempDir::new()?;
+        let file = temp_dir.path().join("utf16.txt");
+        std::fs::write(&file, &le)?;
+        assert!(!BinaryDetector::default().is_binary(&file)?);
+        Ok(())
+    }
+
     #[test]
     fn test_binary_extension_detection() {
         let detector = BinaryDetector::default();
@@ -11,11 +11,11 @@ pub struct Args {
     pub root_dir: PathBuf,
 
     /// Pattern to find and replace
-    #[arg(value_name = "PATTERN")]
+    #[arg(value_name = "PATTERN", required_unless_present = "map_file", default_value = "")]
     pub pattern: String,
 
     /// Replacement text
-    #[arg(value_name = "SUBSTITUTE")]
+    #[arg(value_name = "SUBSTITUTE", required_unless_present = "map_file", default_value = "")]
     pub substitute: String,
 
 
@@ -90,6 +90,80 @@ pub struct Args {
     /// Include binary file names in renaming operations (content will still be skipped)
     #[arg(long = "binary-names")]
     pub binary_names: bool,
+
+    /// Don't respect .gitignore/.ignore/.refacignore files (.git is still skipped)
+    #[arg(long = "no-ignore")]
+    pub no_ignore: bool,
+
+    /// Also replace case-style variants (old_name -> new_name also rewrites OldName, OLD_NAME, oldName, old-name)
+    #[arg(long = "match-case-styles")]
+    pub match_case_styles: bool,
+
+    /// Also replace content and rename members inside these archive formats (comma-separated)
+    #[arg(long = "into-archives", value_name = "FORMATS", value_delimiter = ',')]
+    pub into_archives: Vec<ArchiveFormat>,
+
+    /// Validate and print the planned changes without applying them
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Also rewrite symlink targets that contain the pattern (the links themselves are always renamed, never their targets)
+    #[arg(long = "rewrite-link-targets")]
+    pub rewrite_link_targets: bool,
+
+    /// What to do when renames collide with each other or with existing paths
+    #[arg(long = "on-collision", value_enum, default_value = "abort")]
+    pub on_collision: CollisionStrategy,
+
+    /// Only replace content in files with these extensions (comma-separated, e.g. rs,toml,md)
+    #[arg(long = "content-ext", value_name = "EXTS", value_delimiter = ',')]
+    pub content_extensions: Vec<String>,
+
+    /// Disable content replacement entirely; paths are still renamed
+    #[arg(long = "no-content")]
+    pub no_content: bool,
+
+    /// TOML file of old -> new pairs (under [renames]) applied together in one pass
+    #[arg(long = "map", value_name = "FILE")]
+    pub map_file: Option<PathBuf>,
+
+    /// Use `git mv` for tracked files and stage content edits, so git records renames
+    #[arg(long = "git")]
+    pub git: bool,
+
+    /// Leave matches inside comments untouched (languages known to code analysis)
+    #[arg(long = "skip-comments")]
+    pub skip_comments: bool,
+
+    /// Leave matches inside string literals untouched (languages known to code analysis)
+    #[arg(long = "skip-strings")]
+    pub skip_strings: bool,
+
+    /// Leave matches in code untouched, so only comments and strings are replaced
+    #[arg(long = "skip-code")]
+    pub skip_code: bool,
+
+    /// Keep each rewritten file's modification time
+    #[arg(long = "preserve-mtime")]
+    pub preserve_mtime: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionStrategy {
+    /// Report the collisions and stop before changing anything
+    Abort,
+    /// Leave the colliding items under their current names
+    Skip,
+    /// Rename colliding items to the first free `name_N` variant
+    Suffix,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// .zip archives
+    Zip,
+    /// .tar, .tar.gz and .tgz archives
+    Tar,
 }
 
 #[derive(ValueEnum, Debug, Clone, PartialEq)]
@@ -126,7 +200,65 @@ pub enum Mode {
     ContentOnly,
 }
 
+/// The command-line defaults with an empty root, pattern and substitute; set those and
+/// override the rest with struct update syntax
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            root_dir: PathBuf::new(),
+            pattern: String::new(),
+            substitute: String::new(),
+            assume_yes: false,
+            verbose: false,
+            follow_symlinks: false,
+            backup: false,
+            files_only: false,
+            dirs_only: false,
+            names_only: false,
+            content_only: false,
+            max_depth: 0,
+            exclude_patterns: vec![],
+            include_patterns: vec![],
+            format: OutputFormat::Human,
+            threads: 0,
+            progress: ProgressMode::Auto,
+            ignore_case: false,
+            use_regex: false,
+            include_hidden: false,
+            binary_names: false,
+            no_ignore: false,
+            match_case_styles: false,
+            into_archives: vec![],
+            dry_run: false,
+            rewrite_link_targets: false,
+            on_collision: CollisionStrategy::Abort,
+            content_extensions: vec![],
+            no_content: false,
+            map_file: None,
+            git: false,
+            skip_comments: false,
+            skip_strings: false,
+            skip_code: false,
+            preserve_mtime: false,
+        }
+    }
+}
+
 impl Args {
+    /// The positional PATTERN/SUBSTITUTE pair followed by any `--map` pairs, checked for
+    /// conflicts between them
+    pub fn replacement_pairs(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let mut pairs = Vec::new();
+        if !self.pattern.is_empty() {
+            pairs.push((self.pattern.clone(), self.substitute.clone()));
+        }
+        if let Some(ref map_file) = self.map_file {
+            pairs.extend(super::replacements::load_map(map_file)?);
+        }
+        super::replacements::check_pairs(&pairs, self.ignore_case)?;
+        Ok(pairs)
+    }
+
     pub fn get_mode(&self) -> Mode {
         match (self.files_only, self.dirs_only, self.names_only, self.content_only) {
             (true, false, false, false) => Mode::FilesOnly,
@@ -146,6 +278,24 @@ impl Args {
             return Err("Cannot specify more than one mode flag (--files-only, --dirs-only, --names-only, --content-only)".to_string());
         }
 
+        if self.no_content && self.content_only {
+            return Err("--no-content cannot be combined with --content-only".to_string());
+        }
+
+        if self.no_content && !self.content_extensions.is_empty() {
+            return Err("--no-content cannot be combined with --content-ext".to_string());
+        }
+
+        let skipped_regions = [self.skip_comments, self.skip_strings, self.skip_code];
+        if skipped_regions.iter().any(|&x| x) {
+            if self.no_content || self.names_only {
+                return Err("--skip-comments, --skip-strings and --skip-code need content replacement".to_string());
+            }
+            if skipped_regions.iter().all(|&x| x) {
+                return Err("--skip-comments, --skip-strings and --skip-code together would skip every match".to_string());
+            }
+        }
+
         // Validate root directory exists
         if !self.root_dir.exists() {
             return Err(format!("Root directory does not exist: {}", self.root_dir.display()));
@@ -156,14 +306,25 @@ impl Args {
         }
 
         // Validate strings
-        if self.pattern.is_empty() {
-            return Err("Pattern cannot be empty".to_string());
-        }
+        if self.map_file.is_some() {
+            if self.use_regex {
+                return Err("--map pairs are literal and cannot be combined with --regex".to_string());
+            }
+            if self.pattern.is_empty() != self.substitute.is_empty() {
+                return Err("Give both PATTERN and SUBSTITUTE, or neither when using --map".to_string());
+            }
+        } else {
+            if self.pattern.is_empty() {
+                return Err("Pattern cannot be empty".to_string());
+            }
 
-        if self.substitute.is_empty() {
-            return Err("Substitute cannot be empty".to_string());
+            if self.substitute.is_empty() {
+                return Err("Substitute cannot be empty".to_string());
+            }
         }
 
+        let pairs = self.replacement_pairs().map_err(|e| e.to_string())?;
+
         // Check for path-unsafe characters in substitute (only when processing names)
         // These characters are problematic in file/directory names across different OS:
         // - / and \ : path separators (Unix/Windows)
@@ -172,7 +333,7 @@ impl Args {
         // - null byte : terminator
         if self.should_process_names() {
             let invalid_chars = ['/', '\\', ':', '*', '?', '"', '<', '>', '|', '\0'];
-            if let Some(ch) = self.substitute.chars().find(|c| invalid_chars.contains(c)) {
+            if let Some(ch) = pairs.iter().flat_map(|(_, substitute)| substitute.chars()).find(|c| invalid_chars.contains(c)) {
                 return Err(format!(
                     "Substitute cannot contain path-unsafe characters ({}) when processing names. Use --content-only to replace in file contents only.",
                     ch
@@ -202,7 +363,7 @@ impl Args {
     }
 
     pub fn should_process_content(&self) -> bool {
-        !self.names_only
+        !self.names_only && !self.no_content
     }
 
     pub fn should_process_names(&self) -> bool {
@@ -251,6 +412,7 @@ mod tests {
             use_regex: false,
             include_hidden: false,
             binary_names: false,
+            ..Default::default()
         };
 
         // Valid args should pass
@@ -316,6 +478,7 @@ mod tests {
             use_regex: false,
             include_hidden: false,
             binary_names: false,
+            ..Default::default()
         };
 
         // Test default mode
@@ -368,6 +531,7 @@ mod tests {
             use_regex: false,
             include_hidden: false,
             binary_names: false,
+            ..Default::default()
         };
 
         // Default should process everything
@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use crate::RenameItem;
+use crate::{ItemType, RenameItem};
+use super::cli::CollisionStrategy;
 
 /// Detects naming collisions in rename operations
 #[derive(Debug)]
@@ -135,7 +136,11 @@ impl CollisionDetector {
                     collision_type,
                     target_path: target.clone(),
                     source_paths: sources.clone(),
-                    description: format!("Target path already exists: {}", target.display()),
+                    description: format!(
+                        "Target path already exists: {} (source: {})",
+                        target.display(),
+                        sources.iter().map(|s| s.display().to_string()).collect::<Vec<_>>().join(", ")
+                    ),
                 });
             }
         }
@@ -263,6 +268,85 @@ impl CollisionDetector {
     }
 }
 
+/// How one colliding rename was resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    /// Where the item is renamed instead; `None` when the rename is skipped
+    pub resolved: Option<PathBuf>,
+}
+
+/// `name_N` variant of a path; files keep their extension
+fn suffixed(path: &Path, n: usize, is_dir: bool) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let new_name = match (is_dir, path.file_stem(), path.extension()) {
+        (false, Some(stem), Some(ext)) => format!("{}_{}.{}", stem.to_string_lossy(), n, ext.to_string_lossy()),
+        _ => format!("{}_{}", name, n),
+    };
+    path.with_file_name(new_name)
+}
+
+/// Apply a collision strategy to the planned renames. With `Skip` the colliding items keep
+/// their names; with `Suffix` they move to the first `name_N` variant that is neither on disk
+/// nor planned. When several sources share a free target, the first source (in path order)
+/// keeps it. `Abort` leaves the plan unchanged.
+pub fn resolve_collisions(
+    items: Vec<RenameItem>,
+    collisions: &[Collision],
+    strategy: CollisionStrategy,
+) -> (Vec<RenameItem>, Vec<Resolution>) {
+    if strategy == CollisionStrategy::Abort {
+        return (items, Vec::new());
+    }
+
+    let mut colliding: HashSet<PathBuf> = HashSet::new();
+    for collision in collisions {
+        match collision.collision_type {
+            CollisionType::SourceEqualsTarget => {}
+            CollisionType::MultipleSourcesSameTarget
+                if strategy == CollisionStrategy::Suffix && collision.target_path.symlink_metadata().is_err() =>
+            {
+                let mut sources = collision.source_paths.clone();
+                sources.sort();
+                colliding.extend(sources.into_iter().skip(1));
+            }
+            _ => colliding.extend(collision.source_paths.iter().cloned()),
+        }
+    }
+
+    let mut taken: HashSet<PathBuf> = items.iter().map(|item| item.new_path.clone()).collect();
+    let mut kept = Vec::with_capacity(items.len());
+    let mut resolutions = Vec::new();
+
+    for mut item in items {
+        if !colliding.contains(&item.original_path) {
+            kept.push(item);
+            continue;
+        }
+
+        let target = item.new_path.clone();
+        match strategy {
+            CollisionStrategy::Suffix => {
+                let is_dir = item.item_type == ItemType::Directory;
+                let resolved = (1..)
+                    .map(|n| suffixed(&target, n, is_dir))
+                    .find(|candidate| !taken.contains(candidate) && candidate.symlink_metadata().is_err())
+                    .expect("an unused suffix always exists");
+                taken.insert(resolved.clone());
+                item.new_path = resolved.clone();
+                resolutions.push(Resolution { source: item.original_path.clone(), target, resolved: Some(resolved) });
+                kept.push(item);
+            }
+            _ => {
+                resolutions.push(Resolution { source: item.original_path.clone(), target, resolved: None });
+            }
+        }
+    }
+
+    (kept, resolutions)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,6 +547,59 @@ mod tests {
         Ok(())
     }
 
+    fn rename_item(from: &Path, to: &Path) -> RenameItem {
+        RenameItem {
+            original_path: from.to_path_buf(),
+            new_path: to.to_path_buf(),
+            item_type: ItemType::File,
+            depth: 1,
+        }
+    }
+
+    #[test]
+    fn test_resolve_collisions_suffix_and_skip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        File::create(root.join("existing.txt"))?;
+        File::create(root.join("existing_1.txt"))?;
+
+        let items = vec![
+            rename_item(&root.join("a_old.txt"), &root.join("target.txt")),
+            rename_item(&root.join("b_old.txt"), &root.join("target.txt")),
+            rename_item(&root.join("c_old.txt"), &root.join("existing.txt")),
+            rename_item(&root.join("d_old.txt"), &root.join("free.txt")),
+        ];
+        let mut detector = CollisionDetector::new();
+        detector.scan_existing_paths(root)?;
+        detector.add_renames(&items);
+        let collisions = detector.detect_collisions()?;
+
+        let (resolved, resolutions) = resolve_collisions(items.clone(), &collisions, CollisionStrategy::Suffix);
+        let targets: Vec<PathBuf> = resolved.iter().map(|i| i.new_path.clone()).collect();
+        assert_eq!(targets, vec![
+            root.join("target.txt"),
+            root.join("target_1.txt"),
+            root.join("existing_2.txt"),
+            root.join("free.txt"),
+        ]);
+        assert_eq!(resolutions.len(), 2);
+
+        let (kept, skipped) = resolve_collisions(items, &collisions, CollisionStrategy::Skip);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].new_path, root.join("free.txt"));
+        assert!(skipped.iter().all(|r| r.resolved.is_none()));
+        assert_eq!(skipped.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_suffixed_keeps_extension_for_files_only() {
+        assert_eq!(suffixed(Path::new("/t/new.tar.gz"), 2, false), PathBuf::from("/t/new.tar_2.gz"));
+        assert_eq!(suffixed(Path::new("/t/new.d"), 1, true), PathBuf::from("/t/new.d_1"));
+        assert_eq!(suffixed(Path::new("/t/Makefile"), 1, false), PathBuf::from("/t/Makefile_1"));
+    }
+
     #[test]
     fn test_no_collisions_report() {
         let detector = CollisionDetector::new();
@@ -2,7 +2,8 @@ use anyhow::{Context, Result};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
-use super::binary_detector::BinaryDetector;
+use super::binary_detector::{detect_utf16_without_bom, BinaryDetector};
+use super::replacements::ReplacementSet;
 use encoding_rs::{Encoding, UTF_8};
 use chardet::detect;
 
@@ -19,6 +20,31 @@ struct FileEncoding {
     has_bom: bool,
 }
 
+impl FileEncoding {
+    fn is_utf16(&self) -> bool {
+        std::ptr::eq(self.encoding, encoding_rs::UTF_16LE) || std::ptr::eq(self.encoding, encoding_rs::UTF_16BE)
+    }
+}
+
+/// A text file whose encoding could not be determined or does not round-trip; its
+/// content is skipped rather than guessed at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownEncoding {
+    pub reason: String,
+}
+
+impl std::fmt::Display for UnknownEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown encoding: {}", self.reason)
+    }
+}
+
+impl std::error::Error for UnknownEncoding {}
+
+fn unknown_encoding(reason: String) -> anyhow::Error {
+    UnknownEncoding { reason }.into()
+}
+
 impl Default for FileOperations {
     fn default() -> Self {
         Self::new()
@@ -45,6 +71,28 @@ impl FileOperations {
         pattern: &str,
         substitute: &str,
     ) -> Result<bool> {
+        let replacements = ReplacementSet::literal(pattern, substitute, false)?;
+        self.replace_content_with(file_path, &replacements)
+    }
+
+    /// Replace every match of a replacement set in a file, in a single pass
+    pub fn replace_content_with<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        replacements: &ReplacementSet,
+    ) -> Result<bool> {
+        self.replace_content_by(file_path, |content| {
+            replacements.is_match(content).then(|| replacements.replace(content))
+        })
+    }
+
+    /// Rewrite a text file's content with `transform`, keeping its encoding.
+    /// `transform` returns `None` when the file needs no change.
+    pub fn replace_content_by<P, F>(&self, file_path: P, transform: F) -> Result<bool>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&str) -> Option<String>,
+    {
         let file_path = file_path.as_ref();
         
         // Skip binary files
@@ -63,19 +111,17 @@ impl FileOperations {
         let content = self.decode_with_encoding(&original_bytes, &file_encoding)
             .with_context(|| format!("Failed to decode file with detected encoding: {}", file_path.display()))?;
 
-        // Check if the file contains the target string
-        if !content.contains(pattern) {
-            return Ok(false);
-        }
+        // Replace content; files with nothing to change are left untouched
+        let new_content = match transform(&content) {
+            Some(new_content) => new_content,
+            None => return Ok(false),
+        };
 
         // Create backup if enabled
         if self.backup_enabled {
             self.create_backup(file_path)?;
         }
 
-        // Replace content
-        let new_content = content.replace(pattern, substitute);
-
         // Encode back to the original encoding and write
         let encoded_bytes = self.encode_with_encoding(&new_content, &file_encoding)
             .with_context(|| format!("Failed to encode content back to original encoding: {}", file_path.display()))?;
@@ -140,14 +186,17 @@ impl FileOperations {
         }
 
         if modified {
-            // Replace the original file with the modified one
-            fs::rename(&temp_file_path, file_path).with_context(|| {
+            // Replace the original file with the modified one, keeping its mode, owner and xattrs
+            let lost = super::preserve::replace_with(&temp_file_path, file_path, false).with_context(|| {
                 format!(
                     "Failed to replace original file {} with temp file {}",
                     file_path.display(),
                     temp_file_path.display()
                 )
             })?;
+            if !lost.is_empty() {
+                log::warn!("Could not preserve {} of {}", lost.join(", "), file_path.display());
+            }
         } else {
             // Remove the temp file since no changes were made
             let _ = fs::remove_file(&temp_file_path);
@@ -280,6 +329,39 @@ impl FileOperations {
         Ok(content.contains(search_string))
     }
 
+    /// Check if a file contains any pattern of a replacement set
+    pub fn file_contains_match<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        replacements: &ReplacementSet,
+    ) -> Result<bool> {
+        let file_path = file_path.as_ref();
+        
+        // Skip binary files
+        if self.binary_detector.is_binary(file_path)? {
+            return Ok(false);
+        }
+
+        let bytes = fs::read(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+            
+        let file_encoding = self.detect_encoding(&bytes)?;
+        let content = self.decode_with_encoding(&bytes, &file_encoding)
+            .with_context(|| format!("Failed to decode file: {}", file_path.display()))?;
+
+        Ok(replacements.is_match(&content))
+    }
+
+    /// Read a text file's content, decoding it from its detected encoding
+    pub fn read_text<P: AsRef<Path>>(&self, file_path: P) -> Result<String> {
+        let file_path = file_path.as_ref();
+        let bytes = fs::read(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        let file_encoding = self.detect_encoding(&bytes)?;
+        self.decode_with_encoding(&bytes, &file_encoding)
+            .with_context(|| format!("Failed to decode file: {}", file_path.display()))
+    }
+
     /// Count occurrences of a string in a file
     pub fn count_string_occurrences<P: AsRef<Path>>(
         &self,
@@ -372,17 +454,31 @@ impl FileOperations {
             }
         }
         
-        // Try UTF-8 first (most common)
+        // UTF-16 written without a BOM (common for files produced on Windows). Checked
+        // before UTF-8 because UTF-16 of plain ASCII is also valid UTF-8, null bytes and all.
+        if let Some(encoding) = detect_utf16_without_bom(bytes) {
+            return Ok(FileEncoding {
+                encoding,
+                has_bom: false,
+            });
+        }
+
+        // Try UTF-8 next (most common)
         if let Ok(_) = std::str::from_utf8(bytes) {
             return Ok(FileEncoding {
                 encoding: UTF_8,
                 has_bom: false,
             });
         }
+
+        // Null bytes in anything else mean a multi-byte encoding we cannot identify
+        if bytes.contains(&0) {
+            return Err(unknown_encoding("contains null bytes but is not UTF-16".to_string()));
+        }
         
         // Use chardet for automatic detection
         let detection_result = detect(bytes);
-        let encoding_name = detection_result.0;
+        let encoding_name = detection_result.0.to_uppercase();
         
         // Map chardet encoding names to encoding_rs encodings
         let encoding = match encoding_name.as_str() {
@@ -412,10 +508,14 @@ impl FileOperations {
             "ISO-2022-JP" => encoding_rs::ISO_2022_JP,
             "SHIFT_JIS" => encoding_rs::SHIFT_JIS,
             "EUC-KR" => encoding_rs::EUC_KR,
-            _ => {
-                // Fallback to Windows-1252 for unknown encodings (handles most extended ASCII)
+            "" => {
+                // No confident guess: Windows-1252 decodes any byte and round-trips it
                 encoding_rs::WINDOWS_1252
             }
+            other => match Encoding::for_label(other.as_bytes()) {
+                Some(encoding) => encoding,
+                None => return Err(unknown_encoding(format!("unrecognised charset '{}'", other))),
+            },
         };
         
         Ok(FileEncoding {
@@ -440,13 +540,13 @@ impl FileOperations {
             bytes
         };
         
-        let (decoded, _, had_errors) = file_encoding.encoding.decode(decode_bytes);
+        let (decoded, had_errors) = file_encoding.encoding.decode_without_bom_handling(decode_bytes);
         
         if had_errors {
-            return Err(anyhow::anyhow!(
-                "Decoding errors occurred with encoding: {}", 
+            return Err(unknown_encoding(format!(
+                "invalid byte sequences for {}",
                 file_encoding.encoding.name()
-            ));
+            )));
         }
         
         Ok(decoded.into_owned())
@@ -454,7 +554,18 @@ impl FileOperations {
     
     /// Encode string back to the original encoding
     fn encode_with_encoding(&self, content: &str, file_encoding: &FileEncoding) -> Result<Vec<u8>> {
-        let (encoded, _, had_errors) = file_encoding.encoding.encode(content);
+        // encoding_rs only encodes to UTF-8 for UTF-16 input encodings, so write UTF-16 units directly
+        let (encoded, had_errors) = if file_encoding.is_utf16() {
+            let big_endian = std::ptr::eq(file_encoding.encoding, encoding_rs::UTF_16BE);
+            let bytes: Vec<u8> = content
+                .encode_utf16()
+                .flat_map(|unit| if big_endian { unit.to_be_bytes() } else { unit.to_le_bytes() })
+                .collect();
+            (std::borrow::Cow::Owned(bytes), false)
+        } else {
+            let (encoded, _, had_errors) = file_encoding.encoding.encode(content);
+            (encoded, had_errors)
+        };
         
         if had_errors {
             return Err(anyhow::anyhow!(
@@ -836,6 +947,50 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_utf16_replacement_round_trip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let file_ops = FileOperations::new();
+        let utf16 = |text: &str, big_endian: bool| -> Vec<u8> {
+            text.encode_utf16()
+                .flat_map(|u| if big_endian { u.to_be_bytes() } else { u.to_le_bytes() })
+                .collect()
+        };
+
+        // With a BOM the BOM is kept and the content stays UTF-16 (not rewritten as UTF-8)
+        let with_bom = temp_dir.path().join("bom.txt");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16("let oldname = 1; // Grüße\n", false));
+        fs::write(&with_bom, &bytes)?;
+        assert!(file_ops.replace_content(&with_bom, "oldname", "newname")?);
+        let mut expected = vec![0xFF, 0xFE];
+        expected.extend(utf16("let newname = 1; // Grüße\n", false));
+        assert_eq!(fs::read(&with_bom)?, expected);
+
+        // Without a BOM the byte order is inferred
+        let without_bom = temp_dir.path().join("nobom.txt");
+        fs::write(&without_bom, utf16("oldname here\n", true))?;
+        assert!(file_ops.file_contains_string(&without_bom, "oldname")?);
+        assert!(file_ops.replace_content(&without_bom, "oldname", "newname")?);
+        assert_eq!(fs::read(&without_bom)?, utf16("newname here\n", true));
+        assert_eq!(file_ops.read_text(&without_bom)?, "newname here\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_encoding_is_reported() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let file_ops = FileOperations::new();
+
+        // Null bytes that do not follow a UTF-16 pattern
+        let file = temp_dir.path().join("mystery.txt");
+        fs::write(&file, [b'o', b'l', b'd', 0, 0, 0, 0xC3, b'x', 0, b'y', 0xE9, 0, 0])?;
+        let err = file_ops.read_text(&file).unwrap_err();
+        assert!(err.downcast_ref::<UnknownEncoding>().is_some());
+        Ok(())
+    }
+
     #[test]
     fn test_encoding_detection_methods() -> Result<()> {
         let _temp_dir = TempDir::new()?;
@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Paths are passed to `git add` in batches to stay under command-line limits
+const ADD_BATCH: usize = 200;
+
+/// Tracked files of the repository containing the refactored tree (`--git`)
+#[derive(Debug)]
+pub struct GitIndex {
+    tracked_files: HashSet<PathBuf>,
+    tracked_dirs: HashSet<PathBuf>,
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<std::process::Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))
+}
+
+fn check(output: &std::process::Output, action: &str) -> Result<()> {
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", action, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+impl GitIndex {
+    /// Read the tracked files under `root_dir`; fails outside a git work tree.
+    /// Paths are keyed the way the walker reports them: `root_dir` joined with the relative path.
+    pub fn open(root_dir: &Path) -> Result<Self> {
+        let inside = run_git(root_dir, &["rev-parse", "--is-inside-work-tree"])?;
+        if !inside.status.success() || String::from_utf8_lossy(&inside.stdout).trim() != "true" {
+            anyhow::bail!("--git requires {} to be inside a git work tree", root_dir.display());
+        }
+
+        let listed = run_git(root_dir, &["ls-files", "-z"])?;
+        check(&listed, "ls-files")?;
+
+        let mut tracked_files = HashSet::new();
+        let mut tracked_dirs = HashSet::new();
+        for relative in listed.stdout.split(|b| *b == 0).filter(|p| !p.is_empty()) {
+            let relative = PathBuf::from(String::from_utf8_lossy(relative).as_ref());
+            let path = root_dir.join(&relative);
+            // A directory counts as tracked when any file below it is
+            let mut parent = path.parent();
+            while let Some(dir) = parent {
+                if dir == root_dir || !tracked_dirs.insert(dir.to_path_buf()) {
+                    break;
+                }
+                parent = dir.parent();
+            }
+            tracked_files.insert(path);
+        }
+
+        Ok(Self { tracked_files, tracked_dirs })
+    }
+
+    pub fn is_tracked(&self, path: &Path) -> bool {
+        self.tracked_files.contains(path) || self.tracked_dirs.contains(path)
+    }
+
+    /// Rename through `git mv` so the index records the move
+    pub fn mv(&self, source: &Path, destination: &Path) -> Result<()> {
+        let output = Command::new("git")
+            .arg("mv")
+            .arg("--")
+            .arg(source)
+            .arg(destination)
+            .output()
+            .context("Failed to run git mv")?;
+        check(&output, &format!("mv {} {}", source.display(), destination.display()))
+    }
+
+    /// Stage the tracked files among `paths`; untracked files are left alone
+    pub fn stage(&self, paths: &[PathBuf]) -> Result<usize> {
+        let tracked: Vec<&PathBuf> = paths.iter().filter(|p| self.tracked_files.contains(*p)).collect();
+        for batch in tracked.chunks(ADD_BATCH) {
+            let output = Command::new("git")
+                .arg("add")
+                .arg("--")
+                .args(batch)
+                .output()
+                .context("Failed to run git add")?;
+            check(&output, "add")?;
+        }
+        Ok(tracked.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git").arg("-C").arg(dir).args(args).status().unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_tracked_files_and_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        git(root, &["init", "-q"]);
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/nested/lib.rs"), "").unwrap();
+        fs::write(root.join("untracked.txt"), "").unwrap();
+        git(root, &["add", "src"]);
+
+        let index = GitIndex::open(root).unwrap();
+        assert!(index.is_tracked(&root.join("src/nested/lib.rs")));
+        assert!(index.is_tracked(&root.join("src/nested")));
+        assert!(index.is_tracked(&root.join("src")));
+        assert!(!index.is_tracked(&root.join("untracked.txt")));
+    }
+}
@@ -0,0 +1,403 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::scrap::dedupe::hash_file;
+use crate::ItemType;
+use super::symlinks;
+
+/// Environment variable overriding where journals are stored
+pub const JOURNAL_DIR_ENV: &str = "WSB_REFAC_JOURNAL_DIR";
+
+/// Number of journals kept; older ones are pruned when a new run finishes
+pub const MAX_JOURNALS: usize = 50;
+
+/// Default journal store: `$WSB_REFAC_JOURNAL_DIR`, else `$XDG_DATA_HOME/wsb/refac-journal`
+/// (falling back to `~/.local/share`). Journals live outside the refactored tree so they
+/// are never picked up by a later run.
+pub fn default_store() -> PathBuf {
+    if let Ok(dir) = std::env::var(JOURNAL_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+
+    let data_home = std::env::var("XDG_DATA_HOME").unwrap_or_else(|_| {
+        let home = std::env::var("HOME").unwrap_or_default();
+        format!("{}/.local/share", home)
+    });
+    PathBuf::from(data_home).join("wsb").join("refac-journal")
+}
+
+/// A single change made by a refactor run. Paths are relative to the journal's root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// File content was rewritten; `original` names the saved copy in the journal's blob directory
+    /// and `rewritten` is the SHA-256 of the content the run wrote (absent in older journals)
+    Content {
+        path: PathBuf,
+        original: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rewritten: Option<String>,
+    },
+    /// File or directory was renamed
+    Rename { from: PathBuf, to: PathBuf, directory: bool },
+    /// Symlink at `path` was repointed from target `from` to `to` (targets are stored verbatim)
+    LinkTarget { path: PathBuf, from: PathBuf, to: PathBuf },
+}
+
+/// Journal of one refactor run, stored as `<store>/<id>.json` with blobs in `<store>/<id>/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub id: String,
+    pub root_dir: PathBuf,
+    pub pattern: String,
+    pub substitute: String,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+    pub entries: Vec<JournalEntry>,
+}
+
+impl JournalRecord {
+    pub fn content_changes(&self) -> usize {
+        self.entries.iter().filter(|e| matches!(e, JournalEntry::Content { .. })).count()
+    }
+
+    pub fn renames(&self) -> usize {
+        self.entries.iter().filter(|e| matches!(e, JournalEntry::Rename { .. })).count()
+    }
+}
+
+/// Records changes while a refactor runs; safe to use from worker threads
+pub struct JournalWriter {
+    store: PathBuf,
+    blob_dir: PathBuf,
+    record: Mutex<JournalRecord>,
+    next_blob: AtomicUsize,
+}
+
+impl JournalWriter {
+    /// Start a new journal for a run over `root_dir`. Nothing is written until the first change.
+    pub fn begin(store: &Path, root_dir: &Path, pattern: &str, substitute: &str) -> Self {
+        let now = Utc::now();
+        let id = format!("{}-{}", now.format("%Y%m%d-%H%M%S-%3f"), std::process::id());
+
+        Self {
+            store: store.to_path_buf(),
+            blob_dir: store.join(&id),
+            record: Mutex::new(JournalRecord {
+                id,
+                root_dir: root_dir.to_path_buf(),
+                pattern: pattern.to_string(),
+                substitute: substitute.to_string(),
+                created_at: now.to_rfc3339(),
+                undone_at: None,
+                entries: Vec::new(),
+            }),
+            next_blob: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn id(&self) -> String {
+        self.record.lock().unwrap().id.clone()
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        let record = self.record.lock().unwrap();
+        path.strip_prefix(&record.root_dir).unwrap_or(path).to_path_buf()
+    }
+
+    /// Save the current content of `path` before it is rewritten; returns the blob name
+    pub fn snapshot_content(&self, path: &Path) -> Result<String> {
+        fs::create_dir_all(&self.blob_dir)
+            .with_context(|| format!("Failed to create refactor journal at {}", self.blob_dir.display()))?;
+
+        let blob = format!("{:06}", self.next_blob.fetch_add(1, Ordering::SeqCst));
+        fs::copy(path, self.blob_dir.join(&blob))
+            .with_context(|| format!("Failed to journal original content of {}", path.display()))?;
+        Ok(blob)
+    }
+
+    /// Record a completed content rewrite, or drop the snapshot if nothing changed
+    pub fn finish_content(&self, path: &Path, blob: String, modified: bool) {
+        if modified {
+            let entry = JournalEntry::Content { path: self.relative(path), original: blob, rewritten: hash_file(path).ok() };
+            self.record.lock().unwrap().entries.push(entry);
+        } else {
+            let _ = fs::remove_file(self.blob_dir.join(blob));
+        }
+    }
+
+    /// Record a rewritten symlink target
+    pub fn record_link_target(&self, link: &Path, from: &Path, to: &Path) {
+        let entry = JournalEntry::LinkTarget {
+            path: self.relative(link),
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        };
+        self.record.lock().unwrap().entries.push(entry);
+    }
+
+    /// Record a completed rename
+    pub fn record_rename(&self, from: &Path, to: &Path, item_type: &ItemType) {
+        let entry = JournalEntry::Rename {
+            from: self.relative(from),
+            to: self.relative(to),
+            directory: *item_type == ItemType::Directory,
+        };
+        self.record.lock().unwrap().entries.push(entry);
+    }
+
+    /// Write the journal; a run that changed nothing leaves no journal behind
+    pub fn finish(self) -> Result<Option<JournalRecord>> {
+        let record = self.record.into_inner().unwrap();
+        if record.entries.is_empty() {
+            let _ = fs::remove_dir_all(&self.blob_dir);
+            return Ok(None);
+        }
+
+        save(&self.store, &record)?;
+        prune(&self.store, MAX_JOURNALS)?;
+        Ok(Some(record))
+    }
+}
+
+fn record_path(store: &Path, id: &str) -> PathBuf {
+    store.join(format!("{}.json", id))
+}
+
+fn save(store: &Path, record: &JournalRecord) -> Result<()> {
+    fs::create_dir_all(store)
+        .with_context(|| format!("Failed to create refactor journal store {}", store.display()))?;
+    let path = record_path(store, &record.id);
+    let json = serde_json::to_string_pretty(record)?;
+    fs::write(&path, json).with_context(|| format!("Failed to write refactor journal {}", path.display()))
+}
+
+/// Remove all but the newest `keep` journals
+fn prune(store: &Path, keep: usize) -> Result<()> {
+    let records = list(store)?;
+    if records.len() <= keep {
+        return Ok(());
+    }
+
+    for record in &records[..records.len() - keep] {
+        let _ = fs::remove_file(record_path(store, &record.id));
+        let _ = fs::remove_dir_all(store.join(&record.id));
+    }
+    Ok(())
+}
+
+/// Load a journal by ID
+pub fn load(store: &Path, id: &str) -> Result<JournalRecord> {
+    let path = record_path(store, id);
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Refactor journal not found: {}", id))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse refactor journal {}", path.display()))
+}
+
+/// All journals in a store, oldest first
+pub fn list(store: &Path) -> Result<Vec<JournalRecord>> {
+    if !store.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut records = Vec::new();
+    for entry in fs::read_dir(store)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            let content = fs::read_to_string(&path)?;
+            if let Ok(record) = serde_json::from_str::<JournalRecord>(&content) {
+                records.push(record);
+            }
+        }
+    }
+
+    records.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(records)
+}
+
+/// Outcome of undoing a journal
+#[derive(Debug, Default)]
+pub struct UndoReport {
+    pub reverted_renames: usize,
+    pub restored_files: usize,
+    pub restored_links: usize,
+    pub errors: Vec<String>,
+}
+
+/// Where `path` is now, following the renames the run made after rewriting it
+fn renamed_path(record: &JournalRecord, path: &Path) -> PathBuf {
+    let mut current = path.to_path_buf();
+    for entry in &record.entries {
+        if let JournalEntry::Rename { from, to, .. } = entry {
+            if current == *from {
+                current = to.clone();
+            } else if let Ok(rest) = current.strip_prefix(from) {
+                current = to.join(rest);
+            }
+        }
+    }
+    current
+}
+
+/// Rewritten files whose content no longer matches what the run wrote
+pub fn changed_since(record: &JournalRecord) -> Vec<PathBuf> {
+    record.entries.iter().filter_map(|entry| match entry {
+        JournalEntry::Content { path, rewritten: Some(rewritten), .. } => {
+            let current = renamed_path(record, path);
+            let unchanged = hash_file(&record.root_dir.join(&current)).map_or(false, |hash| &hash == rewritten);
+            (!unchanged).then_some(current)
+        }
+        _ => None,
+    }).collect()
+}
+
+/// Reverse a refactor run: the given journal, or the most recent one not yet undone.
+/// Refuses to touch anything when a rewritten file changed after the run, unless `force` is set.
+pub fn undo(store: &Path, id: Option<&str>, force: bool) -> Result<(JournalRecord, UndoReport)> {
+    let mut record = match id {
+        Some(id) => load(store, id)?,
+        None => list(store)?
+            .into_iter()
+            .rev()
+            .find(|r| r.undone_at.is_none())
+            .ok_or_else(|| anyhow::anyhow!("No refactor journal to undo in {}", store.display()))?,
+    };
+
+    if let Some(ref undone_at) = record.undone_at {
+        anyhow::bail!("Refactor journal {} was already undone at {}", record.id, undone_at);
+    }
+
+    let changed = changed_since(&record);
+    if !changed.is_empty() && !force {
+        let paths: Vec<String> = changed.iter().map(|p| p.display().to_string()).collect();
+        anyhow::bail!(
+            "{} file(s) changed since refactor {}: {}; undo with --force to overwrite them",
+            changed.len(), record.id, paths.join(", ")
+        );
+    }
+
+    let root_dir = record.root_dir.clone();
+    let blob_dir = store.join(&record.id);
+    let mut report = UndoReport::default();
+
+    // Renames are reverted newest first so directories move back before the files inside them
+    for entry in record.entries.iter().rev() {
+        if let JournalEntry::Rename { from, to, .. } = entry {
+            let current = root_dir.join(to);
+            let original = root_dir.join(from);
+
+            if current.symlink_metadata().is_err() {
+                report.errors.push(format!("Cannot revert rename: {} no longer exists", to.display()));
+            } else if original.symlink_metadata().is_ok() {
+                report.errors.push(format!("Cannot revert rename: {} already exists", from.display()));
+            } else {
+                match fs::rename(&current, &original) {
+                    Ok(()) => report.reverted_renames += 1,
+                    Err(e) => report.errors.push(format!("Failed to move {} back to {}: {}", to.display(), from.display(), e)),
+                }
+            }
+        }
+    }
+
+    // Content and link targets were rewritten before renaming, so they are restored at the original paths
+    for entry in &record.entries {
+        match entry {
+            JournalEntry::Content { path, original, .. } => match fs::copy(blob_dir.join(original), root_dir.join(path)) {
+                Ok(_) => report.restored_files += 1,
+                Err(e) => report.errors.push(format!("Failed to restore content of {}: {}", path.display(), e)),
+            },
+            JournalEntry::LinkTarget { path, from, .. } => match symlinks::set_target(&root_dir.join(path), from) {
+                Ok(()) => report.restored_links += 1,
+                Err(e) => report.errors.push(format!("Failed to restore symlink {}: {:#}", path.display(), e)),
+            },
+            JournalEntry::Rename { .. } => {}
+        }
+    }
+
+    record.undone_at = Some(Utc::now().to_rfc3339());
+    save(store, &record)?;
+
+    Ok((record, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_undo_restores_renames_and_content() {
+        let store = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("old_dir")).unwrap();
+        fs::write(root.join("old_dir/old.txt"), "old content").unwrap();
+
+        let journal = JournalWriter::begin(store.path(), root, "old", "new");
+        let blob = journal.snapshot_content(&root.join("old_dir/old.txt")).unwrap();
+        fs::write(root.join("old_dir/old.txt"), "new content").unwrap();
+        journal.finish_content(&root.join("old_dir/old.txt"), blob, true);
+
+        // Files are renamed before their parent directories
+        fs::rename(root.join("old_dir/old.txt"), root.join("old_dir/new.txt")).unwrap();
+        journal.record_rename(&root.join("old_dir/old.txt"), &root.join("old_dir/new.txt"), &ItemType::File);
+        fs::rename(root.join("old_dir"), root.join("new_dir")).unwrap();
+        journal.record_rename(&root.join("old_dir"), &root.join("new_dir"), &ItemType::Directory);
+
+        let record = journal.finish().unwrap().unwrap();
+        assert_eq!(record.renames(), 2);
+        assert_eq!(record.content_changes(), 1);
+
+        let (undone, report) = undo(store.path(), None, false).unwrap();
+        assert_eq!(undone.id, record.id);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(fs::read_to_string(root.join("old_dir/old.txt")).unwrap(), "old content");
+        assert!(!root.join("new_dir").exists());
+
+        // A journal can only be undone once
+        assert!(undo(store.path(), Some(&record.id), false).is_err());
+        assert!(undo(store.path(), None, false).is_err());
+    }
+
+    #[test]
+    fn test_undo_refuses_files_changed_after_the_run() {
+        let store = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("old.txt"), "old content").unwrap();
+
+        let journal = JournalWriter::begin(store.path(), root, "old", "new");
+        let blob = journal.snapshot_content(&root.join("old.txt")).unwrap();
+        fs::write(root.join("old.txt"), "new content").unwrap();
+        journal.finish_content(&root.join("old.txt"), blob, true);
+        fs::rename(root.join("old.txt"), root.join("new.txt")).unwrap();
+        journal.record_rename(&root.join("old.txt"), &root.join("new.txt"), &ItemType::File);
+        let record = journal.finish().unwrap().unwrap();
+        assert!(changed_since(&record).is_empty());
+
+        // An edit made after the run is found at the renamed path and nothing is undone
+        fs::write(root.join("new.txt"), "new content, edited").unwrap();
+        let err = undo(store.path(), None, false).unwrap_err();
+        assert!(err.to_string().contains("changed since refactor") && err.to_string().contains(": new.txt;"), "{}", err);
+        assert!(root.join("new.txt").exists());
+        assert!(load(store.path(), &record.id).unwrap().undone_at.is_none());
+
+        let (_, report) = undo(store.path(), None, true).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(fs::read_to_string(root.join("old.txt")).unwrap(), "old content");
+    }
+
+    #[test]
+    fn test_empty_run_leaves_no_journal() {
+        let store = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let journal = JournalWriter::begin(store.path(), temp_dir.path(), "old", "new");
+        assert!(journal.finish().unwrap().is_none());
+        assert!(list(store.path()).unwrap().is_empty());
+    }
+}
@@ -4,6 +4,14 @@ pub mod rename_engine;
 pub mod collision_detector;
 pub mod binary_detector;
 pub mod progress;
+pub mod journal;
+pub mod path_filter;
+pub mod replacements;
+pub mod archives;
+pub mod symlinks;
+pub mod git;
+pub mod regions;
+pub mod preserve;
 
 pub use cli::{Args, Mode};
 pub use rename_engine::RenameEngine;
\ No newline at end of file
@@ -0,0 +1,271 @@
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+use super::cli::Mode;
+
+/// Per-directory ignore file read in addition to `.gitignore`
+pub const REFACIGNORE_FILE: &str = ".refacignore";
+
+/// Directories never traversed, even with `--include-hidden` or `--no-ignore`
+pub const ALWAYS_SKIPPED_DIRS: &[&str] = &[".git"];
+
+/// Build the directory walker: gitignore-aware (plus `.refacignore`) unless ignore files are disabled.
+/// Hidden-file handling is left to [`EntryFilter`] so `--include-hidden` keeps working.
+pub fn walk_builder(root_dir: &Path, follow_symlinks: bool, max_depth: Option<usize>, respect_ignore_files: bool) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root_dir);
+    builder
+        .standard_filters(respect_ignore_files)
+        .hidden(false)
+        .require_git(false)
+        .follow_links(follow_symlinks)
+        .max_depth(max_depth);
+
+    if respect_ignore_files {
+        builder.add_custom_ignore_filename(REFACIGNORE_FILE);
+    }
+
+    builder
+}
+
+/// Decides which entries the walker descends into (hidden files, `.git`, mode restrictions)
+#[derive(Debug, Clone)]
+pub struct EntryFilter {
+    root_dir: PathBuf,
+    include_hidden: bool,
+    mode: Mode,
+}
+
+impl EntryFilter {
+    pub fn new(root_dir: &Path, include_hidden: bool, include_patterns: &[String], mode: Mode) -> Self {
+        // Wildcard include patterns have always opted hidden files in
+        let include_hidden = include_hidden || include_patterns.iter().any(|p| p == ".*" || p.contains('*'));
+        Self { root_dir: root_dir.to_path_buf(), include_hidden, mode }
+    }
+
+    pub fn accepts(&self, path: &Path) -> bool {
+        // Don't skip the root directory itself, even if it's hidden
+        if path == self.root_dir {
+            return true;
+        }
+
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            if ALWAYS_SKIPPED_DIRS.contains(&name) && path.is_dir() {
+                return false;
+            }
+            if name.starts_with('.') && !self.include_hidden {
+                return false;
+            }
+        }
+
+        // Check file type restrictions
+        match self.mode {
+            Mode::FilesOnly => path.is_file(),
+            Mode::DirsOnly => path.is_dir(),
+            _ => true,
+        }
+    }
+}
+
+/// `--include` / `--exclude` matching. Patterns are gitignore-style globs matched against the
+/// path relative to the root (`*.log`, `target/`, `src/**/gen_*.rs`), or regexes on the file
+/// name when `--regex` is set.
+pub struct PathFilter {
+    root_dir: PathBuf,
+    include: Option<Gitignore>,
+    exclude: Option<Gitignore>,
+    include_regex: Vec<regex::Regex>,
+    exclude_regex: Vec<regex::Regex>,
+}
+
+impl PathFilter {
+    pub fn new(root_dir: &Path, include: &[String], exclude: &[String], ignore_case: bool, use_regex: bool) -> Result<Self> {
+        let mut filter = Self {
+            root_dir: root_dir.to_path_buf(),
+            include: None,
+            exclude: None,
+            include_regex: Vec::new(),
+            exclude_regex: Vec::new(),
+        };
+
+        if use_regex {
+            filter.include_regex = Self::compile_regexes(include, ignore_case)?;
+            filter.exclude_regex = Self::compile_regexes(exclude, ignore_case)?;
+        } else {
+            filter.include = Self::compile_globs(root_dir, include, ignore_case)?;
+            filter.exclude = Self::compile_globs(root_dir, exclude, ignore_case)?;
+        }
+
+        Ok(filter)
+    }
+
+    fn compile_globs(root_dir: &Path, patterns: &[String], ignore_case: bool) -> Result<Option<Gitignore>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut builder = GitignoreBuilder::new(root_dir);
+        builder.case_insensitive(ignore_case)?;
+        for pattern in patterns {
+            builder.add_line(None, pattern)
+                .with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+        }
+        Ok(Some(builder.build()?))
+    }
+
+    fn compile_regexes(patterns: &[String], ignore_case: bool) -> Result<Vec<regex::Regex>> {
+        patterns
+            .iter()
+            .map(|p| {
+                regex::RegexBuilder::new(p)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .with_context(|| format!("Invalid regex pattern: {}", p))
+            })
+            .collect()
+    }
+
+    fn glob_matches(&self, glob: &Gitignore, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root_dir).unwrap_or(path);
+        glob.matched_path_or_any_parents(relative, path.is_dir()).is_ignore()
+    }
+
+    fn regex_matches(regexes: &[regex::Regex], path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        regexes.iter().any(|r| r.is_match(name))
+    }
+
+    /// Whether a path passes the include and exclude patterns
+    pub fn matches(&self, path: &Path) -> bool {
+        // If there are include patterns, the path must match at least one
+        let included = match &self.include {
+            Some(glob) => self.glob_matches(glob, path),
+            None => self.include_regex.is_empty() || Self::regex_matches(&self.include_regex, path),
+        };
+        if !included {
+            return false;
+        }
+
+        // If there are exclude patterns, the path must not match any
+        let excluded = match &self.exclude {
+            Some(glob) => self.glob_matches(glob, path),
+            None => Self::regex_matches(&self.exclude_regex, path),
+        };
+        !excluded
+    }
+}
+
+/// `--content-ext` restriction on which files get content replacement. Extensions are
+/// matched case-insensitively against the end of the file name, so `tar.gz` works too.
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionFilter {
+    extensions: Vec<String>,
+}
+
+impl ExtensionFilter {
+    /// Filter allowing only `extensions` (a leading `.` is optional); an empty list allows every file
+    pub fn new(extensions: &[String]) -> Self {
+        let extensions = extensions
+            .iter()
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+        Self { extensions }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        !self.extensions.is_empty()
+    }
+
+    /// Whether content in `path` may be replaced
+    pub fn allows(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name.to_lowercase(),
+            None => return false,
+        };
+        self.extensions.iter().any(|ext| {
+            name.len() > ext.len() + 1 && name.ends_with(ext.as_str()) && name[..name.len() - ext.len()].ends_with('.')
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_glob_include_exclude() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::write(root.join("target/debug/app.rs"), "").unwrap();
+        fs::write(root.join("main.rs"), "").unwrap();
+        fs::write(root.join("notes.log"), "").unwrap();
+
+        let filter = PathFilter::new(root, &["*.rs".to_string()], &["target/".to_string()], false, false).unwrap();
+        assert!(filter.matches(&root.join("main.rs")));
+        assert!(!filter.matches(&root.join("notes.log")));
+        assert!(!filter.matches(&root.join("target/debug/app.rs")));
+    }
+
+    #[test]
+    fn test_walker_respects_ignore_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(root.join("generated")).unwrap();
+        fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+        fs::write(root.join(REFACIGNORE_FILE), "generated/\n").unwrap();
+        fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+        fs::write(root.join("generated/out.rs"), "").unwrap();
+        fs::write(root.join("lib.rs"), "").unwrap();
+
+        let walked = |respect: bool| -> Vec<PathBuf> {
+            walk_builder(root, false, None, respect)
+                .build()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_file())
+                .map(|e| e.path().strip_prefix(root).unwrap().to_path_buf())
+                .collect()
+        };
+
+        let respected = walked(true);
+        assert!(respected.contains(&PathBuf::from("lib.rs")));
+        assert!(!respected.iter().any(|p| p.starts_with("node_modules") || p.starts_with("generated")));
+
+        let everything = walked(false);
+        assert!(everything.contains(&PathBuf::from("generated/out.rs")));
+        assert!(everything.contains(&PathBuf::from("node_modules/pkg/index.js")));
+    }
+
+    #[test]
+    fn test_extension_filter() {
+        let filter = ExtensionFilter::new(&["rs".to_string(), ".TOML".to_string(), "tar.gz".to_string()]);
+        assert!(filter.allows(Path::new("src/main.rs")));
+        assert!(filter.allows(Path::new("Cargo.toml")));
+        assert!(filter.allows(Path::new("fixtures.tar.gz")));
+        assert!(!filter.allows(Path::new("README.md")));
+        assert!(!filter.allows(Path::new("rs")));
+        assert!(!filter.allows(Path::new("notrs")));
+
+        assert!(ExtensionFilter::new(&[]).allows(Path::new("README.md")));
+    }
+
+    #[test]
+    fn test_entry_filter_skips_git_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join(".config")).unwrap();
+
+        let filter = EntryFilter::new(root, true, &[], Mode::Full);
+        assert!(!filter.accepts(&root.join(".git")));
+        assert!(filter.accepts(&root.join(".config")));
+    }
+}
@@ -0,0 +1,159 @@
+//! File metadata kept across content rewrites.
+//!
+//! In-place writes keep a file's inode, but archive rewrites and streaming replacement
+//! write a new file and rename it over the original, which drops the original's mode,
+//! owner and extended attributes. A `Snapshot` is taken before each rewrite and applied
+//! afterwards, so every write path leaves the same metadata behind. Modification times
+//! are put back only when asked for (`--preserve-mtime`), since a changed file normally
+//! should look changed to build tools. Whatever cannot be restored, typically ownership
+//! when not running as root, is returned so the run can report it.
+
+use anyhow::{Context, Result};
+use std::fs::{self, FileTimes, Permissions};
+use std::path::Path;
+use std::time::SystemTime;
+
+#[cfg(unix)]
+use std::ffi::OsString;
+
+/// Metadata of a file before it is rewritten
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    permissions: Permissions,
+    modified: Option<SystemTime>,
+    accessed: Option<SystemTime>,
+    #[cfg(unix)]
+    uid: u32,
+    #[cfg(unix)]
+    gid: u32,
+    #[cfg(unix)]
+    xattrs: Vec<(OsString, Vec<u8>)>,
+}
+
+impl Snapshot {
+    /// Record `path`'s metadata. Extended attributes that cannot be read are left out.
+    pub fn capture(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Failed to read metadata of {}", path.display()))?;
+
+        #[cfg(unix)]
+        let (uid, gid, xattrs) = {
+            use std::os::unix::fs::MetadataExt;
+            let xattrs = match xattr::list(path) {
+                Ok(names) => names
+                    .filter_map(|name| match xattr::get(path, &name) {
+                        Ok(Some(value)) => Some((name, value)),
+                        _ => None,
+                    })
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            (metadata.uid(), metadata.gid(), xattrs)
+        };
+
+        Ok(Self {
+            permissions: metadata.permissions(),
+            modified: metadata.modified().ok(),
+            accessed: metadata.accessed().ok(),
+            #[cfg(unix)]
+            uid,
+            #[cfg(unix)]
+            gid,
+            #[cfg(unix)]
+            xattrs,
+        })
+    }
+
+    /// Apply the snapshot to the rewritten `path`, returning what could not be preserved.
+    /// Ownership goes first because changing it can clear setuid and setgid bits, and
+    /// permissions last so a read-only mode does not block setting the times.
+    pub fn restore(&self, path: &Path, preserve_mtime: bool) -> Vec<String> {
+        let mut lost = Vec::new();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let owner = fs::metadata(path).map(|m| (m.uid(), m.gid())).ok();
+            if owner != Some((self.uid, self.gid)) {
+                if let Err(e) = std::os::unix::fs::chown(path, Some(self.uid), Some(self.gid)) {
+                    lost.push(format!("ownership ({})", e));
+                }
+            }
+
+            for (name, value) in &self.xattrs {
+                if xattr::get(path, name).ok().flatten().as_deref() == Some(value.as_slice()) {
+                    continue;
+                }
+                if let Err(e) = xattr::set(path, name, value) {
+                    lost.push(format!("extended attribute {} ({})", name.to_string_lossy(), e));
+                }
+            }
+        }
+
+        if preserve_mtime {
+            if let Some(modified) = self.modified {
+                let mut times = FileTimes::new().set_modified(modified);
+                if let Some(accessed) = self.accessed {
+                    times = times.set_accessed(accessed);
+                }
+                if let Err(e) = fs::File::open(path).and_then(|file| file.set_times(times)) {
+                    lost.push(format!("modification time ({})", e));
+                }
+            }
+        }
+
+        if fs::metadata(path).map_or(true, |m| m.permissions() != self.permissions) {
+            if let Err(e) = fs::set_permissions(path, self.permissions.clone()) {
+                lost.push(format!("permissions ({})", e));
+            }
+        }
+
+        lost
+    }
+}
+
+/// Replace `path` with `temp`, carrying `path`'s metadata over to the new file
+pub fn replace_with(temp: &Path, path: &Path, preserve_mtime: bool) -> Result<Vec<String>> {
+    let snapshot = Snapshot::capture(path)?;
+    fs::rename(temp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(snapshot.restore(path, preserve_mtime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_replace_with_keeps_mode_and_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("script.sh");
+        let temp = temp_dir.path().join(".script.sh.tmp");
+        fs::write(&path, "echo old").unwrap();
+        fs::write(&temp, "echo new").unwrap();
+
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            permissions.set_mode(0o550);
+        }
+        let old = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        fs::File::open(&path).unwrap().set_modified(old).unwrap();
+        fs::set_permissions(&path, permissions).unwrap();
+        let before = fs::metadata(&path).unwrap().modified().unwrap();
+
+        let lost = replace_with(&temp, &path, true).unwrap();
+        assert!(lost.is_empty(), "{:?}", lost);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "echo new");
+        let after = fs::metadata(&path).unwrap();
+        assert!(after.permissions().readonly());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(after.permissions().mode() & 0o7777, 0o550);
+        }
+        assert_eq!(after.modified().unwrap(), before);
+    }
+}
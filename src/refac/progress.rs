@@ -1,15 +1,15 @@
 use indicatif::{ProgressBar, ProgressStyle, MultiProgress};
 use log;
-use std::cell::RefCell;
+use std::sync::Mutex;
 use std::time::Duration;
 use colored::*;
 
-/// Progress tracking for the rename operation
+/// Progress tracking for the rename operation (safe to update from worker threads)
 pub struct ProgressTracker {
     multi_progress: MultiProgress,
-    main_bar: RefCell<Option<ProgressBar>>,
-    content_bar: RefCell<Option<ProgressBar>>,
-    rename_bar: RefCell<Option<ProgressBar>>,
+    main_bar: Mutex<Option<ProgressBar>>,
+    content_bar: Mutex<Option<ProgressBar>>,
+    rename_bar: Mutex<Option<ProgressBar>>,
     enabled: bool,
     verbose: bool,
 }
@@ -18,9 +18,9 @@ impl ProgressTracker {
     pub fn new(enabled: bool, verbose: bool) -> Self {
         Self {
             multi_progress: MultiProgress::new(),
-            main_bar: RefCell::new(None),
-            content_bar: RefCell::new(None),
-            rename_bar: RefCell::new(None),
+            main_bar: Mutex::new(None),
+            content_bar: Mutex::new(None),
+            rename_bar: Mutex::new(None),
             enabled,
             verbose,
         }
@@ -41,7 +41,21 @@ impl ProgressTracker {
         );
         pb.set_message(message.to_string());
         pb.enable_steady_tick(Duration::from_millis(100));
-        *self.main_bar.borrow_mut() = Some(pb);
+        *self.main_bar.lock().unwrap() = Some(pb);
+    }
+
+    /// Switch the main bar from an open-ended spinner to a bar of `total` steps
+    pub fn set_main_total(&self, total: u64, message: &str) {
+        if let Some(pb) = self.main_bar.lock().unwrap().as_ref() {
+            pb.set_length(total);
+            pb.set_position(0);
+            pb.set_message(message.to_string());
+        }
+    }
+
+    /// Current position of the main bar
+    pub fn main_position(&self) -> u64 {
+        self.main_bar.lock().unwrap().as_ref().map(|pb| pb.position()).unwrap_or(0)
     }
 
     /// Initialize content replacement progress bar
@@ -58,7 +72,7 @@ impl ProgressTracker {
                 .progress_chars("#>-")
         );
         pb.set_message("Replacing content".to_string());
-        *self.content_bar.borrow_mut() = Some(pb);
+        *self.content_bar.lock().unwrap() = Some(pb);
     }
 
     /// Initialize rename progress bar
@@ -75,12 +89,12 @@ impl ProgressTracker {
                 .progress_chars("#>-")
         );
         pb.set_message("Renaming files/directories".to_string());
-        *self.rename_bar.borrow_mut() = Some(pb);
+        *self.rename_bar.lock().unwrap() = Some(pb);
     }
 
     /// Update main progress
     pub fn update_main(&self, message: &str) {
-        if let Some(pb) = self.main_bar.borrow().as_ref() {
+        if let Some(pb) = self.main_bar.lock().unwrap().as_ref() {
             pb.inc(1);
             if self.verbose {
                 pb.set_message(message.to_string());
@@ -90,7 +104,7 @@ impl ProgressTracker {
 
     /// Update content progress
     pub fn update_content(&self, file_path: &str) {
-        if let Some(pb) = self.content_bar.borrow().as_ref() {
+        if let Some(pb) = self.content_bar.lock().unwrap().as_ref() {
             pb.inc(1);
             if self.verbose {
                 pb.set_message(format!("Processing: {}", file_path));
@@ -100,7 +114,7 @@ impl ProgressTracker {
 
     /// Update rename progress
     pub fn update_rename(&self, item_path: &str) {
-        if let Some(pb) = self.rename_bar.borrow().as_ref() {
+        if let Some(pb) = self.rename_bar.lock().unwrap().as_ref() {
             pb.inc(1);
             if self.verbose {
                 pb.set_message(format!("Renaming: {}", item_path));
@@ -110,21 +124,21 @@ impl ProgressTracker {
 
     /// Finish content progress
     pub fn finish_content(&self, message: &str) {
-        if let Some(pb) = self.content_bar.borrow().as_ref() {
+        if let Some(pb) = self.content_bar.lock().unwrap().as_ref() {
             pb.finish_with_message(message.to_string());
         }
     }
 
     /// Finish rename progress
     pub fn finish_rename(&self, message: &str) {
-        if let Some(pb) = self.rename_bar.borrow().as_ref() {
+        if let Some(pb) = self.rename_bar.lock().unwrap().as_ref() {
             pb.finish_with_message(message.to_string());
         }
     }
 
     /// Finish main progress
     pub fn finish_main(&self, message: &str) {
-        if let Some(pb) = self.main_bar.borrow().as_ref() {
+        if let Some(pb) = self.main_bar.lock().unwrap().as_ref() {
             pb.finish_with_message(message.to_string());
         }
     }
@@ -184,13 +198,13 @@ impl ProgressTracker {
 
     /// Clear all progress bars
     pub fn clear(&self) {
-        if let Some(pb) = self.main_bar.borrow().as_ref() {
+        if let Some(pb) = self.main_bar.lock().unwrap().as_ref() {
             pb.finish_and_clear();
         }
-        if let Some(pb) = self.content_bar.borrow().as_ref() {
+        if let Some(pb) = self.content_bar.lock().unwrap().as_ref() {
             pb.finish_and_clear();
         }
-        if let Some(pb) = self.rename_bar.borrow().as_ref() {
+        if let Some(pb) = self.rename_bar.lock().unwrap().as_ref() {
             pb.finish_and_clear();
         }
     }
@@ -258,9 +272,9 @@ mod tests {
         let tracker = ProgressTracker::new(true, true);
         assert!(tracker.enabled);
         assert!(tracker.verbose);
-        assert!(tracker.main_bar.borrow().is_none());
-        assert!(tracker.content_bar.borrow().is_none());
-        assert!(tracker.rename_bar.borrow().is_none());
+        assert!(tracker.main_bar.lock().unwrap().is_none());
+        assert!(tracker.content_bar.lock().unwrap().is_none());
+        assert!(tracker.rename_bar.lock().unwrap().is_none());
     }
 
     #[test]
@@ -272,9 +286,24 @@ mod tests {
         tracker.init_content_progress(50);
         tracker.init_rename_progress(25);
         
-        assert!(tracker.main_bar.borrow().is_none());
-        assert!(tracker.content_bar.borrow().is_none());
-        assert!(tracker.rename_bar.borrow().is_none());
+        assert!(tracker.main_bar.lock().unwrap().is_none());
+        assert!(tracker.content_bar.lock().unwrap().is_none());
+        assert!(tracker.rename_bar.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_main_total_resets_position() {
+        let tracker = ProgressTracker::new(true, false);
+        tracker.init_main_progress(0, "Walking");
+        tracker.update_main("a");
+        tracker.update_main("b");
+        assert_eq!(tracker.main_position(), 2);
+
+        tracker.set_main_total(10, "Scanning");
+        assert_eq!(tracker.main_position(), 0);
+        assert_eq!(tracker.main_bar.lock().unwrap().as_ref().unwrap().length(), Some(10));
+        tracker.update_main("c");
+        assert_eq!(tracker.main_position(), 1);
     }
 
     #[test]
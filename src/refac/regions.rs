@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::ops::Range;
+use std::path::Path;
+
+use super::replacements::ReplacementSet;
+use crate::code_analysis::tokens::{self, TokenKind};
+use crate::code_analysis::SupportedLanguage;
+
+/// Which lexical regions content replacement leaves alone
+/// (`--skip-comments`, `--skip-strings`, `--skip-code`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionFilter {
+    pub skip_code: bool,
+    pub skip_comments: bool,
+    pub skip_strings: bool,
+}
+
+/// Replacements by the region they fall in, plus the matches left alone
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RegionCounts {
+    pub code: usize,
+    pub comments: usize,
+    pub strings: usize,
+    pub skipped: usize,
+}
+
+impl RegionCounts {
+    pub fn add(&mut self, other: &RegionCounts) {
+        self.code += other.code;
+        self.comments += other.comments;
+        self.strings += other.strings;
+        self.skipped += other.skipped;
+    }
+
+    /// Replacements made (or planned), excluding skipped matches
+    pub fn replaced(&self) -> usize {
+        self.code + self.comments + self.strings
+    }
+}
+
+impl RegionFilter {
+    pub fn is_active(&self) -> bool {
+        self.skip_code || self.skip_comments || self.skip_strings
+    }
+
+    fn skips(&self, kind: TokenKind) -> bool {
+        match kind {
+            TokenKind::Code => self.skip_code,
+            TokenKind::Comment => self.skip_comments,
+            TokenKind::String => self.skip_strings,
+        }
+    }
+
+    /// The matches in `content` to replace, with counts by region. A match belongs to the
+    /// region it starts in. Files in languages code analysis does not know are all code.
+    pub fn select(&self, path: &Path, content: &str, replacements: &ReplacementSet) -> (Vec<Range<usize>>, RegionCounts) {
+        let tokens = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(SupportedLanguage::from_extension)
+            .map(|language| tokens::tokenize(content, language))
+            .unwrap_or_default();
+
+        let mut counts = RegionCounts::default();
+        let mut selected = Vec::new();
+        for range in replacements.find_matches(content) {
+            let kind = tokens::kind_at(&tokens, range.start);
+            if self.skips(kind) {
+                counts.skipped += 1;
+                continue;
+            }
+            match kind {
+                TokenKind::Code => counts.code += 1,
+                TokenKind::Comment => counts.comments += 1,
+                TokenKind::String => counts.strings += 1,
+            }
+            selected.push(range);
+        }
+        (selected, counts)
+    }
+
+    /// `content` with the selected matches replaced, or `None` when none are selected
+    pub fn replace(&self, path: &Path, content: &str, replacements: &ReplacementSet) -> (Option<String>, RegionCounts) {
+        let (selected, counts) = self.select(path, content, replacements);
+        if selected.is_empty() {
+            return (None, counts);
+        }
+        (Some(replacements.replace_matches(content, &selected)), counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "// old helper\nfn old() -> &'static str { \"old\" }\n";
+
+    #[test]
+    fn test_skip_comments_and_strings() {
+        let set = ReplacementSet::literal("old", "new", false).unwrap();
+        let filter = RegionFilter { skip_comments: true, skip_strings: true, ..Default::default() };
+
+        let (replaced, counts) = filter.replace(Path::new("lib.rs"), SOURCE, &set);
+        assert_eq!(replaced.unwrap(), "// old helper\nfn new() -> &'static str { \"old\" }\n");
+        assert_eq!(counts, RegionCounts { code: 1, comments: 0, strings: 0, skipped: 2 });
+    }
+
+    #[test]
+    fn test_skip_code_touches_only_comments_and_strings() {
+        let set = ReplacementSet::literal("old", "new", false).unwrap();
+        let filter = RegionFilter { skip_code: true, ..Default::default() };
+
+        let (replaced, counts) = filter.replace(Path::new("lib.rs"), SOURCE, &set);
+        assert_eq!(replaced.unwrap(), "// new helper\nfn old() -> &'static str { \"new\" }\n");
+        assert_eq!(counts, RegionCounts { code: 0, comments: 1, strings: 1, skipped: 1 });
+    }
+
+    #[test]
+    fn test_unknown_languages_are_code() {
+        let set = ReplacementSet::literal("old", "new", false).unwrap();
+        let filter = RegionFilter { skip_comments: true, ..Default::default() };
+        let (replaced, counts) = filter.replace(Path::new("notes.txt"), "// old", &set);
+        assert_eq!(replaced.unwrap(), "// new");
+        assert_eq!(counts.code, 1);
+
+        let code_only = RegionFilter { skip_code: true, ..Default::default() };
+        assert_eq!(code_only.replace(Path::new("notes.txt"), "// old", &set).0, None);
+    }
+}
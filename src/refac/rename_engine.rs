@@ -1,17 +1,26 @@
 use anyhow::{Context, Result};
 use rayon::prelude::*;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use walkdir::{DirEntry, WalkDir};
 
 use crate::{
     ItemType, RenameConfig, RenameItem, RenameStats, utils,
 };
 use super::{
-    cli::{Args, Mode, OutputFormat},
-    collision_detector::{CollisionDetector, CollisionType},
-    file_ops::FileOperations,
+    archives::{self, ArchiveChanges, ArchiveKind},
+    cli::{ArchiveFormat, Args, CollisionStrategy, Mode, OutputFormat},
+    collision_detector::{self, CollisionDetector, CollisionType},
+    file_ops::{FileOperations, UnknownEncoding},
+    git::GitIndex,
+    journal::{self, JournalWriter},
+    path_filter::{self, EntryFilter, ExtensionFilter, PathFilter},
+    preserve,
+    regions::{RegionCounts, RegionFilter},
+    replacements::ReplacementSet,
     progress::{ProgressTracker, SimpleOutput},
+    symlinks::{self, LinkRetarget},
 };
 
 /// Detailed information about changes to a specific file/directory
@@ -20,6 +29,7 @@ pub struct FileChangeReport {
     pub path: PathBuf,
     pub content_changes: Option<usize>, // Number of content occurrences to replace
     pub rename_target: Option<PathBuf>,  // New path if being renamed
+    pub link_target: Option<PathBuf>,    // New symlink target if being repointed
     pub item_type: ItemType,
 }
 
@@ -30,6 +40,36 @@ pub struct DetailedChangeReport {
     pub total_stats: RenameStats,
 }
 
+/// One planned change, as printed by `--dry-run --format json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedChange {
+    pub old_path: PathBuf,
+    /// Same as `old_path` when the item is not renamed
+    pub new_path: PathBuf,
+    #[serde(rename = "type")]
+    pub item_type: &'static str,
+    /// Occurrences replaced in the content (0 when only renamed)
+    pub content_matches: usize,
+    /// New target of a symlink whose target is rewritten
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_link_target: Option<PathBuf>,
+}
+
+impl From<&FileChangeReport> for PlannedChange {
+    fn from(change: &FileChangeReport) -> Self {
+        Self {
+            old_path: change.path.clone(),
+            new_path: change.rename_target.clone().unwrap_or_else(|| change.path.clone()),
+            item_type: match change.item_type {
+                ItemType::File => "file",
+                ItemType::Directory => "directory",
+            },
+            content_matches: change.content_changes.unwrap_or(0),
+            new_link_target: change.link_target.clone(),
+        }
+    }
+}
+
 /// Structured validation error with location and context information
 #[derive(Debug, Clone)]
 pub struct ValidationError {
@@ -63,12 +103,35 @@ pub struct RenameEngine {
     thread_count: usize,
     output_format: OutputFormat,
     max_depth: Option<usize>,
-    include_patterns: Vec<String>,
-    exclude_patterns: Vec<String>,
-    ignore_case: bool,
-    use_regex: bool,
+    path_filter: PathFilter,
+    content_filter: ExtensionFilter,
+    replacements: ReplacementSet,
     include_hidden: bool,
     binary_names: bool,
+    archive_formats: Vec<ArchiveFormat>,
+    archive_reports: Mutex<Vec<(PathBuf, ArchiveChanges)>>,
+    journal_store: PathBuf,
+    dry_run: bool,
+    rewrite_link_targets: bool,
+    link_retargets: Mutex<Vec<LinkRetarget>>,
+    warnings: Mutex<Vec<String>>,
+    /// Text files whose content was skipped because their encoding is unknown
+    skipped_encodings: Mutex<Vec<String>>,
+    /// Entries examined during discovery
+    entries_scanned: AtomicUsize,
+    /// Content files processed during execution, successfully or not
+    files_processed: AtomicUsize,
+    /// Tracked files when renames and content edits go through git (`--git`)
+    git_index: Option<GitIndex>,
+    on_collision: CollisionStrategy,
+    /// Comment, string or code regions left alone (`--skip-comments` and friends)
+    region_filter: RegionFilter,
+    /// Replacements made by region while the filter is active
+    region_counts: Mutex<RegionCounts>,
+    /// Restore each rewritten file's modification time (`--preserve-mtime`)
+    preserve_mtime: bool,
+    /// Rewritten files whose metadata could not be fully restored
+    metadata_issues: Mutex<Vec<String>>,
 }
 
 impl RenameEngine {
@@ -77,11 +140,32 @@ impl RenameEngine {
         args.validate().map_err(|e| anyhow::anyhow!(e))?;
 
         // Create configuration
-        let config = RenameConfig::new(&args.root_dir, args.pattern.clone(), args.substitute.clone())?
+        let pairs = args.replacement_pairs()?;
+        let (pattern, substitute) = pairs[0].clone();
+        let config = RenameConfig::new(&args.root_dir, pattern, substitute)?
+            .with_pairs(pairs)?
             .with_assume_yes(args.assume_yes)
             .with_verbose(args.verbose)
             .with_follow_symlinks(args.follow_symlinks)
-            .with_backup(args.backup);
+            .with_backup(args.backup)
+            .with_include_patterns(args.include_patterns.clone())
+            .with_exclude_patterns(args.exclude_patterns.clone())
+            .with_respect_ignore_files(!args.no_ignore)
+            .with_match_case_styles(args.match_case_styles)
+            .with_content_extensions(args.content_extensions.clone())
+            .with_replace_content(!args.no_content);
+
+        let replacements = ReplacementSet::from_pairs(&config.pairs, config.match_case_styles, args.ignore_case)?;
+
+        let content_filter = ExtensionFilter::new(&config.content_extensions);
+
+        let path_filter = PathFilter::new(
+            &config.root_dir,
+            &config.include_patterns,
+            &config.exclude_patterns,
+            args.ignore_case,
+            args.use_regex,
+        )?;
 
         // Setup progress tracking
         let show_progress = match args.progress {
@@ -96,6 +180,9 @@ impl RenameEngine {
             (None, Some(SimpleOutput::new(args.verbose)))
         };
 
+        // Keyed by the same root the walker uses, so walked paths match tracked ones
+        let git_index = if args.git { Some(GitIndex::open(&config.root_dir)?) } else { None };
+
         Ok(Self {
             config,
             mode: args.get_mode(),
@@ -105,12 +192,31 @@ impl RenameEngine {
             thread_count: args.get_thread_count(),
             output_format: args.format,
             max_depth: if args.max_depth > 0 { Some(args.max_depth) } else { None },
-            include_patterns: args.include_patterns,
-            exclude_patterns: args.exclude_patterns,
-            ignore_case: args.ignore_case,
-            use_regex: args.use_regex,
+            path_filter,
+            content_filter,
+            replacements,
             include_hidden: args.include_hidden,
             binary_names: args.binary_names,
+            archive_formats: args.into_archives,
+            archive_reports: Mutex::new(Vec::new()),
+            journal_store: journal::default_store(),
+            dry_run: args.dry_run,
+            rewrite_link_targets: args.rewrite_link_targets,
+            link_retargets: Mutex::new(Vec::new()),
+            warnings: Mutex::new(Vec::new()),
+            skipped_encodings: Mutex::new(Vec::new()),
+            entries_scanned: AtomicUsize::new(0),
+            files_processed: AtomicUsize::new(0),
+            git_index,
+            on_collision: args.on_collision,
+            region_filter: RegionFilter {
+                skip_code: args.skip_code,
+                skip_comments: args.skip_comments,
+                skip_strings: args.skip_strings,
+            },
+            region_counts: Mutex::new(RegionCounts::default()),
+            preserve_mtime: args.preserve_mtime,
+            metadata_issues: Mutex::new(Vec::new()),
         })
     }
 
@@ -124,16 +230,20 @@ impl RenameEngine {
 
         // Phase 2: Collision Detection
         self.print_info("Phase 2: Checking for naming collisions...")?;
-        self.check_collisions(&rename_items)?;
+        let rename_items = self.check_collisions(rename_items)?;
 
         // Phase 3: Mandatory Validation (Dry-Run)
         self.print_info("Phase 3: Validating all operations...")?;
         self.validate_all_operations(&content_files, &rename_items)?;
 
+        if self.dry_run {
+            return self.show_plan(&content_files, &rename_items);
+        }
+
         // Phase 4: Summary and Confirmation
         let stats = self.show_summary(&content_files, &rename_items)?;
         if stats.total_changes() == 0 {
-            self.print_success("No changes needed.")?;
+            self.print_success(&crate::t!("refac.no_changes"))?;
             return Ok(());
         }
 
@@ -143,12 +253,20 @@ impl RenameEngine {
         }
 
         if !self.confirm_changes()? {
-            self.print_info("Operation cancelled by user.")?;
+            self.print_info(&crate::t!("refac.cancelled"))?;
             return Ok(());
         }
 
         // Phase 5: Execute Changes
         self.execute_changes(&content_files, &rename_items)?;
+        let mut stats = stats;
+        stats.files_processed = self.files_processed.load(Ordering::Relaxed);
+        if self.region_filter.is_active() {
+            stats.replacements_by_region = Some(*self.region_counts.lock().unwrap());
+        }
+        let mut metadata_issues = self.metadata_issues.lock().unwrap().clone();
+        metadata_issues.sort();
+        stats.metadata_not_preserved = metadata_issues;
 
         // Phase 5: Final Report
         self.show_final_report(&stats)?;
@@ -156,25 +274,48 @@ impl RenameEngine {
         Ok(())
     }
 
+    /// Build the worker pool used for discovery and content replacement
+    fn build_thread_pool(&self) -> Result<rayon::ThreadPool> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.thread_count.max(1))
+            .build()
+            .with_context(|| format!("Failed to create thread pool with {} threads", self.thread_count))
+    }
+
     /// Discover files for content replacement and items for renaming
     fn discover_items(&self) -> Result<(Vec<PathBuf>, Vec<RenameItem>)> {
-        let mut content_files = Vec::new();
-        let mut rename_items = Vec::new();
-
         // Setup progress
         if let Some(progress) = &self.progress {
-            progress.init_main_progress(0, "Scanning files and directories...");
+            progress.init_main_progress(0, "Walking directory tree...");
         }
 
-        // Walk the directory tree
-        let walker = WalkDir::new(&self.config.root_dir)
-            .follow_links(self.config.follow_symlinks)
-            .max_depth(self.max_depth.unwrap_or(usize::MAX))
-            .into_iter()
-            .filter_entry(|e| self.should_process_entry(e));
-
+        // Walk the directory tree (cheap, serial) and collect candidate paths.
+        // Ignore files (.gitignore, .refacignore) prune whole subtrees such as target/.
+        let entry_filter = self.entry_filter();
+        let walker = path_filter::walk_builder(
+            &self.config.root_dir,
+            self.config.follow_symlinks,
+            self.max_depth,
+            self.config.respect_ignore_files,
+        )
+        .filter_entry(move |e| entry_filter.accepts(e.path()))
+        .build();
+
+        let mut paths = Vec::new();
         for entry in walker {
-            let entry = entry.with_context(|| "Failed to read directory entry")?;
+            let entry = match entry {
+                Ok(entry) => entry,
+                // A followed link pointing back at an ancestor is skipped, not fatal
+                Err(err) => match symlinks::walk_loop(&err) {
+                    Some((ancestor, child)) => {
+                        self.warnings.lock().unwrap().push(format!(
+                            "Skipped symlink cycle: {} leads back to {}", child.display(), ancestor.display()
+                        ));
+                        continue;
+                    }
+                    None => return Err(err).with_context(|| "Failed to read directory entry"),
+                },
+            };
             let path = entry.path();
 
             // Skip the root directory itself
@@ -183,35 +324,48 @@ impl RenameEngine {
             }
 
             // Apply include/exclude patterns
-            if !self.matches_patterns(path)? {
+            if !self.path_filter.matches(path) {
                 continue;
             }
 
-            // Check for content replacement in files
-            if self.should_process_content() && 
-               self.should_process_files() && 
-               path.is_file() {
-                if self.file_needs_content_replacement(path)? {
-                    content_files.push(path.to_path_buf());
-                }
-            }
+            paths.push(entry.into_path());
+        }
 
-            // Check for renaming
-            if self.should_process_names() {
-                if let Some(rename_item) = self.create_rename_item(path)? {
-                    rename_items.push(rename_item);
-                }
-            }
+        // Following links can reach the same entry twice; rename it only once
+        if self.config.follow_symlinks {
+            paths = symlinks::dedupe_entries(&self.config.root_dir, paths);
+        }
 
-            // Update progress
-            if let Some(progress) = &self.progress {
-                progress.update_main(&format!("Scanned: {}", path.display()));
-            }
+        // Scan contents and names (expensive) on the worker pool
+        if let Some(progress) = &self.progress {
+            progress.set_main_total(paths.len() as u64, "Scanning files and directories...");
         }
+        let pool = self.build_thread_pool()?;
+        let scanned: Vec<(Option<PathBuf>, Option<RenameItem>)> = pool.install(|| {
+            paths
+                .par_iter()
+                .map(|path| self.scan_path(path))
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut content_files = Vec::new();
+        let mut rename_items = Vec::new();
+        for (content_file, rename_item) in scanned {
+            content_files.extend(content_file);
+            rename_items.extend(rename_item);
+        }
+
+        // Parallel scanning must not change the plan: keep content files in path order
+        if self.config.follow_symlinks {
+            content_files = symlinks::dedupe_targets(content_files);
+        }
+        content_files.sort();
+        self.link_retargets.lock().unwrap().sort_by(|a, b| a.link.cmp(&b.link));
 
         // Sort rename items to prevent race conditions:
         // 1. Files first (deepest first), then directories (deepest first)
         // 2. This ensures files are renamed before their containing directories
+        // 3. Ties are broken by path so the order is identical for any thread count
         rename_items.sort_by(|a, b| {
             match (&a.item_type, &b.item_type) {
                 // Files come before directories to prevent path invalidation
@@ -222,131 +376,99 @@ impl RenameEngine {
                 // Among directories: process deepest first (children before parents)
                 (ItemType::Directory, ItemType::Directory) => b.depth.cmp(&a.depth),
             }
+            .then_with(|| a.original_path.cmp(&b.original_path))
         });
 
         if let Some(progress) = &self.progress {
-            progress.finish_main("Discovery complete");
+            progress.finish_main(&format!(
+                "Discovery complete ({} entries, {} content file(s), {} rename(s))",
+                self.entries_scanned.load(Ordering::Relaxed), content_files.len(), rename_items.len()
+            ));
         }
 
         Ok((content_files, rename_items))
     }
 
-    /// Check if an entry should be processed
-    fn should_process_entry(&self, entry: &DirEntry) -> bool {
-        let path = entry.path();
-        
-        // Don't skip the root directory itself, even if it's hidden
-        if path == self.config.root_dir {
-            return true;
-        }
-        
-        // Skip hidden files unless explicitly included
-        if let Some(name) = path.file_name() {
-            if let Some(name_str) = name.to_str() {
-                if name_str.starts_with('.') {
-                    // Check if include_hidden flag is set
-                    if self.include_hidden {
-                        // Include hidden files when flag is set
-                    } else {
-                        // Original logic: check include patterns
-                        let should_include = self.include_patterns.iter().any(|p| p == ".*" || p.contains("*"));
-                        if !should_include {
-                            return false;
-                        }
-                    }
-                }
-            }
+    /// Scan a single discovered path for content matches and a pending rename
+    fn scan_path(&self, path: &Path) -> Result<(Option<PathBuf>, Option<RenameItem>)> {
+        let mut content_file = None;
+        let mut rename_item = None;
+        let is_link = symlinks::is_symlink(path);
+
+        // Check for content replacement in files. Content behind a symlink is only
+        // touched when links are followed, so targets outside the tree stay untouched.
+        // Archives apply --content-ext to their members rather than to themselves.
+        if self.should_process_content() &&
+           self.should_process_files() &&
+           path.is_file() &&
+           (self.content_filter.allows(path) || self.archive_kind(path).is_some()) &&
+           (!is_link || self.config.follow_symlinks) &&
+           self.file_needs_content_replacement(path)? {
+            content_file = Some(path.to_path_buf());
         }
 
-        // Check file type restrictions
-        match self.mode {
-            Mode::FilesOnly => path.is_file(),
-            Mode::DirsOnly => path.is_dir(),
-            _ => true,
+        if is_link && self.rewrite_link_targets {
+            if let Some(retarget) = symlinks::plan_retarget(path, &self.replacements)? {
+                self.link_retargets.lock().unwrap().push(retarget);
+            }
         }
-    }
 
-    /// Check if a path matches include/exclude patterns
-    fn matches_patterns(&self, path: &Path) -> Result<bool> {
-        // If there are include patterns, the file must match at least one
-        if !self.include_patterns.is_empty() {
-            let matches = self.include_patterns.iter().any(|pattern| {
-                self.path_matches_pattern(path, pattern)
-            });
-            if !matches {
-                return Ok(false);
-            }
+        // Check for renaming
+        if self.should_process_names() {
+            rename_item = self.create_rename_item(path)?;
         }
 
-        // If there are exclude patterns, the file must not match any
-        if !self.exclude_patterns.is_empty() {
-            let excluded = self.exclude_patterns.iter().any(|pattern| {
-                self.path_matches_pattern(path, pattern)
-            });
-            if excluded {
-                return Ok(false);
-            }
+        // Update progress
+        self.entries_scanned.fetch_add(1, Ordering::Relaxed);
+        if let Some(progress) = &self.progress {
+            progress.update_main(&format!("Scanned: {}", path.display()));
         }
 
-        Ok(true)
+        Ok((content_file, rename_item))
     }
 
-    /// Check if a path matches a glob pattern
-    fn path_matches_pattern(&self, path: &Path, pattern: &str) -> bool {
-        // Simple glob matching - could be enhanced with a proper glob library
-        let file_name = path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
-        let pattern_str = if self.ignore_case {
-            pattern.to_lowercase()
-        } else {
-            pattern.to_string()
-        };
-        let compare_str = if self.ignore_case {
-            file_name.to_lowercase()
-        } else {
-            file_name.to_string()
-        };
-
-
-        if self.use_regex {
-            // Use regex matching
-            if let Ok(regex) = regex::Regex::new(&pattern_str) {
-                return regex.is_match(&compare_str);
-            }
-        }
-
-        // Special case for ".*" pattern to match hidden files
-        if pattern_str == ".*" {
-            return compare_str.starts_with('.');
-        }
+    /// Filter deciding which entries the walker descends into
+    fn entry_filter(&self) -> EntryFilter {
+        EntryFilter::new(&self.config.root_dir, self.include_hidden, &self.config.include_patterns, self.mode.clone())
+    }
 
-        // Simple glob-style matching
-        if pattern_str.contains('*') {
-            let parts: Vec<&str> = pattern_str.split('*').collect();
-            if parts.len() == 2 {
-                return compare_str.starts_with(parts[0]) && compare_str.ends_with(parts[1]);
-            }
+    /// Archive kind of a path when `--into-archives` covers its format
+    fn archive_kind(&self, path: &Path) -> Option<ArchiveKind> {
+        if self.archive_formats.is_empty() {
+            return None;
         }
-
-        compare_str.contains(&pattern_str)
+        ArchiveKind::detect(path, &self.archive_formats)
     }
 
     /// Check if a file needs content replacement
     fn file_needs_content_replacement(&self, path: &Path) -> Result<bool> {
+        if let Some(kind) = self.archive_kind(path) {
+            // Unreadable archives are left alone, like other binary files
+            return Ok(archives::scan(path, kind, &self.replacements, &self.content_filter).map(|c| !c.is_empty()).unwrap_or(false));
+        }
+
         if !self.file_ops.is_text_file(path)? {
             return Ok(false);
         }
 
-        let search_string = if self.ignore_case {
-            // For case-insensitive search, we'd need to read the file content
-            // This is simplified - a full implementation would use regex
-            &self.config.pattern.to_lowercase()
+        let found = if self.region_filter.is_active() {
+            self.file_ops.read_text(path)
+                .map(|content| !self.region_filter.select(path, &content, &self.replacements).0.is_empty())
         } else {
-            &self.config.pattern
+            self.file_ops.file_contains_match(path, &self.replacements)
         };
-
-        self.file_ops.file_contains_string(path, search_string)
+        match found {
+            Ok(found) => Ok(found),
+            Err(e) => match e.downcast_ref::<UnknownEncoding>() {
+                // Guessing would risk mangling the file, so leave its content alone and report it
+                Some(unknown) => {
+                    let relative = path.strip_prefix(&self.config.root_dir).unwrap_or(path);
+                    self.skipped_encodings.lock().unwrap().push(format!("{} ({})", relative.display(), unknown.reason));
+                    Ok(false)
+                }
+                None => Err(e),
+            },
+        }
     }
 
     /// Create a rename item if the path needs renaming
@@ -355,19 +477,15 @@ impl RenameEngine {
             .and_then(|n| n.to_str())
             .ok_or_else(|| anyhow::anyhow!("Invalid file name: {}", path.display()))?;
 
-        let contains_pattern = if self.ignore_case {
-            file_name.to_lowercase().contains(&self.config.pattern.to_lowercase())
-        } else {
-            file_name.contains(&self.config.pattern)
-        };
-
-
-        if !contains_pattern {
+        if !self.replacements.is_match(file_name) {
             return Ok(None);
         }
 
+        // A symlink is renamed as a link, never through to its target
+        let is_link = symlinks::is_symlink(path);
+
         // Check binary file handling for files
-        if path.is_file() {
+        if path.is_file() && !is_link {
             match self.file_ops.is_text_file(path) {
                 Ok(false) => {
                     // Binary file - only process if binary_names flag is set
@@ -388,7 +506,7 @@ impl RenameEngine {
         }
 
         // Apply type restrictions
-        let item_type = if path.is_file() {
+        let item_type = if is_link || path.is_file() {
             if !self.should_process_files() {
                 return Ok(None);
             }
@@ -401,15 +519,7 @@ impl RenameEngine {
         };
 
         // Calculate new name
-        let new_name = if self.ignore_case {
-            // Case-insensitive replacement
-            file_name.to_lowercase().replace(
-                &self.config.pattern.to_lowercase(),
-                &self.config.substitute
-            )
-        } else {
-            utils::replace_all(file_name, &self.config.pattern, &self.config.substitute)
-        };
+        let new_name = self.replacements.replace(file_name);
 
         let new_path = path.with_file_name(new_name);
         let depth = utils::calculate_depth(path, &self.config.root_dir);
@@ -422,10 +532,11 @@ impl RenameEngine {
         }))
     }
 
-    /// Check for collisions in the rename operations
-    fn check_collisions(&self, rename_items: &[RenameItem]) -> Result<()> {
+    /// Check for collisions in the rename operations and apply the collision strategy.
+    /// Conflicting pairs are reported before anything on disk changes.
+    fn check_collisions(&self, rename_items: Vec<RenameItem>) -> Result<Vec<RenameItem>> {
         if rename_items.is_empty() {
-            return Ok(());
+            return Ok(rename_items);
         }
 
         let mut detector = CollisionDetector::new();
@@ -434,36 +545,47 @@ impl RenameEngine {
         detector.scan_existing_paths(&self.config.root_dir)?;
         
         // Add rename operations
-        detector.add_renames(rename_items);
-        
-        // Detect collisions
-        let collisions = detector.detect_collisions()?;
+        detector.add_renames(&rename_items);
         
-        if !collisions.is_empty() {
+        // Detect collisions, ignoring no-op renames
+        let collisions: Vec<_> = detector.detect_collisions()?
+            .into_iter()
+            .filter(|c| c.collision_type != CollisionType::SourceEqualsTarget)
+            .collect();
+
+        if collisions.is_empty() {
+            return Ok(rename_items);
+        }
+
+        if self.on_collision == CollisionStrategy::Abort {
             self.print_error("Naming collisions detected!")?;
-            
             for collision in &collisions {
-                match collision.collision_type {
-                    CollisionType::SourceEqualsTarget => {
-                        // Skip no-op renames
-                        continue;
-                    }
-                    _ => {
-                        self.print_error(&collision.description)?;
-                    }
-                }
-            }
-            
-            let serious_collisions: Vec<_> = collisions.iter()
-                .filter(|c| c.collision_type != CollisionType::SourceEqualsTarget)
-                .collect();
-                
-            if !serious_collisions.is_empty() {
-                anyhow::bail!("Cannot proceed due to {} naming collision(s)", serious_collisions.len());
+                self.print_error(&collision.description)?;
             }
+            anyhow::bail!(
+                "Cannot proceed due to {} naming collision(s) (use --on-collision skip or suffix to resolve them)",
+                collisions.len()
+            );
         }
 
-        Ok(())
+        self.print_warning(&format!("{} naming collision(s) detected", collisions.len()))?;
+        let (rename_items, resolutions) = collision_detector::resolve_collisions(rename_items, &collisions, self.on_collision);
+        for resolution in resolutions {
+            let message = match &resolution.resolved {
+                Some(resolved) => format!(
+                    "Collision: renaming {} to {} instead of {}",
+                    resolution.source.display(), resolved.display(), resolution.target.display()
+                ),
+                None => format!(
+                    "Collision: skipped renaming {} to {}",
+                    resolution.source.display(), resolution.target.display()
+                ),
+            };
+            self.print_warning(&message)?;
+            self.warnings.lock().unwrap().push(message);
+        }
+
+        Ok(rename_items)
     }
 
     /// Generate detailed report of all changes organized by file/directory
@@ -472,19 +594,29 @@ impl RenameEngine {
         
         let mut file_changes_map: HashMap<PathBuf, FileChangeReport> = HashMap::new();
         let mut stats = RenameStats::default();
+        let mut region_counts = RegionCounts::default();
         
         // Process content changes
         for file_path in content_files {
             // Count occurrences of old string in this file
-            let content_count = match std::fs::read_to_string(file_path) {
-                Ok(content) => content.matches(&self.config.pattern).count(),
-                Err(_) => 0, // Already validated during validation phase
+            let content_count = match self.archive_kind(file_path) {
+                Some(kind) => archives::scan(file_path, kind, &self.replacements, &self.content_filter).map(|c| c.total()).unwrap_or(0),
+                None => match self.file_ops.read_text(file_path) {
+                    Ok(content) if self.region_filter.is_active() => {
+                        let (_, counts) = self.region_filter.select(file_path, &content, &self.replacements);
+                        region_counts.add(&counts);
+                        counts.replaced()
+                    }
+                    Ok(content) => self.replacements.count(&content),
+                    Err(_) => 0, // Already validated during validation phase
+                },
             };
             
             file_changes_map.insert(file_path.clone(), FileChangeReport {
                 path: file_path.clone(),
                 content_changes: Some(content_count),
                 rename_target: None,
+                link_target: None,
                 item_type: ItemType::File,
             });
             stats.files_with_content_changes += 1;
@@ -497,6 +629,7 @@ impl RenameEngine {
                     path: item.original_path.clone(),
                     content_changes: None,
                     rename_target: None,
+                    link_target: None,
                     item_type: item.item_type.clone(),
                 }
             });
@@ -509,6 +642,27 @@ impl RenameEngine {
                 ItemType::Directory => stats.directories_renamed += 1,
             }
         }
+
+        // Process symlink target rewrites
+        for retarget in self.link_retargets.lock().unwrap().iter() {
+            let entry = file_changes_map.entry(retarget.link.clone()).or_insert_with(|| FileChangeReport {
+                path: retarget.link.clone(),
+                content_changes: None,
+                rename_target: None,
+                link_target: None,
+                item_type: ItemType::File,
+            });
+            entry.link_target = Some(retarget.new_target.clone());
+            stats.links_retargeted += 1;
+        }
+        stats.warnings = self.warnings.lock().unwrap().clone();
+        stats.entries_scanned = self.entries_scanned.load(Ordering::Relaxed);
+        let mut skipped_encodings = self.skipped_encodings.lock().unwrap().clone();
+        skipped_encodings.sort();
+        stats.skipped_encodings = skipped_encodings;
+        if self.region_filter.is_active() {
+            stats.replacements_by_region = Some(region_counts);
+        }
         
         // Convert to sorted vector (by path for consistent ordering)
         let mut file_changes: Vec<FileChangeReport> = file_changes_map.into_values().collect();
@@ -531,13 +685,19 @@ impl RenameEngine {
                         "content_changes": report.total_stats.files_with_content_changes,
                         "file_renames": report.total_stats.files_renamed,
                         "directory_renames": report.total_stats.directories_renamed,
-                        "total_changes": report.total_stats.total_changes()
+                        "link_retargets": report.total_stats.links_retargeted,
+                        "total_changes": report.total_stats.total_changes(),
+                        "entries_scanned": report.total_stats.entries_scanned
                     },
+                    "replacements_by_region": report.total_stats.replacements_by_region,
+                    "warnings": report.total_stats.warnings,
+                    "skipped_encodings": report.total_stats.skipped_encodings,
                     "file_changes": report.file_changes.iter().map(|fc| {
                         serde_json::json!({
                             "path": fc.path,
                             "content_changes": fc.content_changes,
                             "rename_target": fc.rename_target,
+                            "link_target": fc.link_target,
                             "item_type": format!("{:?}", fc.item_type)
                         })
                     }).collect::<Vec<_>>()
@@ -548,7 +708,22 @@ impl RenameEngine {
                 println!("Content changes: {}", report.total_stats.files_with_content_changes);
                 println!("File renames: {}", report.total_stats.files_renamed);
                 println!("Directory renames: {}", report.total_stats.directories_renamed);
+                if report.total_stats.links_retargeted > 0 {
+                    println!("Link retargets: {}", report.total_stats.links_retargeted);
+                }
                 println!("Total changes: {}", report.total_stats.total_changes());
+                if let Some(counts) = &report.total_stats.replacements_by_region {
+                    println!("Replacements in code: {}", counts.code);
+                    println!("Replacements in comments: {}", counts.comments);
+                    println!("Replacements in strings: {}", counts.strings);
+                    println!("Matches skipped: {}", counts.skipped);
+                }
+                for warning in &report.total_stats.warnings {
+                    println!("Warning: {}", warning);
+                }
+                for skipped in &report.total_stats.skipped_encodings {
+                    println!("Skipped (unknown encoding): {}", skipped);
+                }
             }
             OutputFormat::Human => {
                 self.print_info("=== PLANNED CHANGES ===")?;
@@ -556,7 +731,28 @@ impl RenameEngine {
                 self.print_info(&format!("Content modifications: {} file(s)", report.total_stats.files_with_content_changes))?;
                 self.print_info(&format!("File renames:         {} file(s)", report.total_stats.files_renamed))?;
                 self.print_info(&format!("Directory renames:    {} directory(ies)", report.total_stats.directories_renamed))?;
+                if report.total_stats.links_retargeted > 0 {
+                    self.print_info(&format!("Link retargets:       {} symlink(s)", report.total_stats.links_retargeted))?;
+                }
+                if let Some(counts) = &report.total_stats.replacements_by_region {
+                    self.print_info(&format!(
+                        "Replacements:         {} in code, {} in comments, {} in strings ({} match(es) skipped)",
+                        counts.code, counts.comments, counts.strings, counts.skipped
+                    ))?;
+                }
                 self.print_info("")?;
+                for warning in &report.total_stats.warnings {
+                    self.print_warning(warning)?;
+                }
+                if !report.total_stats.skipped_encodings.is_empty() {
+                    self.print_warning(&format!(
+                        "Content skipped in {} file(s) with an unknown encoding:",
+                        report.total_stats.skipped_encodings.len()
+                    ))?;
+                    for skipped in &report.total_stats.skipped_encodings {
+                        self.print_warning(&format!("  {}", skipped))?;
+                    }
+                }
 
                 if !report.file_changes.is_empty() {
                     self.print_info("=== DETAILED CHANGES BY LOCATION ===")?;
@@ -569,8 +765,13 @@ impl RenameEngine {
                         
                         // Show content changes
                         if let Some(count) = change.content_changes {
-                            self.print_verbose(&format!("   Content: {} occurrence(s) of '{}' → '{}'", 
-                                count, self.config.pattern, self.config.substitute))?;
+                            if self.config.pairs.len() > 1 {
+                                self.print_verbose(&format!("   Content: {} occurrence(s) across {} pairs",
+                                    count, self.config.pairs.len()))?;
+                            } else {
+                                self.print_verbose(&format!("   Content: {} occurrence(s) of '{}' → '{}'", 
+                                    count, self.config.pattern, self.config.substitute))?;
+                            }
                         }
                         
                         // Show rename operation
@@ -580,6 +781,10 @@ impl RenameEngine {
                             self.print_verbose(&format!("   Rename:  {} → {}", 
                                 relative_path.display(), relative_target.display()))?;
                         }
+
+                        if let Some(target) = &change.link_target {
+                            self.print_verbose(&format!("   Target:  → {}", target.display()))?;
+                        }
                     }
                 }
             }
@@ -588,6 +793,41 @@ impl RenameEngine {
         Ok(report.total_stats)
     }
 
+    /// Print the plan for `--dry-run` and stop before anything is written
+    fn show_plan(&self, content_files: &[PathBuf], rename_items: &[RenameItem]) -> Result<()> {
+        if self.output_format != OutputFormat::Json {
+            let stats = self.show_summary(content_files, rename_items)?;
+            if stats.total_changes() > 0 && !content_files.is_empty() && self.output_format == OutputFormat::Human {
+                self.show_diff_preview(content_files)?;
+            }
+            return self.print_info("Dry run: no changes were made.");
+        }
+
+        let report = self.generate_detailed_report(content_files, rename_items)?;
+        let changes: Vec<PlannedChange> = report.file_changes.iter().map(PlannedChange::from).collect();
+        let plan = serde_json::json!({
+            "dry_run": true,
+            "root_dir": self.config.root_dir,
+            "pattern": self.config.pattern,
+            "substitute": self.config.substitute,
+            "pairs": self.config.pairs,
+            "summary": {
+                "content_changes": report.total_stats.files_with_content_changes,
+                "file_renames": report.total_stats.files_renamed,
+                "directory_renames": report.total_stats.directories_renamed,
+                "link_retargets": report.total_stats.links_retargeted,
+                "total_changes": report.total_stats.total_changes(),
+                "entries_scanned": report.total_stats.entries_scanned
+            },
+            "replacements_by_region": report.total_stats.replacements_by_region,
+            "warnings": report.total_stats.warnings,
+            "skipped_encodings": report.total_stats.skipped_encodings,
+            "changes": changes
+        });
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        Ok(())
+    }
+
     /// Show diff preview for content changes with colored +/- lines
     fn show_diff_preview(&self, content_files: &[PathBuf]) -> Result<()> {
         self.print_info("=== DIFF PREVIEW ===")?;
@@ -600,9 +840,17 @@ impl RenameEngine {
             
             let relative_path = file_path.strip_prefix(&self.config.root_dir)
                 .unwrap_or(file_path);
+
+            if let Some(kind) = self.archive_kind(file_path) {
+                let changes = archives::scan(file_path, kind, &self.replacements, &self.content_filter)?;
+                self.print_info(&format!("\n⏺ Update archive({})", relative_path.display()))?;
+                self.print_verbose(&format!("  ⎿  {} member(s) with content changes, {} member rename(s)",
+                    changes.content_changes, changes.member_renames))?;
+                continue;
+            }
             
             // Read file content
-            let content = match std::fs::read_to_string(file_path) {
+            let content = match self.file_ops.read_text(file_path) {
                 Ok(content) => content,
                 Err(_) => {
                     self.print_warning(&format!("⚠️  Cannot preview {}: unable to read file", relative_path.display()))?;
@@ -613,7 +861,11 @@ impl RenameEngine {
             self.print_info(&format!("\n⏺ Update({})", relative_path.display()))?;
             
             // Count replacements
-            let replacement_count = content.matches(&self.config.pattern).count();
+            let replacement_count = if self.region_filter.is_active() {
+                self.region_filter.select(file_path, &content, &self.replacements).1.replaced()
+            } else {
+                self.replacements.count(&content)
+            };
             let pattern_removals = self.config.pattern.lines().count() * replacement_count;
             let substitute_additions = self.config.substitute.lines().count() * replacement_count;
             
@@ -629,19 +881,39 @@ impl RenameEngine {
     }
     
     /// Show diff context with colored +/- lines for a specific file
-    fn show_diff_context(&self, content: &str, _file_path: &Path) -> Result<()> {
+    fn show_diff_context(&self, content: &str, file_path: &Path) -> Result<()> {
         use colored::*;
         
         let lines: Vec<&str> = content.lines().collect();
         let mut changes_shown = 0;
         const MAX_CHANGES_TO_SHOW: usize = 3;  // Limit changes shown per file
+
+        // With a region filter only the selected matches change; they are located by byte offset
+        let selected = self.region_filter.is_active()
+            .then(|| self.region_filter.select(file_path, content, &self.replacements).0);
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
         
         for (i, line) in lines.iter().enumerate() {
             if changes_shown >= MAX_CHANGES_TO_SHOW {
                 break;
             }
+
+            let new_line = match &selected {
+                Some(selected) => {
+                    let start = line_starts[i];
+                    let in_line: Vec<std::ops::Range<usize>> = selected
+                        .iter()
+                        .filter(|range| range.start >= start && range.end <= start + line.len())
+                        .map(|range| range.start - start..range.end - start)
+                        .collect();
+                    (!in_line.is_empty()).then(|| self.replacements.replace_matches(line, &in_line))
+                }
+                None => self.replacements.is_match(line).then(|| self.replacements.replace(line)),
+            };
             
-            if line.contains(&self.config.pattern) {
+            if let Some(new_line) = new_line {
                 // Show context: 2 lines before, the change, 2 lines after
                 let start_context = i.saturating_sub(2);
                 let end_context = std::cmp::min(i + 3, lines.len());
@@ -661,8 +933,6 @@ impl RenameEngine {
                 );
                 
                 // Show the added line (bright green background with white text)  
-                let new_line = line.replace(&self.config.pattern, &self.config.substitute);
-                
                 println!("      {} {}      {}", 
                     line_num.to_string().dimmed(),
                     "+".green().bold(),
@@ -695,18 +965,18 @@ impl RenameEngine {
         match self.output_format {
             OutputFormat::Json => Ok(true), // No confirmation in JSON mode
             OutputFormat::Plain | OutputFormat::Human => {
-                self.print_warning("This operation will modify your files and directories.")?;
+                self.print_warning(&crate::t!("refac.will_modify"))?;
                 
                 let confirmation = if let Some(progress) = &self.progress {
                     progress.suspend(|| {
                         dialoguer::Confirm::new()
-                            .with_prompt("Do you want to proceed?")
+                            .with_prompt(crate::t!("refac.confirm"))
                             .default(false)
                             .interact()
                     })
                 } else {
                     dialoguer::Confirm::new()
-                        .with_prompt("Do you want to proceed?")
+                        .with_prompt(crate::t!("refac.confirm"))
                         .default(false)
                         .interact()
                 };
@@ -718,21 +988,98 @@ impl RenameEngine {
 
     /// Execute the actual changes
     fn execute_changes(&self, content_files: &[PathBuf], rename_items: &[RenameItem]) -> Result<()> {
+        // Every change is journaled so the whole run can be reversed with `refactor undo`
+        let journal = JournalWriter::begin(
+            &self.journal_store,
+            &self.config.root_dir,
+            &self.config.pattern,
+            &self.config.substitute,
+        );
+
+        let result = self.execute_journaled_changes(content_files, rename_items, &journal);
+
+        // Write the journal even if some changes failed, so the ones that succeeded can be undone
+        match journal.finish() {
+            Ok(Some(record)) => self.print_info(&format!(
+                "Journal {} recorded ({} content change(s), {} rename(s)); undo with `wsb refactor undo`",
+                record.id, record.content_changes(), record.renames()
+            ))?,
+            Ok(None) => {}
+            Err(e) => self.print_warning(&format!("Failed to write refactor journal: {:#}", e))?,
+        }
+
+        result
+    }
+
+    fn execute_journaled_changes(&self, content_files: &[PathBuf], rename_items: &[RenameItem], journal: &JournalWriter) -> Result<()> {
+        // Phase 0: Symlink targets, repointed while the links are still at their original paths
+        for retarget in self.link_retargets.lock().unwrap().iter() {
+            symlinks::set_target(&retarget.link, &retarget.new_target)?;
+            journal.record_link_target(&retarget.link, &retarget.old_target, &retarget.new_target);
+            self.print_verbose(&format!("Retargeted: {} → {}", retarget.link.display(), retarget.new_target.display()))?;
+        }
+
         // Phase 1: Content replacement
         if !content_files.is_empty() && self.should_process_content() {
-            self.execute_content_changes(content_files)?;
+            self.execute_content_changes(content_files, journal)?;
+
+            // Staged before the renames, while the files are still at the paths git knows
+            if let Some(git_index) = &self.git_index {
+                let staged = git_index.stage(content_files)?;
+                self.print_info(&format!("Staged {} content edit(s) in git", staged))?;
+            }
         }
 
         // Phase 2: Rename items (directories first, then files)
         if !rename_items.is_empty() && self.should_process_names() {
-            self.execute_renames(rename_items)?;
+            self.execute_renames(rename_items, journal)?;
         }
 
         Ok(())
     }
 
+    /// Replace content in one file, saving its original content to the journal first.
+    /// The file's permissions, owner and xattrs (and mtime with `--preserve-mtime`) are
+    /// put back afterwards; anything that could not be is recorded for the report.
+    fn replace_content_journaled(&self, file_path: &Path, journal: &JournalWriter) -> Result<bool> {
+        let blob = journal.snapshot_content(file_path)?;
+        let snapshot = preserve::Snapshot::capture(file_path)?;
+        let result = match self.archive_kind(file_path) {
+            Some(kind) => self.rewrite_archive(file_path, kind),
+            None if self.region_filter.is_active() => self.file_ops.replace_content_by(file_path, |content| {
+                let (replaced, counts) = self.region_filter.replace(file_path, content, &self.replacements);
+                self.region_counts.lock().unwrap().add(&counts);
+                replaced
+            }),
+            None => self.file_ops.replace_content_with(file_path, &self.replacements),
+        };
+        journal.finish_content(file_path, blob, matches!(result, Ok(true)));
+        if matches!(result, Ok(true)) {
+            let lost = snapshot.restore(file_path, self.preserve_mtime);
+            if !lost.is_empty() {
+                let relative = file_path.strip_prefix(&self.config.root_dir).unwrap_or(file_path);
+                self.metadata_issues.lock().unwrap().push(format!("{} ({})", relative.display(), lost.join(", ")));
+            }
+        }
+        result
+    }
+
+    /// Rewrite an archive's members, recording its change counts for the report
+    fn rewrite_archive(&self, file_path: &Path, kind: ArchiveKind) -> Result<bool> {
+        if self.config.backup {
+            self.file_ops.create_backup(file_path)?;
+        }
+
+        let changes = archives::rewrite(file_path, kind, &self.replacements, &self.content_filter)?;
+        if changes.is_empty() {
+            return Ok(false);
+        }
+        self.archive_reports.lock().unwrap().push((file_path.to_path_buf(), changes));
+        Ok(true)
+    }
+
     /// Execute content changes
-    fn execute_content_changes(&self, content_files: &[PathBuf]) -> Result<()> {
+    fn execute_content_changes(&self, content_files: &[PathBuf], journal: &JournalWriter) -> Result<()> {
         self.print_info("Replacing content in files...")?;
 
         if let Some(progress) = &self.progress {
@@ -740,55 +1087,62 @@ impl RenameEngine {
         }
 
         let errors = Arc::new(Mutex::new(Vec::new()));
-        let _progress_ref = &self.progress;
+        let modified = Arc::new(Mutex::new(Vec::new()));
+        let progress_ref = self.progress.as_ref();
         let config_ref = &self.config;
-        let file_ops_ref = &self.file_ops;
         let errors_ref = Arc::clone(&errors);
+        let verbose_ref = Arc::clone(&modified);
 
         if self.thread_count > 1 {
-            // Parallel processing with improved error handling
-            content_files.par_iter().for_each(|file_path| {
-                // Validate file still exists before processing
-                if !file_path.exists() {
-                    errors_ref.lock().unwrap().push(format!("File no longer exists: {}", file_path.display()));
-                    return;
-                }
-
-                let result = file_ops_ref.replace_content(
-                    file_path,
-                    &config_ref.pattern,
-                    &config_ref.substitute,
-                );
-
-                match result {
-                    Ok(modified) => {
-                        if modified && config_ref.verbose {
-                            // Note: Can't use self.print_verbose in parallel context
-                            // Verbose output is handled in sequential mode
+            // Parallel processing on a pool sized by --threads
+            let pool = self.build_thread_pool()?;
+            pool.install(|| {
+                content_files.par_iter().for_each(|file_path| {
+                    // Validate file still exists before processing
+                    if !file_path.exists() {
+                        errors_ref.lock().unwrap().push(format!("File no longer exists: {}", file_path.display()));
+                    } else {
+                        let result = self.replace_content_journaled(file_path, journal);
+
+                        match result {
+                            Ok(modified) => {
+                                if modified && config_ref.verbose {
+                                    verbose_ref.lock().unwrap().push(file_path.clone());
+                                }
+                            }
+                            Err(e) => {
+                                errors_ref.lock().unwrap().push(format!("Failed to modify {}: {}", file_path.display(), e));
+                            }
                         }
                     }
-                    Err(e) => {
-                        errors_ref.lock().unwrap().push(format!("Failed to modify {}: {}", file_path.display(), e));
+
+                    self.files_processed.fetch_add(1, Ordering::Relaxed);
+                    if let Some(progress) = progress_ref {
+                        progress.update_content(&file_path.display().to_string());
                     }
-                }
+                });
             });
+
+            // Report modified files in a stable order once the workers are done
+            let mut modified_files = modified.lock().unwrap();
+            modified_files.sort();
+            for file_path in modified_files.iter() {
+                self.print_verbose(&format!("Modified: {}", file_path.display()))?;
+            }
         } else {
             // Sequential processing with enhanced error handling
             for file_path in content_files {
                 // Validate file still exists before processing
                 if !file_path.exists() {
                     self.print_error(&format!("File no longer exists: {}", file_path.display()))?;
+                    self.files_processed.fetch_add(1, Ordering::Relaxed);
                     if let Some(progress) = &self.progress {
                         progress.update_content(&file_path.display().to_string());
                     }
                     continue;
                 }
 
-                let result = file_ops_ref.replace_content(
-                    file_path,
-                    &config_ref.pattern,
-                    &config_ref.substitute,
-                );
+                let result = self.replace_content_journaled(file_path, journal);
 
                 match result {
                     Ok(modified) => {
@@ -801,6 +1155,7 @@ impl RenameEngine {
                     }
                 }
 
+                self.files_processed.fetch_add(1, Ordering::Relaxed);
                 if let Some(progress) = &self.progress {
                     progress.update_content(&file_path.display().to_string());
                 }
@@ -808,11 +1163,21 @@ impl RenameEngine {
         }
 
         // Report any errors from parallel processing
-        let errors = errors.lock().unwrap();
+        let mut errors = errors.lock().unwrap();
+        errors.sort();
         for error in errors.iter() {
             self.print_error(error)?;
         }
 
+        // Per-archive change counts
+        let mut archive_reports = self.archive_reports.lock().unwrap();
+        archive_reports.sort_by(|a, b| a.0.cmp(&b.0));
+        for (path, changes) in archive_reports.iter() {
+            let relative_path = path.strip_prefix(&self.config.root_dir).unwrap_or(path);
+            self.print_info(&format!("Archive {}: {} member(s) rewritten, {} member(s) renamed",
+                relative_path.display(), changes.content_changes, changes.member_renames))?;
+        }
+
         if let Some(progress) = &self.progress {
             progress.finish_content(&format!("Content replacement complete ({} files)", content_files.len()));
         }
@@ -821,7 +1186,7 @@ impl RenameEngine {
     }
 
     /// Execute rename operations with proper ordering and error handling
-    fn execute_renames(&self, rename_items: &[RenameItem]) -> Result<()> {
+    fn execute_renames(&self, rename_items: &[RenameItem], journal: &JournalWriter) -> Result<()> {
         self.print_info("Renaming files and directories...")?;
 
         if let Some(progress) = &self.progress {
@@ -863,10 +1228,17 @@ impl RenameEngine {
                 }
             }
 
-            let result = self.file_ops.move_item(&item.original_path, &item.new_path);
+            let result = match &self.git_index {
+                // git mv keeps the history attached to the new path
+                Some(git_index) if git_index.is_tracked(&item.original_path) => {
+                    git_index.mv(&item.original_path, &item.new_path)
+                }
+                _ => self.file_ops.move_item(&item.original_path, &item.new_path),
+            };
 
             match result {
                 Ok(()) => {
+                    journal.record_rename(&item.original_path, &item.new_path, &item.item_type);
                     successful_renames.push((item.original_path.clone(), item.new_path.clone()));
                     if self.config.verbose {
                         self.print_verbose(&format!("Renamed: {} → {}", 
@@ -1017,7 +1389,7 @@ impl RenameEngine {
         }
 
         // Validate that file can be read and contains the target string using encoding-aware methods
-        match self.file_ops.file_contains_string(file_path, &self.config.pattern) {
+        match self.file_ops.file_contains_match(file_path, &self.replacements) {
             Ok(contains_string) => {
                 if !contains_string {
                     validation_errors.push(ValidationError {
@@ -1259,19 +1631,54 @@ impl RenameEngine {
                         "file_renames": stats.files_renamed,
                         "directory_renames": stats.directories_renamed,
                         "total_changes": stats.total_changes(),
+                        "entries_scanned": stats.entries_scanned,
+                        "files_processed": stats.files_processed,
                         "errors": stats.errors.len()
                     },
+                    "replacements_by_region": stats.replacements_by_region,
+                    "skipped_encodings": stats.skipped_encodings,
+                    "metadata_not_preserved": stats.metadata_not_preserved,
                 });
                 println!("{}", serde_json::to_string_pretty(&report)?);
             }
             OutputFormat::Plain => {
                 println!("Operation completed successfully.");
                 println!("Total changes: {}", stats.total_changes());
+                println!("Entries scanned: {}", stats.entries_scanned);
+                println!("Content files processed: {}", stats.files_processed);
+                if let Some(counts) = &stats.replacements_by_region {
+                    println!("Replacements in code: {}", counts.code);
+                    println!("Replacements in comments: {}", counts.comments);
+                    println!("Replacements in strings: {}", counts.strings);
+                    println!("Matches skipped: {}", counts.skipped);
+                }
+                for file in &stats.metadata_not_preserved {
+                    println!("Metadata not preserved: {}", file);
+                }
             }
             OutputFormat::Human => {
                 self.print_success("=== OPERATION COMPLETE ===")?;
                 self.print_success("Operation completed successfully!")?;
                 self.print_info(&format!("Total changes applied: {}", stats.total_changes()))?;
+                self.print_info(&format!(
+                    "Scanned {} entries, processed {} content file(s)",
+                    stats.entries_scanned, stats.files_processed
+                ))?;
+                if let Some(counts) = &stats.replacements_by_region {
+                    self.print_info(&format!(
+                        "Replaced {} in code, {} in comments, {} in strings; left {} match(es) alone",
+                        counts.code, counts.comments, counts.strings, counts.skipped
+                    ))?;
+                }
+                if !stats.metadata_not_preserved.is_empty() {
+                    self.print_warning(&format!(
+                        "Metadata of {} rewritten file(s) could not be preserved:",
+                        stats.metadata_not_preserved.len()
+                    ))?;
+                    for file in &stats.metadata_not_preserved {
+                        self.print_warning(&format!("  {}", file))?;
+                    }
+                }
 
                 if !stats.errors.is_empty() {
                     self.print_warning(&format!("{} error(s) occurred:", stats.errors.len()))?;
@@ -1293,8 +1700,24 @@ impl RenameEngine {
 
         self.print_success("=== WORKSPACE REFAC TOOL ===")?;
         self.print_info(&format!("Root directory: {}", self.config.root_dir.display()))?;
-        self.print_info(&format!("Pattern: '{}'", self.config.pattern))?;
-        self.print_info(&format!("Substitute: '{}'", self.config.substitute))?;
+        if self.config.pairs.len() > 1 {
+            let pairs: Vec<String> = self.config.pairs
+                .iter()
+                .map(|(pattern, substitute)| format!("'{}' → '{}'", pattern, substitute))
+                .collect();
+            self.print_info(&format!("Pairs: {}", pairs.join(", ")))?;
+        } else {
+            self.print_info(&format!("Pattern: '{}'", self.config.pattern))?;
+            self.print_info(&format!("Substitute: '{}'", self.config.substitute))?;
+        }
+        if self.config.match_case_styles {
+            let variants: Vec<String> = self.replacements.replacements()
+                .iter()
+                .skip(self.config.pairs.len())
+                .map(|r| format!("'{}' → '{}'", r.pattern, r.substitute))
+                .collect();
+            self.print_info(&format!("Case styles: {}", variants.join(", ")))?;
+        }
         self.print_info(&format!("Mode: {:?}", self.mode))?;
         
         
@@ -1306,7 +1729,17 @@ impl RenameEngine {
     }
 
     // Utility methods for printing
+
+    /// JSON output keeps stdout parseable: progress messages only go to the log
+    fn machine_output(&self) -> bool {
+        self.output_format == OutputFormat::Json
+    }
+
     fn print_info(&self, message: &str) -> Result<()> {
+        if self.machine_output() {
+            log::info!("Refac: {}", message);
+            return Ok(());
+        }
         if let Some(progress) = &self.progress {
             progress.print_info(message);
         } else if let Some(output) = &self.simple_output {
@@ -1325,6 +1758,11 @@ impl RenameEngine {
     }
 
     fn print_warning(&self, message: &str) -> Result<()> {
+        if self.machine_output() {
+            log::warn!("Refac: {}", message);
+            eprintln!("WARNING: {}", message);
+            return Ok(());
+        }
         if let Some(progress) = &self.progress {
             progress.print_warning(message);
         } else if let Some(output) = &self.simple_output {
@@ -1334,6 +1772,10 @@ impl RenameEngine {
     }
 
     fn print_success(&self, message: &str) -> Result<()> {
+        if self.machine_output() {
+            log::info!("Refac: {}", message);
+            return Ok(());
+        }
         if let Some(progress) = &self.progress {
             progress.print_success(message);
         } else if let Some(output) = &self.simple_output {
@@ -1343,6 +1785,10 @@ impl RenameEngine {
     }
 
     fn print_verbose(&self, message: &str) -> Result<()> {
+        if self.machine_output() {
+            log::info!("Refac: {}", message);
+            return Ok(());
+        }
         if let Some(progress) = &self.progress {
             progress.print_verbose(message);
         } else if let Some(output) = &self.simple_output {
@@ -1361,7 +1807,7 @@ impl RenameEngine {
     }
 
     fn should_process_content(&self) -> bool {
-        !matches!(self.mode, Mode::NamesOnly)
+        !matches!(self.mode, Mode::NamesOnly) && self.config.replace_content
     }
 
     fn should_process_names(&self) -> bool {
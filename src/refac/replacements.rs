@@ -0,0 +1,356 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
+
+/// Identifier case styles recognised by `--match-case-styles`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseStyle {
+    /// old_name
+    Snake,
+    /// OLD_NAME
+    ScreamingSnake,
+    /// old-name
+    Kebab,
+    /// oldName
+    Camel,
+    /// OldName
+    Pascal,
+}
+
+impl CaseStyle {
+    pub const ALL: [CaseStyle; 5] = [
+        CaseStyle::Snake,
+        CaseStyle::ScreamingSnake,
+        CaseStyle::Kebab,
+        CaseStyle::Camel,
+        CaseStyle::Pascal,
+    ];
+
+    /// Join lowercase words in this style
+    pub fn join(&self, words: &[String]) -> String {
+        match self {
+            CaseStyle::Snake => words.join("_"),
+            CaseStyle::ScreamingSnake => words.iter().map(|w| w.to_uppercase()).collect::<Vec<_>>().join("_"),
+            CaseStyle::Kebab => words.join("-"),
+            CaseStyle::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+                .collect(),
+            CaseStyle::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Split an identifier in any supported style into lowercase words:
+/// `old_name`, `OLD_NAME`, `old-name`, `oldName`, `OldName` and `HTTPServer` style acronyms
+pub fn split_words(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = identifier.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map(|n| n.is_lowercase()).unwrap_or(false);
+            // Boundary at "aB" and at the last capital of an acronym ("HTTPServer" -> HTTP|Server)
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// A literal pattern and the text it is replaced with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub pattern: String,
+    pub substitute: String,
+}
+
+/// Set of literal replacements applied in a single pass, so a substitute is never
+/// re-matched by another pattern. Where patterns overlap, the longest one wins.
+#[derive(Debug, Clone)]
+pub struct ReplacementSet {
+    replacements: Vec<Replacement>,
+    matcher: Regex,
+    by_pattern: HashMap<String, usize>,
+    ignore_case: bool,
+}
+
+impl ReplacementSet {
+    /// Build a set from pattern/substitute pairs; earlier pairs win when patterns repeat
+    pub fn new(pairs: Vec<(String, String)>, ignore_case: bool) -> Result<Self> {
+        let mut replacements: Vec<Replacement> = Vec::new();
+        let mut by_pattern = HashMap::new();
+
+        for (pattern, substitute) in pairs {
+            if pattern.is_empty() {
+                continue;
+            }
+            let key = if ignore_case { pattern.to_lowercase() } else { pattern.clone() };
+            if by_pattern.contains_key(&key) {
+                continue;
+            }
+            by_pattern.insert(key, replacements.len());
+            replacements.push(Replacement { pattern, substitute });
+        }
+
+        if replacements.is_empty() {
+            anyhow::bail!("At least one non-empty pattern is required");
+        }
+
+        // Alternation is leftmost-first, so list longer patterns first
+        let mut alternatives: Vec<&str> = replacements.iter().map(|r| r.pattern.as_str()).collect();
+        alternatives.sort_by(|a, b| b.len().cmp(&a.len()));
+        let expression = alternatives.iter().map(|p| regex::escape(p)).collect::<Vec<_>>().join("|");
+
+        let matcher = RegexBuilder::new(&expression)
+            .case_insensitive(ignore_case)
+            .build()
+            .with_context(|| "Failed to build replacement matcher")?;
+
+        Ok(Self { replacements, matcher, by_pattern, ignore_case })
+    }
+
+    /// A single literal replacement
+    pub fn literal(pattern: &str, substitute: &str, ignore_case: bool) -> Result<Self> {
+        Self::new(vec![(pattern.to_string(), substitute.to_string())], ignore_case)
+    }
+
+    /// The given replacement plus its snake, SCREAMING, kebab, camel and Pascal case variants
+    pub fn with_case_styles(pattern: &str, substitute: &str, ignore_case: bool) -> Result<Self> {
+        Self::from_pairs(&[(pattern.to_string(), substitute.to_string())], true, ignore_case)
+    }
+
+    /// Several replacements applied together. With `case_styles`, every pair's case variants
+    /// are added after all the given pairs, so an explicit pair always beats a generated one.
+    pub fn from_pairs(pairs: &[(String, String)], case_styles: bool, ignore_case: bool) -> Result<Self> {
+        let mut all = pairs.to_vec();
+
+        if case_styles {
+            for (pattern, substitute) in pairs {
+                let pattern_words = split_words(pattern);
+                let substitute_words = split_words(substitute);
+                if !pattern_words.is_empty() && !substitute_words.is_empty() {
+                    for style in CaseStyle::ALL {
+                        all.push((style.join(&pattern_words), style.join(&substitute_words)));
+                    }
+                }
+            }
+        }
+
+        Self::new(all, ignore_case)
+    }
+
+    /// All replacements, in priority order
+    pub fn replacements(&self) -> &[Replacement] {
+        &self.replacements
+    }
+
+    fn substitute_for<'a>(&'a self, matched: &'a str) -> &'a str {
+        let key = if self.ignore_case { matched.to_lowercase() } else { matched.to_string() };
+        match self.by_pattern.get(&key) {
+            Some(&index) => &self.replacements[index].substitute,
+            None => matched,
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.matcher.is_match(text)
+    }
+
+    /// Number of (non-overlapping) matches in `text`
+    pub fn count(&self, text: &str) -> usize {
+        self.matcher.find_iter(text).count()
+    }
+
+    /// Replace every match in `text`
+    pub fn replace(&self, text: &str) -> String {
+        self.matcher
+            .replace_all(text, |caps: &regex::Captures| self.substitute_for(&caps[0]).to_string())
+            .into_owned()
+    }
+
+    /// Byte ranges of every (non-overlapping) match in `text`
+    pub fn find_matches(&self, text: &str) -> Vec<Range<usize>> {
+        self.matcher.find_iter(text).map(|m| m.start()..m.end()).collect()
+    }
+
+    /// Replace only the given matches (ranges from [`find_matches`](Self::find_matches), in order)
+    pub fn replace_matches(&self, text: &str, matches: &[Range<usize>]) -> String {
+        let mut replaced = String::with_capacity(text.len());
+        let mut last = 0;
+        for range in matches {
+            replaced.push_str(&text[last..range.start]);
+            replaced.push_str(self.substitute_for(&text[range.clone()]));
+            last = range.end;
+        }
+        replaced.push_str(&text[last..]);
+        replaced
+    }
+}
+
+/// Layout of a `--map` file:
+///
+/// ```toml
+/// [renames]
+/// old_name = "new_name"
+/// OldWidget = "NewWidget"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct MapFile {
+    #[serde(default)]
+    renames: BTreeMap<String, String>,
+}
+
+/// Read the old → new pairs from a `--map` TOML file
+pub fn load_map(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read map file: {}", path.display()))?;
+    let map: MapFile = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse map file: {}", path.display()))?;
+
+    if map.renames.is_empty() {
+        anyhow::bail!("Map file {} has no entries under [renames]", path.display());
+    }
+    Ok(map.renames.into_iter().collect())
+}
+
+/// Reject pairs that cannot be applied together: empty pairs, a pattern mapped to
+/// two different substitutes, and two patterns merged into the same substitute
+pub fn check_pairs(pairs: &[(String, String)], ignore_case: bool) -> Result<()> {
+    let key = |s: &str| if ignore_case { s.to_lowercase() } else { s.to_string() };
+    let mut by_pattern: HashMap<String, &str> = HashMap::new();
+    let mut by_substitute: HashMap<String, &str> = HashMap::new();
+
+    for (pattern, substitute) in pairs {
+        if pattern.is_empty() || substitute.is_empty() {
+            anyhow::bail!("Empty pattern or substitute in pair '{}' → '{}'", pattern, substitute);
+        }
+
+        if let Some(previous) = by_pattern.insert(key(pattern), substitute) {
+            if previous != substitute {
+                anyhow::bail!("'{}' is mapped to both '{}' and '{}'", pattern, previous, substitute);
+            }
+            continue;
+        }
+        if let Some(previous) = by_substitute.insert(key(substitute), pattern) {
+            anyhow::bail!("'{}' and '{}' are both mapped to '{}'", previous, pattern, substitute);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("old_name"), vec!["old", "name"]);
+        assert_eq!(split_words("OLD_NAME"), vec!["old", "name"]);
+        assert_eq!(split_words("old-name"), vec!["old", "name"]);
+        assert_eq!(split_words("oldName"), vec!["old", "name"]);
+        assert_eq!(split_words("OldName"), vec!["old", "name"]);
+        assert_eq!(split_words("HTTPServer"), vec!["http", "server"]);
+        assert_eq!(split_words("user2Id"), vec!["user2", "id"]);
+    }
+
+    #[test]
+    fn test_case_style_variants() {
+        let set = ReplacementSet::with_case_styles("old_name", "new_name", false).unwrap();
+        let text = "old_name OldName OLD_NAME oldName old-name";
+        assert_eq!(set.replace(text), "new_name NewName NEW_NAME newName new-name");
+        assert_eq!(set.count(text), 5);
+    }
+
+    #[test]
+    fn test_single_word_keeps_given_substitute() {
+        let set = ReplacementSet::with_case_styles("widget", "gadget_box", false).unwrap();
+        assert_eq!(set.replace("widget Widget WIDGET"), "gadget_box GadgetBox GADGET_BOX");
+    }
+
+    #[test]
+    fn test_single_pass_longest_match() {
+        let set = ReplacementSet::new(
+            vec![("a".to_string(), "b".to_string()), ("b".to_string(), "a".to_string()), ("ab".to_string(), "X".to_string())],
+            false,
+        ).unwrap();
+        assert_eq!(set.replace("a b ab"), "b a X");
+    }
+
+    #[test]
+    fn test_pairs_apply_in_one_pass() {
+        let pairs = vec![
+            ("old_name".to_string(), "new_name".to_string()),
+            ("Widget".to_string(), "Gadget".to_string()),
+            // Swaps are fine because substitutes are never re-matched
+            ("left".to_string(), "right".to_string()),
+            ("right".to_string(), "left".to_string()),
+        ];
+        check_pairs(&pairs, false).unwrap();
+
+        let set = ReplacementSet::from_pairs(&pairs, true, false).unwrap();
+        assert_eq!(set.replace("OldName Widget left right"), "NewName Gadget right left");
+    }
+
+    #[test]
+    fn test_conflicting_pairs() {
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert!(check_pairs(&[pair("a", "b"), pair("a", "c")], false).is_err());
+        assert!(check_pairs(&[pair("a", "c"), pair("b", "c")], false).is_err());
+        assert!(check_pairs(&[pair("a", "b"), pair("A", "c")], false).is_ok());
+        assert!(check_pairs(&[pair("a", "b"), pair("A", "c")], true).is_err());
+        // Repeating an identical pair is harmless
+        assert!(check_pairs(&[pair("a", "b"), pair("a", "b")], false).is_ok());
+    }
+
+    #[test]
+    fn test_load_map() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("renames.toml");
+        std::fs::write(&path, "[renames]\nold_name = \"new_name\"\nOldWidget = \"NewWidget\"\n").unwrap();
+        assert_eq!(
+            load_map(&path).unwrap(),
+            vec![
+                ("OldWidget".to_string(), "NewWidget".to_string()),
+                ("old_name".to_string(), "new_name".to_string()),
+            ]
+        );
+
+        std::fs::write(&path, "[other]\n").unwrap();
+        assert!(load_map(&path).is_err());
+    }
+
+    #[test]
+    fn test_ignore_case_preserves_surrounding_text() {
+        let set = ReplacementSet::literal("old", "new", true).unwrap();
+        assert_eq!(set.replace("My_OLD_File"), "My_new_File");
+    }
+}
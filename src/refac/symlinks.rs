@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use std::collections::hash_map::{Entry, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::replacements::ReplacementSet;
+
+/// Whether `path` is itself a symlink (broken links included)
+pub fn is_symlink(path: &Path) -> bool {
+    path.symlink_metadata().map(|m| m.file_type().is_symlink()).unwrap_or(false)
+}
+
+/// Planned rewrite of a link's target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkRetarget {
+    pub link: PathBuf,
+    pub old_target: PathBuf,
+    pub new_target: PathBuf,
+}
+
+/// The rewrite for `link` when its target path contains a pattern
+pub fn plan_retarget(link: &Path, replacements: &ReplacementSet) -> Result<Option<LinkRetarget>> {
+    let target = fs::read_link(link).with_context(|| format!("Failed to read symlink {}", link.display()))?;
+    let text = match target.to_str() {
+        Some(text) => text,
+        None => return Ok(None),
+    };
+
+    if !replacements.is_match(text) {
+        return Ok(None);
+    }
+
+    Ok(Some(LinkRetarget {
+        link: link.to_path_buf(),
+        old_target: target.clone(),
+        new_target: PathBuf::from(replacements.replace(text)),
+    }))
+}
+
+/// Point `link` at `target`. The new link is created beside the old one and renamed
+/// over it, so the link never goes missing.
+pub fn set_target(link: &Path, target: &Path) -> Result<()> {
+    let name = link.file_name().and_then(|n| n.to_str()).unwrap_or("link");
+    let temp = link.with_file_name(format!(".{}.refac-link", name));
+    let _ = fs::remove_file(&temp);
+
+    create_symlink(target, &temp)
+        .with_context(|| format!("Failed to create symlink to {}", target.display()))?;
+    if let Err(e) = fs::rename(&temp, link) {
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_context(|| format!("Failed to replace symlink {}", link.display()));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    // Windows distinguishes file and directory links; relative targets resolve from the link's directory
+    let resolved = link.parent().map(|dir| dir.join(target)).unwrap_or_else(|| target.to_path_buf());
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+/// The symlink cycle behind a walker error, as (ancestor, child)
+pub fn walk_loop(err: &ignore::Error) -> Option<(PathBuf, PathBuf)> {
+    match err {
+        ignore::Error::Loop { ancestor, child } => Some((ancestor.clone(), child.clone())),
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => walk_loop(err),
+        _ => None,
+    }
+}
+
+/// Identity of the entry itself rather than what it points to: canonical parent plus name
+fn entry_identity(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => fs::canonicalize(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Keep one path per key, preferring paths for which `preferred` holds
+fn dedupe_by<K, P>(paths: Vec<PathBuf>, key: K, preferred: P) -> Vec<PathBuf>
+where
+    K: Fn(&Path) -> PathBuf,
+    P: Fn(&Path, &Path) -> bool,
+{
+    let mut chosen: HashMap<PathBuf, PathBuf> = HashMap::new();
+    for path in paths {
+        let identity = key(&path);
+        let better = preferred(&path, &identity);
+        match chosen.entry(identity) {
+            Entry::Vacant(slot) => {
+                slot.insert(path);
+            }
+            Entry::Occupied(mut slot) => {
+                if better {
+                    slot.insert(path);
+                }
+            }
+        }
+    }
+
+    let mut paths: Vec<PathBuf> = chosen.into_values().collect();
+    paths.sort();
+    paths
+}
+
+/// With links followed, one entry can be reached through several paths. Keep one path
+/// per entry, preferring the one that does not pass through a link.
+pub fn dedupe_entries(root: &Path, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let canonical_root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    dedupe_by(paths, entry_identity, |path, identity| {
+        path.strip_prefix(root).map(|relative| canonical_root.join(relative) == identity).unwrap_or(false)
+    })
+}
+
+/// Keep one path per underlying file so content reached through a link is rewritten once
+pub fn dedupe_targets(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    dedupe_by(
+        paths,
+        |path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+        |path, _| !is_symlink(path),
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_retarget_link() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("newname.txt"), "x").unwrap();
+        symlink("oldname.txt", root.join("link")).unwrap();
+
+        let replacements = ReplacementSet::literal("oldname", "newname", false).unwrap();
+        let plan = plan_retarget(&root.join("link"), &replacements).unwrap().unwrap();
+        assert_eq!(plan.new_target, PathBuf::from("newname.txt"));
+
+        set_target(&plan.link, &plan.new_target).unwrap();
+        assert_eq!(fs::read_link(root.join("link")).unwrap(), PathBuf::from("newname.txt"));
+        assert_eq!(fs::read_to_string(root.join("link")).unwrap(), "x");
+    }
+
+    #[test]
+    fn test_dedupe_prefers_direct_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("real")).unwrap();
+        fs::write(root.join("real/file.txt"), "x").unwrap();
+        symlink("real", root.join("alias")).unwrap();
+
+        let entries = dedupe_entries(root, vec![root.join("alias/file.txt"), root.join("real/file.txt"), root.join("alias")]);
+        assert_eq!(entries, vec![root.join("alias"), root.join("real/file.txt")]);
+
+        let targets = dedupe_targets(vec![root.join("alias/file.txt"), root.join("real/file.txt")]);
+        assert_eq!(targets.len(), 1);
+    }
+}
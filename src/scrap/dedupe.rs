@@ -0,0 +1,180 @@
+//! Content-hash deduplication within `.scrap`.
+//!
+//! Every scrapped file records the SHA-256 of its content. When a newly scrapped file
+//! matches one already stored, its entry is hard-linked to the stored file instead of
+//! being moved in, so identical content is kept once however many entries point at it.
+//! Each entry keeps its own name, original path and time, and restoring or removing one
+//! leaves the others intact. Where a hard link cannot be made the file is stored as usual.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+use super::scrap_common::{ScrapEntry, ScrapMetadata};
+
+/// Hex SHA-256 of a file's content
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut reader = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// A stored, unarchived entry with the content `hash` that a new entry can link to
+pub fn find_stored<'a>(scrap_dir: &Path, metadata: &'a ScrapMetadata, hash: &str) -> Option<&'a ScrapEntry> {
+    metadata.entries.values().find(|entry| {
+        entry.archive.is_none()
+            && entry.content_hash.as_deref() == Some(hash)
+            && fs::symlink_metadata(scrap_dir.join(&entry.scrapped_name)).map_or(false, |m| m.is_file())
+    })
+}
+
+/// Identity of the stored file, shared by every hard link to it
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// Whether other hard links share the file at `path`
+#[cfg(unix)]
+fn is_shared(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    fs::symlink_metadata(path).map_or(false, |m| m.is_file() && m.nlink() > 1)
+}
+
+#[cfg(not(unix))]
+fn is_shared(_path: &Path) -> bool {
+    false
+}
+
+/// Move a stored entry out of `.scrap` to `dest`. A file other entries are linked to is
+/// copied out and only this entry's link removed, so editing the restored file leaves
+/// their content alone.
+pub fn move_out(source: &Path, dest: &Path) -> Result<()> {
+    if !is_shared(source) {
+        return super::transfer::move_path(source, dest);
+    }
+
+    let modified = fs::metadata(source)?.modified()?;
+    fs::copy(source, dest).with_context(|| format!("Failed to copy {} to {}", source.display(), dest.display()))?;
+    File::options().write(true).open(dest)?.set_modified(modified)?;
+    fs::remove_file(source).with_context(|| format!("Copied to {} but failed to unlink {}", dest.display(), source.display()))?;
+    Ok(())
+}
+
+/// Entries holding the same content
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    /// Bytes of the content
+    pub size: u64,
+    /// Scrapped names, oldest first
+    pub entries: Vec<String>,
+    /// Separate copies on disk; 1 when every entry shares one stored file
+    pub copies: usize,
+}
+
+impl DuplicateGroup {
+    /// Bytes not spent because entries share a stored file
+    pub fn saved(&self) -> u64 {
+        self.size * (self.entries.len() - self.copies) as u64
+    }
+
+    /// Bytes still spent on redundant copies (files scrapped before deduplication)
+    pub fn reclaimable(&self) -> u64 {
+        self.size * (self.copies - 1) as u64
+    }
+}
+
+/// Groups of unarchived file entries with identical content. Entries scrapped before
+/// hashes were recorded are hashed on the fly.
+pub fn duplicates(scrap_dir: &Path, metadata: &ScrapMetadata) -> Vec<DuplicateGroup> {
+    let mut entries: Vec<&ScrapEntry> = metadata.entries.values().filter(|e| e.archive.is_none()).collect();
+    entries.sort_by_key(|e| e.scrapped_at);
+
+    let mut by_hash: BTreeMap<String, Vec<&ScrapEntry>> = BTreeMap::new();
+    for entry in entries {
+        let path = scrap_dir.join(&entry.scrapped_name);
+        if !fs::symlink_metadata(&path).map_or(false, |m| m.is_file()) {
+            continue;
+        }
+        let hash = match &entry.content_hash {
+            Some(hash) => hash.clone(),
+            None => match hash_file(&path) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    log::warn!("Could not hash {}: {}", path.display(), e);
+                    continue;
+                }
+            },
+        };
+        by_hash.entry(hash).or_default().push(entry);
+    }
+
+    by_hash
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(hash, group)| {
+            let first = scrap_dir.join(&group[0].scrapped_name);
+            let size = fs::metadata(&first).map_or(0, |m| m.len());
+            let identities: Vec<Option<(u64, u64)>> =
+                group.iter().map(|e| file_identity(&scrap_dir.join(&e.scrapped_name))).collect();
+            let copies = if identities.iter().all(|i| i.is_some()) {
+                identities.iter().collect::<HashSet<_>>().len()
+            } else {
+                group.len()
+            };
+            DuplicateGroup {
+                hash,
+                size,
+                entries: group.iter().map(|e| e.scrapped_name.clone()).collect(),
+                copies,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_duplicates_counts_shared_and_separate_copies() {
+        let temp_dir = TempDir::new().unwrap();
+        let scrap_dir = temp_dir.path();
+        fs::write(scrap_dir.join("notes.txt"), "same notes").unwrap();
+        fs::hard_link(scrap_dir.join("notes.txt"), scrap_dir.join("notes_1.txt")).unwrap();
+        fs::write(scrap_dir.join("notes_2.txt"), "same notes").unwrap();
+        fs::write(scrap_dir.join("other.txt"), "different").unwrap();
+
+        let mut metadata = ScrapMetadata::new();
+        for name in ["notes.txt", "notes_1.txt", "notes_2.txt", "other.txt"] {
+            metadata.add_entry(name, PathBuf::from(name));
+        }
+        let hash = hash_file(&scrap_dir.join("notes.txt")).unwrap();
+        metadata.entries.get_mut("notes.txt").unwrap().content_hash = Some(hash.clone());
+        assert_eq!(find_stored(scrap_dir, &metadata, &hash).unwrap().scrapped_name, "notes.txt");
+
+        let groups = duplicates(scrap_dir, &metadata);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].hash, hash);
+        assert_eq!(groups[0].entries.len(), 3);
+        #[cfg(unix)]
+        {
+            assert_eq!(groups[0].copies, 2);
+            assert_eq!(groups[0].saved(), 10);
+            assert_eq!(groups[0].reclaimable(), 10);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Safety checks run before anything is moved into `.scrap`.
+//!
+//! Protected paths (the project root, `.git`, the workspace directories and
+//! anything matching `tools.scrap.protected_globs`) are refused unless the
+//! caller passes [`OVERRIDE_FLAG`]. Paths that templates or workspace config
+//! still point at are scrapped, but with a warning.
+
+use anyhow::Result;
+use ignore::gitignore::GitignoreBuilder;
+use std::path::{Component, Path, PathBuf};
+
+use super::tiering::ScrapConfig;
+use crate::st8::templates::TemplateManager;
+use crate::workspace_state::WorkspaceState;
+
+/// Flag that lets a protected path be scrapped anyway
+pub const OVERRIDE_FLAG: &str = "--i-know-what-im-doing";
+
+/// Directories that are never scrapped without the override, wherever they appear in the project
+pub const PROTECTED_DIRS: &[&str] = &[".git", ".ws", ".wsb", ".scrap"];
+
+/// Absolute, symlink-resolved form of a path; falls back to joining onto `base`
+fn absolute(path: &Path, base: &Path) -> PathBuf {
+    let joined = if path.is_absolute() { path.to_path_buf() } else { base.join(path) };
+    joined.canonicalize().unwrap_or(joined)
+}
+
+/// Why `path` must not be scrapped, if it is protected
+pub fn protection_reason(path: &Path, project_root: &Path, config: &ScrapConfig) -> Result<Option<String>> {
+    let root = absolute(project_root, project_root);
+    let target = absolute(path, &root);
+
+    if target == root {
+        return Ok(Some("it is the project root".to_string()));
+    }
+    if root.starts_with(&target) {
+        return Ok(Some("it contains the project root".to_string()));
+    }
+
+    let relative = match target.strip_prefix(&root) {
+        Ok(relative) => relative,
+        // Paths outside the project are only protected as ancestors of the root
+        Err(_) => return Ok(None),
+    };
+
+    for component in relative.components() {
+        if let Component::Normal(name) = component {
+            if let Some(dir) = PROTECTED_DIRS.iter().find(|dir| name == std::ffi::OsStr::new(dir)) {
+                return Ok(Some(format!("it is inside {}", dir)));
+            }
+        }
+    }
+
+    if !config.protected_globs.is_empty() {
+        let mut builder = GitignoreBuilder::new(&root);
+        for glob in &config.protected_globs {
+            builder.add_line(None, glob)
+                .map_err(|e| anyhow::anyhow!("Invalid protected glob '{}': {}", glob, e))?;
+        }
+        let globs = builder.build()?;
+        let matched = globs.matched_path_or_any_parents(relative, target.is_dir());
+        if matched.is_ignore() {
+            let glob = matched.inner().map(|g| g.original().to_string()).unwrap_or_default();
+            return Ok(Some(format!("it matches protected glob '{}'", glob)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Templates and workspace config entries that point at `path` or at something inside it
+pub fn references(path: &Path, project_root: &Path) -> Vec<String> {
+    let root = absolute(project_root, project_root);
+    let target = absolute(path, &root);
+    let mut references = Vec::new();
+
+    let state_path = root.join(".wsb").join("state.json");
+    if !state_path.exists() {
+        return references;
+    }
+
+    let templates_dir = root.join(".wsb").join("templates");
+    if let Ok(templates) = TemplateManager::load_template_configs(&templates_dir) {
+        let mut templates: Vec<_> = templates.into_values().collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        for template in templates {
+            if absolute(Path::new(&template.output_path), &root).starts_with(&target) {
+                references.push(format!("template '{}' renders to {}", template.name, template.output_path));
+            }
+            if absolute(&templates_dir.join(&template.source_path), &root).starts_with(&target) {
+                references.push(format!("template '{}' is read from {}", template.name, template.source_path));
+            }
+        }
+    }
+
+    if let Ok(state) = WorkspaceState::load(&root) {
+        for entry in &state.wstemplate_entries {
+            if absolute(&entry.root, &root).starts_with(&target) {
+                references.push(format!("wstemplate alias '{}' points at {}", entry.alias, entry.root.display()));
+            }
+        }
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_protected_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".git/objects")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), "").unwrap();
+        let config = ScrapConfig::default();
+
+        assert!(protection_reason(root, root, &config).unwrap().is_some());
+        assert!(protection_reason(Path::new("."), root, &config).unwrap().is_some());
+        assert!(protection_reason(root.parent().unwrap(), root, &config).unwrap().is_some());
+        assert!(protection_reason(&root.join(".git"), root, &config).unwrap().is_some());
+        assert!(protection_reason(&root.join(".git/objects"), root, &config).unwrap().is_some());
+        assert!(protection_reason(&root.join("src/main.rs"), root, &config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_protected_globs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("migrations")).unwrap();
+        fs::write(root.join("migrations/001.sql"), "").unwrap();
+        fs::write(root.join("Cargo.lock"), "").unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+        let config = ScrapConfig {
+            protected_globs: vec!["migrations/".to_string(), "*.lock".to_string()],
+            ..ScrapConfig::default()
+        };
+
+        let reason = protection_reason(&root.join("migrations/001.sql"), root, &config).unwrap().unwrap();
+        assert!(reason.contains("migrations/"));
+        assert!(protection_reason(&root.join("Cargo.lock"), root, &config).unwrap().is_some());
+        assert!(protection_reason(&root.join("notes.txt"), root, &config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_template_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        WorkspaceState::initialize(root).unwrap();
+        fs::create_dir_all(root.join(".wsb/templates")).unwrap();
+        fs::write(
+            root.join(".wsb/templates/templates.json"),
+            r#"{"readme": {"name": "readme", "description": null, "source_path": "readme.tera", "output_path": "docs/README.md", "enabled": true}}"#,
+        ).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/README.md"), "").unwrap();
+        fs::write(root.join("other.txt"), "").unwrap();
+
+        let refs = references(&root.join("docs"), root);
+        assert_eq!(refs.len(), 1);
+        assert!(refs[0].contains("readme"));
+        assert!(references(&root.join("other.txt"), root).is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+//! Pre- and post-operation hooks for audit logging (`tools.scrap.hooks`).
+//!
+//! Every scrap, unscrap, rm, clean, purge and retention removal is announced to the shell
+//! commands configured under `tools.scrap.hooks.pre` and `tools.scrap.hooks.post`, and to
+//! any `ScrapHook` registered in-process. Shell hooks run from the project root with the
+//! `HookEvent` as JSON on stdin and `WSB_SCRAP_ACTION` / `WSB_SCRAP_PHASE` set. A failing
+//! pre hook cancels the operation, so a hook can also act as a gate; post hook failures
+//! are logged and otherwise ignored. Scrap commands run by a hook do not fire hooks again.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::scrap_common::{self, HookAction, HookEvent, HookPhase, ScrapEntry, ScrapHook};
+
+/// Set for hook commands, so scrap operations they run are not hooked again
+pub const HOOK_ENV: &str = "WSB_SCRAP_HOOK";
+
+/// Shell commands from `tools.scrap.hooks`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookConfig {
+    /// Run before each operation; a non-zero exit cancels it
+    pub pre: Vec<String>,
+    /// Run after each operation has completed
+    pub post: Vec<String>,
+}
+
+/// A configured shell command receiving the event on stdin
+pub struct ShellHook {
+    pub command: String,
+    pub project: PathBuf,
+}
+
+impl ScrapHook for ShellHook {
+    fn on_event(&self, event: &HookEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+        let mut child = Command::new(shell)
+            .args([flag, &self.command])
+            .current_dir(&self.project)
+            .env(HOOK_ENV, "1")
+            .env("WSB_SCRAP_ACTION", label(&event.action))
+            .env("WSB_SCRAP_PHASE", label(&event.phase))
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run scrap hook: {}", self.command))?;
+
+        // A hook that ignores its input may exit before reading it all
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(&payload);
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            anyhow::bail!("Scrap hook `{}` failed ({})", self.command, status);
+        }
+        Ok(())
+    }
+}
+
+fn label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+/// Announces one operation to every hook, before and after it runs
+pub struct Hooks {
+    project: PathBuf,
+    config: HookConfig,
+    action: HookAction,
+    entries: Vec<ScrapEntry>,
+    paths: Vec<PathBuf>,
+}
+
+impl Hooks {
+    pub fn new(scrap_dir: &Path, config: HookConfig, action: HookAction) -> Self {
+        let project = scrap_dir.parent().map(Path::to_path_buf).unwrap_or_else(|| scrap_dir.to_path_buf());
+        // Hook commands that scrap things themselves would otherwise recurse
+        let config = if std::env::var_os(HOOK_ENV).is_some() { HookConfig::default() } else { config };
+        Self { project, config, action, entries: Vec::new(), paths: Vec::new() }
+    }
+
+    /// The entries and paths the operation is about
+    pub fn with(mut self, entries: Vec<ScrapEntry>, paths: Vec<PathBuf>) -> Self {
+        self.entries = entries;
+        self.paths = paths;
+        self
+    }
+
+    fn event(&self, phase: HookPhase) -> HookEvent {
+        HookEvent {
+            action: self.action,
+            phase,
+            project: self.project.clone(),
+            entries: self.entries.clone(),
+            paths: self.paths.clone(),
+        }
+    }
+
+    fn shell_hooks(&self, phase: HookPhase) -> Vec<ShellHook> {
+        let commands = match phase {
+            HookPhase::Pre => &self.config.pre,
+            HookPhase::Post => &self.config.post,
+        };
+        commands.iter().map(|command| ShellHook { command: command.clone(), project: self.project.clone() }).collect()
+    }
+
+    /// Run the pre hooks; the first failure cancels the operation
+    pub fn before(&self) -> Result<()> {
+        let event = self.event(HookPhase::Pre);
+        for hook in self.shell_hooks(HookPhase::Pre) {
+            hook.on_event(&event).context("Cancelled by a scrap pre hook")?;
+        }
+        for hook in scrap_common::registered_hooks() {
+            hook.on_event(&event).context("Cancelled by a scrap pre hook")?;
+        }
+        Ok(())
+    }
+
+    /// Run the post hooks, with the entries as they are after the operation
+    pub fn after(mut self, entries: Vec<ScrapEntry>) {
+        if !entries.is_empty() {
+            self.entries = entries;
+        }
+        let event = self.event(HookPhase::Post);
+        for hook in self.shell_hooks(HookPhase::Post) {
+            if let Err(e) = hook.on_event(&event) {
+                log::warn!("{:#}", e);
+            }
+        }
+        for hook in scrap_common::registered_hooks() {
+            if let Err(e) = hook.on_event(&event) {
+                log::warn!("Scrap post hook failed: {:#}", e);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_shell_hooks_get_event_json_and_can_cancel() {
+        let temp_dir = TempDir::new().unwrap();
+        let scrap_dir = temp_dir.path().join(".scrap");
+        let config = HookConfig {
+            pre: vec!["cat > pre.json".to_string()],
+            post: vec!["echo \"$WSB_SCRAP_ACTION $WSB_SCRAP_PHASE\" > post.txt".to_string()],
+        };
+        let hooks = Hooks::new(&scrap_dir, config, HookAction::Purge)
+            .with(Vec::new(), vec![PathBuf::from("notes.txt")]);
+
+        hooks.before().unwrap();
+        let event: serde_json::Value = serde_json::from_str(&fs::read_to_string(temp_dir.path().join("pre.json")).unwrap()).unwrap();
+        assert_eq!(event["action"], "purge");
+        assert_eq!(event["phase"], "pre");
+        assert_eq!(event["paths"][0], "notes.txt");
+
+        hooks.after(Vec::new());
+        assert_eq!(fs::read_to_string(temp_dir.path().join("post.txt")).unwrap().trim(), "purge post");
+
+        let gate = HookConfig { pre: vec!["exit 3".to_string()], post: Vec::new() };
+        assert!(Hooks::new(&scrap_dir, gate, HookAction::Scrap).before().is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Restoring a scrapped directory into one that already exists (`unscrap --merge`).
+//!
+//! Rather than replacing the existing directory the way `--force` does, the merge walks
+//! the scrapped tree and moves back only what is missing. A file that exists on both
+//! sides with the same content needs nothing; one with different content is a conflict,
+//! reported by path and left in scrap, unless `--backup-existing` moves the existing file
+//! aside (`name.bak`, `name.bak.1`, ...) so the scrapped one can be restored.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::dedupe;
+use super::transfer;
+
+/// What happened to each path of a merged directory, relative to its root
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct MergeReport {
+    /// Moved back because nothing was there
+    pub restored: Vec<PathBuf>,
+    /// Already present with the same content
+    pub identical: Vec<PathBuf>,
+    /// Existing path moved aside (second element, relative) to restore the scrapped one
+    pub backed_up: Vec<(PathBuf, PathBuf)>,
+    /// Different content on both sides; the scrapped copy stays in scrap
+    pub conflicts: Vec<PathBuf>,
+}
+
+impl MergeReport {
+    /// Whether everything in the scrapped tree has found its place
+    pub fn is_complete(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// `name.bak`, or `name.bak.N` when that is taken
+fn backup_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let first = path.with_file_name(format!("{}.bak", name));
+    if fs::symlink_metadata(&first).is_err() {
+        return first;
+    }
+    (1..)
+        .map(|n| path.with_file_name(format!("{}.bak.{}", name, n)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap()
+}
+
+fn same_content(a: &Path, b: &Path) -> bool {
+    let (meta_a, meta_b) = match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+        (Ok(meta_a), Ok(meta_b)) => (meta_a, meta_b),
+        _ => return false,
+    };
+    if meta_a.file_type().is_symlink() || meta_b.file_type().is_symlink() {
+        return meta_a.file_type().is_symlink()
+            && meta_b.file_type().is_symlink()
+            && fs::read_link(a).ok() == fs::read_link(b).ok();
+    }
+    meta_a.is_file()
+        && meta_b.is_file()
+        && meta_a.len() == meta_b.len()
+        && matches!((dedupe::hash_file(a), dedupe::hash_file(b)), (Ok(x), Ok(y)) if x == y)
+}
+
+/// Merge the scrapped tree at `source` into the existing directory `dest`. Restored and
+/// identical files leave `source`; conflicts stay, and empty directories are pruned.
+pub fn merge_into(source: &Path, dest: &Path, backup_existing: bool) -> Result<MergeReport> {
+    let mut report = MergeReport::default();
+
+    let mut walker = WalkDir::new(source).follow_links(false).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?.to_path_buf();
+        let target = dest.join(&relative);
+        let existing = fs::symlink_metadata(&target).ok();
+        let is_dir = entry.file_type().is_dir();
+
+        match existing {
+            // Missing: the whole subtree can go back at once
+            None => {
+                transfer::move_path(entry.path(), &target)?;
+                report.restored.push(relative);
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+            }
+            Some(meta) if is_dir && meta.is_dir() => {}
+            Some(_) if !is_dir && same_content(entry.path(), &target) => {
+                fs::remove_file(entry.path())?;
+                report.identical.push(relative);
+            }
+            Some(_) if backup_existing => {
+                let backup = backup_path(&target);
+                fs::rename(&target, &backup)?;
+                transfer::move_path(entry.path(), &target)?;
+                report.backed_up.push((relative, backup.strip_prefix(dest)?.to_path_buf()));
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+            }
+            Some(_) => {
+                report.conflicts.push(relative);
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+            }
+        }
+    }
+
+    // Drop directories the merge emptied, deepest first
+    for entry in WalkDir::new(source).follow_links(false).contents_first(true) {
+        let entry = entry?;
+        if entry.file_type().is_dir() && fs::read_dir(entry.path())?.next().is_none() {
+            fs::remove_dir(entry.path())?;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_restores_missing_and_reports_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("scrapped");
+        let dest = temp_dir.path().join("project");
+        fs::create_dir_all(source.join("src/new")).unwrap();
+        fs::write(source.join("src/new/mod.rs"), "new module").unwrap();
+        fs::write(source.join("src/lib.rs"), "old lib").unwrap();
+        fs::write(source.join("README.md"), "same").unwrap();
+        fs::create_dir_all(dest.join("src")).unwrap();
+        fs::write(dest.join("src/lib.rs"), "edited lib").unwrap();
+        fs::write(dest.join("README.md"), "same").unwrap();
+
+        let report = merge_into(&source, &dest, false).unwrap();
+        assert_eq!(report.restored, vec![PathBuf::from("src/new")]);
+        assert_eq!(report.identical, vec![PathBuf::from("README.md")]);
+        assert_eq!(report.conflicts, vec![PathBuf::from("src/lib.rs")]);
+        assert_eq!(fs::read_to_string(dest.join("src/new/mod.rs")).unwrap(), "new module");
+        assert_eq!(fs::read_to_string(dest.join("src/lib.rs")).unwrap(), "edited lib");
+        assert!(source.join("src/lib.rs").exists());
+        assert!(!source.join("README.md").exists());
+
+        let report = merge_into(&source, &dest, true).unwrap();
+        assert!(report.is_complete());
+        assert_eq!(report.backed_up, vec![(PathBuf::from("src/lib.rs"), PathBuf::from("src/lib.rs.bak"))]);
+        assert_eq!(fs::read_to_string(dest.join("src/lib.rs")).unwrap(), "old lib");
+        assert_eq!(fs::read_to_string(dest.join("src/lib.rs.bak")).unwrap(), "edited lib");
+        assert!(!source.exists());
+    }
+}
@@ -1,31 +1,78 @@
+pub mod dedupe;
+pub mod guard;
+pub mod hooks;
+pub mod merge;
+pub mod picker;
+pub mod report;
+pub mod retention;
 pub mod scrap_common;
+pub mod search;
+pub mod shred;
+pub mod tiering;
+pub mod transfer;
+pub mod trash;
 
 pub use scrap_common::{ScrapMetadata, ScrapEntry};
 
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use hooks::Hooks;
+use report::{EntryReport, Format};
+use scrap_common::HookAction;
+use serde_json::json;
+use shred::Shredder;
+use tiering::ScrapConfig;
 use log;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 /// Run scrap command with the given arguments
 pub fn run_scrap(args: Vec<String>) -> Result<()> {
+    let allow_protected = args.iter().any(|a| a == guard::OVERRIDE_FLAG);
+    let system_trash = args.iter().any(|a| a == trash::SYSTEM_TRASH_FLAG);
+    let mut args: Vec<String> = args.into_iter()
+        .filter(|a| a != guard::OVERRIDE_FLAG && a != trash::SYSTEM_TRASH_FLAG)
+        .collect();
+    let format = match args.iter().position(|a| a == report::FORMAT_FLAG) {
+        Some(i) => {
+            let value = args.get(i + 1).cloned().ok_or_else(|| anyhow::anyhow!("--format requires a value"))?;
+            args.drain(i..=i + 1);
+            Format::parse(&value)?
+        }
+        None => Format::Human,
+    };
+
     if args.is_empty() {
         // Default action: list contents
-        return list_scrap_contents(None);
+        enforce_retention(None, format)?;
+        return list_scrap_contents(None, format);
     }
 
     let mut args_iter = args.iter();
     let first_arg = args_iter.next().unwrap();
 
+    if matches!(first_arg.as_str(), "list" | "clean" | "find" | "archive") {
+        enforce_retention(None, format)?;
+    }
+
     match first_arg.as_str() {
+        "config" => configure_retention(&args[1..], format),
+        "rm" => {
+            if args.len() < 2 {
+                anyhow::bail!("rm requires the name of a scrapped item");
+            }
+            remove_items(&args[1..], format)
+        }
         "list" => {
+            if args.contains(&"--duplicates".to_string()) {
+                return list_duplicates(format);
+            }
             let sort_option = if args.len() > 2 && args[1] == "--sort" {
                 Some(&args[2])
             } else {
                 None
             };
-            list_scrap_contents(sort_option.map(|s| s.as_str()))
+            list_scrap_contents(sort_option.map(|s| s.as_str()), format)
         }
         "clean" => {
             let days = if args.len() > 2 && args[1] == "--days" {
@@ -34,11 +81,11 @@ pub fn run_scrap(args: Vec<String>) -> Result<()> {
                 30
             };
             let dry_run = args.contains(&"--dry-run".to_string());
-            clean_scrap_folder(days, dry_run)
+            clean_scrap_folder(days, dry_run, secure_passes(&args)?, format)
         }
         "purge" => {
             let force = args.contains(&"--force".to_string());
-            purge_scrap_folder(force)
+            purge_scrap_folder(force, secure_passes(&args)?, format)
         }
         "find" => {
             if args.len() < 2 {
@@ -46,7 +93,11 @@ pub fn run_scrap(args: Vec<String>) -> Result<()> {
             }
             let pattern = &args[1];
             let content_search = args.contains(&"--content".to_string());
-            find_in_scrap(pattern, content_search)
+            let max_size = match args.iter().position(|a| a == "--max-size").and_then(|i| args.get(i + 1)) {
+                Some(size) => size.parse().with_context(|| format!("Invalid --max-size: {}", size))?,
+                None => search::DEFAULT_MAX_SIZE,
+            };
+            find_in_scrap(pattern, content_search, max_size, format)
         }
         "archive" => {
             let output = if args.len() > 2 && args[1] == "--output" {
@@ -55,12 +106,71 @@ pub fn run_scrap(args: Vec<String>) -> Result<()> {
                 None
             };
             let remove = args.contains(&"--remove".to_string());
-            archive_scrap_folder(output.map(|s| s.as_str()), remove)
+            if args.contains(&"--tier".to_string()) {
+                let days = args.iter()
+                    .position(|a| a == "--days")
+                    .and_then(|i| args.get(i + 1))
+                    .and_then(|d| d.parse().ok());
+                return tier_scrap_folder(days, format);
+            }
+            archive_scrap_folder(output.map(|s| s.as_str()), remove, format)
         }
-        path => {
-            // Treat as file path to scrap
-            let path_buf = PathBuf::from(path);
-            scrap_file_or_directory(&path_buf)
+        _ => {
+            // Treat the arguments as paths to scrap
+            let scrap_dir = get_scrap_directory()?;
+            let config = scrap_config(&scrap_dir);
+            let system_trash = system_trash || config.system_trash;
+            let hooks = Hooks::new(&scrap_dir, config.hooks, HookAction::Scrap)
+                .with(Vec::new(), args.iter().map(PathBuf::from).collect());
+            hooks.before()?;
+
+            let mut scrapped = Vec::new();
+            let mut failure = None;
+            for path in &args {
+                match scrap_file_or_directory(Path::new(path), allow_protected, system_trash, format) {
+                    Ok(entry) => scrapped.push(entry),
+                    Err(e) => {
+                        failure = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            // Whatever made it into scrap is reported, even if a later path failed
+            if !scrapped.is_empty() {
+                let metadata = ScrapMetadata::load(&scrap_dir).unwrap_or_else(|_| ScrapMetadata::new());
+                let names: Vec<String> = scrapped.iter().map(|entry| entry.name.clone()).collect();
+                hooks.after(entries_named(&metadata, &names));
+            }
+            if let Some(e) = failure {
+                return Err(e);
+            }
+            format.emit(json!({ "scrapped": scrapped }))
+        }
+    }
+}
+
+/// Overwrite passes for `--secure` (`--passes N`, default 1); `None` without `--secure`
+fn secure_passes(args: &[String]) -> Result<Option<u32>> {
+    if !args.iter().any(|a| a == "--secure") {
+        return Ok(None);
+    }
+    match args.iter().position(|a| a == "--passes").and_then(|i| args.get(i + 1)) {
+        Some(passes) => match passes.parse::<u32>() {
+            Ok(passes) if passes > 0 => Ok(Some(passes)),
+            _ => anyhow::bail!("Invalid --passes: {} (expected a positive number)", passes),
+        },
+        None => Ok(Some(shred::DEFAULT_PASSES)),
+    }
+}
+
+/// Summarise a secure removal for people; the JSON result carries the full report
+fn say_shred_report(report: &shred::ShredReport, format: Format) {
+    format.say(format!("Securely overwrote {} file(s) ({} pass(es))", report.shredded, report.passes));
+    if !report.not_secure.is_empty() {
+        format.say(format!("{} file(s) could not be securely removed (deleted anyway):", report.not_secure.len()));
+        for item in &report.not_secure {
+            format.say(format!("  {}: {}", item.path.display(), item.reason));
         }
     }
 }
@@ -75,10 +185,28 @@ pub fn run_unscrap(args: Vec<String>) -> Result<()> {
         return restore_last_item(&mut metadata, &scrap_dir);
     }
 
+    if args.iter().any(|a| a == "--interactive") {
+        let force = args.iter().any(|a| a == "--force");
+        let to_path = args.iter().position(|a| a == "--to").and_then(|i| args.get(i + 1)).map(PathBuf::from);
+        return restore_interactively(&mut metadata, &scrap_dir, to_path, force);
+    }
+
+    if args.iter().any(|a| a == "--all") {
+        let force = args.iter().any(|a| a == "--force");
+        let to_path = args.iter().position(|a| a == "--to").and_then(|i| args.get(i + 1)).map(PathBuf::from);
+        let since = match args.iter().position(|a| a == "--since").and_then(|i| args.get(i + 1)) {
+            Some(since) => Some(parse_since(since)?),
+            None => None,
+        };
+        return restore_all(&mut metadata, &scrap_dir, since, to_path, force);
+    }
+
     let mut args_iter = args.iter();
     let name = args_iter.next().unwrap();
     let mut to_path = None;
     let mut force = false;
+    let mut merge = false;
+    let mut backup_existing = false;
 
     // Parse remaining arguments
     let mut i = 1;
@@ -96,10 +224,21 @@ pub fn run_unscrap(args: Vec<String>) -> Result<()> {
                 force = true;
                 i += 1;
             }
+            "--merge" => {
+                merge = true;
+                i += 1;
+            }
+            "--backup-existing" => {
+                backup_existing = true;
+                i += 1;
+            }
             _ => i += 1,
         }
     }
 
+    if merge {
+        return merge_item(&mut metadata, &scrap_dir, name, to_path, backup_existing);
+    }
     restore_item(&mut metadata, &scrap_dir, name, to_path, force)
 }
 
@@ -108,6 +247,30 @@ fn get_scrap_directory() -> Result<PathBuf> {
     Ok(current_dir.join(".scrap"))
 }
 
+/// Tiering settings for the project owning `scrap_dir`
+fn scrap_config(scrap_dir: &Path) -> ScrapConfig {
+    scrap_dir.parent().map(ScrapConfig::load).unwrap_or_default()
+}
+
+/// The configured and registered hooks for one operation on `scrap_dir`
+fn hooks_for(scrap_dir: &Path, action: HookAction) -> Hooks {
+    Hooks::new(scrap_dir, scrap_config(scrap_dir).hooks, action)
+}
+
+/// Metadata of the named entries, skipping names without one
+fn entries_named(metadata: &ScrapMetadata, names: &[String]) -> Vec<ScrapEntry> {
+    names.iter().filter_map(|name| metadata.get_entry(name).cloned()).collect()
+}
+
+/// Run the configured auto-archive policy, reporting anything it moved
+fn apply_tiering(scrap_dir: &Path, metadata: &mut ScrapMetadata, format: Format) {
+    let archived = tiering::apply_policy(scrap_dir, metadata, &scrap_config(scrap_dir));
+    if !archived.is_empty() {
+        log::info!("Auto-archived {} scrap item(s): {}", archived.len(), archived.join(", "));
+        format.notice(format!("Auto-archived {} item(s) into .scrap/{}/", archived.len(), tiering::ARCHIVE_DIR));
+    }
+}
+
 fn ensure_scrap_directory() -> Result<PathBuf> {
     let scrap_dir = get_scrap_directory()?;
     if !scrap_dir.exists() {
@@ -139,11 +302,14 @@ fn update_gitignore(scrap_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn scrap_file_or_directory(path: &Path) -> Result<()> {
+/// Move one path into `.scrap`, or the system trash, returning its new entry
+fn scrap_file_or_directory(path: &Path, allow_protected: bool, system_trash: bool, format: Format) -> Result<EntryReport> {
     if !path.exists() {
         anyhow::bail!("Path does not exist: {}", path.display());
     }
 
+    check_guard(path, allow_protected, format)?;
+
     let scrap_dir = ensure_scrap_directory()?;
     let mut metadata = ScrapMetadata::load(&scrap_dir)?;
 
@@ -152,27 +318,259 @@ fn scrap_file_or_directory(path: &Path) -> Result<()> {
         .to_string_lossy();
 
     // Generate unique name if file already exists in scrap
-    let scrapped_name = generate_unique_name(&scrap_dir, &file_name);
+    let scrapped_name = generate_unique_name(&scrap_dir, &metadata, &file_name);
     let dest_path = scrap_dir.join(&scrapped_name);
 
+    if system_trash {
+        let trash_path = trash::move_to_trash(path)?;
+        metadata.add_entry(&scrapped_name, path.to_path_buf());
+        if let Some(entry) = metadata.entries.get_mut(&scrapped_name) {
+            entry.trash_path = Some(trash_path.clone());
+        }
+        metadata.save(&scrap_dir)?;
+
+        log::info!("Scrapped file: {} -> {} (system trash)", path.display(), trash_path.display());
+        format.say(format!("Moved {} to the system trash ({})", path.display(), trash_path.display()));
+        let entry = EntryReport::new(&scrap_dir, &metadata.entries[&scrapped_name]);
+        apply_retention(&scrap_dir, &mut metadata, Some(&scrapped_name), format)?;
+        return Ok(entry);
+    }
+
+    let content_hash = if fs::symlink_metadata(path)?.is_file() {
+        Some(dedupe::hash_file(path)?)
+    } else {
+        None
+    };
+
+    // Identical content already in scrap is linked to rather than stored again
+    let stored = content_hash.as_deref()
+        .and_then(|hash| dedupe::find_stored(&scrap_dir, &metadata, hash))
+        .map(|entry| entry.scrapped_name.clone());
+    let linked = match &stored {
+        Some(existing) => match fs::hard_link(scrap_dir.join(existing), &dest_path) {
+            Ok(()) => {
+                fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {} after linking it into scrap", path.display()))?;
+                true
+            }
+            Err(e) => {
+                log::warn!("Could not link {} to .scrap/{}, storing a copy: {}", path.display(), existing, e);
+                false
+            }
+        },
+        None => false,
+    };
+
     // Move file/directory to scrap
-    fs::rename(path, &dest_path)
-        .with_context(|| format!("Failed to move {} to scrap", path.display()))?;
+    if !linked {
+        transfer::move_path(path, &dest_path)
+            .with_context(|| format!("Failed to move {} to scrap", path.display()))?;
+    }
 
     // Update metadata
     metadata.add_entry(&scrapped_name, path.to_path_buf());
+    if let Some(entry) = metadata.entries.get_mut(&scrapped_name) {
+        entry.content_hash = content_hash;
+    }
     metadata.save(&scrap_dir)?;
 
     log::info!("Scrapped file: {} -> .scrap/{}", path.display(), scrapped_name);
-    println!("Moved {} to .scrap/{}", path.display(), scrapped_name);
+    match stored.filter(|_| linked) {
+        Some(existing) => format.say(format!(
+            "Moved {} to .scrap/{} (same content as .scrap/{}, stored once)",
+            path.display(), scrapped_name, existing
+        )),
+        None => format.say(format!("Moved {} to .scrap/{}", path.display(), scrapped_name)),
+    }
+    let entry = EntryReport::new(&scrap_dir, &metadata.entries[&scrapped_name]);
+
+    apply_tiering(&scrap_dir, &mut metadata, format);
+    apply_retention(&scrap_dir, &mut metadata, Some(&scrapped_name), format)?;
+    Ok(entry)
+}
+
+/// Enforce the retention policy on the project's `.scrap`, if there is one, after
+/// dropping entries whose item has left the system trash
+fn enforce_retention(keep: Option<&str>, format: Format) -> Result<()> {
+    let scrap_dir = get_scrap_directory()?;
+    if !scrap_dir.join(".metadata.json").exists() {
+        return Ok(());
+    }
+    let mut metadata = ScrapMetadata::load(&scrap_dir)?;
+    let gone = trash::prune_missing(&mut metadata);
+    if !gone.is_empty() {
+        metadata.save(&scrap_dir)?;
+        format.notice(format!("No longer in the system trash, dropped from scrap: {}", gone.join(", ")));
+    }
+    apply_retention(&scrap_dir, &mut metadata, keep, format)
+}
+
+/// Remove whatever the retention policy says must go, oldest first, reporting it
+fn apply_retention(scrap_dir: &Path, metadata: &mut ScrapMetadata, keep: Option<&str>, format: Format) -> Result<()> {
+    if metadata.policy.is_empty() {
+        return Ok(());
+    }
+    let items: Vec<retention::Item> = metadata.entries.values()
+        .map(|entry| retention::Item {
+            name: entry.scrapped_name.clone(),
+            scrapped_at: entry.scrapped_at,
+            size: retention::item_size(scrap_dir, entry),
+        })
+        .collect();
+    let evicted = retention::plan(&metadata.policy, &items, keep, Utc::now());
+    if evicted.is_empty() {
+        return Ok(());
+    }
+
+    let hooks = hooks_for(scrap_dir, HookAction::Retention).with(entries_named(metadata, &evicted), Vec::new());
+    hooks.before()?;
+    for name in &evicted {
+        remove_scrapped(scrap_dir, metadata, name, None)?;
+    }
+    metadata.save(scrap_dir)?;
+    hooks.after(Vec::new());
+    log::info!("Retention policy removed {} scrap item(s): {}", evicted.len(), evicted.join(", "));
+    format.notice(format!("Retention policy ({}) removed {} item(s): {}", metadata.policy.describe(), evicted.len(), evicted.join(", ")));
     Ok(())
 }
 
-fn generate_unique_name(scrap_dir: &Path, base_name: &str) -> String {
+/// Delete a scrapped item, from its archive if it has been tiered, and drop its entry.
+/// With a shredder its content is overwritten first.
+fn remove_scrapped(scrap_dir: &Path, metadata: &mut ScrapMetadata, name: &str, shredder: Option<&mut Shredder>) -> Result<()> {
+    let file_path = scrap_dir.join(name);
+    if let Some(trash_path) = metadata.get_entry(name).and_then(|e| e.trash_path.clone()) {
+        trash::delete(&trash_path, shredder)?;
+        metadata.remove_entry(name);
+        return Ok(());
+    }
+    let archive = metadata.get_entry(name).and_then(|e| e.archive.clone());
+    match (archive, shredder) {
+        (Some(archive), Some(shredder)) => shredder.remove_archived(&archive, name)?,
+        (Some(archive), None) => tiering::remove(scrap_dir, &archive, name)?,
+        (None, Some(shredder)) => {
+            if fs::symlink_metadata(&file_path).is_ok() {
+                shredder.remove(&file_path)?;
+            }
+        }
+        (None, None) => {
+            if file_path.is_dir() {
+                fs::remove_dir_all(&file_path)?;
+            } else if file_path.exists() {
+                fs::remove_file(&file_path)?;
+            }
+        }
+    }
+    metadata.remove_entry(name);
+    Ok(())
+}
+
+/// `scrap rm`: delete scrapped items for good, one name at a time
+fn remove_items(names: &[String], format: Format) -> Result<()> {
+    let scrap_dir = get_scrap_directory()?;
+    let mut metadata = ScrapMetadata::load(&scrap_dir)?;
+
+    let mut removed = Vec::new();
+    for name in names {
+        let (entry, hooks) = match metadata.get_entry(name) {
+            Some(entry) => (
+                EntryReport::new(&scrap_dir, entry),
+                hooks_for(&scrap_dir, HookAction::Remove).with(vec![entry.clone()], Vec::new()),
+            ),
+            None => anyhow::bail!("Item not found in scrap: {}", name),
+        };
+        hooks.before()?;
+        remove_scrapped(&scrap_dir, &mut metadata, name, None)?;
+        metadata.save(&scrap_dir)?;
+        hooks.after(Vec::new());
+        log::info!("Removed scrapped item: {}", name);
+        format.say(format!("Removed: {}", name));
+        removed.push(entry);
+    }
+    format.emit(json!({ "removed": removed }))
+}
+
+/// Byte count with an optional K, M or G suffix (powers of 1024)
+fn parse_size(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&value[..value.len() - 1], 1024),
+        Some('M') => (&value[..value.len() - 1], 1024 * 1024),
+        Some('G') => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    let number: u64 = digits.trim().parse().with_context(|| format!("Invalid size: {}", value))?;
+    Ok(number * multiplier)
+}
+
+/// `scrap config`: show the retention policy, or change it and enforce it right away.
+/// A limit of 0 removes that limit; `--clear` removes them all.
+fn configure_retention(args: &[String], format: Format) -> Result<()> {
+    let scrap_dir = ensure_scrap_directory()?;
+    let mut metadata = ScrapMetadata::load(&scrap_dir)?;
+    let value = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1));
+
+    let mut changed = false;
+    if args.iter().any(|a| a == "--clear") {
+        metadata.policy = retention::RetentionPolicy::default();
+        changed = true;
+    }
+    if let Some(days) = value("--max-age-days") {
+        let days: u32 = days.parse().with_context(|| format!("Invalid --max-age-days: {}", days))?;
+        metadata.policy.max_age_days = (days > 0).then_some(days);
+        changed = true;
+    }
+    if let Some(size) = value("--max-size") {
+        let size = parse_size(size)?;
+        metadata.policy.max_total_size = (size > 0).then_some(size);
+        changed = true;
+    }
+    if let Some(items) = value("--max-items") {
+        let items: usize = items.parse().with_context(|| format!("Invalid --max-items: {}", items))?;
+        metadata.policy.max_items = (items > 0).then_some(items);
+        changed = true;
+    }
+
+    if changed {
+        metadata.save(&scrap_dir)?;
+    }
+    format.say(format!("Scrap retention: {}", metadata.policy.describe()));
+    format.emit(json!({ "policy": metadata.policy, "changed": changed }))?;
+    if changed {
+        apply_retention(&scrap_dir, &mut metadata, None, format)?;
+    }
+    Ok(())
+}
+
+/// Refuse protected paths unless overridden, and warn about paths that are still referenced
+fn check_guard(path: &Path, allow_protected: bool, format: Format) -> Result<()> {
+    let project_root = std::env::current_dir()?;
+    let config = ScrapConfig::load(&project_root);
+
+    if let Some(reason) = guard::protection_reason(path, &project_root, &config)? {
+        if !allow_protected {
+            anyhow::bail!(
+                "Refusing to scrap {}: {}. Pass {} to scrap it anyway",
+                path.display(), reason, guard::OVERRIDE_FLAG
+            );
+        }
+        log::warn!("Scrapping protected path {} ({})", path.display(), reason);
+        format.notice(format!("Warning: scrapping protected path {} ({})", path.display(), reason));
+    }
+
+    for reference in guard::references(path, &project_root) {
+        log::warn!("Scrapping referenced path {}: {}", path.display(), reference);
+        format.notice(format!("Warning: {} is still referenced: {}", path.display(), reference));
+    }
+
+    Ok(())
+}
+
+fn generate_unique_name(scrap_dir: &Path, metadata: &ScrapMetadata, base_name: &str) -> String {
     let mut name = base_name.to_string();
     let mut counter = 1;
     
-    while scrap_dir.join(&name).exists() {
+    // Archived items are no longer on disk but still own their name
+    while scrap_dir.join(&name).exists() || metadata.entries.contains_key(&name) {
         if let Some(dot_pos) = base_name.rfind('.') {
             let (stem, ext) = base_name.split_at(dot_pos);
             name = format!("{}_{}{}", stem, counter, ext);
@@ -185,107 +583,196 @@ fn generate_unique_name(scrap_dir: &Path, base_name: &str) -> String {
     name
 }
 
-fn list_scrap_contents(sort_option: Option<&str>) -> Result<()> {
+fn list_scrap_contents(sort_option: Option<&str>, format: Format) -> Result<()> {
     let scrap_dir = get_scrap_directory()?;
     if !scrap_dir.exists() {
         fs::create_dir_all(&scrap_dir)
             .with_context(|| format!("Failed to create scrap directory: {}", scrap_dir.display()))?;
         update_gitignore(&scrap_dir)?;
         log::info!("Scrap folder is empty (new)");
-        println!("Scrap folder is empty");
-        return Ok(());
+        format.say("Scrap folder is empty");
+        return format.emit(json!({ "entries": [], "total_size": 0 }));
     }
 
-    let metadata = ScrapMetadata::load(&scrap_dir)?;
+    let mut metadata = ScrapMetadata::load(&scrap_dir)?;
+    apply_tiering(&scrap_dir, &mut metadata, format);
     if metadata.entries.is_empty() {
         log::info!("Scrap folder is empty (exists but no entries)");
-        println!("Scrap folder is empty");
-        return Ok(());
+        format.say("Scrap folder is empty");
+        return format.emit(json!({ "entries": [], "total_size": 0 }));
     }
 
-    let mut entries: Vec<_> = metadata.entries.values().collect();
+    let mut entries: Vec<EntryReport> = metadata.entries.values()
+        .map(|entry| EntryReport::new(&scrap_dir, entry))
+        .collect();
     
     match sort_option {
         Some("date") => entries.sort_by_key(|e| e.scrapped_at),
-        Some("name") => entries.sort_by_key(|e| &e.scrapped_name),
-        Some("size") => {
-            // For size sorting, we'd need to get actual file sizes
-            entries.sort_by_key(|e| &e.scrapped_name); // Fallback to name
-        }
+        Some("name") => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+        Some("size") => entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name))),
         _ => entries.sort_by_key(|e| e.scrapped_at),
     }
 
+    if format.is_json() {
+        let total_size: u64 = entries.iter().map(|e| e.size).sum();
+        return format.emit(json!({ "entries": entries, "total_size": total_size }));
+    }
+
     println!("Scrapped files:");
     for entry in entries {
-        println!("  {} (from {}) - {}", 
-                 entry.scrapped_name, 
+        let archived = match (&entry.archive, &entry.trash_path) {
+            (Some(archive), _) => format!(" [archived: {}]", archive),
+            (None, Some(trash_path)) => format!(" [system trash: {}]", trash_path.display()),
+            (None, None) => String::new(),
+        };
+        println!("  {} (from {}) - {}{}", 
+                 entry.name, 
                  entry.original_path.display(),
-                 entry.scrapped_at.format("%Y-%m-%d %H:%M:%S"));
+                 entry.scrapped_at.format("%Y-%m-%d %H:%M:%S"),
+                 archived);
     }
 
     Ok(())
 }
 
-fn clean_scrap_folder(days: u32, dry_run: bool) -> Result<()> {
+/// Report entries sharing content and the space deduplication saves
+fn list_duplicates(format: Format) -> Result<()> {
     let scrap_dir = get_scrap_directory()?;
-    if !scrap_dir.exists() {
-        println!("No .scrap directory found");
+    let metadata = ScrapMetadata::load(&scrap_dir)?;
+    let groups = dedupe::duplicates(&scrap_dir, &metadata);
+    let saved: u64 = groups.iter().map(|g| g.saved()).sum();
+    let reclaimable: u64 = groups.iter().map(|g| g.reclaimable()).sum();
+
+    if format.is_json() {
+        let groups: Vec<_> = groups.iter()
+            .map(|g| json!({
+                "hash": g.hash,
+                "size": g.size,
+                "entries": g.entries,
+                "copies": g.copies,
+                "saved": g.saved(),
+                "reclaimable": g.reclaimable(),
+            }))
+            .collect();
+        return format.emit(json!({ "groups": groups, "saved": saved, "reclaimable": reclaimable }));
+    }
+
+    if groups.is_empty() {
+        println!("No duplicate content in scrap");
         return Ok(());
     }
 
+    println!("Duplicate content:");
+    for group in &groups {
+        let storage = if group.copies == 1 {
+            "stored once".to_string()
+        } else {
+            format!("{} separate copies", group.copies)
+        };
+        println!("  {} ({} bytes, {} entries, {})", group.entries.join(", "), group.size, group.entries.len(), storage);
+    }
+
+    println!("Space saved by deduplication: {} bytes", saved);
+    if reclaimable > 0 {
+        println!("Held in separate copies scrapped before deduplication: {} bytes", reclaimable);
+    }
+    Ok(())
+}
+
+fn clean_scrap_folder(days: u32, dry_run: bool, secure: Option<u32>, format: Format) -> Result<()> {
+    let scrap_dir = get_scrap_directory()?;
+    if !scrap_dir.exists() {
+        format.say("No .scrap directory found");
+        return format.emit(json!({ "days": days, "dry_run": dry_run, "removed": [] }));
+    }
+
     let mut metadata = ScrapMetadata::load(&scrap_dir)?;
     let cutoff_date = Utc::now() - chrono::Duration::days(days as i64);
-    let mut removed_count = 0;
 
-    let entries_to_remove: Vec<_> = metadata.entries.iter()
-        .filter(|(_, entry)| entry.scrapped_at < cutoff_date)
-        .map(|(name, _)| name.clone())
+    let mut entries_to_remove: Vec<_> = metadata.entries.values()
+        .filter(|entry| entry.scrapped_at < cutoff_date)
+        .map(|entry| EntryReport::new(&scrap_dir, entry))
         .collect();
+    entries_to_remove.sort_by_key(|e| e.scrapped_at);
+
+    let names: Vec<String> = entries_to_remove.iter().map(|entry| entry.name.clone()).collect();
+    let hooks = hooks_for(&scrap_dir, HookAction::Clean).with(entries_named(&metadata, &names), Vec::new());
+    let hooked = !dry_run && !names.is_empty();
+    if hooked {
+        hooks.before()?;
+    }
 
-    for name in entries_to_remove {
-        let file_path = scrap_dir.join(&name);
+    let mut shredder = secure.map(|passes| Shredder::new(&scrap_dir, passes));
+    for entry in &entries_to_remove {
         if dry_run {
-            println!("Would remove: {}", name);
+            format.say(format!("Would remove: {}", entry.name));
         } else {
-            if file_path.exists() {
-                if file_path.is_dir() {
-                    fs::remove_dir_all(&file_path)?;
-                } else {
-                    fs::remove_file(&file_path)?;
-                }
-            }
-            metadata.remove_entry(&name);
-            println!("Removed: {}", name);
+            remove_scrapped(&scrap_dir, &mut metadata, &entry.name, shredder.as_mut())?;
+            format.say(format!("Removed: {}", entry.name));
         }
-        removed_count += 1;
     }
+    let removed_count = entries_to_remove.len();
 
     if !dry_run && removed_count > 0 {
         metadata.save(&scrap_dir)?;
     }
+    if hooked {
+        hooks.after(Vec::new());
+    }
 
     if dry_run {
-        println!("Would remove {} items older than {} days", removed_count, days);
+        format.say(format!("Would remove {} items older than {} days", removed_count, days));
     } else {
-        println!("Removed {} items older than {} days", removed_count, days);
+        format.say(format!("Removed {} items older than {} days", removed_count, days));
+    }
+    let shred_report = shredder.map(|shredder| shredder.report);
+    if let Some(report) = shred_report.as_ref().filter(|_| !dry_run) {
+        say_shred_report(report, format);
     }
 
-    Ok(())
+    format.emit(json!({ "days": days, "dry_run": dry_run, "removed": entries_to_remove, "secure": shred_report }))
 }
 
-fn purge_scrap_folder(force: bool) -> Result<()> {
+fn purge_scrap_folder(force: bool, secure: Option<u32>, format: Format) -> Result<()> {
     let scrap_dir = get_scrap_directory()?;
     if !scrap_dir.exists() {
-        println!("No .scrap directory found");
-        return Ok(());
+        format.say("No .scrap directory found");
+        return format.emit(json!({ "removed": [] }));
     }
 
     if !force {
         anyhow::bail!("Use --force to confirm purging all scrapped files");
     }
 
+    let mut shredder = secure.map(|passes| Shredder::new(&scrap_dir, passes));
+    let (purged, removed_count) = purge_contents(&scrap_dir, shredder.as_mut(), true)?;
+    format.say(format!("Purged {} items from scrap folder", removed_count));
+    let shred_report = shredder.map(|shredder| shredder.report);
+    if let Some(report) = &shred_report {
+        say_shred_report(report, format);
+    }
+    format.emit(json!({ "removed": purged, "removed_paths": removed_count, "secure": shred_report }))
+}
+
+/// Empty `.scrap`, keeping the retention policy. Items in the system trash are deleted
+/// from it with `include_trash` and otherwise stay tracked. Returns the tracked entries
+/// that were removed and the number of paths deleted.
+fn purge_contents(scrap_dir: &Path, mut shredder: Option<&mut Shredder>, include_trash: bool) -> Result<(Vec<EntryReport>, usize)> {
+    // Report the tracked entries before they go
+    let metadata = ScrapMetadata::load(scrap_dir).unwrap_or_else(|_| ScrapMetadata::new());
+    let mut purged: Vec<EntryReport> = metadata.entries.values()
+        .filter(|entry| include_trash || entry.trash_path.is_none())
+        .map(|entry| EntryReport::new(scrap_dir, entry))
+        .collect();
+    purged.sort_by_key(|e| e.scrapped_at);
+    let hooks = hooks_for(scrap_dir, HookAction::Purge).with(
+        metadata.entries.values().filter(|entry| include_trash || entry.trash_path.is_none()).cloned().collect(),
+        Vec::new(),
+    );
+    hooks.before()?;
+
     // Remove all files and subdirectories in .scrap except .metadata.json
-    let entries = fs::read_dir(&scrap_dir)?;
+    let entries = fs::read_dir(scrap_dir)?;
     let mut removed_count = 0;
 
     for entry in entries {
@@ -294,7 +781,9 @@ fn purge_scrap_folder(force: bool) -> Result<()> {
         let file_name = entry.file_name();
         
         if file_name != ".metadata.json" {
-            if path.is_dir() {
+            if let Some(shredder) = shredder.as_deref_mut() {
+                shredder.remove(&path)?;
+            } else if path.is_dir() {
                 fs::remove_dir_all(&path)?;
             } else {
                 fs::remove_file(&path)?;
@@ -303,56 +792,120 @@ fn purge_scrap_folder(force: bool) -> Result<()> {
         }
     }
 
-    // Clear metadata
-    let empty_metadata = ScrapMetadata::new();
-    empty_metadata.save(&scrap_dir)?;
+    // Clear metadata, keeping the retention policy
+    let mut empty_metadata = ScrapMetadata::new();
+    empty_metadata.policy = metadata.policy;
+    for (name, entry) in metadata.entries {
+        match &entry.trash_path {
+            Some(trash_path) if include_trash => {
+                trash::delete(trash_path, shredder.as_deref_mut())?;
+                removed_count += 1;
+            }
+            Some(_) => {
+                empty_metadata.entries.insert(name, entry);
+            }
+            None => {}
+        }
+    }
+    empty_metadata.save(scrap_dir)?;
+    hooks.after(Vec::new());
 
-    println!("Purged {} items from scrap folder", removed_count);
-    Ok(())
+    Ok((purged, removed_count))
 }
 
-fn find_in_scrap(pattern: &str, content_search: bool) -> Result<()> {
+fn find_in_scrap(pattern: &str, content_search: bool, max_size: u64, format: Format) -> Result<()> {
     let scrap_dir = get_scrap_directory()?;
     if !scrap_dir.exists() {
-        println!("No .scrap directory found");
-        return Ok(());
+        format.say("No .scrap directory found");
+        return format.emit(json!({ "pattern": pattern, "matches": [] }));
     }
 
+    let regex = regex::Regex::new(pattern).with_context(|| format!("Invalid search pattern: {}", pattern))?;
     let metadata = ScrapMetadata::load(&scrap_dir)?;
     let mut found_count = 0;
+    let mut line_count = 0;
+    let (mut skipped_large, mut skipped_binary, mut archived) = (0, 0, 0);
+    let mut matches = Vec::new();
 
-    for (name, entry) in &metadata.entries {
-        let matches = if content_search {
-            // For content search, we'd need to read file contents
-            // For now, just match filename
-            name.contains(pattern) || entry.original_path.to_string_lossy().contains(pattern)
-        } else {
-            name.contains(pattern) || entry.original_path.to_string_lossy().contains(pattern)
-        };
+    let mut entries: Vec<_> = metadata.entries.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (name, entry) in entries {
+        let name_matches = regex.is_match(name) || regex.is_match(&entry.original_path.to_string_lossy());
 
-        if matches {
-            println!("{} (from {}) - {}", 
+        let mut content = search::ContentSearch::default();
+        if content_search {
+            // Archived items live inside the monthly tarballs and are not searched
+            if entry.archive.is_some() {
+                archived += 1;
+            } else {
+                let item = scrap_dir.join(name);
+                if item.symlink_metadata().is_ok() {
+                    content = search::search_item(&item, &regex, max_size)?;
+                }
+            }
+            skipped_large += content.skipped_large;
+            skipped_binary += content.skipped_binary;
+        }
+
+        if name_matches || !content.matches.is_empty() {
+            format.say(format!("{} (from {}) - {}", 
                      name, 
                      entry.original_path.display(),
-                     entry.scrapped_at.format("%Y-%m-%d %H:%M:%S"));
+                     entry.scrapped_at.format("%Y-%m-%d %H:%M:%S")));
+            for found in &content.matches {
+                format.say(format!("  {}:{}: {}", found.path.display(), found.line, found.snippet));
+            }
             found_count += 1;
+            line_count += content.matches.len();
+
+            let lines: Vec<_> = content.matches.iter()
+                .map(|found| json!({ "path": found.path, "line": found.line, "snippet": found.snippet }))
+                .collect();
+            matches.push(json!({
+                "entry": EntryReport::new(&scrap_dir, entry),
+                "name_match": name_matches,
+                "lines": lines,
+            }));
         }
     }
 
+    if format.is_json() {
+        return format.emit(json!({
+            "pattern": pattern,
+            "content": content_search,
+            "matches": matches,
+            "skipped_large": skipped_large,
+            "skipped_binary": skipped_binary,
+            "archived_not_searched": archived,
+        }));
+    }
+
     if found_count == 0 {
         println!("No matching files found");
+    } else if content_search {
+        println!("Found {} matching items ({} matching lines)", found_count, line_count);
     } else {
         println!("Found {} matching files", found_count);
     }
+    if skipped_large > 0 {
+        println!("Skipped {} file(s) larger than {} bytes (raise with --max-size)", skipped_large, max_size);
+    }
+    if skipped_binary > 0 {
+        println!("Skipped {} binary file(s)", skipped_binary);
+    }
+    if archived > 0 {
+        println!("{} archived item(s) were not searched; restore them with unscrap to search their contents", archived);
+    }
 
     Ok(())
 }
 
-fn archive_scrap_folder(output: Option<&str>, remove: bool) -> Result<()> {
+fn archive_scrap_folder(output: Option<&str>, remove: bool, format: Format) -> Result<()> {
     let scrap_dir = get_scrap_directory()?;
     if !scrap_dir.exists() {
-        println!("No .scrap directory found");
-        return Ok(());
+        format.say("No .scrap directory found");
+        return format.emit(json!({ "archive": null, "removed": [] }));
     }
 
     let archive_name = output.unwrap_or("scrap-archive.tar.gz");
@@ -366,14 +919,45 @@ fn archive_scrap_folder(output: Option<&str>, remove: bool) -> Result<()> {
     tar.append_dir_all("scrap", &scrap_dir)?;
     tar.finish()?;
 
-    println!("Created archive: {}", archive_name);
+    format.say(format!("Created archive: {}", archive_name));
 
+    let mut removed = Vec::new();
     if remove {
-        purge_scrap_folder(true)?;
-        println!("Removed all files from scrap folder");
+        let (purged, removed_count) = purge_contents(&scrap_dir, None, false)?;
+        format.say(format!("Purged {} items from scrap folder", removed_count));
+        format.say("Removed all files from scrap folder");
+        removed = purged;
     }
 
-    Ok(())
+    format.emit(json!({ "archive": archive_name, "removed": removed }))
+}
+
+/// Compress items older than `days` (default: the configured policy) into monthly archives
+fn tier_scrap_folder(days: Option<u32>, format: Format) -> Result<()> {
+    let scrap_dir = get_scrap_directory()?;
+    if !scrap_dir.exists() {
+        format.say("No .scrap directory found");
+        return format.emit(json!({ "days": days, "archived": [] }));
+    }
+
+    let days = match days.or(scrap_config(&scrap_dir).archive_after_days) {
+        Some(days) => days,
+        None => anyhow::bail!("No archive age given: pass --days N or set tools.scrap.archive_after_days in .wsb/state.json"),
+    };
+
+    let mut metadata = ScrapMetadata::load(&scrap_dir)?;
+    let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+    let archived = tiering::archive_older_than(&scrap_dir, &mut metadata, cutoff)?;
+
+    let mut moved = Vec::new();
+    for name in &archived {
+        if let Some(archive) = metadata.get_entry(name).and_then(|e| e.archive.as_ref()) {
+            format.say(format!("Archived: {} -> .scrap/{}/{}", name, tiering::ARCHIVE_DIR, archive));
+            moved.push(json!({ "name": name, "archive": format!("{}/{}", tiering::ARCHIVE_DIR, archive) }));
+        }
+    }
+    format.say(format!("Archived {} items older than {} days", archived.len(), days));
+    format.emit(json!({ "days": days, "archived": moved }))
 }
 
 fn restore_last_item(metadata: &mut ScrapMetadata, scrap_dir: &Path) -> Result<()> {
@@ -392,6 +976,88 @@ fn restore_last_item(metadata: &mut ScrapMetadata, scrap_dir: &Path) -> Result<(
     }
 }
 
+/// Pick entries with the fuzzy picker and restore each one. With several entries,
+/// `--to` names the directory they are restored into.
+fn restore_interactively(metadata: &mut ScrapMetadata, scrap_dir: &Path, to_path: Option<PathBuf>, force: bool) -> Result<()> {
+    if metadata.entries.is_empty() {
+        println!("No items in scrap folder to restore");
+        return Ok(());
+    }
+
+    let chosen = picker::pick(metadata)?;
+    if chosen.is_empty() {
+        println!("Nothing selected");
+        return Ok(());
+    }
+
+    let into_directory = chosen.len() > 1;
+    restore_many(metadata, scrap_dir, &chosen, to_path, into_directory, force)
+}
+
+/// `--since` for `unscrap --all`: an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC)
+fn parse_since(since: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(since) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let date = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .with_context(|| format!("Invalid --since date: {} (expected YYYY-MM-DD)", since))?;
+    Ok(date.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(Utc).unwrap())
+}
+
+/// Restore every entry scrapped at or after `since` (all of them without it), oldest
+/// first so that the newest copy of a path wins with `--force`
+fn restore_all(metadata: &mut ScrapMetadata, scrap_dir: &Path, since: Option<DateTime<Utc>>, to_path: Option<PathBuf>, force: bool) -> Result<()> {
+    let mut entries: Vec<(DateTime<Utc>, String)> = metadata.entries.values()
+        .filter(|entry| since.map_or(true, |since| entry.scrapped_at >= since))
+        .map(|entry| (entry.scrapped_at, entry.scrapped_name.clone()))
+        .collect();
+    if entries.is_empty() {
+        println!("No items in scrap folder to restore");
+        return Ok(());
+    }
+    entries.sort();
+
+    let names: Vec<String> = entries.into_iter().map(|(_, name)| name).collect();
+    restore_many(metadata, scrap_dir, &names, to_path, true, force)?;
+    println!("Restored {} items", names.len());
+    Ok(())
+}
+
+/// Restore `names`, carrying on past failures and reporting them at the end.
+/// `to_path` is the destination itself, or with `into_directory` the directory to restore into.
+fn restore_many(metadata: &mut ScrapMetadata, scrap_dir: &Path, names: &[String], to_path: Option<PathBuf>, into_directory: bool, force: bool) -> Result<()> {
+    let mut failed = 0;
+    for name in names {
+        let destination = match (&to_path, into_directory) {
+            (Some(to), false) => Some(to.clone()),
+            (Some(to), true) => metadata.get_entry(name)
+                .and_then(|entry| entry.original_path.file_name().map(|file_name| to.join(file_name))),
+            (None, _) => None,
+        };
+        if let Err(e) = restore_item(metadata, scrap_dir, name, destination, force) {
+            eprintln!("Failed to restore {}: {}", name, e);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} items could not be restored", failed, names.len());
+    }
+    Ok(())
+}
+
+/// Clear the way for a restore with `--force`
+fn remove_existing(dest_path: &Path) -> Result<()> {
+    if dest_path.exists() {
+        if dest_path.is_dir() {
+            fs::remove_dir_all(dest_path)?;
+        } else {
+            fs::remove_file(dest_path)?;
+        }
+    }
+    Ok(())
+}
+
 fn restore_item(metadata: &mut ScrapMetadata, scrap_dir: &Path, name: &str, to_path: Option<PathBuf>, force: bool) -> Result<()> {
     let entry = metadata.get_entry(name)
         .ok_or_else(|| anyhow::anyhow!("Item not found in scrap: {}", name))?;
@@ -403,6 +1069,9 @@ fn restore_item(metadata: &mut ScrapMetadata, scrap_dir: &Path, name: &str, to_p
         anyhow::bail!("Destination already exists: {} (use --force to overwrite)", dest_path.display());
     }
 
+    let hooks = hooks_for(scrap_dir, HookAction::Unscrap).with(vec![entry.clone()], vec![dest_path.clone()]);
+    hooks.before()?;
+
     // Ensure parent directory exists
     if let Some(parent) = dest_path.parent() {
         if !parent.exists() {
@@ -410,14 +1079,95 @@ fn restore_item(metadata: &mut ScrapMetadata, scrap_dir: &Path, name: &str, to_p
         }
     }
 
-    // Move file back
-    fs::rename(&source_path, &dest_path)
-        .with_context(|| format!("Failed to restore {} to {}", name, dest_path.display()))?;
+    // Move file back, extracting it first if it has been tiered into an archive
+    match (&entry.trash_path, &entry.archive) {
+        (Some(trash_path), _) => {
+            remove_existing(&dest_path)?;
+            trash::restore(trash_path, &dest_path)
+                .with_context(|| format!("Failed to restore {} to {}", name, dest_path.display()))?;
+        }
+        (None, Some(archive)) => {
+            remove_existing(&dest_path)?;
+            tiering::extract(scrap_dir, archive, name, &dest_path)
+                .with_context(|| format!("Failed to restore {} to {}", name, dest_path.display()))?;
+        }
+        (None, None) => {
+            remove_existing(&dest_path)?;
+            dedupe::move_out(&source_path, &dest_path)
+                .with_context(|| format!("Failed to restore {} to {}", name, dest_path.display()))?;
+        }
+    }
 
     // Remove from metadata
     metadata.remove_entry(name);
     metadata.save(scrap_dir)?;
+    hooks.after(Vec::new());
 
     println!("Restored {} to {}", name, dest_path.display());
     Ok(())
+}
+
+/// Restore a scrapped directory into an existing one, bringing back only what is missing
+/// there. Conflicting files stay in scrap under the same entry unless `backup_existing`
+/// moves the existing ones aside.
+fn merge_item(metadata: &mut ScrapMetadata, scrap_dir: &Path, name: &str, to_path: Option<PathBuf>, backup_existing: bool) -> Result<()> {
+    let entry = metadata.get_entry(name)
+        .ok_or_else(|| anyhow::anyhow!("Item not found in scrap: {}", name))?
+        .clone();
+    let dest_path = to_path.unwrap_or_else(|| entry.original_path.clone());
+
+    if !dest_path.exists() {
+        return restore_item(metadata, scrap_dir, name, Some(dest_path), false);
+    }
+    if !dest_path.is_dir() {
+        anyhow::bail!("--merge needs an existing directory, but {} is not one", dest_path.display());
+    }
+
+    let hooks = hooks_for(scrap_dir, HookAction::Unscrap).with(vec![entry.clone()], vec![dest_path.clone()]);
+    hooks.before()?;
+
+    // Archived and trashed items are brought back into .scrap first, so any conflicts
+    // left over stay scrapped as an ordinary entry
+    let source_path = scrap_dir.join(name);
+    match (&entry.trash_path, &entry.archive) {
+        (Some(trash_path), _) => trash::restore(trash_path, &source_path)?,
+        (None, Some(archive)) => tiering::extract(scrap_dir, archive, name, &source_path)?,
+        (None, None) => {}
+    }
+    if let Some(stored) = metadata.entries.get_mut(name) {
+        stored.trash_path = None;
+        stored.archive = None;
+    }
+    metadata.save(scrap_dir)?;
+
+    if !source_path.is_dir() {
+        anyhow::bail!("--merge only applies to directories; {} is not one", name);
+    }
+
+    let report = merge::merge_into(&source_path, &dest_path, backup_existing)
+        .with_context(|| format!("Failed to merge {} into {}", name, dest_path.display()))?;
+
+    for path in &report.restored {
+        println!("  restored  {}", path.display());
+    }
+    for (path, backup) in &report.backed_up {
+        println!("  restored  {} (existing moved to {})", path.display(), backup.display());
+    }
+    for path in &report.conflicts {
+        println!("  conflict  {}", path.display());
+    }
+
+    let remaining = metadata.get_entry(name).cloned();
+    hooks.after(remaining.into_iter().collect());
+
+    if report.is_complete() {
+        metadata.remove_entry(name);
+        metadata.save(scrap_dir)?;
+        println!("Merged {} into {} ({} restored, {} already present)",
+            name, dest_path.display(), report.restored.len() + report.backed_up.len(), report.identical.len());
+    } else {
+        println!("Merged {} into {}; {} conflicting path(s) left in .scrap/{} (use --backup-existing to restore them)",
+            name, dest_path.display(), report.conflicts.len(), name);
+    }
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,135 @@
+//! Interactive selection of scrapped items for `unscrap --interactive`.
+//!
+//! Entries are filtered with a fuzzy (in-order subsequence) match against their scrapped
+//! name and original path, then offered in a multi-select list showing each entry's age
+//! and where it came from.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dialoguer::{theme::ColorfulTheme, Input, MultiSelect};
+
+use super::scrap_common::{ScrapEntry, ScrapMetadata};
+
+/// Score of `text` against `query`: `None` unless every query character appears in
+/// order (case-insensitively). Consecutive runs and matches at word starts score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let mut score = 0i64;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query {
+        let position = (next..text.len()).find(|&i| text[i] == wanted)?;
+        score += 1;
+        if previous.map_or(false, |p| p + 1 == position) {
+            score += 5;
+        }
+        if position == 0 || !text[position - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(position);
+        next = position + 1;
+    }
+    Some(score)
+}
+
+/// Compact age of a scrapped item: `just now`, `12m`, `5h`, `3d`
+pub fn age(scrapped_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - scrapped_at;
+    if elapsed.num_days() > 0 {
+        format!("{}d", elapsed.num_days())
+    } else if elapsed.num_hours() > 0 {
+        format!("{}h", elapsed.num_hours())
+    } else if elapsed.num_minutes() > 0 {
+        format!("{}m", elapsed.num_minutes())
+    } else {
+        "just now".to_string()
+    }
+}
+
+/// Entries matching `query`, best match first; newest first for an empty query
+pub fn candidates<'a>(metadata: &'a ScrapMetadata, query: &str) -> Vec<&'a ScrapEntry> {
+    let mut scored: Vec<(i64, &ScrapEntry)> = metadata
+        .entries
+        .values()
+        .filter_map(|entry| {
+            let haystack = format!("{} {}", entry.scrapped_name, entry.original_path.display());
+            fuzzy_score(query, &haystack).map(|score| (score, entry))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.scrapped_at.cmp(&a.1.scrapped_at)));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+fn label(entry: &ScrapEntry, now: DateTime<Utc>) -> String {
+    let archived = if entry.archive.is_some() { " [archived]" } else { "" };
+    format!(
+        "{:<30} {:>8}  from {}{}",
+        entry.scrapped_name,
+        age(entry.scrapped_at, now),
+        entry.original_path.display(),
+        archived
+    )
+}
+
+/// Prompt for a filter and then for the entries to restore. Returns the scrapped names
+/// chosen, which is empty when nothing matched or nothing was picked.
+pub fn pick(metadata: &ScrapMetadata) -> Result<Vec<String>> {
+    if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
+        anyhow::bail!("--interactive needs a terminal; pass the scrapped name instead");
+    }
+
+    let theme = ColorfulTheme::default();
+    let query: String = Input::with_theme(&theme)
+        .with_prompt("Filter (empty for all)")
+        .allow_empty(true)
+        .interact_text()?;
+
+    let matches = candidates(metadata, &query);
+    if matches.is_empty() {
+        println!("No scrapped items match '{}'", query);
+        return Ok(Vec::new());
+    }
+
+    let now = Utc::now();
+    let labels: Vec<String> = matches.iter().map(|entry| label(entry, now)).collect();
+    let chosen = MultiSelect::with_theme(&theme)
+        .with_prompt("Select items to restore (space to toggle, enter to confirm)")
+        .items(&labels)
+        .interact()?;
+
+    Ok(chosen.into_iter().map(|i| matches[i].scrapped_name.clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("cfg", "config.toml").is_some());
+        assert!(fuzzy_score("gfc", "config.toml").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("conf", "config.toml") > fuzzy_score("conf", "c_o_n_f.toml"));
+    }
+
+    #[test]
+    fn test_candidates_rank_and_age() {
+        let now = Utc::now();
+        let mut metadata = ScrapMetadata::new();
+        metadata.add_entry("old_config.toml", PathBuf::from("etc/old_config.toml"));
+        metadata.add_entry("notes.md", PathBuf::from("docs/notes.md"));
+        metadata.entries.get_mut("notes.md").unwrap().scrapped_at = now - chrono::Duration::days(3);
+
+        let ranked: Vec<&str> = candidates(&metadata, "").iter().map(|e| e.scrapped_name.as_str()).collect();
+        assert_eq!(ranked, vec!["old_config.toml", "notes.md"]);
+        let ranked: Vec<&str> = candidates(&metadata, "docs").iter().map(|e| e.scrapped_name.as_str()).collect();
+        assert_eq!(ranked, vec!["notes.md"]);
+
+        assert_eq!(age(now - chrono::Duration::days(3), now), "3d");
+        assert_eq!(age(now - chrono::Duration::minutes(90), now), "1h");
+        assert_eq!(age(now, now), "just now");
+    }
+}
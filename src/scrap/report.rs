@@ -0,0 +1,105 @@
+//! Machine-readable scrap output (`--format json`).
+//!
+//! With JSON output each command prints a single document: the entries it looked at and
+//! the actions it took. Notices that are not part of that result (auto-archiving,
+//! retention removals, guard warnings) go to stderr so stdout stays parseable.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use super::retention;
+use super::scrap_common::ScrapEntry;
+
+/// Command-line flag selecting the output format
+pub const FORMAT_FLAG: &str = "--format";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Human,
+    Json,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            other => anyhow::bail!("Unknown output format '{}' (expected human or json)", other),
+        }
+    }
+
+    pub fn is_json(self) -> bool {
+        self == Format::Json
+    }
+
+    /// Print a line of human output; nothing in JSON mode
+    pub fn say(self, message: impl Display) {
+        if !self.is_json() {
+            println!("{}", message);
+        }
+    }
+
+    /// Print a notice outside the command's result: stdout for people, stderr alongside JSON
+    pub fn notice(self, message: impl Display) {
+        if self.is_json() {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    /// Print the command's JSON result; nothing in human mode
+    pub fn emit(self, value: serde_json::Value) -> Result<()> {
+        if self.is_json() {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        Ok(())
+    }
+}
+
+/// A scrapped item as reported to scripts
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EntryReport {
+    pub name: String,
+    pub original_path: PathBuf,
+    pub scrapped_at: DateTime<Utc>,
+    /// Bytes in `.scrap` (or the system trash); 0 once archived
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Location in the system trash for items scrapped with `--system-trash`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_path: Option<PathBuf>,
+}
+
+impl EntryReport {
+    pub fn new(scrap_dir: &Path, entry: &ScrapEntry) -> Self {
+        Self {
+            name: entry.scrapped_name.clone(),
+            original_path: entry.original_path.clone(),
+            scrapped_at: entry.scrapped_at,
+            size: retention::item_size(scrap_dir, entry),
+            archive: entry.archive.clone(),
+            content_hash: entry.content_hash.clone(),
+            trash_path: entry.trash_path.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(Format::parse("json").unwrap(), Format::Json);
+        assert_eq!(Format::parse("human").unwrap(), Format::Human);
+        assert!(Format::parse("yaml").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Retention policy for `.scrap`: limits on item age, total size and item count.
+//!
+//! The policy is stored with the scrap metadata and set with `scrap config`. Every scrap
+//! operation enforces it, removing the oldest items first. Archived (tiered) items count
+//! toward the age and count limits but not the size limit, since they are already
+//! compressed into the monthly archives.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::scrap_common::ScrapEntry;
+
+/// Limits on what `.scrap` keeps; an unset limit is not enforced
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub max_age_days: Option<u32>,
+    pub max_total_size: Option<u64>,
+    pub max_items: Option<usize>,
+}
+
+impl RetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.max_age_days.is_none() && self.max_total_size.is_none() && self.max_items.is_none()
+    }
+
+    pub fn describe(&self) -> String {
+        if self.is_empty() {
+            return "no retention limits".to_string();
+        }
+        let mut limits = Vec::new();
+        if let Some(days) = self.max_age_days {
+            limits.push(format!("max age {} days", days));
+        }
+        if let Some(size) = self.max_total_size {
+            limits.push(format!("max size {} bytes", size));
+        }
+        if let Some(items) = self.max_items {
+            limits.push(format!("max {} items", items));
+        }
+        limits.join(", ")
+    }
+}
+
+/// An item as the policy sees it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub name: String,
+    pub scrapped_at: DateTime<Utc>,
+    /// Bytes on disk; 0 for archived items
+    pub size: u64,
+}
+
+/// Bytes a scrapped file or directory takes up in `.scrap`, or in the system trash
+pub fn item_size(scrap_dir: &Path, entry: &ScrapEntry) -> u64 {
+    if entry.archive.is_some() {
+        return 0;
+    }
+    let path = match &entry.trash_path {
+        Some(trash_path) => trash_path.clone(),
+        None => scrap_dir.join(&entry.scrapped_name),
+    };
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Items to remove to bring `items` within `policy`, oldest first. Expired items go
+/// first; then the oldest items until the count and size limits hold. `keep` (the item
+/// just scrapped) is only ever removed for age.
+pub fn plan(policy: &RetentionPolicy, items: &[Item], keep: Option<&str>, now: DateTime<Utc>) -> Vec<String> {
+    let mut remaining: Vec<&Item> = items.iter().collect();
+    remaining.sort_by_key(|item| item.scrapped_at);
+    let mut evicted = Vec::new();
+
+    if let Some(days) = policy.max_age_days {
+        let cutoff = now - chrono::Duration::days(days as i64);
+        remaining.retain(|item| {
+            let expired = item.scrapped_at < cutoff;
+            if expired {
+                evicted.push(item.name.clone());
+            }
+            !expired
+        });
+    }
+
+    let over_limits = |remaining: &[&Item]| {
+        let too_many = policy.max_items.map_or(false, |max| remaining.len() > max);
+        let total: u64 = remaining.iter().map(|item| item.size).sum();
+        let too_big = policy.max_total_size.map_or(false, |max| total > max);
+        too_many || too_big
+    };
+    while over_limits(&remaining) {
+        match remaining.iter().position(|item| Some(item.name.as_str()) != keep) {
+            Some(oldest) => evicted.push(remaining.remove(oldest).name.clone()),
+            None => break,
+        }
+    }
+
+    evicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, days_old: i64, size: u64, now: DateTime<Utc>) -> Item {
+        Item { name: name.to_string(), scrapped_at: now - chrono::Duration::days(days_old), size }
+    }
+
+    #[test]
+    fn test_plan_evicts_expired_then_oldest() {
+        let now = Utc::now();
+        let items = vec![
+            item("new.log", 0, 400, now),
+            item("ancient.txt", 90, 10, now),
+            item("old.bin", 20, 500, now),
+            item("mid.rs", 5, 300, now),
+        ];
+
+        let age = RetentionPolicy { max_age_days: Some(30), ..Default::default() };
+        assert_eq!(plan(&age, &items, None, now), vec!["ancient.txt"]);
+
+        let count = RetentionPolicy { max_items: Some(2), ..Default::default() };
+        assert_eq!(plan(&count, &items, None, now), vec!["ancient.txt", "old.bin"]);
+
+        let size = RetentionPolicy { max_total_size: Some(800), ..Default::default() };
+        assert_eq!(plan(&size, &items, None, now), vec!["ancient.txt", "old.bin"]);
+
+        assert!(plan(&RetentionPolicy::default(), &items, None, now).is_empty());
+    }
+
+    #[test]
+    fn test_plan_keeps_item_just_scrapped() {
+        let now = Utc::now();
+        let items = vec![item("huge.iso", 0, 5_000, now), item("small.txt", 1, 10, now)];
+        let policy = RetentionPolicy { max_total_size: Some(1_000), ..Default::default() };
+        assert_eq!(plan(&policy, &items, Some("huge.iso"), now), vec!["small.txt"]);
+    }
+}
@@ -4,11 +4,17 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::retention::RetentionPolicy;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScrapMetadata {
     pub version: u32,
     pub entries: HashMap<String, ScrapEntry>,
+    /// Limits enforced on every scrap operation (`scrap config`)
+    #[serde(default, skip_serializing_if = "RetentionPolicy::is_empty")]
+    pub policy: RetentionPolicy,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +22,15 @@ pub struct ScrapEntry {
     pub original_path: PathBuf,
     pub scrapped_at: DateTime<Utc>,
     pub scrapped_name: String,
+    /// Monthly archive in `.scrap/archive/` holding the item once it has been tiered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+    /// SHA-256 of a scrapped file's content; entries with the same hash share one stored file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Where the item went in the operating system's trash (`--system-trash`); it is not in `.scrap`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_path: Option<PathBuf>,
 }
 
 impl ScrapMetadata {
@@ -23,6 +38,7 @@ impl ScrapMetadata {
         Self {
             version: 1,
             entries: HashMap::new(),
+            policy: RetentionPolicy::default(),
         }
     }
 
@@ -57,6 +73,9 @@ impl ScrapMetadata {
                 original_path,
                 scrapped_at: Utc::now(),
                 scrapped_name: scrapped_name.to_string(),
+                archive: None,
+                content_hash: None,
+                trash_path: None,
             },
         );
     }
@@ -68,4 +87,56 @@ impl ScrapMetadata {
     pub fn get_entry(&self, scrapped_name: &str) -> Option<&ScrapEntry> {
         self.entries.get(scrapped_name)
     }
-}
\ No newline at end of file
+}
+
+/// When a hook runs relative to the operation it observes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookPhase {
+    Pre,
+    Post,
+}
+
+/// Scrap operations reported to hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookAction {
+    Scrap,
+    Unscrap,
+    Remove,
+    Clean,
+    Purge,
+    Retention,
+}
+
+/// What a hook is told about a scrap operation; shell hooks get it as JSON on stdin
+#[derive(Debug, Clone, Serialize)]
+pub struct HookEvent {
+    pub action: HookAction,
+    pub phase: HookPhase,
+    /// Project owning the `.scrap` folder
+    pub project: PathBuf,
+    /// Entries involved; for a pre-scrap event these do not exist yet
+    pub entries: Vec<ScrapEntry>,
+    /// Paths being scrapped, or restore destinations for unscrap
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Callback notified before and after every scrap operation. Returning an error from a
+/// pre event aborts the operation; errors from post events are only logged.
+pub trait ScrapHook: Send + Sync {
+    fn on_event(&self, event: &HookEvent) -> Result<()>;
+}
+
+static HOOKS: Mutex<Vec<Arc<dyn ScrapHook>>> = Mutex::new(Vec::new());
+
+/// Register a hook for the rest of the process, alongside those in `tools.scrap.hooks`
+pub fn register_hook(hook: Arc<dyn ScrapHook>) {
+    HOOKS.lock().unwrap().push(hook);
+}
+
+/// Hooks registered through `register_hook`
+pub fn registered_hooks() -> Vec<Arc<dyn ScrapHook>> {
+    HOOKS.lock().unwrap().clone()
+}
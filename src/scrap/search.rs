@@ -0,0 +1,128 @@
+//! Content search over scrapped items for `scrap find --content`.
+//!
+//! Scrapped directories are searched recursively. Binary files and files larger
+//! than the size cap are skipped and counted, so a stray build artifact cannot
+//! stall the search.
+
+use anyhow::Result;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Files larger than this are not searched unless `--max-size` says otherwise
+pub const DEFAULT_MAX_SIZE: u64 = 1024 * 1024;
+
+/// Longest line snippet printed for a match
+const SNIPPET_CHARS: usize = 120;
+
+/// A line matching the pattern; `path` is relative to the scrapped item's parent
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentMatch {
+    pub path: PathBuf,
+    pub line: usize,
+    pub snippet: String,
+}
+
+/// Matches in one scrapped item, plus the files that were not searched
+#[derive(Debug, Clone, Default)]
+pub struct ContentSearch {
+    pub matches: Vec<ContentMatch>,
+    pub skipped_large: usize,
+    pub skipped_binary: usize,
+}
+
+/// The line trimmed and cut to a printable length
+fn snippet(line: &str) -> String {
+    let line = line.trim();
+    if line.chars().count() <= SNIPPET_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(SNIPPET_CHARS).collect();
+    format!("{}…", cut)
+}
+
+/// Search the file or directory at `item` for lines matching `pattern`
+pub fn search_item(item: &Path, pattern: &Regex, max_size: u64) -> Result<ContentSearch> {
+    let mut search = ContentSearch::default();
+    let base = item.parent().unwrap_or(item);
+
+    let mut files: Vec<PathBuf> = WalkDir::new(item)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+
+    for file in files {
+        let size = fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+        if size > max_size {
+            search.skipped_large += 1;
+            continue;
+        }
+        let bytes = match fs::read(&file) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::warn!("Could not read {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        if content_inspector::inspect(&bytes).is_binary() {
+            search.skipped_binary += 1;
+            continue;
+        }
+
+        let content = String::from_utf8_lossy(&bytes);
+        let relative = file.strip_prefix(base).unwrap_or(&file).to_path_buf();
+        for (index, line) in content.lines().enumerate() {
+            if pattern.is_match(line) {
+                search.matches.push(ContentMatch { path: relative.clone(), line: index + 1, snippet: snippet(line) });
+            }
+        }
+    }
+
+    Ok(search)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_search_descends_into_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let item = temp_dir.path().join("old_module");
+        fs::create_dir_all(item.join("nested")).unwrap();
+        fs::write(item.join("a.rs"), "fn main() {}\nlet api_key = load();\n").unwrap();
+        fs::write(item.join("nested/b.txt"), "API_KEY=abc\nnothing here\n").unwrap();
+        fs::write(item.join("nested/blob.bin"), [0u8, 159, 146, 150, 0, 1]).unwrap();
+
+        let pattern = Regex::new("(?i)api_key").unwrap();
+        let search = search_item(&item, &pattern, DEFAULT_MAX_SIZE).unwrap();
+        assert_eq!(search.matches, vec![
+            ContentMatch { path: PathBuf::from("old_module/a.rs"), line: 2, snippet: "let api_key = load();".to_string() },
+            ContentMatch { path: PathBuf::from("old_module/nested/b.txt"), line: 1, snippet: "API_KEY=abc".to_string() },
+        ]);
+        assert_eq!(search.skipped_binary, 1);
+    }
+
+    #[test]
+    fn test_size_cap_and_snippets() {
+        let temp_dir = TempDir::new().unwrap();
+        let item = temp_dir.path().join("big.log");
+        let long_line = format!("needle {}", "x".repeat(300));
+        fs::write(&item, &long_line).unwrap();
+
+        let pattern = Regex::new("needle").unwrap();
+        let capped = search_item(&item, &pattern, 100).unwrap();
+        assert!(capped.matches.is_empty());
+        assert_eq!(capped.skipped_large, 1);
+
+        let search = search_item(&item, &pattern, DEFAULT_MAX_SIZE).unwrap();
+        assert_eq!(search.matches[0].path, PathBuf::from("big.log"));
+        assert_eq!(search.matches[0].snippet.chars().count(), SNIPPET_CHARS + 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! Secure removal for `scrap purge --secure` and `scrap clean --secure`.
+//!
+//! Each regular file is overwritten with random data (once per pass, synced to disk),
+//! truncated and then unlinked. Overwriting only reaches the blocks a file occupies now,
+//! so files whose old content may survive are reported rather than silently trusted:
+//! files on copy-on-write filesystems (btrfs, ZFS, bcachefs), files still hard-linked
+//! from scrap entries that are kept (deduplicated content is overwritten along with its
+//! last link), and files that could not be opened for writing. They are still deleted.
+//! Items tiered into a compressed archive are dropped from it, and the superseded archive
+//! is overwritten.
+
+use anyhow::{Context, Result};
+use rand::RngCore;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::tiering;
+
+/// Overwrite passes when `--passes` is not given
+pub const DEFAULT_PASSES: u32 = 1;
+
+/// A file whose content may outlive its removal, and why
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Unshredded {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// What a secure removal did
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ShredReport {
+    pub passes: u32,
+    /// Files overwritten before they were unlinked
+    pub shredded: usize,
+    /// Files removed without a trustworthy overwrite
+    pub not_secure: Vec<Unshredded>,
+}
+
+/// Removes scrapped items, overwriting their content first
+pub struct Shredder {
+    scrap_dir: PathBuf,
+    /// Name of the filesystem holding `.scrap` when it is copy-on-write
+    cow: Option<String>,
+    /// Links unlinked without an overwrite, by file identity, in case the last link follows
+    shared: HashMap<(u64, u64), Vec<PathBuf>>,
+    pub report: ShredReport,
+}
+
+impl Shredder {
+    pub fn new(scrap_dir: &Path, passes: u32) -> Self {
+        Self {
+            scrap_dir: scrap_dir.to_path_buf(),
+            cow: cow_filesystem(scrap_dir),
+            shared: HashMap::new(),
+            report: ShredReport { passes: passes.max(1), ..ShredReport::default() },
+        }
+    }
+
+    /// Securely remove a file, symlink or directory tree
+    pub fn remove(&mut self, path: &Path) -> Result<()> {
+        let metadata = fs::symlink_metadata(path)?;
+        if metadata.is_dir() {
+            for entry in WalkDir::new(path).follow_links(false) {
+                let entry = entry?;
+                if entry.file_type().is_file() {
+                    self.shred_file(entry.path());
+                }
+            }
+            fs::remove_dir_all(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        } else {
+            if metadata.is_file() {
+                self.shred_file(path);
+            }
+            fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Securely drop an item from a tier archive. The archive is rebuilt without it, then
+    /// the superseded copy, kept reachable through a temporary hard link, is overwritten.
+    pub fn remove_archived(&mut self, archive: &str, name: &str) -> Result<()> {
+        let path = tiering::archive_path(&self.scrap_dir, archive);
+        if !path.exists() {
+            return Ok(());
+        }
+        let old = path.with_file_name(format!(".{}.shred", archive));
+        let linked = fs::hard_link(&path, &old).is_ok();
+        if let Err(e) = tiering::remove(&self.scrap_dir, archive, name) {
+            let _ = fs::remove_file(&old);
+            return Err(e);
+        }
+
+        if linked {
+            self.shred_file(&old);
+            fs::remove_file(&old)?;
+        } else {
+            self.not_secure(&path, "the superseded archive could not be kept for overwriting".to_string());
+        }
+        Ok(())
+    }
+
+    /// Overwrite a regular file in place; the caller unlinks it. A file with other hard
+    /// links is left intact for them, and counts as shredded if its last link is.
+    fn shred_file(&mut self, path: &Path) {
+        let identity = file_identity(path);
+        if let Some(links) = other_links(path) {
+            self.not_secure(path, format!("content is shared with {} other scrap entr{}; only unlinked", links, if links == 1 { "y" } else { "ies" }));
+            if let Some(identity) = identity {
+                let relative = self.relative(path);
+                self.shared.entry(identity).or_default().push(relative);
+            }
+            return;
+        }
+        if let Err(e) = overwrite(path, self.report.passes) {
+            self.not_secure(path, format!("could not overwrite: {}", e));
+            return;
+        }
+        if let Some(fs_type) = &self.cow {
+            let reason = format!("{} is copy-on-write, so earlier copies of the blocks may survive", fs_type);
+            self.not_secure(path, reason);
+            return;
+        }
+        self.report.shredded += 1;
+        if let Some(links) = identity.and_then(|identity| self.shared.remove(&identity)) {
+            self.report.not_secure.retain(|item| !links.contains(&item.path));
+            self.report.shredded += links.len();
+        }
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.scrap_dir).unwrap_or(path).to_path_buf()
+    }
+
+    fn not_secure(&mut self, path: &Path, reason: String) {
+        let path = self.relative(path);
+        log::warn!("Not securely removed: {} ({})", path.display(), reason);
+        self.report.not_secure.push(Unshredded { path, reason });
+    }
+}
+
+/// Write `passes` rounds of random bytes over the file's length, then truncate it
+fn overwrite(path: &Path, passes: u32) -> Result<()> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    if permissions.readonly() {
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(path, permissions)?;
+    }
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    let len = file.metadata()?.len();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..passes {
+        file.seek(SeekFrom::Start(0))?;
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk = remaining.min(buffer.len() as u64) as usize;
+            rng.fill_bytes(&mut buffer[..chunk]);
+            file.write_all(&buffer[..chunk])?;
+            remaining -= chunk as u64;
+        }
+        file.sync_all()?;
+    }
+    file.set_len(0)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Other hard links to the file, when there are any
+#[cfg(unix)]
+fn other_links(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    let links = fs::metadata(path).ok()?.nlink();
+    (links > 1).then(|| links - 1)
+}
+
+#[cfg(not(unix))]
+fn other_links(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// The copy-on-write filesystem holding `path`, if any, from the longest matching mount point
+#[cfg(target_os = "linux")]
+fn cow_filesystem(path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).ok()?;
+    let mounts = fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point).then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+        .filter(|fs_type| matches!(fs_type.as_str(), "btrfs" | "zfs" | "bcachefs"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cow_filesystem(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_shred_overwrites_and_reports_shared_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let scrap_dir = temp_dir.path();
+        fs::create_dir_all(scrap_dir.join("dir/nested")).unwrap();
+        fs::write(scrap_dir.join("dir/nested/secret.txt"), "password").unwrap();
+        fs::write(scrap_dir.join("key.pem"), "private key").unwrap();
+
+        let mut shredder = Shredder::new(scrap_dir, 2);
+        shredder.cow = None;
+        overwrite(&scrap_dir.join("key.pem"), 1).unwrap();
+        assert_eq!(fs::metadata(scrap_dir.join("key.pem")).unwrap().len(), 0);
+
+        shredder.remove(&scrap_dir.join("dir")).unwrap();
+        shredder.remove(&scrap_dir.join("key.pem")).unwrap();
+        assert!(!scrap_dir.join("dir").exists());
+        assert!(!scrap_dir.join("key.pem").exists());
+        assert_eq!(shredder.report.shredded, 2);
+        assert!(shredder.report.not_secure.is_empty());
+
+        #[cfg(unix)]
+        {
+            fs::write(scrap_dir.join("notes.txt"), "shared").unwrap();
+            fs::hard_link(scrap_dir.join("notes.txt"), scrap_dir.join("notes_1.txt")).unwrap();
+            shredder.remove(&scrap_dir.join("notes.txt")).unwrap();
+            assert_eq!(fs::read_to_string(scrap_dir.join("notes_1.txt")).unwrap(), "shared");
+            assert_eq!(shredder.report.not_secure.len(), 1);
+            assert_eq!(shredder.report.not_secure[0].path, PathBuf::from("notes.txt"));
+
+            // Overwriting the last link covers the earlier one too
+            shredder.remove(&scrap_dir.join("notes_1.txt")).unwrap();
+            assert_eq!(shredder.report.shredded, 4);
+            assert!(shredder.report.not_secure.is_empty());
+        }
+    }
+}
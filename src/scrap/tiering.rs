@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+
+use super::hooks::HookConfig;
+use super::scrap_common::ScrapMetadata;
+
+/// Directory inside `.scrap` holding the monthly archives
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Scrap settings from `tools.scrap` in `.wsb/state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrapConfig {
+    /// Compress items older than this many days into monthly archives (disabled when unset)
+    pub archive_after_days: Option<u32>,
+    /// Gitignore-style globs, relative to the project root, that scrap refuses without an override
+    pub protected_globs: Vec<String>,
+    /// Send scrapped items to the operating system's trash instead of `.scrap`
+    pub system_trash: bool,
+    /// Shell commands run before and after each scrap operation
+    pub hooks: HookConfig,
+}
+
+impl ScrapConfig {
+    /// Load the config for a project; a project without `.wsb/state.json` uses the defaults
+    pub fn load(project_root: &Path) -> Self {
+        if !project_root.join(".wsb").join("state.json").exists() {
+            return Self::default();
+        }
+        crate::workspace_state::WorkspaceState::load(project_root)
+            .ok()
+            .and_then(|state| state.get_tool_config("scrap"))
+            .unwrap_or_default()
+    }
+}
+
+/// Monthly archive file name for an item scrapped at `scrapped_at`
+pub fn archive_name_for(scrapped_at: &DateTime<Utc>) -> String {
+    format!("{}.tar.gz", scrapped_at.format("%Y-%m"))
+}
+
+pub fn archive_path(scrap_dir: &Path, archive: &str) -> PathBuf {
+    scrap_dir.join(ARCHIVE_DIR).join(archive)
+}
+
+fn is_member_of(member: &Path, name: &str) -> bool {
+    member.components().next() == Some(Component::Normal(std::ffi::OsStr::new(name)))
+}
+
+/// Rebuild an archive: keep the existing members `keep` accepts, then add `additions`
+/// (member name, path on disk). The archive is replaced atomically and removed if it ends up empty.
+fn rebuild_archive<F>(path: &Path, keep: F, additions: &[(String, PathBuf)]) -> Result<()>
+where
+    F: Fn(&Path) -> bool,
+{
+    let temp = path.with_extension("gz.tmp");
+    let mut members = 0;
+
+    {
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&temp).with_context(|| format!("Failed to create {}", temp.display()))?,
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder.follow_symlinks(false);
+
+        if path.exists() {
+            let file = File::open(path).with_context(|| format!("Failed to open scrap archive {}", path.display()))?;
+            let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let member = entry.path()?.into_owned();
+                if keep(&member) {
+                    let mut header = entry.header().clone();
+                    builder.append_data(&mut header, &member, &mut entry)?;
+                    members += 1;
+                }
+            }
+        }
+
+        for (name, source) in additions {
+            if source.is_dir() {
+                builder.append_dir_all(name, source)?;
+            } else {
+                builder.append_path_with_name(source, name)?;
+            }
+            members += 1;
+        }
+
+        builder.into_inner()?.finish()?;
+    }
+
+    if members == 0 {
+        let _ = fs::remove_file(&temp);
+        let _ = fs::remove_file(path);
+        return Ok(());
+    }
+
+    fs::rename(&temp, path).with_context(|| format!("Failed to write scrap archive {}", path.display()))
+}
+
+/// Move items scrapped before `cutoff` into monthly archives under `.scrap/archive/`,
+/// recording the containing archive in the metadata. Returns the names archived.
+pub fn archive_older_than(scrap_dir: &Path, metadata: &mut ScrapMetadata, cutoff: DateTime<Utc>) -> Result<Vec<String>> {
+    let mut by_archive: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, entry) in &metadata.entries {
+        if entry.archive.is_none() && entry.scrapped_at < cutoff && scrap_dir.join(name).symlink_metadata().is_ok() {
+            by_archive.entry(archive_name_for(&entry.scrapped_at)).or_default().push(name.clone());
+        }
+    }
+
+    if by_archive.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    fs::create_dir_all(scrap_dir.join(ARCHIVE_DIR))?;
+    let mut archived = Vec::new();
+
+    for (archive, mut names) in by_archive {
+        names.sort();
+        let replaced: HashSet<&str> = names.iter().map(|n| n.as_str()).collect();
+        let additions: Vec<(String, PathBuf)> = names.iter().map(|n| (n.clone(), scrap_dir.join(n))).collect();
+
+        // A re-scrapped name replaces any stale copy already in the archive
+        rebuild_archive(
+            &archive_path(scrap_dir, &archive),
+            |member| !replaced.iter().any(|name| is_member_of(member, name)),
+            &additions,
+        )?;
+
+        for name in names {
+            let live = scrap_dir.join(&name);
+            if live.is_dir() && !live.symlink_metadata().map(|m| m.file_type().is_symlink()).unwrap_or(false) {
+                fs::remove_dir_all(&live)?;
+            } else {
+                fs::remove_file(&live)?;
+            }
+            if let Some(entry) = metadata.entries.get_mut(&name) {
+                entry.archive = Some(archive.clone());
+            }
+            archived.push(name);
+        }
+    }
+
+    metadata.save(scrap_dir)?;
+    Ok(archived)
+}
+
+/// Apply the configured tiering policy, if any. Failures are logged rather than
+/// failing the scrap command that triggered them.
+pub fn apply_policy(scrap_dir: &Path, metadata: &mut ScrapMetadata, config: &ScrapConfig) -> Vec<String> {
+    let days = match config.archive_after_days {
+        Some(days) => days,
+        None => return Vec::new(),
+    };
+
+    let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+    match archive_older_than(scrap_dir, metadata, cutoff) {
+        Ok(archived) => archived,
+        Err(e) => {
+            log::warn!("Scrap auto-archive failed: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Extract an archived item to `dest` and drop it from its archive
+pub fn extract(scrap_dir: &Path, archive: &str, name: &str, dest: &Path) -> Result<()> {
+    let path = archive_path(scrap_dir, archive);
+    let file = File::open(&path).with_context(|| format!("Scrap archive not found: {}", path.display()))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut found = false;
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let member = entry.path()?.into_owned();
+        if !is_member_of(&member, name) {
+            continue;
+        }
+
+        let relative = member.strip_prefix(name).unwrap_or(Path::new(""));
+        let target = if relative.as_os_str().is_empty() { dest.to_path_buf() } else { dest.join(relative) };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&target)
+            .with_context(|| format!("Failed to extract {} from {}", member.display(), path.display()))?;
+        found = true;
+    }
+
+    if !found {
+        anyhow::bail!("{} not found in scrap archive {}", name, archive);
+    }
+
+    remove(scrap_dir, archive, name)
+}
+
+/// Remove an item from its archive
+pub fn remove(scrap_dir: &Path, archive: &str, name: &str) -> Result<()> {
+    let path = archive_path(scrap_dir, archive);
+    if !path.exists() {
+        return Ok(());
+    }
+    rebuild_archive(&path, |member| !is_member_of(member, name), &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_and_extract_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let scrap_dir = temp_dir.path().join(".scrap");
+        fs::create_dir_all(scrap_dir.join("old_dir/nested")).unwrap();
+        fs::write(scrap_dir.join("old_dir/nested/file.txt"), "nested").unwrap();
+        fs::write(scrap_dir.join("old.txt"), "old").unwrap();
+        fs::write(scrap_dir.join("new.txt"), "new").unwrap();
+
+        let mut metadata = ScrapMetadata::new();
+        metadata.add_entry("old_dir", PathBuf::from("old_dir"));
+        metadata.add_entry("old.txt", PathBuf::from("old.txt"));
+        metadata.add_entry("new.txt", PathBuf::from("new.txt"));
+        let old = Utc::now() - chrono::Duration::days(120);
+        metadata.entries.get_mut("old_dir").unwrap().scrapped_at = old;
+        metadata.entries.get_mut("old.txt").unwrap().scrapped_at = old;
+
+        let config = ScrapConfig { archive_after_days: Some(90), ..ScrapConfig::default() };
+        let archived = apply_policy(&scrap_dir, &mut metadata, &config);
+        assert_eq!(archived, vec!["old.txt".to_string(), "old_dir".to_string()]);
+
+        let archive = archive_name_for(&old);
+        assert!(scrap_dir.join(ARCHIVE_DIR).join(&archive).exists());
+        assert!(!scrap_dir.join("old_dir").exists());
+        assert!(scrap_dir.join("new.txt").exists());
+        assert_eq!(metadata.get_entry("old.txt").unwrap().archive.as_deref(), Some(archive.as_str()));
+
+        let restored = temp_dir.path().join("restored");
+        extract(&scrap_dir, &archive, "old_dir", &restored).unwrap();
+        assert_eq!(fs::read_to_string(restored.join("nested/file.txt")).unwrap(), "nested");
+
+        // Extracting the last item removes the now-empty archive
+        extract(&scrap_dir, &archive, "old.txt", &temp_dir.path().join("old.txt")).unwrap();
+        assert_eq!(fs::read_to_string(temp_dir.path().join("old.txt")).unwrap(), "old");
+        assert!(!scrap_dir.join(ARCHIVE_DIR).join(&archive).exists());
+    }
+}
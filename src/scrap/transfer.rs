@@ -0,0 +1,230 @@
+//! Moving items into and out of `.scrap`, across filesystems too.
+//!
+//! A plain rename fails with `EXDEV` when `.scrap` and the item live on different
+//! filesystems (mounted volumes, bind mounts, tmpfs). The fallback copies the item next
+//! to its destination under a temporary name, checks every file's SHA-256 against the
+//! source, moves the copy into place and only then deletes the source, so a failure at
+//! any point leaves the original untouched. Copies larger than `PROGRESS_THRESHOLD`
+//! show a progress bar on a terminal.
+
+use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Copies at least this large show progress
+pub const PROGRESS_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// `EXDEV` on Unix, `ERROR_NOT_SAME_DEVICE` on Windows
+#[cfg(unix)]
+const CROSS_DEVICE: i32 = 18;
+#[cfg(windows)]
+const CROSS_DEVICE: i32 = 17;
+
+fn is_cross_device(error: &io::Error) -> bool {
+    error.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+/// Move `source` to `dest`, falling back to copy, verify and delete across filesystems.
+/// Like a rename, an existing file at `dest` is replaced.
+pub fn move_path(source: &Path, dest: &Path) -> Result<()> {
+    match fs::rename(source, dest) {
+        Ok(()) => Ok(()),
+        Err(e) if is_cross_device(&e) => {
+            log::info!("{} and {} are on different filesystems; copying", source.display(), dest.display());
+            copy_verify_delete(source, dest)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Total bytes of the regular files under `path`
+fn total_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn progress_bar(total: u64, source: &Path) -> Option<ProgressBar> {
+    if total < PROGRESS_THRESHOLD || !atty::is(atty::Stream::Stderr) {
+        return None;
+    }
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb.set_message(format!("Copying {}", source.display()));
+    Some(pb)
+}
+
+/// Copy one file, returning the SHA-256 of what was read
+fn copy_file(source: &Path, dest: &Path, progress: Option<&ProgressBar>) -> Result<Vec<u8>> {
+    let mut reader = File::open(source).with_context(|| format!("Failed to open {}", source.display()))?;
+    let mut writer = File::create(dest).with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        if let Some(pb) = progress {
+            pb.inc(read as u64);
+        }
+    }
+    writer.sync_all()?;
+    fs::set_permissions(dest, fs::metadata(source)?.permissions())?;
+    Ok(hasher.finalize().to_vec())
+}
+
+fn hash_file(path: &Path) -> Result<Vec<u8>> {
+    let mut reader = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, dest: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, dest)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, dest: &Path) -> Result<()> {
+    let target = fs::read_link(source)?;
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(target, dest)?;
+    } else {
+        std::os::windows::fs::symlink_file(target, dest)?;
+    }
+    Ok(())
+}
+
+/// Copy `source` (file, symlink or directory tree) to `dest`, verifying each file
+fn copy_verified(source: &Path, dest: &Path, progress: Option<&ProgressBar>) -> Result<()> {
+    for entry in WalkDir::new(source).follow_links(false) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?;
+        let target = if relative.as_os_str().is_empty() { dest.to_path_buf() } else { dest.join(relative) };
+        let file_type = entry.file_type();
+
+        if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            let expected = copy_file(entry.path(), &target, progress)?;
+            if hash_file(&target)? != expected {
+                anyhow::bail!("Copy of {} does not match the original", entry.path().display());
+            }
+        }
+    }
+
+    // Directory permissions last, so read-only directories can still be filled
+    for entry in WalkDir::new(source).follow_links(false).contents_first(true) {
+        let entry = entry?;
+        if entry.file_type().is_dir() {
+            let relative = entry.path().strip_prefix(source)?;
+            fs::set_permissions(dest.join(relative), entry.metadata()?.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Staging name next to `dest`, on the same filesystem
+fn staging_path(dest: &Path) -> PathBuf {
+    let name = dest.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    dest.with_file_name(format!(".{}.wsb-partial-{}", name, std::process::id()))
+}
+
+/// The cross-device path of `move_path`
+fn copy_verify_delete(source: &Path, dest: &Path) -> Result<()> {
+    let staging = staging_path(dest);
+    let total = total_size(source);
+    let progress = progress_bar(total, source);
+
+    if let Err(e) = copy_verified(source, &staging, progress.as_ref()) {
+        let _ = remove(&staging);
+        if let Some(pb) = progress {
+            pb.abandon();
+        }
+        return Err(e.context(format!("Failed to copy {} to {}", source.display(), dest.display())));
+    }
+    if let Some(pb) = progress {
+        pb.finish_and_clear();
+    }
+
+    if fs::symlink_metadata(dest).map_or(false, |m| !m.is_dir()) {
+        fs::remove_file(dest)?;
+    }
+    fs::rename(&staging, dest).with_context(|| format!("Failed to move the copy into place at {}", dest.display()))?;
+    remove(source).with_context(|| format!("Copied to {} but failed to delete {}", dest.display(), source.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cross_device_error_detection() {
+        assert!(is_cross_device(&io::Error::from_raw_os_error(CROSS_DEVICE)));
+        assert!(!is_cross_device(&io::Error::new(io::ErrorKind::NotFound, "missing")));
+    }
+
+    #[test]
+    fn test_copy_verify_delete_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("project");
+        fs::create_dir_all(source.join("src/nested")).unwrap();
+        fs::write(source.join("README.md"), "# Project").unwrap();
+        fs::write(source.join("src/nested/data.bin"), vec![7u8; 300_000]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("README.md", source.join("link.md")).unwrap();
+
+        let dest = temp_dir.path().join("moved");
+        copy_verify_delete(&source, &dest).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(dest.join("README.md")).unwrap(), "# Project");
+        assert_eq!(fs::read(dest.join("src/nested/data.bin")).unwrap().len(), 300_000);
+        #[cfg(unix)]
+        assert_eq!(fs::read_link(dest.join("link.md")).unwrap(), PathBuf::from("README.md"));
+        assert!(!staging_path(&dest).exists());
+    }
+
+    #[test]
+    fn test_copy_verify_delete_replaces_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("new.txt");
+        let dest = temp_dir.path().join("old.txt");
+        fs::write(&source, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+
+        copy_verify_delete(&source, &dest).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+        assert!(!source.exists());
+    }
+}
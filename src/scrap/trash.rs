@@ -0,0 +1,238 @@
+//! Scrapping into the operating system's trash (`--system-trash`, `tools.scrap.system_trash`).
+//!
+//! The item goes where the desktop's own delete would put it: the freedesktop.org home
+//! trash on Linux and other Unix desktops (`$XDG_DATA_HOME/Trash`, with a `.trashinfo`
+//! record so file managers can put it back), `~/.Trash` on macOS, and the Recycle Bin on
+//! Windows. The scrap index still gets an entry, recording where in the trash the item
+//! went, so `list`, `unscrap`, `rm` and retention keep working. Entries whose item has
+//! since left the trash (emptied, or restored from the file manager) are dropped from
+//! the index. The Recycle Bin does not expose item locations, so items sent there are
+//! restored from the Recycle Bin itself.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::scrap_common::ScrapMetadata;
+use super::shred::Shredder;
+use super::transfer;
+
+/// Command-line flag sending scrapped items to the system trash
+pub const SYSTEM_TRASH_FLAG: &str = "--system-trash";
+
+/// Recorded location of items sent to the Windows Recycle Bin
+pub const RECYCLE_BIN: &str = "$Recycle.Bin";
+
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+fn absolute(path: &Path) -> Result<PathBuf> {
+    let path = if path.is_absolute() { path.to_path_buf() } else { std::env::current_dir()?.join(path) };
+    // Resolve the parent only, so a symlink is trashed rather than its target
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => Ok(fs::canonicalize(parent)?.join(name)),
+        _ => Ok(path),
+    }
+}
+
+#[cfg(unix)]
+fn home() -> Result<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from).ok_or_else(|| anyhow::anyhow!("HOME is not set; cannot find the system trash"))
+}
+
+/// The freedesktop.org home trash
+#[cfg(all(unix, not(target_os = "macos")))]
+fn trash_home() -> Result<PathBuf> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => home()?.join(".local/share"),
+    };
+    Ok(data_home.join("Trash"))
+}
+
+/// Percent-encode a path for a `.trashinfo` file, keeping `/` and unreserved characters
+#[cfg(all(unix, not(target_os = "macos")))]
+fn encode_path(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str()
+        .as_bytes()
+        .iter()
+        .map(|&b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn info_path(trash_path: &Path) -> Option<PathBuf> {
+    let name = trash_path.file_name()?.to_string_lossy().to_string();
+    Some(trash_path.parent()?.parent()?.join("info").join(format!("{}.trashinfo", name)))
+}
+
+/// Move `path` to the trash, returning where it went
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    use std::io::Write;
+
+    let original = absolute(path)?;
+    let trash = trash_home()?;
+    fs::create_dir_all(trash.join("files"))?;
+    fs::create_dir_all(trash.join("info"))?;
+
+    let base = original.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(&original),
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+
+    // Creating the .trashinfo first reserves the name, as the spec requires
+    for n in 0.. {
+        let name = if n == 0 { base.clone() } else { format!("{}.{}", base, n) };
+        let info_path = trash.join("info").join(format!("{}.trashinfo", name));
+        let mut file = match fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {}", info_path.display())),
+        };
+        let dest = trash.join("files").join(&name);
+        if fs::symlink_metadata(&dest).is_ok() {
+            drop(file);
+            let _ = fs::remove_file(&info_path);
+            continue;
+        }
+        file.write_all(info.as_bytes())?;
+
+        if let Err(e) = transfer::move_path(path, &dest) {
+            let _ = fs::remove_file(&info_path);
+            return Err(e.context(format!("Failed to move {} to the trash", path.display())));
+        }
+        return Ok(dest);
+    }
+    unreachable!()
+}
+
+#[cfg(target_os = "macos")]
+fn info_path(_trash_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+/// Move `path` to `~/.Trash`, returning where it went
+#[cfg(target_os = "macos")]
+pub fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    let trash = home()?.join(".Trash");
+    fs::create_dir_all(&trash)?;
+    let base = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let (stem, ext) = match base.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+        _ => (base.clone(), String::new()),
+    };
+    // Finder's naming for clashes: "notes 2.txt"
+    let dest = (1..)
+        .map(|n| if n == 1 { trash.join(&base) } else { trash.join(format!("{} {}{}", stem, n, ext)) })
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap();
+    transfer::move_path(path, &dest).with_context(|| format!("Failed to move {} to the trash", path.display()))?;
+    Ok(dest)
+}
+
+#[cfg(windows)]
+fn info_path(_trash_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+/// Send `path` to the Recycle Bin through the shell's own delete
+#[cfg(windows)]
+pub fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    let original = absolute(path)?;
+    let method = if original.is_dir() { "DeleteDirectory" } else { "DeleteFile" };
+    let script = format!(
+        "Add-Type -AssemblyName Microsoft.VisualBasic; [Microsoft.VisualBasic.FileIO.FileSystem]::{}('{}', 'OnlyErrorDialogs', 'SendToRecycleBin')",
+        method,
+        original.display().to_string().replace('\'', "''")
+    );
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .status()
+        .context("Failed to run PowerShell to reach the Recycle Bin")?;
+    if !status.success() || original.exists() {
+        anyhow::bail!("Failed to move {} to the Recycle Bin", path.display());
+    }
+    Ok(PathBuf::from(RECYCLE_BIN))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn info_path(_trash_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    anyhow::bail!("No system trash on this platform; scrap {} into .scrap instead", path.display())
+}
+
+fn in_recycle_bin(trash_path: &Path) -> bool {
+    trash_path == Path::new(RECYCLE_BIN)
+}
+
+/// Whether the trashed item is still there (assumed for the Recycle Bin)
+pub fn is_present(trash_path: &Path) -> bool {
+    in_recycle_bin(trash_path) || fs::symlink_metadata(trash_path).is_ok()
+}
+
+/// Move a trashed item back to `dest`, dropping its trash record
+pub fn restore(trash_path: &Path, dest: &Path) -> Result<()> {
+    if in_recycle_bin(trash_path) {
+        anyhow::bail!("Items in the Recycle Bin are restored from the Recycle Bin itself");
+    }
+    if !is_present(trash_path) {
+        anyhow::bail!("{} is no longer in the system trash", trash_path.display());
+    }
+    transfer::move_path(trash_path, dest)?;
+    if let Some(info) = info_path(trash_path) {
+        let _ = fs::remove_file(info);
+    }
+    Ok(())
+}
+
+/// Delete a trashed item for good, overwriting it first with a shredder
+pub fn delete(trash_path: &Path, shredder: Option<&mut Shredder>) -> Result<()> {
+    if in_recycle_bin(trash_path) {
+        log::warn!("Items in the Recycle Bin are emptied from the Recycle Bin itself; dropping the scrap entry only");
+        return Ok(());
+    }
+    if fs::symlink_metadata(trash_path).is_ok() {
+        match shredder {
+            Some(shredder) => shredder.remove(trash_path)?,
+            None if trash_path.is_dir() && !fs::symlink_metadata(trash_path)?.file_type().is_symlink() => fs::remove_dir_all(trash_path)?,
+            None => fs::remove_file(trash_path)?,
+        }
+    }
+    if let Some(info) = info_path(trash_path) {
+        let _ = fs::remove_file(info);
+    }
+    Ok(())
+}
+
+/// Drop entries whose item has left the system trash, returning their names
+pub fn prune_missing(metadata: &mut ScrapMetadata) -> Vec<String> {
+    let mut gone: Vec<String> = metadata.entries.values()
+        .filter(|entry| entry.trash_path.as_deref().map_or(false, |path| !is_present(path)))
+        .map(|entry| entry.scrapped_name.clone())
+        .collect();
+    gone.sort();
+    for name in &gone {
+        metadata.remove_entry(name);
+    }
+    gone
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path(Path::new("/home/me/my notes.txt")), "/home/me/my%20notes.txt");
+        assert_eq!(encode_path(Path::new("/tmp/a%b")), "/tmp/a%25b");
+    }
+}
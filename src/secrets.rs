@@ -0,0 +1,172 @@
+//! Encrypted key/value secrets kept in `.wsb/state.json`.
+//!
+//! Values are sealed with AES-256-GCM before they are written, so tokens used by
+//! MCP integrations and hooks never sit in plaintext under `.wsb/`. The key lives in
+//! the OS keychain (created on the first `wsb secret set`); `WSB_SECRET_KEY` supplies
+//! a base64-encoded 32-byte key instead, for CI and machines without a keychain.
+//! The secret name is bound to its ciphertext, so sealed values cannot be swapped
+//! between names by editing the file.
+
+use crate::workspace_state::WorkspaceState;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use rand::RngCore;
+use std::path::Path;
+
+/// Environment variable holding a base64-encoded key that overrides the keychain
+pub const KEY_ENV: &str = "WSB_SECRET_KEY";
+
+const KEYCHAIN_SERVICE: &str = "wsb";
+const KEYCHAIN_USER: &str = "workspace-secrets";
+
+/// Prefix of sealed values, so the format can change later
+const SEALED_PREFIX: &str = "v1:";
+const NONCE_LEN: usize = 12;
+
+/// Encrypt and store `value` under `name`, creating the key on first use
+pub fn set(project_root: &Path, name: &str, value: &str) -> Result<()> {
+    crate::read_only::ensure_writable("store a secret")?;
+    validate_name(name)?;
+
+    let key = load_key(true)?;
+    let mut state = WorkspaceState::load(project_root)?;
+    state.secrets.insert(name.to_string(), seal(&key, name, value)?);
+    state.save(project_root)
+}
+
+/// Decrypt the secret stored under `name`
+pub fn get(project_root: &Path, name: &str) -> Result<Option<String>> {
+    let state = WorkspaceState::load(project_root)?;
+    match state.secrets.get(name) {
+        Some(sealed) => Ok(Some(open(&load_key(false)?, name, sealed)?)),
+        None => Ok(None),
+    }
+}
+
+/// Names of the stored secrets, sorted; values are not decrypted
+pub fn names(project_root: &Path) -> Result<Vec<String>> {
+    let state = WorkspaceState::load(project_root)?;
+    Ok(state.secrets.keys().cloned().collect())
+}
+
+/// Delete the secret stored under `name`. Returns whether it existed.
+pub fn remove(project_root: &Path, name: &str) -> Result<bool> {
+    crate::read_only::ensure_writable("remove a secret")?;
+    let mut state = WorkspaceState::load(project_root)?;
+    if state.secrets.remove(name).is_none() {
+        return Ok(false);
+    }
+    state.save(project_root)?;
+    Ok(true)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        anyhow::bail!("Invalid secret name '{}': use letters, digits, '_', '-' and '.'", name);
+    }
+    Ok(())
+}
+
+/// The encryption key from `WSB_SECRET_KEY` or the OS keychain. With `create`, a
+/// missing keychain entry is generated and stored.
+fn load_key(create: bool) -> Result<[u8; 32]> {
+    if let Ok(encoded) = std::env::var(KEY_ENV) {
+        return decode_key(&encoded).with_context(|| format!("Invalid {}", KEY_ENV));
+    }
+
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER)
+        .context("Failed to open the OS keychain")?;
+    match entry.get_password() {
+        Ok(encoded) => decode_key(&encoded).context("Invalid secret key in the OS keychain"),
+        Err(keyring::Error::NoEntry) if create => {
+            let mut key = [0u8; 32];
+            rand::rngs::OsRng.fill_bytes(&mut key);
+            entry
+                .set_password(&STANDARD.encode(key))
+                .with_context(|| format!("Failed to store the secret key in the OS keychain (set {} to supply one)", KEY_ENV))?;
+            Ok(key)
+        }
+        Err(keyring::Error::NoEntry) => {
+            anyhow::bail!("No secret key in the OS keychain; store a secret first or set {}", KEY_ENV)
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to read the secret key from the OS keychain (set {} to supply one)", KEY_ENV)),
+    }
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; 32]> {
+    let bytes = STANDARD.decode(encoded.trim()).context("Key is not valid base64")?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow::anyhow!("Key must be 32 bytes, got {}", bytes.len()))
+}
+
+/// Encrypt `value` as `v1:` + base64(nonce || ciphertext), bound to `name`
+fn seal(key: &[u8; 32], name: &str, value: &str) -> Result<String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: value.as_bytes(), aad: name.as_bytes() })
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt secret '{}'", name))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(format!("{}{}", SEALED_PREFIX, STANDARD.encode(sealed)))
+}
+
+fn open(key: &[u8; 32], name: &str, sealed: &str) -> Result<String> {
+    let encoded = sealed
+        .strip_prefix(SEALED_PREFIX)
+        .with_context(|| format!("Secret '{}' has an unknown format", name))?;
+    let bytes = STANDARD
+        .decode(encoded)
+        .with_context(|| format!("Secret '{}' is not valid base64", name))?;
+    if bytes.len() < NONCE_LEN {
+        anyhow::bail!("Secret '{}' is truncated", name);
+    }
+
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: name.as_bytes() })
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt secret '{}': wrong key or tampered value", name))?;
+    String::from_utf8(plaintext).with_context(|| format!("Secret '{}' is not valid UTF-8", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    #[test]
+    fn test_seal_roundtrip() {
+        let sealed = seal(&KEY, "github_token", "ghp_secret").unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert!(!sealed.contains("ghp_secret"));
+        assert_eq!(open(&KEY, "github_token", &sealed).unwrap(), "ghp_secret");
+
+        // Fresh nonce per seal
+        assert_ne!(sealed, seal(&KEY, "github_token", "ghp_secret").unwrap());
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_key_and_swapped_name() {
+        let sealed = seal(&KEY, "a", "value").unwrap();
+        assert!(open(&[8u8; 32], "a", &sealed).is_err());
+        assert!(open(&KEY, "b", &sealed).unwrap_err().to_string().contains("wrong key or tampered"));
+    }
+
+    #[test]
+    fn test_decode_key_and_names() {
+        assert_eq!(decode_key(&STANDARD.encode(KEY)).unwrap(), KEY);
+        assert!(decode_key(&STANDARD.encode([1u8; 16])).unwrap_err().to_string().contains("32 bytes"));
+        assert!(validate_name("mcp.api-token_2").is_ok());
+        assert!(validate_name("has space").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
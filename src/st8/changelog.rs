@@ -0,0 +1,309 @@
+//! CHANGELOG.md generation from git history.
+//!
+//! Commits since the last release tag are parsed as conventional commits
+//! (`type(scope)!: description`), grouped into sections by type and rendered through
+//! a Tera template into one `## {version}` section. Writing the section replaces an
+//! existing section for the same version, so running it on every commit keeps the
+//! entry for the upcoming release current.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tera::{Context as TeraContext, Tera};
+
+/// Section titles by commit type, in the order they appear
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build and CI"),
+    ("ci", "Build and CI"),
+    ("chore", "Chores"),
+    ("style", "Chores"),
+    ("revert", "Reverts"),
+];
+
+const OTHER_SECTION: &str = "Other Changes";
+const BREAKING_SECTION: &str = "Breaking Changes";
+
+/// Template used when the project has not configured one. Receives `version`, `date`
+/// and `sections`, each with a `title` and `entries` (`hash`, `kind`, `scope`,
+/// `description`, `breaking`).
+pub const DEFAULT_TEMPLATE: &str = r#"## {{ version }} ({{ date }})
+{% for section in sections %}
+### {{ section.title }}
+
+{% for entry in section.entries -%}
+- {% if entry.scope %}**{{ entry.scope }}:** {% endif %}{{ entry.description }} ({{ entry.hash }})
+{% endfor -%}
+{% endfor %}"#;
+
+/// One commit, parsed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Entry {
+    pub hash: String,
+    /// Conventional-commit type, lowercased; empty for commits that do not follow the format
+    pub kind: String,
+    pub scope: Option<String>,
+    pub description: String,
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Section {
+    pub title: String,
+    pub entries: Vec<Entry>,
+}
+
+/// Parse a commit subject (and body, for `BREAKING CHANGE:` footers)
+pub fn parse_commit(hash: &str, subject: &str, body: &str) -> Entry {
+    let breaking_footer = body
+        .lines()
+        .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+    let plain = Entry {
+        hash: hash.to_string(),
+        kind: String::new(),
+        scope: None,
+        description: subject.trim().to_string(),
+        breaking: breaking_footer,
+    };
+
+    let Some((header, description)) = subject.split_once(": ") else {
+        return plain;
+    };
+    let (header, bang) = match header.strip_suffix('!') {
+        Some(header) => (header, true),
+        None => (header, false),
+    };
+    let (kind, scope) = match header.split_once('(') {
+        Some((kind, rest)) => match rest.strip_suffix(')') {
+            Some(scope) => (kind, Some(scope.to_string())),
+            None => return plain,
+        },
+        None => (header, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+        return plain;
+    }
+
+    Entry {
+        hash: hash.to_string(),
+        kind: kind.to_lowercase(),
+        scope,
+        description: description.trim().to_string(),
+        breaking: bang || breaking_footer,
+    }
+}
+
+/// Group entries into sections: breaking changes first, then by type, then the rest
+pub fn group(entries: &[Entry]) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut push = |title: &str, entry: &Entry| match sections.iter_mut().find(|s| s.title == title) {
+        Some(section) => section.entries.push(entry.clone()),
+        None => sections.push(Section { title: title.to_string(), entries: vec![entry.clone()] }),
+    };
+
+    for entry in entries.iter().filter(|e| e.breaking) {
+        push(BREAKING_SECTION, entry);
+    }
+    let mut seen = Vec::new();
+    for (_, title) in SECTIONS {
+        if seen.contains(title) {
+            continue;
+        }
+        seen.push(*title);
+        for entry in entries.iter().filter(|e| section_title(&e.kind) == *title) {
+            push(*title, entry);
+        }
+    }
+    for entry in entries.iter().filter(|e| section_title(&e.kind) == OTHER_SECTION) {
+        push(OTHER_SECTION, entry);
+    }
+    sections
+}
+
+fn section_title(kind: &str) -> &'static str {
+    SECTIONS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, title)| *title)
+        .unwrap_or(OTHER_SECTION)
+}
+
+/// Commits made after `since` (default: the nearest tag), oldest first, merges skipped
+pub fn commits(project_root: &Path, since: Option<&str>) -> Result<Vec<Entry>> {
+    let since = match since {
+        Some(since) => Some(since.to_string()),
+        None => git(project_root, &["describe", "--tags", "--abbrev=0"])?,
+    };
+    let range = match &since {
+        Some(since) => format!("{}..HEAD", since),
+        None => "HEAD".to_string(),
+    };
+
+    let log = match git(project_root, &["log", "--reverse", "--no-merges", "--format=%h%x1f%s%x1f%b%x1e", &range])? {
+        Some(log) => log,
+        None => return Ok(Vec::new()),
+    };
+    Ok(log
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let hash = fields.next()?.trim();
+            let subject = fields.next()?;
+            let body = fields.next().unwrap_or("");
+            (!hash.is_empty()).then(|| parse_commit(hash, subject, body))
+        })
+        .collect())
+}
+
+fn git(project_root: &Path, args: &[&str]) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_root)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+/// Render the section for `version`; `template` overrides [`DEFAULT_TEMPLATE`]
+pub fn render(version: &str, date: &str, entries: &[Entry], template: Option<&str>) -> Result<String> {
+    let mut context = TeraContext::new();
+    context.insert("version", version);
+    context.insert("date", date);
+    context.insert("sections", &group(entries));
+    context.insert("entries", entries);
+
+    let rendered = Tera::one_off(template.unwrap_or(DEFAULT_TEMPLATE), &context, false)
+        .context("Failed to render changelog template")?;
+    Ok(format!("{}\n", rendered.trim_end()))
+}
+
+/// `existing` changelog content with the `## {version}` section replaced by `section`,
+/// or `section` inserted above the newest release when there is none yet
+pub fn merge(existing: Option<&str>, version: &str, section: &str) -> String {
+    let existing = match existing {
+        Some(existing) if !existing.trim().is_empty() => existing,
+        _ => return format!("# Changelog\n\n{}", section),
+    };
+
+    let lines: Vec<&str> = existing.lines().collect();
+    let is_release = |line: &str| line.starts_with("## ");
+    let is_this_release = |line: &str| {
+        line.strip_prefix("## ")
+            .map(|rest| rest.trim_start_matches('v') == version || rest.trim_start_matches('v').starts_with(&format!("{} ", version)))
+            .unwrap_or(false)
+    };
+
+    let (start, end) = match lines.iter().position(|line| is_this_release(line)) {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| is_release(line))
+                .map(|offset| start + 1 + offset)
+                .unwrap_or(lines.len());
+            (start, end)
+        }
+        None => {
+            let at = lines.iter().position(|line| is_release(line)).unwrap_or(lines.len());
+            (at, at)
+        }
+    };
+
+    let before = lines[..start].join("\n");
+    let after = lines[end..].join("\n");
+    let mut merged = String::new();
+    if !before.trim().is_empty() {
+        merged.push_str(before.trim_end());
+        merged.push_str("\n\n");
+    }
+    merged.push_str(section.trim_end());
+    merged.push('\n');
+    if !after.trim().is_empty() {
+        merged.push('\n');
+        merged.push_str(after.trim_end());
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Write the section for `version` into the changelog at `path`.
+/// Returns whether the file changed.
+pub fn write(path: &Path, version: &str, section: &str) -> Result<bool> {
+    let existing = if path.exists() {
+        Some(fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?)
+    } else {
+        None
+    };
+    let merged = merge(existing.as_deref(), version, section);
+    if existing.as_deref() == Some(merged.as_str()) {
+        return Ok(false);
+    }
+    fs::write(path, merged).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(subject: &str) -> Entry {
+        parse_commit("abc1234", subject, "")
+    }
+
+    #[test]
+    fn test_parse_commit() {
+        let parsed = entry("feat(cli)!: add bump command");
+        assert_eq!(parsed.kind, "feat");
+        assert_eq!(parsed.scope.as_deref(), Some("cli"));
+        assert_eq!(parsed.description, "add bump command");
+        assert!(parsed.breaking);
+
+        assert_eq!(entry("Fix: typo").kind, "fix");
+        assert_eq!(entry("Update README").kind, "");
+        assert_eq!(entry("Release 1.2: notes").kind, "");
+        assert!(parse_commit("abc1234", "fix: drop flag", "BREAKING CHANGE: --old is gone").breaking);
+    }
+
+    #[test]
+    fn test_group_order() {
+        let entries = vec![entry("chore: bump deps"), entry("fix: crash"), entry("feat!: new api"), entry("misc tweak")];
+        let titles: Vec<String> = group(&entries).into_iter().map(|s| s.title).collect();
+        assert_eq!(titles, vec!["Breaking Changes", "Features", "Bug Fixes", "Chores", "Other Changes"]);
+    }
+
+    #[test]
+    fn test_render_default_template() {
+        let entries = vec![entry("feat(cli): add changelog"), entry("fix: handle empty log")];
+        let rendered = render("1.2.0", "2026-10-16", &entries, None).unwrap();
+        assert!(rendered.starts_with("## 1.2.0 (2026-10-16)\n"));
+        assert!(rendered.contains("### Features\n\n- **cli:** add changelog (abc1234)\n"));
+        assert!(rendered.contains("### Bug Fixes\n\n- handle empty log (abc1234)\n"));
+
+        let custom = render("1.2.0", "2026-10-16", &entries, Some("{{ version }}: {{ entries | length }} changes")).unwrap();
+        assert_eq!(custom, "1.2.0: 2 changes\n");
+    }
+
+    #[test]
+    fn test_merge_replaces_same_version() {
+        let created = merge(None, "1.1.0", "## 1.1.0 (2026-10-01)\n\n- first\n");
+        assert_eq!(created, "# Changelog\n\n## 1.1.0 (2026-10-01)\n\n- first\n");
+
+        let added = merge(Some(&created), "1.2.0", "## 1.2.0 (2026-10-16)\n\n- second\n");
+        assert!(added.starts_with("# Changelog\n\n## 1.2.0"));
+        assert!(added.ends_with("## 1.1.0 (2026-10-01)\n\n- first\n"));
+
+        let replaced = merge(Some(&added), "1.2.0", "## 1.2.0 (2026-10-16)\n\n- second\n- third\n");
+        assert_eq!(replaced.matches("## 1.2.0").count(), 1);
+        assert!(replaced.contains("- third"));
+        assert!(replaced.contains("- first"));
+    }
+}
@@ -0,0 +1,126 @@
+//! Line diffs for previewing rendered output against what is on disk.
+
+/// One line of a diff, or a run of unchanged lines left out between hunks
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+    /// This many unchanged lines are not shown
+    Skipped(usize),
+}
+
+/// Above this many line pairs the diff degrades to "everything removed, everything added"
+const MAX_CELLS: usize = 4_000_000;
+
+/// Diff `old` against `new`, keeping `context` unchanged lines around each change.
+/// Returns an empty list when the two are equal.
+pub fn diff_lines(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let full = full_diff(&old, &new);
+    if full.iter().all(|line| matches!(line, DiffLine::Context(_))) {
+        return Vec::new();
+    }
+
+    // Keep changes and the context lines within reach of one
+    let changed: Vec<bool> = full.iter().map(|line| !matches!(line, DiffLine::Context(_))).collect();
+    let near_change = |index: usize| {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(changed.len());
+        changed[start..end].iter().any(|c| *c)
+    };
+
+    let mut result = Vec::new();
+    let mut skipped = 0;
+    for (index, line) in full.into_iter().enumerate() {
+        if near_change(index) {
+            if skipped > 0 {
+                result.push(DiffLine::Skipped(skipped));
+                skipped = 0;
+            }
+            result.push(line);
+        } else {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        result.push(DiffLine::Skipped(skipped));
+    }
+    result
+}
+
+/// Every line of both inputs, aligned on their longest common subsequence
+fn full_diff(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    if old.len().saturating_mul(new.len()) > MAX_CELLS {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string()))
+            .chain(new.iter().map(|line| DiffLine::Added(line.to_string())))
+            .collect();
+    }
+
+    // lengths[i][j]: LCS length of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Context(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line.to_string())));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line.to_string())));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_inputs_have_no_diff() {
+        assert!(diff_lines("a\nb\n", "a\nb\n", 3).is_empty());
+    }
+
+    #[test]
+    fn test_diff_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\nold\n9\n";
+        let new = "1\n2\n3\n4\n5\n6\n7\nnew\n9\nadded\n";
+        assert_eq!(
+            diff_lines(old, new, 1),
+            vec![
+                DiffLine::Skipped(6),
+                DiffLine::Context("7".to_string()),
+                DiffLine::Removed("old".to_string()),
+                DiffLine::Added("new".to_string()),
+                DiffLine::Context("9".to_string()),
+                DiffLine::Added("added".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_output_is_all_added() {
+        let diff = diff_lines("", "Version 1.2.3\n", 3);
+        assert_eq!(diff, vec![DiffLine::Added("Version 1.2.3".to_string())]);
+    }
+}
@@ -0,0 +1,389 @@
+//! Git hooks managed by `wsb git install`.
+//!
+//! Each supported hook gets a WS BLOCK: a marked section that runs one wsb command
+//! and can be replaced or removed without touching whatever else the hook file
+//! contains. A repository can carry blocks in several hook files at once.
+//!
+//! `wsb directive install-hook` adds a second, labelled block to the pre-commit hook
+//! that validates mandatory directives before the version block runs.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const BLOCK_START: &str = "# === WS BLOCK START ===";
+pub const BLOCK_END: &str = "# === WS BLOCK END ===";
+pub const DIRECTIVE_BLOCK_START: &str = "# === WS BLOCK START: directives ===";
+pub const DIRECTIVE_BLOCK_END: &str = "# === WS BLOCK END: directives ===";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// Stamps the version and stages the version file before each commit
+    PreCommit,
+    /// Adds a `Version:` trailer to the commit message
+    PrepareCommitMsg,
+    /// Reports the version the new commit carries
+    PostCommit,
+    /// Refuses to push while the version file has uncommitted changes
+    PrePush,
+}
+
+impl HookKind {
+    pub const ALL: [HookKind; 4] = [
+        HookKind::PreCommit,
+        HookKind::PrepareCommitMsg,
+        HookKind::PostCommit,
+        HookKind::PrePush,
+    ];
+
+    /// File name under `.git/hooks`
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PrepareCommitMsg => "prepare-commit-msg",
+            HookKind::PostCommit => "post-commit",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+
+    /// Arguments the hook passes to wsb
+    pub fn wsb_args(&self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "update --git-add",
+            HookKind::PrepareCommitMsg => "git stamp-message \"$1\"",
+            HookKind::PostCommit => "version show",
+            HookKind::PrePush => "git check",
+        }
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file_name())
+    }
+}
+
+impl FromStr for HookKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        HookKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.file_name() == s.trim())
+            .ok_or_else(|| {
+                let names: Vec<&str> = HookKind::ALL.iter().map(|kind| kind.file_name()).collect();
+                anyhow::anyhow!("Unsupported hook '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Path of `kind`'s hook file in the repository at `git_root`
+pub fn hook_path(git_root: &Path, kind: HookKind) -> PathBuf {
+    git_root.join(".git").join("hooks").join(kind.file_name())
+}
+
+/// The WS BLOCK running `exe` for `kind`
+pub fn block(kind: HookKind, exe: &Path) -> String {
+    format!(
+        "{}\n# DO NOT EDIT THIS BLOCK MANUALLY\n# Use 'wsb git uninstall --hook {}' to remove this hook\n{} {}\n{}\n",
+        BLOCK_START,
+        kind.file_name(),
+        exe.display(),
+        kind.wsb_args(),
+        BLOCK_END
+    )
+}
+
+/// Whether `content` contains a WS BLOCK
+pub fn has_block(content: &str) -> bool {
+    content.contains("=== WS BLOCK START ===")
+}
+
+/// `content` with any WS BLOCK removed
+pub fn remove_block(content: &str) -> String {
+    remove_section(content, "=== WS BLOCK START ===", "=== WS BLOCK END ===")
+}
+
+/// `content` without the lines from each `start` marker through the next `end` marker
+fn remove_section(content: &str, start: &str, end: &str) -> String {
+    let mut result = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        if line.contains(start) {
+            in_block = true;
+            continue;
+        }
+        if line.contains(end) {
+            in_block = false;
+            continue;
+        }
+        if !in_block {
+            result.push(line);
+        }
+    }
+
+    let mut output = result.join("\n");
+    if content.ends_with('\n') && !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+/// `existing` hook content (if any) with its WS BLOCK replaced by `block`
+pub fn with_block(existing: Option<&str>, block: &str) -> String {
+    let cleaned = existing.map(remove_block).unwrap_or_default();
+    // A lone shebang left behind by an earlier uninstall counts as empty
+    if cleaned.trim().is_empty() || cleaned.trim() == "#!/bin/bash" {
+        format!("#!/bin/bash\n{}", block)
+    } else {
+        format!("{}\n{}", cleaned.trim_end(), block)
+    }
+}
+
+/// Whether `kind`'s hook file carries a WS BLOCK
+pub fn is_installed(git_root: &Path, kind: HookKind) -> Result<bool> {
+    let path = hook_path(git_root, kind);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {} hook", kind))?;
+    Ok(has_block(&content))
+}
+
+/// Write (or rewrite) the WS BLOCK for `kind`, keeping the rest of the hook file
+pub fn install(git_root: &Path, kind: HookKind, exe: &Path) -> Result<PathBuf> {
+    let path = hook_path(git_root, kind);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("Failed to create git hooks directory")?;
+    }
+
+    let existing = if path.exists() {
+        Some(fs::read_to_string(&path).with_context(|| format!("Failed to read existing {} hook", kind))?)
+    } else {
+        None
+    };
+    fs::write(&path, with_block(existing.as_deref(), &block(kind, exe)))
+        .with_context(|| format!("Failed to write {} hook", kind))?;
+    make_executable(&path)?;
+
+    Ok(path)
+}
+
+fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// The directive gate block running `exe`. It exits the hook on failure so the commit is
+/// aborted before the version block stamps anything.
+pub fn directive_block(exe: &Path) -> String {
+    format!(
+        "{}\n# DO NOT EDIT THIS BLOCK MANUALLY\n# Use 'wsb directive uninstall-hook' to remove this hook\n{} directive validate --mandatory || exit 1\n{}\n",
+        DIRECTIVE_BLOCK_START,
+        exe.display(),
+        DIRECTIVE_BLOCK_END
+    )
+}
+
+/// `existing` pre-commit content (if any) with its directive gate replaced by `block`,
+/// placed right after the shebang so it runs first
+pub fn with_directive_block(existing: Option<&str>, block: &str) -> String {
+    let cleaned = existing
+        .map(|content| remove_section(content, DIRECTIVE_BLOCK_START, DIRECTIVE_BLOCK_END))
+        .unwrap_or_default();
+    if cleaned.trim().is_empty() || cleaned.trim() == "#!/bin/bash" {
+        return format!("#!/bin/bash\n{}", block);
+    }
+    match cleaned.split_once('\n') {
+        Some((shebang, rest)) if shebang.starts_with("#!") => format!("{}\n{}{}", shebang, block, rest),
+        _ if cleaned.starts_with("#!") => format!("{}\n{}", cleaned, block),
+        _ => format!("#!/bin/bash\n{}{}", block, cleaned),
+    }
+}
+
+/// Whether the pre-commit hook carries the directive gate
+pub fn is_directive_gate_installed(git_root: &Path) -> Result<bool> {
+    let path = hook_path(git_root, HookKind::PreCommit);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&path).context("Failed to read pre-commit hook")?;
+    Ok(content.contains(DIRECTIVE_BLOCK_START))
+}
+
+/// Write (or rewrite) the directive gate in the pre-commit hook, keeping the rest of it
+pub fn install_directive_gate(git_root: &Path, exe: &Path) -> Result<PathBuf> {
+    let path = hook_path(git_root, HookKind::PreCommit);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("Failed to create git hooks directory")?;
+    }
+
+    let existing = if path.exists() {
+        Some(fs::read_to_string(&path).context("Failed to read existing pre-commit hook")?)
+    } else {
+        None
+    };
+    fs::write(&path, with_directive_block(existing.as_deref(), &directive_block(exe)))
+        .context("Failed to write pre-commit hook")?;
+    make_executable(&path)?;
+
+    Ok(path)
+}
+
+/// What uninstalling did to one hook file
+#[derive(Debug, Clone, PartialEq)]
+pub enum Removal {
+    /// No hook file, or one without a WS BLOCK
+    NotInstalled,
+    /// The file held only the WS BLOCK and was deleted
+    Deleted,
+    /// The WS BLOCK was cut out and the rest kept
+    Stripped,
+}
+
+/// Remove the WS BLOCK from `kind`'s hook file, deleting the file if nothing else is left
+pub fn uninstall(git_root: &Path, kind: HookKind) -> Result<Removal> {
+    uninstall_section(git_root, kind, "=== WS BLOCK START ===", "=== WS BLOCK END ===")
+}
+
+/// Remove the directive gate from the pre-commit hook, deleting the file if nothing else is left
+pub fn uninstall_directive_gate(git_root: &Path) -> Result<Removal> {
+    uninstall_section(git_root, HookKind::PreCommit, DIRECTIVE_BLOCK_START, DIRECTIVE_BLOCK_END)
+}
+
+fn uninstall_section(git_root: &Path, kind: HookKind, start: &str, end: &str) -> Result<Removal> {
+    let path = hook_path(git_root, kind);
+    if !path.exists() {
+        return Ok(Removal::NotInstalled);
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {} hook", kind))?;
+    if !content.contains(start) {
+        return Ok(Removal::NotInstalled);
+    }
+
+    let cleaned = remove_section(&content, start, end);
+    if cleaned.trim().is_empty() || cleaned.trim() == "#!/bin/bash" {
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {} hook", kind))?;
+        Ok(Removal::Deleted)
+    } else {
+        fs::write(&path, cleaned.trim_end()).with_context(|| format!("Failed to update {} hook", kind))?;
+        Ok(Removal::Stripped)
+    }
+}
+
+/// Append a `Version: <version>` trailer to a commit message unless it already has one
+pub fn stamp_message(message: &str, version: &str) -> String {
+    let trailer = format!("Version: {}", version);
+    if message.lines().any(|line| line.trim() == trailer) {
+        return message.to_string();
+    }
+
+    // Keep git's commented help text below the trailer
+    let (body, comments) = if message.starts_with('#') {
+        ("", message)
+    } else {
+        match message.find("\n#") {
+            Some(index) => message.split_at(index + 1),
+            None => (message, ""),
+        }
+    };
+    let body = body.trim_end();
+    let separator = if body.is_empty() { "" } else { "\n\n" };
+    format!("{}{}{}\n{}", body, separator, trailer, comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hook_kind_names() {
+        for kind in HookKind::ALL {
+            assert_eq!(kind.file_name().parse::<HookKind>().unwrap(), kind);
+        }
+        assert!("post-merge".parse::<HookKind>().is_err());
+    }
+
+    #[test]
+    fn test_install_and_uninstall_keep_foreign_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let exe = Path::new("/usr/local/bin/wsb");
+        let push_hook = hook_path(root, HookKind::PrePush);
+        fs::create_dir_all(push_hook.parent().unwrap()).unwrap();
+        fs::write(&push_hook, "#!/bin/bash\nmake lint\n").unwrap();
+
+        install(root, HookKind::PrePush, exe).unwrap();
+        install(root, HookKind::PostCommit, exe).unwrap();
+        // Reinstalling replaces the block rather than adding a second one
+        install(root, HookKind::PrePush, exe).unwrap();
+
+        let content = fs::read_to_string(&push_hook).unwrap();
+        assert_eq!(content.matches("WS BLOCK START").count(), 1);
+        assert!(content.contains("make lint"));
+        assert!(content.contains("/usr/local/bin/wsb git check"));
+        assert!(is_installed(root, HookKind::PostCommit).unwrap());
+        assert!(!is_installed(root, HookKind::PreCommit).unwrap());
+
+        assert_eq!(uninstall(root, HookKind::PrePush).unwrap(), Removal::Stripped);
+        assert_eq!(uninstall(root, HookKind::PostCommit).unwrap(), Removal::Deleted);
+        assert_eq!(uninstall(root, HookKind::PreCommit).unwrap(), Removal::NotInstalled);
+        assert_eq!(fs::read_to_string(&push_hook).unwrap(), "#!/bin/bash\nmake lint");
+    }
+
+    #[test]
+    fn test_directive_gate_runs_before_version_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let exe = Path::new("/usr/local/bin/wsb");
+        let hook = hook_path(root, HookKind::PreCommit);
+
+        install(root, HookKind::PreCommit, exe).unwrap();
+        install_directive_gate(root, exe).unwrap();
+        install_directive_gate(root, exe).unwrap();
+        // Reinstalling the version block leaves the gate in front of it
+        install(root, HookKind::PreCommit, exe).unwrap();
+
+        let content = fs::read_to_string(&hook).unwrap();
+        assert!(content.starts_with("#!/bin/bash\n# === WS BLOCK START: directives ==="));
+        assert_eq!(content.matches("WS BLOCK START: directives").count(), 1);
+        assert_eq!(content.matches("=== WS BLOCK START ===").count(), 1);
+        let gate = content.find("directive validate --mandatory || exit 1").unwrap();
+        assert!(gate < content.find("update --git-add").unwrap());
+        assert!(is_directive_gate_installed(root).unwrap());
+
+        assert_eq!(uninstall(root, HookKind::PreCommit).unwrap(), Removal::Stripped);
+        assert!(is_directive_gate_installed(root).unwrap());
+        assert_eq!(uninstall_directive_gate(root).unwrap(), Removal::Deleted);
+        assert_eq!(uninstall_directive_gate(root).unwrap(), Removal::NotInstalled);
+
+        assert_eq!(with_directive_block(Some("make lint\n"), "GATE\n"), "#!/bin/bash\nGATE\nmake lint\n");
+    }
+
+    #[test]
+    fn test_stamp_message() {
+        assert_eq!(stamp_message("Fix parser\n", "1.2.3"), "Fix parser\n\nVersion: 1.2.3\n");
+        assert_eq!(
+            stamp_message("Fix parser\n# Please enter the commit message\n", "1.2.3"),
+            "Fix parser\n\nVersion: 1.2.3\n# Please enter the commit message\n"
+        );
+        let stamped = stamp_message("Fix parser\n", "1.2.3");
+        assert_eq!(stamp_message(&stamped, "1.2.3"), stamped);
+    }
+}
@@ -1,7 +1,16 @@
+pub mod changelog;
+pub mod diff;
+pub mod hooks;
+pub mod packages;
+pub mod propagate;
+pub mod schemes;
+pub mod sources;
 pub mod st8_common;
 pub mod templates;
+pub mod version_guard;
 pub mod wstemplate;
 
+pub use schemes::VersionScheme;
 pub use st8_common::{St8Config, VersionInfo, detect_project_files, ProjectFile, ProjectFileType, update_version_file, update_project_file, update_project_files};
-pub use templates::{TemplateManager, TemplateConfig};
+pub use templates::{TemplateManager, TemplateConfig, OutputMismatch, compare_output, load_fixture};
 pub use wstemplate::{WstemplateEngine, RenderedTemplate};
@@ -0,0 +1,353 @@
+//! Packages of a monorepo: Cargo workspace members and npm workspaces.
+//!
+//! In `lockstep` mode (the default) every package manifest gets the project version,
+//! exactly like the root manifest. In `independent` mode each package has its own
+//! version, tracked in the `package_versions` table: a base version seeded from the
+//! manifest the first time the package is seen, with the patch advanced by every
+//! commit that touches the package's directory after the base was set.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use super::st8_common::{ProjectFile, ProjectFileType};
+use super::{version_guard, VersionInfo};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PackageMode {
+    /// Every package carries the project version
+    #[default]
+    Lockstep,
+    /// Each package is versioned on its own
+    Independent,
+}
+
+impl PackageMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PackageMode::Lockstep => "lockstep",
+            PackageMode::Independent => "independent",
+        }
+    }
+}
+
+impl fmt::Display for PackageMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PackageMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "lockstep" => Ok(PackageMode::Lockstep),
+            "independent" => Ok(PackageMode::Independent),
+            other => anyhow::bail!("Unknown package mode '{}' (expected lockstep or independent)", other),
+        }
+    }
+}
+
+/// A workspace member with its own manifest
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    /// Directory relative to the project root
+    pub dir: PathBuf,
+    pub manifest: ProjectFile,
+    /// Version in the manifest; `None` when it is missing or inherited from the workspace
+    pub version: Option<String>,
+}
+
+/// The stored base of an independently versioned package
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageBase {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Commit the base was set at; commits touching the package after it advance the patch
+    pub base_commit: Option<String>,
+}
+
+/// Members of the Cargo workspace and npm workspaces rooted at `project_root`
+pub fn discover(project_root: &Path) -> Result<Vec<Package>> {
+    let mut packages = Vec::new();
+
+    let cargo_root = project_root.join("Cargo.toml");
+    if cargo_root.exists() {
+        let content = fs::read_to_string(&cargo_root).context("Failed to read Cargo.toml")?;
+        let parsed: toml::Value = content.parse().context("Failed to parse Cargo.toml")?;
+        let members = parsed
+            .get("workspace")
+            .and_then(|w| w.get("members"))
+            .and_then(|m| m.as_array())
+            .map(|m| m.iter().filter_map(|v| v.as_str().map(str::to_string)).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for dir in expand_members(project_root, &members)? {
+            if let Some(package) = cargo_package(project_root, &dir)? {
+                packages.push(package);
+            }
+        }
+    }
+
+    let npm_root = project_root.join("package.json");
+    if npm_root.exists() {
+        let content = fs::read_to_string(&npm_root).context("Failed to read package.json")?;
+        let parsed: serde_json::Value = serde_json::from_str(&content).context("Failed to parse package.json")?;
+        // `workspaces` is either a list of patterns or `{ "packages": [...] }`
+        let workspaces = parsed.get("workspaces").map(|w| w.get("packages").unwrap_or(w));
+        let members = workspaces
+            .and_then(|w| w.as_array())
+            .map(|m| m.iter().filter_map(|v| v.as_str().map(str::to_string)).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for dir in expand_members(project_root, &members)? {
+            if let Some(package) = npm_package(project_root, &dir)? {
+                packages.push(package);
+            }
+        }
+    }
+
+    Ok(packages)
+}
+
+fn cargo_package(project_root: &Path, dir: &Path) -> Result<Option<Package>> {
+    let path = project_root.join(dir).join("Cargo.toml");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed: toml::Value = content.parse().with_context(|| format!("Failed to parse {}", path.display()))?;
+    let Some(package) = parsed.get("package") else {
+        return Ok(None);
+    };
+    let Some(name) = package.get("name").and_then(|n| n.as_str()) else {
+        return Ok(None);
+    };
+    Ok(Some(Package {
+        name: name.to_string(),
+        dir: dir.to_path_buf(),
+        manifest: ProjectFile { path, file_type: ProjectFileType::CargoToml },
+        version: package.get("version").and_then(|v| v.as_str()).map(str::to_string),
+    }))
+}
+
+fn npm_package(project_root: &Path, dir: &Path) -> Result<Option<Package>> {
+    let path = project_root.join(dir).join("package.json");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed: serde_json::Value = serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    let Some(name) = parsed.get("name").and_then(|n| n.as_str()) else {
+        return Ok(None);
+    };
+    Ok(Some(Package {
+        name: name.to_string(),
+        dir: dir.to_path_buf(),
+        manifest: ProjectFile { path, file_type: ProjectFileType::PackageJson },
+        version: parsed.get("version").and_then(|v| v.as_str()).map(str::to_string),
+    }))
+}
+
+/// Directories matching workspace member patterns; a `*` matches within one path segment
+fn expand_members(project_root: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        let mut matches = vec![PathBuf::new()];
+        for segment in pattern.trim_end_matches('/').split('/').filter(|s| !s.is_empty() && *s != ".") {
+            let mut next = Vec::new();
+            for base in &matches {
+                if !segment.contains('*') {
+                    next.push(base.join(segment));
+                    continue;
+                }
+                let Ok(entries) = fs::read_dir(project_root.join(base)) else {
+                    continue;
+                };
+                let mut names: Vec<String> = entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| wildcard_match(segment, name))
+                    .collect();
+                names.sort();
+                next.extend(names.into_iter().map(|name| base.join(name)));
+            }
+            matches = next;
+        }
+        for dir in matches {
+            if !dir.as_os_str().is_empty() && project_root.join(&dir).is_dir() && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    Ok(dirs)
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') {
+        return false;
+    }
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix)
+        }
+        None => pattern == name,
+    }
+}
+
+/// The manifests lockstep mode writes the project version to
+pub fn lockstep_manifests(project_root: &Path) -> Result<Vec<ProjectFile>> {
+    Ok(discover(project_root)?
+        .into_iter()
+        .filter(|package| package.version.is_some())
+        .map(|package| package.manifest)
+        .collect())
+}
+
+/// The package called `name`, or an error listing the ones there are
+pub fn find(packages: &[Package], name: &str) -> Result<Package> {
+    packages.iter().find(|package| package.name == name).cloned().ok_or_else(|| {
+        let names: Vec<&str> = packages.iter().map(|package| package.name.as_str()).collect();
+        if names.is_empty() {
+            anyhow::anyhow!("Unknown package '{}': no Cargo or npm workspace members found", name)
+        } else {
+            anyhow::anyhow!("Unknown package '{}' (packages: {})", name, names.join(", "))
+        }
+    })
+}
+
+/// The stored base for `package`, seeding it from the manifest version at `head` the first time
+pub async fn base(pool: &SqlitePool, package: &Package, head: Option<&str>) -> Result<PackageBase> {
+    let row = sqlx::query("SELECT major, minor, patch, base_commit FROM package_versions WHERE name = ?")
+        .bind(&package.name)
+        .fetch_optional(pool)
+        .await?;
+    if let Some(row) = row {
+        return Ok(PackageBase {
+            major: row.get::<i64, _>("major") as u32,
+            minor: row.get::<i64, _>("minor") as u32,
+            patch: row.get::<i64, _>("patch") as u32,
+            base_commit: row.get("base_commit"),
+        });
+    }
+
+    let (major, minor, patch) = package.version.as_deref().and_then(version_guard::parse).unwrap_or((0, 1, 0));
+    let seeded = PackageBase { major, minor, patch, base_commit: head.map(str::to_string) };
+    set_base(pool, package, &seeded).await?;
+    Ok(seeded)
+}
+
+/// Store `base` as the package's version base
+pub async fn set_base(pool: &SqlitePool, package: &Package, base: &PackageBase) -> Result<()> {
+    sqlx::query(r#"
+        INSERT INTO package_versions (name, path, major, minor, patch, base_commit, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(name) DO UPDATE SET
+            path = excluded.path, major = excluded.major, minor = excluded.minor,
+            patch = excluded.patch, base_commit = excluded.base_commit, updated_at = excluded.updated_at
+    "#)
+    .bind(&package.name)
+    .bind(package.dir.to_string_lossy().to_string())
+    .bind(base.major)
+    .bind(base.minor)
+    .bind(base.patch)
+    .bind(&base.base_commit)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remember the version last written to the package's manifest
+pub async fn record_written(pool: &SqlitePool, package: &Package, version: &str) -> Result<()> {
+    sqlx::query("UPDATE package_versions SET version = ?, updated_at = ? WHERE name = ?")
+        .bind(version)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&package.name)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// The independent version of `package`: its base with the patch advanced by the
+/// commits that touched the package since the base was set
+pub fn independent_version(project_root: &Path, package: &Package, base: &PackageBase) -> Result<VersionInfo> {
+    let commits = match &base.base_commit {
+        Some(commit) => commits_touching(project_root, &package.dir, commit)?,
+        None => 0,
+    };
+    VersionInfo::from_version(&format!("{}.{}.{}", base.major, base.minor, base.patch + commits))
+}
+
+fn commits_touching(project_root: &Path, dir: &Path, since: &str) -> Result<u32> {
+    let range = format!("{}..HEAD", since);
+    let dir = dir.to_string_lossy().to_string();
+    let output = Command::new("git")
+        .args(["rev-list", "--count", &range, "--", &dir])
+        .current_dir(project_root)
+        .output()
+        .context("Failed to run git rev-list")?;
+    if !output.status.success() {
+        // The base commit was rewritten away; count from the base itself
+        return Ok(0);
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().context("Failed to parse commit count")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_discover_cargo_and_npm_workspaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(root, "Cargo.toml", "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\n");
+        write(root, "crates/core/Cargo.toml", "[package]\nname = \"core\"\nversion = \"1.2.0\"\n");
+        write(root, "crates/macros/Cargo.toml", "[package]\nname = \"macros\"\nversion.workspace = true\n");
+        write(root, "crates/notes/README.md", "not a crate");
+        write(root, "tools/cli/Cargo.toml", "[package]\nname = \"cli\"\nversion = \"0.3.1\"\n");
+        write(root, "package.json", r#"{"private": true, "workspaces": {"packages": ["web/*"]}}"#);
+        write(root, "web/app/package.json", r#"{"name": "@demo/app", "version": "2.0.0"}"#);
+
+        let packages = discover(root).unwrap();
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["core", "macros", "cli", "@demo/app"]);
+        assert_eq!(packages[0].dir, PathBuf::from("crates/core"));
+        assert_eq!(packages[1].version, None);
+
+        // Inherited versions follow the workspace, so lockstep leaves their manifests alone
+        assert_eq!(lockstep_manifests(root).unwrap().len(), 3);
+        assert!(find(&packages, "web").unwrap_err().to_string().contains("core, macros, cli, @demo/app"));
+    }
+
+    #[test]
+    fn test_package_mode_names() {
+        assert_eq!("Independent".parse::<PackageMode>().unwrap(), PackageMode::Independent);
+        assert_eq!(PackageMode::default(), PackageMode::Lockstep);
+        assert!("fixed".parse::<PackageMode>().is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "core"));
+        assert!(wildcard_match("plugin-*", "plugin-auth"));
+        assert!(!wildcard_match("plugin-*", "core"));
+        assert!(!wildcard_match("*", ".git"));
+    }
+}
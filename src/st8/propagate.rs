@@ -0,0 +1,207 @@
+//! All-or-nothing version propagation.
+//!
+//! The default update path writes the version file and then updates each detected
+//! project file on a best-effort basis, warning about the ones that fail. With
+//! `wsb update --propagate` the version file, every detected project file and every
+//! configured project file are updated as one unit instead: all new contents are
+//! computed before anything is written, each file is replaced through a temporary
+//! file and a rename, and if any replacement fails the files already replaced are
+//! put back to what they were.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::packages::{self, PackageMode};
+use super::st8_common::{detect_file_type, detect_project_files, updated_project_content, ProjectFile};
+use super::{St8Config, VersionInfo};
+use crate::refac::preserve;
+
+/// A file with the content it had and the content it is about to get
+#[derive(Debug)]
+struct Change {
+    path: PathBuf,
+    /// `None` when the file does not exist yet
+    original: Option<String>,
+    updated: String,
+}
+
+/// The project files a propagation covers: detected ones and lockstep workspace members
+/// (when auto-detection is on) and configured ones, each once
+pub fn target_files(project_root: &Path, config: &St8Config) -> Result<Vec<ProjectFile>> {
+    let mut files = if config.auto_detect_project_files {
+        let mut files = detect_project_files(project_root)?;
+        if config.package_mode == PackageMode::Lockstep {
+            files.extend(packages::lockstep_manifests(project_root)?);
+        }
+        files
+    } else {
+        Vec::new()
+    };
+
+    for configured in &config.project_files {
+        let path = project_root.join(configured);
+        if !path.exists() {
+            anyhow::bail!("Configured project file not found: {}", configured);
+        }
+        let file_type = detect_file_type(&path)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported project file type: {}", configured))?;
+        if !files.iter().any(|f| f.path == path) {
+            files.push(ProjectFile { path, file_type });
+        }
+    }
+
+    Ok(files)
+}
+
+/// Write `version_info` to the version file and all project files, or to none of them.
+/// Returns the files whose content changed.
+pub fn propagate(version_info: &VersionInfo, project_root: &Path, config: &St8Config) -> Result<Vec<PathBuf>> {
+    let mut changes = Vec::new();
+
+    if !config.version_file.is_empty() {
+        let path = project_root.join(&config.version_file);
+        let original = read_existing(&path)?;
+        changes.push(Change { path, original, updated: format!("{}\n", version_info.full_version) });
+    }
+
+    for file in target_files(project_root, config)? {
+        let original = fs::read_to_string(&file.path)
+            .with_context(|| format!("Failed to read {}", file.path.display()))?;
+        let updated = updated_project_content(version_info, &file, &original)
+            .with_context(|| format!("Failed to update version in {}", file.path.display()))?;
+        changes.push(Change { path: file.path, original: Some(original), updated });
+    }
+
+    changes.retain(|change| change.original.as_deref() != Some(change.updated.as_str()));
+    apply(&changes)?;
+    Ok(changes.into_iter().map(|change| change.path).collect())
+}
+
+fn read_existing(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn apply(changes: &[Change]) -> Result<()> {
+    for (index, change) in changes.iter().enumerate() {
+        if let Err(error) = replace(&change.path, &change.updated) {
+            let mut message = format!("{:#}", error);
+            for applied in changes[..index].iter().rev() {
+                if let Err(rollback) = restore(applied) {
+                    message.push_str(&format!("\nRollback of {} failed: {:#}", applied.path.display(), rollback));
+                }
+            }
+            anyhow::bail!("Version propagation aborted, {} file(s) rolled back: {}", index, message);
+        }
+    }
+    Ok(())
+}
+
+fn restore(change: &Change) -> Result<()> {
+    match &change.original {
+        Some(original) => replace(&change.path, original),
+        None => fs::remove_file(&change.path)
+            .with_context(|| format!("Failed to remove {}", change.path.display())),
+    }
+}
+
+/// Replace `path` with `content` through a sibling temporary file and a rename
+fn replace(path: &Path, content: &str) -> Result<()> {
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("version");
+    let temp = path.with_file_name(format!(".{}.st8-tmp", file_name));
+    fs::write(&temp, content).with_context(|| format!("Failed to write {}", temp.display()))?;
+
+    let result = if path.exists() {
+        preserve::replace_with(&temp, path, false).map(|lost| {
+            for what in lost {
+                log::warn!("Could not preserve {} of {}", what, path.display());
+            }
+        })
+    } else {
+        fs::rename(&temp, path).with_context(|| format!("Failed to create {}", path.display()))
+    };
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn version() -> VersionInfo {
+        VersionInfo {
+            major_version: "v2".to_string(),
+            minor_version: 3,
+            patch_version: 4,
+            full_version: "2.3.4".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_propagate_updates_every_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n").unwrap();
+        fs::write(root.join("package.json"), r#"{"name": "demo", "version": "0.1.0"}"#).unwrap();
+
+        let changed = propagate(&version(), root, &St8Config::default()).unwrap();
+
+        assert_eq!(changed.len(), 3);
+        assert_eq!(fs::read_to_string(root.join("version.txt")).unwrap(), "2.3.4\n");
+        assert!(fs::read_to_string(root.join("Cargo.toml")).unwrap().contains("version = \"2.3.4\""));
+        assert!(fs::read_to_string(root.join("package.json")).unwrap().contains("\"2.3.4\""));
+
+        // Nothing left to change the second time
+        assert!(propagate(&version(), root, &St8Config::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unparseable_file_leaves_everything_untouched() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("Cargo.toml"), "[package]\nversion = \"0.1.0\"\n").unwrap();
+        fs::write(root.join("package.json"), "{ not json").unwrap();
+
+        assert!(propagate(&version(), root, &St8Config::default()).is_err());
+
+        assert!(!root.join("version.txt").exists());
+        assert_eq!(fs::read_to_string(root.join("Cargo.toml")).unwrap(), "[package]\nversion = \"0.1.0\"\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_write_rolls_back_earlier_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("version.txt"), "0.1.0\n").unwrap();
+        let locked = root.join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::write(locked.join("package.json"), r#"{"version": "0.1.0"}"#).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let config = St8Config {
+            auto_detect_project_files: false,
+            project_files: vec!["locked/package.json".to_string()],
+            ..St8Config::default()
+        };
+        let result = propagate(&version(), root, &config);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        // Running as root ignores the read-only directory; nothing to roll back then
+        if let Err(error) = result {
+            assert!(error.to_string().contains("rolled back"));
+            assert_eq!(fs::read_to_string(root.join("version.txt")).unwrap(), "0.1.0\n");
+        }
+    }
+}
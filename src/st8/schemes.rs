@@ -0,0 +1,309 @@
+//! Version schemes a project can choose between.
+//!
+//! The scheme is stored with the project's st8 configuration and decides how
+//! `major.minor.patch` is derived from the database and git:
+//!
+//! - `commit-count` (default): major from the database, minor the total commit
+//!   count, patch the lines changed since the last `v{major}.*` release tag
+//! - `semver`: major and minor set by hand, patch advances with every commit
+//!   since the last `v{major}.{minor}.*` tag or the last `wsb version bump`
+//! - `calver`: `YYYY.M.N`, where N counts the commits made this month
+//! - `tag`: the nearest `vX.Y.Z` tag, with patch advanced by the commits since it
+//!
+//! A stored pre-release label (`wsb version bump --pre alpha.1`) is appended to
+//! whatever the scheme calculates.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::process::Command;
+use std::str::FromStr;
+
+use super::version_guard;
+use super::VersionInfo;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionScheme {
+    #[default]
+    CommitCount,
+    Semver,
+    Calver,
+    Tag,
+}
+
+impl VersionScheme {
+    pub const ALL: [VersionScheme; 4] = [
+        VersionScheme::CommitCount,
+        VersionScheme::Semver,
+        VersionScheme::Calver,
+        VersionScheme::Tag,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VersionScheme::CommitCount => "commit-count",
+            VersionScheme::Semver => "semver",
+            VersionScheme::Calver => "calver",
+            VersionScheme::Tag => "tag",
+        }
+    }
+
+    /// One-line explanation of where each part of the version comes from
+    pub fn description(&self) -> &'static str {
+        match self {
+            VersionScheme::CommitCount => "major from database, minor = total commits, patch = changes since last release tag",
+            VersionScheme::Semver => "major and minor from database, patch = commits since last v{major}.{minor}.* tag or bump",
+            VersionScheme::Calver => "year.month.commits-this-month",
+            VersionScheme::Tag => "nearest vX.Y.Z tag, patch advanced by commits since it",
+        }
+    }
+}
+
+impl fmt::Display for VersionScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for VersionScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        VersionScheme::ALL
+            .iter()
+            .copied()
+            .find(|scheme| scheme.name() == s.trim().to_lowercase())
+            .ok_or_else(|| {
+                let names: Vec<&str> = VersionScheme::ALL.iter().map(|scheme| scheme.name()).collect();
+                anyhow::anyhow!("Unknown version scheme '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+/// The hand-managed parts of a version, as stored in the project database
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BaseVersion {
+    pub major: u32,
+    /// Used by the semver scheme
+    pub minor: u32,
+    /// Lowest patch the semver scheme may produce; raised by `wsb version bump patch`
+    pub patch: u32,
+    /// Pre-release label such as `alpha.1`, appended as `-alpha.1`
+    pub pre_release: Option<String>,
+    /// Commit the base was set at; the semver patch counts commits from here
+    pub base_commit: Option<String>,
+}
+
+/// Which part of the version `wsb version bump` raises
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BumpPart {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl FromStr for BumpPart {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "major" => Ok(BumpPart::Major),
+            "minor" => Ok(BumpPart::Minor),
+            "patch" => Ok(BumpPart::Patch),
+            other => anyhow::bail!("Unknown version part '{}' (expected major, minor or patch)", other),
+        }
+    }
+}
+
+/// The base to store so that `scheme` produces the next `part` after `current`, with
+/// `head` the commit being bumped. Returns the new base and whether a release tag is
+/// needed for the scheme to pick it up.
+pub fn bump(
+    scheme: VersionScheme,
+    base: &BaseVersion,
+    current: &VersionInfo,
+    part: BumpPart,
+    pre_release: Option<String>,
+    head: Option<String>,
+) -> Result<(BaseVersion, bool)> {
+    let (major, minor, patch) = version_guard::parse(&current.full_version)
+        .ok_or_else(|| anyhow::anyhow!("Current version '{}' is not major.minor.patch", current.full_version))?;
+    let (major, minor, patch) = match part {
+        BumpPart::Major => (major + 1, 0, 0),
+        BumpPart::Minor => (major, minor + 1, 0),
+        BumpPart::Patch => (major, minor, patch + 1),
+    };
+
+    match (scheme, part) {
+        (VersionScheme::Calver, _) => {
+            anyhow::bail!("The calver scheme takes its version from the date; switch scheme to bump it")
+        }
+        (VersionScheme::CommitCount, BumpPart::Minor | BumpPart::Patch) => anyhow::bail!(
+            "The commit-count scheme derives minor and patch from git; only `bump major` applies (or switch to semver)"
+        ),
+        (VersionScheme::CommitCount, BumpPart::Major) => {
+            Ok((BaseVersion { major, pre_release, ..base.clone() }, false))
+        }
+        (VersionScheme::Semver, _) => Ok((BaseVersion { major, minor, patch, pre_release, base_commit: head }, false)),
+        // The tag scheme reads its base from tags, so the bump only sticks once tagged
+        (VersionScheme::Tag, _) => Ok((BaseVersion { major, minor, patch, pre_release, base_commit: head }, true)),
+    }
+}
+
+/// Calculate the version for `scheme`; `base` holds the values stored in the
+/// database and is partly ignored by schemes that take them from elsewhere
+pub fn calculate(scheme: VersionScheme, base: &BaseVersion) -> Result<VersionInfo> {
+    let mut info = calculate_release(scheme, base)?;
+    if let Some(pre) = base.pre_release.as_deref().filter(|pre| !pre.is_empty()) {
+        info.full_version = format!("{}-{}", info.full_version, pre);
+    }
+    Ok(info)
+}
+
+fn calculate_release(scheme: VersionScheme, base: &BaseVersion) -> Result<VersionInfo> {
+    let (major, minor) = (base.major, base.minor);
+    match scheme {
+        VersionScheme::CommitCount => VersionInfo::calculate_with_major(major),
+        VersionScheme::Semver => {
+            let from_tag = match last_tag_matching(&format!("v{}.{}.*", major, minor))? {
+                Some((tag, (_, _, tag_patch))) => Some(tag_patch + commits_since(Some(&tag))?),
+                None => None,
+            };
+            let from_bump = match &base.base_commit {
+                Some(commit) => Some(base.patch + commits_since(Some(commit))?),
+                None => None,
+            };
+            let patch = match (from_tag, from_bump) {
+                (Some(tag), Some(bump)) => tag.max(bump),
+                (Some(patch), None) | (None, Some(patch)) => patch,
+                (None, None) => commits_since(None)?.max(base.patch),
+            };
+            Ok(version_info(major, minor, patch))
+        }
+        VersionScheme::Calver => {
+            let today = chrono::Utc::now().date_naive();
+            Ok(calver(today, commits_this_month(today)?))
+        }
+        VersionScheme::Tag => {
+            let (major, minor, patch) = match nearest_release_tag()? {
+                Some((tag, (major, minor, patch))) => (major, minor, patch + commits_since(Some(&tag))?),
+                None => (0, 0, commits_since(None)?),
+            };
+            Ok(version_info(major, minor, patch))
+        }
+    }
+}
+
+fn version_info(major: u32, minor: u32, patch: u32) -> VersionInfo {
+    VersionInfo {
+        major_version: format!("v{}", major),
+        minor_version: minor,
+        patch_version: patch,
+        full_version: format!("{}.{}.{}", major, minor, patch),
+    }
+}
+
+fn calver(date: NaiveDate, commits: u32) -> VersionInfo {
+    version_info(date.year() as u32, date.month(), commits)
+}
+
+fn git_output(args: &[&str]) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8(output.stdout).context("Invalid UTF-8 in git output")?.trim().to_string()))
+}
+
+/// Commits reachable from HEAD, or only those made after `rev` (a tag or commit)
+fn commits_since(rev: Option<&str>) -> Result<u32> {
+    let range = match rev {
+        Some(rev) => format!("{}..HEAD", rev),
+        None => "HEAD".to_string(),
+    };
+    match git_output(&["rev-list", "--count", &range])? {
+        Some(count) => count.parse().context("Failed to parse commit count"),
+        None => Ok(0),
+    }
+}
+
+fn commits_this_month(today: NaiveDate) -> Result<u32> {
+    let since = format!("--since={:04}-{:02}-01T00:00:00Z", today.year(), today.month());
+    match git_output(&["rev-list", "--count", &since, "HEAD"])? {
+        Some(count) => count.parse().context("Failed to parse commit count"),
+        None => Ok(0),
+    }
+}
+
+/// Highest-versioned tag matching `pattern` that parses as `vX.Y.Z`
+fn last_tag_matching(pattern: &str) -> Result<Option<(String, (u32, u32, u32))>> {
+    let listing = git_output(&["tag", "--list", pattern, "--sort=-version:refname"])?.unwrap_or_default();
+    Ok(listing
+        .lines()
+        .map(str::trim)
+        .find_map(|tag| version_guard::parse(tag).map(|version| (tag.to_string(), version))))
+}
+
+/// The closest `vX.Y.Z` tag in the history of HEAD
+fn nearest_release_tag() -> Result<Option<(String, (u32, u32, u32))>> {
+    let tag = git_output(&["describe", "--tags", "--abbrev=0", "--match", "v[0-9]*"])?;
+    Ok(tag.and_then(|tag| version_guard::parse(&tag).map(|version| (tag, version))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheme_names_round_trip() {
+        for scheme in VersionScheme::ALL {
+            assert_eq!(scheme.name().parse::<VersionScheme>().unwrap(), scheme);
+        }
+        assert_eq!("CalVer".parse::<VersionScheme>().unwrap(), VersionScheme::Calver);
+        assert!("build-number".parse::<VersionScheme>().unwrap_err().to_string().contains("commit-count"));
+        assert_eq!(VersionScheme::default(), VersionScheme::CommitCount);
+        assert_eq!(serde_json::to_string(&VersionScheme::CommitCount).unwrap(), "\"commit-count\"");
+    }
+
+    #[test]
+    fn test_bump() {
+        let current = version_info(1, 4, 7);
+        let base = BaseVersion { major: 1, minor: 4, ..BaseVersion::default() };
+        let head = Some("abc123".to_string());
+
+        let (next, needs_tag) = bump(VersionScheme::Semver, &base, &current, BumpPart::Minor, None, head.clone()).unwrap();
+        assert_eq!((next.major, next.minor, next.patch), (1, 5, 0));
+        assert_eq!(next.base_commit, head);
+        assert!(!needs_tag);
+
+        let (next, _) = bump(VersionScheme::Semver, &base, &current, BumpPart::Patch, Some("rc.1".to_string()), None).unwrap();
+        assert_eq!((next.major, next.minor, next.patch), (1, 4, 8));
+        assert_eq!(next.pre_release.as_deref(), Some("rc.1"));
+
+        let (next, needs_tag) = bump(VersionScheme::Tag, &base, &current, BumpPart::Major, None, None).unwrap();
+        assert_eq!((next.major, next.minor, next.patch), (2, 0, 0));
+        assert!(needs_tag);
+
+        // commit-count keeps its stored minor; only the major is hand-managed
+        let (next, _) = bump(VersionScheme::CommitCount, &base, &current, BumpPart::Major, None, None).unwrap();
+        assert_eq!((next.major, next.minor), (2, 4));
+        assert!(bump(VersionScheme::CommitCount, &base, &current, BumpPart::Patch, None, None).is_err());
+        assert!(bump(VersionScheme::Calver, &base, &current, BumpPart::Major, None, None).is_err());
+        assert!("build".parse::<BumpPart>().is_err());
+    }
+
+    #[test]
+    fn test_calver_format() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        let info = calver(date, 7);
+        assert_eq!(info.full_version, "2026.3.7");
+        assert_eq!(info.major_version, "v2026");
+        assert!(version_guard::parse(&info.full_version).is_some());
+    }
+}
@@ -0,0 +1,166 @@
+//! Extra template variables from outside the version calculation.
+//!
+//! A template can declare named sources in its `templates.json` entry; each one is
+//! resolved at render time and exposed as `{{ vars.NAME }}`. Sources are an
+//! environment variable, the output of a shell command, a value from
+//! `.wsb/state.json`, or a whole TOML/JSON values file.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContextSource {
+    /// An environment variable, with a fallback for when it is unset
+    Env {
+        var: String,
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Trimmed stdout of a shell command run in the project root
+    Command { command: String },
+    /// A value from `.wsb/state.json`, addressed by a dotted path such as `tools.scrap.retention`
+    State { key: String },
+    /// A TOML or JSON file (chosen by extension), relative to the project root
+    File { path: String },
+}
+
+/// A source and the variable name it is exposed under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedSource {
+    pub name: String,
+    #[serde(flatten)]
+    pub source: ContextSource,
+}
+
+impl ContextSource {
+    /// Resolve the source to a value; `state` is the serialized workspace state
+    pub fn resolve(&self, project_root: &Path, state: &serde_json::Value) -> Result<serde_json::Value> {
+        match self {
+            ContextSource::Env { var, default } => match std::env::var(var) {
+                Ok(value) => Ok(serde_json::Value::String(value)),
+                Err(_) => default
+                    .clone()
+                    .map(serde_json::Value::String)
+                    .ok_or_else(|| anyhow::anyhow!("Environment variable {} is not set and has no default", var)),
+            },
+            ContextSource::Command { command } => {
+                let output = shell(command)
+                    .current_dir(project_root)
+                    .output()
+                    .with_context(|| format!("Failed to run '{}'", command))?;
+                if !output.status.success() {
+                    anyhow::bail!(
+                        "'{}' exited with {}: {}",
+                        command,
+                        output.status,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                Ok(serde_json::Value::String(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+            }
+            ContextSource::State { key } => key
+                .split('.')
+                .try_fold(state, |value, part| value.get(part))
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No '{}' in workspace state", key)),
+            ContextSource::File { path } => {
+                let full_path = project_root.join(path);
+                let content = fs::read_to_string(&full_path)
+                    .with_context(|| format!("Failed to read values file {}", full_path.display()))?;
+                match full_path.extension().and_then(|ext| ext.to_str()) {
+                    Some("toml") => {
+                        let value: toml::Value = toml::from_str(&content)
+                            .with_context(|| format!("Failed to parse {}", full_path.display()))?;
+                        Ok(serde_json::to_value(value)?)
+                    }
+                    Some("json") => serde_json::from_str(&content)
+                        .with_context(|| format!("Failed to parse {}", full_path.display())),
+                    _ => anyhow::bail!("Values file {} must be .toml or .json", full_path.display()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+/// Resolve every source into the object exposed as `vars`
+pub fn resolve_all(sources: &[NamedSource], project_root: &Path, state: &serde_json::Value) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let mut vars = serde_json::Map::new();
+    for named in sources {
+        let value = named
+            .source
+            .resolve(project_root, state)
+            .with_context(|| format!("Template variable '{}'", named.name))?;
+        vars.insert(named.name.clone(), value);
+    }
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_named_source_json_shape() {
+        let parsed: Vec<NamedSource> = serde_json::from_str(
+            r#"[{"name": "branch", "type": "command", "command": "git rev-parse --abbrev-ref HEAD"},
+                {"name": "ci", "type": "env", "var": "CI", "default": "false"}]"#,
+        )
+        .unwrap();
+        assert_eq!(parsed[0].source, ContextSource::Command { command: "git rev-parse --abbrev-ref HEAD".to_string() });
+        assert_eq!(parsed[1].source, ContextSource::Env { var: "CI".to_string(), default: Some("false".to_string()) });
+    }
+
+    #[test]
+    fn test_resolve_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("build.toml"), "channel = \"beta\"\n[target]\narch = \"arm64\"\n").unwrap();
+        let state = serde_json::json!({"project_name": "demo", "tools": {"scrap": {"retention": "30d"}}});
+
+        let sources = vec![
+            NamedSource { name: "build".to_string(), source: ContextSource::File { path: "build.toml".to_string() } },
+            NamedSource { name: "retention".to_string(), source: ContextSource::State { key: "tools.scrap.retention".to_string() } },
+            NamedSource {
+                name: "missing".to_string(),
+                source: ContextSource::Env { var: "WSB_TEST_UNSET_VARIABLE".to_string(), default: Some("none".to_string()) },
+            },
+        ];
+        let vars = resolve_all(&sources, root, &state).unwrap();
+        assert_eq!(vars["build"]["target"]["arch"], "arm64");
+        assert_eq!(vars["retention"], "30d");
+        assert_eq!(vars["missing"], "none");
+
+        let unknown = ContextSource::State { key: "tools.nope".to_string() };
+        assert!(unknown.resolve(root, &state).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = serde_json::Value::Null;
+        let echo = ContextSource::Command { command: "echo  built by ci ".to_string() };
+        assert_eq!(echo.resolve(temp_dir.path(), &state).unwrap(), "built by ci");
+        let failing = ContextSource::Command { command: "exit 3".to_string() };
+        assert!(failing.resolve(temp_dir.path(), &state).is_err());
+    }
+}
@@ -6,7 +6,10 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::packages::PackageMode;
+use super::schemes::VersionScheme;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct St8Config {
     pub version: u32,
     pub enabled: bool,
@@ -15,12 +18,29 @@ pub struct St8Config {
     pub auto_detect_project_files: bool,
     #[serde(default)]
     pub project_files: Vec<String>,
+    #[serde(default)]
+    pub version_scheme: VersionScheme,
+    /// Regenerate the changelog on every `wsb update`
+    #[serde(default)]
+    pub changelog_on_update: bool,
+    #[serde(default = "default_changelog_file")]
+    pub changelog_file: String,
+    /// Tera template for the changelog section, relative to the project root
+    #[serde(default)]
+    pub changelog_template: Option<String>,
+    /// How workspace member packages are versioned
+    #[serde(default)]
+    pub package_mode: PackageMode,
 }
 
 fn default_auto_detect() -> bool {
     true
 }
 
+fn default_changelog_file() -> String {
+    "CHANGELOG.md".to_string()
+}
+
 impl Default for St8Config {
     fn default() -> Self {
         Self {
@@ -29,6 +49,11 @@ impl Default for St8Config {
             version_file: "version.txt".to_string(),
             auto_detect_project_files: true,
             project_files: Vec::new(),
+            version_scheme: VersionScheme::default(),
+            changelog_on_update: false,
+            changelog_file: default_changelog_file(),
+            changelog_template: None,
+            package_mode: PackageMode::default(),
         }
     }
 }
@@ -105,6 +130,19 @@ impl VersionInfo {
         })
     }
 
+    /// An explicit `major.minor.patch` version (with optional `v` prefix and pre-release suffix), e.g. from `--force-version`
+    pub fn from_version(version: &str) -> Result<Self> {
+        let (major, minor_version, patch_version) = super::version_guard::parse(version)
+            .ok_or_else(|| anyhow::anyhow!("'{}' is not a major.minor.patch version", version))?;
+
+        Ok(Self {
+            major_version: format!("v{}", major),
+            minor_version,
+            patch_version,
+            full_version: version.trim().trim_start_matches('v').to_string(),
+        })
+    }
+
     /// Get calculation breakdown for debugging
     pub fn get_calculation_info(major: u32) -> Result<VersionCalculationInfo> {
         let total_commits = get_total_commit_count()?;
@@ -253,7 +291,14 @@ pub fn update_version_file(version_info: &VersionInfo, config: &St8Config) -> Re
         });
         
         match detect_project_files(&project_root) {
-            Ok(project_files) => {
+            Ok(mut project_files) => {
+                // Workspace members carry the project version too unless versioned independently
+                if config.package_mode == PackageMode::Lockstep {
+                    match super::packages::lockstep_manifests(&project_root) {
+                        Ok(manifests) => project_files.extend(manifests),
+                        Err(e) => log::warn!("Failed to discover workspace packages: {}", e),
+                    }
+                }
                 if !project_files.is_empty() {
                     match update_project_files(version_info, &project_files) {
                         Ok(updated_files) => {
@@ -316,7 +361,7 @@ pub fn update_version_file(version_info: &VersionInfo, config: &St8Config) -> Re
     Ok(true)
 }
 
-fn detect_file_type(path: &Path) -> Option<ProjectFileType> {
+pub(crate) fn detect_file_type(path: &Path) -> Option<ProjectFileType> {
     let filename = path.file_name()?.to_str()?;
     match filename {
         "Cargo.toml" => Some(ProjectFileType::CargoToml),
@@ -536,26 +581,7 @@ pub fn update_project_file(version_info: &VersionInfo, project_file: &ProjectFil
     let content = fs::read_to_string(&project_file.path)
         .with_context(|| format!("Failed to read {}", project_file.path.display()))?;
 
-    let updated_content = match project_file.file_type {
-        ProjectFileType::CargoToml => update_cargo_toml(&content, &version_info.full_version)?,
-        ProjectFileType::PackageJson => update_package_json(&content, &version_info.full_version)?,
-        ProjectFileType::PyprojectToml => update_pyproject_toml(&content, &version_info.full_version)?,
-        ProjectFileType::SetupPy => update_setup_py(&content, &version_info.full_version)?,
-        ProjectFileType::ComposerJson => update_composer_json(&content, &version_info.full_version)?,
-        ProjectFileType::PubspecYaml => update_pubspec_yaml(&content, &version_info.full_version)?,
-        ProjectFileType::PomXml => update_pom_xml(&content, &version_info.full_version)?,
-        ProjectFileType::BuildGradle => update_build_gradle(&content, &version_info.full_version)?,
-        ProjectFileType::BuildGradleKts => update_build_gradle_kts(&content, &version_info.full_version)?,
-        ProjectFileType::CMakeLists => update_cmake_lists(&content, &version_info.full_version)?,
-        ProjectFileType::PackageSwift => update_package_swift(&content, &version_info.full_version)?,
-        ProjectFileType::Gemspec => update_gemspec(&content, &version_info.full_version)?,
-        ProjectFileType::Csproj => update_csproj(&content, &version_info.full_version)?,
-        ProjectFileType::GoMod => update_go_mod(&content, &version_info.full_version)?,
-        ProjectFileType::MixExs => update_mix_exs(&content, &version_info.full_version)?,
-        ProjectFileType::BuildSbt => update_build_sbt(&content, &version_info.full_version)?,
-        ProjectFileType::ShardYml => update_shard_yml(&content, &version_info.full_version)?,
-        ProjectFileType::JuliaProject => update_julia_project(&content, &version_info.full_version)?,
-    };
+    let updated_content = updated_project_content(version_info, project_file, &content)?;
 
     fs::write(&project_file.path, updated_content)
         .with_context(|| format!("Failed to write updated {}", project_file.path.display()))?;
@@ -563,12 +589,51 @@ pub fn update_project_file(version_info: &VersionInfo, project_file: &ProjectFil
     Ok(())
 }
 
+/// `content` of `project_file` with its version field set to `version_info`
+pub(crate) fn updated_project_content(version_info: &VersionInfo, project_file: &ProjectFile, content: &str) -> Result<String> {
+    let updated_content = match project_file.file_type {
+        ProjectFileType::CargoToml => update_cargo_toml(content, &version_info.full_version)?,
+        ProjectFileType::PackageJson => update_package_json(content, &version_info.full_version)?,
+        ProjectFileType::PyprojectToml => update_pyproject_toml(content, &version_info.full_version)?,
+        ProjectFileType::SetupPy => update_setup_py(content, &version_info.full_version)?,
+        ProjectFileType::ComposerJson => update_composer_json(content, &version_info.full_version)?,
+        ProjectFileType::PubspecYaml => update_pubspec_yaml(content, &version_info.full_version)?,
+        ProjectFileType::PomXml => update_pom_xml(content, &version_info.full_version)?,
+        ProjectFileType::BuildGradle => update_build_gradle(content, &version_info.full_version)?,
+        ProjectFileType::BuildGradleKts => update_build_gradle_kts(content, &version_info.full_version)?,
+        ProjectFileType::CMakeLists => update_cmake_lists(content, &version_info.full_version)?,
+        ProjectFileType::PackageSwift => update_package_swift(content, &version_info.full_version)?,
+        ProjectFileType::Gemspec => update_gemspec(content, &version_info.full_version)?,
+        ProjectFileType::Csproj => update_csproj(content, &version_info.full_version)?,
+        ProjectFileType::GoMod => update_go_mod(content, &version_info.full_version)?,
+        ProjectFileType::MixExs => update_mix_exs(content, &version_info.full_version)?,
+        ProjectFileType::BuildSbt => update_build_sbt(content, &version_info.full_version)?,
+        ProjectFileType::ShardYml => update_shard_yml(content, &version_info.full_version)?,
+        ProjectFileType::JuliaProject => update_julia_project(content, &version_info.full_version)?,
+    };
+
+    Ok(updated_content)
+}
+
 fn update_cargo_toml(content: &str, version: &str) -> Result<String> {
     let mut parsed: toml::Value = content.parse()
         .context("Failed to parse Cargo.toml")?;
     
     if let Some(package) = parsed.get_mut("package").and_then(|p| p.as_table_mut()) {
-        package.insert("version".to_string(), toml::Value::String(version.to_string()));
+        // `version.workspace = true` inherits the workspace version; keep it that way
+        if !package.get("version").map_or(false, |v| v.is_table()) {
+            package.insert("version".to_string(), toml::Value::String(version.to_string()));
+        }
+    }
+
+    let workspace_package = parsed
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("package"))
+        .and_then(|p| p.as_table_mut());
+    if let Some(workspace_package) = workspace_package {
+        if workspace_package.contains_key("version") {
+            workspace_package.insert("version".to_string(), toml::Value::String(version.to_string()));
+        }
     }
     
     Ok(toml::to_string(&parsed)?)
@@ -803,12 +868,15 @@ async fn load_st8_config_from_db(db_path: &std::path::Path) -> Result<St8Config>
     // Initialize database tables if needed
     super::super::entities::database::initialize_database(db_path).await?;
     
-    // Try to get config from existing project
+    // Each project keeps its own config, so read the current one's
+    let current = super::super::entities::workspace::current_project(&pool).await?;
     let result = sqlx::query(r#"
-        SELECT version_file, auto_detect_project_files, project_files 
+        SELECT version_file, auto_detect_project_files, project_files, version_scheme,
+               changelog_on_update, changelog_file, changelog_template, package_mode
         FROM projects 
-        LIMIT 1
+        WHERE id = ? AND deleted_at IS NULL
     "#)
+    .bind(current.map(|project| project.id))
     .fetch_optional(&pool)
     .await?;
     
@@ -825,11 +893,19 @@ async fn load_st8_config_from_db(db_path: &std::path::Path) -> Result<St8Config>
             version_file: row.get::<String, _>("version_file"),
             auto_detect_project_files: row.get::<bool, _>("auto_detect_project_files"),
             project_files,
+            version_scheme: row.get::<String, _>("version_scheme").parse().unwrap_or_default(),
+            changelog_on_update: row.get::<bool, _>("changelog_on_update"),
+            changelog_file: row.get::<String, _>("changelog_file"),
+            changelog_template: row.get::<Option<String>, _>("changelog_template"),
+            package_mode: row.get::<String, _>("package_mode").parse().unwrap_or_default(),
         })
     } else {
-        // No project exists, create default project with config
+        // No project exists, create default project with config; archived-only workspaces keep the defaults
         let default_config = St8Config::default();
-        create_default_project_with_config(&pool, &default_config).await?;
+        let projects: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects").fetch_one(&pool).await?;
+        if projects == 0 {
+            create_default_project_with_config(&pool, &default_config).await?;
+        }
         Ok(default_config)
     }
 }
@@ -841,18 +917,32 @@ async fn save_st8_config_to_db(db_path: &std::path::Path, config: &St8Config) ->
     let pool = SqlitePool::connect(&database_url).await?;
     
     let project_files_json = serde_json::to_string(&config.project_files)?;
+    let current = super::super::entities::workspace::current_project(&pool).await?
+        .ok_or_else(|| anyhow::anyhow!("No active project"))?;
     
     sqlx::query(r#"
         UPDATE projects 
         SET version_file = ?, 
             auto_detect_project_files = ?, 
             project_files = ?,
-            updated_at = datetime('now')
-        WHERE id = (SELECT id FROM projects LIMIT 1)
+            version_scheme = ?,
+            changelog_on_update = ?,
+            changelog_file = ?,
+            changelog_template = ?,
+            package_mode = ?,
+            updated_at = ?
+        WHERE id = ?
     "#)
     .bind(&config.version_file)
     .bind(config.auto_detect_project_files)
     .bind(project_files_json)
+    .bind(config.version_scheme.name())
+    .bind(config.changelog_on_update)
+    .bind(&config.changelog_file)
+    .bind(&config.changelog_template)
+    .bind(config.package_mode.name())
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(&current.id)
     .execute(&pool)
     .await?;
     
@@ -861,19 +951,25 @@ async fn save_st8_config_to_db(db_path: &std::path::Path, config: &St8Config) ->
 
 async fn create_default_project_with_config(pool: &sqlx::SqlitePool, config: &St8Config) -> Result<()> {
     let project_files_json = serde_json::to_string(&config.project_files)?;
+    // Entity code reads project timestamps as RFC 3339, not SQLite's datetime('now') format
+    let now = chrono::Utc::now().to_rfc3339();
     
     sqlx::query(r#"
         INSERT INTO projects (
             id, name, description, status, version, major_version,
-            version_file, auto_detect_project_files, project_files
+            version_file, auto_detect_project_files, project_files, version_scheme,
+            created_at, updated_at
         ) VALUES (
             'P001', 'Default Project', 'Auto-created project', 'active', '0.1.0', 0,
-            ?, ?, ?
+            ?, ?, ?, ?, ?, ?
         )
     "#)
     .bind(&config.version_file)
     .bind(config.auto_detect_project_files)
     .bind(project_files_json)
+    .bind(config.version_scheme.name())
+    .bind(&now)
+    .bind(&now)
     .execute(pool)
     .await?;
     
@@ -1098,6 +1194,17 @@ serde = "1.0"
         assert!(updated.contains("serde = \"1.0\""));
     }
 
+    #[test]
+    fn test_update_cargo_toml_workspace_version() {
+        let root = "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"0.1.0\"\n";
+        let updated = update_cargo_toml(root, "1.2.3").unwrap();
+        assert!(updated.contains("version = \"1.2.3\""));
+
+        let member = "[package]\nname = \"core\"\nversion.workspace = true\n";
+        let updated = update_cargo_toml(member, "1.2.3").unwrap();
+        assert!(!updated.contains("1.2.3"));
+    }
+
     #[test]
     fn test_update_package_json() {
         let content = r#"{
@@ -1244,6 +1351,8 @@ set(CMAKE_CXX_STANDARD 17)
             version_file: "version.txt".to_string(),
             auto_detect_project_files: true,
             project_files: vec!["custom.toml".to_string()],
+            version_scheme: VersionScheme::Calver,
+            ..St8Config::default()
         };
         
         // Use file-only operations for unit test
@@ -1252,6 +1361,7 @@ set(CMAKE_CXX_STANDARD 17)
         
         assert_eq!(loaded_config.auto_detect_project_files, true);
         assert_eq!(loaded_config.project_files, vec!["custom.toml"]);
+        assert_eq!(loaded_config.version_scheme, VersionScheme::Calver);
     }
 
     #[test]
@@ -5,6 +5,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tera::{Context as TeraContext, Tera};
 
+use crate::entities::schema_models::{Feature, Task};
+use crate::st8::sources::{self, NamedSource};
 use crate::workspace_state::WorkspaceState;
 use crate::st8::VersionInfo;
 
@@ -16,6 +18,15 @@ pub struct TemplateConfig {
     pub source_path: String,
     pub output_path: String,
     pub enabled: bool,
+    /// Extra variables exposed as `vars.NAME`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<NamedSource>,
+    /// Only included by other templates; never rendered to a file of its own
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    /// Templates whose output must be rendered before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
 }
 
 /// Template manager for st8
@@ -23,6 +34,39 @@ pub struct TemplateManager {
     templates_dir: PathBuf,
     tera_engine: Tera,
     templates: HashMap<String, TemplateConfig>,
+    features: Vec<Feature>,
+    tasks: Vec<Task>,
+    project_root: PathBuf,
+    state_values: serde_json::Value,
+    /// Templates each template pulls in with `include`, `import` or `extends`
+    includes: HashMap<String, Vec<String>>,
+}
+
+/// Filters available to every st8 template, in addition to Tera's built-in ones
+pub fn register_filters(tera: &mut Tera) {
+    tera.register_filter("feature_state_emoji", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+        let emoji = match value.as_str().unwrap_or("") {
+            "not_implemented" => "❌",
+            "implemented_no_tests" => "🟠",
+            "implemented_failing_tests" => "🟡",
+            "implemented_passing_tests" => "🟢",
+            "tests_broken" => "⚠️",
+            "critical_issue" => "🔴",
+            _ => "❓",
+        };
+        Ok(tera::Value::String(emoji.to_string()))
+    });
+    tera.register_filter("task_status_icon", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+        let icon = match value.as_str().unwrap_or("") {
+            "pending" => "[ ]",
+            "in_progress" => "[~]",
+            "completed" => "[x]",
+            "blocked" => "[!]",
+            "cancelled" => "[-]",
+            _ => "[?]",
+        };
+        Ok(tera::Value::String(icon.to_string()))
+    });
 }
 
 impl TemplateManager {
@@ -32,30 +76,27 @@ impl TemplateManager {
         fs::create_dir_all(&templates_dir)
             .context("Failed to create templates directory")?;
         
-        // Initialize empty Tera engine
-        let mut tera_engine = Tera::default();
-        
-        // Load template configurations
+        // Load template configurations and register them with Tera
         let templates = Self::load_template_configs(&templates_dir)?;
-        
-        // Register templates with Tera
-        for template_config in templates.values() {
-            let template_path = templates_dir.join(&template_config.source_path);
-            if template_path.exists() {
-                let template_content = fs::read_to_string(&template_path)
-                    .with_context(|| format!("Failed to read template: {}", template_path.display()))?;
-                
-                tera_engine.add_raw_template(&template_config.name, &template_content)
-                    .with_context(|| format!("Failed to register template: {}", template_config.name))?;
-            }
-        }
+        let (tera_engine, includes) = Self::build_tera_engine(&templates_dir, &templates)?;
         
         Ok(Self {
             templates_dir,
             tera_engine,
             templates,
+            features: Vec::new(),
+            tasks: Vec::new(),
+            project_root: workspace_state.project_root.clone(),
+            state_values: serde_json::to_value(workspace_state).unwrap_or_default(),
+            includes,
         })
     }
+
+    /// Make the project's features and tasks available to templates as `features` and `tasks`
+    pub fn set_entities(&mut self, features: Vec<Feature>, tasks: Vec<Task>) {
+        self.features = features;
+        self.tasks = tasks;
+    }
     
     /// Add a new template
     pub fn add_template(&mut self, name: &str, template_content: &str, output_path: &str, description: Option<String>) -> Result<()> {
@@ -73,11 +114,15 @@ impl TemplateManager {
             source_path: template_filename,
             output_path: output_path.to_string(),
             enabled: true,
+            sources: Vec::new(),
+            partial: false,
+            depends_on: Vec::new(),
         };
         
         // Register with Tera
         self.tera_engine.add_raw_template(name, template_content)
             .with_context(|| format!("Failed to register template: {}", name))?;
+        self.includes.insert(name.to_string(), referenced_templates(template_content));
         
         // Store config
         self.templates.insert(name.to_string(), template_config);
@@ -121,6 +166,18 @@ impl TemplateManager {
         self.templates.get(name)
     }
     
+    /// Add (or replace, by name) an extra variable source for a template
+    pub fn add_source(&mut self, name: &str, source: NamedSource) -> Result<bool> {
+        if let Some(template_config) = self.templates.get_mut(name) {
+            template_config.sources.retain(|existing| existing.name != source.name);
+            template_config.sources.push(source);
+            self.save_template_configs()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     /// Enable or disable a template
     pub fn set_template_enabled(&mut self, name: &str, enabled: bool) -> Result<bool> {
         if let Some(template_config) = self.templates.get_mut(name) {
@@ -132,13 +189,76 @@ impl TemplateManager {
         }
     }
     
-    /// Render all enabled templates
+    /// Mark a template as a partial, or declare which templates must render before it
+    pub fn configure_template(&mut self, name: &str, partial: Option<bool>, depends_on: Option<Vec<String>>) -> Result<bool> {
+        let Some(template_config) = self.templates.get_mut(name) else {
+            return Ok(false);
+        };
+        let previous = template_config.clone();
+        if let Some(partial) = partial {
+            template_config.partial = partial;
+        }
+        if let Some(depends_on) = depends_on {
+            template_config.depends_on = depends_on;
+        }
+
+        // Reject a configuration that would leave the templates unrenderable
+        if let Err(error) = self.render_order() {
+            self.templates.insert(name.to_string(), previous);
+            return Err(error);
+        }
+        self.save_template_configs()?;
+        Ok(true)
+    }
+
+    /// Every template, ordered so each comes after the templates it includes or depends on
+    pub fn render_order(&self) -> Result<Vec<String>> {
+        let mut names: Vec<&String> = self.templates.keys().collect();
+        names.sort();
+
+        let mut order = Vec::new();
+        let mut visiting = Vec::new();
+        for name in names {
+            self.visit(name, &mut visiting, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    fn visit(&self, name: &str, visiting: &mut Vec<String>, order: &mut Vec<String>) -> Result<()> {
+        if order.iter().any(|done| done == name) {
+            return Ok(());
+        }
+        if let Some(start) = visiting.iter().position(|v| v == name) {
+            let mut cycle = visiting[start..].to_vec();
+            cycle.push(name.to_string());
+            anyhow::bail!("Template dependency cycle: {}", cycle.join(" -> "));
+        }
+
+        visiting.push(name.to_string());
+        let template_config = &self.templates[name];
+        let included = self.includes.get(name).map(Vec::as_slice).unwrap_or_default();
+        let mut dependencies: Vec<&String> = template_config.depends_on.iter().chain(included).collect();
+        dependencies.sort();
+        dependencies.dedup();
+        for dependency in dependencies {
+            if !self.templates.contains_key(dependency) {
+                anyhow::bail!("Template '{}' depends on unknown template '{}'", name, dependency);
+            }
+            self.visit(dependency, visiting, order)?;
+        }
+        visiting.pop();
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    /// Render all enabled templates, dependencies first; partials are only rendered where included
     pub fn render_all_templates(&self, version_info: &VersionInfo, project_name: Option<&str>) -> Result<Vec<String>> {
         let mut rendered_files = Vec::new();
         let context = self.create_template_context(version_info, project_name);
         
-        for template_config in self.templates.values() {
-            if template_config.enabled {
+        for name in self.render_order()? {
+            let template_config = &self.templates[&name];
+            if template_config.enabled && !template_config.partial {
                 match self.render_template(template_config, &context) {
                     Ok(output_path) => {
                         rendered_files.push(output_path);
@@ -153,10 +273,58 @@ impl TemplateManager {
         Ok(rendered_files)
     }
     
+    /// Render a template to a string without writing its output file
+    pub fn render_to_string(&self, name: &str, context: &TeraContext) -> Result<String> {
+        if !self.templates.contains_key(name) {
+            anyhow::bail!("Template not found: {}", name);
+        }
+        self.tera_engine.render(name, context)
+            .with_context(|| format!("Failed to render template: {}", name))
+    }
+
+    /// Render every enabled template without writing, paired with what its output file holds now
+    pub fn preview_all_templates(&self, version_info: &VersionInfo, project_name: Option<&str>) -> Result<Vec<RenderPreview>> {
+        let context = self.create_template_context(version_info, project_name);
+        let mut previews = Vec::new();
+
+        for name in self.render_order()? {
+            let template_config = &self.templates[&name];
+            if !template_config.enabled || template_config.partial {
+                continue;
+            }
+            let output_path = PathBuf::from(&template_config.output_path);
+            let current = if output_path.exists() {
+                Some(fs::read_to_string(&output_path)
+                    .with_context(|| format!("Failed to read template output: {}", output_path.display()))?)
+            } else {
+                None
+            };
+            previews.push(RenderPreview {
+                name: template_config.name.clone(),
+                output_path,
+                current,
+                rendered: self.render_content(template_config, &context)?,
+            });
+        }
+
+        Ok(previews)
+    }
+
+    fn render_content(&self, template_config: &TemplateConfig, context: &TeraContext) -> Result<String> {
+        let mut context = context.clone();
+        if !template_config.sources.is_empty() {
+            let vars = sources::resolve_all(&template_config.sources, &self.project_root, &self.state_values)
+                .with_context(|| format!("Failed to resolve variables for template: {}", template_config.name))?;
+            context.insert("vars", &vars);
+        }
+
+        self.tera_engine.render(&template_config.name, &context)
+            .with_context(|| format!("Failed to render template: {}", template_config.name))
+    }
+
     /// Render a specific template
     pub fn render_template(&self, template_config: &TemplateConfig, context: &TeraContext) -> Result<String> {
-        let rendered_content = self.tera_engine.render(&template_config.name, context)
-            .with_context(|| format!("Failed to render template: {}", template_config.name))?;
+        let rendered_content = self.render_content(template_config, context)?;
         
         // Write to output file
         let output_path = PathBuf::from(&template_config.output_path);
@@ -176,6 +344,19 @@ impl TemplateManager {
     /// Create template context with all available variables
     fn create_template_context(&self, version_info: &VersionInfo, project_name: Option<&str>) -> TeraContext {
         let mut context = TeraContext::new();
+
+        // Flat placeholders (`{{ version }}`) kept for templates written before `project.*`
+        context.insert("version", &version_info.full_version);
+        context.insert("major_version", &version_info.major_version);
+        context.insert("minor_version", &version_info.minor_version);
+        context.insert("patch_version", &version_info.patch_version);
+        if let Some(name) = project_name {
+            context.insert("project_name", name);
+        }
+
+        // Entity lists for loops such as `{% for feature in features %}`
+        context.insert("features", &self.features);
+        context.insert("tasks", &self.tasks);
         
         // Project information
         let mut project = HashMap::new();
@@ -206,7 +387,7 @@ impl TemplateManager {
     }
     
     /// Load template configurations from disk
-    fn load_template_configs(templates_dir: &Path) -> Result<HashMap<String, TemplateConfig>> {
+    pub(crate) fn load_template_configs(templates_dir: &Path) -> Result<HashMap<String, TemplateConfig>> {
         let config_file = templates_dir.join("templates.json");
         
         if !config_file.exists() {
@@ -236,20 +417,111 @@ impl TemplateManager {
     
     /// Rebuild Tera engine after template removal
     fn rebuild_tera_engine(&mut self) -> Result<()> {
-        self.tera_engine = Tera::default();
-        
-        for template_config in self.templates.values() {
-            let template_path = self.templates_dir.join(&template_config.source_path);
+        let (tera_engine, includes) = Self::build_tera_engine(&self.templates_dir, &self.templates)?;
+        self.tera_engine = tera_engine;
+        self.includes = includes;
+        Ok(())
+    }
+
+    /// A Tera engine with every template registered at once, so `extends` and `include`
+    /// resolve whatever order the templates are listed in
+    fn build_tera_engine(templates_dir: &Path, templates: &HashMap<String, TemplateConfig>) -> Result<(Tera, HashMap<String, Vec<String>>)> {
+        let mut tera_engine = Tera::default();
+        register_filters(&mut tera_engine);
+
+        let mut sources = Vec::new();
+        let mut includes = HashMap::new();
+        for template_config in templates.values() {
+            let template_path = templates_dir.join(&template_config.source_path);
             if template_path.exists() {
                 let template_content = fs::read_to_string(&template_path)
                     .with_context(|| format!("Failed to read template: {}", template_path.display()))?;
-                
-                self.tera_engine.add_raw_template(&template_config.name, &template_content)
-                    .with_context(|| format!("Failed to register template: {}", template_config.name))?;
+                includes.insert(template_config.name.clone(), referenced_templates(&template_content));
+                sources.push((template_config.name.clone(), template_content));
+            }
+        }
+
+        tera_engine.add_raw_templates(sources).context("Failed to register templates")?;
+        Ok((tera_engine, includes))
+    }
+}
+
+/// Names a template pulls in through `{% include %}`, `{% import %}` or `{% extends %}`
+fn referenced_templates(content: &str) -> Vec<String> {
+    let pattern = regex::Regex::new(r#"\{%-?\s*(?:include|import|extends)\s+["']([^"']+)["']"#)
+        .expect("valid template reference pattern");
+    let mut names: Vec<String> = pattern.captures_iter(content).map(|c| c[1].to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Load a JSON fixture as a template context. The top level must be an object; its keys
+/// become the template variables.
+pub fn load_fixture(path: &Path) -> Result<TeraContext> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read fixture: {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse fixture: {}", path.display()))?;
+    if !value.is_object() {
+        anyhow::bail!("Fixture {} must contain a JSON object", path.display());
+    }
+    TeraContext::from_value(value)
+        .with_context(|| format!("Invalid fixture context: {}", path.display()))
+}
+
+/// What a template would render next to what its output file holds now
+#[derive(Debug, Clone)]
+pub struct RenderPreview {
+    pub name: String,
+    pub output_path: PathBuf,
+    /// `None` when the output file does not exist yet
+    pub current: Option<String>,
+    pub rendered: String,
+}
+
+impl RenderPreview {
+    /// Whether writing the rendered output would change the file
+    pub fn has_drift(&self) -> bool {
+        self.current.as_deref() != Some(self.rendered.as_str())
+    }
+}
+
+/// First line where rendered output departs from the expected output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputMismatch {
+    /// 1-based line number
+    pub line: usize,
+    /// Expected line, or `None` if the expected output ended first
+    pub expected: Option<String>,
+    /// Rendered line, or `None` if the rendered output ended first
+    pub actual: Option<String>,
+}
+
+/// Compare rendered output with the expected text. Line endings and trailing newlines
+/// are normalised so fixtures can be edited on any platform.
+pub fn compare_output(expected: &str, actual: &str) -> Option<OutputMismatch> {
+    let normalize = |text: &str| text.replace("\r\n", "\n").trim_end_matches('\n').to_string();
+    let expected = normalize(expected);
+    let actual = normalize(actual);
+    if expected == actual {
+        return None;
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            (e, a) => {
+                return Some(OutputMismatch {
+                    line,
+                    expected: e.map(str::to_string),
+                    actual: a.map(str::to_string),
+                })
             }
         }
-        
-        Ok(())
     }
 }
 
@@ -378,7 +650,146 @@ mod tests {
         assert!(rendered_content.contains("# test-project v1.0.5.100"));
         assert!(rendered_content.contains("Version bump to 1.0.5.100"));
     }
+
+    #[test]
+    fn test_partials_and_render_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = create_test_state(temp_dir.path());
+        let mut manager = TemplateManager::new(&state).unwrap();
+        let readme = temp_dir.path().join("README.md");
+        let notes = temp_dir.path().join("NOTES.md");
+
+        manager.add_template("header", "Version {{ version }}", temp_dir.path().join("header.md").to_str().unwrap(), None).unwrap();
+        manager.add_template("readme", "{% include \"header\" %}\nReadme", readme.to_str().unwrap(), None).unwrap();
+        manager.add_template("notes", "Notes", notes.to_str().unwrap(), None).unwrap();
+        assert!(manager.configure_template("header", Some(true), None).unwrap());
+        assert!(manager.configure_template("notes", None, Some(vec!["readme".to_string()])).unwrap());
+
+        assert_eq!(manager.render_order().unwrap(), vec!["header", "readme", "notes"]);
+
+        let version_info = VersionInfo::from_version("1.2.3").unwrap();
+        let rendered = manager.render_all_templates(&version_info, None).unwrap();
+        assert_eq!(rendered.len(), 2);
+        assert_eq!(fs::read_to_string(&readme).unwrap(), "Version 1.2.3\nReadme");
+        assert!(!temp_dir.path().join("header.md").exists());
+
+        // Reloading from templates.json keeps the partial and the order
+        let manager = TemplateManager::new(&state).unwrap();
+        assert!(manager.get_template("header").unwrap().partial);
+        assert_eq!(manager.render_order().unwrap(), vec!["header", "readme", "notes"]);
+    }
+
+    #[test]
+    fn test_dependency_cycle_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = create_test_state(temp_dir.path());
+        let mut manager = TemplateManager::new(&state).unwrap();
+        manager.add_template("a", "{% include \"b\" %}", temp_dir.path().join("a.md").to_str().unwrap(), None).unwrap();
+        manager.add_template("b", "B", temp_dir.path().join("b.md").to_str().unwrap(), None).unwrap();
+
+        let error = manager.configure_template("b", None, Some(vec!["a".to_string()])).unwrap_err();
+        assert!(error.to_string().contains("cycle: a -> b -> a"));
+        // The rejected dependency is not kept
+        assert!(manager.get_template("b").unwrap().depends_on.is_empty());
+
+        let error = manager.configure_template("b", None, Some(vec!["missing".to_string()])).unwrap_err();
+        assert!(error.to_string().contains("unknown template 'missing'"));
+    }
+
+    #[test]
+    fn test_preview_reports_drift_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = create_test_state(temp_dir.path());
+        let mut manager = TemplateManager::new(&state).unwrap();
+        let output_path = temp_dir.path().join("VERSION.md");
+        manager.add_template("version_doc", "Version {{ version }}\n", output_path.to_str().unwrap(), None).unwrap();
+        let version_info = VersionInfo::from_version("1.2.3").unwrap();
+
+        let previews = manager.preview_all_templates(&version_info, None).unwrap();
+        assert_eq!(previews.len(), 1);
+        assert!(previews[0].has_drift());
+        assert_eq!(previews[0].current, None);
+        assert!(!output_path.exists());
+
+        manager.render_all_templates(&version_info, None).unwrap();
+        let previews = manager.preview_all_templates(&version_info, None).unwrap();
+        assert!(!previews[0].has_drift());
+    }
     
+    #[test]
+    fn test_render_loops_conditionals_and_flat_placeholders() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = create_test_state(temp_dir.path());
+        let mut manager = TemplateManager::new(&state).unwrap();
+
+        let template_content = "v{{version}} of {{ project_name }}\n\
+            {% for feature in features %}{{ feature.state | feature_state_emoji }} {{ feature.name }}\n{% endfor %}\
+            {% if tasks | length == 0 %}no tasks{% endif %}";
+        let output_path = temp_dir.path().join("STATUS.md");
+        manager.add_template("status", template_content, output_path.to_str().unwrap(), None).unwrap();
+
+        let now = chrono::Utc::now();
+        let feature = |id: &str, name: &str, state: &str| Feature {
+            id: id.to_string(),
+            project_id: "P001".to_string(),
+            code: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            category: None,
+            state: state.to_string(),
+            test_status: "not_tested".to_string(),
+            priority: "medium".to_string(),
+            notes: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+        manager.set_entities(
+            vec![feature("F00001", "Parser", "implemented_passing_tests"), feature("F00002", "Exporter", "not_implemented")],
+            Vec::new(),
+        );
+
+        let version_info = VersionInfo {
+            major_version: "v1".to_string(),
+            minor_version: 2,
+            patch_version: 3,
+            full_version: "1.2.3".to_string(),
+        };
+        manager.render_all_templates(&version_info, Some("demo")).unwrap();
+
+        let rendered = fs::read_to_string(&output_path).unwrap();
+        assert_eq!(rendered, "v1.2.3 of demo\n🟢 Parser\n❌ Exporter\nno tasks");
+    }
+
+    #[test]
+    fn test_render_with_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = create_test_state(temp_dir.path());
+        let mut manager = TemplateManager::new(&state).unwrap();
+        fs::write(temp_dir.path().join("build.json"), r#"{"channel": "beta"}"#).unwrap();
+
+        let output_path = temp_dir.path().join("BUILD.txt");
+        manager.add_template("build", "{{ vars.build.channel }} {{ vars.name }}", output_path.to_str().unwrap(), None).unwrap();
+        let file = NamedSource { name: "build".to_string(), source: sources::ContextSource::File { path: "build.json".to_string() } };
+        let state_name = NamedSource { name: "name".to_string(), source: sources::ContextSource::State { key: "project_name".to_string() } };
+        assert!(manager.add_source("build", file).unwrap());
+        assert!(manager.add_source("build", state_name).unwrap());
+        assert!(!manager.add_source("missing", NamedSource { name: "x".to_string(), source: sources::ContextSource::State { key: "version".to_string() } }).unwrap());
+
+        let version_info = VersionInfo {
+            major_version: "v1".to_string(),
+            minor_version: 0,
+            patch_version: 0,
+            full_version: "1.0.0".to_string(),
+        };
+        // Reload so the sources come from templates.json
+        let manager = TemplateManager::new(&state).unwrap();
+        manager.render_all_templates(&version_info, None).unwrap();
+
+        let expected_name = state.project_name.clone().unwrap_or_default();
+        assert_eq!(fs::read_to_string(&output_path).unwrap(), format!("beta {}", expected_name));
+    }
+
     #[test]
     fn test_template_persistence() {
         let temp_dir = TempDir::new().unwrap();
@@ -399,4 +810,38 @@ mod tests {
             assert_eq!(template.description, Some("Test template".to_string()));
         }
     }
+
+    #[test]
+    fn test_render_with_fixture() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = create_test_state(temp_dir.path());
+        let mut manager = TemplateManager::new(&state).unwrap();
+        manager.add_template("release", "# {{ project.name }} v{{ project.version }}\n", "RELEASE.md", None).unwrap();
+
+        let fixture = temp_dir.path().join("fixture.json");
+        fs::write(&fixture, r#"{"project": {"name": "demo", "version": "2.0.0"}}"#).unwrap();
+        let context = load_fixture(&fixture).unwrap();
+
+        let rendered = manager.render_to_string("release", &context).unwrap();
+        assert_eq!(rendered, "# demo v2.0.0\n");
+        // Nothing is written when rendering for a test
+        assert!(!temp_dir.path().join("RELEASE.md").exists());
+        assert!(manager.render_to_string("missing", &context).is_err());
+
+        fs::write(&fixture, "[1, 2]").unwrap();
+        assert!(load_fixture(&fixture).is_err());
+    }
+
+    #[test]
+    fn test_compare_output() {
+        assert_eq!(compare_output("a\nb\n", "a\r\nb"), None);
+        assert_eq!(
+            compare_output("a\nb\nc", "a\nx\nc"),
+            Some(OutputMismatch { line: 2, expected: Some("b".to_string()), actual: Some("x".to_string()) })
+        );
+        assert_eq!(
+            compare_output("a\nb", "a"),
+            Some(OutputMismatch { line: 2, expected: Some("b".to_string()), actual: None })
+        );
+    }
 }
\ No newline at end of file
@@ -0,0 +1,194 @@
+//! Monotonicity guard for the version pipeline.
+//!
+//! Versions are derived from git (commit count and changes since the last release tag),
+//! so a rebase, squash or other history rewrite can make the calculated version drop
+//! below, or land on, one that was already produced. Every version `wsb update` writes is
+//! recorded in `version_history` with the commit it was calculated at; together with the
+//! `vX.Y.Z` release tags that history is what a new version must exceed. Recalculating
+//! at a commit that already has a version keeps that version. `--force-version` is the
+//! explicit way past the guard.
+
+use anyhow::{Context, Result};
+use sqlx::{Row, SqlitePool};
+use std::process::Command;
+
+/// A version produced earlier, from the history table or a release tag
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorded {
+    pub version: String,
+    /// Commit the version belongs to; `None` outside a git repository
+    pub commit: Option<String>,
+    /// Where it was found: `history` or `tag <name>`
+    pub source: String,
+}
+
+/// What to do with a calculated version
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The version is newer than anything recorded
+    Advance,
+    /// HEAD already has this (newer or equal) version; keep using it
+    Keep(String),
+}
+
+/// `major.minor.patch` of a version string, with an optional `v` prefix.
+/// Pre-release (`-alpha.1`) and build (`+sha`) suffixes are ignored.
+pub fn parse(version: &str) -> Option<(u32, u32, u32)> {
+    let release = version.trim().trim_start_matches('v');
+    let release = release.split(['-', '+']).next()?;
+    let mut parts = release.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+fn short(commit: &Option<String>) -> String {
+    match commit {
+        Some(commit) => commit.chars().take(8).collect(),
+        None => "no commit".to_string(),
+    }
+}
+
+/// Check `candidate`, calculated at `head`, against what was recorded before.
+/// `in_history` says whether a commit is still reachable from HEAD.
+pub fn check(candidate: &str, head: Option<&str>, recorded: &[Recorded], in_history: impl Fn(&str) -> bool) -> Result<Verdict> {
+    let wanted = parse(candidate).ok_or_else(|| anyhow::anyhow!("'{}' is not a major.minor.patch version", candidate))?;
+    let highest = match recorded.iter().filter_map(|r| parse(&r.version).map(|v| (v, r))).max_by_key(|(v, _)| *v) {
+        Some(highest) => highest,
+        None => return Ok(Verdict::Advance),
+    };
+    let (last, record) = highest;
+    if wanted > last {
+        return Ok(Verdict::Advance);
+    }
+    if record.commit.as_deref() == head {
+        return Ok(Verdict::Keep(record.version.clone()));
+    }
+
+    let problem = if wanted == last { "duplicates" } else { "is lower than" };
+    let mut message = format!(
+        "Calculated version {} {} {} ({}, commit {})",
+        candidate, problem, record.version, record.source, short(&record.commit)
+    );
+    if let Some(commit) = &record.commit {
+        if !in_history(commit) {
+            message.push_str(&format!(
+                "\nCommit {} is no longer in the history of HEAD; it was probably rebased or rewritten",
+                short(&record.commit)
+            ));
+        }
+    }
+    message.push_str("\nRaise the major version with `wsb version major <n>`, or pass `--force-version <v>` to override");
+    anyhow::bail!(message)
+}
+
+/// Versions recorded by earlier updates, oldest first
+pub async fn history(pool: &SqlitePool) -> Result<Vec<Recorded>> {
+    let rows = sqlx::query("SELECT version, commit_hash FROM version_history ORDER BY id")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| Recorded { version: row.get("version"), commit: row.get("commit_hash"), source: "history".to_string() })
+        .collect())
+}
+
+/// Record that `version` was produced at `commit`; recording the same pair again is a no-op
+pub async fn record(pool: &SqlitePool, version: &str, commit: Option<&str>, forced: bool) -> Result<()> {
+    sqlx::query(r#"
+        INSERT INTO version_history (version, commit_hash, forced, recorded_at)
+        SELECT ?, ?, ?, ?
+        WHERE NOT EXISTS (SELECT 1 FROM version_history WHERE version = ? AND commit_hash IS ?)
+    "#)
+    .bind(version)
+    .bind(commit)
+    .bind(forced)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(version)
+    .bind(commit)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The commit HEAD points at, if there is one
+pub fn head_commit() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Release tags that name a full `vX.Y.Z` version, with the commits they point at
+pub fn release_tags() -> Result<Vec<Recorded>> {
+    let output = Command::new("git")
+        .args(["for-each-ref", "refs/tags", "--format=%(refname:short) %(objectname) %(*objectname)"])
+        .output()
+        .context("Failed to run git for-each-ref")?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    let listing = String::from_utf8(output.stdout).context("Invalid UTF-8 in git tag output")?;
+    Ok(listing
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            parse(name)?;
+            // Annotated tags list the tagged commit last
+            let commit = fields.last()?.to_string();
+            Some(Recorded { version: name.trim_start_matches('v').to_string(), commit: Some(commit), source: format!("tag {}", name) })
+        })
+        .collect())
+}
+
+/// Whether `commit` is HEAD or one of its ancestors
+pub fn in_history(commit: &str) -> bool {
+    Command::new("git")
+        .args(["merge-base", "--is-ancestor", commit, "HEAD"])
+        .output()
+        .map_or(false, |output| output.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(version: &str, commit: &str) -> Recorded {
+        Recorded { version: version.to_string(), commit: Some(commit.to_string()), source: "history".to_string() }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("v1.12.3"), Some((1, 12, 3)));
+        assert_eq!(parse("0.4.0"), Some((0, 4, 0)));
+        assert_eq!(parse("v1.0"), None);
+        assert_eq!(parse("1.2.3.4"), None);
+        assert_eq!(parse("v2.0.0-alpha.1"), Some((2, 0, 0)));
+        assert_eq!(parse("1.4.2+build.7"), Some((1, 4, 2)));
+    }
+
+    #[test]
+    fn test_check_verdicts() {
+        let recorded = vec![recorded("0.9.40", "aaaa1111"), recorded("0.10.2", "bbbb2222")];
+
+        assert_eq!(check("0.11.0", Some("cccc3333"), &recorded, |_| true).unwrap(), Verdict::Advance);
+        assert_eq!(check("0.10.0", Some("bbbb2222"), &recorded, |_| true).unwrap(), Verdict::Keep("0.10.2".to_string()));
+        assert_eq!(check("1.0.0", None, &[], |_| true).unwrap(), Verdict::Advance);
+
+        let duplicate = check("0.10.2", Some("cccc3333"), &recorded, |_| true).unwrap_err().to_string();
+        assert!(duplicate.contains("duplicates 0.10.2"));
+        assert!(!duplicate.contains("rewritten"));
+
+        let regressed = check("0.9.50", Some("cccc3333"), &recorded, |_| false).unwrap_err().to_string();
+        assert!(regressed.contains("is lower than 0.10.2"));
+        assert!(regressed.contains("rebased or rewritten"));
+        assert!(regressed.contains("--force-version"));
+    }
+}
@@ -0,0 +1,116 @@
+//! "Did you mean ...?" suggestions for mistyped subcommands, flags, and entity IDs.
+//!
+//! Shared by the clap error path in the CLI and by entity lookup failures so
+//! both produce the same kind of hint.
+
+/// Levenshtein edit distance between two strings (case-sensitive, by char)
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.is_empty() {
+        return b.len();
+    }
+    if b.is_empty() {
+        return a.len();
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j + 1] + 1)
+                .min(current[j] + 1)
+                .min(previous[j] + cost);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Maximum distance accepted for a suggestion of the given input
+fn max_distance(input: &str) -> usize {
+    match input.chars().count() {
+        0..=3 => 1,
+        4..=6 => 2,
+        _ => 3,
+    }
+}
+
+/// Candidates within the accepted distance, closest first
+pub fn suggest_all<'a, I>(input: &str, candidates: I) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let needle = input.to_lowercase();
+    let limit = max_distance(input);
+
+    let mut scored: Vec<(usize, &'a str)> = candidates
+        .into_iter()
+        .filter(|candidate| *candidate != input)
+        .map(|candidate| (levenshtein(&needle, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Closest candidate within the accepted distance, if any
+pub fn suggest<'a, I>(input: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    suggest_all(input, candidates).into_iter().next()
+}
+
+/// Format a "did you mean" hint, or an empty string when nothing is close
+pub fn did_you_mean<'a, I>(input: &str, candidates: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    match suggest(input, candidates) {
+        Some(candidate) => format!("did you mean `{}`?", candidate),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("feature", "feature"), 0);
+        assert_eq!(levenshtein("featrue", "feature"), 2);
+        assert_eq!(levenshtein("lst", "list"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_suggest_subcommands() {
+        let commands = ["feature", "task", "status", "note"];
+        assert_eq!(suggest("featrue", commands), Some("feature"));
+        assert_eq!(suggest("stauts", commands), Some("status"));
+        assert_eq!(suggest("xyzzy", commands), None);
+    }
+
+    #[test]
+    fn test_suggest_entity_ids() {
+        let ids = ["F00042", "F00420", "F01234"];
+        assert_eq!(suggest("F0420", ids), Some("F00420"));
+        assert_eq!(suggest_all("F0042", ids), vec!["F00042", "F00420"]);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        assert_eq!(did_you_mean("lst", ["list", "show"]), "did you mean `list`?");
+        assert_eq!(did_you_mean("zzz", ["list", "show"]), "");
+    }
+}
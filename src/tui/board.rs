@@ -0,0 +1,451 @@
+//! Kanban board for `wsb board`
+//!
+//! Tasks are laid out in one column per status. A card is "dragged" by picking it up
+//! with Enter/Space, moving to another column and dropping it there; the drop is checked
+//! against `TaskStatus::can_transition_to` and written through `EntityManager`, so the
+//! usual events and audit entries are recorded.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::entities::schema_models::{Task, TaskStatus};
+use crate::entities::EntityManager;
+
+/// Column order on the board
+pub const COLUMNS: [TaskStatus; 5] = [
+    TaskStatus::Pending,
+    TaskStatus::InProgress,
+    TaskStatus::Blocked,
+    TaskStatus::Completed,
+    TaskStatus::Cancelled,
+];
+
+const PRIORITIES: [&str; 3] = ["high", "medium", "low"];
+
+fn column_title(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "Pending",
+        TaskStatus::InProgress => "In progress",
+        TaskStatus::Blocked => "Blocked",
+        TaskStatus::Completed => "Completed",
+        TaskStatus::Cancelled => "Cancelled",
+    }
+}
+
+fn priority_rank(priority: &str) -> usize {
+    PRIORITIES.iter().position(|p| *p == priority).unwrap_or(PRIORITIES.len())
+}
+
+fn priority_color(priority: &str) -> Color {
+    match priority {
+        "high" => Color::Red,
+        "medium" => Color::Yellow,
+        _ => Color::Gray,
+    }
+}
+
+/// A task as shown on the board
+#[derive(Debug, Clone)]
+pub struct Card {
+    pub id: String,
+    pub title: String,
+    pub feature_id: String,
+    pub priority: String,
+    pub status: TaskStatus,
+}
+
+impl Card {
+    /// None for rows whose status is not one the board knows
+    pub fn from_task(task: Task) -> Option<Self> {
+        let status = TaskStatus::from_str(&task.status).ok()?;
+        Some(Self {
+            id: task.id,
+            title: task.task,
+            feature_id: task.feature_id,
+            priority: task.priority,
+            status,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BoardFilter {
+    pub feature: Option<String>,
+    pub priority: Option<String>,
+}
+
+impl BoardFilter {
+    fn matches(&self, card: &Card) -> bool {
+        let feature = match &self.feature {
+            Some(f) => *f == card.feature_id,
+            None => true,
+        };
+        let priority = match &self.priority {
+            Some(p) => *p == card.priority,
+            None => true,
+        };
+        feature && priority
+    }
+
+    fn describe(&self) -> String {
+        match (&self.feature, &self.priority) {
+            (None, None) => "all tasks".to_string(),
+            (Some(f), None) => format!("feature {}", f),
+            (None, Some(p)) => format!("{} priority", p),
+            (Some(f), Some(p)) => format!("feature {}, {} priority", f, p),
+        }
+    }
+}
+
+/// What the event loop should do after a key press
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    None,
+    Quit,
+    Reload,
+    Move { task_id: String, status: TaskStatus },
+}
+
+/// Board state, independent of the terminal
+pub struct Board {
+    cards: Vec<Card>,
+    pub filter: BoardFilter,
+    column: usize,
+    rows: [usize; COLUMNS.len()],
+    carrying: Option<String>,
+    pub message: Option<String>,
+}
+
+impl Board {
+    pub fn new(cards: Vec<Card>, filter: BoardFilter) -> Self {
+        Self { cards, filter, column: 0, rows: [0; COLUMNS.len()], carrying: None, message: None }
+    }
+
+    /// Replace the cards after a reload, dropping a carried card that no longer exists
+    pub fn set_cards(&mut self, cards: Vec<Card>) {
+        self.cards = cards;
+        if let Some(id) = &self.carrying {
+            if !self.cards.iter().any(|c| &c.id == id) {
+                self.carrying = None;
+            }
+        }
+    }
+
+    /// Visible cards in a column, highest priority first
+    pub fn column_cards(&self, column: usize) -> Vec<&Card> {
+        let mut cards: Vec<&Card> = self
+            .cards
+            .iter()
+            .filter(|c| c.status == COLUMNS[column] && self.filter.matches(c))
+            .collect();
+        cards.sort_by(|a, b| priority_rank(&a.priority).cmp(&priority_rank(&b.priority)).then_with(|| a.id.cmp(&b.id)));
+        cards
+    }
+
+    fn row(&self) -> usize {
+        let len = self.column_cards(self.column).len();
+        self.rows[self.column].min(len.saturating_sub(1))
+    }
+
+    pub fn selected(&self) -> Option<&Card> {
+        self.column_cards(self.column).get(self.row()).copied()
+    }
+
+    pub fn carrying(&self) -> Option<&str> {
+        self.carrying.as_deref()
+    }
+
+    pub fn move_column(&mut self, delta: isize) {
+        let last = COLUMNS.len() as isize - 1;
+        self.column = (self.column as isize + delta).clamp(0, last) as usize;
+    }
+
+    pub fn move_row(&mut self, delta: isize) {
+        let len = self.column_cards(self.column).len() as isize;
+        self.rows[self.column] = (self.row() as isize + delta).clamp(0, (len - 1).max(0)) as usize;
+    }
+
+    /// Pick up the selected card, or drop the carried one on the focused column
+    pub fn pick_or_drop(&mut self) -> Action {
+        let Some(task_id) = self.carrying.clone() else {
+            if let Some(id) = self.selected().map(|c| c.id.clone()) {
+                self.message = Some(format!("Moving {}: choose a column and press Enter (Esc cancels)", id));
+                self.carrying = Some(id);
+            }
+            return Action::None;
+        };
+
+        let target = COLUMNS[self.column].clone();
+        let Some(card) = self.cards.iter().find(|c| c.id == task_id) else {
+            self.carrying = None;
+            return Action::None;
+        };
+        if card.status == target {
+            self.carrying = None;
+            self.message = None;
+            return Action::None;
+        }
+        if !card.status.can_transition_to(&target) {
+            self.message = Some(format!(
+                "{} cannot move from {} to {}",
+                card.id,
+                column_title(&card.status),
+                column_title(&target)
+            ));
+            return Action::None;
+        }
+        self.carrying = None;
+        Action::Move { task_id, status: target }
+    }
+
+    /// Reflect a status change that has been written to the database
+    pub fn apply_move(&mut self, task_id: &str, status: TaskStatus) {
+        if let Some(card) = self.cards.iter_mut().find(|c| c.id == task_id) {
+            card.status = status.clone();
+        }
+        if let Some(column) = COLUMNS.iter().position(|s| *s == status) {
+            self.column = column;
+            if let Some(row) = self.column_cards(column).iter().position(|c| c.id == task_id) {
+                self.rows[column] = row;
+            }
+        }
+        self.message = Some(format!("{} → {}", task_id, column_title(&status)));
+    }
+
+    pub fn cycle_feature_filter(&mut self) {
+        let mut features: Vec<&str> = self.cards.iter().map(|c| c.feature_id.as_str()).collect();
+        features.sort();
+        features.dedup();
+        self.filter.feature = next_option(&features, self.filter.feature.as_deref());
+    }
+
+    pub fn cycle_priority_filter(&mut self) {
+        self.filter.priority = next_option(&PRIORITIES, self.filter.priority.as_deref());
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> Action {
+        match code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Char('r') => return Action::Reload,
+            KeyCode::Left | KeyCode::Char('h') => self.move_column(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_column(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_row(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_row(1),
+            KeyCode::Enter | KeyCode::Char(' ') => return self.pick_or_drop(),
+            KeyCode::Esc => {
+                self.carrying = None;
+                self.message = None;
+            }
+            KeyCode::Char('f') => self.cycle_feature_filter(),
+            KeyCode::Char('p') => self.cycle_priority_filter(),
+            KeyCode::Char('c') => self.filter = BoardFilter::default(),
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// Step through `None, options[0], options[1], ..., None`
+fn next_option(options: &[&str], current: Option<&str>) -> Option<String> {
+    let next = match current.and_then(|c| options.iter().position(|o| *o == c)) {
+        Some(i) => options.get(i + 1),
+        None => options.first(),
+    };
+    next.map(|s| s.to_string())
+}
+
+async fn load_cards(manager: &EntityManager, project_id: &str) -> Result<Vec<Card>> {
+    let tasks = manager.list_tasks_by_project(project_id, None).await?;
+    Ok(tasks.into_iter().filter_map(Card::from_task).collect())
+}
+
+/// Show the board for a project until the user quits
+pub async fn run(manager: &EntityManager, project_id: &str, filter: BoardFilter) -> Result<()> {
+    let mut board = Board::new(load_cards(manager, project_id).await?, filter);
+    let mut terminal = super::enter()?;
+    let result = event_loop(&mut terminal, &mut board, manager, project_id).await;
+    super::leave(&mut terminal)?;
+    result
+}
+
+async fn event_loop(terminal: &mut super::Term, board: &mut Board, manager: &EntityManager, project_id: &str) -> Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, board))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(());
+        }
+
+        match board.handle_key(key.code) {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::Reload => match load_cards(manager, project_id).await {
+                Ok(cards) => {
+                    board.set_cards(cards);
+                    board.message = Some("Reloaded".to_string());
+                }
+                Err(e) => board.message = Some(format!("Reload failed: {}", e)),
+            },
+            Action::Move { task_id, status } => match manager.update_task_status(&task_id, status.clone()).await {
+                Ok(()) => board.apply_move(&task_id, status),
+                Err(e) => board.message = Some(format!("Could not move {}: {}", task_id, e)),
+            },
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, board: &Board) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(4)])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, COLUMNS.len() as u32); COLUMNS.len()])
+        .split(rows[0]);
+
+    for (index, status) in COLUMNS.iter().enumerate() {
+        let cards = board.column_cards(index);
+        let items: Vec<ListItem> = cards
+            .iter()
+            .map(|card| {
+                let mut title = Style::default().fg(priority_color(&card.priority));
+                if board.carrying() == Some(card.id.as_str()) {
+                    title = title.add_modifier(Modifier::REVERSED);
+                }
+                ListItem::new(vec![
+                    Line::from(Span::styled(format!("{} {}", card.id, card.title), title)),
+                    Line::from(Span::styled(
+                        format!("  {} · {}", card.feature_id, card.priority),
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                ])
+            })
+            .collect();
+
+        let focused = index == board.column;
+        let border = if focused && board.carrying().is_some() {
+            Style::default().fg(Color::Green)
+        } else if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(format!(" {} ({}) ", column_title(status), cards.len())),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(Color::DarkGray));
+
+        let mut state = ListState::default();
+        if focused && !cards.is_empty() {
+            state.select(Some(board.row()));
+        }
+        frame.render_stateful_widget(list, columns[index], &mut state);
+    }
+
+    let status = board.message.clone().unwrap_or_else(|| format!("Showing {}", board.filter.describe()));
+    let footer = Paragraph::new(vec![
+        Line::from(status),
+        Line::from(Span::styled(
+            "←/→ column  ↑/↓ card  Enter pick up/drop  Esc cancel  f feature  p priority  c clear  r reload  q quit",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ])
+    .block(Block::default().borders(Borders::ALL).title(" wsb board "));
+    frame.render_widget(footer, rows[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str, feature: &str, priority: &str, status: TaskStatus) -> Card {
+        Card {
+            id: id.to_string(),
+            title: format!("Task {}", id),
+            feature_id: feature.to_string(),
+            priority: priority.to_string(),
+            status,
+        }
+    }
+
+    fn sample() -> Board {
+        Board::new(
+            vec![
+                card("T000001", "F00001", "low", TaskStatus::Pending),
+                card("T000002", "F00001", "high", TaskStatus::Pending),
+                card("T000003", "F00002", "medium", TaskStatus::InProgress),
+                card("T000004", "F00002", "high", TaskStatus::Completed),
+            ],
+            BoardFilter::default(),
+        )
+    }
+
+    #[test]
+    fn test_columns_sort_by_priority_and_filter() {
+        let mut board = sample();
+        let pending: Vec<&str> = board.column_cards(0).iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(pending, vec!["T000002", "T000001"]);
+
+        board.handle_key(KeyCode::Char('f'));
+        assert_eq!(board.filter.feature.as_deref(), Some("F00001"));
+        assert!(board.column_cards(1).is_empty());
+        board.handle_key(KeyCode::Char('p'));
+        assert_eq!(board.column_cards(0).len(), 1);
+
+        board.handle_key(KeyCode::Char('f'));
+        board.handle_key(KeyCode::Char('f'));
+        assert_eq!(board.filter.feature, None);
+        board.handle_key(KeyCode::Char('c'));
+        assert_eq!(board.filter.priority, None);
+    }
+
+    #[test]
+    fn test_drag_between_columns() {
+        let mut board = sample();
+        board.handle_key(KeyCode::Down);
+        assert_eq!(board.selected().unwrap().id, "T000001");
+
+        assert_eq!(board.handle_key(KeyCode::Enter), Action::None);
+        assert_eq!(board.carrying(), Some("T000001"));
+
+        // Pending cannot jump straight to Completed
+        board.handle_key(KeyCode::Right);
+        board.handle_key(KeyCode::Right);
+        board.handle_key(KeyCode::Right);
+        assert_eq!(board.handle_key(KeyCode::Enter), Action::None);
+        assert!(board.message.as_deref().unwrap().contains("cannot move"));
+        assert_eq!(board.carrying(), Some("T000001"));
+
+        board.handle_key(KeyCode::Left);
+        board.handle_key(KeyCode::Left);
+        let action = board.handle_key(KeyCode::Char(' '));
+        assert_eq!(action, Action::Move { task_id: "T000001".to_string(), status: TaskStatus::InProgress });
+        board.apply_move("T000001", TaskStatus::InProgress);
+        assert_eq!(board.selected().unwrap().id, "T000001");
+        assert_eq!(board.column_cards(1).len(), 2);
+
+        board.handle_key(KeyCode::Enter);
+        board.handle_key(KeyCode::Esc);
+        assert_eq!(board.carrying(), None);
+        assert_eq!(board.handle_key(KeyCode::Char('q')), Action::Quit);
+    }
+}
@@ -0,0 +1,407 @@
+//! Project dashboard for `wsb dashboard`
+//!
+//! A snapshot of the current project read straight from the database: how many features
+//! are in each state, the latest sessions, checks that are failing (features with failing
+//! or broken tests, directives whose last validation found violations) and the context
+//! usage last recorded for a session. `--tui` redraws it on an interval, so it works as an
+//! offline stand-in for the MCP web dashboard.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph};
+use ratatui::Frame;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+use crate::entities::directive_rules;
+use crate::entities::schema_models::{FeatureState, Project, TaskStatus};
+use crate::entities::{crud, workspace};
+
+/// Token budget the MCP server measures context usage against
+pub const CONTEXT_WINDOW_TOKENS: i64 = 200_000;
+
+const RECENT_SESSIONS: usize = 5;
+
+const FEATURE_STATES: [FeatureState; 6] = [
+    FeatureState::ImplementedPassingTests,
+    FeatureState::ImplementedFailingTests,
+    FeatureState::ImplementedNoTests,
+    FeatureState::NotImplemented,
+    FeatureState::TestsBroken,
+    FeatureState::CriticalIssue,
+];
+
+fn state_label(state: &FeatureState) -> &'static str {
+    match state {
+        FeatureState::NotImplemented => "Not implemented",
+        FeatureState::ImplementedNoTests => "No tests",
+        FeatureState::ImplementedFailingTests => "Failing tests",
+        FeatureState::ImplementedPassingTests => "Passing tests",
+        FeatureState::TestsBroken => "Tests broken",
+        FeatureState::CriticalIssue => "Critical issue",
+    }
+}
+
+fn is_failing(state: &FeatureState) -> bool {
+    matches!(state, FeatureState::ImplementedFailingTests | FeatureState::TestsBroken | FeatureState::CriticalIssue)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateCount {
+    pub state: String,
+    pub emoji: String,
+    pub label: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub title: String,
+    pub state: String,
+    pub date: String,
+    pub focus: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailingCheck {
+    /// "feature" or "directive"
+    pub kind: String,
+    pub id: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextUsage {
+    pub session_id: String,
+    pub tokens: i64,
+    pub percent: f64,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    pub project_id: String,
+    pub project_name: String,
+    pub taken_at: DateTime<Utc>,
+    pub feature_total: usize,
+    pub feature_states: Vec<StateCount>,
+    pub task_statuses: Vec<StateCount>,
+    pub recent_sessions: Vec<SessionSummary>,
+    pub failing_checks: Vec<FailingCheck>,
+    pub context: Option<ContextUsage>,
+}
+
+impl Snapshot {
+    /// Share of features whose tests pass, 0.0 with no features
+    pub fn passing_ratio(&self) -> f64 {
+        if self.feature_total == 0 {
+            return 0.0;
+        }
+        let passing = self
+            .feature_states
+            .iter()
+            .find(|s| s.state == FeatureState::ImplementedPassingTests.as_str())
+            .map_or(0, |s| s.count);
+        passing as f64 / self.feature_total as f64
+    }
+}
+
+/// Read the dashboard for a project
+pub async fn load(pool: &SqlitePool, project: &Project) -> Result<Snapshot> {
+    let features = crud::features::list_by_project(pool, &project.id).await?;
+    let mut failing_checks = Vec::new();
+    let feature_states = FEATURE_STATES
+        .iter()
+        .map(|state| {
+            let matching: Vec<_> = features.iter().filter(|f| f.state == state.as_str()).collect();
+            if is_failing(state) {
+                for feature in &matching {
+                    failing_checks.push(FailingCheck {
+                        kind: "feature".to_string(),
+                        id: feature.id.clone(),
+                        detail: format!("{} ({})", feature.name, state_label(state).to_lowercase()),
+                    });
+                }
+            }
+            StateCount {
+                state: state.as_str().to_string(),
+                emoji: state.emoji().to_string(),
+                label: state_label(state).to_string(),
+                count: matching.len(),
+            }
+        })
+        .collect();
+
+    let mut directives: Vec<_> = directive_rules::validation_states(pool)
+        .await?
+        .into_iter()
+        .filter(|(_, state)| state.violation_count > 0)
+        .collect();
+    directives.sort_by(|a, b| a.0.cmp(&b.0));
+    for (id, state) in directives {
+        failing_checks.push(FailingCheck {
+            kind: "directive".to_string(),
+            id,
+            detail: format!("{} violation(s) at {}", state.violation_count, state.validated_at),
+        });
+    }
+
+    let tasks = crud::tasks::list_by_project(pool, &project.id, None).await?;
+    let task_statuses = [
+        TaskStatus::Pending,
+        TaskStatus::InProgress,
+        TaskStatus::Blocked,
+        TaskStatus::Completed,
+        TaskStatus::Cancelled,
+    ]
+    .iter()
+    .map(|status| StateCount {
+        state: status.as_str().to_string(),
+        emoji: String::new(),
+        label: status.as_str().replace('_', " "),
+        count: tasks.iter().filter(|t| t.status == status.as_str()).count(),
+    })
+    .collect();
+
+    let recent_sessions = crud::sessions::list_by_project(pool, &project.id)
+        .await?
+        .into_iter()
+        .take(RECENT_SESSIONS)
+        .map(|s| SessionSummary { id: s.id, title: s.title, state: s.status, date: s.date, focus: s.focus })
+        .collect();
+
+    Ok(Snapshot {
+        project_id: project.id.clone(),
+        project_name: project.name.clone(),
+        taken_at: Utc::now(),
+        feature_total: features.len(),
+        feature_states,
+        task_statuses,
+        recent_sessions,
+        failing_checks,
+        context: latest_context_usage(pool, &project.id).await?,
+    })
+}
+
+async fn latest_context_usage(pool: &SqlitePool, project_id: &str) -> Result<Option<ContextUsage>> {
+    let row = sqlx::query(r#"
+        SELECT m.session_id, m.context_usage_tokens, m.timestamp
+        FROM session_metrics m JOIN sessions s ON s.id = m.session_id
+        WHERE s.project_id = ?
+        ORDER BY m.timestamp DESC LIMIT 1
+    "#)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| {
+        let tokens: i64 = row.get("context_usage_tokens");
+        ContextUsage {
+            session_id: row.get("session_id"),
+            tokens,
+            percent: tokens as f64 * 100.0 / CONTEXT_WINDOW_TOKENS as f64,
+            recorded_at: row.get("timestamp"),
+        }
+    }))
+}
+
+/// Redraw the current project's dashboard every `interval` until the user quits
+pub async fn run(pool: &SqlitePool, interval: Duration) -> Result<()> {
+    let mut snapshot = load_current(pool).await?;
+    let mut terminal = super::enter()?;
+    let result = event_loop(&mut terminal, pool, &mut snapshot, interval).await;
+    super::leave(&mut terminal)?;
+    result
+}
+
+async fn load_current(pool: &SqlitePool) -> Result<Snapshot> {
+    let project = workspace::current_project(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No active project found"))?;
+    load(pool, &project).await
+}
+
+async fn event_loop(terminal: &mut super::Term, pool: &SqlitePool, snapshot: &mut Snapshot, interval: Duration) -> Result<()> {
+    let mut error: Option<String> = None;
+    let mut refreshed = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, snapshot, error.as_deref()))?;
+
+        let wait = interval.saturating_sub(refreshed.elapsed());
+        let mut refresh = wait.is_zero();
+        if !refresh && event::poll(wait)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                        KeyCode::Char('r') => refresh = true,
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if refresh {
+            refreshed = Instant::now();
+            match load_current(pool).await {
+                Ok(latest) => {
+                    *snapshot = latest;
+                    error = None;
+                }
+                Err(e) => error = Some(format!("Refresh failed: {}", e)),
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, snapshot: &Snapshot, error: Option<&str>) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(FEATURE_STATES.len() as u16 + 2),
+            Constraint::Min(4),
+            Constraint::Length(3),
+        ])
+        .split(frame.size());
+
+    let heading = Paragraph::new(Line::from(vec![
+        Span::styled(snapshot.project_name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!("  {}  ·  {} features", snapshot.project_id, snapshot.feature_total)),
+    ]))
+    .block(Block::default().borders(Borders::ALL).title(" wsb dashboard "));
+    frame.render_widget(heading, rows[0]);
+
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(25), Constraint::Percentage(35)])
+        .split(rows[1]);
+
+    let states: Vec<ListItem> = snapshot
+        .feature_states
+        .iter()
+        .map(|s| ListItem::new(format!("{} {:<16}{:>5}", s.emoji, s.label, s.count)))
+        .collect();
+    frame.render_widget(List::new(states).block(Block::default().borders(Borders::ALL).title(" Features ")), top[0]);
+
+    let tasks: Vec<ListItem> = snapshot
+        .task_statuses
+        .iter()
+        .map(|s| ListItem::new(format!("{:<12}{:>5}", s.label, s.count)))
+        .collect();
+    frame.render_widget(List::new(tasks).block(Block::default().borders(Borders::ALL).title(" Tasks ")), top[1]);
+
+    let gauges = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .split(top[2]);
+    let passing = snapshot.passing_ratio();
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(" Passing tests "))
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(passing)
+            .label(format!("{:.0}%", passing * 100.0)),
+        gauges[0],
+    );
+    let context = match &snapshot.context {
+        Some(usage) => {
+            let color = if usage.percent >= 85.0 { Color::Red } else if usage.percent >= 60.0 { Color::Yellow } else { Color::Cyan };
+            Gauge::default()
+                .gauge_style(Style::default().fg(color))
+                .ratio((usage.percent / 100.0).clamp(0.0, 1.0))
+                .label(format!("{:.0}% · {} tokens · {}", usage.percent, usage.tokens, usage.session_id))
+        }
+        None => Gauge::default().ratio(0.0).label("not recorded"),
+    };
+    frame.render_widget(context.block(Block::default().borders(Borders::ALL).title(" Context usage ")), gauges[1]);
+
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[2]);
+
+    let sessions: Vec<ListItem> = if snapshot.recent_sessions.is_empty() {
+        vec![ListItem::new(Span::styled("No sessions yet", Style::default().fg(Color::DarkGray)))]
+    } else {
+        snapshot
+            .recent_sessions
+            .iter()
+            .map(|s| {
+                ListItem::new(vec![
+                    Line::from(format!("{} {} [{}]", s.id, s.title, s.state)),
+                    Line::from(Span::styled(format!("  {} · {}", s.date, s.focus), Style::default().fg(Color::DarkGray))),
+                ])
+            })
+            .collect()
+    };
+    frame.render_widget(List::new(sessions).block(Block::default().borders(Borders::ALL).title(" Recent sessions ")), middle[0]);
+
+    let checks: Vec<ListItem> = if snapshot.failing_checks.is_empty() {
+        vec![ListItem::new(Span::styled("All checks passing", Style::default().fg(Color::Green)))]
+    } else {
+        snapshot
+            .failing_checks
+            .iter()
+            .map(|c| ListItem::new(Span::styled(format!("{} {}", c.id, c.detail), Style::default().fg(Color::Red))))
+            .collect()
+    };
+    let checks_title = format!(" Failing checks ({}) ", snapshot.failing_checks.len());
+    frame.render_widget(List::new(checks).block(Block::default().borders(Borders::ALL).title(checks_title)), middle[1]);
+
+    let status = match error {
+        Some(message) => Span::styled(message.to_string(), Style::default().fg(Color::Red)),
+        None => Span::styled(
+            format!("Updated {}  ·  r refresh  ·  q quit", snapshot.taken_at.with_timezone(&chrono::Local).format("%H:%M:%S")),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    frame.render_widget(Paragraph::new(Line::from(status)).block(Block::default().borders(Borders::ALL)), rows[3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_snapshot_counts_and_failing_checks() {
+        let temp = TempDir::new().unwrap();
+        let pool = crate::entities::database::initialize_database(&temp.path().join("project.db")).await.unwrap();
+        let project = crud::projects::create(&pool, "Dashboard".to_string(), "Status".to_string()).await.unwrap();
+        let storage = crud::features::create(&pool, project.id.clone(), "Storage".to_string(), "Data".to_string(), None).await.unwrap();
+        crud::features::create(&pool, project.id.clone(), "Sync".to_string(), "Replication".to_string(), None).await.unwrap();
+        crud::features::update_state(&pool, &storage.id, FeatureState::CriticalIssue).await.unwrap();
+        crud::tasks::create(&pool, project.id.clone(), storage.id.clone(), "Fix corruption".to_string(), "bug".to_string()).await.unwrap();
+        let session = crud::sessions::create(&pool, project.id.clone(), "Triage".to_string(), None).await.unwrap();
+
+        sqlx::query("INSERT INTO session_metrics (id, session_id, context_usage_tokens) VALUES ('M1', ?, 50000)")
+            .bind(&session.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let snapshot = load(&pool, &project).await.unwrap();
+        assert_eq!(snapshot.feature_total, 2);
+        let count = |state: FeatureState| snapshot.feature_states.iter().find(|s| s.state == state.as_str()).unwrap().count;
+        assert_eq!(count(FeatureState::CriticalIssue), 1);
+        assert_eq!(count(FeatureState::NotImplemented), 1);
+        assert_eq!(snapshot.task_statuses[0].count, 1);
+        assert_eq!(snapshot.recent_sessions[0].id, session.id);
+        assert_eq!(snapshot.failing_checks.len(), 1);
+        assert_eq!(snapshot.failing_checks[0].id, storage.id);
+        assert_eq!(snapshot.passing_ratio(), 0.0);
+
+        let context = snapshot.context.unwrap();
+        assert_eq!(context.tokens, 50000);
+        assert!((context.percent - 25.0).abs() < f64::EPSILON);
+    }
+}
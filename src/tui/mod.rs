@@ -0,0 +1,46 @@
+//! Full-screen terminal interfaces built on ratatui.
+//!
+//! `wsb board` is a kanban board of the current project's tasks and `wsb dashboard --tui`
+//! a live project status screen. Each screen keeps its
+//! state in a plain struct that the key handling updates, so the behaviour can be tested
+//! without a terminal; the loops here only draw that state and read keys.
+
+pub mod board;
+pub mod dashboard;
+
+use std::io::{self, Stdout};
+
+use anyhow::Result;
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+
+pub type Term = Terminal<CrosstermBackend<Stdout>>;
+
+/// Fail early with a readable message instead of drawing into a pipe
+pub fn require_terminal(command: &str) -> Result<()> {
+    if !atty::is(atty::Stream::Stdout) || !atty::is(atty::Stream::Stdin) {
+        anyhow::bail!("{} needs an interactive terminal", command);
+    }
+    Ok(())
+}
+
+/// Switch to the alternate screen in raw mode
+pub fn enter() -> Result<Term> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    if let Err(e) = execute!(stdout, EnterAlternateScreen) {
+        disable_raw_mode()?;
+        return Err(e.into());
+    }
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+/// Restore the terminal; call this even when the screen's loop failed
+pub fn leave(terminal: &mut Term) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
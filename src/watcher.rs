@@ -0,0 +1,341 @@
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Directories whose changes are never reported, whatever the ignore files say
+const ALWAYS_IGNORED: &[&str] = &[".git", ".scrap"];
+
+/// Settings for a [`WatchService`]
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// Quiet period after the last change before a batch is delivered
+    pub debounce: Duration,
+    /// Skip paths matched by the root's `.gitignore`/`.ignore` files
+    pub respect_ignore: bool,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(300),
+            respect_ignore: true,
+        }
+    }
+}
+
+/// A debounced set of changed paths, sorted and without duplicates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeBatch {
+    pub paths: Vec<PathBuf>,
+}
+
+/// Collects raw change events until no new event has arrived for the debounce window
+#[derive(Debug)]
+pub struct Debouncer {
+    window: Duration,
+    pending: BTreeSet<PathBuf>,
+    last_event: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: BTreeSet::new(),
+            last_event: None,
+        }
+    }
+
+    pub fn push<I>(&mut self, paths: I, now: Instant)
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        self.pending.extend(paths);
+        self.last_event = Some(now);
+    }
+
+    /// When the pending batch becomes due, if anything is pending
+    pub fn deadline(&self) -> Option<Instant> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        self.last_event.map(|last| last + self.window)
+    }
+
+    /// Take the pending batch once the window has passed without new events
+    pub fn take_if_quiet(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        match self.deadline() {
+            Some(deadline) if now >= deadline => {
+                self.last_event = None;
+                Some(std::mem::take(&mut self.pending).into_iter().collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Ignore rules applied to every change before it reaches subscribers
+pub struct IgnoreRules {
+    root: PathBuf,
+    gitignore: Option<Gitignore>,
+}
+
+impl IgnoreRules {
+    /// Rules for `root`, reading its `.gitignore` and `.ignore` when `respect_ignore_files` is set
+    pub fn load(root: &Path, respect_ignore_files: bool) -> Self {
+        let gitignore = if respect_ignore_files {
+            let mut builder = GitignoreBuilder::new(root);
+            for name in [".gitignore", ".ignore"] {
+                let file = root.join(name);
+                if file.exists() {
+                    if let Some(e) = builder.add(&file) {
+                        log::warn!("Ignoring unreadable {}: {}", file.display(), e);
+                    }
+                }
+            }
+            match builder.build() {
+                Ok(gitignore) => Some(gitignore),
+                Err(e) => {
+                    log::warn!("Failed to build ignore rules for {}: {}", root.display(), e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Self {
+            root: root.to_path_buf(),
+            gitignore,
+        }
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let relative = match path.strip_prefix(&self.root) {
+            Ok(relative) => relative,
+            Err(_) => return false,
+        };
+
+        let always = relative.components().any(|c| match c {
+            Component::Normal(name) => ALWAYS_IGNORED.iter().any(|ignored| name == *ignored),
+            _ => false,
+        });
+        if always {
+            return true;
+        }
+
+        match &self.gitignore {
+            Some(gitignore) => gitignore
+                .matched_path_or_any_parents(relative, path.is_dir())
+                .is_ignore(),
+            None => false,
+        }
+    }
+}
+
+type Filter = Box<dyn Fn(&Path) -> bool + Send>;
+
+struct Subscriber {
+    filter: Filter,
+    sender: Sender<ChangeBatch>,
+}
+
+/// Receiving end of a subscription; dropping it unsubscribes
+pub struct Subscription {
+    receiver: Receiver<ChangeBatch>,
+}
+
+impl Subscription {
+    /// Block until the next batch. Returns `None` once the service has stopped.
+    pub fn recv(&self) -> Option<ChangeBatch> {
+        self.receiver.recv().ok()
+    }
+
+    /// Wait up to `timeout` for the next batch
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ChangeBatch> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// The next batch if one is already waiting
+    pub fn try_recv(&self) -> Option<ChangeBatch> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Recursive file watcher over one root, shared by every subsystem that reacts to
+/// file changes. Raw events are filtered through the ignore rules, debounced, and
+/// delivered to each subscriber as a batch of the paths its filter accepts.
+pub struct WatchService {
+    root: PathBuf,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+    // Dropping the watcher closes the event channel and stops the dispatch thread
+    _watcher: RecommendedWatcher,
+}
+
+impl WatchService {
+    pub fn start(root: &Path, config: WatchConfig) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Cannot watch {}", root.display()))?;
+        let (tx, rx) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(tx).context("Failed to create file watcher")?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", root.display()))?;
+
+        let subscribers: Arc<Mutex<Vec<Subscriber>>> = Arc::new(Mutex::new(Vec::new()));
+        let rules = IgnoreRules::load(&root, config.respect_ignore);
+        let dispatch_subscribers = Arc::clone(&subscribers);
+        thread::Builder::new()
+            .name("wsb-watcher".to_string())
+            .spawn(move || dispatch_loop(rx, rules, config.debounce, dispatch_subscribers))
+            .context("Failed to start watcher thread")?;
+
+        Ok(Self {
+            root,
+            subscribers,
+            _watcher: watcher,
+        })
+    }
+
+    /// Canonical root being watched
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Subscribe to batches of changed paths accepted by `filter`
+    pub fn subscribe<F>(&self, filter: F) -> Subscription
+    where
+        F: Fn(&Path) -> bool + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push(Subscriber { filter: Box::new(filter), sender });
+        Subscription { receiver }
+    }
+
+    /// Subscribe to every change that passes the ignore rules
+    pub fn subscribe_all(&self) -> Subscription {
+        self.subscribe(|_| true)
+    }
+}
+
+fn dispatch_loop(
+    events: Receiver<notify::Result<notify::Event>>,
+    rules: IgnoreRules,
+    window: Duration,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+) {
+    let mut debouncer = Debouncer::new(window);
+
+    loop {
+        let received = match debouncer.deadline() {
+            Some(deadline) => events.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => events.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match received {
+            Ok(Ok(event)) => {
+                // Reads would otherwise wake subscribers that only render or report
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                let paths: Vec<PathBuf> = event.paths.into_iter().filter(|p| !rules.is_ignored(p)).collect();
+                if !paths.is_empty() {
+                    debouncer.push(paths, Instant::now());
+                }
+            }
+            Ok(Err(e)) => log::warn!("File watcher error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Some(paths) = debouncer.take_if_quiet(Instant::now()) {
+            deliver(&subscribers, &paths);
+        }
+    }
+}
+
+fn deliver(subscribers: &Mutex<Vec<Subscriber>>, paths: &[PathBuf]) {
+    let mut subscribers = subscribers.lock().unwrap();
+    subscribers.retain(|subscriber| {
+        let accepted: Vec<PathBuf> = paths.iter().filter(|p| (subscriber.filter)(p)).cloned().collect();
+        if accepted.is_empty() {
+            return true;
+        }
+        // A failed send means the subscription was dropped
+        subscriber.sender.send(ChangeBatch { paths: accepted }).is_ok()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_debouncer_waits_for_quiet_period() {
+        let start = Instant::now();
+        let window = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(window);
+        assert_eq!(debouncer.deadline(), None);
+
+        debouncer.push(vec![PathBuf::from("b.txt"), PathBuf::from("a.txt")], start);
+        debouncer.push(vec![PathBuf::from("a.txt")], start + Duration::from_millis(80));
+
+        // A new event restarts the window
+        assert_eq!(debouncer.take_if_quiet(start + Duration::from_millis(120)), None);
+        assert_eq!(
+            debouncer.take_if_quiet(start + Duration::from_millis(180)),
+            Some(vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")])
+        );
+        assert_eq!(debouncer.deadline(), None);
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::create_dir(root.join("target")).unwrap();
+
+        let rules = IgnoreRules::load(root, true);
+        assert!(rules.is_ignored(&root.join("target/debug/app")));
+        assert!(rules.is_ignored(&root.join("build.log")));
+        assert!(rules.is_ignored(&root.join(".git/index")));
+        assert!(rules.is_ignored(&root.join(".scrap/old.txt")));
+        assert!(!rules.is_ignored(&root.join("src/main.rs")));
+
+        let unfiltered = IgnoreRules::load(root, false);
+        assert!(!unfiltered.is_ignored(&root.join("build.log")));
+        assert!(unfiltered.is_ignored(&root.join(".git/index")));
+    }
+
+    #[test]
+    fn test_deliver_applies_filters_and_drops_closed_subscriptions() {
+        let subscribers = Mutex::new(Vec::new());
+        let (rs_tx, rs_rx) = mpsc::channel();
+        let (closed_tx, closed_rx) = mpsc::channel();
+        drop(closed_rx);
+        subscribers.lock().unwrap().push(Subscriber {
+            filter: Box::new(|p: &Path| p.extension().map_or(false, |e| e == "rs")),
+            sender: rs_tx,
+        });
+        subscribers.lock().unwrap().push(Subscriber { filter: Box::new(|_: &Path| true), sender: closed_tx });
+
+        deliver(&subscribers, &[PathBuf::from("main.rs"), PathBuf::from("notes.md")]);
+
+        assert_eq!(rs_rx.try_recv().unwrap().paths, vec![PathBuf::from("main.rs")]);
+        assert_eq!(subscribers.lock().unwrap().len(), 1);
+    }
+}
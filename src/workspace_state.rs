@@ -1,9 +1,23 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Layout version of `.wsb/state.json` written by this build.
+///
+/// Bump this and append a step to [`MIGRATIONS`] whenever a field is renamed,
+/// removed or changes meaning, so older state files are upgraded on load instead
+/// of failing to parse.
+pub const SCHEMA_VERSION: u32 = 2;
+
+type Migration = fn(&mut serde_json::Map<String, serde_json::Value>) -> Result<()>;
+
+/// Upgrade steps in order; the step at index `i` takes schema `i + 1` to `i + 2`
+const MIGRATIONS: &[(&str, Migration)] = &[
+    ("rename `version` to `schema_version`, default missing `tools`", migrate_v1_to_v2),
+];
+
 /// A single project registered for wstemplate scanning.
 ///
 /// Each project has exactly one entry defining:
@@ -27,7 +41,7 @@ pub struct WstemplateEntry {
 /// hard error.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorkspaceState {
-    pub version: u32,
+    pub schema_version: u32,
     pub project_root: PathBuf,
     pub project_name: Option<String>,
     pub tools: HashMap<String, serde_json::Value>,
@@ -36,17 +50,21 @@ pub struct WorkspaceState {
     /// Set to true after the shell completion hint has been shown once.
     #[serde(default)]
     pub completion_hint_shown: bool,
+    /// Encrypted values by name; read and written through [`crate::secrets`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub secrets: BTreeMap<String, String>,
 }
 
 impl Default for WorkspaceState {
     fn default() -> Self {
         Self {
-            version: 1,
+            schema_version: SCHEMA_VERSION,
             project_root: PathBuf::new(),
             project_name: None,
             tools: HashMap::new(),
             wstemplate_entries: Vec::new(),
             completion_hint_shown: false,
+            secrets: BTreeMap::new(),
         }
     }
 }
@@ -54,6 +72,8 @@ impl Default for WorkspaceState {
 impl WorkspaceState {
     /// Initialize workspace state in a project directory
     pub fn initialize(project_root: &Path) -> Result<Self> {
+        crate::read_only::ensure_writable("initialize .wsb directory")?;
+
         let workspace_dir = project_root.join(".wsb");
         fs::create_dir_all(&workspace_dir)
             .context("Failed to create .wsb directory")?;
@@ -77,13 +97,35 @@ impl WorkspaceState {
         let state_file = project_root.join(".wsb").join("state.json");
 
         if !state_file.exists() {
+            // Read-only inspection must not create .wsb/, so fall back to an in-memory state
+            if crate::read_only::is_enabled() {
+                let mut state = Self::default();
+                state.project_root = project_root.to_path_buf();
+                state.project_name = detect_project_name(project_root);
+                return Ok(state);
+            }
             return Self::initialize(project_root);
         }
 
-        let content = fs::read_to_string(&state_file)
-            .context("Failed to read workspace state file")?;
+        // Upgrade older layouts in place; read-only runs only upgrade the in-memory copy
+        Self::read_file(project_root, !crate::read_only::is_enabled())
+    }
 
-        let mut state: Self = serde_json::from_str(&content)
+    /// Load an existing state file without side effects: an older schema is upgraded
+    /// in memory only. For lookups made before a command runs, so that they never
+    /// rewrite the file behind the command's back.
+    pub fn peek(project_root: &Path) -> Result<Self> {
+        Self::read_file(project_root, false)
+    }
+
+    fn read_file(project_root: &Path, persist_migration: bool) -> Result<Self> {
+        let state_file = project_root.join(".wsb").join("state.json");
+        let (value, report) = read_migrated(&state_file)?;
+        if persist_migration && !report.steps.is_empty() {
+            write_migrated(&state_file, &value, report.from)?;
+        }
+
+        let mut state: Self = serde_json::from_value(value)
             .context("Failed to parse workspace state file")?;
 
         // Update project root in case it moved
@@ -108,11 +150,15 @@ impl WorkspaceState {
 
     /// Save workspace state to project directory
     pub fn save(&self, project_root: &Path) -> Result<()> {
+        crate::read_only::ensure_writable("save workspace state")?;
+
         let workspace_dir = project_root.join(".wsb");
         fs::create_dir_all(&workspace_dir)
             .context("Failed to create .wsb directory")?;
 
         let state_file = workspace_dir.join("state.json");
+        // Never overwrite an older layout without keeping a copy of it
+        backup_older_schema(&state_file)?;
         let content = serde_json::to_string_pretty(self)
             .context("Failed to serialize workspace state")?;
 
@@ -122,6 +168,23 @@ impl WorkspaceState {
         Ok(())
     }
 
+    /// Upgrade `.wsb/state.json` to [`SCHEMA_VERSION`], keeping the original as
+    /// `state.json.v{N}.bak`. With `dry_run` nothing is written and the report
+    /// lists the steps that would run.
+    pub fn migrate(project_root: &Path, dry_run: bool) -> Result<MigrationReport> {
+        let state_file = project_root.join(".wsb").join("state.json");
+        if !state_file.exists() {
+            anyhow::bail!("No workspace state at {}", state_file.display());
+        }
+
+        let (value, mut report) = read_migrated(&state_file)?;
+        if !dry_run && !report.steps.is_empty() {
+            crate::read_only::ensure_writable("migrate workspace state")?;
+            report.backup = Some(write_migrated(&state_file, &value, report.from)?);
+        }
+        Ok(report)
+    }
+
     /// Get workspace directory path
     pub fn workspace_dir(&self) -> PathBuf {
         self.project_root.join(".wsb")
@@ -174,6 +237,106 @@ impl WorkspaceState {
     }
 }
 
+/// What a state file migration did, or would do
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    /// Descriptions of the steps applied, oldest first; empty when already current
+    pub steps: Vec<String>,
+    /// Copy of the file as it was before migrating
+    pub backup: Option<PathBuf>,
+}
+
+/// Schema version of a raw state file. Files from before `schema_version` existed
+/// carried `"version": 1`.
+pub fn schema_version_of(value: &serde_json::Value) -> u32 {
+    value
+        .get("schema_version")
+        .or_else(|| value.get("version"))
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(1)
+}
+
+/// Run the migration steps needed to bring `value` up to [`SCHEMA_VERSION`]
+pub fn migrate_value(mut value: serde_json::Value) -> Result<(serde_json::Value, MigrationReport)> {
+    let from = schema_version_of(&value);
+    if from > SCHEMA_VERSION {
+        anyhow::bail!(
+            "Workspace state has schema version {} but this wsb only understands up to {}; upgrade wsb",
+            from,
+            SCHEMA_VERSION
+        );
+    }
+    if from == 0 {
+        anyhow::bail!("Workspace state has invalid schema version 0");
+    }
+
+    let map = value
+        .as_object_mut()
+        .context("Workspace state file is not a JSON object")?;
+    let mut steps = Vec::new();
+    for version in from..SCHEMA_VERSION {
+        let (description, step) = MIGRATIONS[(version - 1) as usize];
+        step(map).with_context(|| format!("Failed to migrate workspace state from schema {}", version))?;
+        map.insert("schema_version".to_string(), serde_json::json!(version + 1));
+        steps.push(format!("v{} -> v{}: {}", version, version + 1, description));
+    }
+
+    Ok((value, MigrationReport { from, to: SCHEMA_VERSION, steps, backup: None }))
+}
+
+fn migrate_v1_to_v2(state: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    state.remove("version");
+    state
+        .entry("tools")
+        .or_insert_with(|| serde_json::Value::Object(Default::default()));
+    Ok(())
+}
+
+fn read_migrated(state_file: &Path) -> Result<(serde_json::Value, MigrationReport)> {
+    let content = fs::read_to_string(state_file)
+        .context("Failed to read workspace state file")?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .context("Failed to parse workspace state file")?;
+    migrate_value(value)
+}
+
+/// Back up the current file as `state.json.v{from}.bak`, then write the migrated state
+fn write_migrated(state_file: &Path, value: &serde_json::Value, from: u32) -> Result<PathBuf> {
+    let backup = backup_path(state_file, from);
+    fs::copy(state_file, &backup)
+        .with_context(|| format!("Failed to back up workspace state to {}", backup.display()))?;
+
+    let content = serde_json::to_string_pretty(value)
+        .context("Failed to serialize workspace state")?;
+    fs::write(state_file, content)
+        .context("Failed to write workspace state file")?;
+    Ok(backup)
+}
+
+fn backup_path(state_file: &Path, from: u32) -> PathBuf {
+    state_file.with_file_name(format!("state.json.v{}.bak", from))
+}
+
+/// Copy a state file written with an older schema to its backup path, once
+fn backup_older_schema(state_file: &Path) -> Result<()> {
+    let Some(value) = fs::read_to_string(state_file)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return Ok(());
+    };
+    let from = schema_version_of(&value);
+    let backup = backup_path(state_file, from);
+    if from < SCHEMA_VERSION && !backup.exists() {
+        fs::copy(state_file, &backup)
+            .with_context(|| format!("Failed to back up workspace state to {}", backup.display()))?;
+    }
+    Ok(())
+}
+
 /// Detect project name from various project files
 fn detect_project_name(project_root: &Path) -> Option<String> {
     // Check Cargo.toml
@@ -232,7 +395,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let state = WorkspaceState::initialize(temp_dir.path()).unwrap();
 
-        assert_eq!(state.version, 1);
+        assert_eq!(state.schema_version, SCHEMA_VERSION);
         assert_eq!(state.project_root, temp_dir.path());
         assert!(temp_dir.path().join(".wsb").exists());
         assert!(temp_dir.path().join(".wsb").join("templates").exists());
@@ -404,6 +567,53 @@ mod tests {
             "completion_hint_shown must persist through save/load");
     }
 
+    #[test]
+    fn test_load_migrates_v1_state_with_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let ws_dir = temp_dir.path().join(".wsb");
+        fs::create_dir_all(&ws_dir).unwrap();
+        let v1 = r#"{"version": 1, "project_root": "/old", "project_name": "legacy"}"#;
+        fs::write(ws_dir.join("state.json"), v1).unwrap();
+
+        let state = WorkspaceState::load(temp_dir.path()).unwrap();
+        assert_eq!(state.schema_version, SCHEMA_VERSION);
+        assert_eq!(state.project_name.as_deref(), Some("legacy"));
+        assert!(state.tools.is_empty());
+
+        assert_eq!(fs::read_to_string(ws_dir.join("state.json.v1.bak")).unwrap(), v1);
+        let rewritten: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(ws_dir.join("state.json")).unwrap()).unwrap();
+        assert_eq!(rewritten["schema_version"], SCHEMA_VERSION);
+        assert!(rewritten.get("version").is_none());
+    }
+
+    #[test]
+    fn test_migrate_dry_run_writes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let ws_dir = temp_dir.path().join(".wsb");
+        fs::create_dir_all(&ws_dir).unwrap();
+        let v1 = r#"{"version": 1, "project_root": "/old", "project_name": null, "tools": {}}"#;
+        fs::write(ws_dir.join("state.json"), v1).unwrap();
+
+        let report = WorkspaceState::migrate(temp_dir.path(), true).unwrap();
+        assert_eq!((report.from, report.to), (1, SCHEMA_VERSION));
+        assert_eq!(report.steps.len(), 1);
+        assert!(report.backup.is_none());
+        assert_eq!(fs::read_to_string(ws_dir.join("state.json")).unwrap(), v1);
+
+        let report = WorkspaceState::migrate(temp_dir.path(), false).unwrap();
+        assert!(report.backup.unwrap().exists());
+        let report = WorkspaceState::migrate(temp_dir.path(), false).unwrap();
+        assert!(report.steps.is_empty(), "second migration must be a no-op");
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let value = serde_json::json!({ "schema_version": SCHEMA_VERSION + 1, "project_root": "/p", "tools": {} });
+        let err = migrate_value(value).unwrap_err();
+        assert!(format!("{}", err).contains("upgrade wsb"));
+    }
+
     #[test]
     fn test_detect_project_name_cargo() {
         let temp_dir = TempDir::new().unwrap();
@@ -3,7 +3,7 @@
 
 use anyhow::Result;
 use tempfile::tempdir;
-use workspace::entities::{
+use wsb::entities::{
     database::initialize_database,
     EntityManager,
     schema_models::{FeatureState, TaskStatus, DirectiveCategory, Priority}
@@ -361,8 +361,13 @@ async fn test_api_data_consistency() -> Result<()> {
     let project_sessions = entity_manager.list_sessions_by_project(&project.id).await?;
     assert_eq!(project_sessions.len(), 1);
 
-    // Test cascade deletion (project deletion should remove all related entities)
+    // Deleting a project only hides it; its features and tasks come back on restore
     entity_manager.delete_project(&project.id).await?;
+    assert!(entity_manager.get_project(&project.id).await?.is_none());
+    assert!(entity_manager.get_feature(&feature.id).await?.is_some());
+
+    // Test cascade deletion (purging the project should remove all related entities)
+    entity_manager.purge_project(&project.id).await?;
 
     // Verify all entities are deleted
     assert!(entity_manager.get_project(&project.id).await?.is_none());
@@ -3,9 +3,9 @@
 
 use anyhow::Result;
 use tempfile::tempdir;
-use workspace::entities::database::initialize_database;
-use workspace::entities::crud::{projects, features, tasks, sessions, directives};
-use workspace::entities::schema_models::{FeatureState, DirectiveCategory, Priority};
+use wsb::entities::database::initialize_database;
+use wsb::entities::crud::{projects, features, tasks, sessions, directives};
+use wsb::entities::schema_models::{FeatureState, DirectiveCategory, Priority};
 
 /// Test project CRUD operations
 #[tokio::test]
@@ -5,7 +5,7 @@ use anyhow::Result;
 use serde_json::json;
 use std::time::Duration;
 use tokio::time::timeout;
-use workspace::entities::{EntityManager, database};
+use wsb::entities::{EntityManager, database};
 
 // mod comprehensive_test_data_generator; // Removed - deprecated
 use comprehensive_test_data_generator::setup_workspace_temp_test_environment;
@@ -29,7 +29,7 @@ async fn test_dashboard_server_startup() -> Result<()> {
     
     // Start server in background
     let server_handle = tokio::spawn(async move {
-        workspace::mcp_server::start_mcp_server(port, false).await
+        wsb::mcp_server::start_mcp_server(port, false).await
     });
     
     // Give server time to start
@@ -131,7 +131,7 @@ async fn _test_note_management_disabled() -> Result<()> {
     
     // assert_eq!(arch_note.title, "Architecture Decision");
     // assert_eq!(arch_note.content, "Decided to use SQLite for persistence");
-    // assert_eq!(arch_note.note_type, workspace::entities::NoteType::Architecture);
+    // assert_eq!(arch_note.note_type, wsb::entities::NoteType::Architecture);
     
     // let decision_note = entity_manager.create_project_note(
     //     "Technical Decision".to_string(),
@@ -139,7 +139,7 @@ async fn _test_note_management_disabled() -> Result<()> {
     //     "decision".to_string()
     // ).await?;
     
-    // assert_eq!(decision_note.note_type, workspace::entities::NoteType::Decision);
+    // assert_eq!(decision_note.note_type, wsb::entities::NoteType::Decision);
     
     // Note: Note creation not implemented in new architecture yet
     // let entity_note = entity_manager.create_note(
@@ -149,7 +149,7 @@ async fn _test_note_management_disabled() -> Result<()> {
     //     Some("progress".to_string())
     // ).await?;
     
-    // assert_eq!(entity_note.note_type, workspace::entities::models::NoteType::Progress);
+    // assert_eq!(entity_note.note_type, wsb::entities::models::NoteType::Progress);
     
     Ok(())
 }
@@ -166,17 +166,17 @@ async fn test_dashboard_metrics() -> Result<()> {
         "Completed Feature".to_string(),
         "A feature that is completed".to_string()
     ).await?;
-    feature1.state = workspace::entities::FeatureState::ImplementedPassingTests.as_str().to_string();
+    feature1.state = wsb::entities::FeatureState::ImplementedPassingTests.as_str().to_string();
     feature1.test_status = "passed".to_string();
-    entity_manager.update_feature_state(&feature1.id, workspace::entities::FeatureState::ImplementedPassingTests).await?;
+    entity_manager.update_feature_state(&feature1.id, wsb::entities::FeatureState::ImplementedPassingTests).await?;
     
     let mut feature2 = entity_manager.create_feature(
         "In Progress Feature".to_string(),
         "A feature in progress".to_string()
     ).await?;
-    feature2.state = workspace::entities::FeatureState::ImplementedFailingTests.as_str().to_string();
+    feature2.state = wsb::entities::FeatureState::ImplementedFailingTests.as_str().to_string();
     feature2.test_status = "failed".to_string();
-    entity_manager.update_feature_state(&feature2.id, workspace::entities::FeatureState::ImplementedFailingTests).await?;
+    entity_manager.update_feature_state(&feature2.id, wsb::entities::FeatureState::ImplementedFailingTests).await?;
     
     let _feature3 = entity_manager.create_feature(
         "Pending Feature".to_string(),
@@ -189,14 +189,14 @@ async fn test_dashboard_metrics() -> Result<()> {
         "Completed Task".to_string(),
         "A completed task".to_string()
     ).await?;
-    task1.status = workspace::entities::models::TaskStatus::Completed;
+    task1.status = wsb::entities::models::TaskStatus::Completed;
     entity_manager.update_task(task1).await?;
     
     let mut task2 = entity_manager.create_task(
         "In Progress Task".to_string(),
         "A task in progress".to_string()
     ).await?;
-    task2.status = workspace::entities::models::TaskStatus::InProgress;
+    task2.status = wsb::entities::models::TaskStatus::InProgress;
     entity_manager.update_task(task2).await?;
     
     let _task3 = entity_manager.create_task(
@@ -213,16 +213,16 @@ async fn test_dashboard_metrics() -> Result<()> {
     assert_eq!(tasks.len(), 3);
     
     // Test feature metrics
-    let completed_features = features.iter().filter(|f| f.state == workspace::entities::FeatureState::ImplementedPassingTests.as_str()).count();
+    let completed_features = features.iter().filter(|f| f.state == wsb::entities::FeatureState::ImplementedPassingTests.as_str()).count();
     let tested_features = features.iter().filter(|f| f.test_status == "passed").count();
     
     assert_eq!(completed_features, 1);
     assert_eq!(tested_features, 1);
     
     // Test task metrics
-    let completed_tasks = tasks.iter().filter(|t| t.status == workspace::entities::models::TaskStatus::Completed).count();
-    let in_progress_tasks = tasks.iter().filter(|t| t.status == workspace::entities::models::TaskStatus::InProgress).count();
-    let pending_tasks = tasks.iter().filter(|t| t.status == workspace::entities::models::TaskStatus::Pending).count();
+    let completed_tasks = tasks.iter().filter(|t| t.status == wsb::entities::models::TaskStatus::Completed).count();
+    let in_progress_tasks = tasks.iter().filter(|t| t.status == wsb::entities::models::TaskStatus::InProgress).count();
+    let pending_tasks = tasks.iter().filter(|t| t.status == wsb::entities::models::TaskStatus::Pending).count();
     
     assert_eq!(completed_tasks, 1);
     assert_eq!(in_progress_tasks, 1);
@@ -239,7 +239,7 @@ async fn test_api_endpoints_integration() -> Result<()> {
     
     // Start server in background
     let server_handle = tokio::spawn(async move {
-        workspace::mcp_server::start_mcp_server(port, false).await
+        wsb::mcp_server::start_mcp_server(port, false).await
     });
     
     // Give server time to start
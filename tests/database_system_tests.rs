@@ -3,7 +3,7 @@
 
 use anyhow::Result;
 use tempfile::tempdir;
-use workspace::entities::database::{
+use wsb::entities::database::{
     initialize_database, health_check, ensure_current_schema, analyze_index_performance, optimize_database
 };
 
@@ -19,7 +19,7 @@ async fn test_database_initialization() -> Result<()> {
     // Check database health
     let health = health_check(&pool).await?;
     assert!(health.connected);
-    assert_eq!(health.schema_version, 1);
+    assert_eq!(health.schema_version, wsb::entities::migrations::latest_version());
     assert_eq!(health.foreign_key_violations, 0);
     
     Ok(())
@@ -38,7 +38,7 @@ async fn test_schema_version_tracking() -> Result<()> {
     ensure_current_schema(&pool).await?;
     
     let health = health_check(&pool).await?;
-    assert_eq!(health.schema_version, 1);
+    assert_eq!(health.schema_version, wsb::entities::migrations::latest_version());
     
     Ok(())
 }
@@ -728,4 +728,64 @@ async fn test_referential_integrity_health_check() -> Result<()> {
     assert_eq!(fk_enabled, 1, "Foreign key constraints should be enabled");
     
     Ok(())
-}
\ No newline at end of file
+}
+/// Databases created before depends_on and worked_in existed are migrated to accept them
+#[tokio::test]
+async fn test_legacy_dependencies_table_accepts_new_relationship_kinds() -> Result<()> {
+    use wsb::entities::{crud, EntityType};
+
+    let temp_dir = tempdir()?;
+    let db_path = temp_dir.path().join("test_legacy_dependencies.db");
+
+    // Recreate the dependencies table as it was before migration 3
+    let pool = initialize_database(&db_path).await?;
+    sqlx::query("DROP TABLE dependencies").execute(&pool).await?;
+    sqlx::query(r#"
+        CREATE TABLE dependencies (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            from_entity_id TEXT NOT NULL,
+            from_entity_type TEXT NOT NULL,
+            to_entity_id TEXT NOT NULL,
+            to_entity_type TEXT NOT NULL,
+            dependency_type TEXT NOT NULL,
+            description TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            resolved_at TEXT,
+            metadata TEXT,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            CONSTRAINT chk_dependencies_entity_types CHECK (
+                from_entity_type IN ('project', 'feature', 'task', 'session', 'directive') AND
+                to_entity_type IN ('project', 'feature', 'task', 'session', 'directive')
+            ),
+            CONSTRAINT chk_dependencies_type CHECK (dependency_type IN (
+                'blocks', 'requires', 'implements', 'tests', 'documents', 'references'
+            )),
+            CONSTRAINT chk_dependencies_not_self CHECK (from_entity_id != to_entity_id OR from_entity_type != to_entity_type)
+        )
+    "#)
+    .execute(&pool)
+    .await?;
+    sqlx::query("DELETE FROM schema_version WHERE version >= 3").execute(&pool).await?;
+
+    let project = crud::projects::create(&pool, "Legacy".to_string(), "Old schema".to_string()).await?;
+    let first = crud::features::create(&pool, project.id.clone(), "First".to_string(), "First feature".to_string(), None).await?;
+    let second = crud::features::create(&pool, project.id.clone(), "Second".to_string(), "Second feature".to_string(), None).await?;
+    let blocked = crud::dependencies::create(&pool, &project.id, &second.id, EntityType::Feature, &first.id, EntityType::Feature, "depends_on", None).await;
+    assert!(blocked.is_err(), "the legacy CHECK should reject depends_on");
+    pool.close().await;
+
+    // Reopening applies the pending migration
+    let pool = initialize_database(&db_path).await?;
+    let task = crud::tasks::create(&pool, project.id.clone(), first.id.clone(), "Wire it up".to_string(), "feature".to_string()).await?;
+    let session = crud::sessions::create(&pool, project.id.clone(), "Pairing".to_string(), None).await?;
+
+    let depends = crud::dependencies::create(&pool, &project.id, &second.id, EntityType::Feature, &first.id, EntityType::Feature, "depends_on", None).await?;
+    let worked = crud::dependencies::create(&pool, &project.id, &session.id, EntityType::Session, &task.id, EntityType::Task, "worked_in", None).await?;
+    assert_eq!(depends.dependency_type, "depends_on");
+    assert_eq!(worked.dependency_type, "worked_in");
+
+    let health = health_check(&pool).await?;
+    assert_eq!(health.schema_version, wsb::entities::migrations::latest_version());
+    Ok(())
+}
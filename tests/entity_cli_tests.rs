@@ -0,0 +1,783 @@
+// Entity CLI Tests - projects, features, tasks and the records around them, driven through the wsb binary
+// Each test gets a fresh workspace; assertions check both the command output and the rows left in the database
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use sqlx::Row;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tempfile::TempDir;
+use wsb::entities::EntityManager;
+
+const DIRECTIVES: &str = "# Directives\n\n### 🚨 🔴 DIR-20260101-000000 - No debug output (coding)\n**Category**: coding\n**Enforcement**: mandatory\n**Priority**: critical\n**Created**: 2026-01-01 00:00:00\n\n**Description**: Remove dbg! before committing\n";
+
+/// A temporary directory with an empty `.wsb/`; the first write command creates the database
+struct Workspace {
+    dir: TempDir,
+}
+
+impl Workspace {
+    fn new() -> Self {
+        let workspace = Self::bare();
+        fs::create_dir_all(workspace.root().join(".wsb")).unwrap();
+        workspace
+    }
+
+    /// A directory wsb has never touched
+    fn bare() -> Self {
+        Self { dir: TempDir::new().unwrap() }
+    }
+
+    /// A workspace whose database holds one project, created through the CLI
+    fn with_project(name: &str) -> Self {
+        let workspace = Self::new();
+        workspace.wsb(&["project", "create", name]).assert().success();
+        workspace
+    }
+
+    fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.root().join(".wsb/project.db")
+    }
+
+    fn wsb(&self, args: &[&str]) -> Command {
+        let mut command = Command::cargo_bin("wsb").unwrap();
+        command.args(args).current_dir(self.root());
+        command
+    }
+
+    /// Every file under `.wsb/` with its modification time
+    fn state_files(&self) -> Vec<(PathBuf, SystemTime)> {
+        let mut files: Vec<_> = walkdir::WalkDir::new(self.root().join(".wsb"))
+            .into_iter()
+            .map(|entry| entry.unwrap())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| (entry.path().to_path_buf(), entry.metadata().unwrap().modified().unwrap()))
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn write(&self, path: &str, contents: &str) {
+        let path = self.root().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn git(&self, args: &[&str]) -> std::process::Output {
+        std::process::Command::new("git").args(args).current_dir(self.root()).output().unwrap()
+    }
+
+    fn git_init(&self) {
+        self.git(&["init"]);
+        self.git(&["config", "user.name", "Test User"]);
+        self.git(&["config", "user.email", "test@example.com"]);
+        self.write("README.md", "# Test Repository");
+        self.git(&["add", "README.md"]);
+        self.git(&["commit", "-m", "Initial commit"]);
+    }
+
+    /// `wsb task add` only writes the backlog file, so tasks are seeded in the database directly
+    fn add_tasks(&self, feature_id: &str, titles: &[&str]) -> Vec<String> {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let pool = wsb::entities::database::initialize_database(&self.db_path()).await.unwrap();
+            let manager = EntityManager::new(pool.clone());
+            let project = manager.get_current_project().await.unwrap().expect("workspace has no project");
+            let mut ids = Vec::new();
+            for title in titles {
+                let task = manager.create_task_full(project.id.clone(), feature_id.to_string(), title.to_string(), "feature".to_string()).await.unwrap();
+                ids.push(task.id);
+            }
+            pool.close().await;
+            ids
+        })
+    }
+
+    /// First column of every row, as text
+    fn query(&self, sql: &str) -> Vec<String> {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", self.db_path().display())).await.unwrap();
+            let rows = sqlx::query(sql).fetch_all(&pool).await.unwrap();
+            pool.close().await;
+            rows.iter()
+                .map(|row| {
+                    row.try_get::<String, _>(0)
+                        .or_else(|_| row.try_get::<i64, _>(0).map(|n| n.to_string()))
+                        .or_else(|_| row.try_get::<f64, _>(0).map(|n| n.to_string()))
+                        .unwrap()
+                })
+                .collect()
+        })
+    }
+
+    fn execute(&self, sql: &str) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", self.db_path().display())).await.unwrap();
+            sqlx::query(sql).execute(&pool).await.unwrap();
+            pool.close().await;
+        });
+    }
+}
+
+#[test]
+fn test_secret_set_get_list() {
+    let workspace = Workspace::new();
+    // Fixed test key so the OS keychain is never touched
+    let key = "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=";
+    let wsb = |args: &[&str]| {
+        let mut command = workspace.wsb(args);
+        command.env("WSB_SECRET_KEY", key);
+        command
+    };
+
+    wsb(&["secret", "set", "mcp_token", "tok-123"]).assert().success();
+    wsb(&["secret", "set", "hook_token"]).write_stdin("from-stdin\n").assert().success();
+
+    let state = fs::read_to_string(workspace.root().join(".wsb/state.json")).unwrap();
+    assert!(state.contains("\"secrets\""));
+    assert!(!state.contains("tok-123"), "secret must not be stored in plaintext");
+
+    wsb(&["secret", "get", "mcp_token"]).assert().success().stdout("tok-123\n");
+    wsb(&["secret", "get", "hook_token"]).assert().success().stdout("from-stdin\n");
+    wsb(&["secret", "list"]).assert().success().stdout("hook_token\nmcp_token\n");
+
+    workspace
+        .wsb(&["secret", "get", "mcp_token"])
+        .env("WSB_SECRET_KEY", "CAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAg=")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("wrong key"));
+
+    wsb(&["secret", "remove", "mcp_token"]).assert().success();
+    wsb(&["secret", "get", "mcp_token"]).assert().failure().stderr(predicate::str::contains("No secret named"));
+}
+
+#[test]
+fn test_query_commands_in_fresh_directory_write_nothing() {
+    let workspace = Workspace::bare();
+
+    for args in [
+        &["status"][..],
+        &["feature", "list"],
+        &["feature", "show", "F00001"],
+        &["task", "list"],
+        &["task", "show", "T000001"],
+        &["milestone", "list"],
+        &["project", "list"],
+        &["export"],
+    ] {
+        workspace.wsb(args).assert();
+        assert!(!workspace.root().join(".wsb").exists(), "wsb {} created .wsb/", args.join(" "));
+    }
+    assert_eq!(fs::read_dir(workspace.root()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_query_commands_leave_database_untouched() {
+    let workspace = Workspace::with_project("Readers");
+    workspace.wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Form"]);
+    workspace.wsb(&["milestone", "add", "Beta", "--target", "2099-12-31"]).assert().success();
+    workspace.wsb(&["task", "schedule", &tasks[0], "--due", "2099-12-01"]).assert().success();
+
+    // SQLite readers keep read marks in the -shm index and open an empty -wal when there is none;
+    // neither holds data, so only the database and non-empty logs must stay as they were
+    let untouched = |files: Vec<(PathBuf, SystemTime)>| -> Vec<(PathBuf, SystemTime)> {
+        files.into_iter()
+            .filter(|(path, _)| {
+                let name = path.to_string_lossy();
+                !name.ends_with("-shm") && !(name.ends_with("-wal") && fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true))
+            })
+            .collect()
+    };
+    let before = untouched(workspace.state_files());
+    assert!(before.iter().any(|(path, _)| path.ends_with("project.db")));
+
+    workspace.wsb(&["status"]).assert().success();
+    workspace.wsb(&["task", "show", &tasks[0]]).assert().success().stdout(predicate::str::contains("Form"));
+    workspace.wsb(&["milestone", "show", "M001"]).assert().success().stdout(predicate::str::contains("Beta"));
+    workspace.wsb(&["project", "list"]).assert().success().stdout(predicate::str::contains("Readers"));
+    workspace.wsb(&["relationship", "graph"]).assert().success();
+    workspace.wsb(&["export"]).assert().success().stdout(predicate::str::contains("\"project_id\": \"P001\""));
+    assert_eq!(untouched(workspace.state_files()), before);
+
+    // Commands that write an output file are not queries
+    workspace.wsb(&["export", "--output", "snapshot.json"]).assert().success();
+    workspace.wsb(&["calendar", "export", "--out", "project.ics"]).assert().success();
+    workspace.wsb(&["relationship", "graph", "--output", "graph.dot"]).assert().success();
+    for file in ["snapshot.json", "project.ics", "graph.dot"] {
+        assert!(workspace.root().join(file).exists(), "{} was not written", file);
+    }
+}
+
+#[test]
+fn test_feature_and_task_delete_and_restore() {
+    let workspace = Workspace::with_project("Cleanup");
+    workspace.wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Form", "Submit"]);
+
+    workspace.wsb(&["task", "delete", &tasks[0]]).assert().success().stdout(predicate::str::contains(format!("wsb task restore {}", tasks[0])));
+    assert_eq!(workspace.query("SELECT id FROM tasks WHERE deleted_at IS NULL"), vec![tasks[1].clone()]);
+    workspace.wsb(&["task", "restore", &tasks[0]]).assert().success();
+    workspace.wsb(&["task", "restore", &tasks[0]]).assert().failure().stderr(predicate::str::contains("is not deleted"));
+
+    workspace.wsb(&["feature", "delete", "F00001"]).assert().success().stdout(predicate::str::contains("and its tasks deleted"));
+    assert_eq!(workspace.query("SELECT count(*) FROM tasks WHERE deleted_at IS NOT NULL"), vec!["2"]);
+    workspace.wsb(&["feature", "show", "F00001"]).assert().failure();
+    workspace.wsb(&["feature", "restore", "F00001"]).assert().success();
+    assert_eq!(workspace.query("SELECT count(*) FROM features WHERE deleted_at IS NULL"), vec!["1"]);
+
+    workspace.wsb(&["feature", "delete", "F99999"]).assert().failure().stderr(predicate::str::contains("Feature not found: F99999"));
+    workspace.wsb(&["task", "delete", "T999999"]).assert().failure().stderr(predicate::str::contains("Task not found: T999999"));
+}
+
+#[test]
+fn test_audit_retention_and_list() {
+    let workspace = Workspace::with_project("Audit");
+    workspace.wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    workspace.wsb(&["feature", "delete", "F00001"]).assert().success();
+
+    workspace.wsb(&["audit", "list", "--entity", "F00001"]).assert().success().stdout(predicate::str::contains("F00001 feature delete"));
+    workspace.wsb(&["audit", "retention"]).assert().success().stdout(predicate::str::contains("kept forever"));
+    workspace.wsb(&["audit", "prune"]).assert().failure().stderr(predicate::str::contains("No audit retention configured"));
+    workspace.wsb(&["audit", "retention", "--days", "180"]).assert().success();
+    let state = fs::read_to_string(workspace.root().join(".wsb/state.json")).unwrap();
+    assert!(state.contains("\"max_age_days\": 180"));
+
+    // Backdate the feature's entries past the retention window; a dry run reports them, --apply deletes them
+    workspace.execute("UPDATE entity_audit_trails SET timestamp = '2020-01-01T00:00:00+00:00' WHERE entity_id = 'F00001'");
+    let entries = workspace.query("SELECT count(*) FROM entity_audit_trails WHERE entity_id = 'F00001'").remove(0);
+    workspace.wsb(&["audit", "prune"]).assert().success().stdout(predicate::str::contains(format!("{} audit entries older than 180 days", entries)));
+    assert_eq!(workspace.query("SELECT count(*) FROM entity_audit_trails WHERE entity_id = 'F00001'"), vec![entries.clone()]);
+    workspace.wsb(&["audit", "prune", "--apply"]).assert().success().stdout(predicate::str::contains(format!("Deleted {} audit entries", entries)));
+    workspace.wsb(&["audit", "list", "--entity", "F00001"]).assert().success().stdout(predicate::str::contains("No audit entries found"));
+}
+
+#[test]
+fn test_milestone_link_and_close() {
+    let workspace = Workspace::new();
+    workspace.wsb(&["milestone", "add", "Beta", "--target", "2099-12-31"]).assert().failure().stderr(predicate::str::contains("No active project found"));
+    workspace.wsb(&["project", "create", "Release"]).assert().success();
+    workspace.wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Form", "Submit"]);
+    let members = tasks.join(",");
+
+    workspace.wsb(&["milestone", "add", "Beta", "--target", "2099-12-31"]).assert().success().stdout(predicate::str::contains("Created milestone M001: Beta"));
+    workspace.wsb(&["milestone", "link", "M001"]).assert().failure().stderr(predicate::str::contains("Nothing to link"));
+    workspace.wsb(&["milestone", "link", "M001", "--features", "F00001", "--tasks", &members]).assert().success()
+        .stdout(predicate::str::contains("1 feature(s) and 2 task(s), 0.0% complete"));
+    assert_eq!(workspace.query("SELECT task_ids FROM milestones WHERE id = 'M001'"), vec![format!("[\"{}\",\"{}\"]", tasks[0], tasks[1])]);
+
+    workspace.wsb(&["task", "update", &tasks[0], "--status", "completed"]).assert().success();
+    workspace.wsb(&["milestone", "show", "M001"]).assert().success()
+        .stdout(predicate::str::contains("Completion: 33.3%").and(predicate::str::contains("F00001 Login")));
+
+    workspace.wsb(&["milestone", "close", "M001", "--status", "done"]).assert().failure().stderr(predicate::str::contains("Invalid milestone status: done"));
+    workspace.wsb(&["milestone", "close", "M001", "--status", "missed"]).assert().success().stdout(predicate::str::contains("Closed M001 as missed at 33.3% complete"));
+    assert_eq!(workspace.query("SELECT status || ' ' || completion_percentage FROM milestones WHERE id = 'M001'"), vec!["missed 33.3"]);
+    workspace.wsb(&["milestone", "link", "M001", "--tasks", &tasks[1]]).assert().failure().stderr(predicate::str::contains("already missed"));
+    workspace.wsb(&["milestone", "show", "M999"]).assert().failure().stderr(predicate::str::contains("Milestone not found: M999"));
+}
+
+#[test]
+fn test_task_depend_rejects_cycles() {
+    let workspace = Workspace::with_project("Pipeline");
+    workspace.wsb(&["feature", "add", "Build", "Compile"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Fetch", "Compile", "Package"]);
+
+    workspace.wsb(&["task", "depend", &tasks[1]]).assert().failure().stderr(predicate::str::contains("--on"));
+    workspace.wsb(&["task", "depend", &tasks[1], "--on", &tasks[0]]).assert().success()
+        .stdout(predicate::str::contains(format!("{} now depends on {}", tasks[1], tasks[0])));
+    workspace.wsb(&["task", "depend", &tasks[2], "--on", &tasks[1]]).assert().success();
+    workspace.wsb(&["task", "depend", &tasks[0], "--on", &tasks[2]]).assert().failure()
+        .stderr(predicate::str::contains(format!("Dependency cycle: {} → {} → {} → {}", tasks[0], tasks[2], tasks[1], tasks[0])));
+    assert_eq!(workspace.query("SELECT count(*) FROM dependencies WHERE dependency_type = 'depends_on'"), vec!["2"]);
+
+    workspace.wsb(&["task", "ready"]).assert().success()
+        .stdout(predicate::str::contains(&tasks[0]).and(predicate::str::contains(&tasks[1]).not()));
+    workspace.wsb(&["task", "list", "--blocked-by", &tasks[0]]).assert().success()
+        .stdout(predicate::str::contains(format!("{} Package (waiting on {})", tasks[2], tasks[1])));
+    workspace.wsb(&["task", "depend", "T999998", "--on", &tasks[0]]).assert().failure().stderr(predicate::str::contains("Task not found: T999998"));
+}
+
+#[test]
+fn test_task_schedule_recurring() {
+    let workspace = Workspace::with_project("Chores");
+    workspace.wsb(&["feature", "add", "Upkeep", "Maintenance"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Rotate keys"]);
+
+    workspace.wsb(&["task", "list", "--recurring"]).assert().success().stdout(predicate::str::contains("No recurring tasks"));
+    workspace.wsb(&["task", "schedule", &tasks[0], "--due", "2026-11-02", "--repeat", "every 2 weeks"]).assert().success()
+        .stdout(predicate::str::contains("due 2026-11-02, repeating every 2 weeks"));
+    assert_eq!(workspace.query("SELECT due_date || '|' || repeat FROM tasks"), vec!["2026-11-02|every 2 weeks"]);
+    workspace.wsb(&["task", "list", "--recurring"]).assert().success()
+        .stdout(predicate::str::contains("Rotate keys [template, every 2 weeks] due 2026-11-02"));
+    workspace.wsb(&["task", "list", "--recurring", "--status", "pending"]).assert().failure();
+}
+
+#[test]
+fn test_task_timer_totals() {
+    let workspace = Workspace::with_project("Timers");
+    workspace.wsb(&["feature", "add", "Search", "Find things"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Index", "Query"]);
+
+    workspace.wsb(&["task", "stop"]).assert().failure().stderr(predicate::str::contains("No timer running"));
+    workspace.wsb(&["task", "start", &tasks[0]]).assert().success().stdout(predicate::str::contains(format!("Timer started on {}", tasks[0])));
+    assert_eq!(workspace.query(&format!("SELECT status FROM tasks WHERE id = '{}'", tasks[0])), vec!["in_progress"]);
+
+    // Backdate the running timer so the totals are deterministic; starting another task stops it
+    let backdate = |minutes: i64| {
+        let started = chrono::Utc::now() - chrono::Duration::minutes(minutes);
+        workspace.execute(&format!("UPDATE time_entries SET started_at = '{}' WHERE ended_at IS NULL", started.to_rfc3339()));
+    };
+    backdate(125);
+    workspace.wsb(&["task", "start", &tasks[1]]).assert().success().stdout(predicate::str::contains(format!("Stopped {} after 2h 05m", tasks[0])));
+    backdate(45);
+    workspace.wsb(&["task", "stop"]).assert().success().stdout(predicate::str::contains(format!("Stopped {} after 45m", tasks[1])));
+
+    assert_eq!(workspace.query("SELECT count(*) FROM time_entries WHERE ended_at IS NULL"), vec!["0"]);
+    assert_eq!(workspace.query("SELECT sum(duration_seconds) / 60 FROM time_entries"), vec!["170"]);
+    workspace.wsb(&["task", "show", &tasks[0]]).assert().success().stdout(predicate::str::contains("Time Tracked: 2h 05m across 1 entries"));
+    workspace.wsb(&["task", "start", "T999999"]).assert().failure().stderr(predicate::str::contains("Task not found: T999999"));
+}
+
+#[test]
+fn test_export_import_remaps_ids() {
+    let source = Workspace::with_project("Source");
+    source.wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    let tasks = source.add_tasks("F00001", &["Form", "Submit"]);
+    source.wsb(&["task", "depend", &tasks[1], "--on", &tasks[0]]).assert().success();
+    source.wsb(&["milestone", "add", "Beta", "--target", "2099-12-31"]).assert().success();
+    source.wsb(&["milestone", "link", "M001", "--features", "F00001"]).assert().success();
+    source.wsb(&["export", "--entity", "widgets"]).assert().failure().stderr(predicate::str::contains("widgets"));
+    source.wsb(&["export", "--output", "snapshot.json"]).assert().success().stderr(predicate::str::contains("to snapshot.json"));
+    let snapshot = source.root().join("snapshot.json");
+
+    // The target already uses P001 and F00001, so the imported rows must move
+    let target = Workspace::with_project("Target");
+    target.wsb(&["feature", "add", "Search", "Find things"]).assert().success();
+    let snapshot = snapshot.to_str().unwrap();
+    target.wsb(&["import", snapshot, "--merge", "--replace"]).assert().failure();
+    target.wsb(&["import", snapshot]).assert().success()
+        .stdout(predicate::str::contains("F00001 → F00002").and(predicate::str::contains("P001 → P002")));
+
+    assert_eq!(target.query("SELECT id || ' ' || name FROM projects ORDER BY id"), vec!["P001 Target", "P002 Source"]);
+    assert_eq!(target.query("SELECT id || ' ' || project_id || ' ' || name FROM features ORDER BY id"), vec!["F00001 P001 Search", "F00002 P002 Login"]);
+    assert_eq!(target.query("SELECT DISTINCT project_id || ' ' || feature_ids FROM tasks"), vec!["P002 F00002"]);
+    assert_eq!(target.query("SELECT feature_ids FROM milestones"), vec!["[\"F00002\"]"]);
+    assert_eq!(target.query("SELECT project_id FROM dependencies"), vec!["P002"]);
+    target.wsb(&["import", "missing.json"]).assert().failure().stderr(predicate::str::contains("Failed to read missing.json"));
+}
+
+#[test]
+fn test_note_attachment_checksums() {
+    let workspace = Workspace::new();
+    workspace.write("design.png", "png-bytes");
+    workspace.wsb(&["note", "add-project", "Layout", "Mockup", "--attach", "design.png"]).assert().failure().stderr(predicate::str::contains("No active project found"));
+    workspace.wsb(&["project", "create", "Design"]).assert().success();
+
+    // sha256 of "png-bytes"
+    let checksum = "ea80334363eed145dfeee51ebae7dc3f1cd7d0c7879f8bfd2070c061d3c33f56";
+    workspace.wsb(&["note", "add-project", "Layout", "Mockup", "--attach", "design.png"]).assert().success()
+        .stdout(predicate::str::contains("design.png (9 bytes, sha256 ea80334363ee)"));
+    assert_eq!(workspace.query("SELECT sha256 FROM note_attachments WHERE path = 'design.png'"), vec![checksum]);
+    let note = workspace.query("SELECT note_id FROM note_attachments").remove(0);
+
+    workspace.wsb(&["note", "show", &note]).assert().success().stdout(predicate::str::contains("design.png (9 bytes) ✓ intact"));
+    workspace.write("design.png", "png-bytes, edited");
+    workspace.wsb(&["note", "show", &note]).assert().success().stdout(predicate::str::contains("⚠ modified"));
+    fs::remove_file(workspace.root().join("design.png")).unwrap();
+    workspace.wsb(&["note", "show", &note]).assert().success().stdout(predicate::str::contains("✗ missing"));
+    workspace.wsb(&["note", "show", "note-missing"]).assert().failure().stderr(predicate::str::contains("Note not found: note-missing"));
+}
+
+#[test]
+fn test_saved_views() {
+    let workspace = Workspace::new();
+    workspace.wsb(&["view", "save", "sprint", "--status", "in_progress"]).assert().failure().stderr(predicate::str::contains("No active project found"));
+    workspace.wsb(&["project", "create", "Views"]).assert().success();
+    workspace.wsb(&["task", "list", "--view", "sprint"]).assert().failure().stderr(predicate::str::contains("View not found: sprint"));
+
+    workspace.wsb(&["view", "save", "sprint", "--status", "in_progress"]).assert().success().stdout(predicate::str::contains("Saved view sprint (task list: status=in_progress)"));
+    assert_eq!(workspace.query("SELECT name FROM views"), vec!["sprint"]);
+    workspace.wsb(&["view", "list"]).assert().success().stdout(predicate::str::contains("sprint [task] status=in_progress"));
+    workspace.wsb(&["task", "list", "--view", "sprint"]).assert().success().stdout(predicate::str::contains("(view sprint: status=in_progress)"));
+}
+
+#[test]
+fn test_tag_add_rename_and_merge() {
+    let workspace = Workspace::with_project("Tags");
+    workspace.wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    let tasks = workspace.add_tasks("F00001", &["Form", "Submit"]);
+
+    workspace.wsb(&["tag", "add", &tasks[0], "ui"]).assert().success().stdout(predicate::str::contains(format!("task {} tagged: ui", tasks[0])));
+    workspace.wsb(&["tag", "add", &tasks[1], "ui,fe"]).assert().success();
+    workspace.wsb(&["tag", "list", "--counts"]).assert().success().stdout(predicate::str::contains("ui 2 (2 tasks)"));
+
+    workspace.wsb(&["tag", "rename", "ui", "frontend"]).assert().success();
+    workspace.wsb(&["tag", "merge", "fe"]).assert().failure().stderr(predicate::str::contains("--into"));
+    workspace.wsb(&["tag", "merge", "fe", "--into", "frontend"]).assert().success();
+    assert_eq!(workspace.query("SELECT name FROM tags"), vec!["frontend"]);
+    workspace.wsb(&["tag", "list", "--counts"]).assert().success().stdout(predicate::str::contains("frontend 2 (2 tasks)"));
+}
+
+#[test]
+fn test_ids_set_and_list() {
+    let workspace = Workspace::new();
+    workspace.wsb(&["ids", "set", "feature", "--prefix", "FEAT", "--width", "4"]).assert().failure().stderr(predicate::str::contains("No active project found"));
+    workspace.wsb(&["project", "create", "Ids"]).assert().success();
+
+    workspace.wsb(&["ids", "set", "feature", "--prefix", "FEAT"]).assert().failure().stderr(predicate::str::contains("--width"));
+    workspace.wsb(&["ids", "set", "feature", "--prefix", "FEAT", "--width", "4"]).assert().success().stdout(predicate::str::contains("FEAT0001"));
+    workspace.wsb(&["feature", "add", "Login", "Sign in"]).assert().success().stdout(predicate::str::contains("Feature FEAT0001 added"));
+    assert_eq!(workspace.query("SELECT id FROM features"), vec!["FEAT0001"]);
+    workspace.wsb(&["ids", "list"]).assert().success().stdout(predicate::str::contains("feature    next FEAT0002"));
+}
+
+#[test]
+fn test_database_backup_check_and_restore_files() {
+    let workspace = Workspace::with_project("Backup");
+
+    workspace.wsb(&["db", "check"]).assert().success().stdout(predicate::str::contains("Integrity check passed"));
+    workspace.wsb(&["db", "backup", "--output", "copy.db"]).assert().success().stdout(predicate::str::contains("backed up"));
+    workspace.wsb(&["db", "backup", "--output", "copy.db"]).assert().failure().stderr(predicate::str::contains("already exists"));
+
+    workspace.wsb(&["project", "create", "After backup"]).assert().success();
+    workspace.wsb(&["db", "restore", "copy.db", "--force"]).assert().success().stdout(predicate::str::contains("Previous database kept"));
+    assert_eq!(workspace.query("SELECT name FROM projects"), vec!["Backup"]);
+
+    workspace.write("junk.db", "not a database");
+    workspace.wsb(&["db", "restore", "junk.db", "--force"]).assert().failure().stderr(predicate::str::contains("Not a SQLite database"));
+}
+
+#[test]
+fn test_database_migrate_status() {
+    let workspace = Workspace::new();
+
+    workspace.wsb(&["db", "migrate", "--status"]).assert().failure().stderr(predicate::str::contains("read-only mode does not create one"));
+    workspace.wsb(&["db", "migrate"]).assert().success().stdout(predicate::str::contains("Schema is up to date"));
+    workspace.wsb(&["db", "migrate", "--status"]).assert().success().stdout(predicate::str::contains("baseline").and(predicate::str::contains("pending").not()));
+}
+
+#[test]
+fn test_project_create_switch_and_archive() {
+    let workspace = Workspace::new();
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["project", "create", "Backend"]).assert().success().stdout(predicate::str::contains("Created project P001"));
+    wsb(&["project", "create", "Frontend", "--description", "Web client"]).assert().success().stdout(predicate::str::contains("P002"));
+    wsb(&["project", "switch", "P001"]).assert().success().stdout(predicate::str::contains("Switched to P001"));
+    wsb(&["ids", "list"]).assert().success().stdout(predicate::str::contains("(P001)"));
+    wsb(&["project", "switch", "P404"]).assert().failure().stderr(predicate::str::contains("Project not found"));
+
+    wsb(&["project", "archive", "P001"]).assert().success().stdout(predicate::str::contains("Current project: P002"));
+    wsb(&["project", "switch", "P001"]).assert().failure().stderr(predicate::str::contains("archived"));
+    wsb(&["project", "list"]).assert().success().stdout(predicate::str::contains("P002").and(predicate::str::contains("P001").not()));
+    wsb(&["project", "list", "--all"]).assert().success().stdout(predicate::str::contains("(archived)"));
+    wsb(&["project", "unarchive", "P001"]).assert().success();
+    assert_eq!(workspace.query("SELECT archived FROM projects WHERE id = 'P001'"), vec!["0"]);
+}
+
+#[test]
+fn test_version_scheme_follows_current_project() {
+    let workspace = Workspace::new();
+    workspace.git_init();
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["project", "create", "Backend"]).assert().success();
+    wsb(&["project", "create", "Frontend"]).assert().success();
+    wsb(&["project", "switch", "P001"]).assert().success();
+    wsb(&["version", "scheme", "semver"]).assert().success();
+    wsb(&["version", "minor", "4"]).assert().success();
+    wsb(&["project", "switch", "P002"]).assert().success();
+    wsb(&["version", "scheme", "calver"]).assert().success();
+    wsb(&["version", "major", "2"]).assert().success();
+    wsb(&["version", "minor", "7"]).assert().success();
+    assert_eq!(
+        workspace.query("SELECT version_scheme || ' ' || major_version || '.' || minor_version FROM projects ORDER BY id"),
+        vec!["semver 0.4", "calver 2.7"]
+    );
+
+    wsb(&["git", "show"]).assert().success().stdout(predicate::str::contains("Version Scheme: calver"));
+    wsb(&["project", "switch", "P001"]).assert().success();
+    wsb(&["git", "show"]).assert().success()
+        .stdout(predicate::str::contains("Version Scheme: semver").and(predicate::str::contains("Current Version: 0.4.")));
+}
+
+#[test]
+fn test_session_complete_and_show() {
+    let workspace = Workspace::new();
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["session", "create", "Parser work"]).assert().failure().stderr(predicate::str::contains("No active project found"));
+    wsb(&["project", "create", "Parser"]).assert().success();
+    wsb(&["session", "create", "Parser work"]).assert().success().stdout(predicate::str::contains("Started session S000001"));
+    wsb(&["session", "show", "S000001"]).assert().success().stdout(predicate::str::contains("captured when the session completes"));
+
+    // The temporary directory is not a git repository, so nothing is recorded
+    wsb(&["session", "complete", "S000001", "--summary", "Done"]).assert().success().stdout(predicate::str::contains("no commits recorded"));
+    wsb(&["session", "complete", "S000001"]).assert().failure().stderr(predicate::str::contains("already completed"));
+    wsb(&["session", "show", "S000001", "--format", "json"]).assert().success().stdout(predicate::str::contains("\"status\": \"completed\""));
+    wsb(&["session", "list"]).assert().success().stdout(predicate::str::contains("Parser work"));
+}
+
+#[test]
+fn test_unknown_ids_suggest_close_matches() {
+    let workspace = Workspace::with_project("Typos");
+    let wsb = |args: &[&str]| workspace.wsb(args);
+    wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    let task = workspace.add_tasks("F00001", &["Form"]).remove(0);
+    let typo = format!("{}9", task);
+    wsb(&["milestone", "add", "Beta"]).assert().success();
+    wsb(&["session", "create", "Typing"]).assert().success();
+
+    let hint = |kind: &str, typo: &str, id: &str| predicate::str::contains(format!("{} not found: {} (did you mean `{}`?)", kind, typo, id));
+    wsb(&["task", "update", &typo, "--status", "completed"]).assert().failure().stderr(hint("Task", &typo, &task));
+    wsb(&["task", "delete", &typo]).assert().failure().stderr(hint("Task", &typo, &task));
+    wsb(&["feature", "delete", "F0001"]).assert().failure().stderr(hint("Feature", "F0001", "F00001"));
+    wsb(&["feature", "update", "F0001", "--state", "implemented", "--force"]).assert().failure().stderr(hint("Feature", "F0001", "F00001"));
+    wsb(&["milestone", "show", "M01"]).assert().failure().stderr(hint("Milestone", "M01", "M001"));
+    wsb(&["milestone", "link", "M001", "--features", "F0001"]).assert().failure().stderr(hint("Feature", "F0001", "F00001"));
+    wsb(&["session", "show", "S00001"]).assert().failure().stderr(hint("Session", "S00001", "S000001"));
+    wsb(&["relationship", "link", "F0001", "-f", "feature", &task, "-t", "task", "-r", "implements"]).assert().failure()
+        .stderr(hint("Feature", "F0001", "F00001"));
+    assert_eq!(workspace.query("SELECT COUNT(*) FROM tasks WHERE deleted_at IS NULL"), vec!["1"]);
+}
+
+#[test]
+fn test_sprint_create_plan_and_close() {
+    let workspace = Workspace::new();
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["sprint", "create", "Sprint 1"]).assert().failure().stderr(predicate::str::contains("No active project found"));
+    wsb(&["project", "create", "Iterations"]).assert().success();
+    wsb(&["sprint", "create", "Sprint 1", "--start", "2026-03-02", "--end", "2026-03-01"]).assert().failure().stderr(predicate::str::contains("before it starts"));
+    wsb(&["sprint", "create", "Sprint 1", "--start", "2026-03-02", "--capacity", "8"]).assert().success().stdout(predicate::str::contains("SP001").and(predicate::str::contains("2026-03-15")));
+    wsb(&["sprint", "plan", "SP001", "--tasks", "T999999"]).assert().failure().stderr(predicate::str::contains("Task not found"));
+    wsb(&["sprint", "burndown", "SP001", "--format", "json"]).assert().success().stdout(predicate::str::contains("\"total_points\": 0"));
+    wsb(&["sprint", "close", "SP001"]).assert().success().stdout(predicate::str::contains("0 of 0 point(s)"));
+    wsb(&["sprint", "velocity"]).assert().success().stdout(predicate::str::contains("Velocity"));
+    wsb(&["sprint", "plan", "SP001", "--tasks", "T000001", "--remove"]).assert().failure().stderr(predicate::str::contains("closed"));
+}
+
+#[test]
+fn test_directive_rules_validate_and_check() {
+    let workspace = Workspace::new();
+    workspace.write("internal/directives.md", DIRECTIVES);
+    workspace.write("src/main.rs", "fn main() {\n    dbg!(1);\n}\n");
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["directive", "add-rule", "DIR-20260101-000001", "--require", "README.md"]).assert().failure().stderr(predicate::str::contains("Directive not found"));
+    wsb(&["directive", "add-rule", "DIR-20260101-000000", "--forbid", "dbg!\\(", "--glob", "*.rs", "--hint", "Use log::debug! instead"])
+        .assert().success().stdout(predicate::str::contains("Rule 1 added"));
+    wsb(&["directive", "rules"]).assert().success().stdout(predicate::str::contains("forbid /dbg!\\(/ in *.rs"));
+
+    wsb(&["directive", "validate"]).assert().failure()
+        .stdout(predicate::str::contains("src/main.rs:2").and(predicate::str::contains("Use log::debug! instead")))
+        .stderr(predicate::str::contains("DIR-20260101-000000"));
+    wsb(&["directive", "show", "DIR-20260101-000000"]).assert().success().stdout(predicate::str::contains("Violations: 1"));
+    wsb(&["directive", "check", "internal", "--format", "json"]).assert().success().stdout(predicate::str::contains("\"issues_found\": 0"));
+
+    workspace.write("src/main.rs", "fn main() {}\n");
+    wsb(&["directive", "validate"]).assert().success().stdout(predicate::str::contains("All directives satisfied"));
+    wsb(&["directive", "list"]).assert().success().stdout(predicate::str::contains("no violations"));
+    wsb(&["directive", "remove-rule", "1"]).assert().success();
+    wsb(&["directive", "remove-rule", "1"]).assert().failure().stderr(predicate::str::contains("rule not found"));
+}
+
+#[test]
+fn test_directive_hook_blocks_commit() {
+    let workspace = Workspace::new();
+    workspace.git_init();
+    workspace.write("internal/directives.md", DIRECTIVES);
+    let wsb = |args: &[&str]| workspace.wsb(args);
+    let commit = |message: &str| {
+        workspace.git(&["add", "src/main.rs"]);
+        let output = workspace.git(&["commit", "-m", message]);
+        (output.status.success(), format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)))
+    };
+
+    wsb(&["directive", "add-rule", "DIR-20260101-000000", "--forbid", "dbg!\\(", "--glob", "*.rs", "--hint", "Use log::debug! instead"]).assert().success();
+    wsb(&["directive", "install-hook"]).assert().success().stdout(predicate::str::contains("Directive pre-commit hook installed"));
+    wsb(&["directive", "install-hook"]).assert().success().stdout(predicate::str::contains("already installed"));
+    let hook = fs::read_to_string(workspace.root().join(".git/hooks/pre-commit")).unwrap();
+    assert!(hook.contains("directive validate --mandatory || exit 1"));
+
+    workspace.write("src/main.rs", "fn main() {\n    dbg!(1);\n}\n");
+    let (committed, output) = commit("Add main");
+    assert!(!committed);
+    assert!(output.contains("DIR-20260101-000000"));
+    assert!(output.contains("Use log::debug! instead"));
+
+    workspace.write("src/main.rs", "fn main() {}\n");
+    let (committed, output) = commit("Add main");
+    assert!(committed, "{}", output);
+
+    wsb(&["directive", "uninstall-hook"]).assert().success().stdout(predicate::str::contains("Removed pre-commit hook"));
+    assert!(!workspace.root().join(".git/hooks/pre-commit").exists());
+}
+
+#[test]
+fn test_directive_search() {
+    let workspace = Workspace::new();
+    workspace.write("internal/directives.md", DIRECTIVES);
+
+    workspace.wsb(&["directive", "search", "DBG!"]).assert().success()
+        .stdout(predicate::str::contains("DIR-20260101-000000").and(predicate::str::contains("No debug output")));
+    workspace.wsb(&["directive", "search", "debug", "--category", "security"]).assert().success()
+        .stdout(predicate::str::contains("No directives match 'debug'"));
+}
+
+#[test]
+fn test_relationship_graph_export() {
+    let workspace = Workspace::with_project("Graph");
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["feature", "add", "Login", "Sign in"]).assert().success();
+    wsb(&["feature", "add", "Audit", "Audit trail"]).assert().success();
+    wsb(&["relationship", "link", "F00002", "-f", "feature", "F00001", "-t", "feature", "-r", "depends_on"]).assert().success();
+
+    wsb(&["relationship", "graph"]).assert().success()
+        .stdout(predicate::str::contains("digraph entities {").and(predicate::str::contains("\"F00002\" -> \"F00001\" [label=\"depends_on\"]")));
+    wsb(&["relationship", "graph", "--format", "mermaid", "--entity", "F00001", "--depth", "1"]).assert().success()
+        .stdout(predicate::str::contains("F00002 -->|depends_on| F00001"));
+    wsb(&["relationship", "graph", "--format", "json", "--output", "graph.json"]).assert().success().stdout(predicate::str::contains("2 entities and 1 links"));
+    assert!(fs::read_to_string(workspace.root().join("graph.json")).unwrap().contains("\"kind\": \"depends_on\""));
+    wsb(&["relationship", "graph", "--entity", "F99999"]).assert().failure().stderr(predicate::str::contains("Entity not found"));
+    wsb(&["relationship", "graph", "--format", "svg"]).assert().failure().stderr(predicate::str::contains("Unknown graph format"));
+}
+
+#[test]
+fn test_relationship_impact() {
+    let workspace = Workspace::with_project("Impact");
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    for (name, description) in [("Storage", "Data layer"), ("Sync", "Replication"), ("Backup", "Snapshots")] {
+        wsb(&["feature", "add", name, description]).assert().success();
+    }
+    wsb(&["relationship", "link", "F00002", "-f", "feature", "F00001", "-t", "feature", "-r", "depends_on"]).assert().success();
+    wsb(&["relationship", "link", "F00002", "-f", "feature", "F00003", "-t", "feature", "-r", "blocks"]).assert().success();
+
+    wsb(&["relationship", "impact", "F00001"]).assert().success()
+        .stdout(predicate::str::contains("Features blocked (2)").and(predicate::str::contains("F00001 → F00002 → F00003")));
+    wsb(&["relationship", "impact", "F00001", "--depth", "1", "--format", "json"]).assert().success()
+        .stdout(predicate::str::contains("\"id\": \"F00002\"").and(predicate::str::contains("F00003").not()));
+    wsb(&["relationship", "impact", "F00003"]).assert().success().stdout(predicate::str::contains("Nothing waits on F00003"));
+    wsb(&["relationship", "impact", "F99999"]).assert().failure().stderr(predicate::str::contains("Entity not found"));
+}
+
+#[test]
+fn test_feature_review_matches_whole_feature_ids() {
+    let workspace = Workspace::with_project("Review");
+    for n in 1..=10 {
+        workspace.wsb(&["feature", "add", &format!("Feature {}", n), "Reviewed"]).assert().success();
+    }
+    let first = workspace.add_tasks("F00001", &["Sign in"]);
+    let tenth = workspace.add_tasks("F00010", &["Sign out"]);
+    workspace.execute(&format!("UPDATE tasks SET acceptance_criteria = 'Signs in' WHERE id = '{}'", first[0]));
+    workspace.execute(&format!("UPDATE tasks SET acceptance_criteria = 'Signs out' WHERE id = '{}'", tenth[0]));
+    workspace.execute("UPDATE features SET state = 'implemented_no_tests' WHERE id = 'F00001'");
+
+    workspace.wsb(&["feature", "review"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Signs in").and(predicate::str::contains("Signs out").not()))
+        .stderr(predicate::str::contains("needs an interactive terminal"));
+}
+
+#[test]
+fn test_board_requires_terminal() {
+    let workspace = Workspace::with_project("Board");
+
+    workspace.wsb(&["board", "--priority", "urgent"]).assert().failure().stderr(predicate::str::contains("Invalid task priority"));
+    workspace.wsb(&["board"]).assert().failure().stderr(predicate::str::contains("needs an interactive terminal"));
+}
+
+#[test]
+fn test_dashboard_report() {
+    let workspace = Workspace::with_project("Dashboard");
+    workspace.wsb(&["feature", "add", "Storage", "Data layer"]).assert().success();
+    workspace.wsb(&["feature", "add", "Sync", "Replication"]).assert().success();
+
+    workspace.wsb(&["dashboard"]).assert().success()
+        .stdout(predicate::str::contains("Not implemented").and(predicate::str::contains("All checks passing")));
+    workspace.wsb(&["dashboard", "--format", "json"]).assert().success()
+        .stdout(predicate::str::contains("\"feature_total\": 2").and(predicate::str::contains("\"failing_checks\": []")));
+    workspace.wsb(&["dashboard", "--tui"]).assert().failure().stderr(predicate::str::contains("needs an interactive terminal"));
+}
+
+#[test]
+fn test_mcp_server_tls_options() {
+    let workspace = Workspace::new();
+    let wsb = |args: &[&str]| workspace.wsb(args);
+
+    wsb(&["mcp-server", "--tls-cert", "cert.pem"]).assert().failure().stderr(predicate::str::contains("--tls-key"));
+    wsb(&["mcp-server", "--self-signed", "--tls-cert", "cert.pem", "--tls-key", "key.pem"]).assert().failure();
+    wsb(&["mcp-server", "--redirect-port", "8080"]).assert().failure()
+        .stderr(predicate::str::contains("needs a TLS certificate"));
+    wsb(&["mcp-server", "--tls-cert", "missing.pem", "--tls-key", "missing.key"]).assert().failure()
+        .stderr(predicate::str::contains("Failed to load TLS certificate"));
+}
+
+#[test]
+fn test_mcp_server_daemon_lifecycle() {
+    let workspace = Workspace::new();
+    let wsb = |args: &[&str]| workspace.wsb(args);
+    let root = workspace.root();
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port().to_string();
+
+    wsb(&["mcp-server", "status"]).assert().success().stdout(predicate::str::contains("not running"));
+    wsb(&["mcp-server", "restart"]).assert().failure().stderr(predicate::str::contains("not running"));
+
+    wsb(&["mcp-server", "--daemon", "--port", &port]).assert().success()
+        .stdout(predicate::str::contains("running in the background"));
+    assert!(root.join(".wsb/mcp-server.pid").exists());
+    std::net::TcpStream::connect(format!("127.0.0.1:{}", port)).unwrap();
+    wsb(&["mcp-server", "status"]).assert().success()
+        .stdout(predicate::str::contains(format!("http://127.0.0.1:{}", port)));
+    wsb(&["mcp-server", "--port", &port]).assert().failure().stderr(predicate::str::contains("already running"));
+
+    wsb(&["mcp-server", "restart"]).assert().success().stdout(predicate::str::contains("Stopped MCP server"));
+    wsb(&["mcp-server", "stop"]).assert().success().stdout(predicate::str::contains("Stopped MCP server"));
+    assert!(!root.join(".wsb/mcp-server.pid").exists());
+    assert!(fs::read_to_string(root.join(".wsb/mcp-server.log")).unwrap().contains("MCP server stopped"));
+}
+
+#[test]
+fn test_sync_markdown_with_database() {
+    let workspace = Workspace::with_project("Sync");
+    let wsb = |args: &[&str]| workspace.wsb(args);
+    let features = workspace.root().join("internal/features.md");
+
+    wsb(&["feature", "add", "Storage", "Data layer"]).assert().success();
+    wsb(&["sync", "--direction", "sideways"]).assert().failure().stderr(predicate::str::contains("Unknown sync direction"));
+    wsb(&["sync"]).assert().success().stdout(predicate::str::contains("Wrote internal/features.md"));
+    assert!(fs::read_to_string(&features).unwrap().contains("| F00001 | **Storage** | Data layer | ❌ |"));
+
+    let markdown = fs::read_to_string(&features).unwrap();
+    fs::write(&features, markdown.replace("**Storage**", "**Persistence**")).unwrap();
+    wsb(&["sync", "--dry-run"]).assert().success()
+        .stdout(predicate::str::contains("F00001 updated in the database (title)").and(predicate::str::contains("Dry run")));
+    wsb(&["sync"]).assert().success().stdout(predicate::str::contains("in sync"));
+    wsb(&["feature", "list"]).assert().success().stdout(predicate::str::contains("Persistence"));
+
+    let markdown = fs::read_to_string(&features).unwrap();
+    fs::write(&features, markdown.replace("| ❌ |", "| 🟠 |")).unwrap();
+    wsb(&["feature", "update", "F00001", "--state", "critical", "--force"]).assert().success();
+    wsb(&["sync"]).assert().failure()
+        .stdout(predicate::str::contains("F00001 changed on both sides (state)"))
+        .stderr(predicate::str::contains("1 conflicting records"));
+
+    wsb(&["sync", "canonical"]).assert().success().stdout(predicate::str::contains("No canonical source"));
+    wsb(&["sync", "canonical", "db"]).assert().success();
+    wsb(&["sync"]).assert().success();
+    assert!(fs::read_to_string(&features).unwrap().contains("| 🔴 |"));
+    wsb(&["sync", "canonical", "yes"]).assert().failure().stderr(predicate::str::contains("Unknown sync source"));
+}
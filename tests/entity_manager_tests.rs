@@ -3,8 +3,8 @@
 
 use anyhow::Result;
 use tempfile::tempdir;
-use workspace::entities::database::initialize_database;
-use workspace::entities::{EntityManager, FeatureState, TaskStatus, SessionStatus, DirectiveCategory, Priority};
+use wsb::entities::database::initialize_database;
+use wsb::entities::{EntityManager, FeatureState, TaskStatus, SessionStatus, DirectiveCategory, Priority};
 
 /// Test EntityManager project operations
 #[tokio::test]
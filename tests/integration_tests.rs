@@ -3,7 +3,7 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 use tempfile::TempDir;
-use workspace::{cli::Args, run_refac};
+use wsb::{cli::Args, run_refac};
 
 /// Test utilities
 mod test_utils {
@@ -108,13 +108,14 @@ fn test_basic_replacement() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     // Run refac
@@ -158,13 +159,14 @@ fn test_mandatory_validation() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     // Run operation (validation is now mandatory and automatic)
@@ -208,13 +210,14 @@ fn test_case_sensitivity() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -254,13 +257,14 @@ fn test_complex_nested_structure() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -306,13 +310,14 @@ fn test_files_only_mode() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -349,13 +354,14 @@ fn test_dirs_only_mode() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -395,13 +401,14 @@ fn test_names_only_mode() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -439,13 +446,14 @@ fn test_content_only_mode() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -488,13 +496,14 @@ fn test_binary_file_handling_default() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false, // Default: binary files are NOT renamed
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -541,13 +550,14 @@ fn test_binary_file_handling_with_flag() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: true, // Enable binary file renaming
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -600,13 +610,14 @@ fn test_binary_flag_cli_integration() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args_default)?;
@@ -655,13 +666,14 @@ fn test_binary_names_flag_enables_renaming() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: true, // Enable binary renaming
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -711,13 +723,14 @@ fn test_mixed_binary_text_with_flag() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: true,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -767,13 +780,14 @@ fn test_binary_flag_with_names_only_mode() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: true,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -820,13 +834,14 @@ fn test_binary_flag_with_content_only_mode() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: true, // This should have no effect in content_only mode
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -879,13 +894,14 @@ fn test_binary_flag_edge_cases() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args_default)?;
@@ -926,13 +942,14 @@ fn test_binary_flag_edge_cases() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: true,
+        ..Default::default()
     };
 
     run_refac(args_with_flag)?;
@@ -980,13 +997,14 @@ fn test_max_depth() -> Result<()> {
         max_depth: 4,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1023,13 +1041,14 @@ fn test_backup_functionality() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1090,13 +1109,14 @@ fn test_multiple_occurrences() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1135,13 +1155,14 @@ fn test_hidden_files() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![".*".to_string()], // Include hidden files
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1181,13 +1202,14 @@ fn test_exclude_patterns() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec!["*.log".to_string()],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1199,6 +1221,405 @@ fn test_exclude_patterns() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ignore_files_respected() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    // Build output ignored by .gitignore, generated code ignored by .refacignore
+    fs::create_dir_all(temp_dir.path().join("target/debug"))?;
+    fs::create_dir_all(temp_dir.path().join("generated"))?;
+    fs::write(temp_dir.path().join(".gitignore"), "target/\n")?;
+    fs::write(temp_dir.path().join(".refacignore"), "generated/\n")?;
+    fs::write(temp_dir.path().join("target/debug/oldname.rs"), "oldname")?;
+    fs::write(temp_dir.path().join("generated/oldname.rs"), "oldname")?;
+    fs::write(temp_dir.path().join("oldname.rs"), "oldname")?;
+
+    let make_args = |no_ignore: bool| Args {
+        root_dir: temp_dir.path().to_path_buf(),
+        pattern: "oldname".to_string(),
+        substitute: "newname".to_string(),
+        assume_yes: true,
+        verbose: false,
+        follow_symlinks: false,
+        backup: false,
+        files_only: false,
+        dirs_only: false,
+        names_only: false,
+        content_only: false,
+        max_depth: 0,
+        exclude_patterns: vec![],
+        include_patterns: vec![],
+        format: wsb::cli::OutputFormat::Plain,
+        threads: 1,
+        progress: wsb::cli::ProgressMode::Never,
+        ignore_case: false,
+        use_regex: false,
+        include_hidden: false,
+        binary_names: false,
+        no_ignore,
+        match_case_styles: false,
+        into_archives: vec![],
+        dry_run: false,
+        rewrite_link_targets: false,
+        on_collision: wsb::cli::CollisionStrategy::Abort,
+        content_extensions: vec![],
+        no_content: false,
+        map_file: None,
+        git: false,
+        skip_comments: false,
+        skip_strings: false,
+        skip_code: false,
+        preserve_mtime: false,
+    };
+
+    run_refac(make_args(false))?;
+
+    assert!(temp_dir.path().join("newname.rs").exists());
+    assert!(temp_dir.path().join("target/debug/oldname.rs").exists());
+    assert!(temp_dir.path().join("generated/oldname.rs").exists());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("generated/oldname.rs"))?, "oldname");
+
+    // --no-ignore processes ignored paths too
+    run_refac(make_args(true))?;
+
+    assert!(temp_dir.path().join("target/debug/newname.rs").exists());
+    assert!(temp_dir.path().join("generated/newname.rs").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_match_case_styles() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    fs::write(
+        temp_dir.path().join("old_name.rs"),
+        "struct OldName;\nconst OLD_NAME: u8 = 1;\nlet oldName = old_name();\n// see old-name docs\n",
+    )?;
+    fs::create_dir(temp_dir.path().join("OldName"))?;
+
+    let args = Args {
+        root_dir: temp_dir.path().to_path_buf(),
+        pattern: "old_name".to_string(),
+        substitute: "new_name".to_string(),
+        assume_yes: true,
+        verbose: false,
+        follow_symlinks: false,
+        backup: false,
+        files_only: false,
+        dirs_only: false,
+        names_only: false,
+        content_only: false,
+        max_depth: 0,
+        exclude_patterns: vec![],
+        include_patterns: vec![],
+        format: wsb::cli::OutputFormat::Plain,
+        threads: 1,
+        progress: wsb::cli::ProgressMode::Never,
+        ignore_case: false,
+        use_regex: false,
+        include_hidden: false,
+        binary_names: false,
+        match_case_styles: true,
+        ..Default::default()
+    };
+
+    run_refac(args)?;
+
+    assert!(temp_dir.path().join("new_name.rs").exists());
+    assert!(temp_dir.path().join("NewName").is_dir());
+    let content = fs::read_to_string(temp_dir.path().join("new_name.rs"))?;
+    assert_eq!(content, "struct NewName;\nconst NEW_NAME: u8 = 1;\nlet newName = new_name();\n// see new-name docs\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_into_archives() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    let archive_path = temp_dir.path().join("fixtures.tar");
+    {
+        let mut builder = tar::Builder::new(File::create(&archive_path)?);
+        let content = b"oldname fixture";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, "oldname.txt", &content[..])?;
+        builder.finish()?;
+    }
+
+    let args = Args {
+        root_dir: temp_dir.path().to_path_buf(),
+        pattern: "oldname".to_string(),
+        substitute: "newname".to_string(),
+        assume_yes: true,
+        verbose: false,
+        follow_symlinks: false,
+        backup: false,
+        files_only: false,
+        dirs_only: false,
+        names_only: false,
+        content_only: false,
+        max_depth: 0,
+        exclude_patterns: vec![],
+        include_patterns: vec![],
+        format: wsb::cli::OutputFormat::Plain,
+        threads: 1,
+        progress: wsb::cli::ProgressMode::Never,
+        ignore_case: false,
+        use_regex: false,
+        include_hidden: false,
+        binary_names: false,
+        into_archives: vec![wsb::cli::ArchiveFormat::Tar],
+        ..Default::default()
+    };
+
+    run_refac(args)?;
+
+    let mut archive = tar::Archive::new(File::open(&archive_path)?);
+    let mut entries = archive.entries()?;
+    let mut entry = entries.next().unwrap()?;
+    assert_eq!(entry.path()?.to_string_lossy(), "newname.txt");
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut entry, &mut content)?;
+    assert_eq!(content, "newname fixture");
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_makes_no_changes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir(temp_dir.path().join("oldname_dir"))?;
+    fs::write(temp_dir.path().join("oldname_dir").join("oldname.txt"), "oldname content")?;
+
+    let args = Args {
+        root_dir: temp_dir.path().to_path_buf(),
+        pattern: "oldname".to_string(),
+        substitute: "newname".to_string(),
+        assume_yes: true,
+        verbose: false,
+        follow_symlinks: false,
+        backup: false,
+        files_only: false,
+        dirs_only: false,
+        names_only: false,
+        content_only: false,
+        max_depth: 0,
+        exclude_patterns: vec![],
+        include_patterns: vec![],
+        format: wsb::cli::OutputFormat::Json,
+        threads: 1,
+        progress: wsb::cli::ProgressMode::Never,
+        ignore_case: false,
+        use_regex: false,
+        include_hidden: false,
+        binary_names: false,
+        dry_run: true,
+        ..Default::default()
+    };
+
+    run_refac(args)?;
+
+    assert!(temp_dir.path().join("oldname_dir").join("oldname.txt").exists());
+    assert!(!temp_dir.path().join("newname_dir").exists());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("oldname_dir").join("oldname.txt"))?, "oldname content");
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_json_plan() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir(temp_dir.path().join("oldname_dir"))?;
+    fs::write(temp_dir.path().join("oldname_dir").join("oldname.txt"), "oldname and oldname")?;
+    fs::write(temp_dir.path().join("notes.txt"), "mentions oldname")?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--dry-run", "--format", "json"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(plan["dry_run"], true);
+    assert_eq!(plan["summary"]["content_changes"], 2);
+    assert_eq!(plan["summary"]["file_renames"], 1);
+    assert_eq!(plan["summary"]["directory_renames"], 1);
+
+    let changes = plan["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 3);
+    let file = changes.iter().find(|c| c["old_path"].as_str().unwrap().ends_with("oldname.txt")).unwrap();
+    assert_eq!(file["type"], "file");
+    assert_eq!(file["content_matches"], 2);
+    assert!(file["new_path"].as_str().unwrap().ends_with("newname.txt"));
+    let dir = changes.iter().find(|c| c["type"] == "directory").unwrap();
+    assert!(dir["new_path"].as_str().unwrap().ends_with("newname_dir"));
+
+    // Nothing was applied
+    assert!(temp_dir.path().join("oldname_dir").join("oldname.txt").exists());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("notes.txt"))?, "mentions oldname");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlink_rename_with_target_rewrite_and_cycle() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("oldname_target.txt"), "oldname content")?;
+    std::os::unix::fs::symlink("oldname_target.txt", temp_dir.path().join("oldname_link"))?;
+    std::os::unix::fs::symlink(".", temp_dir.path().join("cycle"))?;
+
+    let args = Args {
+        root_dir: temp_dir.path().to_path_buf(),
+        pattern: "oldname".to_string(),
+        substitute: "newname".to_string(),
+        assume_yes: true,
+        verbose: false,
+        follow_symlinks: true,
+        backup: false,
+        files_only: false,
+        dirs_only: false,
+        names_only: false,
+        content_only: false,
+        max_depth: 0,
+        exclude_patterns: vec![],
+        include_patterns: vec![],
+        format: wsb::cli::OutputFormat::Plain,
+        threads: 1,
+        progress: wsb::cli::ProgressMode::Never,
+        ignore_case: false,
+        use_regex: false,
+        include_hidden: false,
+        binary_names: false,
+        rewrite_link_targets: true,
+        ..Default::default()
+    };
+
+    // The cycle is skipped with a warning rather than failing the run
+    run_refac(args)?;
+
+    let link = temp_dir.path().join("newname_link");
+    assert!(link.symlink_metadata()?.file_type().is_symlink());
+    assert!(!temp_dir.path().join("oldname_link").symlink_metadata().is_ok());
+    assert_eq!(fs::read_link(&link)?, Path::new("newname_target.txt"));
+
+    // Content reached through both the link and the target is rewritten once
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname_target.txt"))?, "newname content");
+    assert_eq!(fs::read_to_string(&link)?, "newname content");
+
+    Ok(())
+}
+
+#[test]
+fn test_on_collision_strategies() -> Result<()> {
+    let run = |strategy: &str| -> Result<(TempDir, std::process::Output)> {
+        let temp_dir = TempDir::new()?;
+        fs::write(temp_dir.path().join("oldname.txt"), "first")?;
+        fs::write(temp_dir.path().join("newname.txt"), "second")?;
+        let output = assert_cmd::Command::cargo_bin("wsb")?
+            .args(["refactor", ".", "oldname", "newname", "--assume-yes", "--on-collision", strategy])
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .current_dir(temp_dir.path())
+            .output()?;
+        Ok((temp_dir, output))
+    };
+
+    // abort: the conflicting pair is reported and nothing is touched
+    let (temp_dir, output) = run("abort")?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("oldname.txt")
+        || String::from_utf8_lossy(&output.stderr).contains("oldname.txt"));
+    assert_eq!(fs::read_to_string(temp_dir.path().join("oldname.txt"))?, "first");
+
+    // skip: the colliding item keeps its name
+    let (temp_dir, output) = run("skip")?;
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("oldname.txt"))?, "first");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.txt"))?, "second");
+
+    // suffix: the colliding item moves to the first free variant
+    let (temp_dir, output) = run("suffix")?;
+    assert!(output.status.success());
+    assert!(!temp_dir.path().join("oldname.txt").exists());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname_1.txt"))?, "first");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.txt"))?, "second");
+
+    Ok(())
+}
+
+#[test]
+fn test_content_extension_filters() -> Result<()> {
+    let run = |extra: &[&str]| -> Result<(TempDir, std::process::Output)> {
+        let temp_dir = TempDir::new()?;
+        fs::write(temp_dir.path().join("oldname.rs"), "use oldname;")?;
+        fs::write(temp_dir.path().join("oldname.md"), "# oldname")?;
+        let mut args = vec!["refactor", ".", "oldname", "newname", "--assume-yes"];
+        args.extend_from_slice(extra);
+        let output = assert_cmd::Command::cargo_bin("wsb")?
+            .args(&args)
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .current_dir(temp_dir.path())
+            .output()?;
+        Ok((temp_dir, output))
+    };
+
+    // --content-ext: only listed extensions get content replaced, every path is renamed
+    let (temp_dir, output) = run(&["--content-ext", "rs"])?;
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.rs"))?, "use newname;");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.md"))?, "# oldname");
+
+    // --no-content: paths are renamed, contents left alone
+    let (temp_dir, output) = run(&["--no-content"])?;
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.rs"))?, "use oldname;");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.md"))?, "# oldname");
+
+    // --no-content cannot be combined with --content-only
+    let (_temp_dir, output) = run(&["--no-content", "--content-only"])?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
+
+#[test]
+fn test_map_file_applies_pairs_in_one_pass() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("old_widget.rs"), "use old_widget::OldPanel;\nlet left = right;")?;
+    let map = temp_dir.path().join("renames.toml");
+    fs::write(&map, "[renames]\nold_widget = \"new_widget\"\nOldPanel = \"NewPanel\"\nleft = \"right\"\nright = \"left\"\n")?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "--map", "renames.toml", "--assume-yes", "--exclude", "renames.toml"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // Every pair is applied once; swapped pairs do not cancel each other out
+    assert!(!temp_dir.path().join("old_widget.rs").exists());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("new_widget.rs"))?,
+        "use new_widget::NewPanel;\nlet right = left;"
+    );
+
+    // Two patterns merged into one substitute are rejected before anything changes
+    fs::write(&map, "[renames]\nnew_widget = \"gadget\"\nNewPanel = \"gadget\"\n")?;
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "--map", "renames.toml", "--assume-yes", "--exclude", "renames.toml"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(!output.status.success());
+    assert!(temp_dir.path().join("new_widget.rs").exists());
+
+    Ok(())
+}
+
 #[test]
 fn test_parallel_processing() -> Result<()> {
     let temp_dir = TempDir::new()?;
@@ -1224,13 +1645,14 @@ fn test_parallel_processing() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 4, // Use multiple threads
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1278,13 +1700,14 @@ fn test_include_hidden_flag() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: true, // Enable hidden file processing
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1337,13 +1760,14 @@ fn test_include_hidden_flag_disabled() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false, // Disable hidden file processing
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1386,13 +1810,14 @@ fn test_include_hidden_with_patterns() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec!["*.log".to_string()], // Exclude .log files
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: true, // Enable hidden file processing
         binary_names: false,
+        ..Default::default()
     };
 
     run_refac(args)?;
@@ -1433,13 +1858,14 @@ fn test_content_only_allows_path_separators() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
     
     run_refac(args)?;
@@ -1480,13 +1906,14 @@ fn test_typescript_file_processing() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
     
     run_refac(args)?;
@@ -1525,13 +1952,14 @@ fn test_mutual_exclusion_validation() -> Result<()> {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     };
     
     // Should fail during validation
@@ -1539,4 +1967,217 @@ fn test_mutual_exclusion_validation() -> Result<()> {
     assert!(result.is_err());
     
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_localized_messages_from_catalog_dir() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let locales = TempDir::new()?;
+    fs::write(temp_dir.path().join("notes.txt"), "nothing to change")?;
+    fs::write(
+        locales.path().join("de.toml"),
+        "[refac]\nno_changes = \"Keine Änderungen nötig.\"\n",
+    )?;
+
+    let run = |lang: &str| -> Result<String> {
+        let output = assert_cmd::Command::cargo_bin("wsb")?
+            .args(["refactor", ".", "oldname", "newname", "--assume-yes"])
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .env("WSB_LANG", lang)
+            .env("WSB_LOCALE_DIR", locales.path())
+            .current_dir(temp_dir.path())
+            .output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    assert!(run("de_DE.UTF-8")?.contains("Keine Änderungen nötig."));
+    // Locales without a catalog use English
+    assert!(run("fr_FR.UTF-8")?.contains("No changes needed."));
+
+    Ok(())
+}
+
+#[test]
+fn test_refactor_undo_subcommand() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let journals = TempDir::new()?;
+    // A directory named like the subcommand is still reachable as ./undo
+    fs::create_dir(temp_dir.path().join("undo"))?;
+    fs::write(temp_dir.path().join("undo").join("oldname.txt"), "oldname here")?;
+
+    let wsb = |args: &[&str]| -> Result<assert_cmd::assert::Assert> {
+        Ok(assert_cmd::Command::cargo_bin("wsb")?
+            .args(args)
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .env("WSB_REFAC_JOURNAL_DIR", journals.path())
+            .current_dir(temp_dir.path())
+            .assert())
+    };
+
+    wsb(&["refactor", "./undo", "oldname", "newname", "--assume-yes"])?.success();
+    wsb(&["refactor", "undo", "--list"])?.success().stdout(predicates::str::contains("oldname"));
+    wsb(&["--read-only", "refactor", "undo"])?.failure().stderr(predicates::str::contains("read-only mode"));
+
+    // An edit made after the run is only overwritten with --force
+    fs::write(temp_dir.path().join("undo").join("newname.txt"), "newname here, edited")?;
+    wsb(&["refactor", "undo"])?.failure().stderr(predicates::str::contains("undo with --force"));
+    assert!(temp_dir.path().join("undo").join("newname.txt").exists());
+    wsb(&["refactor", "undo", "--force"])?.success();
+    assert_eq!(fs::read_to_string(temp_dir.path().join("undo").join("oldname.txt"))?, "oldname here");
+
+    Ok(())
+}
+
+#[test]
+fn test_progress_counts_in_final_report() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir(temp_dir.path().join("src"))?;
+    fs::write(temp_dir.path().join("src").join("oldname.rs"), "fn oldname() {}")?;
+    fs::write(temp_dir.path().join("src").join("lib.rs"), "mod oldname;")?;
+    fs::write(temp_dir.path().join("README.md"), "unrelated")?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--assume-yes", "--format", "plain", "--progress", "always"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // src/, both files under it and README.md
+    assert!(stdout.contains("Entries scanned: 4"), "{}", stdout);
+    assert!(stdout.contains("Content files processed: 2"), "{}", stdout);
+    assert!(temp_dir.path().join("src").join("newname.rs").exists());
+
+    // Progress bars are drawn in human mode without disturbing the run
+    fs::write(temp_dir.path().join("oldname.txt"), "oldname")?;
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--assume-yes", "--progress", "always"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(temp_dir.path().join("newname.txt"))?, "newname");
+
+    Ok(())
+}
+
+#[test]
+fn test_git_mode_records_renames() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    let git = |args: &[&str]| -> Result<String> {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()?;
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    git(&["init", "-q"])?;
+    fs::create_dir(root.join("oldname_dir"))?;
+    // Enough unchanged lines for git to pair the old and new paths as a rename
+    let body: String = (0..20).map(|i| format!("const LINE_{}: u32 = {};\n", i, i)).collect();
+    fs::write(root.join("oldname_dir").join("oldname.rs"), format!("pub fn oldname() {{}}\n{}", body))?;
+    fs::write(root.join("lib.rs"), "mod oldname;\n")?;
+    git(&["add", "."])?;
+    git(&["commit", "-q", "-m", "initial"])?;
+    fs::write(root.join("oldname_notes.txt"), "untracked oldname\n")?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--assume-yes", "--git"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(root)
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // Tracked renames and edits are staged; nothing tracked is left unstaged
+    let staged = git(&["diff", "--cached", "--name-status", "-M"])?;
+    assert!(staged.contains("oldname_dir/oldname.rs\tnewname_dir/newname.rs"), "{}", staged);
+    assert!(staged.contains("M\tlib.rs"), "{}", staged);
+    assert!(git(&["diff", "--name-only"])?.is_empty());
+
+    // Untracked files are renamed on disk but not added
+    assert!(root.join("newname_notes.txt").exists());
+    assert!(git(&["status", "--porcelain"])?.contains("?? newname_notes.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn test_skip_comments_and_strings_leave_prose_alone() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let source = "// oldname is the legacy helper\nfn oldname() -> &'static str {\n    \"oldname\"\n}\n";
+    fs::write(temp_dir.path().join("lib.rs"), source)?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--content-only", "--skip-comments", "--skip-strings", "--dry-run", "--format", "json"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(plan["replacements_by_region"], serde_json::json!({"code": 1, "comments": 0, "strings": 0, "skipped": 2}));
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--content-only", "--skip-comments", "--skip-strings", "--assume-yes", "--format", "plain"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Replacements in code: 1"), "{}", stdout);
+    assert!(stdout.contains("Matches skipped: 2"), "{}", stdout);
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("lib.rs"))?,
+        "// oldname is the legacy helper\nfn newname() -> &'static str {\n    \"oldname\"\n}\n"
+    );
+
+    // The inverse: only the human-facing text changes
+    fs::write(temp_dir.path().join("lib.rs"), source)?;
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--content-only", "--skip-code", "--assume-yes"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("lib.rs"))?,
+        "// newname is the legacy helper\nfn oldname() -> &'static str {\n    \"newname\"\n}\n"
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_preserve_mode_and_mtime_across_rewrites() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, SystemTime};
+
+    let temp_dir = TempDir::new()?;
+    let script = temp_dir.path().join("run.sh");
+    fs::write(&script, "#!/bin/sh\necho oldname\n")?;
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    File::open(&script)?.set_modified(old)?;
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o750))?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--content-only", "--preserve-mtime", "--assume-yes", "--format", "json"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    // The plan is printed before the result
+    let report = serde_json::Deserializer::from_slice(&output.stdout).into_iter::<serde_json::Value>().last().unwrap()?;
+    assert_eq!(report["metadata_not_preserved"], serde_json::json!([]));
+
+    let metadata = fs::metadata(&script)?;
+    assert_eq!(fs::read_to_string(&script)?, "#!/bin/sh\necho newname\n");
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+    assert_eq!(metadata.modified()?, old);
+
+    Ok(())
+}
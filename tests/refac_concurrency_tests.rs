@@ -6,7 +6,7 @@ use std::path::Path;
 use std::thread;
 use std::time::Duration;
 use tempfile::TempDir;
-use workspace::{cli::Args, run_refac};
+use wsb::{cli::Args, run_refac};
 
 /// Tests for concurrent operation safety and thread-related edge cases in refac tool
 /// These tests ensure the tool handles multi-threading and concurrent file system operations safely
@@ -445,6 +445,59 @@ fn test_concurrent_directory_tree_modification() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_thread_count_does_not_change_result() -> Result<()> {
+    // Build the same nested tree twice and process it serially and in parallel
+    let build_tree = |root: &Path| -> Result<()> {
+        for i in 0..20 {
+            let dir = root.join(format!("oldname_dir_{:02}", i)).join("oldname_inner");
+            fs::create_dir_all(&dir)?;
+            for j in 0..5 {
+                File::create(dir.join(format!("oldname_{}.txt", j)))?
+                    .write_all(format!("oldname {} {}", i, j).as_bytes())?;
+            }
+        }
+        Ok(())
+    };
+
+    let snapshot = |root: &Path| -> Result<Vec<(String, String)>> {
+        let mut entries = Vec::new();
+        for entry in walkdir::WalkDir::new(root).min_depth(1) {
+            let entry = entry?;
+            let relative = entry.path().strip_prefix(root)?.display().to_string();
+            let content = if entry.file_type().is_file() {
+                fs::read_to_string(entry.path())?
+            } else {
+                String::new()
+            };
+            entries.push((relative, content));
+        }
+        entries.sort();
+        Ok(entries)
+    };
+
+    let serial_dir = TempDir::new()?;
+    let parallel_dir = TempDir::new()?;
+    build_tree(serial_dir.path())?;
+    build_tree(parallel_dir.path())?;
+
+    run_refac(Args {
+        threads: 1,
+        ..create_test_args(serial_dir.path(), "oldname", "newname")
+    })?;
+    run_refac(Args {
+        threads: 8,
+        ..create_test_args(parallel_dir.path(), "oldname", "newname")
+    })?;
+
+    let serial = snapshot(serial_dir.path())?;
+    let parallel = snapshot(parallel_dir.path())?;
+    assert_eq!(serial, parallel, "Parallel run produced a different tree than the serial run");
+    assert!(serial.iter().all(|(path, content)| !path.contains("oldname") && !content.contains("oldname")));
+
+    Ok(())
+}
+
 // Helper function to create standardized test arguments
 fn create_test_args(root_dir: &Path, pattern: &str, substitute: &str) -> Args {
     Args {
@@ -462,12 +515,13 @@ fn create_test_args(root_dir: &Path, pattern: &str, substitute: &str) -> Args {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1, // Will be overridden in individual tests
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     }
 }
\ No newline at end of file
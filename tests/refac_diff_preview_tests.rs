@@ -3,8 +3,8 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 use tempfile::TempDir;
-use workspace::{Args, RenameEngine};
-use workspace::refac::cli::{OutputFormat, ProgressMode};
+use wsb::{Args, RenameEngine};
+use wsb::refac::cli::{OutputFormat, ProgressMode};
 
 /// Test utilities for diff preview functionality
 mod test_utils {
@@ -62,6 +62,7 @@ fn test_diff_preview_basic_functionality() -> Result<()> {
         include_hidden: false,
         backup: false,
         binary_names: false,
+        ..Default::default()
     };
 
     // Create rename engine
@@ -117,6 +118,7 @@ fn test_diff_preview_line_counting() -> Result<()> {
         include_hidden: false,
         backup: false,
         binary_names: false,
+        ..Default::default()
     };
 
     let engine = RenameEngine::new(args)?;
@@ -165,6 +167,7 @@ fn test_diff_preview_with_regex_patterns() -> Result<()> {
         include_hidden: false,
         backup: false,
         binary_names: false,
+        ..Default::default()
     };
 
     let engine = RenameEngine::new(args)?;
@@ -209,6 +212,7 @@ fn test_diff_preview_no_changes_scenario() -> Result<()> {
         include_hidden: false,
         backup: false,
         binary_names: false,
+        ..Default::default()
     };
 
     let engine = RenameEngine::new(args)?;
@@ -264,6 +268,7 @@ fn test_diff_preview_context_lines() -> Result<()> {
         include_hidden: false,
         backup: false,
         binary_names: false,
+        ..Default::default()
     };
 
     let engine = RenameEngine::new(args)?;
@@ -317,6 +322,7 @@ fn test_diff_preview_multiple_files_limit() -> Result<()> {
         include_hidden: false,
         backup: false,
         binary_names: false,
+        ..Default::default()
     };
 
     let engine = RenameEngine::new(args)?;
@@ -2,9 +2,9 @@ use anyhow::Result;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
-use std::os::unix::fs::PermissionsExt;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use tempfile::TempDir;
-use workspace::{cli::Args, run_refac};
+use wsb::{cli::Args, run_refac};
 
 /// Critical edge case tests for refac tool - mission critical scenarios
 /// These tests cover complex directory structures, empty directory cleanup,
@@ -231,6 +231,12 @@ fn test_readonly_files_and_directories() -> Result<()> {
     let readonly_file = temp_dir.path().join("oldname_readonly.txt");
     File::create(&readonly_file)?
         .write_all(b"oldname content")?;
+
+    // New files belong to the effective uid; root ignores the permission bits under test
+    if fs::metadata(&readonly_file)?.uid() == 0 {
+        eprintln!("skipping test_readonly_files_and_directories: running as root");
+        return Ok(());
+    }
     
     // Make file read-only
     let mut perms = fs::metadata(&readonly_file)?.permissions();
@@ -523,12 +529,13 @@ fn create_test_args(root_dir: &Path, pattern: &str, substitute: &str) -> Args {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     }
 }
\ No newline at end of file
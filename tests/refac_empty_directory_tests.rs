@@ -3,7 +3,7 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 use tempfile::TempDir;
-use workspace::{cli::Args, run_refac};
+use wsb::{cli::Args, run_refac};
 
 /// Specific tests for empty directory cleanup issues in refac tool
 /// These tests address the specific issue where folders remain empty but undeleted
@@ -374,12 +374,13 @@ fn create_test_args(root_dir: &Path, pattern: &str, substitute: &str) -> Args {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     }
 }
\ No newline at end of file
@@ -3,7 +3,7 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 use tempfile::TempDir;
-use workspace::{cli::Args, run_refac};
+use wsb::{cli::Args, run_refac};
 
 /// Tests for file encoding handling and validation in refac tool
 /// These tests ensure encoding issues are caught during validation, not during execution
@@ -274,6 +274,76 @@ fn test_very_long_lines_handling() -> Result<()> {
 }
 
 // Helper function to create standardized test arguments
+#[test]
+fn test_utf16_content_replacement_preserves_encoding() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    // UTF-16LE with a BOM, as written by many Windows tools
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend("config for oldname\r\n".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    fs::write(temp_dir.path().join("settings.txt"), &bytes)?;
+
+    // UTF-16BE without a BOM
+    let bytes: Vec<u8> = "oldname = 1\n".encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+    fs::write(temp_dir.path().join("values.txt"), &bytes)?;
+
+    run_refac(create_test_args(temp_dir.path(), "oldname", "newname"))?;
+
+    let mut expected = vec![0xFF, 0xFE];
+    expected.extend("config for newname\r\n".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    assert_eq!(fs::read(temp_dir.path().join("settings.txt"))?, expected);
+
+    let expected: Vec<u8> = "newname = 1\n".encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+    assert_eq!(fs::read(temp_dir.path().join("values.txt"))?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_latin1_content_replacement_preserves_bytes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    // "café oldname" in Latin-1: é is the single byte 0xE9
+    fs::write(temp_dir.path().join("menu.txt"), b"caf\xE9 oldname\n")?;
+
+    run_refac(create_test_args(temp_dir.path(), "oldname", "newname"))?;
+
+    assert_eq!(fs::read(temp_dir.path().join("menu.txt"))?, b"caf\xE9 newname\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_encoding_is_reported_and_skipped() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    // Looks like text in the sampled prefix, but the tail is neither UTF-8 nor UTF-16
+    let mut bytes = "oldname line\n".repeat(2000).into_bytes();
+    bytes.extend_from_slice(&[0xC3, 0x00, 0x00, 0x00, 0xE9, 0x00, b'x']);
+    fs::write(temp_dir.path().join("corrupt.log"), &bytes)?;
+    fs::write(temp_dir.path().join("plain.txt"), "oldname")?;
+
+    let output = assert_cmd::Command::cargo_bin("wsb")?
+        .args(["refactor", ".", "oldname", "newname", "--dry-run", "--format", "json"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let skipped = report["skipped_encodings"].as_array().unwrap();
+    assert_eq!(skipped.len(), 1);
+    assert!(skipped[0].as_str().unwrap().starts_with("corrupt.log"));
+
+    run_refac(create_test_args(temp_dir.path(), "oldname", "newname"))?;
+
+    // The undecodable file is left untouched while the rest of the run proceeds
+    assert_eq!(fs::read(temp_dir.path().join("corrupt.log"))?, bytes);
+    assert_eq!(fs::read_to_string(temp_dir.path().join("plain.txt"))?, "newname");
+
+    Ok(())
+}
+
 fn create_test_args(root_dir: &Path, pattern: &str, substitute: &str) -> Args {
     Args {
         root_dir: root_dir.to_path_buf(),
@@ -290,12 +360,13 @@ fn create_test_args(root_dir: &Path, pattern: &str, substitute: &str) -> Args {
         max_depth: 0,
         exclude_patterns: vec![],
         include_patterns: vec![],
-        format: workspace::cli::OutputFormat::Plain,
+        format: wsb::cli::OutputFormat::Plain,
         threads: 1,
-        progress: workspace::cli::ProgressMode::Never,
+        progress: wsb::cli::ProgressMode::Never,
         ignore_case: false,
         use_regex: false,
         include_hidden: false,
         binary_names: false,
+        ..Default::default()
     }
 }
\ No newline at end of file
@@ -237,7 +237,9 @@ fn test_scrap_find_filename() {
         .current_dir(temp_path)
         .assert()
         .success()
-        .stdout(predicate::str::contains("No matching files found"));
+        .stdout(predicate::str::contains("file1.txt"))
+        .stdout(predicate::str::contains("file2.log").not())
+        .stdout(predicate::str::contains("Found 1 matching files"));
 }
 
 #[test]
@@ -274,7 +276,8 @@ fn test_scrap_find_content() {
         .current_dir(temp_path)
         .assert()
         .success()
-        .stdout(predicate::str::contains("No matching files found"));
+        .stdout(predicate::str::contains("file1.txt:1: content1"))
+        .stdout(predicate::str::contains("Found 1 matching items (1 matching lines)"));
 }
 
 #[test]
@@ -557,4 +560,58 @@ fn test_unscrap_custom_destination() {
     assert!(custom_file.exists());
     assert!(!test_file.exists());
     assert!(!temp_path.join(".scrap").join("test.txt").exists());
-}
\ No newline at end of file
+}
+#[test]
+fn test_scrap_archive_tier_and_unscrap() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+
+    let test_file = temp_path.join("old.txt");
+    fs::write(&test_file, "old content").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .arg("scrap")
+        .arg("old.txt")
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success();
+
+    // Everything is older than zero days
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "archive", "--tier", "--days", "0"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archived 1 items older than 0 days"));
+
+    assert!(!temp_path.join(".scrap").join("old.txt").exists());
+    let archives: Vec<_> = fs::read_dir(temp_path.join(".scrap").join("archive")).unwrap().collect();
+    assert_eq!(archives.len(), 1);
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .arg("scrap")
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[archived: "));
+
+    // unscrap extracts transparently from the archive
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .arg("unscrap")
+        .arg("old.txt")
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored old.txt to old.txt"));
+
+    assert_eq!(fs::read_to_string(&test_file).unwrap(), "old content");
+    assert_eq!(fs::read_dir(temp_path.join(".scrap").join("archive")).unwrap().count(), 0);
+}
@@ -471,4 +471,516 @@ fn test_scrap_handles_empty_gitignore() {
     // Check .gitignore was updated correctly
     let contents = fs::read_to_string(&gitignore_path).unwrap();
     assert_eq!(contents, ".scrap/\n");
-}
\ No newline at end of file
+}
+#[test]
+fn test_scrap_refuses_protected_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::create_dir_all(temp_path.join(".git").join("refs")).unwrap();
+
+    for path in [".", ".git", ".git/refs"] {
+        Command::cargo_bin("wsb")
+            .unwrap()
+            .arg("scrap")
+            .arg(path)
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .current_dir(temp_path)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Refusing to scrap"))
+            .stderr(predicate::str::contains("--i-know-what-im-doing"));
+    }
+    assert!(temp_path.join(".git").join("refs").exists());
+
+    // The override lets an explicitly confirmed protected path through
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", ".git/refs", "--i-know-what-im-doing"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("protected path"));
+    assert!(!temp_path.join(".git").join("refs").exists());
+    assert!(temp_path.join(".scrap").join("refs").exists());
+}
+
+#[test]
+fn test_scrap_respects_protected_globs() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::create_dir_all(temp_path.join(".wsb")).unwrap();
+    fs::write(
+        temp_path.join(".wsb").join("state.json"),
+        r#"{"version": 1, "project_root": ".", "project_name": null, "tools": {"scrap": {"protected_globs": ["*.lock"]}}, "wstemplate_entries": []}"#,
+    ).unwrap();
+    fs::write(temp_path.join("Cargo.lock"), "locked").unwrap();
+    fs::write(temp_path.join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "Cargo.lock"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("*.lock"));
+    assert!(temp_path.join("Cargo.lock").exists());
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "notes.txt"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success();
+    assert!(!temp_path.join("notes.txt").exists());
+}
+
+#[test]
+fn test_scrap_find_searches_content() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::create_dir_all(temp_path.join("legacy/nested")).unwrap();
+    fs::write(temp_path.join("legacy/nested/config.rs"), "fn load() {}\nlet token = \"abc123\";\n").unwrap();
+    fs::write(temp_path.join("notes.txt"), "nothing to see").unwrap();
+
+    for path in ["legacy", "notes.txt"] {
+        Command::cargo_bin("wsb")
+            .unwrap()
+            .args(["scrap", path])
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .current_dir(temp_path)
+            .assert()
+            .success();
+    }
+
+    // Name-only search does not look inside files
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "find", "tok[e]n"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No matching files found"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "find", "tok[e]n", "--content"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("legacy/nested/config.rs:2: let token = \"abc123\";"))
+        .stdout(predicate::str::contains("Found 1 matching items (1 matching lines)"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "find", "token", "--content", "--max-size", "10"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped 2 file(s) larger than 10 bytes"));
+}
+
+#[test]
+fn test_unscrap_interactive_requires_terminal() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::write(temp_path.join("test.txt"), "test content").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "test.txt"])
+        .current_dir(temp_path)
+        .assert()
+        .success();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "--interactive"])
+        .current_dir(temp_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--interactive needs a terminal"));
+
+    // Nothing was restored
+    assert!(temp_path.join(".scrap").join("test.txt").exists());
+}
+
+#[test]
+fn test_scrap_retention_max_items() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "config", "--max-items", "2"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("max 2 items"));
+
+    for name in ["first.txt", "second.txt", "third.txt"] {
+        fs::write(temp_path.join(name), name).unwrap();
+        Command::cargo_bin("wsb")
+            .unwrap()
+            .args(["scrap", name])
+            .env("WS_COMPLETIONS_LOADED", "1")
+            .current_dir(temp_path)
+            .assert()
+            .success();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    // The oldest item made room for the newest
+    assert!(!temp_path.join(".scrap/first.txt").exists());
+    assert!(temp_path.join(".scrap/second.txt").exists());
+    assert!(temp_path.join(".scrap/third.txt").exists());
+
+    // Purging keeps the policy
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "purge", "--force"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success();
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "config"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("max 2 items"));
+}
+
+#[test]
+fn test_scrap_rm_and_unscrap_all() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(temp_path.join(name), name).unwrap();
+        Command::cargo_bin("wsb")
+            .unwrap()
+            .args(["scrap", name])
+            .current_dir(temp_path)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "rm", "b.txt"])
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed: b.txt"));
+    assert!(!temp_path.join(".scrap").join("b.txt").exists());
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "rm", "missing.txt"])
+        .current_dir(temp_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Item not found in scrap: missing.txt"));
+
+    // Nothing was scrapped after this date
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "--all", "--since", "2999-01-01"])
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No items in scrap folder to restore"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "--all", "--since", "2000-01-01"])
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored 2 items"));
+
+    assert_eq!(fs::read_to_string(temp_path.join("a.txt")).unwrap(), "a.txt");
+    assert_eq!(fs::read_to_string(temp_path.join("c.txt")).unwrap(), "c.txt");
+    assert!(!temp_path.join("b.txt").exists());
+}
+
+#[test]
+fn test_scrap_deduplicates_identical_content() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+
+    for _ in 0..3 {
+        fs::write(temp_path.join("notes.txt"), "the same notes").unwrap();
+        Command::cargo_bin("wsb")
+            .unwrap()
+            .args(["scrap", "notes.txt"])
+            .current_dir(temp_path)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "list", "--duplicates"])
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 entries"))
+        .stdout(predicate::str::contains("Space saved by deduplication: 28 bytes"));
+
+    // Restoring one entry leaves the others' shared content in place
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "notes.txt"])
+        .current_dir(temp_path)
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(temp_path.join("notes.txt")).unwrap(), "the same notes");
+    assert_eq!(fs::read_to_string(temp_path.join(".scrap").join("notes_1.txt")).unwrap(), "the same notes");
+
+    // The restored file is a copy of its own, so editing it does not reach the scrap
+    fs::write(temp_path.join("notes.txt"), "edited after restore").unwrap();
+    assert_eq!(fs::read_to_string(temp_path.join(".scrap").join("notes_1.txt")).unwrap(), "the same notes");
+    assert_eq!(fs::read_to_string(temp_path.join(".scrap").join("notes_2.txt")).unwrap(), "the same notes");
+}
+
+#[test]
+fn test_scrap_json_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::write(temp_path.join("a.txt"), "alpha token").unwrap();
+    fs::write(temp_path.join("b.txt"), "beta").unwrap();
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "a.txt", "b.txt", "--format", "json"])
+        .current_dir(temp_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let scrapped: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scrapped["scrapped"].as_array().unwrap().len(), 2);
+    assert!(!temp_path.join("b.txt").exists());
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "list", "--sort", "name", "--format", "json"])
+        .current_dir(temp_path)
+        .output()
+        .unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["entries"][0]["name"], "a.txt");
+    assert_eq!(listing["entries"][0]["size"], 11);
+    assert_eq!(listing["total_size"], 15);
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "find", "token", "--content", "--format", "json"])
+        .current_dir(temp_path)
+        .output()
+        .unwrap();
+    let found: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(found["matches"].as_array().unwrap().len(), 1);
+    assert_eq!(found["matches"][0]["lines"][0]["line"], 1);
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "clean", "--days", "0", "--dry-run", "--format", "json"])
+        .current_dir(temp_path)
+        .output()
+        .unwrap();
+    let cleaned: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(cleaned["dry_run"], true);
+    assert_eq!(cleaned["removed"].as_array().unwrap().len(), 2);
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "purge", "--force", "--format", "json"])
+        .current_dir(temp_path)
+        .output()
+        .unwrap();
+    let purged: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(purged["removed"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_scrap_purge_secure() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::write(temp_path.join("key.pem"), "private key").unwrap();
+    fs::write(temp_path.join("copy.pem"), "private key").unwrap();
+    fs::write(temp_path.join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "key.pem", "copy.pem", "notes.txt"])
+        .current_dir(temp_path)
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "purge", "--force", "--secure", "--passes", "2", "--format", "json"])
+        .current_dir(temp_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let purged: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(purged["removed"].as_array().unwrap().len(), 3);
+    assert_eq!(purged["secure"]["passes"], 2);
+    // Filesystems that cannot be overwritten in place report the files instead
+    let shredded = purged["secure"]["shredded"].as_u64().unwrap();
+    let not_secure = purged["secure"]["not_secure"].as_array().unwrap().len() as u64;
+    assert_eq!(shredded + not_secure, 3);
+
+    let remaining: Vec<_> = fs::read_dir(temp_path.join(".scrap"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(remaining, vec![std::ffi::OsString::from(".metadata.json")]);
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn test_scrap_to_system_trash() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    let data_home = temp_dir.path().join("data");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("old notes.txt"), "keep me around").unwrap();
+    fs::write(project.join("build.log"), "log").unwrap();
+
+    let wsb = || {
+        let mut cmd = Command::cargo_bin("wsb").unwrap();
+        cmd.env("XDG_DATA_HOME", &data_home).current_dir(&project);
+        cmd
+    };
+
+    wsb().args(["scrap", "old notes.txt", "build.log", "--system-trash"]).assert().success();
+    let trashed = data_home.join("Trash/files/old notes.txt");
+    assert_eq!(fs::read_to_string(&trashed).unwrap(), "keep me around");
+    let info = fs::read_to_string(data_home.join("Trash/info/old notes.txt.trashinfo")).unwrap();
+    assert!(info.contains("/project/old%20notes.txt"), "{}", info);
+    assert!(!project.join(".scrap/old notes.txt").exists());
+
+    let output = wsb().args(["scrap", "list", "--format", "json"]).output().unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["entries"].as_array().unwrap().len(), 2);
+    assert_eq!(listing["total_size"], 17);
+
+    wsb().args(["unscrap", "old notes.txt"]).assert().success();
+    assert_eq!(fs::read_to_string(project.join("old notes.txt")).unwrap(), "keep me around");
+    assert!(!trashed.exists());
+    assert!(!data_home.join("Trash/info/old notes.txt.trashinfo").exists());
+
+    // Emptying the trash elsewhere drops the entry from the index
+    fs::remove_file(data_home.join("Trash/files/build.log")).unwrap();
+    let output = wsb().args(["scrap", "list", "--format", "json"]).output().unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["entries"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_unscrap_merge_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::create_dir_all(temp_path.join("src/extra")).unwrap();
+    fs::write(temp_path.join("src/lib.rs"), "old lib").unwrap();
+    fs::write(temp_path.join("src/extra/helper.rs"), "helper").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "src"])
+        .current_dir(temp_path)
+        .assert()
+        .success();
+
+    fs::create_dir_all(temp_path.join("src")).unwrap();
+    fs::write(temp_path.join("src/lib.rs"), "new lib").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "src", "--merge"])
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("restored  extra"))
+        .stdout(predicate::str::contains("conflict  lib.rs"));
+
+    assert_eq!(fs::read_to_string(temp_path.join("src/extra/helper.rs")).unwrap(), "helper");
+    assert_eq!(fs::read_to_string(temp_path.join("src/lib.rs")).unwrap(), "new lib");
+    assert!(temp_path.join(".scrap/src/lib.rs").exists());
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "src", "--merge", "--backup-existing"])
+        .current_dir(temp_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("existing moved to lib.rs.bak"));
+
+    assert_eq!(fs::read_to_string(temp_path.join("src/lib.rs")).unwrap(), "old lib");
+    assert_eq!(fs::read_to_string(temp_path.join("src/lib.rs.bak")).unwrap(), "new lib");
+    assert!(!temp_path.join(".scrap/src").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scrap_hooks_receive_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path();
+    fs::create_dir_all(temp_path.join(".wsb")).unwrap();
+    fs::write(
+        temp_path.join(".wsb").join("state.json"),
+        r#"{"version": 1, "project_root": ".", "project_name": null, "tools": {"scrap": {"hooks": {"pre": ["test \"$WSB_SCRAP_ACTION\" != remove"], "post": ["cat >> audit.log; echo >> audit.log"]}}}, "wstemplate_entries": []}"#,
+    ).unwrap();
+    fs::write(temp_path.join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "notes.txt"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success();
+
+    // The pre hook refuses removals
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["scrap", "rm", "notes.txt"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cancelled by a scrap pre hook"));
+    assert!(temp_path.join(".scrap").join("notes.txt").exists());
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["unscrap", "notes.txt"])
+        .env("WS_COMPLETIONS_LOADED", "1")
+        .current_dir(temp_path)
+        .assert()
+        .success();
+
+    let events: Vec<serde_json::Value> = fs::read_to_string(temp_path.join("audit.log"))
+        .unwrap()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["action"], "scrap");
+    assert_eq!(events[0]["entries"][0]["scrapped_name"], "notes.txt");
+    assert_eq!(events[1]["action"], "unscrap");
+    assert_eq!(events[1]["phase"], "post");
+}
@@ -2,7 +2,7 @@
 // Tests that MCP protocol handler can be instantiated
 
 // use anyhow::Result; // Unused in this simple test
-use workspace::mcp_protocol::McpProtocolHandler;
+use wsb::mcp_protocol::McpProtocolHandler;
 
 /// Test MCP protocol handler creation
 #[test]
@@ -950,7 +950,7 @@ fn test_st8_install_hook_includes_git_add() {
 
 // ==== PROJECT FILE UPDATE TESTS ====
 
-use workspace::st8::{ProjectFile, ProjectFileType, VersionInfo, update_project_file, update_project_files, detect_project_files};
+use wsb::st8::{ProjectFile, ProjectFileType, VersionInfo, update_project_file, update_project_files, detect_project_files};
 
 /// Test helper to create version info
 fn create_test_version() -> VersionInfo {
@@ -1206,4 +1206,410 @@ fn test_multiple_project_file_formats() {
             _ => {} // Other types handled in separate specific tests
         }
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_st8_update_refuses_regressed_version() {
+    let temp_dir = TempDir::new().unwrap();
+    setup_git_repo(temp_dir.path()).unwrap();
+    create_test_commits(temp_dir.path(), 3).unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .arg("update")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    // Same commit again keeps the recorded version
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .arg("update")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    // Rewrite history so the commit count drops
+    std::process::Command::new("git")
+        .args(["reset", "--hard", "HEAD~2"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .arg("update")
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is lower than"))
+        .stderr(predicate::str::contains("rebased or rewritten"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--force-version", "5.0.0"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let version = fs::read_to_string(temp_dir.path().join("version.txt")).unwrap();
+    assert_eq!(version.trim(), "5.0.0");
+}
+
+#[test]
+fn test_st8_update_respects_document_lock() {
+    let temp_dir = TempDir::new().unwrap();
+    setup_git_repo(temp_dir.path()).unwrap();
+    create_test_commits(temp_dir.path(), 1).unwrap();
+
+    // A live holder on another host: neither exited nor old, so not stale
+    let locks = temp_dir.path().join(".wsb/locks");
+    fs::create_dir_all(&locks).unwrap();
+    let holder = serde_json::json!({
+        "pid": 1,
+        "host": "build-agent",
+        "command": "wsb end",
+        "acquired_at": chrono::Utc::now().to_rfc3339(),
+    });
+    fs::write(locks.join("documents.lock"), holder.to_string()).unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--no-wait"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Lock 'documents' is held by pid 1 on build-agent (wsb end)"));
+    assert!(!temp_dir.path().join("version.txt").exists());
+
+    let output = Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["locks", "--format", "json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["locks"][0]["name"], "documents");
+    assert_eq!(listing["locks"][0]["stale"], false);
+
+    // Once released, update runs and leaves no lock behind
+    fs::remove_file(locks.join("documents.lock")).unwrap();
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--no-wait"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    assert!(!locks.join("documents.lock").exists());
+}
+
+#[test]
+fn test_st8_version_scheme_selection() {
+    let temp_dir = TempDir::new().unwrap();
+    setup_git_repo(temp_dir.path()).unwrap();
+    create_test_commits(temp_dir.path(), 2).unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["git", "show"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Version Scheme: commit-count"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "scheme", "semver"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "minor", "4"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    // No v0.4.* tag yet, so patch counts every commit
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["git", "show"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Current Version: 0.4.3"))
+        .stdout(predicate::str::contains("Version Scheme: semver"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "scheme", "build-number"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown version scheme"));
+}
+
+#[test]
+fn test_st8_version_bump() {
+    let temp_dir = TempDir::new().unwrap();
+    setup_git_repo(temp_dir.path()).unwrap();
+    create_test_commits(temp_dir.path(), 2).unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "scheme", "semver"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "bump", "minor", "--pre", "alpha.1", "--tag"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0.1.0-alpha.1"))
+        .stdout(predicate::str::contains("Created git tag: v0.1.0-alpha.1"));
+    assert_eq!(fs::read_to_string(temp_dir.path().join("version.txt")).unwrap().trim(), "0.1.0-alpha.1");
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "bump", "patch"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(temp_dir.path().join("version.txt")).unwrap().trim(), "0.1.1");
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "bump", "build"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown version part"));
+}
+
+#[test]
+fn test_st8_changelog() {
+    let temp_dir = TempDir::new().unwrap();
+    setup_git_repo(temp_dir.path()).unwrap();
+    for message in ["feat(cli): add changelog command", "fix: handle empty history"] {
+        fs::write(temp_dir.path().join("notes.txt"), message).unwrap();
+        std::process::Command::new("git").args(["add", "notes.txt"]).current_dir(temp_dir.path()).output().unwrap();
+        std::process::Command::new("git").args(["commit", "-m", message]).current_dir(temp_dir.path()).output().unwrap();
+    }
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["changelog", "--dry-run"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("### Features"))
+        .stdout(predicate::str::contains("- **cli:** add changelog command"))
+        .stdout(predicate::str::contains("### Bug Fixes"));
+    assert!(!temp_dir.path().join("CHANGELOG.md").exists());
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["changelog", "--on-update", "on"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let changelog = fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.starts_with("# Changelog\n\n## "));
+
+    // wsb update rewrites the same section rather than adding another
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--no-git"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let changelog = fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert_eq!(changelog.matches("### Features").count(), 1);
+}
+
+#[test]
+fn test_st8_independent_packages() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    setup_git_repo(root).unwrap();
+    fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+    for name in ["core", "cli"] {
+        fs::create_dir_all(root.join("crates").join(name)).unwrap();
+        fs::write(
+            root.join("crates").join(name).join("Cargo.toml"),
+            format!("[package]\nname = \"{}\"\nversion = \"1.0.0\"\n", name),
+        )
+        .unwrap();
+    }
+    std::process::Command::new("git").args(["add", "."]).current_dir(root).output().unwrap();
+    std::process::Command::new("git").args(["commit", "-m", "Add workspace"]).current_dir(root).output().unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--package", "core"])
+        .current_dir(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("lockstep"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["version", "packages", "--mode", "independent"])
+        .current_dir(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("core"))
+        .stdout(predicate::str::contains("1.0.0"));
+
+    // A commit touching only core advances core alone
+    fs::write(root.join("crates/core/lib.rs"), "// core\n").unwrap();
+    std::process::Command::new("git").args(["add", "."]).current_dir(root).output().unwrap();
+    std::process::Command::new("git").args(["commit", "-m", "Touch core"]).current_dir(root).output().unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--package", "core"])
+        .current_dir(root)
+        .assert()
+        .success();
+    assert!(fs::read_to_string(root.join("crates/core/Cargo.toml")).unwrap().contains("version = \"1.0.1\""));
+    assert!(fs::read_to_string(root.join("crates/cli/Cargo.toml")).unwrap().contains("version = \"1.0.0\""));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["update", "--package", "web"])
+        .current_dir(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown package 'web'"));
+}
+
+#[test]
+fn test_st8_template_render_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    setup_git_repo(root).unwrap();
+    let templates_dir = root.join(".wsb").join("templates");
+    fs::create_dir_all(&templates_dir).unwrap();
+    fs::write(templates_dir.join("version_doc.tera"), "# Release\n\nVersion {{ version }}\n").unwrap();
+    fs::write(
+        templates_dir.join("templates.json"),
+        r#"{"version_doc": {"name": "version_doc", "description": null, "source_path": "version_doc.tera", "output_path": "VERSION.md", "enabled": true}}"#,
+    )
+    .unwrap();
+    fs::write(root.join("VERSION.md"), "# Release\n\nVersion 0.0.0\n").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["template", "render", "--dry-run"])
+        .current_dir(root)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("-Version 0.0.0"))
+        .stderr(predicate::str::contains("out of date"));
+    assert_eq!(fs::read_to_string(root.join("VERSION.md")).unwrap(), "# Release\n\nVersion 0.0.0\n");
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["template", "render"])
+        .current_dir(root)
+        .assert()
+        .success();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["template", "render", "--dry-run"])
+        .current_dir(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("up to date"));
+}
+
+#[test]
+fn test_st8_install_multiple_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    setup_git_repo(temp_dir.path()).unwrap();
+    let hooks_dir = temp_dir.path().join(".git").join("hooks");
+
+    for hook in ["pre-commit", "pre-push", "prepare-commit-msg"] {
+        Command::cargo_bin("wsb")
+            .unwrap()
+            .args(["git", "install", "--hook", hook])
+            .current_dir(temp_dir.path())
+            .assert()
+            .success();
+    }
+    assert!(fs::read_to_string(hooks_dir.join("pre-push")).unwrap().contains("git check"));
+    assert!(fs::read_to_string(hooks_dir.join("prepare-commit-msg")).unwrap().contains("git stamp-message \"$1\""));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["git", "status"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pre-push hook: Installed"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["git", "install", "--hook", "post-merge"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unsupported hook"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["git", "uninstall", "--hook", "pre-push"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    assert!(!hooks_dir.join("pre-push").exists());
+    assert!(hooks_dir.join("pre-commit").exists());
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["git", "uninstall"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    assert!(!hooks_dir.join("pre-commit").exists());
+    assert!(!hooks_dir.join("prepare-commit-msg").exists());
+}
+
+#[test]
+fn test_state_migrate() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let ws_dir = root.join(".wsb");
+    fs::create_dir_all(&ws_dir).unwrap();
+    let v1 = r#"{"version": 1, "project_root": "/elsewhere", "project_name": "legacy", "tools": {}}"#;
+    fs::write(ws_dir.join("state.json"), v1).unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["state", "migrate", "--dry-run"])
+        .current_dir(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would migrate workspace state from schema v1 to v2"));
+    assert_eq!(fs::read_to_string(ws_dir.join("state.json")).unwrap(), v1);
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["state", "migrate"])
+        .current_dir(root)
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(ws_dir.join("state.json.v1.bak")).unwrap(), v1);
+    assert!(fs::read_to_string(ws_dir.join("state.json")).unwrap().contains("\"schema_version\": 2"));
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["state", "migrate", "--dry-run"])
+        .current_dir(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already at schema v2"));
+}
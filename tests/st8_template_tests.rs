@@ -521,4 +521,46 @@ Generated on {{ datetime.iso }}
     assert!(content.contains("**Release Date:** "));
     assert!(content.contains("**Year:** 20"));
     assert!(content.contains("Generated on "));
-}
\ No newline at end of file
+}
+#[test]
+fn test_template_test_with_fixture() {
+    let temp_dir = TempDir::new().unwrap();
+    let templates_dir = temp_dir.path().join("src/templates");
+    fs::create_dir_all(&templates_dir).unwrap();
+    fs::write(
+        templates_dir.join("summary.tera"),
+        "# {{ project.name }}\n{% for f in features %}- {{ f.name }} {{ f.state | feature_state_emoji }}\n{% endfor %}",
+    ).unwrap();
+    fs::write(
+        temp_dir.path().join("fixture.json"),
+        r#"{"project": {"name": "Demo"}, "features": [{"name": "Login", "state": "implemented_passing_tests"}]}"#,
+    ).unwrap();
+    fs::write(temp_dir.path().join("expected.md"), "# Demo\n- Login 🟢\n").unwrap();
+
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["template", "test", "summary", "--fixture", "fixture.json", "--expect", "expected.md"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("matches"));
+
+    // A changed expectation fails and points at the first differing line
+    fs::write(temp_dir.path().join("expected.md"), "# Demo\n- Login ❌\n").unwrap();
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["template", "test", "summary", "--fixture", "fixture.json", "--expect", "expected.md"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 2"));
+
+    // Without --expect the rendered output is printed
+    Command::cargo_bin("wsb")
+        .unwrap()
+        .args(["template", "test", "summary", "--fixture", "fixture.json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Login 🟢"));
+}